@@ -0,0 +1,63 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap::ArgMatches;
+use jj_cli::cli_util::CliRunner;
+use jj_cli::cli_util::CommandHelper;
+use jj_cli::cli_util::RevisionArg;
+use jj_cli::command_error::CommandError;
+use jj_cli::ui::Ui;
+
+/// Builds the command at runtime instead of deriving `clap::Subcommand`.
+fn frobnicate_command() -> clap::Command {
+    clap::Command::new("frobnicate")
+        .about("Frobnicate a revision")
+        .arg(
+            clap::Arg::new("revision")
+                .help("The revision to frobnicate")
+                .default_value("@"),
+        )
+}
+
+fn run_frobnicate(
+    ui: &mut Ui,
+    command_helper: &CommandHelper,
+    matches: &ArgMatches,
+) -> Result<(), CommandError> {
+    let revision = RevisionArg::from(matches.get_one::<String>("revision").unwrap().clone());
+    let mut workspace_command = command_helper.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &revision)?;
+    let mut tx = workspace_command.start_transaction();
+    let new_commit = tx
+        .repo_mut()
+        .rewrite_commit(&commit)
+        .set_description("Frobnicated!")
+        .write()?;
+    tx.finish(ui, "frobnicate")?;
+    writeln!(
+        ui.status(),
+        "Frobnicated revision: {}",
+        workspace_command.format_commit_summary(&new_commit)
+    )?;
+    Ok(())
+}
+
+fn main() -> std::process::ExitCode {
+    CliRunner::init()
+        .add_subcommand_extension(frobnicate_command(), run_frobnicate)
+        .run()
+        .into()
+}
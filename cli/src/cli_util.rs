@@ -21,7 +21,9 @@ use std::env;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::Debug;
+use std::fs;
 use std::io;
+use std::io::Read as _;
 use std::io::Write as _;
 use std::mem;
 use std::path::Path;
@@ -65,15 +67,22 @@ use jj_lib::config::ConfigMigrationRule;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
+use jj_lib::conflicts::ConflictLabels;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::gitattributes::GitAttributes;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::IdPrefixContext;
+use jj_lib::local_working_copy::CheckoutRecovery;
+use jj_lib::local_working_copy::LockedLocalWorkingCopy;
+use jj_lib::mailmap::Mailmap;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::ConflictSummary;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_heads_store;
@@ -96,6 +105,7 @@ use jj_lib::repo::Repo;
 use jj_lib::repo::RepoLoader;
 use jj_lib::repo::StoreFactories;
 use jj_lib::repo::StoreLoadError;
+use jj_lib::repo_format::RepoFormatError;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
@@ -123,6 +133,7 @@ use jj_lib::view::View;
 use jj_lib::working_copy;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::NewDirectoryPolicy;
 use jj_lib::working_copy::SnapshotOptions;
 use jj_lib::working_copy::SnapshotStats;
 use jj_lib::working_copy::UntrackedReason;
@@ -151,6 +162,7 @@ use crate::command_error::internal_error_with_message;
 use crate::command_error::print_parse_diagnostics;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::commit_templater::CommitTemplateLanguageExtension;
@@ -174,6 +186,8 @@ use crate::operation_templater::OperationTemplateLanguage;
 use crate::operation_templater::OperationTemplateLanguageExtension;
 use crate::revset_util;
 use crate::revset_util::RevsetExpressionEvaluator;
+use crate::snapshot_daemon;
+use crate::snapshot_daemon::SnapshotLease;
 use crate::template_builder;
 use crate::template_builder::TemplateLanguage;
 use crate::template_parser::TemplateAliasesMap;
@@ -182,6 +196,7 @@ use crate::templater::TemplateRenderer;
 use crate::templater::WrapTemplateProperty;
 use crate::text_util;
 use crate::ui::ColorChoice;
+use crate::ui::ErrorFormat;
 use crate::ui::Ui;
 
 const SHORT_CHANGE_ID_TEMPLATE_TEXT: &str = "format_short_change_id(self.change_id())";
@@ -373,6 +388,14 @@ impl CommandHelper {
         TextEditor::from_settings(self.settings())
     }
 
+    pub fn store_factories(&self) -> &StoreFactories {
+        &self.data.store_factories
+    }
+
+    pub fn working_copy_factories(&self) -> &WorkingCopyFactories {
+        &self.data.working_copy_factories
+    }
+
     pub fn revset_extensions(&self) -> &Arc<RevsetExtensions> {
         &self.data.revset_extensions
     }
@@ -437,9 +460,20 @@ impl CommandHelper {
         &self,
         ui: &Ui,
     ) -> Result<(WorkspaceCommandHelper, SnapshotStats), CommandError> {
+        // If `jj debug snapshot --watch` is running, let it snapshot the
+        // working copy before the repo is loaded at the new head.
+        let snapshot_lease = self.request_snapshot_from_watcher();
         let mut workspace_command = self.workspace_helper_no_snapshot(ui)?;
+        if snapshot_lease
+            .as_ref()
+            .is_some_and(|lease| lease.snapshotted())
+        {
+            tracing::debug!("working copy was snapshotted by watching process");
+            workspace_command.snapshot_lease = snapshot_lease;
+            return Ok((workspace_command, SnapshotStats::default()));
+        }
 
-        let (workspace_command, stats) = match workspace_command.maybe_snapshot_impl(ui) {
+        let (mut workspace_command, stats) = match workspace_command.maybe_snapshot_impl(ui) {
             Ok(stats) => (workspace_command, stats),
             Err(SnapshotWorkingCopyError::Command(err)) => return Err(err),
             Err(SnapshotWorkingCopyError::StaleWorkingCopy(err)) => {
@@ -452,13 +486,25 @@ impl CommandHelper {
                 // auto-update-stale, so let's do that now. We need to do it up here, not at a
                 // lower level (e.g. inside snapshot_working_copy()) to avoid recursive locking
                 // of the working copy.
-                self.recover_stale_working_copy(ui)?
+                self.recover_stale_working_copy(ui, false)?
             }
         };
+        // Keep the watching process paused until this command exits.
+        workspace_command.snapshot_lease = snapshot_lease;
 
         Ok((workspace_command, stats))
     }
 
+    fn request_snapshot_from_watcher(&self) -> Option<SnapshotLease> {
+        if !self.is_working_copy_writable() {
+            return None;
+        }
+        let workspace_root = self.workspace_loader().ok()?.workspace_root();
+        let socket_path = snapshot_daemon::socket_path(workspace_root);
+        let fingerprint = snapshot_daemon::config_fingerprint(self.settings().config());
+        snapshot_daemon::request_snapshot(&socket_path, &fingerprint)
+    }
+
     /// Loads workspace and repo, but never snapshots the working copy. Most
     /// commands should use `workspace_helper()` instead.
     #[instrument(skip(self, ui))]
@@ -474,6 +520,23 @@ impl CommandHelper {
         WorkspaceCommandHelper::new(ui, workspace, repo, env, self.is_at_head_operation())
     }
 
+    /// Loads workspace and repo for a command that creates commits without
+    /// touching the working copy.
+    ///
+    /// The working copy is neither snapshotted nor updated. The transaction
+    /// fails if it would rewrite or move the working-copy commit of this
+    /// workspace.
+    #[instrument(skip(self, ui))]
+    pub fn workspace_helper_detached(
+        &self,
+        ui: &Ui,
+    ) -> Result<WorkspaceCommandHelper, CommandError> {
+        let mut workspace_command = self.workspace_helper_no_snapshot(ui)?;
+        workspace_command.may_update_working_copy = false;
+        workspace_command.working_copy_detached = true;
+        Ok(workspace_command)
+    }
+
     pub fn get_working_copy_factory(&self) -> Result<&dyn WorkingCopyFactory, CommandError> {
         let loader = self.workspace_loader()?;
 
@@ -519,12 +582,16 @@ impl CommandHelper {
             .map_err(|err| map_workspace_load_error(err, None))
     }
 
+    /// If `rebase_local_changes` is true, changes made in the stale working
+    /// copy are rebased onto the fresh working-copy commit as a new commit.
+    ///
     /// Note that unless you have a good reason not to do so, you should always
     /// call [`print_snapshot_stats`] with the [`SnapshotStats`] returned by
     /// this function to present possible untracked files to the user.
     pub fn recover_stale_working_copy(
         &self,
         ui: &Ui,
+        rebase_local_changes: bool,
     ) -> Result<(WorkspaceCommandHelper, SnapshotStats), CommandError> {
         let workspace = self.load_workspace()?;
         let op_id = workspace.working_copy().operation_id();
@@ -533,6 +600,7 @@ impl CommandHelper {
             Ok(op) => {
                 let repo = workspace.repo_loader().load_at(&op)?;
                 let mut workspace_command = self.for_workable_repo(ui, workspace, repo)?;
+                let old_wc_commit_id = workspace_command.get_wc_commit_id().unwrap().clone();
 
                 // Snapshot the current working copy on top of the last known working-copy
                 // operation, then merge the divergent operations. The wc_commit_id of the
@@ -545,6 +613,13 @@ impl CommandHelper {
                 let wc_commit_id = workspace_command.get_wc_commit_id().unwrap();
                 let repo = workspace_command.repo().clone();
                 let stale_wc_commit = repo.store().get_commit(wc_commit_id)?;
+                // The commit that the local changes were made on top of
+                let local_changes_base = if rebase_local_changes {
+                    Some(repo.store().get_commit(&old_wc_commit_id)?)
+                        .filter(|commit| commit.tree_id() != stale_wc_commit.tree_id())
+                } else {
+                    None
+                };
 
                 let mut workspace_command = self.workspace_helper_no_snapshot(ui)?;
                 let checkout_options = workspace_command.checkout_options();
@@ -565,9 +640,25 @@ impl CommandHelper {
                     }
                     WorkingCopyFreshness::WorkingCopyStale
                     | WorkingCopyFreshness::SiblingOperation => {
+                        let (locked_ws, op_id, desired_wc_commit) =
+                            if let Some(base_commit) = &local_changes_base {
+                                drop(locked_ws);
+                                let new_wc_commit = workspace_command.rebase_stale_local_changes(
+                                    ui,
+                                    base_commit,
+                                    &stale_wc_commit,
+                                    &desired_wc_commit,
+                                )?;
+                                let op_id = workspace_command.repo().op_id().clone();
+                                let (locked_ws, _) =
+                                    workspace_command.unchecked_start_working_copy_mutation()?;
+                                (locked_ws, op_id, new_wc_commit)
+                            } else {
+                                (locked_ws, repo.op_id().clone(), desired_wc_commit)
+                            };
                         let stats = update_stale_working_copy(
                             locked_ws,
-                            repo.op_id().clone(),
+                            op_id,
                             &stale_wc_commit,
                             &desired_wc_commit,
                             &checkout_options,
@@ -645,12 +736,41 @@ impl CommandHelper {
                 repo_loader.op_heads_store().as_ref(),
                 repo_loader.op_store(),
                 |op_heads| {
-                    writeln!(
-                        ui.status(),
-                        "Concurrent modification detected, resolving automatically.",
-                    )?;
+                    let policy: OpHeadsMergePolicy =
+                        repo_loader.settings().get("operation.merge-heads")?;
+                    match policy {
+                        OpHeadsMergePolicy::Auto => {
+                            writeln!(
+                                ui.status(),
+                                "Concurrent modification detected, resolving automatically.",
+                            )?;
+                        }
+                        OpHeadsMergePolicy::Prompt => {
+                            writeln!(
+                                ui.status(),
+                                "Concurrent modification detected. Operation heads:"
+                            )?;
+                            write_op_heads_summary(ui, &op_heads)?;
+                            if !ui.prompt_yes_no("Merge the operation heads?", Some(false))? {
+                                return Err(user_error_with_hint(
+                                    "Operation heads were not merged",
+                                    "Run `jj op heads` to inspect them.",
+                                ));
+                            }
+                        }
+                        OpHeadsMergePolicy::Fail => {
+                            return Err(user_error_with_hint(
+                                format!(
+                                    "Concurrent modification detected: found {} operation heads",
+                                    op_heads.len()
+                                ),
+                                "Run `jj op heads` to inspect them, or run a command with \
+                                 `--config operation.merge-heads=auto` to merge them.",
+                            ));
+                        }
+                    }
+                    let head_views: Vec<_> = op_heads.iter().map(|op| op.view()).try_collect()?;
                     let base_repo = repo_loader.load_at(&op_heads[0])?;
-                    // TODO: It may be helpful to print each operation we're merging here
                     let mut tx = start_repo_transaction(&base_repo, &self.data.string_args);
                     for other_op_head in op_heads.into_iter().skip(1) {
                         tx.merge_operation(other_op_head)?;
@@ -663,11 +783,11 @@ impl CommandHelper {
                             )?;
                         }
                     }
-                    Ok(tx
+                    let merged_repo = tx
                         .write("reconcile divergent operations")?
-                        .leave_unpublished()
-                        .operation()
-                        .clone())
+                        .leave_unpublished();
+                    write_new_ref_conflicts(ui, &head_views, merged_repo.view())?;
+                    Ok(merged_repo.operation().clone())
                 },
             )
         }
@@ -689,6 +809,71 @@ impl CommandHelper {
     }
 }
 
+/// What to do when multiple operation heads are found while loading the repo.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OpHeadsMergePolicy {
+    /// Merge the operation heads automatically.
+    Auto,
+    /// Ask before merging the operation heads.
+    Prompt,
+    /// Refuse to load the repo until the operation heads are merged.
+    Fail,
+}
+
+fn write_op_heads_summary(ui: &Ui, op_heads: &[Operation]) -> io::Result<()> {
+    for op in op_heads {
+        writeln!(
+            ui.status(),
+            "  {} {}",
+            short_operation_hash(op.id()),
+            op.metadata().description.lines().next().unwrap_or_default()
+        )?;
+    }
+    Ok(())
+}
+
+/// Reports bookmarks and tags which became conflicted by merging the views of
+/// the `head_views`.
+fn write_new_ref_conflicts(ui: &Ui, head_views: &[View], merged_view: &View) -> io::Result<()> {
+    let conflicted_bookmarks = merged_view
+        .local_bookmarks()
+        .filter(|(name, target)| {
+            target.has_conflict()
+                && head_views
+                    .iter()
+                    .all(|view| !view.get_local_bookmark(name).has_conflict())
+        })
+        .map(|(name, _)| name.as_symbol())
+        .collect_vec();
+    if !conflicted_bookmarks.is_empty() {
+        writeln!(
+            ui.status(),
+            "Bookmarks with conflicting changes from concurrent operations: {}",
+            conflicted_bookmarks.iter().join(", ")
+        )?;
+    }
+    let conflicted_tags = merged_view
+        .tags()
+        .iter()
+        .filter(|(name, target)| {
+            target.has_conflict()
+                && head_views
+                    .iter()
+                    .all(|view| !view.get_tag(name).has_conflict())
+        })
+        .map(|(name, _)| name.as_symbol())
+        .collect_vec();
+    if !conflicted_tags.is_empty() {
+        writeln!(
+            ui.status(),
+            "Tags with conflicting changes from concurrent operations: {}",
+            conflicted_tags.iter().join(", ")
+        )?;
+    }
+    Ok(())
+}
+
 /// A ReadonlyRepo along with user-config-dependent derived data. The derived
 /// data is lazily loaded.
 struct ReadonlyUserRepo {
@@ -785,9 +970,12 @@ pub struct WorkspaceCommandEnvironment {
     template_aliases_map: TemplateAliasesMap,
     path_converter: RepoPathUiConverter,
     workspace_name: WorkspaceNameBuf,
+    repo_path: PathBuf,
     immutable_heads_expression: Rc<UserRevsetExpression>,
     short_prefixes_expression: Option<Rc<UserRevsetExpression>>,
     conflict_marker_style: ConflictMarkerStyle,
+    mailmap: Mailmap,
+    attribute_overrides: GitAttributes,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -800,6 +988,8 @@ impl WorkspaceCommandEnvironment {
             cwd: command.cwd().to_owned(),
             base: workspace.workspace_root().to_owned(),
         };
+        let mailmap = load_mailmap(workspace.workspace_root())?;
+        let attribute_overrides = load_attribute_overrides(workspace.workspace_root())?;
         let mut env = Self {
             command: command.clone(),
             settings: settings.clone(),
@@ -807,9 +997,12 @@ impl WorkspaceCommandEnvironment {
             template_aliases_map,
             path_converter,
             workspace_name: workspace.workspace_name().to_owned(),
+            repo_path: workspace.repo_path().to_owned(),
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
             conflict_marker_style: settings.get("ui.conflict-marker-style")?,
+            mailmap,
+            attribute_overrides,
         };
         env.immutable_heads_expression = env.load_immutable_heads_expression(ui)?;
         env.short_prefixes_expression = env.load_short_prefixes_expression(ui)?;
@@ -824,10 +1017,22 @@ impl WorkspaceCommandEnvironment {
         &self.workspace_name
     }
 
+    /// Mailmap loaded from the `.mailmap` file in the workspace root.
+    pub fn mailmap(&self) -> &Mailmap {
+        &self.mailmap
+    }
+
+    /// Path attributes loaded from the `.jj/attributes` file, which take
+    /// precedence over the `.gitattributes` files in the trees.
+    pub fn attribute_overrides(&self) -> &GitAttributes {
+        &self.attribute_overrides
+    }
+
     pub(crate) fn revset_parse_context(&self) -> RevsetParseContext<'_> {
         let workspace_context = RevsetWorkspaceContext {
             path_converter: &self.path_converter,
             workspace_name: &self.workspace_name,
+            repo_path: &self.repo_path,
         };
         let now = if let Some(timestamp) = self.settings.commit_timestamp() {
             chrono::Local
@@ -840,9 +1045,11 @@ impl WorkspaceCommandEnvironment {
             aliases_map: &self.revset_aliases_map,
             local_variables: HashMap::new(),
             user_email: self.settings.user_email(),
+            user_email_aliases: self.settings.user_email_aliases(),
             date_pattern_context: now.into(),
             extensions: self.command.revset_extensions(),
             workspace: Some(workspace_context),
+            mailmap: &self.mailmap,
         }
     }
 
@@ -985,6 +1192,7 @@ impl WorkspaceCommandEnvironment {
             id_prefix_context,
             self.immutable_expression(),
             self.conflict_marker_style,
+            &self.attribute_overrides,
             &self.command.data.commit_template_extensions,
         )
     }
@@ -1004,7 +1212,11 @@ pub struct WorkspaceCommandHelper {
     commit_summary_template_text: String,
     op_summary_template_text: String,
     may_update_working_copy: bool,
+    working_copy_detached: bool,
     working_copy_shared_with_git: bool,
+    /// Connection to `jj debug snapshot --watch`, which pauses the watching
+    /// process while this command runs.
+    snapshot_lease: Option<SnapshotLease>,
 }
 
 enum SnapshotWorkingCopyError {
@@ -1052,7 +1264,9 @@ impl WorkspaceCommandHelper {
             commit_summary_template_text,
             op_summary_template_text,
             may_update_working_copy,
+            working_copy_detached: false,
             working_copy_shared_with_git,
+            snapshot_lease: None,
         };
         // Parse commit_summary template early to report error before starting
         // mutable operation.
@@ -1114,13 +1328,18 @@ impl WorkspaceCommandHelper {
     /// copy is collocated with Git.
     #[instrument(skip_all)]
     pub fn maybe_snapshot(&mut self, ui: &Ui) -> Result<(), CommandError> {
-        let stats = self
-            .maybe_snapshot_impl(ui)
-            .map_err(|err| err.into_command_error())?;
+        let stats = self.maybe_snapshot_with_stats(ui)?;
         print_snapshot_stats(ui, &stats, self.env().path_converter())?;
         Ok(())
     }
 
+    /// Same as [`Self::maybe_snapshot()`], but returns the stats instead of
+    /// printing them.
+    pub fn maybe_snapshot_with_stats(&mut self, ui: &Ui) -> Result<SnapshotStats, CommandError> {
+        self.maybe_snapshot_impl(ui)
+            .map_err(|err| err.into_command_error())
+    }
+
     /// Imports new HEAD from the colocated Git repo.
     ///
     /// If the Git HEAD has changed, this function checks out the new Git HEAD.
@@ -1173,7 +1392,7 @@ impl WorkspaceCommandHelper {
             }
         } else {
             // Unlikely, but the HEAD ref got deleted by git?
-            self.finish_transaction(ui, tx, "import git head")?;
+            self.finish_transaction(ui, tx, "import git head", &[])?;
         }
         Ok(())
     }
@@ -1206,7 +1425,7 @@ impl WorkspaceCommandHelper {
                 "Rebased {num_rebased} descendant commits off of commits rewritten from git"
             )?;
         }
-        self.finish_transaction(ui, tx, "import git refs")?;
+        self.finish_transaction(ui, tx, "import git refs", &[])?;
         writeln!(
             ui.status(),
             "Done importing changes from the underlying Git repo."
@@ -1237,6 +1456,8 @@ impl WorkspaceCommandHelper {
     pub fn checkout_options(&self) -> CheckoutOptions {
         CheckoutOptions {
             conflict_marker_style: self.env.conflict_marker_style(),
+            conflict_labels: ConflictLabels::unlabeled(),
+            progress: None,
         }
     }
 
@@ -1299,6 +1520,41 @@ to the current parents may contain changes from multiple commits.
             .map_err(|err| err.into_command_error())
     }
 
+    /// Creates a commit on top of `new_wc_commit` with the changes made in
+    /// the stale working copy, i.e. the difference from `base_commit` to
+    /// `snapshot_commit`, and makes it the working-copy commit. Conflicts are
+    /// recorded in the new commit. The `snapshot_commit` is abandoned since its
+    /// changes are moved to the new commit.
+    fn rebase_stale_local_changes(
+        &mut self,
+        ui: &Ui,
+        base_commit: &Commit,
+        snapshot_commit: &Commit,
+        new_wc_commit: &Commit,
+    ) -> Result<Commit, CommandError> {
+        let new_tree = new_wc_commit
+            .tree()?
+            .merge(base_commit.tree()?, snapshot_commit.tree()?)
+            .block_on()?;
+        let mut tx = self.user_repo.repo.start_transaction();
+        let new_commit = tx
+            .repo_mut()
+            .new_commit(vec![new_wc_commit.id().clone()], new_tree.id())
+            .write()?;
+        tx.repo_mut()
+            .set_wc_commit(self.workspace_name().to_owned(), new_commit.id().clone())?;
+        tx.repo_mut().record_abandoned_commit(snapshot_commit);
+        tx.repo_mut().rebase_descendants()?;
+        self.user_repo = ReadonlyUserRepo::new(tx.commit("rebase stale working-copy changes")?);
+
+        writeln!(
+            ui.status(),
+            "Rebased local changes onto the fresh working-copy commit as {}",
+            short_commit_hash(new_commit.id())
+        )?;
+        Ok(new_commit)
+    }
+
     pub fn workspace_root(&self) -> &Path {
         self.workspace.workspace_root()
     }
@@ -1311,6 +1567,30 @@ to the current parents may contain changes from multiple commits.
         self.repo().view().get_wc_commit_id(self.workspace_name())
     }
 
+    /// Returns the `JJ_*` environment variables describing this workspace,
+    /// which are set for external commands run by jj.
+    ///
+    /// * `JJ_WORKSPACE_ROOT`: the path to the workspace root
+    /// * `JJ_REPO_PATH`: the path to the repo directory (usually `.jj/repo`)
+    /// * `JJ_WORKSPACE_NAME`: the name of the workspace
+    /// * `JJ_OP_ID`: the ID of the operation the command is run at
+    /// * `JJ_WC_COMMIT_ID`, `JJ_WC_CHANGE_ID`: the commit and change ID of the
+    ///   working-copy commit, if any
+    pub fn workspace_env_vars(&self) -> Result<Vec<(&'static str, OsString)>, CommandError> {
+        let mut vars = vec![
+            ("JJ_WORKSPACE_ROOT", self.workspace_root().into()),
+            ("JJ_REPO_PATH", self.repo_path().into()),
+            ("JJ_WORKSPACE_NAME", self.workspace_name().as_str().into()),
+            ("JJ_OP_ID", self.repo().op_id().hex().into()),
+        ];
+        if let Some(commit_id) = self.get_wc_commit_id() {
+            let commit = self.repo().store().get_commit(commit_id)?;
+            vars.push(("JJ_WC_COMMIT_ID", commit_id.hex().into()));
+            vars.push(("JJ_WC_CHANGE_ID", commit.change_id().reverse_hex().into()));
+        }
+        Ok(vars)
+    }
+
     pub fn working_copy_shared_with_git(&self) -> bool {
         self.working_copy_shared_with_git
     }
@@ -1341,6 +1621,24 @@ to the current parents may contain changes from multiple commits.
         }
     }
 
+    /// Parses the given strings and the lines read from `pathspec_file` as
+    /// file patterns.
+    ///
+    /// Unlike `parse_file_patterns()`, no files are matched if both are empty.
+    pub fn parse_file_patterns_with_pathspec_file(
+        &self,
+        ui: &Ui,
+        values: &[String],
+        pathspec_file: Option<&Path>,
+    ) -> Result<FilesetExpression, CommandError> {
+        let Some(pathspec_file) = pathspec_file else {
+            return self.parse_file_patterns(ui, values);
+        };
+        let mut values = values.to_vec();
+        values.extend(read_pathspec_file(pathspec_file)?);
+        self.parse_union_filesets(ui, &values)
+    }
+
     /// Parses the given fileset expressions and concatenates them all.
     pub fn parse_union_filesets(
         &self,
@@ -1383,6 +1681,19 @@ to the current parents may contain changes from multiple commits.
         if max_new_file_size == 0 {
             max_new_file_size = u64::MAX;
         }
+        let max_files = self
+            .settings()
+            .get::<usize>("snapshot.new-directory-max-files")?;
+        let new_directory_policy =
+            self.settings()
+                .get_value_with("snapshot.new-directory-policy", |value| {
+                    match value.as_str() {
+                        Some("allow") => Ok(NewDirectoryPolicy::Allow),
+                        Some("warn") => Ok(NewDirectoryPolicy::Warn { max_files }),
+                        Some("exclude") => Ok(NewDirectoryPolicy::Exclude { max_files }),
+                        _ => Err(r#"Expected one of "allow", "warn", or "exclude""#),
+                    }
+                })?;
         let conflict_marker_style = self.env.conflict_marker_style();
         Ok(SnapshotOptions {
             base_ignores,
@@ -1391,9 +1702,28 @@ to the current parents may contain changes from multiple commits.
             start_tracking_matcher,
             max_new_file_size,
             conflict_marker_style,
+            new_directory_policy,
+            report_ignored_paths: false,
         })
     }
 
+    /// Scans the working copy without recording the changes, and returns the
+    /// snapshot stats. Unlike the regular snapshot, new paths matching the
+    /// ignore patterns are also reported as untracked.
+    pub fn snapshot_dry_run(&mut self, ui: &Ui) -> Result<SnapshotStats, CommandError> {
+        let auto_tracking_matcher = self.auto_tracking_matcher(ui)?;
+        let options = SnapshotOptions {
+            // Scan the whole working copy, not only the recently changed paths.
+            fsmonitor_settings: FsmonitorSettings::None,
+            report_ignored_paths: true,
+            ..self.snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)?
+        };
+        let mut locked_ws = self.workspace.start_working_copy_mutation()?;
+        let (_tree_id, stats) = locked_ws.locked_wc().snapshot(&options)?;
+        // Drop the lock without saving the working-copy state.
+        Ok(stats)
+    }
+
     pub(crate) fn path_converter(&self) -> &RepoPathUiConverter {
         self.env.path_converter()
     }
@@ -1454,6 +1784,7 @@ to the current parents may contain changes from multiple commits.
             self.env.conflict_marker_style(),
             formats,
         )
+        .with_attribute_overrides(self.env.attribute_overrides())
     }
 
     /// Loads textual diff renderer from the settings and command arguments.
@@ -1462,7 +1793,8 @@ to the current parents may contain changes from multiple commits.
         args: &DiffFormatArgs,
     ) -> Result<DiffRenderer<'_>, CommandError> {
         let formats = diff_util::diff_formats_for(self.settings(), args)?;
-        Ok(self.diff_renderer(formats))
+        let overrides = diff_util::diff_format_overrides_for(self.settings(), args)?;
+        Ok(self.diff_renderer(formats).with_format_overrides(overrides))
     }
 
     /// Loads textual diff renderer from the settings and log-like command
@@ -1474,7 +1806,13 @@ to the current parents may contain changes from multiple commits.
         patch: bool,
     ) -> Result<Option<DiffRenderer<'_>>, CommandError> {
         let formats = diff_util::diff_formats_for_log(self.settings(), args, patch)?;
-        Ok((!formats.is_empty()).then(|| self.diff_renderer(formats)))
+        if formats.is_empty() {
+            return Ok(None);
+        }
+        let overrides = diff_util::diff_format_overrides_for(self.settings(), args)?;
+        Ok(Some(
+            self.diff_renderer(formats).with_format_overrides(overrides),
+        ))
     }
 
     /// Loads diff editor from the settings.
@@ -1855,6 +2193,7 @@ to the current parents may contain changes from multiple commits.
         let options = self
             .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
             .map_err(snapshot_command_error)?;
+        let checkout_options = self.checkout_options();
 
         // Compare working-copy tree and operation with repo's, and reload as needed.
         let mut locked_ws = self
@@ -1862,6 +2201,22 @@ to the current parents may contain changes from multiple commits.
             .start_working_copy_mutation()
             .map_err(snapshot_command_error)?;
         let old_op_id = locked_ws.locked_wc().old_operation_id().clone();
+        // Finish the checkout of a previous command if it was interrupted, so
+        // the files it already wrote aren't snapshotted as user changes. Other
+        // changes made since the interruption are kept.
+        if let Some(locked_local_wc) = locked_ws
+            .locked_wc()
+            .as_any_mut()
+            .downcast_mut::<LockedLocalWorkingCopy>()
+        {
+            let recovered = locked_local_wc
+                .recover_checkout(CheckoutRecovery::Resume, &options, &checkout_options)
+                .map_err(snapshot_command_error)?;
+            if let Some(stats) = recovered {
+                print_recovered_checkout_stats(ui, CheckoutRecovery::Resume, &stats)
+                    .map_err(snapshot_command_error)?;
+            }
+        }
 
         let (repo, wc_commit) =
             match WorkingCopyFreshness::check_stale(locked_ws.locked_wc(), &wc_commit, &repo) {
@@ -1981,9 +2336,16 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         ui: &Ui,
         maybe_old_commit: Option<&Commit>,
         new_commit: &Commit,
+        conflict_labels: &[(MergedTreeId, ConflictLabels)],
     ) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
-        let checkout_options = self.checkout_options();
+        let mut checkout_options = self.checkout_options();
+        if let Some((_, labels)) = conflict_labels
+            .iter()
+            .find(|(tree_id, _)| tree_id == new_commit.tree_id())
+        {
+            checkout_options.conflict_labels = labels.clone();
+        }
         let stats = update_working_copy(
             &self.user_repo.repo,
             &mut self.workspace,
@@ -2020,12 +2382,14 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         if Some(new_commit) != maybe_old_commit {
             if let Some(mut formatter) = ui.status_formatter() {
                 if new_commit.has_conflict()? {
-                    let conflicts = new_commit.tree()?.conflicts().collect_vec();
+                    let tree = new_commit.tree()?;
+                    let conflicts = tree.conflicts().collect_vec();
                     writeln!(
                         formatter.labeled("warning").with_heading("Warning: "),
                         "There are unresolved conflicts at these paths:"
                     )?;
                     print_conflicted_paths(conflicts, formatter.as_mut(), self)?;
+                    print_conflict_resolution_hints(formatter.as_mut(), &tree, self)?;
                 }
             }
         }
@@ -2039,6 +2403,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             helper: self,
             tx,
             id_prefix_context,
+            conflict_labels: vec![],
         }
     }
 
@@ -2047,6 +2412,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         ui: &Ui,
         mut tx: Transaction,
         description: impl Into<String>,
+        conflict_labels: &[(MergedTreeId, ConflictLabels)],
     ) -> Result<(), CommandError> {
         if !tx.repo().has_changes() {
             writeln!(ui.status(), "Nothing changed.")?;
@@ -2088,6 +2454,13 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             .map(|commit_id| tx.repo().store().get_commit(commit_id))
             .transpose()?;
 
+        if self.working_copy_detached && maybe_new_wc_commit != maybe_old_wc_commit {
+            return Err(user_error_with_hint(
+                "The working-copy commit would be rewritten, but the working copy is detached.",
+                "Run the command without --detached to update the working copy.",
+            ));
+        }
+
         #[cfg(feature = "git")]
         if self.working_copy_shared_with_git {
             use std::error::Error as _;
@@ -2114,7 +2487,12 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         // don't leave the working copy in a stale state.
         if self.may_update_working_copy {
             if let Some(new_commit) = &maybe_new_wc_commit {
-                self.update_working_copy(ui, maybe_old_wc_commit.as_ref(), new_commit)?;
+                self.update_working_copy(
+                    ui,
+                    maybe_old_wc_commit.as_ref(),
+                    new_commit,
+                    conflict_labels,
+                )?;
             } else {
                 // It seems the workspace was deleted, so we shouldn't try to
                 // update it.
@@ -2123,6 +2501,18 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
 
         self.report_repo_changes(ui, &old_repo)?;
 
+        let command = &self.env.command;
+        crate::maintenance::maybe_spawn_auto_gc(
+            self.settings(),
+            self.workspace_root(),
+            self.repo_path(),
+            &command
+                .global_args()
+                .early_args
+                .merged_config_args(command.matches()),
+            command.cwd(),
+        );
+
         let settings = self.settings();
         let missing_user_name = settings.user_name().is_empty();
         let missing_user_mail = settings.user_email().is_empty();
@@ -2378,6 +2768,8 @@ pub struct WorkspaceCommandTransaction<'a> {
     tx: Transaction,
     /// Cache of index built against the current MutableRepo state.
     id_prefix_context: OnceCell<IdPrefixContext>,
+    /// Labels of conflicts to be materialized if the tree is checked out.
+    conflict_labels: Vec<(MergedTreeId, ConflictLabels)>,
 }
 
 impl WorkspaceCommandTransaction<'_> {
@@ -2458,8 +2850,15 @@ impl WorkspaceCommandTransaction<'_> {
         self.helper.env.parse_template(ui, &language, template_text)
     }
 
+    /// Annotates conflicts of the `tree_id` with the `labels` in case the tree
+    /// is checked out to the working copy by this transaction.
+    pub fn add_conflict_labels(&mut self, tree_id: MergedTreeId, labels: ConflictLabels) {
+        self.conflict_labels.push((tree_id, labels));
+    }
+
     pub fn finish(self, ui: &Ui, description: impl Into<String>) -> Result<(), CommandError> {
-        self.helper.finish_transaction(ui, self.tx, description)
+        self.helper
+            .finish_transaction(ui, self.tx, description, &self.conflict_labels)
     }
 
     /// Returns the wrapped [`Transaction`] for circumstances where
@@ -2526,6 +2925,12 @@ jj git init --colocate",
             err @ (StoreLoadError::ReadError { .. } | StoreLoadError::Backend(_)),
         ) => internal_error_with_message("The repository appears broken or inaccessible", err),
         WorkspaceLoadError::StoreLoadError(StoreLoadError::Signing(err)) => user_error(err),
+        WorkspaceLoadError::StoreLoadError(StoreLoadError::Format(
+            err @ RepoFormatError::TooNew { .. },
+        )) => user_error(err),
+        WorkspaceLoadError::StoreLoadError(StoreLoadError::Format(err)) => {
+            internal_error_with_message("The repository appears broken or inaccessible", err)
+        }
         WorkspaceLoadError::WorkingCopyState(err) => internal_error(err),
         WorkspaceLoadError::DecodeRepoPath(_) | WorkspaceLoadError::Path(_) => user_error(err),
     }
@@ -2536,20 +2941,7 @@ pub fn start_repo_transaction(repo: &Arc<ReadonlyRepo>, string_args: &[String])
     // TODO: Either do better shell-escaping here or store the values in some list
     // type (which we currently don't have).
     let shell_escape = |arg: &String| {
-        if arg.as_bytes().iter().all(|b| {
-            matches!(b,
-                b'A'..=b'Z'
-                | b'a'..=b'z'
-                | b'0'..=b'9'
-                | b','
-                | b'-'
-                | b'.'
-                | b'/'
-                | b':'
-                | b'@'
-                | b'_'
-            )
-        }) {
+        if is_shell_safe(arg) {
             arg.clone()
         } else {
             format!("'{}'", arg.replace('\'', "\\'"))
@@ -2561,6 +2953,32 @@ pub fn start_repo_transaction(repo: &Arc<ReadonlyRepo>, string_args: &[String])
     tx
 }
 
+fn is_shell_safe(arg: &str) -> bool {
+    arg.as_bytes().iter().all(|b| {
+        matches!(b,
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b','
+            | b'-'
+            | b'.'
+            | b'/'
+            | b':'
+            | b'@'
+            | b'_'
+        )
+    })
+}
+
+/// Quotes `arg` so it can be pasted into a POSIX shell as a single word.
+fn shell_quote(arg: &str) -> Cow<'_, str> {
+    if is_shell_safe(arg) {
+        Cow::Borrowed(arg)
+    } else {
+        Cow::Owned(format!("'{}'", arg.replace('\'', r"'\''")))
+    }
+}
+
 fn update_stale_working_copy(
     mut locked_ws: LockedWorkspace,
     op_id: OperationId,
@@ -2700,6 +3118,109 @@ pub fn print_conflicted_paths(
     Ok(())
 }
 
+enum ConflictHint {
+    NotAFile,
+    Deleted { deleted_side: usize },
+    Binary,
+}
+
+/// Prints hints suggesting how to resolve the conflicts in `tree` which can't
+/// be resolved by editing conflict markers.
+#[instrument(skip_all)]
+pub fn print_conflict_resolution_hints(
+    formatter: &mut dyn Formatter,
+    tree: &MergedTree,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    if !workspace_command
+        .settings()
+        .get_bool("hints.resolving-conflicts")?
+    {
+        return Ok(());
+    }
+    // A hint per conflict would bury the rest of the output, and telling binary
+    // files apart means reading them, so only look at the first few of each.
+    const MAX_HINTS: usize = 5;
+    const MAX_PROBED_FILES: usize = 10;
+    let mut num_hints = 0;
+    let mut num_probed_files = 0;
+    for (path, conflict) in tree.conflicts() {
+        // Errors are reported by print_conflicted_paths().
+        let Ok(conflict) = conflict else {
+            continue;
+        };
+        let conflict = conflict.simplify();
+        let hint = match conflict.to_file_merge() {
+            None => ConflictHint::NotAFile,
+            // Only 2-sided conflicts can be resolved by picking a side.
+            Some(file_merge) if file_merge.num_sides() != 2 => continue,
+            Some(file_merge) => {
+                if let Some(deleted_side) = file_merge.adds().position(|id| id.is_none()) {
+                    ConflictHint::Deleted { deleted_side }
+                } else if num_probed_files < MAX_PROBED_FILES {
+                    num_probed_files += 1;
+                    let summary =
+                        ConflictSummary::new(tree.store(), path.clone(), conflict).block_on()?;
+                    if !summary.is_binary {
+                        continue;
+                    }
+                    ConflictHint::Binary
+                } else {
+                    continue;
+                }
+            }
+        };
+        if num_hints == MAX_HINTS {
+            writeln!(
+                formatter.labeled("hint").with_heading("Hint: "),
+                "More conflicts may need to be resolved this way. Run `jj resolve --list` to see \
+                 all of them."
+            )?;
+            break;
+        }
+        num_hints += 1;
+        let ui_path = workspace_command.format_file_path(&path);
+        let quoted_path = shell_quote(&ui_path);
+        match hint {
+            ConflictHint::NotAFile => {
+                writeln!(
+                    formatter.labeled("hint").with_heading("Hint: "),
+                    "{ui_path} conflicts with a symlink, directory, or submodule, which `jj \
+                     resolve` cannot handle. Replace it with a regular file or remove it to \
+                     resolve the conflict."
+                )?;
+            }
+            ConflictHint::Deleted { deleted_side } => {
+                let (keep_tool, delete_tool) = if deleted_side == 0 {
+                    (":theirs", ":ours")
+                } else {
+                    (":ours", ":theirs")
+                };
+                writedoc!(
+                    formatter.labeled("hint").with_heading("Hint: "),
+                    "
+                    {ui_path} was deleted on one side of the conflict. To keep the modified file, run:
+                      jj resolve --tool {keep_tool} {quoted_path}
+                    To delete the file, run:
+                      jj resolve --tool {delete_tool} {quoted_path}
+                    "
+                )?;
+            }
+            ConflictHint::Binary => {
+                writedoc!(
+                    formatter.labeled("hint").with_heading("Hint: "),
+                    "
+                    {ui_path} is a binary file. To take one side of the conflict, run one of:
+                      jj resolve --tool :ours {quoted_path}
+                      jj resolve --tool :theirs {quoted_path}
+                    "
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Build human-readable messages explaining why the file was not tracked
 fn build_untracked_reason_message(reason: &UntrackedReason) -> Option<String> {
     match reason {
@@ -2716,7 +3237,10 @@ fn build_untracked_reason_message(reason: &UntrackedReason) -> Option<String> {
         // Paths with UntrackedReason::FileNotAutoTracked shouldn't be warned about
         // every time we make a snapshot. These paths will be printed by
         // "jj status" instead.
-        UntrackedReason::FileNotAutoTracked => None,
+        UntrackedReason::FileNotAutoTracked | UntrackedReason::Ignored => None,
+        UntrackedReason::NewDirectoryTooLarge { max_files } => Some(format!(
+            "new directory with more than {max_files} files; it's likely a build output"
+        )),
     }
 }
 
@@ -2755,7 +3279,9 @@ pub fn print_snapshot_stats(
         .values()
         .filter_map(|reason| match reason {
             UntrackedReason::FileTooLarge { size, .. } => Some(size),
-            UntrackedReason::FileNotAutoTracked => None,
+            UntrackedReason::FileNotAutoTracked
+            | UntrackedReason::NewDirectoryTooLarge { .. }
+            | UntrackedReason::Ignored => None,
         });
     if let Some(size) = large_files_sizes.max() {
         writedoc!(
@@ -2770,6 +3296,85 @@ pub fn print_snapshot_stats(
             "
         )?;
     }
+
+    let has_large_new_directories = stats
+        .untracked_paths
+        .values()
+        .any(|reason| matches!(reason, UntrackedReason::NewDirectoryTooLarge { .. }));
+    if has_large_new_directories {
+        writedoc!(
+            ui.hint_default(),
+            r"
+            This is to prevent build outputs from being added by accident. You can fix this by:
+              - Adding the directory to `.gitignore`
+              - Run `jj --config snapshot.new-directory-policy=allow st`
+                This will snapshot the directory, for this command only.
+            "
+        )?;
+    }
+
+    if !stats.large_new_directories.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "Snapshotted new directories with many files:"
+        )?;
+        let gitignore_path =
+            path_converter.format_file_path(RepoPath::from_internal_string(".gitignore").unwrap());
+        let mut commands = String::new();
+        {
+            let mut formatter = ui.stderr_formatter();
+            for dir in &stats.large_new_directories {
+                let ui_path = path_converter.format_file_path(dir);
+                writeln!(formatter, "  {ui_path}")?;
+                // Escape glob characters so the pattern matches the directory
+                // literally, and fall back to a quoted fileset if the path
+                // contains characters the fileset parser would interpret.
+                let mut pattern = "/".to_owned();
+                for c in dir.as_internal_file_string().chars() {
+                    if matches!(c, '\\' | '*' | '?' | '[') {
+                        pattern.push('\\');
+                    }
+                    pattern.push(c);
+                }
+                pattern.push('/');
+                let fileset = if ui_path
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '/' | '_'))
+                {
+                    ui_path
+                } else {
+                    format!(
+                        "root:{}",
+                        revset::format_string(dir.as_internal_file_string())
+                    )
+                };
+                commands.push_str(&format!(
+                    "  echo {pattern} >> {gitignore_path} && jj file untrack {fileset}\n",
+                    pattern = shell_quote(&pattern),
+                    gitignore_path = shell_quote(&gitignore_path),
+                    fileset = shell_quote(&fileset),
+                ));
+            }
+        }
+        write!(
+            ui.hint_default(),
+            "If these are build outputs, ignore and untrack them with:\n{commands}"
+        )?;
+    }
+
+    if !stats.malformed_conflicts.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "Some conflict markers couldn't be parsed and were kept as regular text:"
+        )?;
+        let mut formatter = ui.stderr_formatter();
+        for (path, hunks) in &stats.malformed_conflicts {
+            let ui_path = path_converter.format_file_path(path);
+            for hunk in hunks {
+                writeln!(formatter, "  {ui_path}:{}: {}", hunk.line, hunk.reason)?;
+            }
+        }
+    }
     Ok(())
 }
 
@@ -2805,6 +3410,56 @@ Discard the conflicting changes with `jj restore --from {}`.",
     Ok(())
 }
 
+/// Reports a checkout that was resumed after being interrupted.
+pub fn print_recovered_checkout_stats(
+    ui: &Ui,
+    recovery: CheckoutRecovery,
+    stats: &CheckoutStats,
+) -> io::Result<()> {
+    match recovery {
+        CheckoutRecovery::Resume => writeln!(
+            ui.warning_default(),
+            "Resumed an interrupted working copy update."
+        )?,
+        CheckoutRecovery::Rollback => writeln!(
+            ui.warning_default(),
+            "Rolled back an interrupted working copy update."
+        )?,
+    }
+    writeln!(
+        ui.status(),
+        "Added {} files, modified {} files, removed {} files",
+        stats.added_files,
+        stats.updated_files,
+        stats.removed_files
+    )?;
+    Ok(())
+}
+
+/// Reads fileset expressions from the file at `path`, one per line. Empty
+/// lines are skipped. If `path` is `-`, reads from stdin.
+pub fn read_pathspec_file(path: &Path) -> Result<Vec<String>, CommandError> {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|err| user_error_with_message("Failed to read filesets from stdin", err))?;
+        content
+    } else {
+        fs::read_to_string(path).map_err(|err| {
+            user_error_with_message(
+                format!("Failed to read filesets from {}", path.display()),
+                err,
+            )
+        })?
+    };
+    Ok(content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
 /// Prints warning about explicit paths that don't match any of the tree
 /// entries.
 pub fn print_unmatched_explicit_paths<'a>(
@@ -2869,6 +3524,34 @@ pub fn has_tracked_remote_bookmarks(view: &View, bookmark: &RefName) -> bool {
     .any(|(_, remote_ref)| remote_ref.is_tracked())
 }
 
+/// Loads `.mailmap` file in the workspace root. Returns an empty mailmap if
+/// the file doesn't exist.
+fn load_mailmap(workspace_root: &Path) -> Result<Mailmap, CommandError> {
+    let path = workspace_root.join(".mailmap");
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(Mailmap::parse(&text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Mailmap::empty()),
+        Err(err) => Err(user_error_with_message(
+            format!("Failed to read {}", path.display()),
+            err,
+        )),
+    }
+}
+
+/// Loads `.jj/attributes` file whose rules take precedence over the
+/// `.gitattributes` files in the trees. Missing file is ignored.
+fn load_attribute_overrides(workspace_root: &Path) -> Result<GitAttributes, CommandError> {
+    let path = workspace_root.join(".jj").join("attributes");
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(GitAttributes::parse(&text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(GitAttributes::empty()),
+        Err(err) => Err(user_error_with_message(
+            format!("Failed to read {}", path.display()),
+            err,
+        )),
+    }
+}
+
 pub fn load_template_aliases(
     ui: &Ui,
     stacked_config: &StackedConfig,
@@ -3260,6 +3943,9 @@ pub struct EarlyArgs {
     // Parsing with ignore_errors will crash if this is bool, so use
     // Option<bool>.
     pub no_pager: Option<bool>,
+    /// Format of error messages
+    #[arg(long, value_name = "FORMAT", global = true)]
+    pub error_format: Option<ErrorFormat>,
     /// Additional configuration options (can be repeated)
     ///
     /// The name should be specified as TOML dotted keys. The value should be
@@ -3507,6 +4193,11 @@ fn parse_early_args(
     if args.no_pager.unwrap_or_default() {
         layer.set_value("ui.paginate", "never").unwrap();
     }
+    if let Some(format) = args.error_format {
+        layer
+            .set_value("ui.error-format", format.to_string())
+            .unwrap();
+    }
     if !layer.is_empty() {
         config_layers.push(layer);
     }
@@ -3674,6 +4365,9 @@ impl<'a> CliRunner<'a> {
     pub fn init() -> Self {
         let tracing_subscription = TracingSubscription::init();
         crate::cleanup_guard::init();
+        let mut revset_extensions = RevsetExtensions::default();
+        revset_extensions.add_custom_function("tests", crate::test_results::tests_revset_function);
+        revset_extensions.add_custom_function("topic", crate::revset_util::topic_revset_function);
         CliRunner {
             tracing_subscription,
             app: crate::commands::default_app(),
@@ -3682,7 +4376,7 @@ impl<'a> CliRunner<'a> {
             store_factories: StoreFactories::default(),
             working_copy_factories: default_working_copy_factories(),
             workspace_loader_factory: Box::new(DefaultWorkspaceLoaderFactory),
-            revset_extensions: Default::default(),
+            revset_extensions,
             commit_template_extensions: vec![],
             operation_template_extensions: vec![],
             dispatch_fn: Box::new(crate::commands::run_command),
@@ -3813,6 +4507,33 @@ impl<'a> CliRunner<'a> {
         self
     }
 
+    /// Registers a new subcommand built with the clap builder API.
+    ///
+    /// Unlike [`Self::add_subcommand()`], the subcommand doesn't have to be
+    /// defined as a type deriving [`clap::Subcommand`], so commands can be
+    /// constructed at runtime (e.g. from a plugin manifest.) The `handler` is
+    /// called with the matches of the subcommand if it was invoked.
+    pub fn add_subcommand_extension<F>(mut self, command: clap::Command, handler: F) -> Self
+    where
+        F: FnOnce(&mut Ui, &CommandHelper, &ArgMatches) -> Result<(), CommandError> + 'a,
+    {
+        let name = command.get_name().to_owned();
+        let old_dispatch_fn = self.dispatch_fn;
+        let new_dispatch_fn =
+            move |ui: &mut Ui, command_helper: &CommandHelper| match command_helper
+                .matches()
+                .subcommand()
+            {
+                Some((subcommand_name, sub_matches)) if subcommand_name == name => {
+                    handler(ui, command_helper, sub_matches)
+                }
+                _ => old_dispatch_fn(ui, command_helper),
+            };
+        self.app = self.app.subcommand(command);
+        self.dispatch_fn = Box::new(new_dispatch_fn);
+        self
+    }
+
     /// Registers new global arguments in addition to the default ones.
     pub fn add_global_args<A, F>(mut self, process_before: F) -> Self
     where
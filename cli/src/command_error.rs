@@ -35,6 +35,7 @@ use jj_lib::fileset::FilesetParseError;
 use jj_lib::fileset::FilesetParseErrorKind;
 use jj_lib::fix::FixError;
 use jj_lib::gitignore::GitIgnoreError;
+use jj_lib::op_bundle::OpBundleError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
@@ -55,6 +56,7 @@ use jj_lib::str_util::StringPatternParseError;
 use jj_lib::trailer::TrailerParseError;
 use jj_lib::transaction::TransactionCommitError;
 use jj_lib::view::RenameWorkspaceError;
+use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::RecoverWorkspaceError;
 use jj_lib::working_copy::ResetError;
 use jj_lib::working_copy::SnapshotError;
@@ -77,6 +79,7 @@ use crate::revset_util::BookmarkNameParseError;
 use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_parser::TemplateParseError;
 use crate::template_parser::TemplateParseErrorKind;
+use crate::ui::ErrorFormat;
 use crate::ui::Ui;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -321,6 +324,12 @@ impl From<BackendError> for CommandError {
     fn from(err: BackendError) -> Self {
         match &err {
             BackendError::Unsupported(_) => user_error(err),
+            BackendError::CorruptObject { hint, .. } => {
+                let hint = hint.clone();
+                let mut cmd_err = internal_error_with_message("Corrupt object in the backend", err);
+                cmd_err.extend_hints(hint);
+                cmd_err
+            }
             _ => internal_error_with_message("Unexpected error from backend", err),
         }
     }
@@ -392,6 +401,19 @@ impl From<SnapshotError> for CommandError {
     }
 }
 
+impl From<OpBundleError> for CommandError {
+    fn from(err: OpBundleError) -> Self {
+        match err {
+            OpBundleError::InvalidFormat(_) | OpBundleError::MissingObject { .. } => {
+                user_error(err)
+            }
+            OpBundleError::Io(_) | OpBundleError::OpStore(_) | OpBundleError::Backend(_) => {
+                internal_error_with_message("Failed to transfer the operation bundle", err)
+            }
+        }
+    }
+}
+
 impl From<OpStoreError> for CommandError {
     fn from(err: OpStoreError) -> Self {
         internal_error_with_message("Failed to load an operation", err)
@@ -404,6 +426,12 @@ impl From<RepoLoaderError> for CommandError {
     }
 }
 
+impl From<CheckoutError> for CommandError {
+    fn from(err: CheckoutError) -> Self {
+        internal_error_with_message("Failed to update the working copy", err)
+    }
+}
+
 impl From<ResetError> for CommandError {
     fn from(err: ResetError) -> Self {
         internal_error_with_message("Failed to reset the working copy", err)
@@ -938,6 +966,9 @@ fn template_parse_error_hint(err: &TemplateParseError) -> Option<String> {
 
 const BROKEN_PIPE_EXIT_CODE: u8 = 3;
 
+const CONFIG_HELP_HINT: &str =
+    "For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k config`.";
+
 pub(crate) fn handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> u8 {
     try_handle_command_result(ui, result).unwrap_or(BROKEN_PIPE_EXIT_CODE)
 }
@@ -946,6 +977,9 @@ fn try_handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> i
     let Err(cmd_err) = &result else {
         return Ok(0);
     };
+    if ui.error_format() == ErrorFormat::Json {
+        return print_error_json(ui, cmd_err);
+    }
     let err = &cmd_err.error;
     let hints = &cmd_err.hints;
     match cmd_err.kind {
@@ -955,11 +989,7 @@ fn try_handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> i
         }
         CommandErrorKind::Config => {
             print_error(ui, "Config error: ", err, hints)?;
-            writeln!(
-                ui.stderr_formatter().labeled("hint"),
-                "For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k \
-                 config`."
-            )?;
+            writeln!(ui.stderr_formatter().labeled("hint"), "{CONFIG_HELP_HINT}")?;
             Ok(1)
         }
         CommandErrorKind::Cli => {
@@ -981,6 +1011,64 @@ fn try_handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> i
     }
 }
 
+/// Error report to be serialized by `--error-format=json`.
+#[derive(serde::Serialize)]
+struct JsonErrorReport {
+    kind: &'static str,
+    message: String,
+    sources: Vec<String>,
+    hints: Vec<String>,
+}
+
+fn print_error_json(ui: &mut Ui, cmd_err: &CommandError) -> io::Result<u8> {
+    let err = &cmd_err.error;
+    let mut hints = cmd_err
+        .hints
+        .iter()
+        .map(|hint| match hint {
+            ErrorHint::PlainText(message) => message.clone(),
+            ErrorHint::Formatted(recorded) => String::from_utf8_lossy(recorded.data())
+                .trim_end()
+                .to_owned(),
+        })
+        .collect_vec();
+    let (kind, exit_code) = match cmd_err.kind {
+        CommandErrorKind::User => ("user", 1),
+        CommandErrorKind::Config => {
+            hints.push(CONFIG_HELP_HINT.to_owned());
+            ("config", 1)
+        }
+        CommandErrorKind::Cli => {
+            if let Some(err) = err.downcast_ref::<clap::Error>() {
+                // Help and version requests aren't errors to be reported.
+                if matches!(
+                    err.kind(),
+                    clap::error::ErrorKind::DisplayHelp
+                        | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+                        | clap::error::ErrorKind::DisplayVersion
+                ) {
+                    return handle_clap_error(ui, err, &cmd_err.hints);
+                }
+            }
+            ("cli", 2)
+        }
+        CommandErrorKind::BrokenPipe => return Ok(BROKEN_PIPE_EXIT_CODE),
+        CommandErrorKind::Internal => ("internal", 255),
+    };
+    let report = JsonErrorReport {
+        kind,
+        message: err.to_string().trim_end().to_owned(),
+        sources: iter::successors(err.source(), |&err| err.source())
+            .map(|err| err.to_string())
+            .collect(),
+        hints,
+    };
+    let mut stderr = ui.stderr();
+    serde_json::to_writer(&mut stderr, &report)?;
+    writeln!(stderr)?;
+    Ok(exit_code)
+}
+
 fn print_error(
     ui: &Ui,
     heading: &str,
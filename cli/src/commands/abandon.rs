@@ -111,6 +111,7 @@ pub(crate) fn cmd_abandon(
     let mut tx = workspace_command.start_transaction();
     let options = RewriteRefsOptions {
         delete_abandoned_bookmarks: !args.retain_bookmarks,
+        ..Default::default()
     };
     let mut num_rebased = 0;
     tx.repo_mut().transform_descendants_with_options(
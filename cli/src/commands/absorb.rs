@@ -16,6 +16,7 @@ use clap_complete::ArgValueCompleter;
 use jj_lib::absorb::absorb_hunks;
 use jj_lib::absorb::split_hunks_to_trees;
 use jj_lib::absorb::AbsorbSource;
+use jj_lib::absorb::UnambiguousAbsorbPolicy;
 use jj_lib::matchers::EverythingMatcher;
 use pollster::FutureExt as _;
 use tracing::instrument;
@@ -51,7 +52,10 @@ pub(crate) struct AbsorbArgs {
     from: RevisionArg,
     /// Destination revisions to absorb into
     ///
-    /// Only ancestors of the source revision will be considered.
+    /// Only ancestors of the source revision will be considered. The
+    /// destinations don't have to form a contiguous range (e.g.
+    /// `mutable() & mine()`). Changes to lines last modified by revisions
+    /// outside of the destinations will be left in the source revision.
     #[arg(
         long, short = 't', visible_alias = "to",
         default_value = "mutable()",
@@ -87,7 +91,14 @@ pub(crate) fn cmd_absorb(
 
     let repo = workspace_command.repo().as_ref();
     let source = AbsorbSource::from_commit(repo, source_commit)?;
-    let selected_trees = split_hunks_to_trees(repo, &source, &destinations, &matcher).block_on()?;
+    let selected_trees = split_hunks_to_trees(
+        repo,
+        &source,
+        &destinations,
+        &matcher,
+        &UnambiguousAbsorbPolicy,
+    )
+    .block_on()?;
 
     let path_converter = workspace_command.path_converter();
     for (path, reason) in selected_trees.skipped_paths {
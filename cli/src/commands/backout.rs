@@ -23,6 +23,8 @@ use tracing::instrument;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
+use crate::commands::revert::new_conflict_paths;
+use crate::commands::revert::revert_conflict_labels;
 use crate::complete;
 use crate::ui::Ui;
 
@@ -115,6 +117,11 @@ pub(crate) fn cmd_backout(
         let old_base_tree = commit_to_back_out.parent_tree(tx.repo())?;
         let old_tree = commit_to_back_out.tree()?;
         let new_tree = new_base_tree.merge(old_tree, old_base_tree).block_on()?;
+        if new_tree.has_conflict() {
+            let labels = revert_conflict_labels(&commit_to_back_out)
+                .with_paths(new_conflict_paths(&new_base_tree, &new_tree)?);
+            tx.add_conflict_labels(new_tree.id(), labels);
+        }
         let new_parent_ids = parents.iter().map(|commit| commit.id().clone()).collect();
         let new_commit = tx
             .repo_mut()
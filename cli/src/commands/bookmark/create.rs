@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteNameBuf;
 
+use super::print_missing_remote_bookmarks_hint;
+use super::push_bookmarks_to_remote;
+use super::track_bookmarks_at_remote;
 use crate::cli_util::has_tracked_remote_bookmarks;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
@@ -45,6 +50,23 @@ pub struct BookmarkCreateArgs {
     )]
     revision: Option<RevisionArg>,
 
+    /// Track the bookmarks at this remote
+    ///
+    /// Existing remote bookmarks are tracked immediately. Remote bookmarks
+    /// that don't exist yet will be tracked once pushed.
+    #[arg(
+        long,
+        value_name = "REMOTE",
+        add = ArgValueCandidates::new(complete::git_remotes),
+    )]
+    at_remote: Option<RemoteNameBuf>,
+
+    /// Push the bookmarks to the remote given by `--at-remote`
+    ///
+    /// The bookmarks are updated and pushed in two separate operations.
+    #[arg(long, requires = "at_remote")]
+    push: bool,
+
     /// The bookmarks to create
     #[arg(required = true, value_parser = revset_util::parse_bookmark_name)]
     names: Vec<RefNameBuf>,
@@ -90,6 +112,12 @@ pub fn cmd_bookmark_create(
     }
 
     let mut tx = workspace_command.start_transaction();
+    // Track existing remote bookmarks first so the local bookmarks will point
+    // to the target revision rather than to the remote targets.
+    let missing_remote_names = match &args.at_remote {
+        Some(remote) => track_bookmarks_at_remote(tx.repo_mut(), bookmark_names, remote),
+        None => vec![],
+    };
     for name in bookmark_names {
         tx.repo_mut()
             .set_local_bookmark_target(name, RefTarget::normal(target_commit.id().clone()));
@@ -112,5 +140,12 @@ pub fn cmd_bookmark_create(
             id = target_commit.id().hex()
         ),
     )?;
+    if let Some(remote) = &args.at_remote {
+        if args.push {
+            push_bookmarks_to_remote(ui, command, bookmark_names, remote)?;
+        } else {
+            print_missing_remote_bookmarks_hint(ui, &missing_remote_names, remote)?;
+        }
+    }
     Ok(())
 }
@@ -18,7 +18,10 @@ use jj_lib::op_store::RefTarget;
 use jj_lib::str_util::StringPattern;
 
 use super::find_local_bookmarks;
+use super::find_unmerged_bookmarks;
+use super::print_bookmark_impact;
 use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -33,6 +36,10 @@ use crate::ui::Ui;
 ///
 /// If you don't want the deletion of the local bookmark to propagate to any
 /// tracked remote bookmarks, use `jj bookmark forget` instead.
+///
+/// Deleting bookmarks selected by a non-exact pattern requires `--force` if
+/// any of them isn't merged into `trunk()` or any remote bookmark. Use
+/// `--dry-run` to review the matched bookmarks first.
 #[derive(clap::Args, Clone, Debug)]
 pub struct BookmarkDeleteArgs {
     /// The bookmarks to delete
@@ -48,6 +55,12 @@ pub struct BookmarkDeleteArgs {
         add = ArgValueCandidates::new(complete::local_bookmarks),
     )]
     names: Vec<StringPattern>,
+    /// List the matched bookmarks without deleting them
+    #[arg(long)]
+    dry_run: bool,
+    /// Delete bookmarks matched by patterns even if they aren't merged
+    #[arg(long)]
+    force: bool,
 }
 
 pub fn cmd_bookmark_delete(
@@ -58,6 +71,42 @@ pub fn cmd_bookmark_delete(
     let mut workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo().clone();
     let matched_bookmarks = find_local_bookmarks(repo.view(), &args.names)?;
+    let unmerged_bookmarks = if args.names.iter().all(|pattern| pattern.is_exact()) {
+        vec![]
+    } else {
+        find_unmerged_bookmarks(ui, &workspace_command, &matched_bookmarks)?
+    };
+    if args.dry_run {
+        print_bookmark_impact(
+            ui,
+            &workspace_command,
+            matched_bookmarks.iter().map(|(name, _)| *name),
+        )?;
+        if !unmerged_bookmarks.is_empty() && !args.force {
+            writeln!(
+                ui.warning_default(),
+                "Deleting unmerged bookmarks requires --force: {}",
+                unmerged_bookmarks
+                    .iter()
+                    .map(|name| name.as_symbol())
+                    .join(", ")
+            )?;
+        }
+        writeln!(ui.status(), "Dry-run requested, not deleting bookmarks.")?;
+        return Ok(());
+    }
+    if !unmerged_bookmarks.is_empty() && !args.force {
+        return Err(user_error_with_hint(
+            format!(
+                "Refusing to delete bookmarks not merged into trunk() or any remote bookmark: {}",
+                unmerged_bookmarks
+                    .iter()
+                    .map(|name| name.as_symbol())
+                    .join(", ")
+            ),
+            "Use --force to delete them anyway, or --dry-run to review the matched bookmarks.",
+        ));
+    }
     let mut tx = workspace_command.start_transaction();
     for (name, _) in &matched_bookmarks {
         tx.repo_mut()
@@ -22,6 +22,7 @@ use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
 
 use super::find_bookmarks_with;
+use super::print_bookmark_impact;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -56,6 +57,9 @@ pub struct BookmarkForgetArgs {
         add = ArgValueCandidates::new(complete::bookmarks),
     )]
     names: Vec<StringPattern>,
+    /// List the matched bookmarks without forgetting them
+    #[arg(long)]
+    dry_run: bool,
 }
 
 pub fn cmd_bookmark_forget(
@@ -66,6 +70,15 @@ pub fn cmd_bookmark_forget(
     let mut workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo().clone();
     let matched_bookmarks = find_forgettable_bookmarks(repo.view(), &args.names)?;
+    if args.dry_run {
+        print_bookmark_impact(
+            ui,
+            &workspace_command,
+            matched_bookmarks.iter().map(|(name, _)| *name),
+        )?;
+        writeln!(ui.status(), "Dry-run requested, not forgetting bookmarks.")?;
+        return Ok(());
+    }
     let mut tx = workspace_command.start_transaction();
     let mut forgotten_remote: usize = 0;
     for (name, bookmark_target) in &matched_bookmarks {
@@ -20,6 +20,7 @@ use std::sync::Arc;
 
 use clap::ValueEnum;
 use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::backend;
 use jj_lib::backend::CommitId;
@@ -113,7 +114,7 @@ pub struct BookmarkListArgs {
     ///
     /// [`jj help -k templates`]:
     ///     https://jj-vcs.github.io/jj/latest/templates/
-    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::template_expression))]
     template: Option<String>,
 
     /// Sort bookmarks based on the given key (or multiple keys)
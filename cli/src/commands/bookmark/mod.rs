@@ -22,12 +22,18 @@ mod set;
 mod track;
 mod untrack;
 
+use std::io;
+use std::io::Write as _;
+
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteName;
 use jj_lib::ref_name::RemoteRefSymbol;
+use jj_lib::repo::MutableRepo;
 use jj_lib::repo::Repo;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
@@ -52,6 +58,8 @@ use self::untrack::cmd_bookmark_untrack;
 use self::untrack::BookmarkUntrackArgs;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RemoteBookmarkNamePattern;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -174,6 +182,70 @@ fn find_remote_bookmarks<'a>(
     }
 }
 
+/// Prints the given bookmarks with their local targets and the remotes which
+/// still reference them.
+fn print_bookmark_impact<'a>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    names: impl IntoIterator<Item = &'a RefName>,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let view = repo.view();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    for name in names {
+        write!(formatter.labeled("bookmark"), "{}", name.as_symbol())?;
+        let local_target = view.get_local_bookmark(name);
+        if let Some(id) = local_target.as_normal() {
+            write!(formatter, ": ")?;
+            let commit = repo.store().get_commit(id)?;
+            workspace_command.write_commit_summary(formatter, &commit)?;
+            writeln!(formatter)?;
+        } else if local_target.has_conflict() {
+            writeln!(formatter, " (conflicted)")?;
+        } else {
+            writeln!(formatter, " (deleted)")?;
+        }
+        let remotes = view
+            .all_remote_bookmarks()
+            .filter(|(symbol, remote_ref)| {
+                symbol.name == name
+                    && remote_ref.is_present()
+                    && !jj_lib::git::is_special_git_remote(symbol.remote)
+            })
+            .map(|(symbol, _)| symbol.remote.as_symbol())
+            .join(", ");
+        if !remotes.is_empty() {
+            writeln!(formatter, "  Still present on remotes: {remotes}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the bookmarks whose local targets are neither merged into
+/// `trunk()` nor reachable from any remote bookmark.
+fn find_unmerged_bookmarks<'a>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    bookmarks: &[(&'a RefName, &RefTarget)],
+) -> Result<Vec<&'a RefName>, CommandError> {
+    let merged_revset = RevisionArg::from("::(present(trunk()) | remote_bookmarks())".to_owned());
+    let merged = workspace_command
+        .parse_revset(ui, &merged_revset)?
+        .evaluate()?;
+    let is_merged = merged.containing_fn();
+    let mut unmerged = vec![];
+    for (name, target) in bookmarks {
+        for id in target.added_ids() {
+            if !is_merged(id)? {
+                unmerged.push(*name);
+                break;
+            }
+        }
+    }
+    Ok(unmerged)
+}
+
 fn is_fast_forward(repo: &dyn Repo, old_target: &RefTarget, new_target_id: &CommitId) -> bool {
     if old_target.is_present() {
         // Strictly speaking, "all" old targets should be ancestors, but we allow
@@ -186,3 +258,66 @@ fn is_fast_forward(repo: &dyn Repo, old_target: &RefTarget, new_target_id: &Comm
         true
     }
 }
+
+/// Tracks the remote bookmarks of the given `names` at `remote`. Returns the
+/// names of the bookmarks which don't exist on the remote yet, and therefore
+/// can't be tracked until they are pushed.
+fn track_bookmarks_at_remote<'a>(
+    repo: &mut MutableRepo,
+    names: &'a [RefNameBuf],
+    remote: &RemoteName,
+) -> Vec<&'a RefNameBuf> {
+    let mut missing_names = vec![];
+    for name in names {
+        let symbol = name.to_remote_symbol(remote);
+        let remote_ref = repo.get_remote_bookmark(symbol);
+        if remote_ref.is_absent() {
+            missing_names.push(name);
+        } else if !remote_ref.is_tracked() {
+            repo.track_remote_bookmark(symbol);
+        }
+    }
+    missing_names
+}
+
+/// Prints a hint about creating the remote bookmarks which couldn't be tracked
+/// by [`track_bookmarks_at_remote()`].
+fn print_missing_remote_bookmarks_hint(
+    ui: &Ui,
+    missing_names: &[&RefNameBuf],
+    remote: &RemoteName,
+) -> io::Result<()> {
+    if missing_names.is_empty() {
+        return Ok(());
+    }
+    let bookmark_args = missing_names
+        .iter()
+        .map(|name| format!("--bookmark {}", name.as_symbol()))
+        .join(" ");
+    writeln!(
+        ui.hint_default(),
+        "Remote bookmarks will be tracked once pushed. Use `--push` or run `jj git push \
+         --remote {remote} --allow-new {bookmark_args}` to push them.",
+        remote = remote.as_symbol(),
+    )
+}
+
+/// Pushes the given bookmarks to `remote` in a separate operation.
+fn push_bookmarks_to_remote(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    names: &[RefNameBuf],
+    remote: &RemoteName,
+) -> Result<(), CommandError> {
+    #[cfg(feature = "git")]
+    {
+        crate::commands::git::push_bookmarks(ui, command, remote, names)
+    }
+    #[cfg(not(feature = "git"))]
+    {
+        let _ = (ui, command, names, remote);
+        Err(user_error(
+            "Cannot push bookmarks because jj was compiled without Git support",
+        ))
+    }
+}
@@ -18,8 +18,12 @@ use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteNameBuf;
 
 use super::is_fast_forward;
+use super::print_missing_remote_bookmarks_hint;
+use super::push_bookmarks_to_remote;
+use super::track_bookmarks_at_remote;
 use crate::cli_util::has_tracked_remote_bookmarks;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
@@ -49,6 +53,23 @@ pub struct BookmarkSetArgs {
     #[arg(long, short = 'B')]
     allow_backwards: bool,
 
+    /// Track the bookmarks at this remote
+    ///
+    /// Existing remote bookmarks are tracked immediately. Remote bookmarks
+    /// that don't exist yet will be tracked once pushed.
+    #[arg(
+        long,
+        value_name = "REMOTE",
+        add = ArgValueCandidates::new(complete::git_remotes),
+    )]
+    at_remote: Option<RemoteNameBuf>,
+
+    /// Push the bookmarks to the remote given by `--at-remote`
+    ///
+    /// The bookmarks are updated and pushed in two separate operations.
+    #[arg(long, requires = "at_remote")]
+    push: bool,
+
     /// The bookmarks to update
     #[arg(
         required = true,
@@ -98,6 +119,12 @@ pub fn cmd_bookmark_set(
     }
 
     let mut tx = workspace_command.start_transaction();
+    // Track existing remote bookmarks first so the local bookmarks will point
+    // to the target revision rather than to the remote targets.
+    let missing_remote_names = match &args.at_remote {
+        Some(remote) => track_bookmarks_at_remote(tx.repo_mut(), bookmark_names, remote),
+        None => vec![],
+    };
     for bookmark_name in bookmark_names {
         tx.repo_mut().set_local_bookmark_target(
             bookmark_name,
@@ -132,5 +159,12 @@ pub fn cmd_bookmark_set(
             id = target_commit.id().hex()
         ),
     )?;
+    if let Some(remote) = &args.at_remote {
+        if args.push {
+            push_bookmarks_to_remote(ui, command, bookmark_names, remote)?;
+        } else {
+            print_missing_remote_bookmarks_hint(ui, &missing_remote_names, remote)?;
+        }
+    }
     Ok(())
 }
@@ -32,6 +32,9 @@ use crate::ui::Ui;
 /// A tracking remote bookmark will be imported as a local bookmark of the same
 /// name. Changes to it will propagate to the existing local bookmark on future
 /// pulls.
+///
+/// Remote bookmarks can also be tracked automatically when they are fetched
+/// by setting `remotes.<name>.auto-track-bookmarks` to a string pattern.
 #[derive(clap::Args, Clone, Debug)]
 pub struct BookmarkTrackArgs {
     /// Remote bookmarks to track
@@ -44,11 +47,18 @@ pub struct BookmarkTrackArgs {
     /// [wildcard pattern]:
     ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
     #[arg(
-        required = true,
+        required_unless_present = "auto_from_config",
         value_name = "BOOKMARK@REMOTE",
         add = ArgValueCandidates::new(complete::untracked_bookmarks),
     )]
     names: Vec<RemoteBookmarkNamePattern>,
+    /// Track existing remote bookmarks matching the configured
+    /// `remotes.<name>.auto-track-bookmarks` patterns
+    ///
+    /// Bookmarks of remotes without a pattern are tracked if
+    /// `git.auto-local-bookmark` is enabled.
+    #[arg(long)]
+    auto_from_config: bool,
 }
 
 pub fn cmd_bookmark_track(
@@ -69,6 +79,18 @@ pub fn cmd_bookmark_track(
             symbols.push(symbol);
         }
     }
+    if args.auto_from_config {
+        let git_settings = workspace_command.settings().git_settings()?;
+        for (symbol, remote_ref) in repo.view().all_remote_bookmarks() {
+            if !remote_ref.is_tracked()
+                && git_settings.should_auto_track_bookmark(symbol)
+                && !symbols.contains(&symbol)
+            {
+                symbols.push(symbol);
+            }
+        }
+        symbols.sort_unstable();
+    }
     let mut tx = workspace_command.start_transaction();
     for &symbol in &symbols {
         tx.repo_mut().track_remote_bookmark(symbol);
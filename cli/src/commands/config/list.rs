@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::settings::UserSettings;
@@ -31,6 +32,9 @@ use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// List variables set in config files, along with their values.
+///
+/// Values of variables named like `*.token`, `*.password`, `*-token`, or
+/// `*-password` are redacted. Use `jj config get` to print them.
 #[derive(clap::Args, Clone, Debug)]
 #[command(mut_group("config_level", |g| g.required(false)))]
 pub struct ConfigListArgs {
@@ -66,7 +70,7 @@ pub struct ConfigListArgs {
     #[arg(
         long, short = 'T',
         verbatim_doc_comment,
-        add = ArgValueCandidates::new(complete::template_aliases)
+        add = ArgValueCompleter::new(complete::template_expression)
     )]
     template: Option<String>,
 }
@@ -101,6 +105,9 @@ pub fn cmd_config_list(
     if !args.include_overridden {
         annotated_values.retain(|annotated| !annotated.is_overridden);
     }
+    for annotated in &mut annotated_values {
+        annotated.redact_secret();
+    }
 
     if !annotated_values.is_empty() {
         ui.request_pager();
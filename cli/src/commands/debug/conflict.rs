@@ -0,0 +1,124 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io;
+use std::io::Write as _;
+
+use jj_lib::gitattributes::TreeAttributes;
+use jj_lib::merge::Merge;
+use jj_lib::repo::Repo as _;
+use jj_lib::tree::explain_file_conflict;
+use jj_lib::tree::FileConflictFailure;
+use pollster::FutureExt as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show how the merge at a path is simplified and resolved.
+///
+/// Prints the unsimplified terms of the merge, the pairs of terms cancelled
+/// out by simplification, and why the conflict couldn't be resolved
+/// automatically.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugConflictArgs {
+    #[arg(long, short = 'r', value_name = "REVSET")]
+    revision: Option<RevisionArg>,
+    #[arg(value_hint = clap::ValueHint::AnyPath)]
+    path: String,
+}
+
+pub fn cmd_debug_conflict(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugConflictArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command
+        .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let path = workspace_command.parse_file_path(&args.path)?;
+    let value = commit.tree()?.path_value(&path)?;
+    let mut out = ui.stdout();
+
+    writeln!(out, "Terms:")?;
+    write_terms(&mut out, &value)?;
+    if value.is_resolved() {
+        writeln!(out, "Resolution: not conflicted")?;
+        return Ok(());
+    }
+
+    writeln!(out, "Simplification:")?;
+    let steps = value.simplification_steps();
+    if steps.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for step in &steps {
+        writeln!(
+            out,
+            "  {} cancels out {}",
+            term_label(step.add_index),
+            term_label(step.remove_index)
+        )?;
+    }
+    let simplified = value.simplify();
+    if !steps.is_empty() {
+        writeln!(out, "Simplified terms:")?;
+        write_terms(&mut out, &simplified)?;
+    }
+
+    let resolution = if simplified.is_resolved() {
+        "resolved by simplification".to_owned()
+    } else if value.resolve_trivial().is_some() {
+        "resolved trivially (all sides made the same change)".to_owned()
+    } else {
+        let store = workspace_command.repo().store();
+        let attributes = TreeAttributes::new(
+            commit.tree()?,
+            workspace_command.env().attribute_overrides().clone(),
+        );
+        let driver = attributes.merge_driver(&path)?;
+        let conflict = simplified.map(|term| term.as_ref());
+        match explain_file_conflict(store, &path, &conflict, driver).block_on()? {
+            None => "resolved by merging the file contents".to_owned(),
+            Some(failure) => format!("unresolved ({})", describe_failure(failure)),
+        }
+    };
+    writeln!(out, "Resolution: {resolution}")?;
+    Ok(())
+}
+
+fn write_terms<T: Debug>(out: &mut impl io::Write, merge: &Merge<T>) -> io::Result<()> {
+    for (index, term) in merge.iter().enumerate() {
+        writeln!(out, "  {}: {term:?}", term_label(index))?;
+    }
+    Ok(())
+}
+
+/// Formats the term index as `+N` (Nth add) or `-N` (Nth remove).
+fn term_label(index: usize) -> String {
+    let sign = if index % 2 == 0 { '+' } else { '-' };
+    format!("{sign}{}", index / 2)
+}
+
+fn describe_failure(failure: FileConflictFailure) -> &'static str {
+    match failure {
+        FileConflictFailure::NonFileTerms => "some sides are absent or not regular files",
+        FileConflictFailure::ExecutableConflict => "the sides disagree on the executable bit",
+        FileConflictFailure::CopyIdConflict => "the sides disagree on the copy id",
+        FileConflictFailure::ContentConflict => "the file contents have conflicting hunks",
+        FileConflictFailure::MergeDisabled => "the merge attribute disables content merging",
+    }
+}
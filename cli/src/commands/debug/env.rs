@@ -0,0 +1,92 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::diagnostics;
+use crate::ui::Ui;
+
+/// Show information about the build and the environment, and check them for
+/// problems
+///
+/// The output is meant to be included in bug reports.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugEnvArgs {}
+
+pub fn cmd_debug_env(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &DebugEnvArgs,
+) -> Result<(), CommandError> {
+    let mut stdout = ui.stdout();
+    write!(stdout, "{}", command.app().render_version())?;
+    diagnostics::write_build_info(&mut stdout, command)?;
+
+    let settings = command.settings();
+    writeln!(
+        stdout,
+        "Configured fsmonitor: {}",
+        settings.get_string("fsmonitor.backend")?
+    )?;
+    // The workspace may be missing or broken, which shouldn't prevent the
+    // rest of the environment from being checked.
+    if let Ok(workspace_command) = command.workspace_helper_no_snapshot(ui) {
+        let backend = workspace_command.repo().store().backend().name();
+        let git_interop = if workspace_command.working_copy_shared_with_git() {
+            "colocated"
+        } else if backend == "git" {
+            "internal"
+        } else {
+            "none"
+        };
+        writeln!(
+            stdout,
+            "Workspace: {}",
+            workspace_command.workspace_root().display()
+        )?;
+        writeln!(stdout, "Backend: {backend}")?;
+        writeln!(
+            stdout,
+            "Working copy: {}",
+            workspace_command.working_copy().name()
+        )?;
+        writeln!(stdout, "Git interop: {git_interop}")?;
+    } else {
+        writeln!(stdout, "Workspace: none")?;
+    }
+    let env_vars = diagnostics::env_vars_set();
+    writeln!(
+        stdout,
+        "Environment variables: {}",
+        if env_vars.is_empty() {
+            "none".to_owned()
+        } else {
+            env_vars.iter().join(", ")
+        }
+    )?;
+    drop(stdout);
+
+    for diagnostic in diagnostics::check_environment(command) {
+        writeln!(ui.warning_default(), "{}", diagnostic.message)?;
+        if let Some(hint) = &diagnostic.hint {
+            writeln!(ui.hint_default(), "{hint}")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show canonical identities as mapped by the `.mailmap` file
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugMailmapArgs {
+    /// Identities in `Name <email>` form
+    #[arg(required = true)]
+    identities: Vec<String>,
+}
+
+pub fn cmd_debug_mailmap(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugMailmapArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let mailmap = workspace_command.env().mailmap();
+    for identity in &args.identities {
+        let (name, email) = parse_identity(identity).ok_or_else(|| {
+            user_error(format!(
+                "Invalid identity `{identity}`, expected `Name <email>`"
+            ))
+        })?;
+        let (name, email) = mailmap.resolve(name, email);
+        writeln!(ui.stdout(), "{name} <{email}>")?;
+    }
+    Ok(())
+}
+
+fn parse_identity(text: &str) -> Option<(&str, &str)> {
+    let (name, rest) = text.split_once('<')?;
+    let email = rest.trim_end().strip_suffix('>')?;
+    Some((name.trim(), email.trim()))
+}
@@ -12,17 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod conflict;
 mod copy_detection;
+mod env;
 mod fileset;
 mod index;
 mod init_simple;
 mod local_working_copy;
+mod mailmap;
+mod object;
+mod op_store;
 mod operation;
 mod reindex;
 mod revset;
 mod snapshot;
 mod template;
 mod tree;
+mod upgrade_repo;
 mod watchman;
 mod working_copy;
 
@@ -32,8 +38,12 @@ use std::fmt::Debug;
 use clap::Subcommand;
 use jj_lib::local_working_copy::LocalWorkingCopy;
 
+use self::conflict::cmd_debug_conflict;
+use self::conflict::DebugConflictArgs;
 use self::copy_detection::cmd_debug_copy_detection;
 use self::copy_detection::CopyDetectionArgs;
+use self::env::cmd_debug_env;
+use self::env::DebugEnvArgs;
 use self::fileset::cmd_debug_fileset;
 use self::fileset::DebugFilesetArgs;
 use self::index::cmd_debug_index;
@@ -42,6 +52,12 @@ use self::init_simple::cmd_debug_init_simple;
 use self::init_simple::DebugInitSimpleArgs;
 use self::local_working_copy::cmd_debug_local_working_copy;
 use self::local_working_copy::DebugLocalWorkingCopyArgs;
+use self::mailmap::cmd_debug_mailmap;
+use self::mailmap::DebugMailmapArgs;
+use self::object::cmd_debug_object;
+use self::object::DebugObjectArgs;
+use self::op_store::cmd_debug_op_store;
+use self::op_store::DebugOpStoreCommand;
 use self::operation::cmd_debug_operation;
 use self::operation::DebugOperationArgs;
 use self::reindex::cmd_debug_reindex;
@@ -54,6 +70,8 @@ use self::template::cmd_debug_template;
 use self::template::DebugTemplateArgs;
 use self::tree::cmd_debug_tree;
 use self::tree::DebugTreeArgs;
+use self::upgrade_repo::cmd_debug_upgrade_repo;
+use self::upgrade_repo::DebugUpgradeRepoArgs;
 use self::watchman::cmd_debug_watchman;
 use self::watchman::DebugWatchmanCommand;
 use self::working_copy::cmd_debug_working_copy;
@@ -67,11 +85,17 @@ use crate::ui::Ui;
 #[derive(Subcommand, Clone, Debug)]
 #[command(hide = true)]
 pub enum DebugCommand {
+    Conflict(DebugConflictArgs),
     CopyDetection(CopyDetectionArgs),
+    Env(DebugEnvArgs),
     Fileset(DebugFilesetArgs),
     Index(DebugIndexArgs),
     InitSimple(DebugInitSimpleArgs),
     LocalWorkingCopy(DebugLocalWorkingCopyArgs),
+    Mailmap(DebugMailmapArgs),
+    Object(DebugObjectArgs),
+    #[command(subcommand, visible_alias = "op")]
+    OpStore(DebugOpStoreCommand),
     #[command(visible_alias = "view")]
     Operation(DebugOperationArgs),
     Reindex(DebugReindexArgs),
@@ -79,6 +103,7 @@ pub enum DebugCommand {
     Snapshot(DebugSnapshotArgs),
     Template(DebugTemplateArgs),
     Tree(DebugTreeArgs),
+    UpgradeRepo(DebugUpgradeRepoArgs),
     #[command(subcommand)]
     Watchman(DebugWatchmanCommand),
     WorkingCopy(DebugWorkingCopyArgs),
@@ -90,17 +115,23 @@ pub fn cmd_debug(
     subcommand: &DebugCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        DebugCommand::Conflict(args) => cmd_debug_conflict(ui, command, args),
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args),
+        DebugCommand::Env(args) => cmd_debug_env(ui, command, args),
         DebugCommand::Fileset(args) => cmd_debug_fileset(ui, command, args),
         DebugCommand::Index(args) => cmd_debug_index(ui, command, args),
         DebugCommand::InitSimple(args) => cmd_debug_init_simple(ui, command, args),
         DebugCommand::LocalWorkingCopy(args) => cmd_debug_local_working_copy(ui, command, args),
+        DebugCommand::Mailmap(args) => cmd_debug_mailmap(ui, command, args),
+        DebugCommand::Object(args) => cmd_debug_object(ui, command, args),
+        DebugCommand::OpStore(args) => cmd_debug_op_store(ui, command, args),
         DebugCommand::Operation(args) => cmd_debug_operation(ui, command, args),
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args),
         DebugCommand::Revset(args) => cmd_debug_revset(ui, command, args),
         DebugCommand::Snapshot(args) => cmd_debug_snapshot(ui, command, args),
         DebugCommand::Template(args) => cmd_debug_template(ui, command, args),
         DebugCommand::Tree(args) => cmd_debug_tree(ui, command, args),
+        DebugCommand::UpgradeRepo(args) => cmd_debug_upgrade_repo(ui, command, args),
         DebugCommand::Watchman(args) => cmd_debug_watchman(ui, command, args),
         DebugCommand::WorkingCopy(args) => cmd_debug_working_copy(ui, command, args),
     }
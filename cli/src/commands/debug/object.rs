@@ -0,0 +1,258 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::io::BufRead as _;
+use std::io::Write as _;
+
+use jj_lib::backend::Backend;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::Commit;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::FileId;
+use jj_lib::backend::Signature;
+use jj_lib::backend::SymlinkId;
+use jj_lib::backend::Tree;
+use jj_lib::backend::TreeId;
+use jj_lib::backend::TreeValue;
+use jj_lib::hex_util;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use pollster::FutureExt as _;
+use tokio::io::AsyncReadExt as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Print objects from the backend, like `git cat-file --batch`
+///
+/// Reads one object per line from stdin. Each line contains the hex id of the
+/// object, optionally preceded by its type (`commit`, `tree`, `file`, or
+/// `symlink`) and a space. Without a type, the object is looked up as a
+/// commit, a tree, and a file in that order.
+///
+/// For each object, a `<id> <type> <size>` line is printed, followed by the
+/// content and a newline. Trees and commits are printed in a textual format
+/// which doesn't depend on the backend. If the object doesn't exist,
+/// `<id> missing` is printed instead. Other errors from the backend abort the
+/// command.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugObjectArgs {}
+
+pub fn cmd_debug_object(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &DebugObjectArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let backend = workspace_command.repo().store().backend();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (kind, hex) = match line.split_once(' ') {
+            Some((kind, hex)) => (Some(ObjectKind::parse(kind)), hex.trim()),
+            None => (None, line),
+        };
+        // Objects of unknown type are reported as missing.
+        let object = match (kind, hex_util::decode_hex(hex)) {
+            (Some(None), _) | (_, None) => None,
+            (kind, Some(id)) => read_object(backend, kind.flatten(), &id)?,
+        };
+        let mut stdout = ui.stdout();
+        if let Some((kind, content)) = object {
+            writeln!(stdout, "{hex} {} {}", kind.name(), content.len())?;
+            stdout.write_all(&content)?;
+            writeln!(stdout)?;
+        } else {
+            writeln!(stdout, "{hex} missing")?;
+        }
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ObjectKind {
+    Commit,
+    Tree,
+    File,
+    Symlink,
+}
+
+impl ObjectKind {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "commit" => Some(Self::Commit),
+            "tree" => Some(Self::Tree),
+            "file" => Some(Self::File),
+            "symlink" => Some(Self::Symlink),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Commit => "commit",
+            Self::Tree => "tree",
+            Self::File => "file",
+            Self::Symlink => "symlink",
+        }
+    }
+}
+
+/// Reads the object with the given id. If the `kind` isn't specified, the
+/// object is looked up as a commit, a tree, and a file.
+fn read_object(
+    backend: &dyn Backend,
+    kind: Option<ObjectKind>,
+    id: &[u8],
+) -> BackendResult<Option<(ObjectKind, Vec<u8>)>> {
+    if let Some(kind) = kind {
+        let content = read_object_content(backend, kind, id)?;
+        return Ok(content.map(|content| (kind, content)));
+    }
+    // Reading an object as a different kind usually fails with a backend
+    // error, so the error is only reported if no kind matches.
+    let mut first_err = None;
+    for kind in [ObjectKind::Commit, ObjectKind::Tree, ObjectKind::File] {
+        match read_object_content(backend, kind, id) {
+            Ok(Some(content)) => return Ok(Some((kind, content))),
+            Ok(None) => {}
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    match first_err {
+        Some(err) => Err(err),
+        None => Ok(None),
+    }
+}
+
+/// Reads the content of the object, or returns `None` if there's no object
+/// of the given `kind` with the `id`.
+fn read_object_content(
+    backend: &dyn Backend,
+    kind: ObjectKind,
+    id: &[u8],
+) -> BackendResult<Option<Vec<u8>>> {
+    // The path is only a hint for backends, and isn't known here.
+    let path = RepoPath::root();
+    let result = match kind {
+        ObjectKind::Commit => {
+            if id.len() != backend.commit_id_length() {
+                return Ok(None);
+            }
+            let commit_id = CommitId::from_bytes(id);
+            let commit = backend.read_commit(&commit_id).block_on();
+            commit.map(|commit| format_commit(&commit).into_bytes())
+        }
+        ObjectKind::Tree => {
+            let tree_id = TreeId::from_bytes(id);
+            let tree = backend.read_tree(path, &tree_id).block_on();
+            tree.map(|tree| format_tree(&tree).into_bytes())
+        }
+        ObjectKind::File => {
+            let file_id = FileId::from_bytes(id);
+            read_file_content(backend, path, &file_id)
+        }
+        ObjectKind::Symlink => {
+            let symlink_id = SymlinkId::from_bytes(id);
+            let target = backend.read_symlink(path, &symlink_id).block_on();
+            target.map(String::into_bytes)
+        }
+    };
+    match result {
+        Ok(content) => Ok(Some(content)),
+        Err(BackendError::ObjectNotFound { .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn read_file_content(
+    backend: &dyn Backend,
+    path: &RepoPath,
+    id: &FileId,
+) -> BackendResult<Vec<u8>> {
+    let mut reader = backend.read_file(path, id).block_on()?;
+    let mut content = vec![];
+    reader
+        .read_to_end(&mut content)
+        .block_on()
+        .map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    Ok(content)
+}
+
+fn format_commit(commit: &Commit) -> String {
+    let mut text = String::new();
+    for tree_id in commit.root_tree.to_merge().iter() {
+        text.push_str(&format!("tree {}\n", tree_id.hex()));
+    }
+    for parent_id in &commit.parents {
+        text.push_str(&format!("parent {}\n", parent_id.hex()));
+    }
+    text.push_str(&format!("change-id {}\n", commit.change_id.reverse_hex()));
+    text.push_str(&format!("author {}\n", format_signature(&commit.author)));
+    text.push_str(&format!(
+        "committer {}\n",
+        format_signature(&commit.committer)
+    ));
+    text.push('\n');
+    text.push_str(&commit.description);
+    text
+}
+
+fn format_signature(signature: &Signature) -> String {
+    format!(
+        "{} <{}> {} {:+}",
+        signature.name,
+        signature.email,
+        signature.timestamp.timestamp.0,
+        signature.timestamp.tz_offset
+    )
+}
+
+fn format_tree(tree: &Tree) -> String {
+    let mut text = String::new();
+    for entry in tree.entries() {
+        let kind = match entry.value() {
+            TreeValue::File {
+                executable: false, ..
+            } => "file",
+            TreeValue::File {
+                executable: true, ..
+            } => "executable",
+            TreeValue::Symlink(_) => "symlink",
+            TreeValue::Tree(_) => "tree",
+            TreeValue::GitSubmodule(_) => "submodule",
+            TreeValue::Conflict(_) => "conflict",
+        };
+        text.push_str(&format!(
+            "{kind} {}\t{}\n",
+            entry.value().hex(),
+            entry.name().as_internal_str()
+        ));
+    }
+    text
+}
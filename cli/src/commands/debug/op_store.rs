@@ -0,0 +1,353 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::hex_util;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_bundle::ExportOptions;
+use jj_lib::op_bundle::OpBundle;
+use jj_lib::op_store::OpStore;
+use jj_lib::op_store::OperationId;
+use jj_lib::op_walk;
+use jj_lib::simple_op_heads_store::SimpleOpHeadsStore;
+use jj_lib::simple_op_store::SimpleOpStore;
+
+use crate::cli_util::short_operation_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Commands for inspecting the operation store
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugOpStoreCommand {
+    Export(DebugOpStoreExportArgs),
+    Fsck(DebugOpStoreFsckArgs),
+    Import(DebugOpStoreImportArgs),
+}
+
+/// Write a range of the operation log to a bundle file
+///
+/// The bundle contains the operations reachable from the op heads and their
+/// views, so it can be imported into another repo to reproduce problems with
+/// the operation log. File contents are never included.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugOpStoreExportArgs {
+    /// Only export the operations after this operation
+    ///
+    /// By default, all operations except the root operation are exported.
+    #[arg(long, value_name = "OPERATION")]
+    since: Option<String>,
+    /// The bundle file to write
+    #[arg(long, short, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    output: PathBuf,
+    /// Also export the commits reachable from the views
+    ///
+    /// Only the commit metadata is exported. The imported commits have empty
+    /// trees.
+    #[arg(long)]
+    with_commits: bool,
+    /// Replace descriptions, operation tags (which include the command
+    /// arguments), user names, emails, hostnames, and bookmark, tag, and
+    /// workspace names with placeholders
+    #[arg(long)]
+    redact: bool,
+}
+
+/// Import operations from a bundle file written by `jj debug op-store export`
+///
+/// The imported operations aren't added to the op heads. The ids of the new
+/// head operations are printed, which can be passed to `--at-op` to inspect
+/// them. Unless the bundle was exported with `--with-commits`, the repo can't
+/// be loaded at the imported operations.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugOpStoreImportArgs {
+    /// The bundle file to read
+    #[arg(value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    bundle: PathBuf,
+}
+
+/// Check integrity of the operations reachable from the op heads
+///
+/// All operations and views are read from the op heads down to the root
+/// operation. Missing or unreadable objects are reported, as well as objects
+/// whose content doesn't match their id.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugOpStoreFsckArgs {
+    /// Move op heads with broken history out of the op heads directory
+    ///
+    /// At least one op head with intact history must remain.
+    #[arg(long)]
+    quarantine: bool,
+}
+
+pub fn cmd_debug_op_store(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugOpStoreCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        DebugOpStoreCommand::Export(args) => cmd_debug_op_store_export(ui, command, args),
+        DebugOpStoreCommand::Fsck(args) => cmd_debug_op_store_fsck(ui, command, args),
+        DebugOpStoreCommand::Import(args) => cmd_debug_op_store_import(ui, command, args),
+    }
+}
+
+fn cmd_debug_op_store_export(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugOpStoreExportArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo, which would merge the operation heads.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let head_ops = op_walk::get_current_head_ops(
+        repo_loader.op_store(),
+        repo_loader.op_heads_store().as_ref(),
+    )?;
+    let since_ops = match &args.since {
+        Some(since) => vec![op_walk::resolve_op_for_load(repo_loader, since)?],
+        None => vec![],
+    };
+    let options = ExportOptions {
+        with_commits: args.with_commits,
+        redact: args.redact,
+    };
+    let bundle = OpBundle::export(repo_loader, &head_ops, &since_ops, &options)?;
+    let file = File::create(&args.output).context(&args.output)?;
+    let mut writer = BufWriter::new(file);
+    bundle.write_to(&mut writer).context(&args.output)?;
+    writer.flush().context(&args.output)?;
+    writeln!(
+        ui.status(),
+        "Exported {} operations and {} commits.",
+        bundle.operations.len(),
+        bundle.commits.len()
+    )?;
+    Ok(())
+}
+
+fn cmd_debug_op_store_import(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugOpStoreImportArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let file = File::open(&args.bundle).context(&args.bundle)?;
+    let bundle = OpBundle::read_from(&mut BufReader::new(file))?;
+    let stats = bundle.import(repo_loader)?;
+    writeln!(
+        ui.status(),
+        "Imported {} operations and {} commits.",
+        stats.operation_count,
+        stats.commit_count
+    )?;
+    for id in &stats.new_head_ids {
+        writeln!(ui.stdout(), "{}", id.hex())?;
+    }
+    Ok(())
+}
+
+fn cmd_debug_op_store_fsck(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugOpStoreFsckArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo, which would fail if the op heads are broken.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store();
+    let op_heads_store = repo_loader.op_heads_store();
+    let simple_op_heads_store = op_heads_store.as_any().downcast_ref::<SimpleOpHeadsStore>();
+    // Ids are content hashes only in the default op store.
+    let simple_op_store = op_store.as_any().downcast_ref::<SimpleOpStore>();
+
+    let mut num_problems = 0;
+    if let Some(store) = simple_op_heads_store {
+        let dir = store.heads_dir();
+        let entries = dir.read_dir().map_err(internal_error)?;
+        for entry in entries {
+            let name = entry.map_err(internal_error)?.file_name();
+            let name = name.to_string_lossy();
+            if name != "lock" && hex_util::decode_hex(name.as_bytes()).is_none() {
+                writeln!(
+                    ui.stdout(),
+                    "Unrecognized file in op heads directory: {name}"
+                )?;
+                num_problems += 1;
+            }
+        }
+    }
+
+    let head_ids = op_heads_store.get_op_heads()?;
+    if head_ids.is_empty() {
+        writeln!(ui.stdout(), "No op heads found")?;
+        num_problems += 1;
+    }
+    let mut op_parents: HashMap<OperationId, Vec<OperationId>> = HashMap::new();
+    let mut broken_ops = HashSet::new();
+    let mut to_visit = head_ids.clone();
+    while let Some(id) = to_visit.pop() {
+        if op_parents.contains_key(&id) {
+            continue;
+        }
+        let (problems, parent_ids) = check_operation(op_store.as_ref(), simple_op_store, &id);
+        for problem in &problems {
+            writeln!(
+                ui.stdout(),
+                "Operation {}: {problem}",
+                short_operation_hash(&id)
+            )?;
+        }
+        if !problems.is_empty() {
+            num_problems += problems.len();
+            broken_ops.insert(id.clone());
+        }
+        to_visit.extend(parent_ids.iter().cloned());
+        op_parents.insert(id, parent_ids);
+    }
+
+    let (broken_head_ids, intact_head_ids): (Vec<_>, Vec<_>) = head_ids
+        .iter()
+        .partition(|id| has_broken_ancestor(id, &op_parents, &broken_ops));
+    for id in &broken_head_ids {
+        writeln!(
+            ui.stdout(),
+            "Op head {} has broken history",
+            short_operation_hash(id)
+        )?;
+    }
+    writeln!(
+        ui.status(),
+        "Checked {} operations, found {num_problems} problems.",
+        op_parents.len()
+    )?;
+    if num_problems == 0 {
+        return Ok(());
+    }
+
+    if broken_head_ids.is_empty() || !args.quarantine {
+        let hint = if broken_head_ids.is_empty() || intact_head_ids.is_empty() {
+            "Restore the `.jj/repo/op_store` and `.jj/repo/op_heads` directories from a backup \
+             if the repository can't be loaded."
+        } else {
+            "Run `jj debug op-store fsck --quarantine` to move the broken op heads aside."
+        };
+        return Err(user_error_with_hint(
+            "The operation store is corrupted",
+            hint,
+        ));
+    }
+    if intact_head_ids.is_empty() {
+        return Err(user_error_with_hint(
+            "Cannot quarantine all op heads",
+            "Restore the `.jj/repo/op_store` and `.jj/repo/op_heads` directories from a backup.",
+        ));
+    }
+    let Some(store) = simple_op_heads_store else {
+        return Err(user_error(format!(
+            "Cannot quarantine op heads of type '{}'",
+            op_heads_store.name()
+        )));
+    };
+    for id in &broken_head_ids {
+        let path = store.quarantine_op_head(id).map_err(internal_error)?;
+        writeln!(
+            ui.status(),
+            "Quarantined op head {} to {}",
+            short_operation_hash(id),
+            path.display()
+        )?;
+    }
+    writeln!(
+        ui.hint_default(),
+        "Run `jj op log` to check the remaining operation history."
+    )?;
+    Ok(())
+}
+
+/// Reads the operation and its view, and returns the problems found and the
+/// parent operation ids.
+///
+/// If `simple_op_store` is given, the stored records are also checked against
+/// their ids. Records in legacy formats are skipped.
+fn check_operation(
+    op_store: &dyn OpStore,
+    simple_op_store: Option<&SimpleOpStore>,
+    id: &OperationId,
+) -> (Vec<String>, Vec<OperationId>) {
+    let mut problems = vec![];
+    let operation = match op_store.read_operation(id) {
+        Ok(operation) => operation,
+        Err(err) => {
+            problems.push(format!("Failed to read operation: {err}"));
+            return (problems, vec![]);
+        }
+    };
+    if let Some(store) = simple_op_store {
+        match store.verify_operation_id(id) {
+            Ok(Some(true) | None) => {}
+            Ok(Some(false)) => problems.push("Operation content doesn't match its id".to_owned()),
+            Err(err) => problems.push(format!("Failed to verify operation: {err}")),
+        }
+    }
+    match op_store.read_view(&operation.view_id) {
+        Ok(_) => {
+            if let Some(store) = simple_op_store {
+                match store.verify_view_id(&operation.view_id) {
+                    Ok(Some(true) | None) => {}
+                    Ok(Some(false)) => problems.push(format!(
+                        "View {} content doesn't match its id",
+                        operation.view_id.hex()
+                    )),
+                    Err(err) => problems.push(format!("Failed to verify view: {err}")),
+                }
+            }
+        }
+        Err(err) => problems.push(format!("Failed to read view: {err}")),
+    }
+    (problems, operation.parents)
+}
+
+fn has_broken_ancestor(
+    head_id: &OperationId,
+    op_parents: &HashMap<OperationId, Vec<OperationId>>,
+    broken_ops: &HashSet<OperationId>,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut to_visit = vec![head_id];
+    while let Some(id) = to_visit.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if broken_ops.contains(id) {
+            return true;
+        }
+        to_visit.extend(op_parents.get(id).into_iter().flatten());
+    }
+    false
+}
@@ -20,14 +20,159 @@ use crate::ui::Ui;
 
 /// Trigger a snapshot in the op log
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugSnapshotArgs {}
+pub struct DebugSnapshotArgs {
+    /// Keep running, and snapshot the working copy whenever it changes
+    ///
+    /// The working copy is checked for changes every `--interval` seconds,
+    /// which is cheap if a filesystem monitor is configured. Like the
+    /// snapshots of other commands, a snapshot which finds changes is recorded
+    /// as an operation.
+    ///
+    /// Other commands in the same workspace ask the running process to
+    /// snapshot the working copy on their behalf, and skip their own
+    /// snapshot. This only happens if the config of the command is the same
+    /// as the config of the running process. The running process doesn't
+    /// touch the working copy while other commands are running.
+    ///
+    /// Only supported on Unix.
+    #[arg(long)]
+    watch: bool,
+    /// How often to check for changes in `--watch` mode, in seconds
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 1.0,
+        requires = "watch"
+    )]
+    interval: f64,
+}
 
 pub fn cmd_debug_snapshot(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugSnapshotArgs,
+    args: &DebugSnapshotArgs,
 ) -> Result<(), CommandError> {
+    if args.watch {
+        return watch::watch_working_copy(ui, command, args);
+    }
     // workspace helper will snapshot as needed
     command.workspace_helper(ui)?;
     Ok(())
 }
+
+#[cfg(unix)]
+mod watch {
+    use std::io::Write as _;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use jj_lib::fsmonitor::FsmonitorSettings;
+
+    use super::DebugSnapshotArgs;
+    use crate::cli_util::CommandHelper;
+    use crate::command_error::user_error;
+    use crate::command_error::user_error_with_message;
+    use crate::command_error::CommandError;
+    use crate::snapshot_daemon;
+    use crate::snapshot_daemon::ClientConnection;
+    use crate::snapshot_daemon::SnapshotListener;
+    use crate::ui::Ui;
+
+    /// How often to check for requests from other commands.
+    const REQUEST_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+    pub fn watch_working_copy(
+        ui: &mut Ui,
+        command: &CommandHelper,
+        args: &DebugSnapshotArgs,
+    ) -> Result<(), CommandError> {
+        let interval = Duration::try_from_secs_f64(args.interval)
+            .map_err(|err| user_error_with_message("Invalid --interval", err))?;
+        if !command.is_working_copy_writable() {
+            return Err(user_error(
+                "Cannot watch the working copy with --ignore-working-copy or --at-operation",
+            ));
+        }
+        let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+        if workspace_command.settings().fsmonitor_settings()? == FsmonitorSettings::None {
+            writeln!(
+                ui.warning_default(),
+                "No filesystem monitor is configured. The whole working copy will be scanned \
+                 every {interval:?}."
+            )?;
+        }
+        let socket_path = snapshot_daemon::socket_path(workspace_command.workspace_root());
+        drop(workspace_command);
+        let fingerprint = snapshot_daemon::config_fingerprint(command.settings().config());
+        let listener = SnapshotListener::bind(&socket_path).map_err(|err| {
+            user_error_with_message("Failed to listen for snapshot requests", err)
+        })?;
+        writeln!(
+            ui.status(),
+            "Watching the working copy for changes. Press Ctrl-C to stop."
+        )?;
+
+        let mut clients: Vec<ClientConnection> = vec![];
+        let mut last_snapshot: Option<Instant> = None;
+        loop {
+            clients.retain(|client| !client.is_closed());
+            for request in listener.pending_requests()? {
+                // Don't touch the working copy while another command is using
+                // it. The requesting command will snapshot by itself.
+                let snapshotted =
+                    request.fingerprint() == fingerprint && clients.is_empty() && snapshot(command);
+                if snapshotted {
+                    last_snapshot = Some(Instant::now());
+                }
+                clients.push(request.reply(snapshotted));
+            }
+            let is_due = last_snapshot.is_none_or(|time| time.elapsed() >= interval);
+            if clients.is_empty() && is_due {
+                snapshot(command);
+                last_snapshot = Some(Instant::now());
+            }
+            thread::sleep(REQUEST_POLL_INTERVAL);
+        }
+    }
+
+    /// Snapshots the working copy. Returns true if there's nothing to report
+    /// to the user.
+    ///
+    /// Warnings and errors aren't printed. If there are any, the requesting
+    /// command snapshots by itself and reports them.
+    fn snapshot(command: &CommandHelper) -> bool {
+        let ui = Ui::null();
+        let result = command
+            .workspace_helper_no_snapshot(&ui)
+            .and_then(|mut workspace_command| workspace_command.maybe_snapshot_with_stats(&ui));
+        match result {
+            Ok(stats) => {
+                stats.untracked_paths.is_empty()
+                    && stats.large_new_directories.is_empty()
+                    && stats.malformed_conflicts.is_empty()
+            }
+            Err(err) => {
+                tracing::debug!(?err.error, "failed to snapshot the working copy");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod watch {
+    use super::DebugSnapshotArgs;
+    use crate::cli_util::CommandHelper;
+    use crate::command_error::user_error;
+    use crate::command_error::CommandError;
+    use crate::ui::Ui;
+
+    pub fn watch_working_copy(
+        _ui: &mut Ui,
+        _command: &CommandHelper,
+        _args: &DebugSnapshotArgs,
+    ) -> Result<(), CommandError> {
+        Err(user_error("--watch is only supported on Unix"))
+    }
+}
@@ -15,23 +15,133 @@
 use std::fmt::Debug;
 use std::io::Write as _;
 
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use serde_json::json;
+
 use crate::cli_util::CommandHelper;
+use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::template_builder;
 use crate::template_parser;
+use crate::template_parser::ExpressionKind;
+use crate::template_parser::ExpressionNode;
+use crate::template_parser::FunctionCallNode;
+use crate::template_parser::TemplateDiagnostics;
 use crate::ui::Ui;
 
 /// Parse a template
+///
+/// With `--json-ast`, the template is type-checked as a commit template, and
+/// the syntax tree is printed as JSON. Each node has its `kind`, the `span` of
+/// byte offsets in the source text, and the inferred `type` of the node if it
+/// can be built on its own. Aliases are expanded, and the nodes substituted
+/// for an alias refer to the source text of the alias definition.
+///
+/// Warnings about deprecated keywords and branches that are never evaluated
+/// are listed in `warnings`.
 #[derive(clap::Args, Clone, Debug)]
 pub struct DebugTemplateArgs {
     template: String,
+    /// Print the typed syntax tree and warnings as JSON
+    #[arg(long)]
+    json_ast: bool,
 }
 
 pub fn cmd_debug_template(
     ui: &mut Ui,
-    _command: &CommandHelper,
+    command: &CommandHelper,
     args: &DebugTemplateArgs,
 ) -> Result<(), CommandError> {
-    let node = template_parser::parse_template(&args.template)?;
-    writeln!(ui.stdout(), "{node:#?}")?;
+    if !args.json_ast {
+        let node = template_parser::parse_template(&args.template)?;
+        writeln!(ui.stdout(), "{node:#?}")?;
+        return Ok(());
+    }
+
+    let workspace_command = command.workspace_helper(ui)?;
+    let language = workspace_command.commit_template_language();
+    let aliases_map = workspace_command.env().template_aliases_map();
+    let node = template_parser::parse(&args.template, aliases_map)?;
+    let mut diagnostics = TemplateDiagnostics::new();
+    template_builder::build::<Commit, _>(&language, &mut diagnostics, &node)
+        .map_err(|err| err.extend_alias_candidates(aliases_map))?;
+    template_parser::check_unreachable_branches(&mut diagnostics, &node);
+    print_parse_diagnostics(ui, "In template expression", &diagnostics)?;
+
+    let output = json!({
+        "ast": node_to_json(&language, &node),
+        "warnings": diagnostics.iter().map(|diag| diag.kind().to_string()).collect_vec(),
+    });
+    let output = serde_json::to_string_pretty(&output).unwrap();
+    writeln!(ui.stdout(), "{output}")?;
     Ok(())
 }
+
+fn node_to_json(language: &CommitTemplateLanguage, node: &ExpressionNode) -> serde_json::Value {
+    let mut value = match &node.kind {
+        ExpressionKind::Identifier(name) => json!({ "kind": "Identifier", "name": name }),
+        ExpressionKind::Boolean(value) => json!({ "kind": "Boolean", "value": value }),
+        ExpressionKind::Integer(value) => json!({ "kind": "Integer", "value": value }),
+        ExpressionKind::String(value) => json!({ "kind": "String", "value": value }),
+        ExpressionKind::Unary(op, arg) => json!({
+            "kind": "Unary",
+            "op": format!("{op:?}"),
+            "arg": node_to_json(language, arg),
+        }),
+        ExpressionKind::Binary(op, lhs, rhs) => json!({
+            "kind": "Binary",
+            "op": format!("{op:?}"),
+            "lhs": node_to_json(language, lhs),
+            "rhs": node_to_json(language, rhs),
+        }),
+        ExpressionKind::Concat(nodes) => json!({
+            "kind": "Concat",
+            "items": nodes.iter().map(|node| node_to_json(language, node)).collect_vec(),
+        }),
+        ExpressionKind::FunctionCall(function) => {
+            let mut value = function_call_to_json(language, function);
+            value["kind"] = json!("FunctionCall");
+            value
+        }
+        ExpressionKind::MethodCall(method) => {
+            let mut value = function_call_to_json(language, &method.function);
+            value["kind"] = json!("MethodCall");
+            value["object"] = node_to_json(language, &method.object);
+            value
+        }
+        ExpressionKind::Lambda(lambda) => json!({
+            "kind": "Lambda",
+            "params": lambda.params,
+            "body": node_to_json(language, &lambda.body),
+        }),
+        ExpressionKind::AliasExpanded(id, subst) => json!({
+            "kind": "AliasExpanded",
+            "alias": id.to_string(),
+            "expansion": node_to_json(language, subst),
+        }),
+    };
+    let mut diagnostics = TemplateDiagnostics::new();
+    let type_name =
+        template_builder::infer_type_name::<Commit, _>(language, &mut diagnostics, node).ok();
+    value["span"] = json!([node.span.start(), node.span.end()]);
+    value["type"] = json!(type_name);
+    value
+}
+
+fn function_call_to_json(
+    language: &CommitTemplateLanguage,
+    function: &FunctionCallNode,
+) -> serde_json::Value {
+    let keyword_args = function
+        .keyword_args
+        .iter()
+        .map(|arg| json!({ "name": arg.name, "value": node_to_json(language, &arg.value) }))
+        .collect_vec();
+    json!({
+        "name": function.name,
+        "args": function.args.iter().map(|node| node_to_json(language, node)).collect_vec(),
+        "keyword_args": keyword_args,
+    })
+}
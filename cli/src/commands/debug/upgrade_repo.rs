@@ -0,0 +1,70 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use jj_lib::repo_format;
+use jj_lib::repo_format::REPO_FORMAT_VERSION;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Upgrade the on-disk repository format to the latest version
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugUpgradeRepoArgs {
+    /// List pending migrations without running them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub fn cmd_debug_upgrade_repo(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugUpgradeRepoArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo. Only the format version needs to be compatible.
+    let workspace = command.load_workspace()?;
+    let repo_path = workspace.repo_path();
+    let version = repo_format::check_format_version(repo_path).map_err(internal_error)?;
+    let pending = repo_format::pending_migrations(version);
+    if pending.is_empty() {
+        writeln!(
+            ui.status(),
+            "Repository format is up to date (version {version})."
+        )?;
+        return Ok(());
+    }
+    if args.dry_run {
+        writeln!(
+            ui.status(),
+            "Would upgrade repository format from version {version} to {REPO_FORMAT_VERSION}:"
+        )?;
+        for migration in pending {
+            writeln!(ui.status(), "  {}", migration.description)?;
+        }
+        return Ok(());
+    }
+    let migrations = repo_format::upgrade_repo(repo_path).map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Upgraded repository format from version {version} to {REPO_FORMAT_VERSION}:"
+    )?;
+    for migration in &migrations {
+        writeln!(ui.status(), "  {}", migration.description)?;
+    }
+    Ok(())
+}
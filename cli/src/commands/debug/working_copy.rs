@@ -15,20 +15,65 @@
 use std::fmt::Debug;
 use std::io::Write as _;
 
+use jj_lib::local_working_copy::CheckoutRecovery;
+use jj_lib::local_working_copy::LockedLocalWorkingCopy;
+
+use crate::cli_util::print_recovered_checkout_stats;
 use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Show information about the working copy state
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugWorkingCopyArgs {}
+pub struct DebugWorkingCopyArgs {
+    /// Resume a working copy update that was interrupted
+    ///
+    /// This normally happens automatically when the working copy is
+    /// snapshotted.
+    #[arg(long)]
+    recover: bool,
+    /// Undo the files written by a working copy update that was interrupted
+    ///
+    /// Changes made to other files since the interruption are kept.
+    #[arg(long, conflicts_with = "recover")]
+    rollback: bool,
+}
 
 pub fn cmd_debug_working_copy(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugWorkingCopyArgs,
+    args: &DebugWorkingCopyArgs,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let mut workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    if args.recover || args.rollback {
+        let recovery = if args.rollback {
+            CheckoutRecovery::Rollback
+        } else {
+            CheckoutRecovery::Resume
+        };
+        let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+        let snapshot_options = workspace_command
+            .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)?;
+        let options = workspace_command.checkout_options();
+        let op_id = workspace_command.working_copy().operation_id().clone();
+        let (mut locked_ws, _commit) = workspace_command.unchecked_start_working_copy_mutation()?;
+        let Some(locked_local_wc): Option<&mut LockedLocalWorkingCopy> =
+            locked_ws.locked_wc().as_any_mut().downcast_mut()
+        else {
+            return Err(user_error(
+                "This command requires a standard local-disk working copy",
+            ));
+        };
+        if let Some(stats) =
+            locked_local_wc.recover_checkout(recovery, &snapshot_options, &options)?
+        {
+            print_recovered_checkout_stats(ui, recovery, &stats)?;
+        } else {
+            writeln!(ui.status(), "No interrupted working copy update to recover")?;
+        }
+        locked_ws.finish(op_id)?;
+    }
     let wc = workspace_command.working_copy();
     writeln!(ui.stdout(), "Type: {:?}", wc.name())?;
     writeln!(ui.stdout(), "Current operation: {:?}", wc.operation_id())?;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::io;
 use std::io::Read as _;
@@ -19,7 +20,9 @@ use std::iter;
 
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::backend::Signature;
+use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::object_id::ObjectId as _;
 use tracing::instrument;
@@ -27,10 +30,12 @@ use tracing::instrument;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::description_util::add_trailers_with_template;
-use crate::description_util::description_template;
+use crate::description_util::description_template_with_text;
+use crate::description_util::draft_description_template_text;
 use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
 use crate::description_util::join_message_paragraphs;
@@ -75,6 +80,15 @@ pub(crate) struct DescribeArgs {
     /// for all of them.
     #[arg(long)]
     stdin: bool,
+    /// Read the descriptions from stdin as a JSON object
+    ///
+    /// The object maps change IDs (or unique prefixes of them among the
+    /// revisions being described) to their new descriptions, e.g.
+    /// `{"kkmpptxz": "fix typo", "rlvkpnrz": "add feature"}`. All
+    /// descriptions are updated in a single operation. Revisions not
+    /// mentioned in the object keep their current description.
+    #[arg(long, requires = "stdin")]
+    json: bool,
     /// Don't open an editor
     ///
     /// This is mainly useful in combination with e.g. `--reset-author`.
@@ -106,6 +120,20 @@ pub(crate) struct DescribeArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Render the draft description in the editor using the given template
+    ///
+    /// This overrides the `templates.draft_commit_description` setting. The
+    /// template can refer to the changes of the revision, e.g.
+    /// `diff.stat(72)` or `diff.files()`. If the revision changes more files
+    /// than the `describe.draft-max-files` setting, the builtin draft template
+    /// is used instead.
+    #[arg(
+        long,
+        short = 'T',
+        conflicts_with = "no_edit",
+        add = ArgValueCompleter::new(complete::commit_template_expression)
+    )]
+    template: Option<String>,
 }
 
 #[instrument(skip_all)]
@@ -143,10 +171,16 @@ pub(crate) fn cmd_describe(
         }
     };
 
+    let mut json_descriptions = None;
     let shared_description = if args.stdin {
         let mut buffer = String::new();
         io::stdin().read_to_string(&mut buffer)?;
-        Some(buffer)
+        if args.json {
+            json_descriptions = Some(parse_json_descriptions(&buffer, &commits)?);
+            None
+        } else {
+            Some(buffer)
+        }
     } else if !args.message_paragraphs.is_empty() {
         Some(join_message_paragraphs(&args.message_paragraphs))
     } else {
@@ -160,6 +194,12 @@ pub(crate) fn cmd_describe(
             if let Some(description) = &shared_description {
                 commit_builder.set_description(description);
             }
+            if let Some(description) = json_descriptions
+                .as_ref()
+                .and_then(|descriptions| descriptions.get(commit.id()))
+            {
+                commit_builder.set_description(description);
+            }
             if args.reset_author {
                 let new_author = commit_builder.committer().clone();
                 commit_builder.set_author(new_author);
@@ -176,7 +216,8 @@ pub(crate) fn cmd_describe(
         })
         .collect_vec();
 
-    let use_editor = args.edit || (shared_description.is_none() && !args.no_edit);
+    let use_editor =
+        args.edit || (shared_description.is_none() && json_descriptions.is_none() && !args.no_edit);
 
     if let Some(trailer_template) = parse_trailers_template(ui, &tx)? {
         for commit_builder in &mut commit_builders {
@@ -193,6 +234,10 @@ pub(crate) fn cmd_describe(
     }
 
     if use_editor {
+        let template_text = match &args.template {
+            Some(template_text) => template_text.clone(),
+            None => draft_description_template_text(tx.settings())?,
+        };
         let temp_commits: Vec<_> = iter::zip(&commits, &commit_builders)
             // Edit descriptions in topological order
             .rev()
@@ -205,7 +250,8 @@ pub(crate) fn cmd_describe(
 
         if let [(_, temp_commit)] = &*temp_commits {
             let intro = "";
-            let template = description_template(ui, &tx, intro, temp_commit)?;
+            let template =
+                description_template_with_text(ui, &tx, intro, temp_commit, &template_text)?;
             let description = edit_description(&text_editor, &template)?;
             commit_builders[0].set_description(description);
         } else {
@@ -214,7 +260,7 @@ pub(crate) fn cmd_describe(
                 missing,
                 duplicates,
                 unexpected,
-            } = edit_multiple_descriptions(ui, &text_editor, &tx, &temp_commits)?;
+            } = edit_multiple_descriptions(ui, &text_editor, &tx, &temp_commits, &template_text)?;
             if !missing.is_empty() {
                 return Err(user_error(format!(
                     "The description for the following commits were not found in the edited \
@@ -294,3 +340,43 @@ pub(crate) fn cmd_describe(
     tx.finish(ui, tx_description)?;
     Ok(())
 }
+
+/// Parses a JSON object mapping change ID prefixes to descriptions, and
+/// resolves the keys against the `commits` being described.
+fn parse_json_descriptions(
+    text: &str,
+    commits: &[Commit],
+) -> Result<HashMap<CommitId, String>, CommandError> {
+    let entries: BTreeMap<String, String> = serde_json::from_str(text)
+        .map_err(|err| user_error_with_message("Failed to parse descriptions from stdin", err))?;
+    let mut descriptions = HashMap::new();
+    for (key, description) in entries {
+        let matched = commits
+            .iter()
+            .filter(|commit| commit.change_id().reverse_hex().starts_with(&key))
+            .collect_vec();
+        let commit = match matched.as_slice() {
+            [] => {
+                return Err(user_error(format!(
+                    "Change ID prefix `{key}` doesn't match any of the revisions being described"
+                )));
+            }
+            [commit] => commit,
+            _ => {
+                return Err(user_error(format!(
+                    "Change ID prefix `{key}` is ambiguous among the revisions being described"
+                )));
+            }
+        };
+        if descriptions
+            .insert(commit.id().clone(), description)
+            .is_some()
+        {
+            return Err(user_error(format!(
+                "Multiple descriptions were given for change {}",
+                commit.change_id().reverse_hex()
+            )));
+        }
+    }
+    Ok(descriptions)
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use clap_complete::ArgValueCompleter;
 use indexmap::IndexSet;
 use itertools::Itertools as _;
@@ -25,12 +27,14 @@ use crate::cli_util::print_unmatched_explicit_paths;
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::get_copy_records;
 use crate::diff_util::show_templated;
 use crate::diff_util::DiffFormatArgs;
+use crate::diff_util::UnifiedDiffOptions;
 use crate::ui::Ui;
 
 /// Compare file contents between two revisions
@@ -100,6 +104,12 @@ pub(crate) struct DiffArgs {
         add = ArgValueCompleter::new(complete::modified_revision_or_range_files),
     )]
     paths: Vec<String>,
+    /// Read additional filesets from the given file, one per line
+    ///
+    /// Use `-` to read from stdin. Empty lines are ignored. If the file is
+    /// empty and no paths are specified, no files are matched.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    pathspec_from_file: Option<PathBuf>,
     /// Render each file diff entry using the given template
     ///
     /// All 0-argument methods of the [`TreeDiffEntry` type] are available as
@@ -118,6 +128,18 @@ pub(crate) struct DiffArgs {
         help_heading = "Diff Formatting Options",
     )]
     template: Option<String>,
+    /// Show the combined diff of a merge commit against all of its parents
+    ///
+    /// Instead of comparing with the automatic merge of the parents, each
+    /// line is compared with all parents at once, similar to `git diff --cc`.
+    /// Each line is prefixed with one column per parent. Only files that
+    /// differ from every parent are shown.
+    #[arg(
+        long,
+        conflicts_with_all = ["from", "to", "template", "short-format", "long-format", "tool"],
+        help_heading = "Diff Formatting Options",
+    )]
+    against_all_parents: bool,
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -130,9 +152,37 @@ pub(crate) fn cmd_diff(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let fileset_expression = workspace_command.parse_file_patterns_with_pathspec_file(
+        ui,
+        &args.paths,
+        args.pathspec_from_file.as_deref(),
+    )?;
     let matcher = fileset_expression.to_matcher();
 
+    if args.against_all_parents {
+        let revision_args = args
+            .revisions
+            .as_deref()
+            .unwrap_or(std::slice::from_ref(&RevisionArg::AT));
+        let [revision_arg] = revision_args else {
+            return Err(user_error(
+                "--against-all-parents requires exactly one revision",
+            ));
+        };
+        let commit = workspace_command.resolve_single_rev(ui, revision_arg)?;
+        let options =
+            UnifiedDiffOptions::from_settings_and_args(workspace_command.settings(), &args.format)?;
+        let diff_renderer = workspace_command.diff_renderer(vec![]);
+        ui.request_pager();
+        diff_renderer.show_combined_diff(
+            ui.stdout_formatter().as_mut(),
+            &commit,
+            matcher.as_ref(),
+            &options,
+        )?;
+        return Ok(());
+    }
+
     let from_tree;
     let to_tree;
     let mut copy_records = CopyRecords::default();
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
 use std::io::Write as _;
 
 use bstr::ByteVec as _;
@@ -19,6 +20,7 @@ use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::CommitId;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::duplicate_commits;
 use jj_lib::rewrite::duplicate_commits_onto_parents;
@@ -48,6 +50,10 @@ use crate::ui::Ui;
 /// children indicated by the arguments will be rebased onto the heads of the
 /// specified commits.
 ///
+/// With `--onto-each`, the specified commits are duplicated onto each of the
+/// destination revisions separately. This can be used to backport a stack of
+/// changes to several release branches at once.
+///
 /// By default, the duplicated commits retain the descriptions of the originals.
 /// This can be customized with the `templates.duplicate_description` setting.
 #[derive(clap::Args, Clone, Debug)]
@@ -96,6 +102,46 @@ pub(crate) struct DuplicateArgs {
         add = ArgValueCompleter::new(complete::revset_expression_mutable),
     )]
     insert_before: Option<Vec<RevisionArg>>,
+    /// The revision(s) to duplicate onto, one copy per revision
+    ///
+    /// Unlike `--destination`, multiple revisions don't create a merge commit.
+    /// Instead, the specified commits are duplicated onto each of them.
+    #[arg(
+        long,
+        conflicts_with_all = ["destination", "insert_after", "insert_before"],
+        value_name = "REVSETS",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    onto_each: Option<Vec<RevisionArg>>,
+    /// Print the mapping of original to duplicated commits using the given
+    /// template
+    ///
+    /// Each line consists of the original commit ID followed by the duplicated
+    /// commit rendered with the template. For example, `--mapping-template
+    /// commit_id` prints pairs of the full commit IDs.
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        add = ArgValueCompleter::new(complete::commit_template_expression),
+    )]
+    mapping_template: Option<String>,
+    /// Print the mapping of original to duplicated commit IDs as JSON
+    #[arg(long, conflicts_with = "mapping_template")]
+    mapping_json: bool,
+    /// Create the duplicates without touching the working copy
+    ///
+    /// The working copy is not snapshotted before the duplicates are created.
+    /// The command fails if it would rewrite the working-copy commit.
+    #[arg(long)]
+    detached: bool,
+}
+
+/// Entry of `--mapping-json` output.
+#[derive(serde::Serialize)]
+struct DuplicateMappingEntry {
+    old: String,
+    new: String,
+    parents: Vec<String>,
 }
 
 #[instrument(skip_all)]
@@ -104,7 +150,11 @@ pub(crate) fn cmd_duplicate(
     command: &CommandHelper,
     args: &DuplicateArgs,
 ) -> Result<(), CommandError> {
-    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut workspace_command = if args.detached {
+        command.workspace_helper_detached(ui)?
+    } else {
+        command.workspace_helper(ui)?
+    };
     let to_duplicate: Vec<CommitId> =
         if !args.revisions_pos.is_empty() || !args.revisions_opt.is_empty() {
             workspace_command
@@ -122,25 +172,38 @@ pub(crate) fn cmd_duplicate(
         return Err(user_error("Cannot duplicate the root commit"));
     }
 
-    let location = if args.destination.is_none()
+    let locations = if let Some(onto_each) = &args.onto_each {
+        let destinations: Vec<CommitId> = workspace_command
+            .parse_union_revsets(ui, onto_each)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?;
+        if destinations.is_empty() {
+            return Err(user_error("No revisions to duplicate onto"));
+        }
+        destinations
+            .into_iter()
+            .rev()
+            .map(|destination| Some((vec![destination], vec![])))
+            .collect_vec()
+    } else if args.destination.is_none()
         && args.insert_after.is_none()
         && args.insert_before.is_none()
     {
-        None
+        vec![None]
     } else {
-        Some(compute_commit_location(
+        vec![Some(compute_commit_location(
             ui,
             &workspace_command,
             args.destination.as_deref(),
             args.insert_after.as_deref(),
             args.insert_before.as_deref(),
             "duplicated commits",
-        )?)
+        )?)]
     };
 
     let mut tx = workspace_command.start_transaction();
 
-    if let Some((parent_commit_ids, children_commit_ids)) = &location {
+    for (parent_commit_ids, children_commit_ids) in locations.iter().flatten() {
         if !parent_commit_ids.is_empty() {
             for commit_id in &to_duplicate {
                 for parent_commit_id in parent_commit_ids {
@@ -187,20 +250,49 @@ pub(crate) fn cmd_duplicate(
     };
 
     let num_to_duplicate = to_duplicate.len();
-    let DuplicateCommitsStats {
-        duplicated_commits,
-        num_rebased,
-    } = if let Some((parent_commit_ids, children_commit_ids)) = location {
-        duplicate_commits(
-            tx.repo_mut(),
-            &to_duplicate,
-            &new_descs,
-            &parent_commit_ids,
-            &children_commit_ids,
-        )?
-    } else {
-        duplicate_commits_onto_parents(tx.repo_mut(), &to_duplicate, &new_descs)?
-    };
+    let mut duplicated_commits = Vec::new();
+    let mut num_rebased = 0;
+    for location in locations {
+        let stats = if let Some((parent_commit_ids, children_commit_ids)) = location {
+            duplicate_commits(
+                tx.repo_mut(),
+                &to_duplicate,
+                &new_descs,
+                &parent_commit_ids,
+                &children_commit_ids,
+            )?
+        } else {
+            duplicate_commits_onto_parents(tx.repo_mut(), &to_duplicate, &new_descs)?
+        };
+        let DuplicateCommitsStats {
+            duplicated_commits: new_commits,
+            num_rebased: new_num_rebased,
+        } = stats;
+        duplicated_commits.extend(new_commits);
+        num_rebased += new_num_rebased;
+    }
+
+    if let Some(text) = &args.mapping_template {
+        let template = tx.parse_commit_template(ui, text)?;
+        let mut formatter = ui.stdout_formatter();
+        for (old_id, new_commit) in &duplicated_commits {
+            write!(formatter, "{} ", old_id.hex())?;
+            template.format(new_commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+    } else if args.mapping_json {
+        let entries = duplicated_commits
+            .iter()
+            .map(|(old_id, new_commit)| DuplicateMappingEntry {
+                old: old_id.hex(),
+                new: new_commit.id().hex(),
+                parents: new_commit.parent_ids().iter().map(|id| id.hex()).collect(),
+            })
+            .collect_vec();
+        let mut stdout = ui.stdout();
+        serde_json::to_writer(&mut stdout, &entries).map_err(io::Error::from)?;
+        writeln!(stdout)?;
+    }
 
     if let Some(mut formatter) = ui.status_formatter() {
         for (old_id, new_commit) in &duplicated_commits {
@@ -14,7 +14,6 @@
 
 use std::io;
 
-use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::commit::Commit;
@@ -77,7 +76,7 @@ pub(crate) struct EvologArgs {
     ///
     /// [`jj help -k templates`]:
     ///     https://jj-vcs.github.io/jj/latest/templates/
-    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::commit_template_expression))]
     template: Option<String>,
     /// Show patch compared to the previous version of this change
     ///
@@ -142,7 +141,7 @@ pub(crate) fn cmd_evolog(
     let evolution_entries = walk_predecessors(repo, &start_commit_ids);
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
-        let mut graph = get_graphlog(graph_style, raw_output.as_mut());
+        let mut graph = get_graphlog(graph_style, false, raw_output.as_mut());
 
         let evolution_nodes = evolution_entries.map_ok(|entry| {
             let ids = entry.predecessor_ids();
@@ -0,0 +1,660 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal writers of the archive formats supported by `jj file show`.
+//!
+//! Only regular files and symlinks are stored, and file contents are never
+//! compressed. Since an entry may fail to be written halfway, callers should
+//! write to a temporary file and copy the archive to its destination once it's
+//! finished.
+
+use std::io;
+use std::io::Write;
+
+use chrono::Datelike as _;
+use chrono::Timelike as _;
+use jj_lib::backend::Timestamp;
+
+const TAR_BLOCK_SIZE: usize = 512;
+
+/// Sink of files to be written in some archive format.
+pub(crate) trait ArchiveWriter {
+    fn add_file(&mut self, path: &str, executable: bool, data: &[u8]) -> io::Result<()>;
+    fn add_symlink(&mut self, path: &str, target: &str) -> io::Result<()>;
+    /// Writes trailing data of the archive.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+fn file_mode(executable: bool) -> u32 {
+    if executable {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+/// Writes files in ustar format. Paths which don't fit in the header are
+/// stored as GNU long name entries, and sizes which don't fit in the header
+/// are stored in GNU base-256 encoding.
+pub(crate) struct TarWriter<W> {
+    out: W,
+    mtime: u64,
+}
+
+impl<W: Write> TarWriter<W> {
+    pub fn new(out: W, timestamp: &Timestamp) -> Self {
+        let mtime = (timestamp.timestamp.0 / 1000).try_into().unwrap_or(0);
+        TarWriter { out, mtime }
+    }
+
+    fn write_entry(
+        &mut self,
+        path: &str,
+        mode: u32,
+        type_flag: u8,
+        link_name: &str,
+        data: &[u8],
+    ) -> io::Result<()> {
+        if link_name.len() > 100 {
+            return Err(io::Error::other(format!(
+                "Symlink target of {path} is too long to be archived"
+            )));
+        }
+        let (prefix, name) = match split_ustar_path(path) {
+            Some(names) => names,
+            None => {
+                let mut long_name = path.as_bytes().to_vec();
+                long_name.push(0);
+                self.write_header("././@LongLink", "", 0, b'L', "", long_name.len() as u64)?;
+                self.write_data(&long_name)?;
+                ("", truncate_bytes(path, 100))
+            }
+        };
+        self.write_header(name, prefix, mode, type_flag, link_name, data.len() as u64)?;
+        self.write_data(data)
+    }
+
+    fn write_header(
+        &mut self,
+        name: &str,
+        prefix: &str,
+        mode: u32,
+        type_flag: u8,
+        link_name: &str,
+        size: u64,
+    ) -> io::Result<()> {
+        let mut header = [0u8; TAR_BLOCK_SIZE];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        write_numeric(&mut header[100..108], mode.into());
+        write_numeric(&mut header[108..116], 0); // uid
+        write_numeric(&mut header[116..124], 0); // gid
+        write_numeric(&mut header[124..136], size);
+        write_numeric(&mut header[136..148], self.mtime);
+        header[148..156].fill(b' ');
+        header[156] = type_flag;
+        header[157..157 + link_name.len()].copy_from_slice(link_name.as_bytes());
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+        header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+        self.out.write_all(&header)
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> io::Result<()> {
+        self.out.write_all(data)?;
+        let padding = (TAR_BLOCK_SIZE - data.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+        self.out.write_all(&[0; TAR_BLOCK_SIZE][..padding])
+    }
+}
+
+impl<W: Write> ArchiveWriter for TarWriter<W> {
+    fn add_file(&mut self, path: &str, executable: bool, data: &[u8]) -> io::Result<()> {
+        self.write_entry(path, file_mode(executable), b'0', "", data)
+    }
+
+    fn add_symlink(&mut self, path: &str, target: &str) -> io::Result<()> {
+        self.write_entry(path, 0o777, b'2', target, &[])
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.write_all(&[0; TAR_BLOCK_SIZE * 2])?;
+        self.out.flush()
+    }
+}
+
+/// Splits `path` into the ustar prefix and name fields.
+fn split_ustar_path(path: &str) -> Option<(&str, &str)> {
+    if path.len() <= 100 {
+        return Some(("", path));
+    }
+    path.match_indices('/')
+        .map(|(i, _)| (&path[..i], &path[i + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100 && !name.is_empty())
+}
+
+fn truncate_bytes(s: &str, max_len: usize) -> &str {
+    let mut end = s.len().min(max_len);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Writes `value` as NUL-terminated octal digits, or in GNU base-256
+/// encoding if it's too large. (e.g. size of 8 GiB or larger)
+fn write_numeric(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    if value < 1 << (3 * width) {
+        field[..width].copy_from_slice(format!("{value:0width$o}").as_bytes());
+        field[width] = 0;
+    } else {
+        // The remaining bytes of the field store the value in big endian.
+        let bytes = value.to_be_bytes();
+        let (marker, digits) = field.split_first_mut().unwrap();
+        *marker = 0x80;
+        let len = bytes.len().min(digits.len());
+        let (padding, digits) = digits.split_at_mut(digits.len() - len);
+        padding.fill(0);
+        digits.copy_from_slice(&bytes[bytes.len() - len..]);
+    }
+}
+
+/// Marker of a field whose value is stored in the ZIP64 extra field.
+const ZIP64_U16_MARKER: u16 = 0xffff;
+const ZIP64_U32_MARKER: u32 = 0xffffffff;
+
+/// Writes files in zip format without compression. ZIP64 extensions are used
+/// for entries and archives which exceed the limits of the original format.
+pub(crate) struct ZipWriter<W> {
+    out: W,
+    dos_time: u16,
+    dos_date: u16,
+    offset: u64,
+    central_directory: Vec<u8>,
+    num_entries: u64,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W, timestamp: &Timestamp) -> Self {
+        let (dos_time, dos_date) = to_dos_date_time(timestamp);
+        ZipWriter {
+            out,
+            dos_time,
+            dos_date,
+            offset: 0,
+            central_directory: vec![],
+            num_entries: 0,
+        }
+    }
+
+    fn write_entry(&mut self, path: &str, unix_mode: u32, data: &[u8]) -> io::Result<()> {
+        let name_len = u16::try_from(path.len())
+            .map_err(|_| io::Error::other(format!("Path {path} is too long to be archived")))?;
+        let size = data.len() as u64;
+        let offset = self.offset;
+        let crc = crc32fast::hash(data);
+
+        // Sizes are recorded in both the local header and the central
+        // directory, whereas the offset is only in the central directory.
+        let size_overflows = size >= u64::from(ZIP64_U32_MARKER);
+        let offset_overflows = offset >= u64::from(ZIP64_U32_MARKER);
+        let local_extra = if size_overflows {
+            zip64_extra_field(&[size, size])
+        } else {
+            vec![]
+        };
+        let central_extra = {
+            let mut values = vec![];
+            if size_overflows {
+                values.extend([size, size]);
+            }
+            if offset_overflows {
+                values.push(offset);
+            }
+            if values.is_empty() {
+                vec![]
+            } else {
+                zip64_extra_field(&values)
+            }
+        };
+        let version_needed: u16 = if size_overflows || offset_overflows {
+            45
+        } else {
+            10
+        };
+        let size32 = clamp_u32(size);
+        let offset32 = clamp_u32(offset);
+
+        let mut local_header = vec![];
+        local_header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        local_header.extend_from_slice(&version_needed.to_le_bytes());
+        local_header.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        local_header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        local_header.extend_from_slice(&self.dos_time.to_le_bytes());
+        local_header.extend_from_slice(&self.dos_date.to_le_bytes());
+        local_header.extend_from_slice(&crc.to_le_bytes());
+        local_header.extend_from_slice(&size32.to_le_bytes());
+        local_header.extend_from_slice(&size32.to_le_bytes());
+        local_header.extend_from_slice(&name_len.to_le_bytes());
+        local_header.extend_from_slice(&(local_extra.len() as u16).to_le_bytes());
+        local_header.extend_from_slice(path.as_bytes());
+        local_header.extend_from_slice(&local_extra);
+        self.out.write_all(&local_header)?;
+        self.out.write_all(data)?;
+        self.offset += local_header.len() as u64 + size;
+        self.num_entries += 1;
+
+        let entry = &mut self.central_directory;
+        entry.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        entry.extend_from_slice(&0x032du16.to_le_bytes()); // made by Unix, version 4.5
+        entry.extend_from_slice(&version_needed.to_le_bytes());
+        entry.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        entry.extend_from_slice(&0u16.to_le_bytes()); // stored
+        entry.extend_from_slice(&self.dos_time.to_le_bytes());
+        entry.extend_from_slice(&self.dos_date.to_le_bytes());
+        entry.extend_from_slice(&crc.to_le_bytes());
+        entry.extend_from_slice(&size32.to_le_bytes());
+        entry.extend_from_slice(&size32.to_le_bytes());
+        entry.extend_from_slice(&name_len.to_le_bytes());
+        entry.extend_from_slice(&(central_extra.len() as u16).to_le_bytes());
+        entry.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        entry.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        entry.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        entry.extend_from_slice(&(unix_mode << 16).to_le_bytes());
+        entry.extend_from_slice(&offset32.to_le_bytes());
+        entry.extend_from_slice(path.as_bytes());
+        entry.extend_from_slice(&central_extra);
+        Ok(())
+    }
+}
+
+impl<W: Write> ArchiveWriter for ZipWriter<W> {
+    fn add_file(&mut self, path: &str, executable: bool, data: &[u8]) -> io::Result<()> {
+        self.write_entry(path, 0o100000 | file_mode(executable), data)
+    }
+
+    fn add_symlink(&mut self, path: &str, target: &str) -> io::Result<()> {
+        self.write_entry(path, 0o120777, target.as_bytes())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let size = self.central_directory.len() as u64;
+        let offset = self.offset;
+        self.out.write_all(&self.central_directory)?;
+
+        let num_entries16 = u16::try_from(self.num_entries)
+            .ok()
+            .filter(|&n| n < ZIP64_U16_MARKER);
+        let size32 = u32::try_from(size).ok().filter(|&n| n < ZIP64_U32_MARKER);
+        let offset32 = u32::try_from(offset).ok().filter(|&n| n < ZIP64_U32_MARKER);
+        if num_entries16.is_none() || size32.is_none() || offset32.is_none() {
+            let zip64_end_offset = offset + size;
+            let mut zip64_end_record = vec![];
+            zip64_end_record.extend_from_slice(&0x06064b50u32.to_le_bytes());
+            zip64_end_record.extend_from_slice(&44u64.to_le_bytes()); // size of remaining record
+            zip64_end_record.extend_from_slice(&0x032du16.to_le_bytes()); // made by
+            zip64_end_record.extend_from_slice(&45u16.to_le_bytes()); // version needed
+            zip64_end_record.extend_from_slice(&0u32.to_le_bytes()); // disk number
+            zip64_end_record.extend_from_slice(&0u32.to_le_bytes()); // disk with central directory
+            zip64_end_record.extend_from_slice(&self.num_entries.to_le_bytes());
+            zip64_end_record.extend_from_slice(&self.num_entries.to_le_bytes());
+            zip64_end_record.extend_from_slice(&size.to_le_bytes());
+            zip64_end_record.extend_from_slice(&offset.to_le_bytes());
+            zip64_end_record.extend_from_slice(&0x07064b50u32.to_le_bytes()); // locator
+            zip64_end_record.extend_from_slice(&0u32.to_le_bytes()); // disk with end record
+            zip64_end_record.extend_from_slice(&zip64_end_offset.to_le_bytes());
+            zip64_end_record.extend_from_slice(&1u32.to_le_bytes()); // total number of disks
+            self.out.write_all(&zip64_end_record)?;
+        }
+
+        let num_entries16 = num_entries16.unwrap_or(ZIP64_U16_MARKER);
+        let mut end_record = vec![];
+        end_record.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        end_record.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        end_record.extend_from_slice(&0u16.to_le_bytes()); // disk with central directory
+        end_record.extend_from_slice(&num_entries16.to_le_bytes());
+        end_record.extend_from_slice(&num_entries16.to_le_bytes());
+        end_record.extend_from_slice(&size32.unwrap_or(ZIP64_U32_MARKER).to_le_bytes());
+        end_record.extend_from_slice(&offset32.unwrap_or(ZIP64_U32_MARKER).to_le_bytes());
+        end_record.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.out.write_all(&end_record)?;
+        self.out.flush()
+    }
+}
+
+/// Builds the ZIP64 extended information extra field of the given values.
+fn zip64_extra_field(values: &[u64]) -> Vec<u8> {
+    let mut field = vec![];
+    field.extend_from_slice(&0x0001u16.to_le_bytes());
+    field.extend_from_slice(&(values.len() as u16 * 8).to_le_bytes());
+    for value in values {
+        field.extend_from_slice(&value.to_le_bytes());
+    }
+    field
+}
+
+/// Returns the `value` if it fits in the original zip format, or the marker
+/// of the ZIP64 extra field.
+fn clamp_u32(value: u64) -> u32 {
+    u32::try_from(value)
+        .ok()
+        .filter(|&n| n < ZIP64_U32_MARKER)
+        .unwrap_or(ZIP64_U32_MARKER)
+}
+
+/// Converts the timestamp to MS-DOS time and date in the timestamp's time
+/// zone. Dates before 1980 are clamped.
+fn to_dos_date_time(timestamp: &Timestamp) -> (u16, u16) {
+    let seconds = timestamp.timestamp.0.div_euclid(1000) + i64::from(timestamp.tz_offset) * 60;
+    let Some(datetime) = chrono::DateTime::from_timestamp(seconds, 0) else {
+        return (0, 0x21);
+    };
+    if datetime.year() < 1980 {
+        return (0, 0x21); // 1980-01-01
+    }
+    let year = u16::try_from(datetime.year() - 1980)
+        .unwrap_or(127)
+        .min(127);
+    let time = (datetime.hour() << 11) | (datetime.minute() << 5) | (datetime.second() / 2);
+    let time = time as u16;
+    let date = (year << 9) | ((datetime.month() << 5) | datetime.day()) as u16;
+    (time, date)
+}
+
+/// Writes each file as the path, the content size, and the content, with
+/// the path and size terminated by NUL bytes.
+pub(crate) struct NulSeparatedWriter<W> {
+    out: W,
+}
+
+impl<W: Write> NulSeparatedWriter<W> {
+    pub fn new(out: W) -> Self {
+        NulSeparatedWriter { out }
+    }
+}
+
+impl<W: Write> ArchiveWriter for NulSeparatedWriter<W> {
+    fn add_file(&mut self, path: &str, _executable: bool, data: &[u8]) -> io::Result<()> {
+        write!(self.out, "{path}\0{}\0", data.len())?;
+        self.out.write_all(data)
+    }
+
+    fn add_symlink(&mut self, path: &str, target: &str) -> io::Result<()> {
+        self.add_file(path, false, target.as_bytes())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Entry {
+        path: String,
+        mode: u32,
+        data: Vec<u8>,
+    }
+
+    fn timestamp() -> Timestamp {
+        Timestamp {
+            timestamp: jj_lib::backend::MillisSinceEpoch(1_700_000_000_000),
+            tz_offset: 0,
+        }
+    }
+
+    fn write_archive(writer: &mut dyn ArchiveWriter) {
+        let long_dir = "d".repeat(120);
+        writer.add_file("file", false, b"data").unwrap();
+        writer.add_file("dir/exec", true, b"#!/bin/sh\n").unwrap();
+        writer
+            .add_file(&format!("{long_dir}/file"), false, &[b'x'; 1000])
+            .unwrap();
+        writer.add_file(&"f".repeat(300), false, b"").unwrap();
+        writer.add_symlink("link", "dir/exec").unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn expected_entries() -> Vec<Entry> {
+        let long_dir = "d".repeat(120);
+        vec![
+            Entry {
+                path: "file".to_owned(),
+                mode: 0o644,
+                data: b"data".to_vec(),
+            },
+            Entry {
+                path: "dir/exec".to_owned(),
+                mode: 0o755,
+                data: b"#!/bin/sh\n".to_vec(),
+            },
+            Entry {
+                path: format!("{long_dir}/file"),
+                mode: 0o644,
+                data: vec![b'x'; 1000],
+            },
+            Entry {
+                path: "f".repeat(300),
+                mode: 0o644,
+                data: vec![],
+            },
+            Entry {
+                path: "link".to_owned(),
+                mode: 0o777,
+                data: b"dir/exec".to_vec(),
+            },
+        ]
+    }
+
+    fn read_numeric(field: &[u8]) -> u64 {
+        if field[0] & 0x80 != 0 {
+            field[1..]
+                .iter()
+                .fold(0, |acc, &b| (acc << 8) | u64::from(b))
+        } else {
+            let digits = std::str::from_utf8(field).unwrap().trim_end_matches('\0');
+            u64::from_str_radix(digits, 8).unwrap()
+        }
+    }
+
+    fn read_str(field: &[u8]) -> String {
+        let len = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8(field[..len].to_vec()).unwrap()
+    }
+
+    fn parse_tar_header(header: &[u8]) -> (String, u32, u8, String, u64) {
+        let mut checksum_header = header.to_vec();
+        checksum_header[148..156].fill(b' ');
+        let checksum: u64 = checksum_header.iter().map(|&b| u64::from(b)).sum();
+        assert_eq!(read_numeric(&header[148..155]), checksum);
+        assert_eq!(&header[257..265], b"ustar\000");
+        let name = read_str(&header[..100]);
+        let prefix = read_str(&header[345..500]);
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let mode = read_numeric(&header[100..108]) as u32;
+        let size = read_numeric(&header[124..136]);
+        let link_name = read_str(&header[157..257]);
+        (path, mode, header[156], link_name, size)
+    }
+
+    fn read_tar(archive: &[u8]) -> Vec<Entry> {
+        assert_eq!(archive.len() % TAR_BLOCK_SIZE, 0);
+        let mut blocks = archive.chunks(TAR_BLOCK_SIZE);
+        let mut entries = vec![];
+        let mut long_name = None;
+        loop {
+            let header = blocks.next().unwrap();
+            if header.iter().all(|&b| b == 0) {
+                assert!(blocks.next().unwrap().iter().all(|&b| b == 0));
+                assert!(blocks.next().is_none());
+                return entries;
+            }
+            let (path, mode, type_flag, link_name, size) = parse_tar_header(header);
+            let num_blocks = (size as usize).div_ceil(TAR_BLOCK_SIZE);
+            let mut data: Vec<u8> = blocks
+                .by_ref()
+                .take(num_blocks)
+                .flatten()
+                .copied()
+                .collect();
+            data.truncate(size as usize);
+            match type_flag {
+                b'L' => long_name = Some(read_str(&data)),
+                b'0' => entries.push(Entry {
+                    path: long_name.take().unwrap_or(path),
+                    mode,
+                    data,
+                }),
+                b'2' => entries.push(Entry {
+                    path: long_name.take().unwrap_or(path),
+                    mode,
+                    data: link_name.into_bytes(),
+                }),
+                _ => panic!("unexpected type flag {type_flag}"),
+            }
+        }
+    }
+
+    fn read_zip(archive: &[u8]) -> Vec<Entry> {
+        let u16_at = |pos: usize| u16::from_le_bytes(archive[pos..pos + 2].try_into().unwrap());
+        let u32_at = |pos: usize| u32::from_le_bytes(archive[pos..pos + 4].try_into().unwrap());
+        let end_record = archive.len() - 22;
+        assert_eq!(u32_at(end_record), 0x06054b50);
+        let num_entries = u16_at(end_record + 10);
+        let mut pos = u32_at(end_record + 16) as usize;
+        let mut entries = vec![];
+        for _ in 0..num_entries {
+            assert_eq!(u32_at(pos), 0x02014b50);
+            let crc = u32_at(pos + 16);
+            let size = u32_at(pos + 24) as usize;
+            let name_len = usize::from(u16_at(pos + 28));
+            let extra_len = usize::from(u16_at(pos + 30));
+            let mode = u32_at(pos + 38) >> 16;
+            let offset = u32_at(pos + 42) as usize;
+            let path = String::from_utf8(archive[pos + 46..pos + 46 + name_len].to_vec()).unwrap();
+            pos += 46 + name_len + extra_len;
+
+            assert_eq!(u32_at(offset), 0x04034b50);
+            assert_eq!(u32_at(offset + 14), crc);
+            let local_name_len = usize::from(u16_at(offset + 26));
+            let local_extra_len = usize::from(u16_at(offset + 28));
+            let data_start = offset + 30 + local_name_len + local_extra_len;
+            let data = archive[data_start..data_start + size].to_vec();
+            assert_eq!(crc32fast::hash(&data), crc);
+            entries.push(Entry {
+                path,
+                mode: mode & 0o777,
+                data,
+            });
+        }
+        entries
+    }
+
+    #[test]
+    fn test_tar_round_trip() {
+        let mut output = vec![];
+        write_archive(&mut TarWriter::new(&mut output, &timestamp()));
+        assert_eq!(read_tar(&output), expected_entries());
+        assert_eq!(read_numeric(&output[136..148]), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_tar_large_size() {
+        let size = 8 << 30; // 8 GiB
+        let mut output = vec![];
+        let mut writer = TarWriter::new(&mut output, &timestamp());
+        writer
+            .write_header("file", "", 0o644, b'0', "", size)
+            .unwrap();
+        assert_eq!(output[124], 0x80);
+        let (path, _mode, _type_flag, _link_name, parsed_size) = parse_tar_header(&output);
+        assert_eq!(path, "file");
+        assert_eq!(parsed_size, size);
+    }
+
+    #[test]
+    fn test_write_numeric() {
+        let mut field = [0; 12];
+        write_numeric(&mut field, 0o77777777777);
+        assert_eq!(&field, b"77777777777\0");
+        write_numeric(&mut field, 0o100000000000);
+        assert_eq!(field, [0x80, 0, 0, 0, 0, 0, 0, 0x02, 0, 0, 0, 0]);
+        assert_eq!(read_numeric(&field), 0o100000000000);
+        write_numeric(&mut field, u64::MAX);
+        assert_eq!(read_numeric(&field), u64::MAX);
+    }
+
+    #[test]
+    fn test_zip_round_trip() {
+        let mut output = vec![];
+        write_archive(&mut ZipWriter::new(&mut output, &timestamp()));
+        assert_eq!(read_zip(&output), expected_entries());
+    }
+
+    #[test]
+    fn test_zip_end_record() {
+        let timestamp = Timestamp {
+            timestamp: jj_lib::backend::MillisSinceEpoch(0),
+            tz_offset: 0,
+        };
+        let mut output = vec![];
+        let mut writer = ZipWriter::new(&mut output, &timestamp);
+        writer.add_file("file", false, b"data").unwrap();
+        writer.finish().unwrap();
+        let end_record = &output[output.len() - 22..];
+        assert_eq!(&end_record[..4], b"PK\x05\x06");
+        assert_eq!(&end_record[10..12], &1u16.to_le_bytes());
+
+        // Too many entries for the original format
+        let mut output = vec![];
+        let mut writer = ZipWriter::new(&mut output, &timestamp);
+        writer.num_entries = u64::from(ZIP64_U16_MARKER);
+        writer.finish().unwrap();
+        let end_record = &output[output.len() - 22..];
+        assert_eq!(&end_record[10..12], &ZIP64_U16_MARKER.to_le_bytes());
+        let locator = &output[output.len() - 42..output.len() - 22];
+        assert_eq!(&locator[..4], b"PK\x06\x07");
+        let zip64_end_record = &output[..56];
+        assert_eq!(&zip64_end_record[..4], b"PK\x06\x06");
+        assert_eq!(&zip64_end_record[32..40], &65535u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_zip64_extra_field() {
+        assert_eq!(
+            zip64_extra_field(&[1, 2]),
+            [&[1, 0, 16, 0][..], &1u64.to_le_bytes(), &2u64.to_le_bytes()].concat()
+        );
+        assert_eq!(clamp_u32(1), 1);
+        assert_eq!(clamp_u32(u64::from(u32::MAX)), ZIP64_U32_MARKER);
+        assert_eq!(clamp_u32(1 << 40), ZIP64_U32_MARKER);
+    }
+
+    #[test]
+    fn test_split_ustar_path() {
+        assert_eq!(split_ustar_path("a/b"), Some(("", "a/b")));
+        let long_dir = "d".repeat(120);
+        let path = format!("{long_dir}/file");
+        assert_eq!(split_ustar_path(&path), Some((long_dir.as_str(), "file")));
+        assert_eq!(split_ustar_path(&"f".repeat(101)), None);
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::file_util::PathError;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPathBuf;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Number of candidates offered by `--interactive` if `--limit` isn't given.
+const DEFAULT_INTERACTIVE_LIMIT: usize = 10;
+
+/// Search for files by name in a revision
+///
+/// Paths are matched fuzzily: all characters of the pattern have to appear in
+/// the path in the same order, ignoring case. Paths containing the pattern as
+/// a substring are listed first, followed by paths where the matched
+/// characters are close together or at the start of path components.
+///
+/// The list of paths in each tree is cached in the repo, so searching the
+/// same tree again is fast even in huge repos.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileSearchArgs {
+    /// The revision to search files in
+    #[arg(
+        long, short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    revision: RevisionArg,
+    /// The pattern to search for in file paths
+    #[arg(long, value_name = "PATTERN")]
+    name: String,
+    /// Limit number of paths to show
+    #[arg(long, short = 'n')]
+    limit: Option<usize>,
+    /// Pick one of the matching paths from a list and print it
+    ///
+    /// At most 10 paths are offered unless `--limit` is specified.
+    #[arg(long, short)]
+    interactive: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_search(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileSearchArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let tree = commit.tree()?;
+    let cache = PathListCache::new(workspace_command.repo_path());
+    let paths = match cache.get(commit.tree_id()) {
+        Some(paths) => paths,
+        None => {
+            let paths = list_paths(&tree)?;
+            // The cache is only an optimization, so failing to write it isn't
+            // fatal.
+            if let Err(err) = cache.insert(commit.tree_id(), &paths) {
+                tracing::warn!(?err, "failed to write path list cache");
+            }
+            paths
+        }
+    };
+
+    let pattern = args.name.to_lowercase();
+    let limit = if args.interactive {
+        args.limit.unwrap_or(DEFAULT_INTERACTIVE_LIMIT)
+    } else {
+        args.limit.unwrap_or(usize::MAX)
+    };
+    let matches = paths
+        .iter()
+        .filter_map(|path| {
+            let path_str = path.as_internal_file_string();
+            let score = fuzzy_score(&pattern, &path_str.to_lowercase())?;
+            Some((score, path))
+        })
+        .sorted_by(|(score1, path1), (score2, path2)| {
+            let len1 = path1.as_internal_file_string().len();
+            let len2 = path2.as_internal_file_string().len();
+            score2
+                .cmp(score1)
+                .then(len1.cmp(&len2))
+                .then(path1.cmp(path2))
+        })
+        .map(|(_, path)| path)
+        .take(limit)
+        .collect_vec();
+
+    if args.interactive {
+        if matches.is_empty() {
+            return Err(user_error(format!(
+                "No paths matching `{}` in the revision",
+                args.name
+            )));
+        }
+        let selected = pick_path(ui, &workspace_command, &matches)?;
+        writeln!(
+            ui.stdout(),
+            "{}",
+            workspace_command.format_file_path(selected)
+        )?;
+        return Ok(());
+    }
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for path in matches {
+        writeln!(formatter, "{}", workspace_command.format_file_path(path))?;
+    }
+    Ok(())
+}
+
+fn list_paths(tree: &MergedTree) -> Result<Vec<RepoPathBuf>, CommandError> {
+    let mut paths = vec![];
+    for (path, value) in tree.entries() {
+        value?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn pick_path<'a>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    candidates: &[&'a RepoPathBuf],
+) -> Result<&'a RepoPathBuf, CommandError> {
+    for (i, path) in candidates.iter().enumerate() {
+        writeln!(
+            ui.stderr(),
+            "{:>3}: {}",
+            i + 1,
+            workspace_command.format_file_path(path)
+        )?;
+    }
+    let index = ui.prompt_choice_with("Enter the number of the path", Some("1"), |input| {
+        input
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=candidates.len()).contains(n))
+            .ok_or("unrecognized response")
+    })?;
+    Ok(candidates[index - 1])
+}
+
+/// Returns how well the `path` matches the `pattern`, or `None` if it doesn't
+/// match. Both arguments should be lowercased. Higher scores are better.
+fn fuzzy_score(pattern: &str, path: &str) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    if let Some(pos) = path.rfind(pattern) {
+        // Prefer matches in the file name over matches in the directories.
+        let file_name_start = path.rfind('/').map_or(0, |i| i + 1);
+        let in_file_name = pos >= file_name_start;
+        return Some(10_000 + if in_file_name { 5_000 } else { 0 });
+    }
+
+    let mut score = 0;
+    let mut pattern_chars = pattern.chars().peekable();
+    let mut prev_char = None;
+    let mut prev_matched = false;
+    for c in path.chars() {
+        let Some(&expected) = pattern_chars.peek() else {
+            break;
+        };
+        if c == expected {
+            pattern_chars.next();
+            score += 10;
+            if prev_matched {
+                score += 15;
+            }
+            if prev_char.is_none_or(|p| matches!(p, '/' | '_' | '-' | '.' | ' ')) {
+                score += 10;
+            }
+            prev_matched = true;
+        } else {
+            // Penalize gaps between matched characters.
+            if score > 0 {
+                score -= 1;
+            }
+            prev_matched = false;
+        }
+        prev_char = Some(c);
+    }
+    pattern_chars.peek().is_none().then_some(score)
+}
+
+/// Lists of paths in trees, stored in the `file_search_cache` directory of
+/// the repo.
+///
+/// Trees are immutable, so the entries never need to be invalidated. Only the
+/// most recently written [`MAX_CACHED_PATH_LISTS`] entries are kept.
+#[derive(Clone, Debug)]
+struct PathListCache {
+    dir: PathBuf,
+}
+
+impl PathListCache {
+    fn new(repo_path: &Path) -> Self {
+        Self {
+            dir: repo_path.join("file_search_cache"),
+        }
+    }
+
+    /// Looks up the cached path list. Unreadable entries are treated as
+    /// missing.
+    fn get(&self, tree_id: &MergedTreeId) -> Option<Vec<RepoPathBuf>> {
+        let content = fs::read_to_string(self.dir.join(tree_key(tree_id))).ok()?;
+        content
+            .split_terminator('\0')
+            .map(|path| RepoPathBuf::from_internal_string(path).ok())
+            .collect()
+    }
+
+    fn insert(&self, tree_id: &MergedTreeId, paths: &[RepoPathBuf]) -> Result<(), PathError> {
+        fs::create_dir_all(&self.dir).context(&self.dir)?;
+        let path = self.dir.join(tree_key(tree_id));
+        let mut content = String::new();
+        for repo_path in paths {
+            content.push_str(repo_path.as_internal_file_string());
+            content.push('\0');
+        }
+        // Concurrent readers never see a partially-written list.
+        file_util::write_file_atomically(&path, |file| file.write_all(content.as_bytes()))?;
+        self.evict(MAX_CACHED_PATH_LISTS)
+    }
+
+    /// Removes all but the `max_entries` most recently written entries.
+    /// Leftover temporary files are removed the same way.
+    fn evict(&self, max_entries: usize) -> Result<(), PathError> {
+        let entries: Vec<_> = fs::read_dir(&self.dir)
+            .context(&self.dir)?
+            .map(|entry| {
+                let entry = entry.context(&self.dir)?;
+                let path = entry.path();
+                let modified = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .context(&path)?;
+                Ok::<_, PathError>((modified, path))
+            })
+            .try_collect()?;
+        let stale_paths = entries
+            .into_iter()
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .skip(max_entries)
+            .map(|(_, path)| path);
+        for path in stale_paths {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err).context(&path),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of path lists kept in [`PathListCache`].
+const MAX_CACHED_PATH_LISTS: usize = 16;
+
+fn tree_key(tree_id: &MergedTreeId) -> String {
+    tree_id.to_merge().iter().map(|id| id.hex()).join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use jj_lib::backend::TreeId;
+
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "foo"), Some(0));
+        assert_eq!(fuzzy_score("abc", "foo"), None);
+        assert_eq!(fuzzy_score("ofo", "foo"), None);
+
+        // Substring matches are ranked first, preferably in the file name
+        let in_file_name = fuzzy_score("main", "src/main.rs").unwrap();
+        let in_dir = fuzzy_score("main", "main/lib.rs").unwrap();
+        let fuzzy = fuzzy_score("mn", "src/main.rs").unwrap();
+        assert!(in_file_name > in_dir);
+        assert!(in_dir > fuzzy);
+
+        // Consecutive and word-boundary matches are preferred
+        let consecutive = fuzzy_score("clu", "cli/src/cli_util.rs").unwrap();
+        let boundaries = fuzzy_score("csu", "cli/src/cli_util.rs").unwrap();
+        let scattered = fuzzy_score("clt", "cal/lit.rs").unwrap();
+        assert!(consecutive > scattered);
+        assert!(boundaries > scattered);
+    }
+
+    #[test]
+    fn test_path_list_cache() {
+        let temp_dir = testutils::new_temp_dir();
+        let cache = PathListCache::new(temp_dir.path());
+        let tree_id = |hex: &str| MergedTreeId::resolved(TreeId::from_hex(hex));
+        let paths = ["a", "b/c"]
+            .map(|path| RepoPathBuf::from_internal_string(path).unwrap())
+            .to_vec();
+
+        assert_eq!(cache.get(&tree_id("01")), None);
+        cache.insert(&tree_id("01"), &paths).unwrap();
+        assert_eq!(cache.get(&tree_id("01")), Some(paths.clone()));
+        cache.insert(&tree_id("02"), &[]).unwrap();
+        assert_eq!(cache.get(&tree_id("02")), Some(vec![]));
+
+        // The least recently written entry is evicted first
+        let old_file = fs::File::options()
+            .write(true)
+            .open(temp_dir.path().join("file_search_cache").join("01"))
+            .unwrap();
+        old_file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        cache.evict(1).unwrap();
+        assert_eq!(cache.get(&tree_id("01")), None);
+        assert_eq!(cache.get(&tree_id("02")), Some(vec![]));
+    }
+}
@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
+use std::io::BufWriter;
+use std::io::Seek as _;
 use std::io::Write as _;
 
 use clap_complete::ArgValueCompleter;
 use jj_lib::backend::BackendResult;
 use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::file_util::copy_async_to_sync;
@@ -28,6 +32,10 @@ use jj_lib::repo_path::RepoPath;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
+use super::archive::ArchiveWriter;
+use super::archive::NulSeparatedWriter;
+use super::archive::TarWriter;
+use super::archive::ZipWriter;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
@@ -41,6 +49,11 @@ use crate::ui::Ui;
 ///
 /// If the given path is a directory, files in the directory will be visited
 /// recursively.
+///
+/// With `--tar`, `--zip`, or `--null`, the matched files are written as a
+/// single stream which keeps the file paths, e.g. for exporting files from
+/// a historical revision without checking it out. Paths in the stream are
+/// relative to the workspace root.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileShowArgs {
     /// The revision to get the file contents from
@@ -59,6 +72,17 @@ pub(crate) struct FileShowArgs {
         add = ArgValueCompleter::new(complete::all_revision_files),
     )]
     paths: Vec<String>,
+    /// Write the files as a tar archive
+    #[arg(long, conflicts_with_all = ["zip", "null"])]
+    tar: bool,
+    /// Write the files as an uncompressed zip archive
+    #[arg(long, conflicts_with_all = ["tar", "null"])]
+    zip: bool,
+    /// Write each file as its path, its size in bytes, and its content
+    ///
+    /// The path and the size are each terminated by a NUL byte.
+    #[arg(long, conflicts_with_all = ["tar", "zip"])]
+    null: bool,
 }
 
 #[instrument(skip_all)]
@@ -74,6 +98,37 @@ pub(crate) fn cmd_file_show(
     // parse_union_filesets(). paths = [] should be "none()" if supported.
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
 
+    if args.tar || args.zip || args.null {
+        let timestamp = &commit.committer().timestamp;
+        // Archives are written to a temporary file first so that an error
+        // doesn't leave a truncated archive in the output.
+        let mut archive_file = if args.tar || args.zip {
+            Some(tempfile::tempfile()?)
+        } else {
+            None
+        };
+        let mut writer: Box<dyn ArchiveWriter + '_> = match &mut archive_file {
+            Some(file) if args.tar => Box::new(TarWriter::new(BufWriter::new(file), timestamp)),
+            Some(file) => Box::new(ZipWriter::new(BufWriter::new(file), timestamp)),
+            None => Box::new(NulSeparatedWriter::new(ui.stdout())),
+        };
+        let matcher = fileset_expression.to_matcher();
+        write_archive_entries(
+            ui,
+            &workspace_command,
+            writer.as_mut(),
+            tree.entries_matching(matcher.as_ref()),
+        )?;
+        writer.finish()?;
+        drop(writer);
+        if let Some(mut file) = archive_file {
+            file.rewind()?;
+            io::copy(&mut file, &mut ui.stdout())?;
+        }
+        print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
+        return Ok(());
+    }
+
     // Try fast path for single file entry
     if let Some(path) = get_single_path(&fileset_expression) {
         let value = tree.path_value(path)?;
@@ -154,3 +209,52 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
     }
     Ok(())
 }
+
+fn write_archive_entries<P: AsRef<RepoPath>>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    writer: &mut dyn ArchiveWriter,
+    entries: impl IntoIterator<Item = (P, BackendResult<MergedTreeValue>)>,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    for (path, result) in entries {
+        let path = path.as_ref();
+        let value = result?;
+        let materialized = materialize_tree_value(repo.store(), path, value).block_on()?;
+        let archive_path = path.as_internal_file_string();
+        match materialized {
+            MaterializedTreeValue::Absent => panic!("absent values should be excluded"),
+            MaterializedTreeValue::AccessDenied(err) => {
+                let ui_path = workspace_command.format_file_path(path);
+                writeln!(
+                    ui.warning_default(),
+                    "Path '{ui_path}' exists but access is denied: {err}"
+                )?;
+            }
+            MaterializedTreeValue::File(mut file) => {
+                let data = file.read_all(path).block_on()?;
+                writer.add_file(archive_path, file.executable, &data)?;
+            }
+            MaterializedTreeValue::FileConflict(file) => {
+                let data = materialize_merge_result_to_bytes(
+                    &file.contents,
+                    workspace_command.env().conflict_marker_style(),
+                );
+                writer.add_file(archive_path, file.executable.unwrap_or(false), &data)?;
+            }
+            MaterializedTreeValue::Symlink { target, .. } => {
+                writer.add_symlink(archive_path, &target)?;
+            }
+            MaterializedTreeValue::OtherConflict { .. }
+            | MaterializedTreeValue::GitSubmodule(_) => {
+                let ui_path = workspace_command.format_file_path(path);
+                writeln!(
+                    ui.warning_default(),
+                    "Path '{ui_path}' exists but is not a file"
+                )?;
+            }
+            MaterializedTreeValue::Tree(_) => panic!("entries should not contain trees"),
+        }
+    }
+    Ok(())
+}
@@ -29,6 +29,9 @@ use crate::complete;
 use crate::ui::Ui;
 
 /// Stop tracking specified paths in the working copy
+///
+/// Conflicted paths are untracked by dropping all sides of the conflict. If
+/// any of the paths is not ignored, nothing is untracked.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileUntrackArgs {
     /// Paths to untrack. They must already be ignored.
@@ -61,10 +64,17 @@ pub(crate) fn cmd_file_untrack(
 
     let mut tx = workspace_command.start_transaction().into_inner();
     let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
-    // Create a new tree without the unwanted files
+    // Create a new tree without the unwanted files. All sides of conflicted
+    // files are dropped.
     let mut tree_builder = MergedTreeBuilder::new(wc_commit.tree_id().clone());
     let wc_tree = wc_commit.tree()?;
-    for (path, _value) in wc_tree.entries_matching(matcher.as_ref()) {
+    let mut num_untracked = 0;
+    let mut num_conflicted = 0;
+    for (path, value) in wc_tree.entries_matching(matcher.as_ref()) {
+        if !value?.is_resolved() {
+            num_conflicted += 1;
+        }
+        num_untracked += 1;
         tree_builder.set_or_remove(path, Merge::absent());
     }
     let new_tree_id = tree_builder.write_tree(&store)?;
@@ -111,6 +121,14 @@ Make sure they're ignored, then try again.",
     }
     let repo = tx.commit("untrack paths")?;
     locked_ws.finish(repo.op_id().clone())?;
+    if num_conflicted > 0 {
+        writeln!(
+            ui.status(),
+            "Untracked {num_untracked} files, including {num_conflicted} conflicted files"
+        )?;
+    } else {
+        writeln!(ui.status(), "Untracked {num_untracked} files")?;
+    }
     print_snapshot_stats(ui, &stats, workspace_command.env().path_converter())?;
     Ok(())
 }
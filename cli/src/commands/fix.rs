@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::io::Write as _;
 use std::path::Path;
 use std::process::Stdio;
@@ -20,15 +21,17 @@ use std::process::Stdio;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
-use jj_lib::backend::FileId;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
 use jj_lib::fix::fix_files;
 use jj_lib::fix::FileToFix;
 use jj_lib::fix::FixError;
+use jj_lib::fix::FixedFile;
 use jj_lib::fix::ParallelFileFixer;
 use jj_lib::matchers::Matcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
@@ -60,9 +63,21 @@ use crate::ui::Ui;
 /// can potentially increase or decrease the number of conflict markers.
 ///
 /// The external tools must accept the current file content on standard input,
-/// and return the updated file content on standard output. A tool's output will
-/// not be used unless it exits with a successful exit code. Output on standard
-/// error will be passed through to the terminal.
+/// and return the updated file content on standard output, or a JSON object
+/// describing the updated file if `output-format = "json"` is set. A tool's
+/// output will not be used unless it exits with a successful exit code. Output
+/// on standard error will be passed through to the terminal.
+///
+/// Tools are run in the workspace root, with the following environment
+/// variables set:
+///  - `JJ_FILE_PATH`: The repo-relative path of the file being fixed.
+///  - `JJ_COMMIT_ID`, `JJ_CHANGE_ID`: The commit and change ID of the first
+///    revision containing the file. Since identical files are only fixed once,
+///    the same fix is also applied to descendants containing the file, unless
+///    `per-commit = true` is set for a matching tool.
+///  - `JJ_WORKSPACE_ROOT`, `JJ_REPO_PATH`, `JJ_WORKSPACE_NAME`, `JJ_OP_ID`,
+///    `JJ_WC_COMMIT_ID`, `JJ_WC_CHANGE_ID`: The same workspace variables as
+///    set by `jj util exec`.
 ///
 /// Tools are defined in a table where the keys are arbitrary identifiers and
 /// the values have the following properties:
@@ -79,6 +94,20 @@ use crate::ui::Ui;
 ///  - `enabled`: Enables or disables the tool. If omitted, the tool is enabled.
 ///    This is useful for defining disabled tools in user configuration that can
 ///    be enabled in individual repositories with one config setting.
+///  - `output-format`: Either `"content"` (the default), if the tool prints
+///    the new file content, or `"json"`, if the tool prints a JSON object with
+///    the following optional fields:
+///     - `content`: The new file content, either as a string or, for binary
+///       files, as an array of byte values. If omitted, the content is kept.
+///     - `executable`: Whether the file should be executable.
+///     - `path`: The new repo-relative path of the file, to rename it.
+///     - `delete`: If true, the file is deleted and the other fields are
+///       ignored.
+///    Renaming or deleting conflicted files isn't supported.
+///  - `per-commit`: If true, the tool is run once for each revision containing
+///    a matching file, even if the file is unchanged from the parent. This is
+///    useful for tools which depend on `JJ_COMMIT_ID` or `JJ_CHANGE_ID`, but
+///    runs the tool more often on long stacks of revisions.
 ///
 /// For example, the following configuration defines how two code formatters
 /// (`clang-format` and `black`) will apply to three different file extensions
@@ -129,6 +158,7 @@ pub(crate) fn cmd_fix(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let workspace_root = workspace_command.workspace_root().to_owned();
+    let workspace_env = workspace_command.workspace_env_vars()?;
     let path_converter = workspace_command.path_converter().to_owned();
     let tools_config = get_tools_config(ui, workspace_command.settings())?;
     let root_commits: Vec<CommitId> = if args.source.is_empty() {
@@ -149,13 +179,15 @@ pub(crate) fn cmd_fix(
         fix_one_file(
             ui,
             &workspace_root,
+            &workspace_env,
             &path_converter,
             &tools_config,
             store,
             file_to_fix,
         )
         .block_on()
-    });
+    })
+    .with_commit_dependent_paths(tools_config.per_commit_matcher());
     let summary = fix_files(
         root_commits,
         &matcher,
@@ -173,91 +205,131 @@ pub(crate) fn cmd_fix(
     tx.finish(ui, format!("fixed {} commits", summary.num_fixed_commits))
 }
 
-/// Invokes all matching tools (if any) to file_to_fix. If the content is
-/// successfully transformed the new content is written and the new FileId is
-/// returned. Returns None if the content is unchanged.
+/// Invokes all matching tools (if any) to file_to_fix. If the file is
+/// successfully transformed the new content is written and the new state of
+/// the file is returned. Returns None if the file is unchanged.
 ///
 /// The matching tools are invoked in order, with the result of one tool feeding
 /// into the next tool. Returns FixError if there is an error reading or writing
 /// the file. However, if a tool invocation fails for whatever reason, the tool
 /// is simply skipped and we proceed to invoke the next tool (this is
-/// indistinguishable from succeeding with no changes).
+/// indistinguishable from succeeding with no changes). Once a tool deletes the
+/// file, no further tools are invoked.
 ///
 /// TODO: Better error handling so we can tell the user what went wrong with
 /// each failed input.
 async fn fix_one_file(
     ui: &Ui,
     workspace_root: &Path,
+    workspace_env: &[(&'static str, OsString)],
     path_converter: &RepoPathUiConverter,
     tools_config: &ToolsConfig,
     store: &Store,
     file_to_fix: &FileToFix,
-) -> Result<Option<FileId>, FixError> {
+) -> Result<Option<FixedFile>, FixError> {
     let mut matching_tools = tools_config
         .tools
         .iter()
         .filter(|tool_config| tool_config.matcher.matches(&file_to_fix.repo_path))
         .peekable();
-    if matching_tools.peek().is_some() {
-        // The first matching tool gets its input from the committed file, and any
-        // subsequent matching tool gets its input from the previous matching tool's
-        // output.
-        let mut old_content = vec![];
-        let mut read = store
-            .read_file(&file_to_fix.repo_path, &file_to_fix.file_id)
-            .await?;
-        read.read_to_end(&mut old_content).await?;
-        let new_content = matching_tools.fold(old_content.clone(), |prev_content, tool_config| {
-            match run_tool(
-                ui,
-                workspace_root,
-                path_converter,
-                &tool_config.command,
-                file_to_fix,
-                &prev_content,
-            ) {
-                Ok(next_content) => next_content,
-                // TODO: Because the stderr is passed through, this isn't always failing
-                // silently, but it should do something better will the exit code, tool
-                // name, etc.
-                Err(_) => prev_content,
-            }
-        });
-        if new_content != old_content {
-            // TODO: send futures back over channel
-            let new_file_id = store
-                .write_file(&file_to_fix.repo_path, &mut new_content.as_slice())
-                .await?;
-            return Ok(Some(new_file_id));
+    if matching_tools.peek().is_none() {
+        return Ok(None);
+    }
+    // The first matching tool gets its input from the committed file, and any
+    // subsequent matching tool gets its input from the previous matching tool's
+    // output.
+    let mut old_content = vec![];
+    let mut read = store
+        .read_file(&file_to_fix.repo_path, &file_to_fix.file_id)
+        .await?;
+    read.read_to_end(&mut old_content).await?;
+    let commit = store.backend().read_commit(&file_to_fix.commit_id).await?;
+    let context = ToolContext {
+        workspace_root,
+        workspace_env,
+        commit_id: file_to_fix.commit_id.hex(),
+        change_id: commit.change_id.reverse_hex(),
+    };
+    let old_state = FileState {
+        content: old_content,
+        executable: file_to_fix.executable,
+        path: file_to_fix.repo_path.clone(),
+    };
+    let mut new_state = Some(old_state.clone());
+    for tool_config in matching_tools {
+        let Some(prev_state) = &new_state else {
+            break;
+        };
+        // TODO: Because the stderr is passed through, this isn't always failing
+        // silently, but it should do something better will the exit code, tool
+        // name, etc.
+        if let Ok(next_state) = run_tool(ui, path_converter, &context, tool_config, prev_state) {
+            new_state = next_state;
+        }
+    }
+    match new_state {
+        None => Ok(Some(FixedFile::Deleted)),
+        Some(new_state) if new_state == old_state => Ok(None),
+        Some(new_state) => {
+            let file_id = if new_state.content == old_state.content {
+                file_to_fix.file_id.clone()
+            } else {
+                // TODO: send futures back over channel
+                store
+                    .write_file(&new_state.path, &mut new_state.content.as_slice())
+                    .await?
+            };
+            let new_path = (new_state.path != old_state.path).then_some(new_state.path);
+            Ok(Some(FixedFile::Modified {
+                file_id,
+                executable: new_state.executable,
+                new_path,
+            }))
         }
     }
-    Ok(None)
 }
 
-/// Runs the `tool_command` to fix the given file content.
-///
-/// The `old_content` is assumed to be that of the `file_to_fix`'s `FileId`, but
-/// this is not verified.
+/// Information about the file being fixed that is passed to the tools.
+struct ToolContext<'a> {
+    workspace_root: &'a Path,
+    workspace_env: &'a [(&'static str, OsString)],
+    commit_id: String,
+    change_id: String,
+}
+
+/// A file as it is passed from one tool to the next.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct FileState {
+    content: Vec<u8>,
+    executable: bool,
+    path: RepoPathBuf,
+}
+
+/// Runs the tool to fix the given file.
 ///
-/// Returns the new file content, whose value will be the same as `old_content`
-/// unless the command introduced changes. Returns `None` if there were any
-/// failures when starting, stopping, or communicating with the subprocess.
+/// Returns the new state of the file, which will be the same as `old_state`
+/// unless the command introduced changes, or `None` if the tool deleted the
+/// file. Returns `Err` if there were any failures when starting, stopping, or
+/// communicating with the subprocess, or if its output couldn't be parsed.
 fn run_tool(
     ui: &Ui,
-    workspace_root: &Path,
     path_converter: &RepoPathUiConverter,
-    tool_command: &CommandNameAndArgs,
-    file_to_fix: &FileToFix,
-    old_content: &[u8],
-) -> Result<Vec<u8>, ()> {
+    context: &ToolContext,
+    tool_config: &ToolConfig,
+    old_state: &FileState,
+) -> Result<Option<FileState>, ()> {
     // TODO: Pipe stderr so we can tell the user which commit, file, and tool it is
     // associated with.
     let mut vars: HashMap<&str, &str> = HashMap::new();
-    vars.insert("path", file_to_fix.repo_path.as_internal_file_string());
-    let mut command = tool_command.to_command_with_variables(&vars);
-    tracing::debug!(?command, ?file_to_fix.repo_path, "spawning fix tool");
+    vars.insert("path", old_state.path.as_internal_file_string());
+    let mut command = tool_config.command.to_command_with_variables(&vars);
+    tracing::debug!(?command, ?old_state.path, "spawning fix tool");
     let mut child = command
-        .current_dir(workspace_root)
+        .current_dir(context.workspace_root)
+        .envs(context.workspace_env.iter().cloned())
+        .env("JJ_FILE_PATH", old_state.path.as_internal_file_string())
+        .env("JJ_COMMIT_ID", &context.commit_id)
+        .env("JJ_CHANGE_ID", &context.change_id)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -266,28 +338,59 @@ fn run_tool(
     let mut stdin = child.stdin.take().unwrap();
     let output = std::thread::scope(|s| {
         s.spawn(move || {
-            stdin.write_all(old_content).ok();
+            stdin.write_all(&old_state.content).ok();
         });
         Some(child.wait_with_output().or(Err(())))
     })
     .unwrap()?;
     tracing::debug!(?command, ?output.status, "fix tool exited:");
+    let ui_path = path_converter.format_file_path(&old_state.path);
     if !output.stderr.is_empty() {
         let mut stderr = ui.stderr();
-        writeln!(
-            stderr,
-            "{}:",
-            path_converter.format_file_path(&file_to_fix.repo_path)
-        )
-        .ok();
+        writeln!(stderr, "{ui_path}:").ok();
         stderr.write_all(&output.stderr).ok();
         writeln!(stderr).ok();
     }
-    if output.status.success() {
-        Ok(output.stdout)
-    } else {
-        Err(())
+    if !output.status.success() {
+        return Err(());
     }
+    match tool_config.output_format {
+        ToolOutputFormat::Content => Ok(Some(FileState {
+            content: output.stdout,
+            ..old_state.clone()
+        })),
+        ToolOutputFormat::Json => parse_json_output(&output.stdout, old_state).map_err(|err| {
+            writeln!(
+                ui.warning_default(),
+                "Ignoring invalid output of fix tool for {ui_path}: {err}"
+            )
+            .ok();
+        }),
+    }
+}
+
+/// Parses the output of a tool with `output-format = "json"`.
+fn parse_json_output(stdout: &[u8], old_state: &FileState) -> Result<Option<FileState>, String> {
+    let output: JsonToolOutput = serde_json::from_slice(stdout).map_err(|err| err.to_string())?;
+    if output.delete {
+        return Ok(None);
+    }
+    let path = match output.path {
+        Some(path) => match RepoPathBuf::from_internal_string(&path) {
+            Ok(path) if !path.is_root() => path,
+            _ => return Err(format!("Invalid path {path:?}")),
+        },
+        None => old_state.path.clone(),
+    };
+    Ok(Some(FileState {
+        content: match output.content {
+            Some(JsonFileContent::Text(text)) => text.into_bytes(),
+            Some(JsonFileContent::Bytes(bytes)) => bytes,
+            None => old_state.content.clone(),
+        },
+        executable: output.executable.unwrap_or(old_state.executable),
+        path,
+    }))
 }
 
 /// Represents an entry in the `fix.tools` config table.
@@ -298,6 +401,12 @@ struct ToolConfig {
     matcher: Box<dyn Matcher>,
     /// Whether the tool is enabled
     enabled: bool,
+    /// How to interpret the output of the command.
+    output_format: ToolOutputFormat,
+    /// Whether the tool is run for each commit containing the file.
+    per_commit: bool,
+    /// The fileset expression that `matcher` was built from.
+    expression: FilesetExpression,
     // TODO: Store the `name` field here and print it with the command's stderr, to clearly
     // associate any errors/warnings with the tool and its configuration entry.
 }
@@ -309,6 +418,20 @@ struct ToolsConfig {
     tools: Vec<ToolConfig>,
 }
 
+impl ToolsConfig {
+    /// Returns a matcher for the files that should be fixed in each commit
+    /// containing them.
+    fn per_commit_matcher(&self) -> Box<dyn Matcher> {
+        let expressions = self
+            .tools
+            .iter()
+            .filter(|tool| tool.per_commit)
+            .map(|tool| tool.expression.clone())
+            .collect_vec();
+        FilesetExpression::union_all(expressions).to_matcher()
+    }
+}
+
 /// Simplifies deserialization of the config values while building a ToolConfig.
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -317,6 +440,42 @@ struct RawToolConfig {
     patterns: Vec<String>,
     #[serde(default = "default_tool_enabled")]
     enabled: bool,
+    #[serde(default)]
+    output_format: ToolOutputFormat,
+    #[serde(default)]
+    per_commit: bool,
+}
+
+/// The `output-format` of a tool.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ToolOutputFormat {
+    /// The tool prints the new file content.
+    #[default]
+    Content,
+    /// The tool prints a JSON object describing the new file.
+    Json,
+}
+
+/// The output of a tool with `output-format = "json"`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonToolOutput {
+    content: Option<JsonFileContent>,
+    executable: Option<bool>,
+    path: Option<String>,
+    #[serde(default)]
+    delete: bool,
+}
+
+/// The `content` of [`JsonToolOutput`].
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum JsonFileContent {
+    /// UTF-8 text.
+    Text(String),
+    /// Arbitrary bytes, as an array of numbers.
+    Bytes(Vec<u8>),
 }
 
 fn default_tool_enabled() -> bool {
@@ -356,6 +515,9 @@ fn get_tools_config(ui: &mut Ui, settings: &UserSettings) -> Result<ToolsConfig,
                 command: tool.command,
                 matcher: expression.to_matcher(),
                 enabled: tool.enabled,
+                output_format: tool.output_format,
+                per_commit: tool.per_commit,
+                expression,
             })
         })
         .try_collect()?;
@@ -27,6 +27,7 @@ use jj_lib::repo::Repo as _;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 
+use super::repo_template::RepoTemplate;
 use super::write_repository_level_trunk_alias;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
@@ -65,6 +66,13 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Apply settings and files from the given repo template
+    ///
+    /// See `jj git init --template` for the template format. The sparse
+    /// patterns of the template are applied before the working copy is
+    /// checked out.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::AnyPath)]
+    template: Option<String>,
 }
 
 fn clone_destination_for_source(source: &str) -> Option<&str> {
@@ -93,6 +101,11 @@ pub fn cmd_git_clone(
         return Err(cli_error("--at-op is not respected"));
     }
     let source = absolute_git_url(command.cwd(), &args.source)?;
+    let template = args
+        .template
+        .as_deref()
+        .map(|source| RepoTemplate::load(ui, command.cwd(), source))
+        .transpose()?;
     let wc_path_str = args
         .destination
         .as_deref()
@@ -117,7 +130,10 @@ pub fn cmd_git_clone(
         .map_err(|err| user_error_with_message(format!("Failed to create {wc_path_str}"), err))?;
 
     let clone_result = (|| -> Result<_, CommandError> {
-        let workspace_command = init_workspace(ui, command, &canonical_wc_path, args.colocate)?;
+        let mut workspace_command = init_workspace(ui, command, &canonical_wc_path, args.colocate)?;
+        if let Some(template) = &template {
+            template.apply_settings(ui, &mut workspace_command)?;
+        }
         let mut workspace_command =
             configure_remote(ui, command, workspace_command, remote_name, &source)?;
         let default_branch = fetch_new_remote(ui, &mut workspace_command, remote_name, args.depth)?;
@@ -162,6 +178,9 @@ pub fn cmd_git_clone(
             tx.finish(ui, "check out git remote's default branch")?;
         }
     }
+    if let Some(template) = &template {
+        template.copy_files(ui, workspace_command.workspace_root())?;
+    }
     Ok(())
 }
 
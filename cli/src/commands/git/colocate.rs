@@ -0,0 +1,206 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::git;
+use jj_lib::git::GitExportStats;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::cli_error;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::commands::git::maybe_add_gitignore;
+use crate::git_util::print_git_export_stats;
+use crate::ui::Ui;
+
+/// Convert the repo into a colocated Git repo
+///
+/// Moves the Git repo backing this repo from `.jj/repo/store/git` to a `.git`
+/// directory in the workspace root, so that both `jj` and `git` commands can be
+/// used in the same directory. Bookmarks are then exported to the Git repo, and
+/// Git's `HEAD` is set to the parent of the working-copy commit.
+///
+/// Only repos whose Git repo is stored inside the `.jj` directory can be
+/// converted. If moving the Git repo or exporting to it fails, the original
+/// layout is restored.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitColocateArgs {}
+
+pub fn cmd_git_colocate(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &GitColocateArgs,
+) -> Result<(), CommandError> {
+    if command.global_args().ignore_working_copy {
+        return Err(cli_error("--ignore-working-copy is not respected"));
+    }
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let workspace_command = command.workspace_helper(ui)?;
+    if workspace_command.working_copy_shared_with_git() {
+        return Err(user_error("The repo is already colocated with Git"));
+    }
+    let store_path = workspace_command.repo_path().join("store");
+    let internal_git_path = store_path.join("git");
+    let git_backend = git::get_git_backend(workspace_command.repo().store())?;
+    let is_internal = match (
+        dunce::canonicalize(git_backend.git_repo_path()),
+        dunce::canonicalize(&internal_git_path),
+    ) {
+        (Ok(git_repo_path), Ok(internal_git_path)) => git_repo_path == internal_git_path,
+        _ => false,
+    };
+    if !is_internal {
+        return Err(user_error_with_hint(
+            "The Git repo is not stored inside the jj repo",
+            "Only repos created without `--colocate` or `--git-repo` can be converted.",
+        ));
+    }
+    let dot_git_path = workspace_command.workspace_root().join(".git");
+    if dot_git_path.symlink_metadata().is_ok() {
+        return Err(user_error(
+            "Cannot colocate the repo because `.git` already exists in the workspace root",
+        ));
+    }
+    let op_heads_store = workspace_command.repo().op_heads_store().clone();
+    // Close the Git repo before moving it.
+    drop(workspace_command);
+
+    // Hold the repo lock so that no other command commits an operation while
+    // the Git repo is being moved. The lock can't be held while the refs are
+    // exported, since committing the transaction takes it again.
+    let moved_repo = {
+        let _lock = op_heads_store.lock()?;
+        MovedGitRepo::move_to(&store_path, &internal_git_path, &dot_git_path)?
+    };
+
+    let stats = match export_to_colocated_repo(ui, command) {
+        Ok(stats) => stats,
+        Err(err) => {
+            let _lock = op_heads_store.lock()?;
+            moved_repo.restore();
+            return Err(err);
+        }
+    };
+    print_git_export_stats(ui, &stats)?;
+    writeln!(
+        ui.status(),
+        "Moved the Git repo to \"{}\"",
+        file_util::relative_path(command.cwd(), &dot_git_path).display()
+    )?;
+    Ok(())
+}
+
+/// Exports the refs and `HEAD` to the Git repo at its new location.
+fn export_to_colocated_repo(
+    ui: &mut Ui,
+    command: &CommandHelper,
+) -> Result<GitExportStats, CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    maybe_add_gitignore(&workspace_command)?;
+    let wc_commit_id = workspace_command.get_wc_commit_id().cloned();
+    let mut tx = workspace_command.start_transaction();
+    let stats = git::export_refs(tx.repo_mut())?;
+    if let Some(wc_commit_id) = wc_commit_id {
+        let wc_commit = tx.repo().store().get_commit(&wc_commit_id)?;
+        git::reset_head(tx.repo_mut(), &wc_commit)?;
+    }
+    tx.finish(ui, "colocate git repo")?;
+    Ok(stats)
+}
+
+/// Git repo moved from the jj repo to the workspace root, along with what is
+/// needed to move it back.
+struct MovedGitRepo {
+    internal_git_path: PathBuf,
+    dot_git_path: PathBuf,
+    target_path: PathBuf,
+    old_target: Vec<u8>,
+    config_path: PathBuf,
+    old_config: Vec<u8>,
+}
+
+impl MovedGitRepo {
+    /// Moves the internal Git repo to `dot_git_path` and points the backend at
+    /// the new location. Rolls back on failure.
+    fn move_to(
+        store_path: &Path,
+        internal_git_path: &Path,
+        dot_git_path: &Path,
+    ) -> Result<Self, CommandError> {
+        let target_path = store_path.join("git_target");
+        let old_target = fs::read(&target_path).context(&target_path)?;
+        let internal_config_path = internal_git_path.join("config");
+        let old_config = fs::read(&internal_config_path).context(&internal_config_path)?;
+        fs::rename(internal_git_path, dot_git_path).context(internal_git_path)?;
+        let moved_repo = Self {
+            internal_git_path: internal_git_path.to_owned(),
+            dot_git_path: dot_git_path.to_owned(),
+            target_path,
+            old_target,
+            config_path: dot_git_path.join("config"),
+            old_config,
+        };
+        let result = set_git_repo_non_bare(dot_git_path, &moved_repo.config_path).and_then(|()| {
+            let new_target = file_util::relative_path(store_path, dot_git_path);
+            let new_target = file_util::slash_path(&new_target);
+            let new_target = file_util::path_to_bytes(&new_target).map_err(user_error)?;
+            file_util::write_file_atomically(&moved_repo.target_path, |file| {
+                file.write_all(new_target)
+            })?;
+            Ok(())
+        });
+        if let Err(err) = result {
+            moved_repo.restore();
+            return Err(err);
+        }
+        Ok(moved_repo)
+    }
+
+    /// Restores the original layout so the repo stays usable. Errors are
+    /// ignored since there's nothing more to do about them.
+    fn restore(&self) {
+        let old_target = self.old_target.as_slice();
+        let old_config = self.old_config.as_slice();
+        file_util::write_file_atomically(&self.target_path, |file| file.write_all(old_target)).ok();
+        file_util::write_file_atomically(&self.config_path, |file| file.write_all(old_config)).ok();
+        fs::rename(&self.dot_git_path, &self.internal_git_path).ok();
+    }
+}
+
+/// Sets `core.bare = false` so Git uses the parent directory as working tree.
+fn set_git_repo_non_bare(git_repo_path: &Path, config_path: &Path) -> Result<(), CommandError> {
+    let mut git_repo = gix::open(git_repo_path).map_err(internal_error)?;
+    let mut config = git_repo.config_snapshot_mut();
+    config
+        .section_mut("core", None)
+        .map_err(internal_error)?
+        .set(
+            "bare".try_into().expect("'bare' to be a valid value name"),
+            "false".into(),
+        );
+    file_util::write_file_atomically(config_path, |file| {
+        config.write_to_filter(file, |section| section.meta() == config.meta())
+    })?;
+    Ok(())
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::mem;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
@@ -46,10 +47,16 @@ pub struct GitFetchArgs {
     /// By default, the specified name matches exactly. Use `glob:` prefix to
     /// expand `*` as a glob, e.g. `--branch 'glob:push-*'`. Other wildcard
     /// characters such as `?` are *not* supported.
+    ///
+    /// If not specified, this defaults to the `remotes.<name>.fetch-bookmarks`
+    /// setting of each remote. If that is not configured, all branches are
+    /// fetched.
+    ///
+    /// Fetching only exact branch names is faster against remotes with many
+    /// refs.
     #[arg(
         long, short,
         alias = "bookmark",
-        default_value = "glob:*",
         value_parser = StringPattern::parse,
         add = ArgValueCandidates::new(complete::bookmarks),
     )]
@@ -118,8 +125,9 @@ pub fn cmd_git_fetch(
         .sorted()
         .collect_vec();
 
+    let branch_groups = group_remotes_by_branches(&workspace_command, &remotes, &args.branch)?;
     let mut tx = workspace_command.start_transaction();
-    do_git_fetch(ui, &mut tx, &remotes, &args.branch)?;
+    do_git_fetch(ui, &mut tx, &branch_groups)?;
     tx.finish(
         ui,
         format!(
@@ -164,23 +172,79 @@ fn parse_remote_pattern(remote: &str) -> Result<StringPattern, CommandError> {
     StringPattern::parse(remote).map_err(config_error)
 }
 
+/// Branch patterns to fetch and the remotes to fetch them from.
+struct BranchGroup<'a> {
+    branches: Vec<StringPattern>,
+    remotes: Vec<&'a RemoteName>,
+}
+
+/// Groups `remotes` by the branch patterns to fetch. The branches specified by
+/// command arguments take precedence over the per-remote settings.
+fn group_remotes_by_branches<'a>(
+    workspace_command: &WorkspaceCommandHelper,
+    remotes: &[&'a RemoteName],
+    branch_args: &[StringPattern],
+) -> Result<Vec<BranchGroup<'a>>, CommandError> {
+    if !branch_args.is_empty() {
+        return Ok(vec![BranchGroup {
+            branches: branch_args.to_vec(),
+            remotes: remotes.to_vec(),
+        }]);
+    }
+    let settings = workspace_command.settings();
+    let mut groups: Vec<BranchGroup> = vec![];
+    for &remote in remotes {
+        let key = ["remotes", remote.as_str(), "fetch-bookmarks"];
+        let branches = if let Some(names) = settings.get::<Vec<String>>(key).optional()? {
+            names
+                .iter()
+                .map(|name| StringPattern::parse(name).map_err(config_error))
+                .try_collect()?
+        } else {
+            vec![StringPattern::glob("*").unwrap()]
+        };
+        // StringPattern isn't comparable, so compare the kinds and the source
+        // strings.
+        let to_key = |p: &StringPattern| (mem::discriminant(p), p.as_str().to_owned());
+        let same_branches = |group: &&mut BranchGroup| {
+            itertools::equal(
+                group.branches.iter().map(to_key),
+                branches.iter().map(to_key),
+            )
+        };
+        if let Some(group) = groups.iter_mut().find(same_branches) {
+            group.remotes.push(remote);
+        } else {
+            groups.push(BranchGroup {
+                branches,
+                remotes: vec![remote],
+            });
+        }
+    }
+    Ok(groups)
+}
+
 fn do_git_fetch(
     ui: &mut Ui,
     tx: &mut WorkspaceCommandTransaction,
-    remotes: &[&RemoteName],
-    branch_names: &[StringPattern],
+    branch_groups: &[BranchGroup],
 ) -> Result<(), CommandError> {
     let git_settings = tx.settings().git_settings()?;
     let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings)?;
 
-    for remote_name in remotes {
-        with_remote_git_callbacks(ui, |callbacks| {
-            git_fetch.fetch(remote_name, branch_names, callbacks, None)
-        })?;
+    for group in branch_groups {
+        for remote_name in &group.remotes {
+            with_remote_git_callbacks(ui, |callbacks| {
+                git_fetch.fetch(remote_name, &group.branches, callbacks, None)
+            })?;
+        }
     }
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
-    warn_if_branches_not_found(ui, tx, branch_names, remotes)
+    for group in branch_groups {
+        warn_if_branches_not_found(ui, tx, &group.branches, &group.remotes)?;
+    }
+    Ok(())
 }
 
 fn warn_if_branches_not_found(
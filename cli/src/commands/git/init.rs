@@ -30,6 +30,7 @@ use jj_lib::repo::Repo as _;
 use jj_lib::view::View;
 use jj_lib::workspace::Workspace;
 
+use super::repo_template::RepoTemplate;
 use super::write_repository_level_trunk_alias;
 use crate::cli_util::start_repo_transaction;
 use crate::cli_util::CommandHelper;
@@ -79,6 +80,18 @@ pub struct GitInitArgs {
     /// This option is mutually exclusive with `--colocate`.
     #[arg(long, conflicts_with = "colocate", value_hint = clap::ValueHint::DirPath)]
     git_repo: Option<String>,
+
+    /// Apply settings and files from the given repo template
+    ///
+    /// The template is either a TOML file, which is used as the repo config,
+    /// or a directory which may contain a `config.toml` file, a
+    /// `sparse-patterns` file listing the sparse patterns one per line, and a
+    /// `files/` directory whose contents are copied into the working copy.
+    ///
+    /// The path to the template is recorded as `repo-template.source` in the
+    /// repo config.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::AnyPath)]
+    template: Option<String>,
 }
 
 pub fn cmd_git_init(
@@ -93,6 +106,11 @@ pub fn cmd_git_init(
         return Err(cli_error("--at-op is not respected"));
     }
     let cwd = command.cwd();
+    let template = args
+        .template
+        .as_deref()
+        .map(|source| RepoTemplate::load(ui, cwd, source))
+        .transpose()?;
     let wc_path = cwd.join(&args.destination);
     let wc_path = file_util::create_or_reuse_dir(&wc_path)
         .and_then(|_| dunce::canonicalize(wc_path))
@@ -104,6 +122,7 @@ pub fn cmd_git_init(
         &wc_path,
         args.colocate,
         args.git_repo.as_deref(),
+        template.as_ref(),
     )?;
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
@@ -122,6 +141,7 @@ fn do_init(
     workspace_root: &Path,
     colocate: bool,
     git_repo: Option<&str>,
+    template: Option<&RepoTemplate>,
 ) -> Result<(), CommandError> {
     #[derive(Clone, Debug)]
     enum GitInitMode {
@@ -162,8 +182,11 @@ fn do_init(
     match &init_mode {
         GitInitMode::Colocate => {
             let (workspace, repo) = Workspace::init_colocated_git(&settings, workspace_root)?;
-            let workspace_command = command.for_workable_repo(ui, workspace, repo)?;
+            let mut workspace_command = command.for_workable_repo(ui, workspace, repo)?;
             maybe_add_gitignore(&workspace_command)?;
+            if let Some(template) = template {
+                template.apply(ui, &mut workspace_command)?;
+            }
         }
         GitInitMode::External(git_repo_path) => {
             let (workspace, repo) =
@@ -174,6 +197,9 @@ fn do_init(
             let repo = init_git_refs(ui, repo, command.string_args(), colocated)?;
             let mut workspace_command = command.for_workable_repo(ui, workspace, repo)?;
             maybe_add_gitignore(&workspace_command)?;
+            if let Some(template) = template {
+                template.apply(ui, &mut workspace_command)?;
+            }
             workspace_command.maybe_snapshot(ui)?;
             maybe_set_repository_level_trunk_alias(ui, &workspace_command)?;
             if !workspace_command.working_copy_shared_with_git() {
@@ -190,7 +216,11 @@ fn do_init(
             print_trackable_remote_bookmarks(ui, workspace_command.repo().view())?;
         }
         GitInitMode::Internal => {
-            Workspace::init_internal_git(&settings, workspace_root)?;
+            let (workspace, repo) = Workspace::init_internal_git(&settings, workspace_root)?;
+            if let Some(template) = template {
+                let mut workspace_command = command.for_workable_repo(ui, workspace, repo)?;
+                template.apply(ui, &mut workspace_command)?;
+            }
         }
     }
     Ok(())
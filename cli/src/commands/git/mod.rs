@@ -13,12 +13,15 @@
 // limitations under the License.
 
 mod clone;
+mod colocate;
 mod export;
 mod fetch;
 mod import;
 mod init;
+mod outgoing;
 mod push;
 mod remote;
+mod repo_template;
 mod root;
 
 use std::path::Path;
@@ -34,6 +37,8 @@ use jj_lib::store::Store;
 
 use self::clone::cmd_git_clone;
 use self::clone::GitCloneArgs;
+use self::colocate::cmd_git_colocate;
+use self::colocate::GitColocateArgs;
 use self::export::cmd_git_export;
 use self::export::GitExportArgs;
 use self::fetch::cmd_git_fetch;
@@ -42,6 +47,8 @@ use self::import::cmd_git_import;
 use self::import::GitImportArgs;
 use self::init::cmd_git_init;
 use self::init::GitInitArgs;
+use self::outgoing::cmd_git_outgoing;
+use self::outgoing::GitOutgoingArgs;
 use self::push::cmd_git_push;
 use self::push::GitPushArgs;
 use self::remote::cmd_git_remote;
@@ -54,6 +61,8 @@ use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
+pub use self::push::push_bookmarks;
+
 /// Commands for working with Git remotes and the underlying Git repo
 ///
 /// See this [comparison], including a [table of commands].
@@ -66,10 +75,12 @@ use crate::ui::Ui;
 #[derive(Subcommand, Clone, Debug)]
 pub enum GitCommand {
     Clone(GitCloneArgs),
+    Colocate(GitColocateArgs),
     Export(GitExportArgs),
     Fetch(GitFetchArgs),
     Import(GitImportArgs),
     Init(GitInitArgs),
+    Outgoing(GitOutgoingArgs),
     Push(GitPushArgs),
     #[command(subcommand)]
     Remote(RemoteCommand),
@@ -83,10 +94,12 @@ pub fn cmd_git(
 ) -> Result<(), CommandError> {
     match subcommand {
         GitCommand::Clone(args) => cmd_git_clone(ui, command, args),
+        GitCommand::Colocate(args) => cmd_git_colocate(ui, command, args),
         GitCommand::Export(args) => cmd_git_export(ui, command, args),
         GitCommand::Fetch(args) => cmd_git_fetch(ui, command, args),
         GitCommand::Import(args) => cmd_git_import(ui, command, args),
         GitCommand::Init(args) => cmd_git_init(ui, command, args),
+        GitCommand::Outgoing(args) => cmd_git_outgoing(ui, command, args),
         GitCommand::Push(args) => cmd_git_push(ui, command, args),
         GitCommand::Remote(args) => cmd_git_remote(ui, command, args),
         GitCommand::Root(args) => cmd_git_root(ui, command, args),
@@ -0,0 +1,159 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::ref_name::RemoteNameBuf;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::str_util::StringPattern;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::commands::git::push::get_default_push_remote;
+use crate::complete;
+use crate::diff_util::DiffFormatArgs;
+use crate::ui::Ui;
+
+/// Show commits that would be pushed to a Git remote
+///
+/// For each bookmark tracking the remote, lists the commits reachable from the
+/// local bookmark but not from the remote-tracking bookmark, i.e.
+/// `<bookmark>@<remote>..<bookmark>`.
+///
+/// The remote state is the one recorded by the last `jj git fetch` or `jj git
+/// push`. This command doesn't access the network.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitOutgoingArgs {
+    /// The remote to compare against
+    ///
+    /// This defaults to the `git.push` setting. If that is not configured, and
+    /// if there are multiple remotes, the remote named "origin" will be used.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<RemoteNameBuf>,
+    /// Show only bookmarks matching the given name pattern (can be repeated)
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select bookmarks by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets#string-patterns
+    #[arg(
+        long, short,
+        alias = "branch",
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::local_bookmarks),
+    )]
+    bookmark: Vec<StringPattern>,
+    /// Render each commit using the given template
+    ///
+    /// If not specified, this defaults to the `templates.commit_summary`
+    /// setting.
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::template_expression))]
+    template: Option<String>,
+    /// Show patch of each commit
+    #[arg(long, short = 'p')]
+    patch: bool,
+    #[command(flatten)]
+    diff_format: DiffFormatArgs,
+}
+
+pub fn cmd_git_outgoing(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitOutgoingArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let remote = match &args.remote {
+        Some(remote) => remote.clone(),
+        None => get_default_push_remote(ui, &workspace_command)?,
+    };
+    let repo = workspace_command.repo().clone();
+    let template = match &args.template {
+        Some(text) => workspace_command.parse_commit_template(ui, text)?,
+        None => workspace_command.commit_summary_template(),
+    };
+    let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    let mut num_outgoing_bookmarks = 0;
+    for (name, targets) in repo.view().local_remote_bookmarks(&remote) {
+        if !args.bookmark.is_empty()
+            && !args
+                .bookmark
+                .iter()
+                .any(|pattern| pattern.is_match(name.as_str()))
+        {
+            continue;
+        }
+        // Untracked bookmarks aren't pushed unless requested explicitly.
+        if !targets.remote_ref.is_tracked() || targets.local_target == &targets.remote_ref.target {
+            continue;
+        }
+        num_outgoing_bookmarks += 1;
+        let symbol = name.to_remote_symbol(&remote);
+        if targets.local_target.is_absent() {
+            writeln!(formatter.labeled("bookmark"), "{symbol}")?;
+            writeln!(
+                formatter,
+                "  Delete bookmark from {}",
+                targets
+                    .remote_ref
+                    .target
+                    .added_ids()
+                    .map(short_commit_hash)
+                    .join(", ")
+            )?;
+            continue;
+        }
+        let remote_heads = targets.remote_ref.target.added_ids().cloned().collect();
+        let local_heads = targets.local_target.added_ids().cloned().collect();
+        let expression =
+            RevsetExpression::commits(remote_heads).range(&RevsetExpression::commits(local_heads));
+        let commits: Vec<Commit> = workspace_command
+            .attach_revset_evaluator(expression)
+            .evaluate_to_commits()?
+            .try_collect()?;
+        writeln!(formatter.labeled("bookmark"), "{symbol}")?;
+        if commits.is_empty() {
+            // The bookmark moved backward or sideways to an already pushed
+            // commit.
+            writeln!(formatter, "  (no new commits)")?;
+        }
+        for commit in &commits {
+            write!(formatter, "  ")?;
+            template.format(commit, formatter)?;
+            writeln!(formatter)?;
+            if let Some(renderer) = &diff_renderer {
+                let width = ui.term_width();
+                renderer.show_patch(ui, formatter, commit, &EverythingMatcher, width)?;
+            }
+        }
+    }
+    if num_outgoing_bookmarks == 0 {
+        writeln!(
+            ui.status(),
+            "No outgoing changes to {remote}.",
+            remote = remote.as_symbol()
+        )?;
+    }
+    Ok(())
+}
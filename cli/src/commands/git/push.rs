@@ -30,6 +30,7 @@ use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
+use jj_lib::git::GitPushNegotiation;
 use jj_lib::git::GitPushStats;
 use jj_lib::op_store::RefTarget;
 use jj_lib::ref_name::RefName;
@@ -191,6 +192,28 @@ pub struct GitPushArgs {
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
+    /// Show details of the push negotiation
+    ///
+    /// Prints the number of objects sent to the remote, the update reported
+    /// by the remote for each reference, and the messages sent by the remote.
+    #[arg(long)]
+    verbose: bool,
+    /// Resolve conflicted bookmarks by taking one side before pushing
+    ///
+    /// With `local`, a conflicted bookmark is set to the target which the
+    /// remote bookmark doesn't point to. With `remote`, it is reset to the
+    /// remote bookmark, so nothing will be pushed for it.
+    #[arg(long, value_name = "SIDE")]
+    resolve_with: Option<BookmarkConflictSide>,
+}
+
+/// Side to take when resolving a conflicted bookmark
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum BookmarkConflictSide {
+    /// The target which was set locally
+    Local,
+    /// The target of the remote bookmark
+    Remote,
 }
 
 fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
@@ -227,11 +250,13 @@ pub fn cmd_git_push(
     let mut tx = workspace_command.start_transaction();
     let view = tx.repo().view();
     let tx_description;
+    let mut conflicted_bookmarks: Vec<RefNameBuf> = vec![];
     let mut bookmark_updates = vec![];
     if args.all {
         for (name, targets) in view.local_remote_bookmarks(remote) {
             let allow_new = true; // implied by --all
             match classify_bookmark_update(
+                &tx,
                 name.to_remote_symbol(remote),
                 targets,
                 allow_new,
@@ -239,9 +264,22 @@ pub fn cmd_git_push(
             ) {
                 Ok(Some(update)) => bookmark_updates.push((name.to_owned(), update)),
                 Ok(None) => {}
+                Err(_) if args.resolve_with.is_some() && targets.local_target.has_conflict() => {
+                    conflicted_bookmarks.push(name.to_owned());
+                }
                 Err(reason) => reason.print(ui)?,
             }
         }
+        if let Some(side) = args.resolve_with {
+            resolve_conflicted_bookmarks(
+                ui,
+                &mut tx,
+                remote,
+                side,
+                &conflicted_bookmarks,
+                &mut bookmark_updates,
+            )?;
+        }
         tx_description = format!(
             "push all bookmarks to git remote {remote}",
             remote = remote.as_symbol()
@@ -253,6 +291,7 @@ pub fn cmd_git_push(
             }
             let allow_new = false; // doesn't matter
             match classify_bookmark_update(
+                &tx,
                 name.to_remote_symbol(remote),
                 targets,
                 allow_new,
@@ -260,9 +299,22 @@ pub fn cmd_git_push(
             ) {
                 Ok(Some(update)) => bookmark_updates.push((name.to_owned(), update)),
                 Ok(None) => {}
+                Err(_) if args.resolve_with.is_some() && targets.local_target.has_conflict() => {
+                    conflicted_bookmarks.push(name.to_owned());
+                }
                 Err(reason) => reason.print(ui)?,
             }
         }
+        if let Some(side) = args.resolve_with {
+            resolve_conflicted_bookmarks(
+                ui,
+                &mut tx,
+                remote,
+                side,
+                &conflicted_bookmarks,
+                &mut bookmark_updates,
+            )?;
+        }
         tx_description = format!(
             "push all tracked bookmarks to git remote {remote}",
             remote = remote.as_symbol()
@@ -275,6 +327,7 @@ pub fn cmd_git_push(
             let allow_new = false; // doesn't matter
             let allow_delete = true;
             match classify_bookmark_update(
+                &tx,
                 name.to_remote_symbol(remote),
                 targets,
                 allow_new,
@@ -318,7 +371,7 @@ pub fn cmd_git_push(
             }
             let allow_new = true; // --change implies creation of remote bookmark
             let allow_delete = false; // doesn't matter
-            match classify_bookmark_update(remote_symbol, targets, allow_new, allow_delete) {
+            match classify_bookmark_update(&tx, remote_symbol, targets, allow_new, allow_delete) {
                 Ok(Some(update)) => bookmark_updates.push((name.to_owned(), update)),
                 Ok(None) => writeln!(
                     ui.status(),
@@ -338,13 +391,16 @@ pub fn cmd_git_push(
             }
             let remote_symbol = name.to_remote_symbol(remote);
             let allow_delete = true; // named explicitly, allow delete without --delete
-            match classify_bookmark_update(remote_symbol, targets, allow_new, allow_delete) {
+            match classify_bookmark_update(&tx, remote_symbol, targets, allow_new, allow_delete) {
                 Ok(Some(update)) => bookmark_updates.push((name.to_owned(), update)),
                 Ok(None) => writeln!(
                     ui.status(),
                     "Bookmark {remote_symbol} already matches {name}",
                     name = name.as_symbol()
                 )?,
+                Err(_) if args.resolve_with.is_some() && targets.local_target.has_conflict() => {
+                    conflicted_bookmarks.push(name.to_owned());
+                }
                 Err(reason) => return Err(reason.into()),
             }
         }
@@ -366,6 +422,7 @@ pub fn cmd_git_push(
             }
             let allow_delete = false;
             match classify_bookmark_update(
+                &tx,
                 name.to_remote_symbol(remote),
                 targets,
                 allow_new,
@@ -373,10 +430,23 @@ pub fn cmd_git_push(
             ) {
                 Ok(Some(update)) => bookmark_updates.push((name.to_owned(), update)),
                 Ok(None) => {}
+                Err(_) if args.resolve_with.is_some() && targets.local_target.has_conflict() => {
+                    conflicted_bookmarks.push(name.to_owned());
+                }
                 Err(reason) => reason.print(ui)?,
             }
         }
 
+        if let Some(side) = args.resolve_with {
+            resolve_conflicted_bookmarks(
+                ui,
+                &mut tx,
+                remote,
+                side,
+                &conflicted_bookmarks,
+                &mut bookmark_updates,
+            )?;
+        }
         tx_description = format!(
             "push {names} to git remote {remote}",
             names = make_bookmark_term(
@@ -390,6 +460,16 @@ pub fn cmd_git_push(
     }
     if bookmark_updates.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
+        if !args.dry_run && !conflicted_bookmarks.is_empty() {
+            let names = conflicted_bookmarks
+                .iter()
+                .map(|name| name.as_symbol())
+                .collect_vec();
+            tx.finish(
+                ui,
+                format!("resolve conflicted {}", make_bookmark_term(&names)),
+            )?;
+        }
         return Ok(());
     }
 
@@ -443,14 +523,82 @@ pub fn cmd_git_push(
         branch_updates: bookmark_updates,
     };
     let git_settings = tx.settings().git_settings()?;
-    let push_stats = with_remote_git_callbacks(ui, |cb| {
+    let mut negotiation = None;
+    let mut negotiation_callback = |n: &GitPushNegotiation| negotiation = Some(n.clone());
+    let push_stats = with_remote_git_callbacks(ui, |mut cb| {
+        if args.verbose {
+            cb.push_negotiation = Some(&mut negotiation_callback);
+        }
         git::push_branches(tx.repo_mut(), &git_settings, remote, &targets, cb)
     })?;
+    if let Some(negotiation) = &negotiation {
+        print_push_negotiation(ui, negotiation)?;
+    }
     process_push_stats(&push_stats)?;
     tx.finish(ui, tx_description)?;
     Ok(())
 }
 
+/// Pushes the named bookmarks to `remote`, allowing new remote bookmarks to be
+/// created. Used by `jj bookmark create/set --push`.
+pub fn push_bookmarks(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    remote: &RemoteName,
+    names: &[RefNameBuf],
+) -> Result<(), CommandError> {
+    let args = GitPushArgs {
+        remote: Some(remote.to_owned()),
+        bookmark: names
+            .iter()
+            .map(|name| StringPattern::exact(name.as_str()))
+            .collect(),
+        all: false,
+        tracked: false,
+        deleted: false,
+        allow_new: true,
+        allow_empty_description: false,
+        allow_private: false,
+        revisions: vec![],
+        change: vec![],
+        named: vec![],
+        dry_run: false,
+        verbose: false,
+        resolve_with: None,
+    };
+    cmd_git_push(ui, command, &args)
+}
+
+fn print_push_negotiation(ui: &Ui, negotiation: &GitPushNegotiation) -> Result<(), CommandError> {
+    let Some(mut formatter) = ui.status_formatter() else {
+        return Ok(());
+    };
+    let transfer = &negotiation.transfer;
+    writeln!(formatter, "Push negotiation:")?;
+    writeln!(
+        formatter,
+        "  Objects: {} counted, {} compressed, {} written",
+        transfer.counted_objects, transfer.compressed_objects, transfer.written_objects
+    )?;
+    writeln!(
+        formatter,
+        "  Deltas: {} sent, {} resolved by the remote",
+        transfer.deltas, transfer.resolved_deltas
+    )?;
+    for (reference, summary) in &negotiation.summaries {
+        write!(formatter, "  ")?;
+        write!(formatter.labeled("git_ref"), "{}", reference.as_symbol())?;
+        writeln!(formatter, ": {summary}")?;
+    }
+    if !negotiation.remote_messages.is_empty() {
+        writeln!(formatter, "  Messages from the remote:")?;
+        for message in &negotiation.remote_messages {
+            writeln!(formatter, "    {message}")?;
+        }
+    }
+    Ok(())
+}
+
 fn process_push_stats(push_stats: &GitPushStats) -> Result<(), CommandError> {
     if !push_stats.all_ok() {
         let mut error = user_error("Failed to push some bookmarks");
@@ -706,7 +854,7 @@ fn print_commits_ready_to_push(
     Ok(())
 }
 
-fn get_default_push_remote(
+pub(super) fn get_default_push_remote(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
 ) -> Result<RemoteNameBuf, CommandError> {
@@ -731,13 +879,13 @@ fn get_default_push_remote(
 #[derive(Clone, Debug)]
 struct RejectedBookmarkUpdateReason {
     message: String,
-    hint: Option<String>,
+    hints: Vec<String>,
 }
 
 impl RejectedBookmarkUpdateReason {
     fn print(&self, ui: &Ui) -> io::Result<()> {
         writeln!(ui.warning_default(), "{}", self.message)?;
-        if let Some(hint) = &self.hint {
+        for hint in &self.hints {
             writeln!(ui.hint_default(), "{hint}")?;
         }
         Ok(())
@@ -746,14 +894,15 @@ impl RejectedBookmarkUpdateReason {
 
 impl From<RejectedBookmarkUpdateReason> for CommandError {
     fn from(reason: RejectedBookmarkUpdateReason) -> Self {
-        let RejectedBookmarkUpdateReason { message, hint } = reason;
+        let RejectedBookmarkUpdateReason { message, hints } = reason;
         let mut cmd_err = user_error(message);
-        cmd_err.extend_hints(hint);
+        cmd_err.extend_hints(hints);
         cmd_err
     }
 }
 
 fn classify_bookmark_update(
+    tx: &WorkspaceCommandTransaction,
     remote_symbol: RemoteRefSymbol<'_>,
     targets: LocalAndRemoteRef,
     allow_new: bool,
@@ -767,29 +916,31 @@ fn classify_bookmark_update(
                 "Bookmark {name} is conflicted",
                 name = remote_symbol.name.as_symbol()
             ),
-            hint: Some(
-                "Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up."
+            hints: vec![
+                describe_conflicted_bookmark(tx, remote_symbol, targets),
+                "Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up. Use \
+                 --resolve-with=local or --resolve-with=remote to resolve it while pushing."
                     .to_owned(),
-            ),
+            ],
         }),
         BookmarkPushAction::RemoteConflicted => Err(RejectedBookmarkUpdateReason {
             message: format!("Bookmark {remote_symbol} is conflicted"),
-            hint: Some("Run `jj git fetch` to update the conflicted remote bookmark.".to_owned()),
+            hints: vec!["Run `jj git fetch` to update the conflicted remote bookmark.".to_owned()],
         }),
         BookmarkPushAction::RemoteUntracked => Err(RejectedBookmarkUpdateReason {
             message: format!("Non-tracking remote bookmark {remote_symbol} exists"),
-            hint: Some(format!(
+            hints: vec![format!(
                 "Run `jj bookmark track {remote_symbol}` to import the remote bookmark."
-            )),
+            )],
         }),
         BookmarkPushAction::Update(update) if update.old_target.is_none() && !allow_new => {
             Err(RejectedBookmarkUpdateReason {
                 message: format!("Refusing to create new remote bookmark {remote_symbol}"),
-                hint: Some(
+                hints: vec![
                     "Use --allow-new to push new bookmark. Use --remote to specify the remote to \
                      push to."
                         .to_owned(),
-                ),
+                ],
             })
         }
         BookmarkPushAction::Update(update) if update.new_target.is_none() && !allow_delete => {
@@ -798,17 +949,130 @@ fn classify_bookmark_update(
                     "Refusing to push deleted bookmark {name}",
                     name = remote_symbol.name.as_symbol(),
                 ),
-                hint: Some(
+                hints: vec![
                     "Push deleted bookmarks with --deleted or forget the bookmark to suppress \
                      this warning."
                         .to_owned(),
-                ),
+                ],
             })
         }
         BookmarkPushAction::Update(update) => Ok(Some(update)),
     }
 }
 
+/// Lists the local and remote targets of the conflicted bookmark.
+fn describe_conflicted_bookmark(
+    tx: &WorkspaceCommandTransaction,
+    remote_symbol: RemoteRefSymbol<'_>,
+    targets: LocalAndRemoteRef,
+) -> String {
+    let format_ids = |target: &RefTarget| {
+        target
+            .added_ids()
+            .map(|id| match tx.repo().store().get_commit(id) {
+                Ok(commit) => format!("\n  {}", tx.format_commit_summary(&commit)),
+                Err(_) => format!("\n  {}", short_commit_hash(id)),
+            })
+            .collect::<String>()
+    };
+    let mut description = format!(
+        "Bookmark {name} points to:{local}",
+        name = remote_symbol.name.as_symbol(),
+        local = format_ids(targets.local_target)
+    );
+    if targets.remote_ref.is_present() {
+        description.push_str(&format!(
+            "\nand {remote_symbol} points to:{remote}",
+            remote = format_ids(&targets.remote_ref.target)
+        ));
+    }
+    description
+}
+
+/// Resolves the conflicted bookmarks by taking one side, and adds the
+/// resulting updates to `bookmark_updates`.
+fn resolve_conflicted_bookmarks(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    remote: &RemoteName,
+    side: BookmarkConflictSide,
+    names: &[RefNameBuf],
+    bookmark_updates: &mut Vec<(RefNameBuf, BookmarkPushUpdate)>,
+) -> Result<(), CommandError> {
+    for name in names {
+        let remote_symbol = name.to_remote_symbol(remote);
+        let view = tx.repo().view();
+        let local_target = view.get_local_bookmark(name);
+        let remote_target = &view.get_remote_bookmark(remote_symbol).target;
+        let new_id = match side {
+            BookmarkConflictSide::Local => {
+                let candidates = local_target
+                    .added_ids()
+                    .filter(|&id| remote_target.added_ids().all(|remote_id| remote_id != id))
+                    .unique()
+                    .collect_vec();
+                match candidates.as_slice() {
+                    [id] => (*id).clone(),
+                    _ => {
+                        return Err(user_error_with_hint(
+                            format!(
+                                "Cannot resolve conflicted bookmark {name} with the local target",
+                                name = name.as_symbol()
+                            ),
+                            format!(
+                                "Bookmark {name} has {n} targets which {remote_symbol} doesn't \
+                                 point to. Use `jj bookmark set` to choose one.",
+                                name = name.as_symbol(),
+                                n = candidates.len()
+                            ),
+                        ));
+                    }
+                }
+            }
+            BookmarkConflictSide::Remote => match remote_target.as_normal() {
+                Some(id) => id.clone(),
+                None => {
+                    return Err(user_error(format!(
+                        "Cannot resolve conflicted bookmark {name} with the remote target since \
+                         {remote_symbol} is absent or conflicted",
+                        name = name.as_symbol()
+                    )));
+                }
+            },
+        };
+        tx.repo_mut()
+            .set_local_bookmark_target(name, RefTarget::normal(new_id.clone()));
+        if let Some(mut formatter) = ui.status_formatter() {
+            let commit = tx.repo().store().get_commit(&new_id)?;
+            write!(
+                formatter,
+                "Resolved conflicted bookmark {name} to ",
+                name = name.as_symbol()
+            )?;
+            tx.write_commit_summary(formatter.as_mut(), &commit)?;
+            writeln!(formatter)?;
+        }
+
+        let view = tx.repo().view();
+        let targets = LocalAndRemoteRef {
+            local_target: view.get_local_bookmark(name),
+            remote_ref: view.get_remote_bookmark(remote_symbol),
+        };
+        let allow_new = true; // the bookmark was selected to be resolved
+        let allow_delete = false; // doesn't matter
+        match classify_bookmark_update(tx, remote_symbol, targets, allow_new, allow_delete) {
+            Ok(Some(update)) => bookmark_updates.push((name.clone(), update)),
+            Ok(None) => writeln!(
+                ui.status(),
+                "Bookmark {remote_symbol} already matches {name}",
+                name = name.as_symbol()
+            )?,
+            Err(reason) => return Err(reason.into()),
+        }
+    }
+    Ok(())
+}
+
 fn ensure_new_bookmark_name(view: &View, name: &RefName) -> Result<(), CommandError> {
     let symbol = name.as_symbol();
     if view.get_local_bookmark(name).is_present() {
@@ -0,0 +1,178 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::config::ConfigFile;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::repo_path::RepoPathBuf;
+
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::commands::sparse::read_sparse_patterns_file;
+use crate::commands::sparse::update_sparse_patterns_with;
+use crate::ui::Ui;
+
+/// Name of the config file in a template directory.
+const CONFIG_FILE_NAME: &str = "config.toml";
+/// Name of the sparse patterns file in a template directory.
+const SPARSE_PATTERNS_FILE_NAME: &str = "sparse-patterns";
+/// Name of the directory of files to copy into the working copy.
+const FILES_DIR_NAME: &str = "files";
+
+/// Settings and files applied to a newly created repo.
+///
+/// A template is either a TOML file, which is used as the repo config, or a
+/// directory which may contain:
+/// * `config.toml`: the repo config
+/// * `sparse-patterns`: sparse patterns, one per line
+/// * `files/`: files to be copied into the working copy
+#[derive(Debug)]
+pub(super) struct RepoTemplate {
+    source: PathBuf,
+    config_path: Option<PathBuf>,
+    sparse_patterns: Option<Vec<RepoPathBuf>>,
+    files_dir: Option<PathBuf>,
+}
+
+impl RepoTemplate {
+    /// Loads and validates the template at `source`.
+    pub fn load(ui: &Ui, cwd: &Path, source: &str) -> Result<Self, CommandError> {
+        if source.contains("://") {
+            return Err(user_error(format!(
+                "Template `{source}` isn't a local path; only local templates are supported"
+            )));
+        }
+        let source = dunce::canonicalize(cwd.join(source)).map_err(|err| {
+            user_error_with_message(format!("Failed to load template {source}"), err)
+        })?;
+        if !source.is_dir() {
+            // Parse the config early to not leave a broken repo behind.
+            ConfigLayer::load_from_file(ConfigSource::Repo, source.clone())?;
+            return Ok(RepoTemplate {
+                config_path: Some(source.clone()),
+                source,
+                sparse_patterns: None,
+                files_dir: None,
+            });
+        }
+
+        let config_path = Some(source.join(CONFIG_FILE_NAME)).filter(|path| path.is_file());
+        if let Some(path) = &config_path {
+            ConfigLayer::load_from_file(ConfigSource::Repo, path.clone())?;
+        }
+        let sparse_patterns_path = source.join(SPARSE_PATTERNS_FILE_NAME);
+        let sparse_patterns = if sparse_patterns_path.is_file() {
+            Some(read_sparse_patterns_file(ui, &sparse_patterns_path)?)
+        } else {
+            None
+        };
+        let files_dir = Some(source.join(FILES_DIR_NAME)).filter(|path| path.is_dir());
+        Ok(RepoTemplate {
+            source,
+            config_path,
+            sparse_patterns,
+            files_dir,
+        })
+    }
+
+    /// Applies all the settings and files of the template to the new repo.
+    pub fn apply(
+        &self,
+        ui: &mut Ui,
+        workspace_command: &mut WorkspaceCommandHelper,
+    ) -> Result<(), CommandError> {
+        self.apply_settings(ui, workspace_command)?;
+        self.copy_files(ui, workspace_command.workspace_root())
+    }
+
+    /// Writes the repo config, and restricts the working copy to the sparse
+    /// patterns of the template.
+    ///
+    /// The template source is recorded as `repo-template.source` in the repo
+    /// config.
+    pub fn apply_settings(
+        &self,
+        ui: &mut Ui,
+        workspace_command: &mut WorkspaceCommandHelper,
+    ) -> Result<(), CommandError> {
+        let config_path = workspace_command.repo_path().join("config.toml");
+        if let Some(path) = &self.config_path {
+            fs::copy(path, &config_path).context(&config_path)?;
+        }
+        let mut file = ConfigFile::load_or_empty(ConfigSource::Repo, config_path)?;
+        file.set_value(
+            ["repo-template", "source"],
+            self.source.to_string_lossy().into_owned(),
+        )
+        .map_err(|err| user_error_with_message("Failed to record template source", err))?;
+        file.save()?;
+
+        if let Some(patterns) = &self.sparse_patterns {
+            update_sparse_patterns_with(ui, workspace_command, |_ui, _old_patterns| {
+                Ok(patterns.clone())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Copies the template files into the working copy. Files that already
+    /// exist in the working copy are left alone.
+    pub fn copy_files(&self, ui: &Ui, workspace_root: &Path) -> Result<(), CommandError> {
+        let Some(files_dir) = &self.files_dir else {
+            return Ok(());
+        };
+        let mut skipped = vec![];
+        copy_dir_contents(files_dir, workspace_root, &mut skipped)?;
+        if !skipped.is_empty() {
+            writeln!(
+                ui.warning_default(),
+                "The following template files weren't copied because they already exist:"
+            )?;
+            let mut formatter = ui.stderr_formatter();
+            for path in &skipped {
+                let path = path.strip_prefix(workspace_root).unwrap_or(path);
+                writeln!(formatter, "  {}", path.display())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn copy_dir_contents(
+    from: &Path,
+    to: &Path,
+    skipped: &mut Vec<PathBuf>,
+) -> Result<(), CommandError> {
+    fs::create_dir_all(to).context(to)?;
+    for entry in fs::read_dir(from).context(from)? {
+        let entry = entry.context(from)?;
+        let target = to.join(entry.file_name());
+        if entry.file_type().context(entry.path())?.is_dir() {
+            copy_dir_contents(&entry.path(), &target, skipped)?;
+        } else if target.exists() {
+            skipped.push(target);
+        } else {
+            fs::copy(entry.path(), &target).context(&target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,230 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use bstr::ByteSlice as _;
+use clap_complete::ArgValueCompleter;
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
+use jj_lib::commit::Commit;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_tree_value;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::matchers::Matcher;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::store::Store;
+use pollster::FutureExt as _;
+use regex::bytes::Regex;
+use regex::bytes::RegexBuilder;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::formatter::Formatter;
+use crate::ui::Ui;
+
+/// Search file contents in revisions
+///
+/// Prints the lines matching the regular expression `PATTERN` in the files of
+/// the given revisions, without checking them out. Conflicted files are
+/// searched with conflict markers included.
+///
+/// When more than one revision is searched, the matches are grouped under a
+/// summary of the revision they were found in.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct GrepArgs {
+    /// Regular expression to search for
+    pattern: String,
+    /// The revision(s) to search in
+    #[arg(
+        long, short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    revisions: Vec<RevisionArg>,
+    /// Only search files matching these filesets
+    #[arg(
+        value_name = "FILESETS",
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+    /// Match case-insensitively
+    #[arg(long, short)]
+    ignore_case: bool,
+    /// Only print the paths of files that contain a match
+    #[arg(long, short = 'l')]
+    files_with_matches: bool,
+}
+
+/// Matching lines of a single file.
+struct FileMatches {
+    path: RepoPathBuf,
+    /// 1-based line numbers and contents of the matching lines.
+    lines: Vec<(usize, Vec<u8>)>,
+    binary: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_grep(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GrepArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let regex = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .map_err(|err| user_error_with_message("Invalid regular expression", err))?;
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let matcher = fileset_expression.to_matcher();
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let show_commit_headers = commits.len() > 1;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    let mut is_first_commit = true;
+    for commit in &commits {
+        let matches = grep_commit(&workspace_command, commit, matcher.as_ref(), &regex)?;
+        if matches.is_empty() {
+            continue;
+        }
+        if show_commit_headers {
+            if !is_first_commit {
+                writeln!(formatter)?;
+            }
+            workspace_command.write_commit_summary(formatter, commit)?;
+            writeln!(formatter)?;
+        }
+        is_first_commit = false;
+        for file_matches in &matches {
+            write_file_matches(
+                formatter,
+                &workspace_command,
+                file_matches,
+                args.files_with_matches,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Searches the files of `commit` matched by `matcher`. The file contents are
+/// read concurrently.
+fn grep_commit(
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+    regex: &Regex,
+) -> Result<Vec<FileMatches>, CommandError> {
+    let store = workspace_command.repo().store();
+    let conflict_marker_style = workspace_command.env().conflict_marker_style();
+    let tree = commit.tree()?;
+    let matches = futures::stream::iter(tree.entries_matching(matcher))
+        .map(async |(path, value)| {
+            let contents = read_file_contents(store, &path, value?, conflict_marker_style).await?;
+            BackendResult::Ok(contents.and_then(|contents| find_matches(path, &contents, regex)))
+        })
+        .buffered(store.concurrency().max(1))
+        .try_filter_map(async |matches| BackendResult::Ok(matches))
+        .try_collect()
+        .block_on()?;
+    Ok(matches)
+}
+
+/// Returns the contents of a file or file conflict, or `None` for other kinds
+/// of entries.
+async fn read_file_contents(
+    store: &Store,
+    path: &RepoPath,
+    value: MergedTreeValue,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> BackendResult<Option<Vec<u8>>> {
+    match materialize_tree_value(store, path, value).await? {
+        MaterializedTreeValue::File(mut file) => Ok(Some(file.read_all(path).await?)),
+        MaterializedTreeValue::FileConflict(file) => Ok(Some(
+            materialize_merge_result_to_bytes(&file.contents, conflict_marker_style).into(),
+        )),
+        _ => Ok(None),
+    }
+}
+
+fn find_matches(path: RepoPathBuf, contents: &[u8], regex: &Regex) -> Option<FileMatches> {
+    // Like Git, treat files containing NUL bytes as binary.
+    if contents.contains(&b'\0') {
+        return regex.is_match(contents).then_some(FileMatches {
+            path,
+            lines: vec![],
+            binary: true,
+        });
+    }
+    let lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(index, line)| (index + 1, line.to_vec()))
+        .collect_vec();
+    (!lines.is_empty()).then_some(FileMatches {
+        path,
+        lines,
+        binary: false,
+    })
+}
+
+fn write_file_matches(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    file_matches: &FileMatches,
+    files_with_matches: bool,
+) -> Result<(), CommandError> {
+    let ui_path = workspace_command.format_file_path(&file_matches.path);
+    if files_with_matches {
+        formatter.with_label("grep", |formatter| {
+            writeln!(formatter.labeled("path"), "{ui_path}")
+        })?;
+        return Ok(());
+    }
+    if file_matches.binary {
+        writeln!(formatter, "Binary file {ui_path} matches")?;
+        return Ok(());
+    }
+    formatter.with_label("grep", |formatter| {
+        for (line_number, line) in &file_matches.lines {
+            write!(formatter.labeled("path"), "{ui_path}")?;
+            write!(formatter, ":")?;
+            write!(formatter.labeled("line_number"), "{line_number}")?;
+            write!(formatter, ":")?;
+            formatter.write_all(line)?;
+            writeln!(formatter)?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -19,11 +19,15 @@ use clap::builder::PossibleValue;
 use clap::builder::StyledStr;
 use crossterm::style::Stylize as _;
 use itertools::Itertools as _;
+use jj_lib::fileset;
+use jj_lib::revset;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateBuildFnTable;
+use crate::operation_templater::OperationTemplateBuildFnTable;
 use crate::ui::Ui;
 
 /// Print this message or the help of the given subcommand(s)
@@ -42,6 +46,25 @@ pub(crate) struct HelpArgs {
             .collect_vec()
     )]
     pub(crate) keyword: Option<String>,
+    /// Show the functions and methods available in the given language
+    ///
+    /// The reference is generated from the functions built into jj. Functions
+    /// defined by aliases aren't included.
+    #[arg(long, value_name = "LANGUAGE", conflicts_with_all = ["command", "keyword"])]
+    pub(crate) reference: Option<ReferenceLanguage>,
+    /// Render the language reference as an HTML page
+    ///
+    /// The page is written to stdout, e.g. `jj help --web >reference.html`. All
+    /// languages are included unless `--reference` is specified.
+    #[arg(long, conflicts_with_all = ["command", "keyword"])]
+    pub(crate) web: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ReferenceLanguage {
+    Filesets,
+    Revsets,
+    Templates,
 }
 
 #[instrument(skip_all)]
@@ -58,6 +81,29 @@ pub(crate) fn cmd_help(
         return Ok(());
     }
 
+    if args.web {
+        let languages = match args.reference {
+            Some(language) => vec![language],
+            None => vec![
+                ReferenceLanguage::Revsets,
+                ReferenceLanguage::Templates,
+                ReferenceLanguage::Filesets,
+            ],
+        };
+        write!(ui.stdout(), "{}", render_reference_html(&languages))?;
+        return Ok(());
+    }
+    if let Some(language) = args.reference {
+        ui.request_pager();
+        write!(ui.stdout(), "{}", render_reference_text(language))?;
+        writeln!(
+            ui.hint_default(),
+            "Use `jj help -k {}` for the full documentation.",
+            language.name()
+        )?;
+        return Ok(());
+    }
+
     let bin_name = command
         .string_args()
         .first()
@@ -78,6 +124,98 @@ pub(crate) fn cmd_help(
     Err(command_error::cli_error(help_err))
 }
 
+impl ReferenceLanguage {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Filesets => "filesets",
+            Self::Revsets => "revsets",
+            Self::Templates => "templates",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Filesets => "Filesets",
+            Self::Revsets => "Revsets",
+            Self::Templates => "Templates",
+        }
+    }
+
+    /// Returns pairs of section title and entries, built from the symbol
+    /// tables of the language.
+    fn sections(self) -> Vec<(String, Vec<String>)> {
+        match self {
+            Self::Filesets => {
+                let names = fileset::builtin_function_names();
+                vec![("Functions".to_owned(), function_entries(&names))]
+            }
+            Self::Revsets => {
+                let names = revset::builtin_function_names();
+                vec![("Functions".to_owned(), function_entries(&names))]
+            }
+            Self::Templates => {
+                let mut names = CommitTemplateBuildFnTable::builtin().symbol_names();
+                let operation_names = OperationTemplateBuildFnTable::builtin().symbol_names();
+                for (type_name, methods) in operation_names.methods {
+                    if names.methods.iter().all(|(name, _)| *name != type_name) {
+                        names.methods.push((type_name, methods));
+                    }
+                }
+                let functions = function_entries(&names.functions);
+                let mut sections = vec![("Functions".to_owned(), functions)];
+                sections.extend(names.methods.into_iter().map(|(type_name, methods)| {
+                    let entries = methods.iter().map(|name| format!(".{name}()")).collect();
+                    (format!("{type_name} methods"), entries)
+                }));
+                sections
+            }
+        }
+    }
+}
+
+fn function_entries(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| format!("{name}()")).collect()
+}
+
+fn render_reference_text(language: ReferenceLanguage) -> String {
+    let mut out = String::new();
+    for (i, (title, entries)) in language.sections().iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        writeln!(out, "{title}:").unwrap();
+        for entry in entries {
+            writeln!(out, "  {entry}").unwrap();
+        }
+    }
+    out
+}
+
+fn render_reference_html(languages: &[ReferenceLanguage]) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>jj language reference</title>\n</head>\n<body>\n");
+    out.push_str("<h1>jj language reference</h1>\n");
+    for language in languages {
+        writeln!(out, "<h2>{}</h2>", language.title()).unwrap();
+        for (title, entries) in language.sections() {
+            writeln!(out, "<h3>{}</h3>\n<ul>", escape_html(&title)).unwrap();
+            for entry in entries {
+                writeln!(out, "<li><code>{}</code></li>", escape_html(&entry)).unwrap();
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 #[derive(Clone)]
 struct Keyword {
     name: &'static str,
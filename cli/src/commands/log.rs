@@ -12,30 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use clap_complete::ArgValueCandidates;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
 use jj_lib::graph::TopoGroupedGraphIterator;
+use jj_lib::matchers::FilesMatcher;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::revset::UserRevsetExpression;
 use tracing::instrument;
 
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::diff_util::get_copy_records;
 use crate::diff_util::DiffFormatArgs;
+use crate::diff_util::MergeDiffMode;
 use crate::graphlog::get_graphlog;
+use crate::graphlog::GraphLayout;
 use crate::graphlog::GraphStyle;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
@@ -82,6 +96,13 @@ pub(crate) struct LogArgs {
         add = ArgValueCompleter::new(complete::log_files),
     )]
     paths: Vec<String>,
+    /// Follow the history of the given file across renames
+    ///
+    /// Requires exactly one path, which is matched as an exact file path.
+    /// When a revision renames or copies the file, its ancestors are matched
+    /// against the original path.
+    #[arg(long, requires = "paths")]
+    follow: bool,
     /// Limit number of revisions to show
     ///
     /// Applied after revisions are filtered and reordered topologically, but
@@ -91,9 +112,30 @@ pub(crate) struct LogArgs {
     /// Show revisions in the opposite order (older revisions first)
     #[arg(long)]
     reversed: bool,
+    /// Hide empty revisions
+    ///
+    /// Empty revisions other than working-copy commits are omitted as if they
+    /// weren't selected by `--revisions`. This is equivalent to intersecting
+    /// the revisions with `~(empty() ~ working_copies())`.
+    #[arg(long)]
+    hide_empty: bool,
     /// Don't show the graph, show a flat list of revisions
     #[arg(long)]
     no_graph: bool,
+    /// Draw the graph as narrow as possible
+    ///
+    /// Lanes are placed in the leftmost free column, and edges may cross other
+    /// lanes to get there. Crossed lanes are drawn on top of the edge.
+    #[arg(long, conflicts_with = "no_graph")]
+    compact_graph: bool,
+    /// Print each revision as a line of JSON, including the graph layout
+    ///
+    /// Each line is an object with the `commit` data, the `edges` to its
+    /// parents within the selected revisions (`direct`, `indirect`, or
+    /// `missing`), and the `column` of the node and `width` of the graph at
+    /// that row, so that the same graph can be drawn by another program.
+    #[arg(long, conflicts_with_all = ["no_graph", "template", "patch"])]
+    json: bool,
     /// Render each revision using the given template
     ///
     /// Run `jj log -T` to list the built-in templates.
@@ -109,11 +151,19 @@ pub(crate) struct LogArgs {
     ///
     /// [`jj help -k templates`]:
     ///     https://jj-vcs.github.io/jj/latest/templates/
-    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::commit_template_expression))]
     template: Option<String>,
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
+    /// How to show the patch of merge commits
+    #[arg(long, value_name = "MODE", value_enum, default_value_t = MergeDiffMode::Merged)]
+    merges: MergeDiffMode,
+    /// Show the patch of merge commits compared to their first parent
+    ///
+    /// This is equivalent to `--merges=first-parent`.
+    #[arg(long, conflicts_with = "merges")]
+    first_parent: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -127,8 +177,11 @@ pub(crate) fn cmd_log(
     let workspace_command = command.workspace_helper(ui)?;
     let settings = workspace_command.settings();
 
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
-    let revset_expression = {
+    if args.follow && args.paths.len() != 1 {
+        return Err(user_error("--follow requires exactly one path"));
+    }
+    let mut fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let mut revset_expression = {
         // only use default revset if neither revset nor path are specified
         let mut expression = if args.revisions.is_empty() && args.paths.is_empty() {
             let revset_string = settings.get_string("revsets.log")?;
@@ -139,7 +192,7 @@ pub(crate) fn cmd_log(
             // a path was specified so we use all() and add path filter later
             workspace_command.attach_revset_evaluator(RevsetExpression::all())
         };
-        if !args.paths.is_empty() {
+        if !args.paths.is_empty() && !args.follow {
             // Beware that args.paths = ["root:."] is not identical to []. The
             // former will filter out empty commits.
             let predicate = RevsetFilterPredicate::File(fileset_expression.clone());
@@ -147,6 +200,23 @@ pub(crate) fn cmd_log(
         }
         expression
     };
+    if args.follow {
+        let path = workspace_command.parse_file_path(&args.paths[0])?;
+        let (commit_ids, paths) =
+            collect_file_history(&workspace_command, revset_expression.expression(), path)?;
+        fileset_expression = FilesetExpression::union_all(
+            paths
+                .into_iter()
+                .map(FilesetExpression::file_path)
+                .collect(),
+        );
+        revset_expression =
+            workspace_command.attach_revset_evaluator(RevsetExpression::commits(commit_ids));
+    }
+    if args.hide_empty {
+        let hidden = RevsetExpression::is_empty().minus(&RevsetExpression::working_copies());
+        revset_expression.intersect_with(&hidden.negated());
+    }
     let prio_revset = settings.get_string("revsets.log-graph-prioritize")?;
     let prio_revset = workspace_command.parse_revset(ui, &RevisionArg::from(prio_revset))?;
 
@@ -156,6 +226,11 @@ pub(crate) fn cmd_log(
 
     let store = repo.store();
     let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
+    let merge_mode = if args.first_parent {
+        MergeDiffMode::FirstParent
+    } else {
+        args.merges
+    };
     let graph_style = GraphStyle::from_settings(settings)?;
 
     let use_elided_nodes = settings.get_bool("ui.log-synthetic-elided-nodes")?;
@@ -182,9 +257,7 @@ pub(crate) fn cmd_log(
         let mut formatter = ui.stdout_formatter();
         let formatter = formatter.as_mut();
 
-        if !args.no_graph {
-            let mut raw_output = formatter.raw()?;
-            let mut graph = get_graphlog(graph_style, raw_output.as_mut());
+        if args.json || !args.no_graph {
             let iter: Box<dyn Iterator<Item = _>> = {
                 let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph(), |id| id);
 
@@ -206,83 +279,104 @@ pub(crate) fn cmd_log(
                     Box::new(forward_iter)
                 }
             };
-            for node in iter {
-                let (commit_id, edges) = node?;
+            if args.json {
+                let mut layout = GraphLayout::new();
+                for node in iter {
+                    let (commit_id, edges) = node?;
+                    let commit = store.get_commit(&commit_id)?;
+                    let row = layout.next_row(&commit_id, &edges);
+                    let entry = JsonLogEntry {
+                        commit: &commit,
+                        edges: edges.iter().map(JsonGraphEdge::new).collect(),
+                        column: row.column,
+                        width: row.width,
+                    };
+                    let line = serde_json::to_string(&entry).map_err(internal_error)?;
+                    writeln!(formatter, "{line}")?;
+                }
+            } else {
+                let mut raw_output = formatter.raw()?;
+                let mut graph = get_graphlog(graph_style, args.compact_graph, raw_output.as_mut());
+                for node in iter {
+                    let (commit_id, edges) = node?;
 
-                // The graph is keyed by (CommitId, is_synthetic)
-                let mut graphlog_edges = vec![];
-                // TODO: Should we update revset.iter_graph() to yield a `has_missing` flag
-                // instead of all the missing edges since we don't care about
-                // where they point here anyway?
-                let mut missing_edge_id = None;
-                let mut elided_targets = vec![];
-                for edge in edges {
-                    match edge.edge_type {
-                        GraphEdgeType::Missing => {
-                            missing_edge_id = Some(edge.target);
-                        }
-                        GraphEdgeType::Direct => {
-                            graphlog_edges.push(GraphEdge::direct((edge.target, false)));
-                        }
-                        GraphEdgeType::Indirect => {
-                            if use_elided_nodes {
-                                elided_targets.push(edge.target.clone());
-                                graphlog_edges.push(GraphEdge::direct((edge.target, true)));
-                            } else {
-                                graphlog_edges.push(GraphEdge::indirect((edge.target, false)));
+                    // The graph is keyed by (CommitId, is_synthetic)
+                    let mut graphlog_edges = vec![];
+                    // TODO: Should we update revset.iter_graph() to yield a `has_missing` flag
+                    // instead of all the missing edges since we don't care about
+                    // where they point here anyway?
+                    let mut missing_edge_id = None;
+                    let mut elided_targets = vec![];
+                    for edge in edges {
+                        match edge.edge_type {
+                            GraphEdgeType::Missing => {
+                                missing_edge_id = Some(edge.target);
+                            }
+                            GraphEdgeType::Direct => {
+                                graphlog_edges.push(GraphEdge::direct((edge.target, false)));
+                            }
+                            GraphEdgeType::Indirect => {
+                                if use_elided_nodes {
+                                    elided_targets.push(edge.target.clone());
+                                    graphlog_edges.push(GraphEdge::direct((edge.target, true)));
+                                } else {
+                                    graphlog_edges.push(GraphEdge::indirect((edge.target, false)));
+                                }
                             }
                         }
                     }
-                }
-                if let Some(missing_edge_id) = missing_edge_id {
-                    graphlog_edges.push(GraphEdge::missing((missing_edge_id, false)));
-                }
-                let mut buffer = vec![];
-                let key = (commit_id, false);
-                let commit = store.get_commit(&key.0)?;
-                let within_graph =
-                    with_content_format.sub_width(graph.width(&key, &graphlog_edges));
-                within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                    template.format(&commit, formatter)
-                })?;
-                if !buffer.ends_with(b"\n") {
-                    buffer.push(b'\n');
-                }
-                if let Some(renderer) = &diff_renderer {
-                    let mut formatter = ui.new_formatter(&mut buffer);
-                    renderer.show_patch(
-                        ui,
-                        formatter.as_mut(),
-                        &commit,
-                        matcher.as_ref(),
-                        within_graph.width(),
-                    )?;
-                }
-
-                let node_symbol = format_template(ui, &Some(commit), &node_template);
-                graph.add_node(
-                    &key,
-                    &graphlog_edges,
-                    &node_symbol,
-                    &String::from_utf8_lossy(&buffer),
-                )?;
-                for elided_target in elided_targets {
-                    let elided_key = (elided_target, true);
-                    let real_key = (elided_key.0.clone(), false);
-                    let edges = [GraphEdge::direct(real_key)];
+                    if let Some(missing_edge_id) = missing_edge_id {
+                        graphlog_edges.push(GraphEdge::missing((missing_edge_id, false)));
+                    }
                     let mut buffer = vec![];
+                    let key = (commit_id, false);
+                    let commit = store.get_commit(&key.0)?;
                     let within_graph =
-                        with_content_format.sub_width(graph.width(&elided_key, &edges));
+                        with_content_format.sub_width(graph.width(&key, &graphlog_edges));
                     within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                        writeln!(formatter.labeled("elided"), "(elided revisions)")
+                        template.format(&commit, formatter)
                     })?;
-                    let node_symbol = format_template(ui, &None, &node_template);
+                    if !buffer.ends_with(b"\n") {
+                        buffer.push(b'\n');
+                    }
+                    if let Some(renderer) = &diff_renderer {
+                        let mut formatter = ui.new_formatter(&mut buffer);
+                        renderer.show_patch_with_merge_mode(
+                            ui,
+                            formatter.as_mut(),
+                            &commit,
+                            matcher.as_ref(),
+                            within_graph.width(),
+                            merge_mode,
+                        )?;
+                    }
+
+                    let node_symbol = format_template(ui, &Some(commit), &node_template);
                     graph.add_node(
-                        &elided_key,
-                        &edges,
+                        &key,
+                        &graphlog_edges,
                         &node_symbol,
                         &String::from_utf8_lossy(&buffer),
                     )?;
+                    for elided_target in elided_targets {
+                        let elided_key = (elided_target, true);
+                        let real_key = (elided_key.0.clone(), false);
+                        let edges = [GraphEdge::direct(real_key)];
+                        let mut buffer = vec![];
+                        let within_graph =
+                            with_content_format.sub_width(graph.width(&elided_key, &edges));
+                        within_graph
+                            .write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
+                                writeln!(formatter.labeled("elided"), "(elided revisions)")
+                            })?;
+                        let node_symbol = format_template(ui, &None, &node_template);
+                        graph.add_node(
+                            &elided_key,
+                            &edges,
+                            &node_symbol,
+                            &String::from_utf8_lossy(&buffer),
+                        )?;
+                    }
                 }
             }
         } else {
@@ -301,7 +395,14 @@ pub(crate) fn cmd_log(
                     .write(formatter, |formatter| template.format(&commit, formatter))?;
                 if let Some(renderer) = &diff_renderer {
                     let width = ui.term_width();
-                    renderer.show_patch(ui, formatter, &commit, matcher.as_ref(), width)?;
+                    renderer.show_patch_with_merge_mode(
+                        ui,
+                        formatter,
+                        &commit,
+                        matcher.as_ref(),
+                        width,
+                        merge_mode,
+                    )?;
                 }
             }
         }
@@ -333,3 +434,106 @@ pub(crate) fn cmd_log(
 
     Ok(())
 }
+
+/// A line of `jj log --json` output.
+#[derive(serde::Serialize)]
+struct JsonLogEntry<'a> {
+    commit: &'a Commit,
+    /// Edges to the parents within the evaluated revisions.
+    edges: Vec<JsonGraphEdge<'a>>,
+    /// Column of the node in the graph.
+    column: usize,
+    /// Number of columns of the graph at the node row.
+    width: usize,
+}
+
+#[derive(serde::Serialize)]
+struct JsonGraphEdge<'a> {
+    target: &'a CommitId,
+    #[serde(rename = "type")]
+    edge_type: &'static str,
+}
+
+impl<'a> JsonGraphEdge<'a> {
+    fn new(edge: &'a GraphEdge<CommitId>) -> Self {
+        let edge_type = match edge.edge_type {
+            GraphEdgeType::Direct => "direct",
+            GraphEdgeType::Indirect => "indirect",
+            GraphEdgeType::Missing => "missing",
+        };
+        JsonGraphEdge {
+            target: &edge.target,
+            edge_type,
+        }
+    }
+}
+
+/// Walks `expression` collecting commits that modify the file at `path`. If
+/// the file was renamed or copied, the ancestors of the renaming commit are
+/// matched against the source path, which is tracked separately for each line
+/// of history. Returns the matching commits and all paths of the file.
+fn collect_file_history(
+    workspace_command: &WorkspaceCommandHelper,
+    expression: &Rc<UserRevsetExpression>,
+    path: RepoPathBuf,
+) -> Result<(Vec<CommitId>, Vec<RepoPathBuf>), CommandError> {
+    let repo = workspace_command.repo().as_ref();
+    let store = repo.store();
+    let revset = workspace_command
+        .attach_revset_evaluator(expression.clone())
+        .evaluate()?;
+    // Paths of the file as seen from the descendants of each commit. Since
+    // descendants are visited first, the paths are complete by the time the
+    // commit is visited. Rewritten or divergent commits of the same change
+    // that aren't reachable from a visited descendant inherit the paths
+    // previously seen for their change.
+    let mut commit_paths: HashMap<CommitId, HashSet<RepoPathBuf>> = HashMap::new();
+    let mut change_paths: HashMap<ChangeId, HashSet<RepoPathBuf>> = HashMap::new();
+    let mut commit_ids = vec![];
+    let mut all_paths = vec![path.clone()];
+    for commit in revset.iter().commits(store) {
+        let commit = commit?;
+        let paths = commit_paths
+            .remove(commit.id())
+            .or_else(|| change_paths.get(commit.change_id()).cloned())
+            .unwrap_or_else(|| HashSet::from([path.clone()]));
+        change_paths
+            .entry(commit.change_id().clone())
+            .or_default()
+            .extend(paths.iter().cloned());
+
+        let tree = commit.tree()?;
+        let parent_tree = commit.parent_tree(repo)?;
+        let mut touched_paths = vec![];
+        for path in &paths {
+            if tree.path_value(path)? != parent_tree.path_value(path)? {
+                touched_paths.push(path);
+            }
+        }
+        if !touched_paths.is_empty() {
+            commit_ids.push(commit.id().clone());
+        }
+
+        // Only a commit touching the file can rename it.
+        let matcher = FilesMatcher::new(&touched_paths);
+        for parent_id in commit.parent_ids() {
+            let mut parent_paths = paths.clone();
+            if !touched_paths.is_empty() {
+                for record in get_copy_records(store, parent_id, commit.id(), &matcher)? {
+                    let record = record?;
+                    if record.source != record.target && parent_paths.remove(&record.target) {
+                        if !all_paths.contains(&record.source) {
+                            all_paths.push(record.source.clone());
+                        }
+                        parent_paths.insert(record.source);
+                    }
+                }
+            }
+            commit_paths
+                .entry(parent_id.clone())
+                .or_default()
+                .extend(parent_paths);
+        }
+    }
+    Ok((commit_ids, all_paths))
+}
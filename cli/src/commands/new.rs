@@ -36,7 +36,10 @@ use crate::ui::Ui;
 /// Create a new, empty change and (by default) edit it in the working copy
 ///
 /// By default, `jj` will edit the new change, making the [working copy]
-/// represent the new commit. This can be avoided with `--no-edit`.
+/// represent the new commit. This can be avoided with `--no-edit`. With
+/// `--detached`, the working copy is left alone entirely: it is neither
+/// snapshotted nor updated, which is useful for scripting on a busy working
+/// copy.
 ///
 /// Note that you can create a merge commit by specifying multiple revisions as
 /// argument. For example, `jj new @ main` will create a new commit with the
@@ -66,6 +69,14 @@ pub(crate) struct NewArgs {
     /// No-op flag to pair with --no-edit
     #[arg(long, hide = true)]
     _edit: bool,
+    /// Create the new change without touching the working copy
+    ///
+    /// The working copy is not snapshotted before the new change is created.
+    /// The command fails if it would rewrite the working-copy commit.
+    ///
+    /// Implies `--no-edit`.
+    #[arg(long, conflicts_with = "_edit")]
+    detached: bool,
     /// Insert the new change after the given commit(s)
     ///
     /// Example: `jj new --after A` creates a new change between `A` and its
@@ -149,7 +160,11 @@ pub(crate) fn cmd_new(
     command: &CommandHelper,
     args: &NewArgs,
 ) -> Result<(), CommandError> {
-    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut workspace_command = if args.detached {
+        command.workspace_helper_detached(ui)?
+    } else {
+        command.workspace_helper(ui)?
+    };
 
     let (parent_commit_ids, child_commit_ids) = compute_commit_location(
         ui,
@@ -220,7 +235,7 @@ pub(crate) fn cmd_new(
     }
     num_rebased += tx.repo_mut().rebase_descendants()?;
 
-    if args.no_edit {
+    if args.no_edit || args.detached {
         if let Some(mut formatter) = ui.status_formatter() {
             write!(formatter, "Created new commit ")?;
             tx.write_commit_summary(formatter.as_mut(), &new_commit)?;
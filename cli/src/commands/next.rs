@@ -66,11 +66,17 @@ pub(crate) struct NextArgs {
     ///
     /// Takes precedence over config in `ui.movement.edit`; i.e.
     /// will negate `ui.movement.edit = true`
-    #[arg(long, short, conflicts_with = "edit")]
+    #[arg(long, short, visible_alias = "new", conflicts_with = "edit")]
     no_edit: bool,
     /// Jump to the next conflicted descendant
     #[arg(long, conflicts_with = "offset")]
     conflict: bool,
+    /// Skip over empty revisions
+    ///
+    /// Keeps moving in the same direction until a revision with changes is
+    /// found.
+    #[arg(long)]
+    skip_empty: bool,
 }
 
 impl From<&NextArgs> for MovementArgs {
@@ -80,6 +86,7 @@ impl From<&NextArgs> for MovementArgs {
             edit: val.edit,
             no_edit: val.no_edit,
             conflict: val.conflict,
+            skip_empty: val.skip_empty,
         }
     }
 }
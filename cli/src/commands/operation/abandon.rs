@@ -65,30 +65,20 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
-    let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
-            let root_op = if root_op_str.is_empty() {
-                repo_loader.root_operation()
-            } else {
-                resolve_op(root_op_str)?
-            };
-            let head_ops = if head_op_str.is_empty() {
-                current_head_ops.clone()
-            } else {
-                vec![resolve_op(head_op_str)?]
-            };
-            (root_op, head_ops)
-        } else {
-            let op = resolve_op(&args.operation)?;
-            let parent_ops: Vec<_> = op.parents().try_collect()?;
-            let parent_op = match parent_ops.len() {
-                0 => return Err(user_error("Cannot abandon the root operation")),
-                1 => parent_ops.into_iter().next().unwrap(),
-                _ => return Err(user_error("Cannot abandon a merge operation")),
-            };
-            (parent_op, vec![op])
+    let (abandon_root_op, abandon_head_ops) = if let Some(range) =
+        op_walk::resolve_op_range_at(repo_loader, &current_head_ops, &args.operation)?
+    {
+        (range.root_op, range.head_ops)
+    } else {
+        let op = op_walk::resolve_op_at(op_store, &current_head_ops, &args.operation)?;
+        let parent_ops: Vec<_> = op.parents().try_collect()?;
+        let parent_op = match parent_ops.len() {
+            0 => return Err(user_error("Cannot abandon the root operation")),
+            1 => parent_ops.into_iter().next().unwrap(),
+            _ => return Err(user_error("Cannot abandon a merge operation")),
         };
+        (parent_op, vec![op])
+    };
 
     if let Some(op) = abandon_head_ops
         .iter()
@@ -38,6 +38,7 @@ use jj_lib::revset::RevsetExpression;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::diff_formats_for_log;
@@ -53,6 +54,9 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationDiffArgs {
     /// Show repository changes in this operation, compared to its parent
+    ///
+    /// If a range `ROOT..HEAD` is given, show repository changes from `ROOT`
+    /// to `HEAD` instead.
     #[arg(
         long,
         visible_alias = "op",
@@ -96,11 +100,25 @@ pub fn cmd_op_diff(
     let workspace_env = workspace_command.env();
     let repo_loader = workspace_command.workspace().repo_loader();
     let settings = workspace_command.settings();
+    let current_op = workspace_command.repo().operation();
+    let op_range = match &args.operation {
+        Some(op_str) => {
+            op_walk::resolve_op_range_at(repo_loader, slice::from_ref(current_op), op_str)?
+        }
+        None => None,
+    };
     let from_ops;
     let to_op;
     if args.from.is_some() || args.to.is_some() {
         from_ops = vec![workspace_command.resolve_single_op(args.from.as_deref().unwrap_or("@"))?];
         to_op = workspace_command.resolve_single_op(args.to.as_deref().unwrap_or("@"))?;
+    } else if let Some(range) = op_range {
+        from_ops = vec![range.root_op];
+        to_op = range
+            .head_ops
+            .into_iter()
+            .exactly_one()
+            .map_err(|_| user_error("Operation range must have a single head"))?;
     } else {
         to_op = workspace_command.resolve_single_op(args.operation.as_deref().unwrap_or("@"))?;
         from_ops = to_op.parents().try_collect()?;
@@ -123,8 +141,10 @@ pub fn cmd_op_diff(
         let formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
         let path_converter = workspace_env.path_converter();
         let conflict_marker_style = workspace_env.conflict_marker_style();
-        (!formats.is_empty())
-            .then(|| DiffRenderer::new(merged_repo, path_converter, conflict_marker_style, formats))
+        (!formats.is_empty()).then(|| {
+            DiffRenderer::new(merged_repo, path_converter, conflict_marker_style, formats)
+                .with_attribute_overrides(workspace_env.attribute_overrides())
+        })
     };
     let id_prefix_context = workspace_env.new_id_prefix_context();
     let commit_summary_template = {
@@ -189,7 +209,7 @@ pub fn show_op_diff(
         })?;
         if let Some(graph_style) = graph_style {
             let mut raw_output = formatter.raw()?;
-            let mut graph = get_graphlog(graph_style, raw_output.as_mut());
+            let mut graph = get_graphlog(graph_style, false, raw_output.as_mut());
             let graph_iter = TopoGroupedGraphIterator::new(revset.iter_graph(), |id| id);
             for node in graph_iter {
                 let (commit_id, mut edges) = node?;
@@ -310,7 +330,8 @@ pub fn show_op_diff(
                     from_target,
                     false,
                     None,
-                )
+                )?;
+                write_bookmark_move_summary(formatter, current_repo, from_target, to_target)
             })?;
         }
     }
@@ -379,6 +400,12 @@ pub fn show_op_diff(
                     &from_ref.target,
                     false,
                     Some(get_remote_ref_prefix(from_ref)),
+                )?;
+                write_bookmark_move_summary(
+                    formatter,
+                    current_repo,
+                    &from_ref.target,
+                    &to_ref.target,
                 )
             })?;
         }
@@ -464,6 +491,48 @@ fn write_ref_target_summary(
     Ok(())
 }
 
+/// Writes the direction and distance of a bookmark move if the bookmark
+/// pointed to a single commit both before and after the operation.
+///
+/// A move is "forward" if the new target is a descendant of the old target,
+/// "backward" if it is an ancestor, and "sideways" otherwise.
+fn write_bookmark_move_summary(
+    formatter: &mut dyn Formatter,
+    repo: &dyn Repo,
+    from_target: &RefTarget,
+    to_target: &RefTarget,
+) -> Result<(), CommandError> {
+    let (Some(from_id), Some(to_id)) = (from_target.as_normal(), to_target.as_normal()) else {
+        return Ok(());
+    };
+    if from_id == to_id {
+        return Ok(());
+    }
+    let from_expr = RevsetExpression::commit(from_id.clone());
+    let to_expr = RevsetExpression::commit(to_id.clone());
+    let ahead = from_expr
+        .range(&to_expr)
+        .evaluate(repo)?
+        .iter()
+        .process_results(|iter| iter.count())?;
+    let behind = to_expr
+        .range(&from_expr)
+        .evaluate(repo)?
+        .iter()
+        .process_results(|iter| iter.count())?;
+    if behind == 0 {
+        writeln!(formatter, "(moved forward by {ahead} commits)")?;
+    } else if ahead == 0 {
+        writeln!(formatter, "(moved backward by {behind} commits)")?;
+    } else {
+        writeln!(
+            formatter,
+            "(moved sideways: {ahead} commits ahead, {behind} commits behind)"
+        )?;
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum ModifiedChange {
     /// Created or rewritten commit.
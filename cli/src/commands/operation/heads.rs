@@ -0,0 +1,87 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::operation_templater::OperationTemplateLanguage;
+use crate::templater::TemplateRenderer;
+use crate::ui::Ui;
+
+/// List the current operation heads
+///
+/// There is normally a single operation head. Concurrent commands can leave
+/// multiple heads behind, which are merged when the repo is loaded next time
+/// according to the `operation.merge-heads` setting. Unlike other commands,
+/// `jj op heads` doesn't merge the heads, so they can be inspected before
+/// they are merged.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationHeadsArgs {
+    /// Render each operation using the given template
+    ///
+    /// You can specify arbitrary template expressions using the
+    /// [built-in keywords]. See [`jj help -k templates`] for more
+    /// information.
+    ///
+    /// [built-in keywords]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#operation-keywords
+    ///
+    /// [`jj help -k templates`]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::operation_template_expression))]
+    template: Option<String>,
+}
+
+pub fn cmd_op_heads(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationHeadsArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo, which would merge the operation heads.
+    let workspace = command.load_workspace()?;
+    let workspace_env = command.workspace_environment(ui, &workspace)?;
+    let repo_loader = workspace.repo_loader();
+    let settings = repo_loader.settings();
+    let head_ops = op_walk::get_current_head_ops(
+        repo_loader.op_store(),
+        repo_loader.op_heads_store().as_ref(),
+    )?;
+
+    let template: TemplateRenderer<Operation> = {
+        let language = OperationTemplateLanguage::new(
+            repo_loader,
+            None,
+            workspace_env.operation_template_extensions(),
+        );
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => settings.get_string("templates.op_log")?,
+        };
+        workspace_env
+            .parse_template(ui, &language, &text)?
+            .labeled(["op_heads", "operation"])
+    };
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    // Show the most recent operation first, as in `jj op log`.
+    for op in head_ops.iter().rev() {
+        template.format(op, formatter.as_mut())?;
+    }
+    Ok(())
+}
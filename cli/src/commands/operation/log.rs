@@ -13,21 +13,31 @@
 // limitations under the License.
 
 use std::slice;
+use std::sync::Arc;
 
 use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use futures::StreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::fileset;
+use jj_lib::fileset::FilesetDiagnostics;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
-use jj_lib::op_store::OpStoreError;
+use jj_lib::matchers::Matcher;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
+use jj_lib::store::Store;
+use pollster::FutureExt as _;
 
 use super::diff::show_op_diff;
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::WorkspaceCommandEnvironment;
+use crate::command_error::print_parse_diagnostics;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::diff_formats_for_log;
@@ -59,6 +69,12 @@ pub struct OperationLogArgs {
     /// Don't show the graph, show a flat list of operations
     #[arg(long)]
     no_graph: bool,
+    /// Draw the graph as narrow as possible
+    ///
+    /// Lanes are placed in the leftmost free column, and edges may cross other
+    /// lanes to get there. Crossed lanes are drawn on top of the edge.
+    #[arg(long, conflicts_with = "no_graph")]
+    compact_graph: bool,
     /// Render each operation using the given template
     ///
     /// You can specify arbitrary template expressions using the
@@ -70,7 +86,7 @@ pub struct OperationLogArgs {
     ///
     /// [`jj help -k templates`]:
     ///     https://jj-vcs.github.io/jj/latest/templates/
-    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::operation_template_expression))]
     template: Option<String>,
     /// Show changes to the repository at each operation
     #[arg(long, short = 'd')]
@@ -82,6 +98,32 @@ pub struct OperationLogArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Show only operations that changed the given paths
+    ///
+    /// An operation is shown if the tree of any working-copy commit differs
+    /// from the one recorded in the parent operation in the given paths. This
+    /// includes snapshots of the working copy as well as rewrites that changed
+    /// the working-copy contents.
+    #[arg(long, value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    ops_affecting: Vec<String>,
+    /// Show only operations in the given range
+    ///
+    /// The range is specified as `ROOT..HEAD`, which selects operations that
+    /// are ancestors of `HEAD` but not ancestors of `ROOT`. If `ROOT` is
+    /// omitted, it defaults to the root operation. If `HEAD` is omitted, it
+    /// defaults to the current operation.
+    #[arg(long, value_name = "RANGE", add = ArgValueCandidates::new(complete::operations))]
+    between: Option<String>,
+    /// Hide operations that only snapshotted the working copy
+    ///
+    /// An operation is considered a snapshot if it was recorded by the
+    /// automatic working-copy snapshot and didn't change anything in the
+    /// view other than the working-copy commits and heads.
+    #[arg(long, conflicts_with = "only_snapshots")]
+    no_snapshot_ops: bool,
+    /// Show only operations that only snapshotted the working copy
+    #[arg(long)]
+    only_snapshots: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -172,6 +214,7 @@ fn do_op_log(
                     conflict_marker_style,
                     diff_formats.clone(),
                 )
+                .with_attribute_overrides(workspace_env.attribute_overrides())
             });
 
             // TODO: Merged repo may have newly rebased commits, which wouldn't
@@ -196,21 +239,81 @@ fn do_op_log(
         None
     };
 
+    let paths_matcher = if args.ops_affecting.is_empty() {
+        None
+    } else {
+        let mut diagnostics = FilesetDiagnostics::new();
+        let expressions: Vec<_> = args
+            .ops_affecting
+            .iter()
+            .map(|arg| {
+                fileset::parse_maybe_bare(&mut diagnostics, arg, workspace_env.path_converter())
+            })
+            .try_collect()?;
+        print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
+        Some(FilesetExpression::union_all(expressions).to_matcher())
+    };
+
+    let ops_iter: Box<dyn Iterator<Item = _>> = if let Some(range_str) = &args.between {
+        let range =
+            op_walk::resolve_op_range_at(repo_loader, slice::from_ref(current_op), range_str)?
+                .ok_or_else(|| {
+                    user_error(format!(
+                        "Invalid operation range `{range_str}`; expected `ROOT..HEAD`"
+                    ))
+                })?;
+        Box::new(range.walk())
+    } else {
+        Box::new(op_walk::walk_ancestors(slice::from_ref(current_op)))
+    };
+
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    let iter =
-        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
+    let iter = ops_iter
+        .map(|op| -> Result<_, CommandError> {
+            let op = op?;
+            if args.no_snapshot_ops || args.only_snapshots {
+                if is_snapshot_only_op(&op)? != args.only_snapshots {
+                    return Ok(None);
+                }
+            }
+            match &paths_matcher {
+                Some(matcher) if !op_affects_paths(repo_loader.store(), &op, matcher.as_ref())? => {
+                    Ok(None)
+                }
+                _ => Ok(Some(op)),
+            }
+        })
+        .filter_map(Result::transpose)
+        .take(args.limit.unwrap_or(usize::MAX));
 
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
-        let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let iter = iter.map(|op| -> Result<_, OpStoreError> {
-            let op = op?;
-            let ids = op.parent_ids();
-            let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
-            Ok((op, edges))
-        });
+        let mut graph = get_graphlog(graph_style, args.compact_graph, raw_output.as_mut());
+        let is_filtered = paths_matcher.is_some() || args.no_snapshot_ops || args.only_snapshots;
+        let iter: Box<dyn Iterator<Item = Result<_, CommandError>>> = if is_filtered {
+            // Operations in between are hidden, so connect each operation to
+            // the next one shown.
+            let ops: Vec<Operation> = iter.try_collect()?;
+            let next_ids = ops
+                .iter()
+                .skip(1)
+                .map(|op| Some(op.id().clone()))
+                .chain([None])
+                .collect_vec();
+            Box::new(ops.into_iter().zip(next_ids).map(|(op, next_id)| {
+                let edges = next_id.into_iter().map(GraphEdge::indirect).collect();
+                Ok((op, edges))
+            }))
+        } else {
+            Box::new(iter.map(|op| {
+                let op = op?;
+                let ids = op.parent_ids();
+                let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
+                Ok((op, edges))
+            }))
+        };
         let iter_nodes: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(reverse_graph(iter, Operation::id)?.into_iter().map(Ok))
         } else {
@@ -255,3 +358,63 @@ fn do_op_log(
 
     Ok(())
 }
+
+/// Returns true if `op` is an automatic working-copy snapshot which changed
+/// nothing but the working-copy commits and heads.
+fn is_snapshot_only_op(op: &Operation) -> Result<bool, CommandError> {
+    if !op.metadata().is_snapshot || op.parent_ids().len() != 1 {
+        return Ok(false);
+    }
+    let parent_op = op.parents().next().unwrap()?;
+    if parent_op.view_id() == op.view_id() {
+        return Ok(true);
+    }
+    let view = op.view()?;
+    let parent_view = parent_op.view()?;
+    let view = view.store_view();
+    let parent_view = parent_view.store_view();
+    Ok(view.local_bookmarks == parent_view.local_bookmarks
+        && view.tags == parent_view.tags
+        && view.remote_views == parent_view.remote_views
+        && view.git_refs == parent_view.git_refs
+        && view.git_head == parent_view.git_head
+        && view
+            .wc_commit_ids
+            .keys()
+            .eq(parent_view.wc_commit_ids.keys()))
+}
+
+/// Returns true if any working-copy commit tree at `op` differs from the one at
+/// a parent operation in paths matched by `matcher`.
+fn op_affects_paths(
+    store: &Arc<Store>,
+    op: &Operation,
+    matcher: &dyn Matcher,
+) -> Result<bool, CommandError> {
+    let view = op.view()?;
+    for parent_op in op.parents() {
+        let parent_view = parent_op?.view()?;
+        for (name, commit_id) in view.wc_commit_ids() {
+            let Some(parent_commit_id) = parent_view.wc_commit_ids().get(name) else {
+                continue;
+            };
+            if commit_id == parent_commit_id {
+                continue;
+            }
+            let tree = store.get_commit(commit_id)?.tree()?;
+            let parent_tree = store.get_commit(parent_commit_id)?.tree()?;
+            if tree.id() == parent_tree.id() {
+                continue;
+            }
+            if parent_tree
+                .diff_stream(&tree, matcher)
+                .next()
+                .block_on()
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
@@ -14,9 +14,11 @@
 
 mod abandon;
 mod diff;
+mod heads;
 mod log;
 mod restore;
 mod show;
+mod squash;
 pub mod undo;
 
 use abandon::cmd_op_abandon;
@@ -24,12 +26,16 @@ use abandon::OperationAbandonArgs;
 use clap::Subcommand;
 use diff::cmd_op_diff;
 use diff::OperationDiffArgs;
+use heads::cmd_op_heads;
+use heads::OperationHeadsArgs;
 use log::cmd_op_log;
 use log::OperationLogArgs;
 use restore::cmd_op_restore;
 use restore::OperationRestoreArgs;
 use show::cmd_op_show;
 use show::OperationShowArgs;
+use squash::cmd_op_squash;
+use squash::OperationSquashArgs;
 use undo::cmd_op_undo;
 use undo::OperationUndoArgs;
 
@@ -47,9 +53,11 @@ use crate::ui::Ui;
 pub enum OperationCommand {
     Abandon(OperationAbandonArgs),
     Diff(OperationDiffArgs),
+    Heads(OperationHeadsArgs),
     Log(OperationLogArgs),
     Restore(OperationRestoreArgs),
     Show(OperationShowArgs),
+    Squash(OperationSquashArgs),
     Undo(OperationUndoArgs),
 }
 
@@ -61,9 +69,11 @@ pub fn cmd_operation(
     match subcommand {
         OperationCommand::Abandon(args) => cmd_op_abandon(ui, command, args),
         OperationCommand::Diff(args) => cmd_op_diff(ui, command, args),
+        OperationCommand::Heads(args) => cmd_op_heads(ui, command, args),
         OperationCommand::Log(args) => cmd_op_log(ui, command, args),
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
+        OperationCommand::Squash(args) => cmd_op_squash(ui, command, args),
         OperationCommand::Undo(args) => cmd_op_undo(ui, command, args),
     }
 }
@@ -85,6 +85,7 @@ pub fn cmd_op_show(
                 conflict_marker_style,
                 formats,
             )
+            .with_attribute_overrides(workspace_env.attribute_overrides())
         })
     };
 
@@ -0,0 +1,210 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::iter;
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::op_store;
+use jj_lib::op_store::OperationMetadata;
+use jj_lib::op_store::TimestampRange;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+
+use crate::cli_util::short_operation_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::cli_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Combine a range of consecutive operations into one
+///
+/// `jj op squash <X>..<Y>` replaces the operations after `X` up to and
+/// including `Y` with a single operation. The new operation has the view of
+/// `Y` and the descriptions of the squashed operations. Descendants of `Y`
+/// are reparented onto the new operation.
+///
+/// The operations in the range must form a linear chain, and no other
+/// operation may branch off from the middle of the range.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationSquashArgs {
+    /// The operation range to squash
+    #[arg(add = ArgValueCandidates::new(complete::operations))]
+    operations: String,
+}
+
+pub fn cmd_op_squash(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationSquashArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo so that this command can be used to recover from
+    // corrupted repo state.
+    let mut workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store();
+    let op_heads_store = repo_loader.op_heads_store();
+    // It doesn't make sense to create divergent operations that will be merged
+    // with the current head.
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
+    let Some(range) =
+        op_walk::resolve_op_range_at(repo_loader, &current_head_ops, &args.operations)?
+    else {
+        return Err(cli_error(format!(
+            "Expected an operation range like `X..Y`, got `{}`",
+            args.operations
+        )));
+    };
+    let [squash_head_op] = &range.head_ops[..] else {
+        return Err(user_error("Cannot squash operations into multiple heads"));
+    };
+    if current_head_ops.len() != 1 {
+        return Err(user_error(
+            "Cannot squash operations while there are divergent operations",
+        ));
+    }
+
+    // Operations to squash, newest first.
+    let squash_ops: Vec<_> = range.walk().try_collect()?;
+    for (op, parent_id) in iter::zip(
+        &squash_ops,
+        squash_ops
+            .iter()
+            .skip(1)
+            .map(|op| op.id())
+            .chain([range.root_op.id()]),
+    ) {
+        if op.parent_ids() != slice::from_ref(parent_id) {
+            return Err(user_error(format!(
+                "Cannot squash operation {} because the range isn't a linear chain",
+                short_operation_hash(op.id())
+            )));
+        }
+    }
+    if squash_ops.len() < 2 {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    // Descendants of the squashed range must all descend from its head.
+    // Otherwise, they would refer to operations which no longer exist in the
+    // rewritten history.
+    let descendant_ops: Vec<_> =
+        op_walk::walk_ancestors_range(&current_head_ops, slice::from_ref(squash_head_op))
+            .try_collect()?;
+    let descendant_ids: HashSet<_> = descendant_ops
+        .iter()
+        .map(|op| op.id())
+        .chain([squash_head_op.id()])
+        .collect();
+    if let Some(op) = descendant_ops
+        .iter()
+        .find(|op| !op.parent_ids().iter().all(|id| descendant_ids.contains(id)))
+    {
+        return Err(user_error(format!(
+            "Cannot squash operations because operation {} branches off from outside of {}",
+            short_operation_hash(op.id()),
+            short_operation_hash(squash_head_op.id())
+        )));
+    }
+
+    let new_op_id = op_store.write_operation(&squash_operations(&squash_ops, &range.root_op))?;
+    let new_op = repo_loader.load_operation(&new_op_id)?;
+    let stats = op_walk::reparent_range(
+        op_store.as_ref(),
+        slice::from_ref(squash_head_op),
+        &current_head_ops,
+        &new_op,
+    )?;
+    writeln!(
+        ui.status(),
+        "Squashed {} operations and reparented {} descendant operations.",
+        squash_ops.len(),
+        stats.rewritten_count,
+    )?;
+    let reparented_head_ops = || iter::zip(&current_head_ops, &stats.new_head_ids);
+    for (old, new_id) in reparented_head_ops() {
+        op_heads_store.update_op_heads(slice::from_ref(old.id()), new_id)?;
+    }
+    // Remap the operation id of the current workspace.
+    if !command.global_args().ignore_working_copy {
+        let mut locked_ws = workspace.start_working_copy_mutation()?;
+        let old_op_id = locked_ws.locked_wc().old_operation_id();
+        if let Some((_, new_id)) = reparented_head_ops().find(|(old, _)| old.id() == old_op_id) {
+            locked_ws.finish(new_id.clone())?;
+        } else {
+            writeln!(
+                ui.warning_default(),
+                "The working copy operation {} is not updated because it differs from the repo {}.",
+                short_operation_hash(old_op_id),
+                current_head_ops
+                    .iter()
+                    .map(|op| short_operation_hash(op.id()))
+                    .join(", "),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds an operation which has the net effect of the `ops` (newest first)
+/// applied on top of the `parent_op`.
+fn squash_operations(ops: &[Operation], parent_op: &Operation) -> op_store::Operation {
+    let newest = ops.first().unwrap().store_operation();
+    let oldest = ops.last().unwrap().store_operation();
+    let mut tags = HashMap::new();
+    let mut commit_predecessors = Some(BTreeMap::new());
+    for op in ops.iter().rev() {
+        let data = op.store_operation();
+        // Tags of newer operations take precedence.
+        tags.extend(data.metadata.tags.clone());
+        commit_predecessors = match (commit_predecessors, &data.commit_predecessors) {
+            (Some(mut acc), Some(predecessors)) => {
+                acc.extend(predecessors.clone());
+                Some(acc)
+            }
+            _ => None,
+        };
+    }
+    op_store::Operation {
+        view_id: newest.view_id.clone(),
+        parents: vec![parent_op.id().clone()],
+        metadata: OperationMetadata {
+            time: TimestampRange {
+                start: oldest.metadata.time.start,
+                end: newest.metadata.time.end,
+            },
+            description: ops
+                .iter()
+                .rev()
+                .map(|op| op.metadata().description.as_str())
+                .join("\n"),
+            hostname: newest.metadata.hostname.clone(),
+            username: newest.metadata.username.clone(),
+            is_snapshot: ops.iter().all(|op| op.metadata().is_snapshot),
+            tags,
+        },
+        commit_predecessors,
+    }
+}
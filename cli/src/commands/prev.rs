@@ -62,11 +62,17 @@ pub(crate) struct PrevArgs {
     ///
     /// Takes precedence over config in `ui.movement.edit`; i.e.
     /// will negate `ui.movement.edit = true`
-    #[arg(long, short, conflicts_with = "edit")]
+    #[arg(long, short, visible_alias = "new", conflicts_with = "edit")]
     no_edit: bool,
     /// Jump to the previous conflicted ancestor
     #[arg(long, conflicts_with = "offset")]
     conflict: bool,
+    /// Skip over empty revisions
+    ///
+    /// Keeps moving in the same direction until a revision with changes is
+    /// found.
+    #[arg(long)]
+    skip_empty: bool,
 }
 
 impl From<&PrevArgs> for MovementArgs {
@@ -76,6 +82,7 @@ impl From<&PrevArgs> for MovementArgs {
             edit: val.edit,
             no_edit: val.no_edit,
             conflict: val.conflict,
+            skip_empty: val.skip_empty,
         }
     }
 }
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io::Write as _;
 use std::sync::Arc;
 
@@ -20,7 +22,9 @@ use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::ref_name::RefNameBuf;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::revset::RevsetExpression;
@@ -328,6 +332,27 @@ pub(crate) struct RebaseArgs {
     /// destination with identical changes.
     #[arg(long)]
     keep_divergent: bool,
+
+    /// What to do with bookmarks pointing to rebased revisions
+    ///
+    /// If not specified, this defaults to the `rebase.move-bookmarks-policy`
+    /// setting. The policy of individual bookmarks can be overridden by the
+    /// `rebase.move-bookmarks-overrides` setting. Bookmarks left at the
+    /// original revisions keep them visible.
+    #[arg(long, value_name = "POLICY")]
+    move_bookmarks_policy: Option<MoveBookmarksPolicy>,
+}
+
+/// What to do with bookmarks pointing to rebased revisions
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum MoveBookmarksPolicy {
+    /// Move bookmarks to the rebased revisions
+    Follow,
+    /// Leave bookmarks at the original revisions
+    Leave,
+    /// Ask whether to move each bookmark
+    Ask,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -372,6 +397,16 @@ pub(crate) fn cmd_rebase(
     command: &CommandHelper,
     args: &RebaseArgs,
 ) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let loc = if !args.revisions.is_empty() {
+        plan_rebase_revisions(ui, &workspace_command, &args.revisions, &args.destination)?
+    } else if !args.source.is_empty() {
+        plan_rebase_source(ui, &workspace_command, &args.source, &args.destination)?
+    } else {
+        plan_rebase_branch(ui, &workspace_command, &args.branch, &args.destination)?
+    };
+    let pinned_bookmarks =
+        find_pinned_bookmarks(ui, &workspace_command, &loc, args.move_bookmarks_policy)?;
     let rebase_options = RebaseOptions {
         empty: match args.skip_emptied {
             true => EmptyBehaviour::AbandonNewlyEmpty,
@@ -379,17 +414,10 @@ pub(crate) fn cmd_rebase(
         },
         rewrite_refs: RewriteRefsOptions {
             delete_abandoned_bookmarks: false,
+            pinned_bookmarks,
         },
         simplify_ancestor_merge: false,
     };
-    let mut workspace_command = command.workspace_helper(ui)?;
-    let loc = if !args.revisions.is_empty() {
-        plan_rebase_revisions(ui, &workspace_command, &args.revisions, &args.destination)?
-    } else if !args.source.is_empty() {
-        plan_rebase_source(ui, &workspace_command, &args.source, &args.destination)?
-    } else {
-        plan_rebase_branch(ui, &workspace_command, &args.branch, &args.destination)?
-    };
 
     let mut tx = workspace_command.start_transaction();
     let mut computed_move = compute_move_commits(tx.repo(), &loc)?;
@@ -413,6 +441,12 @@ pub(crate) fn cmd_rebase(
         }
     };
     let stats = computed_move.apply(tx.repo_mut(), &rebase_options)?;
+    if !rebase_options.rewrite_refs.pinned_bookmarks.is_empty() {
+        // References are updated again when the transaction is committed, which
+        // would move the pinned bookmarks. Finish the rebase with our options.
+        tx.repo_mut()
+            .rebase_descendants_with_options(&rebase_options, |_, _| {})?;
+    }
     print_move_commits_stats(ui, &stats)?;
     tx.finish(ui, tx_description(&loc.target))?;
 
@@ -537,6 +571,68 @@ fn plan_rebase_branch(
     })
 }
 
+/// Returns local bookmarks which should stay at the original commits.
+fn find_pinned_bookmarks(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    loc: &MoveCommitsLocation,
+    policy: Option<MoveBookmarksPolicy>,
+) -> Result<HashSet<RefNameBuf>, CommandError> {
+    let settings = workspace_command.settings();
+    let default_policy = match policy {
+        Some(policy) => policy,
+        None => settings.get("rebase.move-bookmarks-policy")?,
+    };
+    let overrides: HashMap<String, MoveBookmarksPolicy> = settings
+        .get("rebase.move-bookmarks-overrides")
+        .optional()?
+        .unwrap_or_default();
+    if default_policy == MoveBookmarksPolicy::Follow
+        && overrides
+            .values()
+            .all(|&policy| policy == MoveBookmarksPolicy::Follow)
+    {
+        return Ok(HashSet::new());
+    }
+
+    let (MoveCommitsTarget::Commits(target_ids) | MoveCommitsTarget::Roots(target_ids)) =
+        &loc.target;
+    let rewritten_ids: HashSet<CommitId> = workspace_command
+        .attach_revset_evaluator(
+            RevsetExpression::commits(target_ids.clone())
+                .union(&RevsetExpression::commits(loc.new_child_ids.clone()))
+                .descendants(),
+        )
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let repo = workspace_command.repo();
+    let mut pinned_bookmarks = HashSet::new();
+    for (name, target) in repo.view().local_bookmarks() {
+        if !target.added_ids().any(|id| rewritten_ids.contains(id)) {
+            continue;
+        }
+        let policy = overrides
+            .get(name.as_str())
+            .copied()
+            .unwrap_or(default_policy);
+        let leave = match policy {
+            MoveBookmarksPolicy::Follow => false,
+            MoveBookmarksPolicy::Leave => true,
+            MoveBookmarksPolicy::Ask => !ui.prompt_yes_no(
+                &format!(
+                    "Move bookmark {} to the rebased revision?",
+                    name.as_symbol()
+                ),
+                Some(true),
+            )?,
+        };
+        if leave {
+            pinned_bookmarks.insert(name.to_owned());
+        }
+    }
+    Ok(pinned_bookmarks)
+}
+
 fn check_rebase_destinations(
     repo: &Arc<ReadonlyRepo>,
     new_parents: &[CommitId],
@@ -12,10 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
+use std::io::Write as _;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
+use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
@@ -23,7 +32,9 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::cli_error;
 use crate::command_error::CommandError;
+use crate::commit_templater::TreeEntry;
 use crate::complete;
+use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// Resolve conflicted files with an external merge tool
@@ -58,6 +69,27 @@ pub(crate) struct ResolveArgs {
     // `diff --summary`, but should be more verbose.
     #[arg(long, short)]
     list: bool,
+    /// Print the list of conflicts as JSON
+    ///
+    /// Each entry contains the `path` relative to the workspace root, the
+    /// number of `sides`, whether the conflict involves `binary` files, and
+    /// the `side_commits`. Each item of `side_commits` is the ID of the parent
+    /// commit the side came from, or null if it can't be derived.
+    #[arg(long, requires = "list")]
+    json: bool,
+    /// Render each conflicted file using the given template
+    ///
+    /// All 0-argument methods of the [`TreeEntry` type] are available as
+    /// keywords in the template expression. See [`jj help -k templates`] for
+    /// more information.
+    ///
+    /// [`TreeEntry` type]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#treeentry-type
+    ///
+    /// [`jj help -k templates`]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', requires = "list", conflicts_with = "json")]
+    template: Option<String>,
     /// Specify 3-way merge tool to be used
     ///
     /// The built-in merge tools `:ours` and `:theirs` can be used to choose
@@ -102,6 +134,26 @@ pub(crate) fn cmd_resolve(
             "No conflicts found at the given path(s)"
         }));
     }
+    if args.list && args.json {
+        return print_conflicts_json(ui, &commit, &tree, matcher.as_ref());
+    }
+    if let Some(text) = &args.template {
+        let template: TemplateRenderer<TreeEntry> = {
+            let language = workspace_command.commit_template_language();
+            workspace_command
+                .parse_template(ui, &language, text)?
+                .labeled(["resolve_list"])
+        };
+        let mut formatter = ui.stdout_formatter();
+        for (path, value) in conflicts {
+            let entry = TreeEntry {
+                path,
+                value: value?,
+            };
+            template.format(&entry, formatter.as_mut())?;
+        }
+        return Ok(());
+    }
     if args.list {
         return print_conflicted_paths(
             conflicts,
@@ -151,3 +203,56 @@ pub(crate) fn cmd_resolve(
     }
     Ok(())
 }
+
+/// Entry of `--list --json` output.
+#[derive(serde::Serialize)]
+struct ConflictJsonEntry {
+    path: String,
+    sides: usize,
+    binary: bool,
+    side_commits: Vec<Option<String>>,
+}
+
+fn print_conflicts_json(
+    ui: &Ui,
+    commit: &Commit,
+    tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    let parent_trees: Vec<(CommitId, MergedTree)> = commit
+        .parents()
+        .map(|parent| {
+            let parent = parent?;
+            let tree = parent.tree()?;
+            Ok((parent.id().clone(), tree))
+        })
+        .collect::<BackendResult<_>>()?;
+    let mut entries = vec![];
+    for summary in tree.conflict_summaries(matcher).block_on()? {
+        // A side can be attributed to a parent if the parent has exactly the
+        // same value at the path.
+        let side_commits = summary
+            .values
+            .adds()
+            .map(|value| {
+                for (parent_id, parent_tree) in &parent_trees {
+                    let parent_value = parent_tree.path_value(&summary.path)?;
+                    if parent_value.as_resolved() == Some(value) {
+                        return Ok(Some(parent_id.hex()));
+                    }
+                }
+                Ok(None)
+            })
+            .collect::<BackendResult<_>>()?;
+        entries.push(ConflictJsonEntry {
+            path: summary.path.as_internal_file_string().to_owned(),
+            sides: summary.num_sides(),
+            binary: summary.is_binary,
+            side_commits,
+        });
+    }
+    let mut stdout = ui.stdout();
+    serde_json::to_writer(&mut stdout, &entries).map_err(io::Error::from)?;
+    writeln!(stdout)?;
+    Ok(())
+}
@@ -19,19 +19,27 @@ use clap::ArgGroup;
 use clap_complete::ArgValueCompleter;
 use indexmap::IndexSet;
 use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::conflicts::ConflictLabels;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::rewrite::merge_commit_trees;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::compute_commit_location;
 use crate::cli_util::print_updated_commits;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::text_util;
 use crate::ui::Ui;
 
 /// Apply the reverse of the given revision(s)
@@ -80,6 +88,12 @@ pub(crate) struct RevertArgs {
         add = ArgValueCompleter::new(complete::revset_expression_mutable),
     )]
     insert_before: Option<Vec<RevisionArg>>,
+    /// Create the reverted commits without touching the working copy
+    ///
+    /// The working copy is not snapshotted before the reverted commits are created.
+    /// The command fails if it would rewrite the working-copy commit.
+    #[arg(long)]
+    detached: bool,
 }
 
 #[instrument(skip_all)]
@@ -88,7 +102,11 @@ pub(crate) fn cmd_revert(
     command: &CommandHelper,
     args: &RevertArgs,
 ) -> Result<(), CommandError> {
-    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut workspace_command = if args.detached {
+        command.workspace_helper_detached(ui)?
+    } else {
+        command.workspace_helper(ui)?
+    };
     let to_revert: Vec<_> = workspace_command
         .parse_union_revsets(ui, &args.revisions)?
         .evaluate_to_commits()?
@@ -144,6 +162,11 @@ pub(crate) fn cmd_revert(
         let old_base_tree = commit_to_revert.parent_tree(tx.repo())?;
         let old_tree = commit_to_revert.tree()?;
         let new_tree = new_base_tree.merge(old_tree, old_base_tree).block_on()?;
+        if new_tree.has_conflict() {
+            let labels = revert_conflict_labels(commit_to_revert)
+                .with_paths(new_conflict_paths(&new_base_tree, &new_tree)?);
+            tx.add_conflict_labels(new_tree.id(), labels);
+        }
         let new_parent_ids = parent_ids.clone();
         let new_commit = tx
             .repo_mut()
@@ -203,3 +226,35 @@ pub(crate) fn cmd_revert(
 
     Ok(())
 }
+
+/// Builds labels of the conflicts produced by applying the reverse of the
+/// `commit`, which describe the conflict sides by the reverted commit.
+pub(crate) fn revert_conflict_labels(commit: &Commit) -> ConflictLabels {
+    let mut source = short_commit_hash(commit.id());
+    let subject = commit.description().lines().next().unwrap_or_default();
+    if !subject.is_empty() {
+        let (subject, _) = text_util::elide_end(subject, "...", 30);
+        source.push_str(&format!(" \"{subject}\""));
+    }
+    ConflictLabels::from_merge(Merge::from_vec(vec![
+        "destination".to_owned(),
+        format!("reverted {source}"),
+        format!("parent of {source}"),
+    ]))
+}
+
+/// Returns the paths of the conflicts in the `new_tree` which didn't exist in
+/// the `base_tree` the reverse of a commit was applied to.
+pub(crate) fn new_conflict_paths(
+    base_tree: &MergedTree,
+    new_tree: &MergedTree,
+) -> BackendResult<Vec<RepoPathBuf>> {
+    let mut paths = vec![];
+    for (path, value) in new_tree.conflicts() {
+        value?;
+        if base_tree.path_value(&path)?.is_resolved() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
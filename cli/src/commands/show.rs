@@ -12,7 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::matchers::EverythingMatcher;
 use tracing::instrument;
@@ -49,7 +48,7 @@ pub(crate) struct ShowArgs {
     ///
     /// [`jj help -k templates`]:
     ///     https://jj-vcs.github.io/jj/latest/templates/
-    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    #[arg(long, short = 'T', add = ArgValueCompleter::new(complete::commit_template_expression))]
     template: Option<String>,
     #[command(flatten)]
     format: DiffFormatArgs,
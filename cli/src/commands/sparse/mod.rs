@@ -17,8 +17,11 @@ mod list;
 mod reset;
 mod set;
 
+use std::sync::Arc;
+
 use clap::Subcommand;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::working_copy::CheckoutProgress;
 use tracing::instrument;
 
 use self::edit::cmd_sparse_edit;
@@ -60,18 +63,22 @@ pub(crate) fn cmd_sparse(
     }
 }
 
-fn update_sparse_patterns_with(
+pub(crate) fn update_sparse_patterns_with(
     ui: &mut Ui,
     workspace_command: &mut WorkspaceCommandHelper,
     f: impl FnOnce(&mut Ui, &[RepoPathBuf]) -> Result<Vec<RepoPathBuf>, CommandError>,
 ) -> Result<(), CommandError> {
-    let checkout_options = workspace_command.checkout_options();
+    let mut checkout_options = workspace_command.checkout_options();
     let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
     let new_patterns = f(ui, locked_ws.locked_wc().sparse_patterns()?)?;
+    checkout_options.progress = crate::progress::checkout_progress(ui)
+        .map(|progress| Arc::new(progress) as Arc<CheckoutProgress>);
     let stats = locked_ws
         .locked_wc()
         .set_sparse_patterns(new_patterns, &checkout_options)
         .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))?;
+    // Clear the progress output before printing the stats.
+    drop(checkout_options);
     let operation_id = locked_ws.locked_wc().old_operation_id().clone();
     locked_ws.finish(operation_id)?;
     print_checkout_stats(ui, &stats, &wc_commit)?;
@@ -13,13 +13,23 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
 
 use itertools::Itertools as _;
+use jj_lib::fileset;
+use jj_lib::fileset::FilePattern;
+use jj_lib::fileset::FilesetDiagnostics;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::repo_path::RepoPathUiConverter;
 use tracing::instrument;
 
 use super::update_sparse_patterns_with;
+use crate::cli_util::read_pathspec_file;
 use crate::cli_util::CommandHelper;
+use crate::command_error::print_parse_diagnostics;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -48,6 +58,15 @@ pub struct SparseSetArgs {
     /// Include no files in the working copy (combine with --add)
     #[arg(long)]
     clear: bool,
+    /// Read patterns to add from the given file, one per line
+    ///
+    /// Each line is a fileset expression relative to the workspace root, such
+    /// as `lib | docs/README.md`. Only unions of path prefixes are supported;
+    /// `file:` and `glob:` patterns are rejected.
+    /// Empty lines and lines starting with `#` are ignored. Use `-` to read
+    /// from stdin.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    from_file: Option<PathBuf>,
 }
 
 #[instrument(skip_all)]
@@ -57,6 +76,12 @@ pub fn cmd_sparse_set(
     args: &SparseSetArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    let file_patterns = args
+        .from_file
+        .as_deref()
+        .map(|path| read_sparse_patterns_file(ui, path))
+        .transpose()?
+        .unwrap_or_default();
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
         let mut new_patterns = HashSet::new();
         if !args.clear {
@@ -65,9 +90,72 @@ pub fn cmd_sparse_set(
                 new_patterns.remove(path);
             }
         }
-        for path in &args.add {
+        for path in args.add.iter().chain(&file_patterns) {
             new_patterns.insert(path.to_owned());
         }
         Ok(new_patterns.into_iter().sorted_unstable().collect())
     })
 }
+
+/// Reads fileset expressions from `path`, and converts them to sparse path
+/// prefixes.
+pub(crate) fn read_sparse_patterns_file(
+    ui: &Ui,
+    path: &Path,
+) -> Result<Vec<RepoPathBuf>, CommandError> {
+    // Sparse patterns are workspace-relative regardless of the current
+    // directory.
+    let path_converter = RepoPathUiConverter::Fs {
+        cwd: "".into(),
+        base: "".into(),
+    };
+    let mut diagnostics = FilesetDiagnostics::new();
+    let mut patterns = vec![];
+    for line in read_pathspec_file(path)? {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let expression = fileset::parse_maybe_bare(&mut diagnostics, line, &path_converter)?;
+        let mut paths = vec![];
+        if !collect_prefix_paths(&expression, &mut paths) {
+            return Err(user_error(format!(
+                "Sparse pattern `{line}` is not a union of path prefixes"
+            ))
+            .hinted(
+                "Sparse patterns can only include path prefixes such as `dir` or \
+                 `root:dir`. File and glob patterns are not supported.",
+            ));
+        }
+        patterns.extend(paths);
+    }
+    print_parse_diagnostics(ui, "In sparse pattern", &diagnostics)?;
+    Ok(patterns)
+}
+
+/// Collects the path prefixes matched by `expression`. Returns false if the
+/// expression can't be represented as path prefixes.
+///
+/// `file:` patterns are rejected rather than widened to prefixes since the
+/// working copy would otherwise include everything under a directory of the
+/// same name.
+fn collect_prefix_paths(expression: &FilesetExpression, paths: &mut Vec<RepoPathBuf>) -> bool {
+    match expression {
+        FilesetExpression::None => true,
+        FilesetExpression::All => {
+            paths.push(RepoPathBuf::root());
+            true
+        }
+        FilesetExpression::Pattern(FilePattern::PrefixPath(path)) => {
+            paths.push(path.clone());
+            true
+        }
+        FilesetExpression::Pattern(FilePattern::FilePath(_) | FilePattern::FileGlob { .. }) => {
+            false
+        }
+        FilesetExpression::UnionAll(expressions) => expressions
+            .iter()
+            .all(|expression| collect_prefix_paths(expression, paths)),
+        FilesetExpression::Intersection(..) | FilesetExpression::Difference(..) => false,
+    }
+}
@@ -368,6 +368,7 @@ fn move_first_commit(
             empty: EmptyBehaviour::Keep,
             rewrite_refs: RewriteRefsOptions {
                 delete_abandoned_bookmarks: false,
+                ..Default::default()
             },
             simplify_ancestor_merge: false,
         },
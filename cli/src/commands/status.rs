@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use itertools::Itertools as _;
 use jj_lib::copies::CopyRecords;
 use jj_lib::merged_tree::MergedTree;
@@ -20,9 +23,12 @@ use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::settings::HumanByteSize;
+use jj_lib::working_copy::UntrackedReason;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
+use crate::cli_util::print_conflict_resolution_hints;
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::print_snapshot_stats;
 use crate::cli_util::CommandHelper;
@@ -47,6 +53,25 @@ pub(crate) struct StatusArgs {
     /// Restrict the status display to these paths
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Read additional filesets from the given file, one per line
+    ///
+    /// Use `-` to read from stdin. Empty lines are ignored. If the file is
+    /// empty and no paths are specified, no files are matched.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    pathspec_from_file: Option<PathBuf>,
+    /// How to show new files which aren't tracked
+    #[arg(long, value_name = "MODE", value_enum, default_value_t = UntrackedMode::Standard)]
+    untracked: UntrackedMode,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum UntrackedMode {
+    /// Show files which weren't tracked because of the snapshot settings
+    Standard,
+    /// Also show ignored files, and the reason each file isn't tracked
+    All,
+    /// Don't show untracked files
+    No,
 }
 
 #[instrument(skip_all)]
@@ -55,19 +80,32 @@ pub(crate) fn cmd_status(
     command: &CommandHelper,
     args: &StatusArgs,
 ) -> Result<(), CommandError> {
-    let (workspace_command, snapshot_stats) = command.workspace_helper_with_stats(ui)?;
+    let (mut workspace_command, snapshot_stats) = command.workspace_helper_with_stats(ui)?;
     print_snapshot_stats(
         ui,
         &snapshot_stats,
         workspace_command.env().path_converter(),
     )?;
+    let untracked_paths = match args.untracked {
+        UntrackedMode::Standard => snapshot_stats.untracked_paths,
+        UntrackedMode::All => {
+            let mut untracked_paths = snapshot_stats.untracked_paths;
+            untracked_paths.extend(workspace_command.snapshot_dry_run(ui)?.untracked_paths);
+            untracked_paths
+        }
+        UntrackedMode::No => BTreeMap::new(),
+    };
     let repo = workspace_command.repo();
     let maybe_wc_commit = workspace_command
         .get_wc_commit_id()
         .map(|id| repo.store().get_commit(id))
         .transpose()?;
     let matcher = workspace_command
-        .parse_file_patterns(ui, &args.paths)?
+        .parse_file_patterns_with_pathspec_file(
+            ui,
+            &args.paths,
+            args.pathspec_from_file.as_deref(),
+        )?
         .to_matcher();
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
@@ -78,7 +116,7 @@ pub(crate) fn cmd_status(
         let tree = wc_commit.tree()?;
 
         let wc_has_changes = tree.id() != parent_tree.id();
-        let wc_has_untracked = !snapshot_stats.untracked_paths.is_empty();
+        let wc_has_untracked = !untracked_paths.is_empty();
         if !wc_has_changes && !wc_has_untracked {
             writeln!(formatter, "The working copy has no changes.")?;
         } else {
@@ -102,26 +140,35 @@ pub(crate) fn cmd_status(
                 )?;
             }
 
-            if wc_has_untracked {
+            if wc_has_untracked && args.untracked == UntrackedMode::All {
+                writeln!(formatter, "Untracked paths:")?;
+                formatter.with_label("diff", |formatter| {
+                    for (path, reason) in &untracked_paths {
+                        let ui_path = workspace_command.path_converter().format_file_path(path);
+                        writeln!(
+                            formatter.labeled("untracked"),
+                            "? {ui_path} ({})",
+                            untracked_reason_label(reason)
+                        )?;
+                    }
+                    Ok::<_, CommandError>(())
+                })?;
+            } else if wc_has_untracked {
                 writeln!(formatter, "Untracked paths:")?;
                 formatter.with_label("diff", |formatter| {
-                    visit_collapsed_untracked_files(
-                        snapshot_stats.untracked_paths.keys(),
-                        tree,
-                        |path, is_dir| {
-                            let ui_path = workspace_command.path_converter().format_file_path(path);
-                            writeln!(
-                                formatter.labeled("untracked"),
-                                "? {ui_path}{}",
-                                if is_dir {
-                                    std::path::MAIN_SEPARATOR_STR
-                                } else {
-                                    ""
-                                }
-                            )?;
-                            Ok(())
-                        },
-                    )
+                    visit_collapsed_untracked_files(untracked_paths.keys(), tree, |path, is_dir| {
+                        let ui_path = workspace_command.path_converter().format_file_path(path);
+                        writeln!(
+                            formatter.labeled("untracked"),
+                            "? {ui_path}{}",
+                            if is_dir {
+                                std::path::MAIN_SEPARATOR_STR
+                            } else {
+                                ""
+                            }
+                        )?;
+                        Ok(())
+                    })
                     .block_on()
                 })?;
             }
@@ -142,12 +189,14 @@ pub(crate) fn cmd_status(
         if wc_commit.has_conflict()? {
             // TODO: Conflicts should also be filtered by the `matcher`. See the related
             // TODO on `MergedTree::conflicts()`.
-            let conflicts = wc_commit.tree()?.conflicts().collect_vec();
+            let tree = wc_commit.tree()?;
+            let conflicts = tree.conflicts().collect_vec();
             writeln!(
                 formatter.labeled("warning").with_heading("Warning: "),
                 "There are unresolved conflicts at these paths:"
             )?;
             print_conflicted_paths(conflicts, formatter, &workspace_command)?;
+            print_conflict_resolution_hints(formatter, &tree, &workspace_command)?;
 
             let wc_revset = RevsetExpression::commit(wc_commit.id().clone());
 
@@ -227,6 +276,19 @@ pub(crate) fn cmd_status(
     Ok(())
 }
 
+fn untracked_reason_label(reason: &UntrackedReason) -> String {
+    match reason {
+        UntrackedReason::FileTooLarge { max_size, .. } => {
+            format!("larger than {}", HumanByteSize(*max_size))
+        }
+        UntrackedReason::FileNotAutoTracked => "not auto-tracked".to_owned(),
+        UntrackedReason::NewDirectoryTooLarge { max_files } => {
+            format!("new directory with more than {max_files} files")
+        }
+        UntrackedReason::Ignored => "ignored".to_owned(),
+    }
+}
+
 async fn visit_collapsed_untracked_files(
     untracked_paths: impl IntoIterator<Item = impl AsRef<RepoPath>>,
     tree: MergedTree,
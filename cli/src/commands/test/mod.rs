@@ -0,0 +1,46 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod run;
+
+use clap::Subcommand;
+use tracing::instrument;
+
+use self::run::cmd_test_run;
+use self::run::TestRunArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Run tests on revisions and cache the results
+///
+/// Results are cached by the tree of each revision, so revisions that have
+/// been rewritten without changing their contents aren't tested again. Cached
+/// results can be queried with the `tests()` revset function and the
+/// `tests_status()` commit template method.
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum TestCommand {
+    Run(TestRunArgs),
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_test(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &TestCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        TestCommand::Run(args) => cmd_test_run(ui, command, args),
+    }
+}
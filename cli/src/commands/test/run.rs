@@ -0,0 +1,140 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::config::ConfigGetResultExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::config_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::commands::util::materialize_commit;
+use crate::complete;
+use crate::config::CommandNameAndArgs;
+use crate::test_results::TestResultStore;
+use crate::test_results::TestStatus;
+use crate::ui::Ui;
+
+/// Run the configured test command on revisions
+///
+/// The command configured by `test.command` is run in a read-only copy of
+/// each revision, parents before children. The command is run with the
+/// following environment variables set:
+///
+/// * `JJ_CHANGE_ID`: the change ID of the revision
+/// * `JJ_COMMIT_ID`: the commit ID of the revision
+/// * `JJ_ROOT`: the path to the copy of the revision
+///
+/// A revision passes if the command exits successfully. The result is stored
+/// in the repo, and reused for any revision with the same tree as long as
+/// the test command and its environment are unchanged.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct TestRunArgs {
+    /// The revisions to test
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    revisions: Vec<RevisionArg>,
+    /// Run the command even if a result is already cached
+    #[arg(long)]
+    rerun: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_test_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TestRunArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let Some(test_command) = workspace_command
+        .settings()
+        .get::<CommandNameAndArgs>("test.command")
+        .optional()?
+    else {
+        return Err(config_error("No test command is configured").hinted(
+            "Set `test.command` to the command to run, e.g. `jj config set --repo test.command \
+             'cargo test'`",
+        ));
+    };
+    let commits: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let store = TestResultStore::for_command(workspace_command.repo_path(), &test_command)?;
+
+    let mut num_failed = 0;
+    // Test parents before children
+    for commit in commits.iter().rev() {
+        let cached = if args.rerun {
+            None
+        } else {
+            store.get(commit.tree_id())
+        };
+        let status = if let Some(status) = cached {
+            status
+        } else {
+            let materialized = materialize_commit(&workspace_command, commit, "jj-test-")?;
+            let mut cmd = test_command.to_command();
+            materialized.configure_command(&mut cmd);
+            let output = cmd.output().map_err(|err| {
+                user_error_with_message(
+                    format!(
+                        "Failed to execute test command '{}'",
+                        test_command.split_name()
+                    ),
+                    err,
+                )
+            })?;
+            let status = if output.status.success() {
+                TestStatus::Passed
+            } else {
+                ui.stderr().write_all(&output.stdout)?;
+                ui.stderr().write_all(&output.stderr)?;
+                TestStatus::Failed
+            };
+            store.insert(commit.tree_id(), status)?;
+            status
+        };
+        if status == TestStatus::Failed {
+            num_failed += 1;
+        }
+        if let Some(mut formatter) = ui.status_formatter() {
+            write!(formatter, "{}", status.as_str())?;
+            if cached.is_some() {
+                write!(formatter, " (cached)")?;
+            }
+            write!(formatter, ": ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    if num_failed > 0 {
+        return Err(user_error(format!(
+            "{num_failed} of {} revisions failed",
+            commits.len()
+        )));
+    }
+    Ok(())
+}
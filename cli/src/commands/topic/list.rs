@@ -0,0 +1,72 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::commit::Commit;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::description_util::topic_from_description;
+use crate::ui::Ui;
+
+/// List topics and the number of revisions in each
+#[derive(clap::Args, Clone, Debug)]
+pub struct TopicListArgs {
+    /// Revisions to collect topics from
+    ///
+    /// Defaults to `mutable()`.
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+pub fn cmd_topic_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TopicListArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let revisions = if args.revisions.is_empty() {
+        vec![RevisionArg::from("mutable()".to_owned())]
+    } else {
+        args.revisions.clone()
+    };
+    let mut topics: BTreeMap<String, usize> = BTreeMap::new();
+    for commit in workspace_command
+        .parse_union_revsets(ui, &revisions)?
+        .evaluate_to_commits()?
+    {
+        let commit: Commit = commit?;
+        if let Some(topic) = topic_from_description(commit.description()) {
+            *topics.entry(topic).or_default() += 1;
+        }
+    }
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (topic, count) in &topics {
+        write!(formatter.labeled("topic"), "{topic}")?;
+        writeln!(formatter, ": {count} revisions")?;
+    }
+    Ok(())
+}
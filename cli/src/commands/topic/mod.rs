@@ -0,0 +1,121 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod clear;
+mod list;
+mod set;
+
+use std::collections::HashMap;
+
+use clap::Subcommand;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+
+use self::clear::cmd_topic_clear;
+use self::clear::TopicClearArgs;
+use self::list::cmd_topic_list;
+use self::list::TopicListArgs;
+use self::set::cmd_topic_set;
+use self::set::TopicSetArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::description_util::set_description_topic;
+use crate::ui::Ui;
+
+/// Manage topics of revisions
+///
+/// A topic groups a series of revisions without creating a bookmark. It is
+/// recorded as a `Topic:` trailer in the commit description, so it survives
+/// rewrites and is visible to other tools.
+///
+/// Use `topic(name)` in revsets and `topic()` in commit templates to select and
+/// display revisions by topic.
+#[derive(Subcommand, Clone, Debug)]
+pub enum TopicCommand {
+    Clear(TopicClearArgs),
+    #[command(visible_alias("l"))]
+    List(TopicListArgs),
+    Set(TopicSetArgs),
+}
+
+pub fn cmd_topic(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &TopicCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        TopicCommand::Clear(args) => cmd_topic_clear(ui, command, args),
+        TopicCommand::List(args) => cmd_topic_list(ui, command, args),
+        TopicCommand::Set(args) => cmd_topic_set(ui, command, args),
+    }
+}
+
+/// Rewrites the descriptions of the `revisions` to record the `topic`.
+fn update_topics(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    revisions: &[RevisionArg],
+    topic: Option<&str>,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let new_descriptions: HashMap<CommitId, String> = commits
+        .iter()
+        .filter_map(|commit| {
+            let description = set_description_topic(commit.description(), topic);
+            (description != commit.description()).then(|| (commit.id().clone(), description))
+        })
+        .collect();
+    if new_descriptions.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    workspace_command.check_rewritable(new_descriptions.keys())?;
+
+    let mut tx = workspace_command.start_transaction();
+    let mut num_updated = 0;
+    let mut num_reparented = 0;
+    tx.repo_mut().transform_descendants(
+        new_descriptions.keys().cloned().collect(),
+        |rewriter| {
+            let old_commit_id = rewriter.old_commit().id().clone();
+            let commit_builder = rewriter.reparent();
+            if let Some(description) = new_descriptions.get(&old_commit_id) {
+                commit_builder.set_description(description).write()?;
+                num_updated += 1;
+            } else {
+                commit_builder.write()?;
+                num_reparented += 1;
+            }
+            Ok(())
+        },
+    )?;
+    let tx_description = if let Some(topic) = topic {
+        writeln!(ui.status(), "Set topic {topic} on {num_updated} commits")?;
+        format!("set topic {topic} on {num_updated} commits")
+    } else {
+        writeln!(ui.status(), "Cleared topic of {num_updated} commits")?;
+        format!("clear topic of {num_updated} commits")
+    };
+    if num_reparented > 0 {
+        writeln!(ui.status(), "Rebased {num_reparented} descendant commits")?;
+    }
+    tx.finish(ui, tx_description)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+
+use super::update_topics;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Set the topic of revisions
+///
+/// Any existing topic of the revisions is replaced.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TopicSetArgs {
+    /// The topic name
+    name: String,
+    /// The revision(s) to set the topic of
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCompleter::new(complete::revset_expression_mutable),
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+pub fn cmd_topic_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TopicSetArgs,
+) -> Result<(), CommandError> {
+    let name = args.name.trim();
+    if name.is_empty() || name.contains('\n') {
+        return Err(user_error("Topic name must be a non-empty single line"));
+    }
+    update_topics(ui, command, &args.revisions, Some(name))
+}
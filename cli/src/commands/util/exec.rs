@@ -12,10 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::commit::Commit;
+use jj_lib::local_working_copy::TreeState;
+use jj_lib::local_working_copy::TreeStateSettings;
+use jj_lib::object_id::ObjectId as _;
+use tempfile::TempDir;
+
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
+use crate::complete;
+use crate::merge_tools::new_utf8_temp_dir;
+use crate::merge_tools::set_readonly_recursively;
 use crate::ui::Ui;
 
 /// Execute an external command via jj
@@ -61,9 +78,42 @@ use crate::ui::Ui;
 ///
 /// > Note: Shebangs (e.g. `#!/usr/bin/env`) aren't necessary since you're
 /// > already explicitly passing your script into the right shell.
+///
+/// When run inside a repo, the command is run with the following environment
+/// variables set, so that scripts don't have to invoke jj again to look them
+/// up:
+///
+/// * `JJ_WORKSPACE_ROOT`: the path to the workspace root
+/// * `JJ_REPO_PATH`: the path to the repo directory (usually `.jj/repo`)
+/// * `JJ_WORKSPACE_NAME`: the name of the workspace
+/// * `JJ_OP_ID`: the ID of the current operation
+/// * `JJ_WC_COMMIT_ID`, `JJ_WC_CHANGE_ID`: the commit and change ID of the
+///   working-copy commit
+///
+/// With `--on-rev`, the files of the given revision are written to a
+/// temporary directory, and the command is run there:
+///
+/// ```shell
+/// jj util exec --on-rev @- -- cargo test
+/// ```
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 pub(crate) struct UtilExecArgs {
+    /// Run the command in a read-only copy of this revision
+    ///
+    /// The files are written to a temporary directory, which is deleted after
+    /// the command exits. The command is run in that directory with the
+    /// following environment variables set:
+    ///
+    /// * `JJ_CHANGE_ID`: the change ID of the revision
+    /// * `JJ_COMMIT_ID`: the commit ID of the revision
+    /// * `JJ_ROOT`: the path to the temporary directory
+    #[arg(
+        long,
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    on_rev: Option<RevisionArg>,
     /// External command to execute
     command: String,
     /// Arguments to pass to the external command
@@ -71,19 +121,40 @@ pub(crate) struct UtilExecArgs {
 }
 
 pub fn cmd_util_exec(
-    _ui: &mut Ui,
-    _command: &CommandHelper,
+    ui: &mut Ui,
+    command: &CommandHelper,
     args: &UtilExecArgs,
 ) -> Result<(), CommandError> {
-    let status = std::process::Command::new(&args.command)
-        .args(&args.args)
-        .status()
-        .map_err(|err| {
-            user_error_with_message(
-                format!("Failed to execute external command '{}'", &args.command),
-                err,
-            )
-        })?;
+    // Outside of a repo, the command is run without the workspace variables
+    let workspace_command = if command.workspace_loader().is_ok() || args.on_rev.is_some() {
+        Some(command.workspace_helper(ui)?)
+    } else {
+        None
+    };
+    // The temporary directory is deleted when this is dropped
+    let materialized = args
+        .on_rev
+        .as_ref()
+        .zip(workspace_command.as_ref())
+        .map(|(revision, workspace_command)| {
+            let commit = workspace_command.resolve_single_rev(ui, revision)?;
+            materialize_commit(workspace_command, &commit, "jj-exec-")
+        })
+        .transpose()?;
+    let mut cmd = Command::new(&args.command);
+    cmd.args(&args.args);
+    if let Some(workspace_command) = &workspace_command {
+        cmd.envs(workspace_command.workspace_env_vars()?);
+    }
+    if let Some(materialized) = &materialized {
+        materialized.configure_command(&mut cmd);
+    }
+    let status = cmd.status().map_err(|err| {
+        user_error_with_message(
+            format!("Failed to execute external command '{}'", &args.command),
+            err,
+        )
+    })?;
     if !status.success() {
         let error_msg = if let Some(exit_code) = status.code() {
             format!("External command exited with {exit_code}")
@@ -95,3 +166,57 @@ pub fn cmd_util_exec(
     }
     Ok(())
 }
+
+/// Files of a revision written to a temporary directory.
+pub(crate) struct MaterializedRevision {
+    _temp_dir: TempDir, // Temp dir will be deleted when this is dropped
+    root: PathBuf,
+    change_id: String,
+    commit_id: String,
+}
+
+impl MaterializedRevision {
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Sets the working directory and `JJ_*` environment variables of `cmd`.
+    pub fn configure_command(&self, cmd: &mut Command) {
+        cmd.current_dir(self.root())
+            .env("JJ_CHANGE_ID", &self.change_id)
+            .env("JJ_COMMIT_ID", &self.commit_id)
+            .env("JJ_ROOT", self.root());
+    }
+}
+
+/// Writes the files of `commit` to a new read-only temporary directory.
+pub(crate) fn materialize_commit(
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    temp_dir_prefix: &str,
+) -> Result<MaterializedRevision, CommandError> {
+    let tree = commit.tree()?;
+    let temp_dir = new_utf8_temp_dir(temp_dir_prefix)?;
+    let root = temp_dir.path().join("root");
+    let state_dir = temp_dir.path().join("state");
+    std::fs::create_dir(&root)?;
+    std::fs::create_dir(&state_dir)?;
+    let store = workspace_command.repo().store().clone();
+    let mut tree_state = TreeState::init(
+        store,
+        root.clone(),
+        state_dir,
+        &TreeStateSettings::default(),
+    )
+    .map_err(|err| internal_error_with_message("Failed to set up working copy", err))?;
+    tree_state
+        .check_out(&tree, &workspace_command.checkout_options())
+        .map_err(|err| internal_error_with_message("Failed to check out revision", err))?;
+    set_readonly_recursively(&root)?;
+    Ok(MaterializedRevision {
+        _temp_dir: temp_dir,
+        root,
+        change_id: commit.change_id().reverse_hex(),
+        commit_id: commit.id().hex(),
+    })
+}
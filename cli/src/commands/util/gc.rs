@@ -16,17 +16,25 @@ use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
 
+use itertools::Itertools as _;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
 
 use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::maintenance;
+use crate::test_results::TestResultStore;
 use crate::ui::Ui;
 
 /// Run backend-dependent garbage collection.
 ///
 /// To garbage-collect old operations and the commits/objects referenced by
 /// them, run `jj op abandon ..<some old operation>` before `jj util gc`.
+///
+/// Results of `jj test run` are also removed if no visible revision has the
+/// tested tree.
 #[derive(clap::Args, Clone, Debug)]
 pub struct UtilGcArgs {
     /// Time threshold
@@ -39,6 +47,13 @@ pub struct UtilGcArgs {
     /// release.
     #[arg(long)]
     expire: Option<String>,
+    /// Only run if garbage collection is due
+    ///
+    /// Garbage collection is skipped if it has already run within
+    /// `gc.auto-interval-hours`. This is what commands run in the background
+    /// when `gc.auto` is enabled.
+    #[arg(long)]
+    auto: bool,
 }
 
 pub fn cmd_util_gc(
@@ -57,10 +72,30 @@ pub fn cmd_util_gc(
         _ => return Err(user_error("--expire only accepts 'now'")),
     };
     let workspace_command = command.workspace_helper(ui)?;
+    let repo_path = workspace_command.repo_path();
+    let settings = workspace_command.settings();
+    if args.auto && !maintenance::is_auto_gc_due(settings, repo_path)? {
+        return Ok(());
+    }
+    // Serialize concurrent runs, and re-check the state since another process
+    // may have collected garbage while we were waiting for the lock.
+    let _lock = maintenance::lock_maintenance(repo_path).map_err(internal_error)?;
+    if args.auto && !maintenance::is_auto_gc_due(settings, repo_path)? {
+        return Ok(());
+    }
 
     let repo = workspace_command.repo();
     repo.op_store()
         .gc(slice::from_ref(repo.op_id()), keep_newer)?;
     repo.store().gc(repo.index(), keep_newer)?;
+    let test_results = TestResultStore::new(repo_path);
+    if !test_results.is_empty() {
+        let commits: Vec<_> = workspace_command
+            .attach_revset_evaluator(RevsetExpression::all())
+            .evaluate_to_commits()?
+            .try_collect()?;
+        test_results.gc(commits.iter().map(|commit| commit.tree_id()), keep_newer)?;
+    }
+    maintenance::record_gc_run(repo_path)?;
     Ok(())
 }
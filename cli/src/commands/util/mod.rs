@@ -18,6 +18,7 @@ mod exec;
 mod gc;
 mod install_man_pages;
 mod markdown_help;
+mod quote;
 
 use clap::Subcommand;
 use tracing::instrument;
@@ -27,6 +28,7 @@ use self::completion::UtilCompletionArgs;
 use self::config_schema::cmd_util_config_schema;
 use self::config_schema::UtilConfigSchemaArgs;
 use self::exec::cmd_util_exec;
+pub(crate) use self::exec::materialize_commit;
 use self::exec::UtilExecArgs;
 use self::gc::cmd_util_gc;
 use self::gc::UtilGcArgs;
@@ -34,6 +36,8 @@ use self::install_man_pages::cmd_util_install_man_pages;
 use self::install_man_pages::UtilInstallManPagesArgs;
 use self::markdown_help::cmd_util_markdown_help;
 use self::markdown_help::UtilMarkdownHelp;
+use self::quote::cmd_util_quote;
+use self::quote::UtilQuoteArgs;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -47,6 +51,7 @@ pub(crate) enum UtilCommand {
     Gc(UtilGcArgs),
     InstallManPages(UtilInstallManPagesArgs),
     MarkdownHelp(UtilMarkdownHelp),
+    Quote(UtilQuoteArgs),
 }
 
 #[instrument(skip_all)]
@@ -62,5 +67,6 @@ pub(crate) fn cmd_util(
         UtilCommand::Gc(args) => cmd_util_gc(ui, command, args),
         UtilCommand::InstallManPages(args) => cmd_util_install_man_pages(ui, command, args),
         UtilCommand::MarkdownHelp(args) => cmd_util_markdown_help(ui, command, args),
+        UtilCommand::Quote(args) => cmd_util_quote(ui, command, args),
     }
 }
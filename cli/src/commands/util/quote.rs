@@ -0,0 +1,89 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::revset;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Quote strings for use in revset, fileset, and template expressions
+///
+/// Each value is printed on its own line as a literal that evaluates back to
+/// the original string. This allows scripts to safely interpolate arbitrary
+/// file names, bookmark names, or descriptions into `-r`, `-T`, or fileset
+/// arguments.
+///
+/// For example, `jj log -r "bookmarks($(jj util quote --kind exact "$name"))"`
+/// selects the bookmark named `$name` even if it contains special characters.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct UtilQuoteArgs {
+    /// Strings to quote
+    #[arg(required = true)]
+    values: Vec<String>,
+    /// How the values will be interpreted
+    #[arg(long, value_enum, default_value_t = QuoteKind::String)]
+    kind: QuoteKind,
+    /// Join the quoted values into a single union (`x | y`) expression
+    #[arg(long)]
+    union: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum QuoteKind {
+    /// String literal, usable in revsets, filesets, and templates
+    String,
+    /// Revset symbol, quoted only if needed
+    Symbol,
+    /// Exact string pattern, such as `exact:"name"`
+    Exact,
+    /// Fileset matching the exact path relative to the current directory
+    File,
+    /// Fileset matching the exact path relative to the workspace root
+    RootFile,
+}
+
+pub fn cmd_util_quote(
+    ui: &mut Ui,
+    _command: &CommandHelper,
+    args: &UtilQuoteArgs,
+) -> Result<(), CommandError> {
+    let quoted = args
+        .values
+        .iter()
+        .map(|value| quote_value(value, args.kind))
+        .collect_vec();
+    let mut stdout = ui.stdout();
+    if args.union {
+        writeln!(stdout, "{}", quoted.iter().join(" | "))?;
+    } else {
+        for value in &quoted {
+            writeln!(stdout, "{value}")?;
+        }
+    }
+    Ok(())
+}
+
+fn quote_value(value: &str, kind: QuoteKind) -> String {
+    match kind {
+        QuoteKind::String => revset::format_string(value),
+        QuoteKind::Symbol => revset::format_symbol(value),
+        QuoteKind::Exact => format!("exact:{}", revset::format_string(value)),
+        QuoteKind::File => format!("file:{}", revset::format_string(value)),
+        QuoteKind::RootFile => format!("root-file:{}", revset::format_string(value)),
+    }
+}
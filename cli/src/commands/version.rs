@@ -18,18 +18,28 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
+use crate::diagnostics;
 use crate::ui::Ui;
 
 /// Display version information
 #[derive(clap::Args, Clone, Debug)]
-pub(crate) struct VersionArgs {}
+pub(crate) struct VersionArgs {
+    /// Also show the enabled build features and backend implementations
+    ///
+    /// Run `jj debug env` to also check the configuration for problems.
+    #[arg(long, short)]
+    verbose: bool,
+}
 
 #[instrument(skip_all)]
 pub(crate) fn cmd_version(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &VersionArgs,
+    args: &VersionArgs,
 ) -> Result<(), CommandError> {
     write!(ui.stdout(), "{}", command.app().render_version())?;
+    if args.verbose {
+        diagnostics::write_build_info(&mut ui.stdout(), command)?;
+    }
     Ok(())
 }
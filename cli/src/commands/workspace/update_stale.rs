@@ -26,15 +26,27 @@ use crate::ui::Ui;
 /// [stale working copy documentation]:
 ///     https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy
 #[derive(clap::Args, Clone, Debug)]
-pub struct WorkspaceUpdateStaleArgs {}
+pub struct WorkspaceUpdateStaleArgs {
+    /// Rebase changes made in the stale working copy onto the fresh
+    /// working-copy commit
+    ///
+    /// The local changes are snapshotted first, and then committed on top of
+    /// the working-copy commit of the current operation. The new commit
+    /// becomes the working-copy commit. If the changes conflict with the
+    /// updated working-copy commit, the conflicts are recorded in the new
+    /// commit.
+    #[arg(long)]
+    rebase_local_changes: bool,
+}
 
 #[instrument(skip_all)]
 pub fn cmd_workspace_update_stale(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &WorkspaceUpdateStaleArgs,
+    args: &WorkspaceUpdateStaleArgs,
 ) -> Result<(), CommandError> {
-    let (workspace_command, stats) = command.recover_stale_working_copy(ui)?;
+    let (workspace_command, stats) =
+        command.recover_stale_working_copy(ui, args.rebase_local_changes)?;
     print_snapshot_stats(ui, &stats, workspace_command.env().path_converter())?;
 
     Ok(())
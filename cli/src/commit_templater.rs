@@ -13,9 +13,12 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::max;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Display;
 use std::io;
@@ -40,6 +43,8 @@ use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+use jj_lib::gitattributes::GitAttributes;
+use jj_lib::gitattributes::TreeAttributes;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::id_prefix::IdPrefixIndex;
 use jj_lib::matchers::Matcher;
@@ -72,6 +77,7 @@ use once_cell::unsync::OnceCell;
 use pollster::FutureExt as _;
 use serde::Serialize as _;
 
+use crate::description_util;
 use crate::diff_util;
 use crate::diff_util::DiffStats;
 use crate::formatter::Formatter;
@@ -85,6 +91,7 @@ use crate::template_builder::CoreTemplatePropertyKind;
 use crate::template_builder::CoreTemplatePropertyVar;
 use crate::template_builder::TemplateBuildMethodFnMap;
 use crate::template_builder::TemplateLanguage;
+use crate::template_builder::TemplateSymbolNames;
 use crate::template_parser;
 use crate::template_parser::ExpressionNode;
 use crate::template_parser::FunctionCallNode;
@@ -101,6 +108,8 @@ use crate::templater::Template;
 use crate::templater::TemplateFormatter;
 use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
+use crate::test_results::TestResultStore;
+use crate::test_results::TestStatus;
 
 pub trait CommitTemplateLanguageExtension {
     fn build_fn_table<'repo>(&self) -> CommitTemplateBuildFnTable<'repo>;
@@ -121,6 +130,7 @@ pub struct CommitTemplateLanguage<'repo> {
     id_prefix_context: &'repo IdPrefixContext,
     immutable_expression: Rc<UserRevsetExpression>,
     conflict_marker_style: ConflictMarkerStyle,
+    attribute_overrides: &'repo GitAttributes,
     build_fn_table: CommitTemplateBuildFnTable<'repo>,
     keyword_cache: CommitKeywordCache<'repo>,
     cache_extensions: ExtensionsMap,
@@ -138,6 +148,7 @@ impl<'repo> CommitTemplateLanguage<'repo> {
         id_prefix_context: &'repo IdPrefixContext,
         immutable_expression: Rc<UserRevsetExpression>,
         conflict_marker_style: ConflictMarkerStyle,
+        attribute_overrides: &'repo GitAttributes,
         extensions: &[impl AsRef<dyn CommitTemplateLanguageExtension>],
     ) -> Self {
         let mut build_fn_table = CommitTemplateBuildFnTable::builtin();
@@ -158,6 +169,7 @@ impl<'repo> CommitTemplateLanguage<'repo> {
             id_prefix_context,
             immutable_expression,
             conflict_marker_style,
+            attribute_overrides,
             build_fn_table,
             keyword_cache: CommitKeywordCache::default(),
             cache_extensions,
@@ -708,7 +720,7 @@ pub struct CommitTemplateBuildFnTable<'repo> {
 
 impl<'repo> CommitTemplateBuildFnTable<'repo> {
     /// Creates new symbol table containing the builtin methods.
-    fn builtin() -> Self {
+    pub fn builtin() -> Self {
         CommitTemplateBuildFnTable {
             core: CoreTemplateBuildFnTable::builtin(),
             commit_methods: builtin_commit_methods(),
@@ -815,6 +827,34 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
         merge_fn_map(&mut self.trailer_methods, trailer_methods);
         merge_fn_map(&mut self.trailer_list_methods, trailer_list_methods);
     }
+
+    /// Returns the names of the functions and methods in this table.
+    pub fn symbol_names(&self) -> TemplateSymbolNames {
+        let mut names = self.core.symbol_names();
+        names.add_methods("Commit", &self.commit_methods);
+        names.add_methods("List<Commit>", &self.commit_list_methods);
+        names.add_methods("CommitRef", &self.commit_ref_methods);
+        names.add_methods("List<CommitRef>", &self.commit_ref_list_methods);
+        names.add_methods("WorkspaceRef", &self.workspace_ref_methods);
+        names.add_methods("List<WorkspaceRef>", &self.workspace_ref_list_methods);
+        names.add_methods("RepoPath", &self.repo_path_methods);
+        names.add_methods("ChangeId", &self.change_id_methods);
+        names.add_methods("CommitId", &self.commit_id_methods);
+        names.add_methods("ShortestIdPrefix", &self.shortest_id_prefix_methods);
+        names.add_methods("TreeDiff", &self.tree_diff_methods);
+        names.add_methods("TreeDiffEntry", &self.tree_diff_entry_methods);
+        names.add_methods("List<TreeDiffEntry>", &self.tree_diff_entry_list_methods);
+        names.add_methods("TreeEntry", &self.tree_entry_methods);
+        names.add_methods("DiffStats", &self.diff_stats_methods);
+        names.add_methods(
+            "CryptographicSignature",
+            &self.cryptographic_signature_methods,
+        );
+        names.add_methods("AnnotationLine", &self.annotation_line_methods);
+        names.add_methods("Trailer", &self.trailer_methods);
+        names.add_methods("List<Trailer>", &self.trailer_list_methods);
+        names
+    }
 }
 
 #[derive(Default)]
@@ -824,6 +864,8 @@ pub struct CommitKeywordCache<'repo> {
     tags_index: OnceCell<Rc<CommitRefsIndex>>,
     git_refs_index: OnceCell<Rc<CommitRefsIndex>>,
     is_immutable_fn: OnceCell<Rc<RevsetContainingFn<'repo>>>,
+    closest_bookmarks: Rc<ClosestRefCache>,
+    closest_tags: Rc<ClosestRefCache>,
 }
 
 impl<'repo> CommitKeywordCache<'repo> {
@@ -856,6 +898,14 @@ impl<'repo> CommitKeywordCache<'repo> {
             Ok(revset.containing_fn().into())
         })
     }
+
+    pub fn closest_bookmarks(&self) -> &Rc<ClosestRefCache> {
+        &self.closest_bookmarks
+    }
+
+    pub fn closest_tags(&self) -> &Rc<ClosestRefCache> {
+        &self.closest_tags
+    }
 }
 
 fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Commit> {
@@ -879,6 +929,16 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "topic",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|commit| {
+                description_util::topic_from_description(commit.description()).unwrap_or_default()
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "change_id",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -908,17 +968,21 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
     );
     map.insert(
         "author",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
+        |language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            let out_property = self_property.map(|commit| commit.author().clone());
+            let mailmap = language.revset_parse_context.mailmap;
+            let out_property =
+                self_property.map(move |commit| mailmap.resolve_signature(commit.author()));
             Ok(out_property.into_dyn_wrapped())
         },
     );
     map.insert(
         "committer",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
+        |language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            let out_property = self_property.map(|commit| commit.committer().clone());
+            let mailmap = language.revset_parse_context.mailmap;
+            let out_property =
+                self_property.map(move |commit| mailmap.resolve_signature(commit.committer()));
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -927,7 +991,14 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
         |language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
             let user_email = language.revset_parse_context.user_email.to_owned();
-            let out_property = self_property.map(move |commit| commit.author().email == user_email);
+            let user_email_aliases = language.revset_parse_context.user_email_aliases.to_vec();
+            let out_property = self_property.map(move |commit| {
+                let email = &commit.author().email;
+                *email == user_email
+                    || user_email_aliases
+                        .iter()
+                        .any(|pattern| pattern.is_match(email))
+            });
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -1025,6 +1096,38 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "closest_bookmark",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let index = language
+                .keyword_cache
+                .bookmarks_index(language.repo)
+                .clone();
+            let cache = language.keyword_cache.closest_bookmarks().clone();
+            let out_property = self_property.and_then(move |commit| {
+                let is_local = |commit_ref: &CommitRef| commit_ref.is_local();
+                let closest = find_closest_ref(repo, &index, is_local, &cache, commit.id())?;
+                Ok(format_closest_ref(closest))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "nearest_tag",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let index = language.keyword_cache.tags_index(language.repo).clone();
+            let cache = language.keyword_cache.closest_tags().clone();
+            let out_property = self_property.and_then(move |commit| {
+                let closest = find_closest_ref(repo, &index, |_| true, &cache, commit.id())?;
+                Ok(format_closest_ref(closest))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "git_refs",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1059,6 +1162,24 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "divergent_commits",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property.and_then(|commit| {
+                let commit_ids = repo
+                    .resolve_change_id(commit.change_id())
+                    .unwrap_or_default();
+                let commits: Vec<_> = commit_ids
+                    .iter()
+                    .map(|id| repo.store().get_commit(id))
+                    .try_collect()?;
+                Ok(commits)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "hidden",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1113,6 +1234,21 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "tests_status",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language
+                .revset_parse_context
+                .workspace
+                .map(|workspace| TestResultStore::new(workspace.repo_path));
+            let out_property = self_property.map(move |commit| {
+                let status = store.as_ref().and_then(|store| store.get(commit.tree_id()));
+                status.map_or("unknown", TestStatus::as_str).to_owned()
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "diff",
         |language, diagnostics, _build_ctx, self_property, function| {
@@ -1197,12 +1333,12 @@ fn evaluate_revset_expression<'repo>(
     Ok(revset)
 }
 
-fn evaluate_user_revset<'repo>(
-    language: &CommitTemplateLanguage<'repo>,
+fn parse_user_revset(
+    language: &CommitTemplateLanguage<'_>,
     diagnostics: &mut TemplateDiagnostics,
     span: pest::Span<'_>,
     revset: &str,
-) -> Result<Box<dyn Revset + 'repo>, TemplateParseError> {
+) -> Result<Rc<UserRevsetExpression>, TemplateParseError> {
     let mut inner_diagnostics = RevsetDiagnostics::new();
     let (expression, modifier) = revset::parse_with_modifier(
         &mut inner_diagnostics,
@@ -1214,7 +1350,16 @@ fn evaluate_user_revset<'repo>(
         TemplateParseError::expression("In revset expression", span).with_source(diag)
     });
     let (None | Some(RevsetModifier::All)) = modifier;
+    Ok(expression)
+}
 
+fn evaluate_user_revset<'repo>(
+    language: &CommitTemplateLanguage<'repo>,
+    diagnostics: &mut TemplateDiagnostics,
+    span: pest::Span<'_>,
+    revset: &str,
+) -> Result<Box<dyn Revset + 'repo>, TemplateParseError> {
+    let expression = parse_user_revset(language, diagnostics, span, revset)?;
     evaluate_revset_expression(language, span, &expression)
 }
 
@@ -1658,6 +1803,73 @@ fn build_bookmarks_index(repo: &dyn Repo) -> CommitRefsIndex {
     index
 }
 
+/// Maximum number of ancestors to visit when looking up the closest ref.
+const CLOSEST_REF_SEARCH_LIMIT: usize = 1000;
+
+/// Memoized `(ref_name, distance)` of the closest ref by commit.
+pub type ClosestRefCache = RefCell<HashMap<CommitId, Option<(String, usize)>>>;
+
+/// Finds the ref closest to the given commit among its ancestors (including
+/// the commit itself.)
+///
+/// The ancestors are visited in breadth-first order up to
+/// `CLOSEST_REF_SEARCH_LIMIT` commits, so a far ancestor may not be found.
+fn find_closest_ref(
+    repo: &dyn Repo,
+    index: &CommitRefsIndex,
+    filter: impl Fn(&CommitRef) -> bool,
+    cache: &ClosestRefCache,
+    commit_id: &CommitId,
+) -> BackendResult<Option<(String, usize)>> {
+    if let Some(closest) = cache.borrow().get(commit_id) {
+        return Ok(closest.clone());
+    }
+    let mut closest: Option<(String, usize)> = None;
+    let mut visited = HashSet::from([commit_id.clone()]);
+    let mut queue = VecDeque::from([(commit_id.clone(), 0)]);
+    while let Some((id, distance)) = queue.pop_front() {
+        if closest.as_ref().is_some_and(|(_, d)| *d <= distance) {
+            break;
+        }
+        if let Some(commit_ref) = index.get(&id).iter().find(|commit_ref| filter(commit_ref)) {
+            closest = Some((commit_ref.name.to_string(), distance));
+            continue;
+        }
+        // The closest ref of a visited ancestor gives an upper bound. Refs
+        // reachable through that ancestor can't be closer than that.
+        if let Some(Some((name, d))) = cache.borrow().get(&id) {
+            if closest
+                .as_ref()
+                .is_none_or(|(_, best)| distance + d < *best)
+            {
+                closest = Some((name.clone(), distance + d));
+            }
+            continue;
+        }
+        if visited.len() >= CLOSEST_REF_SEARCH_LIMIT {
+            continue;
+        }
+        let commit = repo.store().get_commit(&id)?;
+        for parent_id in commit.parent_ids() {
+            if visited.insert(parent_id.clone()) {
+                queue.push_back((parent_id.clone(), distance + 1));
+            }
+        }
+    }
+    cache
+        .borrow_mut()
+        .insert(commit_id.clone(), closest.clone());
+    Ok(closest)
+}
+
+fn format_closest_ref(closest: Option<(String, usize)>) -> String {
+    match closest {
+        Some((name, 0)) => name,
+        Some((name, distance)) => format!("{name}~{distance}"),
+        None => String::new(),
+    }
+}
+
 fn build_commit_refs_index<'a, K: Into<String>>(
     ref_pairs: impl IntoIterator<Item = (K, &'a RefTarget)>,
 ) -> CommitRefsIndex {
@@ -1825,28 +2037,54 @@ where
                 })
                 .transpose()?;
             let repo = language.repo;
-            let index = match language.id_prefix_context.populate(repo) {
-                Ok(index) => index,
-                Err(err) => {
-                    // Not an error because we can still produce somewhat
-                    // reasonable output.
-                    diagnostics.add_warning(
-                        TemplateParseError::expression(
-                            "Failed to load short-prefixes index",
-                            function.name_span,
-                        )
-                        .with_source(err),
-                    );
-                    IdPrefixIndex::empty()
-                }
-            };
-            // The length of the id printed will be the maximum of the minimum
-            // `len` and the length of the shortest unique prefix.
-            let out_property = (self_property, len_property).map(move |(id, len)| {
-                let prefix_len = id.shortest_prefix_len(repo, &index);
-                let mut hex = format!("{id:.len$}", len = max(prefix_len, len.unwrap_or(0)));
-                let rest = hex.split_off(prefix_len);
-                ShortestIdPrefix { prefix: hex, rest }
+            let index = load_id_prefix_index(language, diagnostics, function.name_span);
+            let out_property = (self_property, len_property)
+                .map(move |(id, len)| shortest_id_prefix(repo, &index, &id, len));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "prefix_len",
+        |language, diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let index = load_id_prefix_index(language, diagnostics, function.name_span);
+            let out_property = self_property
+                .and_then(move |id| Ok(i64::try_from(id.shortest_prefix_len(repo, &index))?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "shortest_within",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let ([revset_node], [len_node]) = function.expect_arguments()?;
+            let (expression, span) =
+                template_parser::catch_aliases(diagnostics, revset_node, |diagnostics, node| {
+                    let text = template_parser::expect_string_literal(node)?;
+                    let expression = parse_user_revset(language, diagnostics, node.span, text)?;
+                    Ok((expression, node.span))
+                })?;
+            let len_property = len_node
+                .map(|node| {
+                    template_builder::expect_usize_expression(
+                        language,
+                        diagnostics,
+                        build_ctx,
+                        node,
+                    )
+                })
+                .transpose()?;
+            let repo = language.repo;
+            // Unlike .shortest(), the prefixes are only unique within the
+            // given revset, so the index is scoped to it.
+            let id_prefix_context = language.id_prefix_context.scoped_to(expression);
+            id_prefix_context.populate(repo).map_err(|err| {
+                TemplateParseError::expression("Failed to load short-prefixes index", span)
+                    .with_source(err)
+            })?;
+            let out_property = (self_property, len_property).and_then(move |(id, len)| {
+                let index = id_prefix_context.populate(repo)?;
+                Ok(shortest_id_prefix(repo, &index, &id, len))
             });
             Ok(out_property.into_dyn_wrapped())
         },
@@ -1854,6 +2092,39 @@ where
     map
 }
 
+fn load_id_prefix_index<'repo>(
+    language: &CommitTemplateLanguage<'repo>,
+    diagnostics: &mut TemplateDiagnostics,
+    span: pest::Span<'_>,
+) -> IdPrefixIndex<'repo> {
+    match language.id_prefix_context.populate(language.repo) {
+        Ok(index) => index,
+        Err(err) => {
+            // Not an error because we can still produce somewhat reasonable
+            // output.
+            diagnostics.add_warning(
+                TemplateParseError::expression("Failed to load short-prefixes index", span)
+                    .with_source(err),
+            );
+            IdPrefixIndex::empty()
+        }
+    }
+}
+
+fn shortest_id_prefix(
+    repo: &dyn Repo,
+    index: &IdPrefixIndex,
+    id: &(impl Display + ShortestIdPrefixLen),
+    len: Option<usize>,
+) -> ShortestIdPrefix {
+    // The length of the id printed will be the maximum of the minimum `len`
+    // and the length of the shortest unique prefix.
+    let prefix_len = id.shortest_prefix_len(repo, index);
+    let mut hex = format!("{id:.len$}", len = max(prefix_len, len.unwrap_or(0)));
+    let rest = hex.split_off(prefix_len);
+    ShortestIdPrefix { prefix: hex, rest }
+}
+
 #[derive(Clone, Debug, serde::Serialize)]
 pub struct ShortestIdPrefix {
     pub prefix: String,
@@ -1952,6 +2223,12 @@ impl TreeDiff {
         })
     }
 
+    /// Returns the attributes of the source and target trees.
+    fn tree_attributes(&self, attribute_overrides: &GitAttributes) -> [TreeAttributes; 2] {
+        [&self.from_tree, &self.to_tree]
+            .map(|tree| TreeAttributes::new(tree.clone(), attribute_overrides.clone()))
+    }
+
     fn diff_stream(&self) -> BoxStream<'_, CopiesTreeDiffEntry> {
         self.from_tree
             .diff_stream_with_copies(&self.to_tree, &*self.matcher, &self.copy_records)
@@ -2028,12 +2305,14 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     TemplateParseError::expression(message, function.name_span).with_source(err)
                 })?;
             let conflict_marker_style = language.conflict_marker_style;
+            let attribute_overrides = language.attribute_overrides;
             let template = (self_property, context_property)
                 .map(move |(diff, context)| {
                     let mut options = options.clone();
                     if let Some(context) = context {
                         options.context = context;
                     }
+                    let attributes = diff.tree_attributes(attribute_overrides);
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_color_words_diff(
                             formatter,
@@ -2042,6 +2321,7 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                             path_converter,
                             &options,
                             conflict_marker_style,
+                            attributes.each_ref(),
                         )
                         .block_on()
                     })
@@ -2070,12 +2350,14 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     TemplateParseError::expression(message, function.name_span).with_source(err)
                 })?;
             let conflict_marker_style = language.conflict_marker_style;
+            let attribute_overrides = language.attribute_overrides;
             let template = (self_property, context_property)
                 .map(move |(diff, context)| {
                     let mut options = options.clone();
                     if let Some(context) = context {
                         options.context = context;
                     }
+                    let attributes = diff.tree_attributes(attribute_overrides);
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_git_diff(
                             formatter,
@@ -2083,6 +2365,7 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                             tree_diff,
                             &options,
                             conflict_marker_style,
+                            attributes.each_ref(),
                         )
                         .block_on()
                     })
@@ -2273,6 +2556,35 @@ fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "conflict_side_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|entry| Ok(i64::try_from(entry.value.num_sides())?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "conflict_has_deletion",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property
+                .map(|entry| !entry.value.is_resolved() && entry.value.adds().any(Option::is_none));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "executable_conflict",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|entry| {
+                entry.value.to_executable_merge().is_some()
+                    && is_executable_file(&entry.value).is_none()
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -2522,6 +2834,7 @@ mod tests {
 
     use jj_lib::config::ConfigLayer;
     use jj_lib::config::ConfigSource;
+    use jj_lib::mailmap::Mailmap;
     use jj_lib::revset::RevsetAliasesMap;
     use jj_lib::revset::RevsetExpression;
     use jj_lib::revset::RevsetExtensions;
@@ -2551,6 +2864,8 @@ mod tests {
         revset_aliases_map: RevsetAliasesMap,
         template_aliases_map: TemplateAliasesMap,
         immutable_expression: Rc<UserRevsetExpression>,
+        mailmap: Mailmap,
+        attribute_overrides: GitAttributes,
         extra_functions: HashMap<&'static str, BuildFunctionFn>,
     }
 
@@ -2576,6 +2891,8 @@ mod tests {
                 revset_aliases_map: RevsetAliasesMap::new(),
                 template_aliases_map: TemplateAliasesMap::new(),
                 immutable_expression: RevsetExpression::none(),
+                mailmap: Mailmap::empty(),
+                attribute_overrides: GitAttributes::empty(),
                 extra_functions: HashMap::new(),
             }
         }
@@ -2596,12 +2913,15 @@ mod tests {
                 aliases_map: &self.revset_aliases_map,
                 local_variables: HashMap::new(),
                 user_email: "test.user@example.com",
+                user_email_aliases: &[],
                 date_pattern_context: chrono::DateTime::UNIX_EPOCH.fixed_offset().into(),
                 extensions: &self.revset_extensions,
                 workspace: Some(RevsetWorkspaceContext {
                     path_converter: &self.path_converter,
                     workspace_name: self.test_workspace.workspace.workspace_name(),
+                    repo_path: self.test_workspace.workspace.repo_path(),
                 }),
+                mailmap: &self.mailmap,
             };
             let mut language = CommitTemplateLanguage::new(
                 self.test_workspace.repo.as_ref(),
@@ -2611,6 +2931,7 @@ mod tests {
                 &self.id_prefix_context,
                 self.immutable_expression.clone(),
                 ConflictMarkerStyle::default(),
+                &self.attribute_overrides,
                 &[] as &[Box<dyn CommitTemplateLanguageExtension>],
             );
             // Not using .extend() to infer lifetime of f
@@ -21,6 +21,8 @@ use clap_complete::CompletionCandidate;
 use indoc::indoc;
 use itertools::Itertools as _;
 use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::fileset;
+use jj_lib::revset;
 use jj_lib::settings::UserSettings;
 use jj_lib::workspace::DefaultWorkspaceLoaderFactory;
 use jj_lib::workspace::WorkspaceLoaderFactory as _;
@@ -31,6 +33,7 @@ use crate::cli_util::load_template_aliases;
 use crate::cli_util::GlobalArgs;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateBuildFnTable;
 use crate::config::config_from_environment;
 use crate::config::default_config_layers;
 use crate::config::ConfigArgKind;
@@ -39,7 +42,9 @@ use crate::config::CONFIG_SCHEMA;
 use crate::merge_tools::configured_merge_tools;
 use crate::merge_tools::get_external_tool_config;
 use crate::merge_tools::ExternalMergeTool;
+use crate::operation_templater::OperationTemplateBuildFnTable;
 use crate::revset_util::load_revset_aliases;
+use crate::template_builder::TemplateSymbolNames;
 use crate::ui::Ui;
 
 const BOOKMARK_HELP_TEMPLATE: &str = r#"template-aliases.'bookmark_help()'='''
@@ -191,19 +196,119 @@ pub fn git_remotes() -> Vec<CompletionCandidate> {
     })
 }
 
-pub fn template_aliases() -> Vec<CompletionCandidate> {
+fn template_expression_with(
+    current: &std::ffi::OsStr,
+    keywords: impl FnOnce() -> Vec<&'static str>,
+) -> Vec<CompletionCandidate> {
+    // display order
+    const TEMPLATE_ALIAS: usize = 0;
+    const KEYWORD: usize = 1;
+    const FUNCTION: usize = 2;
+
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let Some((prepend, match_prefix)) = split_template_trailing_name(current) else {
+        return Vec::new();
+    };
     with_jj(|_, settings| {
-        let Ok(template_aliases) = load_template_aliases(&Ui::null(), settings.config()) else {
-            return Ok(Vec::new());
-        };
-        Ok(template_aliases
-            .symbol_names()
-            .map(CompletionCandidate::new)
-            .sorted()
+        let mut candidates = Vec::new();
+        if let Ok(template_aliases) = load_template_aliases(&Ui::null(), settings.config()) {
+            candidates.extend(
+                template_aliases
+                    .symbol_names()
+                    .filter(|name| name.starts_with(match_prefix))
+                    .sorted()
+                    .map(|name| CompletionCandidate::new(name).display_order(Some(TEMPLATE_ALIAS))),
+            );
+        }
+        // Listing all keywords and functions for an empty input would bury
+        // the aliases.
+        if !match_prefix.is_empty() {
+            candidates.extend(
+                keywords()
+                    .into_iter()
+                    .filter(|name| name.starts_with(match_prefix))
+                    .map(|name| {
+                        CompletionCandidate::new(name)
+                            .help(Some("Keyword".into()))
+                            .display_order(Some(KEYWORD))
+                    }),
+            );
+            let functions = CommitTemplateBuildFnTable::builtin()
+                .symbol_names()
+                .functions;
+            candidates.extend(
+                functions
+                    .into_iter()
+                    .filter(|name| name.starts_with(match_prefix))
+                    .map(|name| {
+                        CompletionCandidate::new(format!("{name}("))
+                            .help(Some("Template function".into()))
+                            .display_order(Some(FUNCTION))
+                    }),
+            );
+        }
+        Ok(candidates
+            .into_iter()
+            .map(|candidate| candidate.add_prefix(prepend))
             .collect())
     })
 }
 
+/// Completes template aliases and functions, and also keywords once a partial
+/// name is typed.
+pub fn template_expression(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
+    template_expression_with(current, Vec::new)
+}
+
+pub fn commit_template_expression(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
+    template_expression_with(current, || {
+        template_method_names(
+            CommitTemplateBuildFnTable::builtin().symbol_names(),
+            "Commit",
+        )
+    })
+}
+
+pub fn operation_template_expression(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
+    template_expression_with(current, || {
+        template_method_names(
+            OperationTemplateBuildFnTable::builtin().symbol_names(),
+            "Operation",
+        )
+    })
+}
+
+fn template_method_names(names: TemplateSymbolNames, type_name: &str) -> Vec<&'static str> {
+    names
+        .methods
+        .into_iter()
+        .find(|(name, _)| *name == type_name)
+        .map(|(_, methods)| methods)
+        .unwrap_or_default()
+}
+
+/// Identifies if an incomplete template expression ends with a name that
+/// can be completed as a keyword, function, or alias.
+///
+/// Returns a tuple that splits the string at the point the name starts, or
+/// `None` if the name is a method name or inside a string literal.
+fn split_template_trailing_name(incomplete_template_str: &str) -> Option<(&str, &str)> {
+    let name_len = incomplete_template_str
+        .chars()
+        .rev()
+        .take_while(|&c| c.is_alphanumeric() || c == '_')
+        .map(char::len_utf8)
+        .sum::<usize>();
+    let (prepend, name) =
+        incomplete_template_str.split_at(incomplete_template_str.len() - name_len);
+    // Methods can't be completed without knowing the type of the object.
+    let is_method = prepend.trim_ascii_end().ends_with('.');
+    let in_string = prepend.chars().filter(|&c| c == '"').count() % 2 == 1;
+    (!is_method && !in_string).then_some((prepend, name))
+}
+
 pub fn aliases() -> Vec<CompletionCandidate> {
     with_jj(|_, settings| {
         Ok(settings
@@ -218,7 +323,56 @@ pub fn aliases() -> Vec<CompletionCandidate> {
     })
 }
 
-fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionCandidate> {
+/// Kinds of names that can be completed at some position in a revset
+/// expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RevsetNameKind {
+    /// Any revision, alias, or function name.
+    Any,
+    /// Bookmark name without remote part.
+    Bookmark,
+    /// Tag name.
+    Tag,
+}
+
+/// Functions taking bookmark name patterns as arguments.
+const BOOKMARK_NAME_FUNCTIONS: &[&str] = &[
+    "bookmarks",
+    "remote_bookmarks",
+    "tracked_remote_bookmarks",
+    "untracked_remote_bookmarks",
+];
+
+/// Functions taking tag name patterns as arguments.
+const TAG_NAME_FUNCTIONS: &[&str] = &["tags"];
+
+/// Functions taking arguments other than revsets or names, such as text
+/// patterns or filesets.
+const NON_REVSET_ARG_FUNCTIONS: &[&str] = &[
+    "author",
+    "author_date",
+    "author_email",
+    "author_name",
+    "change_id",
+    "commit_id",
+    "committer",
+    "committer_date",
+    "committer_email",
+    "committer_name",
+    "conflicts",
+    "description",
+    "diff_contains",
+    "files",
+    "subject",
+    "touches",
+    "trailer",
+];
+
+fn revisions(
+    match_prefix: &str,
+    revset_filter: Option<&str>,
+    kind: RevsetNameKind,
+) -> Vec<CompletionCandidate> {
     with_jj(|jj, settings| {
         // display order
         const LOCAL_BOOKMARK: usize = 0;
@@ -226,43 +380,68 @@ fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionC
         const CHANGE_ID: usize = 2;
         const REMOTE_BOOKMARK: usize = 3;
         const REVSET_ALIAS: usize = 4;
+        const REVSET_FUNCTION: usize = 5;
 
         let mut candidates = Vec::new();
 
         // bookmarks
 
-        let mut cmd = jj.build();
-        cmd.arg("bookmark")
-            .arg("list")
-            .arg("--all-remotes")
-            .arg("--config")
-            .arg(BOOKMARK_HELP_TEMPLATE)
-            .arg("--template")
-            .arg(
-                r#"if(remote != "git", name ++ if(remote, "@" ++ remote) ++ bookmark_help() ++ "\n")"#,
+        if kind == RevsetNameKind::Bookmark {
+            // Bookmark name patterns don't include the remote part
+            let output = jj
+                .build()
+                .arg("bookmark")
+                .arg("list")
+                .arg("--all-remotes")
+                .arg("--template")
+                .arg(r#"if(remote != "git", name ++ "\n")"#)
+                .output()
+                .map_err(user_error)?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            candidates.extend(
+                stdout
+                    .lines()
+                    .filter(|name| name.starts_with(match_prefix))
+                    .unique()
+                    .map(|name| CompletionCandidate::new(name).display_order(Some(LOCAL_BOOKMARK))),
             );
-        if let Some(revs) = revset_filter {
-            cmd.arg("--revisions").arg(revs);
+            return Ok(candidates);
         }
-        let output = cmd.output().map_err(user_error)?;
-        let stdout = String::from_utf8_lossy(&output.stdout);
 
-        candidates.extend(
-            stdout
-                .lines()
-                .map(split_help_text)
-                .filter(|(bookmark, _)| bookmark.starts_with(match_prefix))
-                .map(|(bookmark, help)| {
-                    let local = !bookmark.contains('@');
-                    let display_order = match local {
-                        true => LOCAL_BOOKMARK,
-                        false => REMOTE_BOOKMARK,
-                    };
-                    CompletionCandidate::new(bookmark)
-                        .help(help)
-                        .display_order(Some(display_order))
-                }),
-        );
+        if kind == RevsetNameKind::Any {
+            let mut cmd = jj.build();
+            cmd.arg("bookmark")
+                .arg("list")
+                .arg("--all-remotes")
+                .arg("--config")
+                .arg(BOOKMARK_HELP_TEMPLATE)
+                .arg("--template")
+                .arg(
+                    r#"if(remote != "git", name ++ if(remote, "@" ++ remote) ++ bookmark_help() ++ "\n")"#,
+                );
+            if let Some(revs) = revset_filter {
+                cmd.arg("--revisions").arg(revs);
+            }
+            let output = cmd.output().map_err(user_error)?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+
+            candidates.extend(
+                stdout
+                    .lines()
+                    .map(split_help_text)
+                    .filter(|(bookmark, _)| bookmark.starts_with(match_prefix))
+                    .map(|(bookmark, help)| {
+                        let local = !bookmark.contains('@');
+                        let display_order = match local {
+                            true => LOCAL_BOOKMARK,
+                            false => REMOTE_BOOKMARK,
+                        };
+                        CompletionCandidate::new(bookmark)
+                            .help(help)
+                            .display_order(Some(display_order))
+                    }),
+            );
+        }
 
         // tags
 
@@ -270,7 +449,7 @@ fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionC
         // immutable tags for mutable revision args, we skip tags entirely if
         // revset_filter is set. This is not a big loss, since tags usually point
         // to immutable revisions anyway.
-        if revset_filter.is_none() {
+        if revset_filter.is_none() || kind == RevsetNameKind::Tag {
             let output = jj
                 .build()
                 .arg("tag")
@@ -292,6 +471,10 @@ fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionC
             }));
         }
 
+        if kind == RevsetNameKind::Tag {
+            return Ok(candidates);
+        }
+
         // change IDs
 
         let revisions = revset_filter
@@ -343,6 +526,22 @@ fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionC
                 }),
         );
 
+        // revset functions
+
+        // Listing all functions for an empty input would bury the revisions.
+        if !match_prefix.is_empty() {
+            candidates.extend(
+                revset::builtin_function_names()
+                    .into_iter()
+                    .filter(|name| name.starts_with(match_prefix))
+                    .map(|name| {
+                        CompletionCandidate::new(format!("{name}("))
+                            .help(Some("Revset function".into()))
+                            .display_order(Some(REVSET_FUNCTION))
+                    }),
+            );
+        }
+
         Ok(candidates)
     })
 }
@@ -355,7 +554,21 @@ fn revset_expression(
         return Vec::new();
     };
     let (prepend, match_prefix) = split_revset_trailing_name(current).unwrap_or(("", current));
-    let candidates = revisions(match_prefix, revset_filter);
+    let kind = match revset_name_context(prepend) {
+        RevsetNameContext::TopLevel => RevsetNameKind::Any,
+        RevsetNameContext::FunctionArgument(name) if BOOKMARK_NAME_FUNCTIONS.contains(&name) => {
+            RevsetNameKind::Bookmark
+        }
+        RevsetNameContext::FunctionArgument(name) if TAG_NAME_FUNCTIONS.contains(&name) => {
+            RevsetNameKind::Tag
+        }
+        RevsetNameContext::FunctionArgument(name) if NON_REVSET_ARG_FUNCTIONS.contains(&name) => {
+            return Vec::new();
+        }
+        RevsetNameContext::FunctionArgument(_) => RevsetNameKind::Any,
+        RevsetNameContext::StringLiteral => return Vec::new(),
+    };
+    let candidates = revisions(match_prefix, revset_filter, kind);
     if prepend.is_empty() {
         candidates
     } else {
@@ -402,6 +615,62 @@ fn split_revset_trailing_name(incomplete_revset_str: &str) -> Option<(&str, &str
         .then(|| incomplete_revset_str.split_at(incomplete_revset_str.len() - final_part.len()))
 }
 
+/// Where the end of an incomplete revset expression is located.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RevsetNameContext<'a> {
+    /// Top level, or inside parentheses which aren't a function call.
+    TopLevel,
+    /// Inside the argument list of the named function.
+    FunctionArgument(&'a str),
+    /// Inside an unterminated string literal.
+    StringLiteral,
+}
+
+/// Determines the innermost function call or string literal enclosing the end
+/// of an incomplete revset expression.
+///
+/// Like [`split_revset_trailing_name()`], this doesn't validate the
+/// expression.
+fn revset_name_context(incomplete_revset_str: &str) -> RevsetNameContext<'_> {
+    let mut functions: Vec<Option<&str>> = Vec::new();
+    let mut chars = incomplete_revset_str.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '(' => {
+                let before = incomplete_revset_str[..i].trim_ascii_end();
+                let name_start = before
+                    .trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_')
+                    .len();
+                let name = &before[name_start..];
+                functions.push((!name.is_empty()).then_some(name));
+            }
+            ')' => {
+                functions.pop();
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut terminated = false;
+                while let Some((_, c)) = chars.next() {
+                    if c == quote {
+                        terminated = true;
+                        break;
+                    } else if c == '\\' && quote == '"' {
+                        chars.next();
+                    }
+                }
+                if !terminated {
+                    return RevsetNameContext::StringLiteral;
+                }
+            }
+            _ => {}
+        }
+    }
+    match functions.last() {
+        Some(Some(name)) => RevsetNameContext::FunctionArgument(name),
+        _ => RevsetNameContext::TopLevel,
+    }
+}
+
 pub fn operations() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
         let output = jj
@@ -706,10 +975,25 @@ fn all_files_from_rev(rev: String, current: &std::ffi::OsStr) -> Vec<CompletionC
                 CompletionCandidate::new(path)
             })
             .dedup() // directories may occur multiple times
+            .chain(fileset_functions(current))
             .collect())
     })
 }
 
+fn fileset_functions(current: &str) -> Vec<CompletionCandidate> {
+    // Listing all functions for an empty input would bury the file paths.
+    if current.is_empty() {
+        return Vec::new();
+    }
+    fileset::builtin_function_names()
+        .into_iter()
+        .filter(|name| name.starts_with(current))
+        .map(|name| {
+            CompletionCandidate::new(format!("{name}()")).help(Some("Fileset function".into()))
+        })
+        .collect()
+}
+
 fn modified_files_from_rev_with_jj_cmd(
     rev: (String, Option<String>),
     mut cmd: std::process::Command,
@@ -1186,6 +1470,41 @@ mod tests {
         assert_eq!(split_revset_trailing_name("all: f"), Some(("all: ", "f")));
     }
 
+    #[test]
+    fn test_revset_name_context() {
+        use RevsetNameContext::*;
+        assert_eq!(revset_name_context(""), TopLevel);
+        assert_eq!(revset_name_context("foo | "), TopLevel);
+        assert_eq!(revset_name_context("("), TopLevel);
+        assert_eq!(revset_name_context("::("), TopLevel);
+        assert_eq!(revset_name_context("foo("), FunctionArgument("foo"));
+        assert_eq!(revset_name_context("foo ("), FunctionArgument("foo"));
+        assert_eq!(revset_name_context("foo(x, "), FunctionArgument("foo"));
+        assert_eq!(revset_name_context("foo(bar()"), FunctionArgument("foo"));
+        assert_eq!(
+            revset_name_context("foo(bar(x) | "),
+            FunctionArgument("foo")
+        );
+        assert_eq!(revset_name_context("foo(bar(x))"), TopLevel);
+        assert_eq!(revset_name_context("foo((bar | "), TopLevel);
+        assert_eq!(revset_name_context("x::foo("), FunctionArgument("foo"));
+        assert_eq!(revset_name_context("all:foo("), FunctionArgument("foo"));
+
+        assert_eq!(revset_name_context(r#"foo(""#), StringLiteral);
+        assert_eq!(revset_name_context(r#"foo("a(b"#), StringLiteral);
+        assert_eq!(revset_name_context(r#"foo("a\"b"#), StringLiteral);
+        assert_eq!(revset_name_context("foo('a"), StringLiteral);
+        assert_eq!(revset_name_context(r#"foo("a(b") | "#), TopLevel);
+        assert_eq!(
+            revset_name_context(r#"foo("a\"b", "#),
+            FunctionArgument("foo")
+        );
+        assert_eq!(
+            revset_name_context(r#"foo('a\', "#),
+            FunctionArgument("foo")
+        );
+    }
+
     #[test]
     fn test_config_keys() {
         // Just make sure the schema is parsed without failure.
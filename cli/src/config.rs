@@ -86,6 +86,37 @@ pub struct AnnotatedValue {
     pub is_overridden: bool,
 }
 
+impl AnnotatedValue {
+    /// Returns true if the value is likely a secret, such as `*.token`,
+    /// `*.api-token`, or `*.smtp_password`.
+    pub fn is_secret(&self) -> bool {
+        self.name
+            .components()
+            .next_back()
+            .is_some_and(|key| is_secret_key(key.get()))
+    }
+
+    /// Replaces the value with a placeholder if it is likely a secret.
+    pub fn redact_secret(&mut self) {
+        if self.is_secret() {
+            self.value = ConfigValue::from(REDACTED_CONFIG_VALUE);
+        }
+    }
+}
+
+/// Config keys whose values shouldn't be printed by default.
+const SECRET_CONFIG_KEYS: &[&str] = &["token", "password"];
+
+const REDACTED_CONFIG_VALUE: &str = "<redacted>";
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_CONFIG_KEYS.iter().any(|secret| {
+        key.strip_suffix(secret)
+            .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with(['-', '_']))
+    })
+}
+
 /// Collects values under the given `filter_prefix` name recursively, from all
 /// layers.
 pub fn resolved_config_values(
@@ -491,15 +522,18 @@ impl ConfigEnv {
         Ok(())
     }
 
-    /// Resolves conditional scopes within the current environment. Returns new
-    /// resolved config.
+    /// Resolves conditional scopes and `${env:NAME}` environment variables
+    /// within the current environment. Returns new resolved config.
     pub fn resolve_config(&self, config: &RawConfig) -> Result<StackedConfig, ConfigGetError> {
         let context = ConfigResolutionContext {
             home_dir: self.home_dir.as_deref(),
             repo_path: self.repo_path.as_deref(),
             command: self.command.as_deref(),
         };
-        jj_lib::config::resolve(config.as_ref(), &context)
+        let config = jj_lib::config::resolve(config.as_ref(), &context)?;
+        Ok(jj_lib::config::interpolate_env_vars(&config, |name| {
+            env::var(name).ok()
+        }))
     }
 }
 
@@ -7,19 +7,24 @@ use std::path::PathBuf;
 use std::process::ExitStatus;
 
 use bstr::ByteVec as _;
+use futures::StreamExt as _;
 use indexmap::IndexMap;
 use indoc::indoc;
 use itertools::FoldWhile;
 use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::DetachedCommitBuilder;
 use jj_lib::config::ConfigGetError;
 use jj_lib::file_util::IoResultExt as _;
 use jj_lib::file_util::PathError;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
 use jj_lib::trailer::parse_description_trailers;
 use jj_lib::trailer::parse_trailers;
+use pollster::FutureExt as _;
 use thiserror::Error;
 
 use crate::cli_util::short_commit_hash;
@@ -184,11 +189,14 @@ pub fn edit_description(editor: &TextEditor, description: &str) -> Result<String
 }
 
 /// Edits the descriptions of the given commits in a single editor session.
+///
+/// Each draft description is rendered by the `template_text`.
 pub fn edit_multiple_descriptions(
     ui: &Ui,
     editor: &TextEditor,
     tx: &WorkspaceCommandTransaction,
     commits: &[(&CommitId, Commit)],
+    template_text: &str,
 ) -> Result<ParsedBulkEditMessage<CommitId>, CommandError> {
     let mut commits_map = IndexMap::new();
     let mut bulk_message = String::new();
@@ -207,7 +215,7 @@ pub fn edit_multiple_descriptions(
         bulk_message.push_str(" -------\n");
         commits_map.insert(commit_hash, *commit_id);
         let intro = "";
-        let template = description_template(ui, tx, intro, temp_commit)?;
+        let template = description_template_with_text(ui, tx, intro, temp_commit, template_text)?;
         bulk_message.push_str(&template);
         append_blank_line(&mut bulk_message);
     }
@@ -437,6 +445,56 @@ pub fn add_trailers(
     }
 }
 
+/// Trailer key used to record the topic of a commit.
+pub const TOPIC_TRAILER_KEY: &str = "Topic";
+
+/// Returns the topic recorded in the trailers of the `description`.
+///
+/// If there are multiple topic trailers, the last one wins.
+pub fn topic_from_description(description: &str) -> Option<String> {
+    parse_description_trailers(description)
+        .into_iter()
+        .rev()
+        .find(|trailer| trailer.key == TOPIC_TRAILER_KEY)
+        .map(|trailer| trailer.value)
+}
+
+/// Replaces the topic trailers of the `description` with the given `topic`.
+///
+/// If `topic` is `None`, existing topic trailers are removed.
+pub fn set_description_topic(description: &str, topic: Option<&str>) -> String {
+    let mut lines = description.lines().collect_vec();
+    if !parse_description_trailers(description).is_empty() {
+        let start = lines
+            .iter()
+            .rposition(|line| line.trim().is_empty())
+            .map_or(0, |pos| pos + 1);
+        let trailer_lines = lines.split_off(start);
+        lines.extend(trailer_lines.into_iter().filter(|line| {
+            !line
+                .split_once(':')
+                .is_some_and(|(key, _)| key.trim_end() == TOPIC_TRAILER_KEY)
+        }));
+    }
+    // Drop the separator of the trailer paragraph if it becomes empty.
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let mut new_description: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    if let Some(topic) = topic {
+        if parse_description_trailers(&new_description).is_empty() {
+            if new_description.is_empty() {
+                // a first empty line where the user will edit the commit summary
+                new_description.push('\n');
+            }
+            // create a new paragraph for the trailer
+            new_description.push('\n');
+        }
+        new_description.push_str(&format!("{TOPIC_TRAILER_KEY}: {topic}\n"));
+    }
+    new_description
+}
+
 /// Renders commit description template, which will be edited by user.
 pub fn description_template(
     ui: &Ui,
@@ -444,15 +502,47 @@ pub fn description_template(
     intro: &str,
     commit: &Commit,
 ) -> Result<String, CommandError> {
+    let template_text = draft_description_template_text(tx.settings())?;
+    description_template_with_text(ui, tx, intro, commit, &template_text)
+}
+
+/// Returns the template text of `templates.draft_commit_description`.
+pub fn draft_description_template_text(settings: &UserSettings) -> Result<String, ConfigGetError> {
     // Named as "draft" because the output can contain "JJ:" comment lines.
-    let template_key = "templates.draft_commit_description";
-    let template_text = tx.settings().get_string(template_key)?;
-    let template = tx.parse_commit_template(ui, &template_text)?;
+    settings.get_string("templates.draft_commit_description")
+}
 
+/// Renders the given draft description template, which will be edited by
+/// user.
+///
+/// If the commit changes more files than `describe.draft-max-files`, the
+/// builtin draft template is used instead so that a custom template doesn't
+/// have to render a huge diff.
+pub fn description_template_with_text(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    intro: &str,
+    commit: &Commit,
+    template_text: &str,
+) -> Result<String, CommandError> {
+    let max_files: usize = tx.settings().get("describe.draft-max-files")?;
     let mut output = Vec::new();
     if !intro.is_empty() {
         writeln!(output, "JJ: {intro}").unwrap();
     }
+    let template_text = if template_text != BUILTIN_DRAFT_TEMPLATE
+        && count_changed_files(tx.repo(), commit, max_files.saturating_add(1))? > max_files
+    {
+        writeln!(
+            output,
+            "JJ: This commit changes more than {max_files} files; using the builtin draft template."
+        )
+        .unwrap();
+        BUILTIN_DRAFT_TEMPLATE
+    } else {
+        template_text
+    };
+    let template = tx.parse_commit_template(ui, template_text)?;
     template
         .format(commit, &mut PlainTextFormatter::new(&mut output))
         .expect("write() to vec backed formatter should never fail");
@@ -460,6 +550,19 @@ pub fn description_template(
     Ok(output.into_string_lossy())
 }
 
+const BUILTIN_DRAFT_TEMPLATE: &str = "builtin_draft_commit_description";
+
+/// Counts the files changed by the commit, stopping at `limit`.
+fn count_changed_files(repo: &dyn Repo, commit: &Commit, limit: usize) -> BackendResult<usize> {
+    let from_tree = commit.parent_tree(repo)?;
+    let to_tree = commit.tree()?;
+    Ok(from_tree
+        .diff_stream(&to_tree, &EverythingMatcher)
+        .take(limit)
+        .count()
+        .block_on())
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::indexmap;
@@ -467,6 +570,8 @@ mod tests {
     use maplit::hashmap;
 
     use super::parse_bulk_edit_message;
+    use super::set_description_topic;
+    use super::topic_from_description;
     use crate::description_util::ParseBulkEditMessageError;
 
     #[test]
@@ -619,4 +724,31 @@ mod tests {
         assert!(result.duplicates.is_empty());
         assert!(result.unexpected.is_empty());
     }
+
+    #[test]
+    fn test_set_description_topic() {
+        assert_eq!(set_description_topic("", Some("foo")), "\n\nTopic: foo\n");
+        assert_eq!(
+            set_description_topic("subject\n", Some("foo")),
+            "subject\n\nTopic: foo\n"
+        );
+        assert_eq!(
+            set_description_topic("subject\n\nTopic: foo\nSigned-off-by: me\n", Some("bar")),
+            "subject\n\nSigned-off-by: me\nTopic: bar\n"
+        );
+        assert_eq!(
+            set_description_topic("subject\n\nbody\n\nTopic: foo\n", None),
+            "subject\n\nbody\n"
+        );
+        // A single paragraph isn't a trailer
+        assert_eq!(
+            set_description_topic("Topic: foo\n", Some("bar")),
+            "Topic: foo\n\nTopic: bar\n"
+        );
+        assert_eq!(
+            topic_from_description("subject\n\nTopic: foo\nTopic: bar\n"),
+            Some("bar".to_owned())
+        );
+        assert_eq!(topic_from_description("Topic: foo\n"), None);
+    }
 }
@@ -0,0 +1,212 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Introspection of the build and the environment, used by `jj version
+//! --verbose` and `jj debug env` to help with bug reports.
+
+use std::env;
+use std::io;
+use std::io::Write;
+
+use itertools::Itertools as _;
+use jj_lib::fsmonitor::FsmonitorSettings;
+
+use crate::cli_util::CommandHelper;
+
+/// Cargo features of `jj-cli`, and whether they are enabled in this build.
+const CARGO_FEATURES: &[(&str, bool)] = &[
+    ("git", cfg!(feature = "git")),
+    ("syntax-highlight", cfg!(feature = "syntax-highlight")),
+    ("test-fakes", cfg!(feature = "test-fakes")),
+    ("watchman", cfg!(feature = "watchman")),
+];
+
+/// Environment variables which change the behavior of jj.
+const ENV_VARS: &[&str] = &[
+    "JJ_CONFIG",
+    "JJ_EDITOR",
+    "JJ_EMAIL",
+    "JJ_LOG",
+    "JJ_OP_HOSTNAME",
+    "JJ_OP_TIMESTAMP",
+    "JJ_OP_USERNAME",
+    "JJ_RANDOMNESS_SEED",
+    "JJ_TIMESTAMP",
+    "JJ_TRACE",
+    "JJ_TZ_OFFSET_MINS",
+    "JJ_USER",
+];
+
+/// Environment variables which are meant to be set only by tests.
+const TESTING_ENV_VARS: &[&str] = &[
+    "JJ_OP_TIMESTAMP",
+    "JJ_RANDOMNESS_SEED",
+    "JJ_TIMESTAMP",
+    "JJ_TZ_OFFSET_MINS",
+];
+
+/// Probes checking the environment for problems. Each probe adds the
+/// problems it finds to the list.
+const PROBES: &[fn(&CommandHelper, &mut Vec<Diagnostic>)] = &[
+    check_user_identity,
+    check_fsmonitor,
+    check_git_executable,
+    check_testing_env_vars,
+];
+
+/// Problem found in the environment.
+#[derive(Clone, Debug)]
+pub(crate) struct Diagnostic {
+    pub message: String,
+    /// How the problem can be fixed.
+    pub hint: Option<String>,
+}
+
+/// Writes the features enabled in this build and the registered backend
+/// implementations.
+pub(crate) fn write_build_info(out: &mut dyn Write, command: &CommandHelper) -> io::Result<()> {
+    let features = CARGO_FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name)
+        .join(", ");
+    let backends = command
+        .store_factories()
+        .backend_names()
+        .sorted()
+        .join(", ");
+    let working_copies = command.working_copy_factories().keys().sorted().join(", ");
+    let fsmonitors = if cfg!(feature = "watchman") {
+        "watchman"
+    } else {
+        "none"
+    };
+    writeln!(out, "Features: {features}")?;
+    writeln!(out, "Backends: {backends}")?;
+    writeln!(out, "Working copies: {working_copies}")?;
+    writeln!(out, "Fsmonitors: {fsmonitors}")?;
+    Ok(())
+}
+
+/// Returns the names of the environment variables affecting jj which are set.
+pub(crate) fn env_vars_set() -> Vec<&'static str> {
+    ENV_VARS
+        .iter()
+        .copied()
+        .filter(|name| env::var_os(name).is_some())
+        .collect()
+}
+
+/// Runs all probes, and returns the problems found.
+pub(crate) fn check_environment(command: &CommandHelper) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for probe in PROBES {
+        probe(command, &mut diagnostics);
+    }
+    diagnostics
+}
+
+fn check_user_identity(command: &CommandHelper, diagnostics: &mut Vec<Diagnostic>) {
+    let settings = command.settings();
+    if settings.user_name().is_empty() {
+        diagnostics.push(Diagnostic {
+            message: "Name not configured.".to_owned(),
+            hint: Some(r#"Run `jj config set --user user.name "Some One"`."#.to_owned()),
+        });
+    }
+    if settings.user_email().is_empty() {
+        diagnostics.push(Diagnostic {
+            message: "Email not configured.".to_owned(),
+            hint: Some(
+                r#"Run `jj config set --user user.email "someone@example.com"`."#.to_owned(),
+            ),
+        });
+    }
+}
+
+fn check_fsmonitor(command: &CommandHelper, diagnostics: &mut Vec<Diagnostic>) {
+    match command.settings().fsmonitor_settings() {
+        Ok(FsmonitorSettings::Watchman(_)) if !cfg!(feature = "watchman") => {
+            diagnostics.push(Diagnostic {
+                message: "`fsmonitor.backend` is set to `watchman`, but jj was built without \
+                          the `watchman` feature."
+                    .to_owned(),
+                hint: Some("Set `fsmonitor.backend` to `none`.".to_owned()),
+            });
+        }
+        Ok(_) => {}
+        Err(err) => {
+            diagnostics.push(Diagnostic {
+                message: format!("Invalid fsmonitor config: {err}"),
+                hint: None,
+            });
+        }
+    }
+}
+
+#[cfg(feature = "git")]
+fn check_git_executable(command: &CommandHelper, diagnostics: &mut Vec<Diagnostic>) {
+    use std::process::Command;
+    use std::process::Stdio;
+
+    let git_settings = match command.settings().git_settings() {
+        Ok(git_settings) => git_settings,
+        Err(err) => {
+            diagnostics.push(Diagnostic {
+                message: format!("Invalid Git config: {err}"),
+                hint: None,
+            });
+            return;
+        }
+    };
+    let path = &git_settings.executable_path;
+    let status = Command::new(path)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if let Err(err) = status {
+        diagnostics.push(Diagnostic {
+            message: format!(
+                "Git executable `{}` could not be run: {err}",
+                path.display()
+            ),
+            hint: Some(
+                "Git remotes can't be fetched from or pushed to. Set `git.executable-path` to \
+                 the path of a Git executable."
+                    .to_owned(),
+            ),
+        });
+    }
+}
+
+#[cfg(not(feature = "git"))]
+fn check_git_executable(_command: &CommandHelper, _diagnostics: &mut Vec<Diagnostic>) {}
+
+fn check_testing_env_vars(_command: &CommandHelper, diagnostics: &mut Vec<Diagnostic>) {
+    let names = TESTING_ENV_VARS
+        .iter()
+        .filter(|name| env::var_os(name).is_some())
+        .join(", ");
+    if !names.is_empty() {
+        diagnostics.push(Diagnostic {
+            message: format!("Environment variables meant for testing are set: {names}"),
+            hint: Some(
+                "These make timestamps and ids deterministic. Unset them unless you're running \
+                 tests."
+                    .to_owned(),
+            ),
+        });
+    }
+}
@@ -14,12 +14,14 @@
 
 use std::borrow::Borrow;
 use std::cmp::max;
+use std::collections::HashSet;
 use std::io;
 use std::iter;
 use std::mem;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::LazyLock;
 
 use bstr::BStr;
 use bstr::BString;
@@ -37,6 +39,7 @@ use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
 use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::conflicts::materialized_diff_stream;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::MaterializedFileValue;
@@ -58,7 +61,17 @@ use jj_lib::files::ConflictDiffHunk;
 use jj_lib::files::DiffLineHunkSide;
 use jj_lib::files::DiffLineIterator;
 use jj_lib::files::DiffLineNumber;
+use jj_lib::fileset;
+use jj_lib::fileset::FilesetDiagnostics;
+use jj_lib::fileset::FilesetExpression;
+use jj_lib::gitattributes::GitAttributes;
+use jj_lib::gitattributes::TreeAttributes;
+use jj_lib::matchers::DifferenceMatcher;
+use jj_lib::matchers::FilesMatcher;
+use jj_lib::matchers::IntersectionMatcher;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
+use jj_lib::matchers::UnionMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
@@ -66,6 +79,7 @@ use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::InvalidRepoPathError;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
@@ -87,13 +101,14 @@ use crate::merge_tools::new_utf8_temp_dir;
 use crate::merge_tools::DiffGenerateError;
 use crate::merge_tools::DiffToolMode;
 use crate::merge_tools::ExternalMergeTool;
+use crate::syntax_highlight::SyntaxHighlights;
 use crate::templater::TemplateRenderer;
 use crate::text_util;
 use crate::ui::Ui;
 
 #[derive(clap::Args, Clone, Debug)]
 #[command(next_help_heading = "Diff Formatting Options")]
-#[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
+#[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "numstat", "types", "name_only", "name_status"])))]
 #[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or deleted
@@ -102,6 +117,14 @@ pub struct DiffFormatArgs {
     /// Show a histogram of the changes
     #[arg(long)]
     pub stat: bool,
+    /// For each path, show the number of added and deleted lines
+    ///
+    /// Each line consists of the number of inserted lines, a tab, the number
+    /// of deleted lines, a tab, and the path, like `git diff --numstat`.
+    /// Renames and copies are shown as `{old => new}`, or as the source and
+    /// target paths separated by NUL if --null is specified.
+    #[arg(long)]
+    pub numstat: bool,
     /// For each path, show only its type before and after
     ///
     /// The diff is shown as two letters. The first letter indicates the type
@@ -117,6 +140,23 @@ pub struct DiffFormatArgs {
     ///    `jj diff -r @- --name-only | xargs perl -pi -e's/OLD/NEW/g`
     #[arg(long)]
     pub name_only: bool,
+    /// For each path, show only its status and path
+    ///
+    /// Each line consists of a status letter ('M' for modified, 'A' for added,
+    /// 'D' for deleted, 'R' for renamed, 'C' for copied), a tab, and the
+    /// path. For renames and copies, the source and target paths are
+    /// separated by a tab.
+    #[arg(long)]
+    pub name_status: bool,
+    /// Terminate each entry with NUL instead of newline
+    ///
+    /// For --name-status, fields are also separated by NUL instead of tab.
+    /// Can only be used with --numstat, --name-only, and --name-status.
+    #[arg(
+        long,
+        conflicts_with_all = ["summary", "stat", "types", "git", "color_words"],
+    )]
+    pub null: bool,
     /// Show a Git-format diff
     #[arg(long)]
     pub git: bool,
@@ -135,6 +175,12 @@ pub struct DiffFormatArgs {
     /// Number of lines of context to show
     #[arg(long)]
     context: Option<usize>,
+    /// Show the whole enclosing function as context for each change
+    ///
+    /// Function boundaries are detected heuristically based on the file
+    /// extension. Only applies to --git.
+    #[arg(long)]
+    function_context: bool,
 
     // Short flags are set by command to avoid future conflicts.
     /// Ignore whitespace when comparing lines.
@@ -150,19 +196,45 @@ pub enum DiffFormat {
     // Non-trivial parameters are boxed in order to keep the variants small
     Summary,
     Stat(Box<DiffStatOptions>),
+    NumStat(Box<DiffStatOptions>, NameListOptions),
     Types,
-    NameOnly,
+    NameOnly(NameListOptions),
+    NameStatus(NameListOptions),
     Git(Box<UnifiedDiffOptions>),
     ColorWords(Box<ColorWordsDiffOptions>),
     Tool(Box<ExternalMergeTool>),
 }
 
+impl DiffFormat {
+    /// Returns true if this format shows the contents of the changed files.
+    fn is_long(&self) -> bool {
+        match self {
+            Self::Summary
+            | Self::Stat(_)
+            | Self::NumStat(..)
+            | Self::Types
+            | Self::NameOnly(_)
+            | Self::NameStatus(_) => false,
+            Self::Git(_) | Self::ColorWords(_) | Self::Tool(_) => true,
+        }
+    }
+}
+
+/// Diff format to be used for the files matched by `matcher` instead of the
+/// default format.
+pub struct DiffFormatOverride {
+    matcher: Box<dyn Matcher>,
+    format: DiffFormat,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum BuiltinFormatKind {
     Summary,
     Stat,
+    NumStat,
     Types,
     NameOnly,
+    NameStatus,
     Git,
     ColorWords,
 }
@@ -174,8 +246,10 @@ impl BuiltinFormatKind {
     const ALL_VARIANTS: &[BuiltinFormatKind] = &[
         Self::Summary,
         Self::Stat,
+        Self::NumStat,
         Self::Types,
         Self::NameOnly,
+        Self::NameStatus,
         Self::Git,
         Self::ColorWords,
     ];
@@ -184,8 +258,10 @@ impl BuiltinFormatKind {
         match name {
             "summary" => Ok(Self::Summary),
             "stat" => Ok(Self::Stat),
+            "numstat" => Ok(Self::NumStat),
             "types" => Ok(Self::Types),
             "name-only" => Ok(Self::NameOnly),
+            "name-status" => Ok(Self::NameStatus),
             "git" => Ok(Self::Git),
             "color-words" => Ok(Self::ColorWords),
             _ => Err(format!("Invalid builtin diff format: {name}")),
@@ -197,10 +273,14 @@ impl BuiltinFormatKind {
             Some(Self::Summary)
         } else if args.stat {
             Some(Self::Stat)
+        } else if args.numstat {
+            Some(Self::NumStat)
         } else if args.types {
             Some(Self::Types)
         } else if args.name_only {
             Some(Self::NameOnly)
+        } else if args.name_status {
+            Some(Self::NameStatus)
         } else {
             None
         }
@@ -218,7 +298,12 @@ impl BuiltinFormatKind {
 
     fn is_short(self) -> bool {
         match self {
-            Self::Summary | Self::Stat | Self::Types | Self::NameOnly => true,
+            Self::Summary
+            | Self::Stat
+            | Self::NumStat
+            | Self::Types
+            | Self::NameOnly
+            | Self::NameStatus => true,
             Self::Git | Self::ColorWords => false,
         }
     }
@@ -227,8 +312,10 @@ impl BuiltinFormatKind {
         match self {
             Self::Summary => "summary",
             Self::Stat => "stat",
+            Self::NumStat => "numstat",
             Self::Types => "types",
             Self::NameOnly => "name-only",
+            Self::NameStatus => "name-status",
             Self::Git => "git",
             Self::ColorWords => "color-words",
         }
@@ -246,8 +333,17 @@ impl BuiltinFormatKind {
                 options.merge_args(args);
                 Ok(DiffFormat::Stat(Box::new(options)))
             }
+            Self::NumStat => {
+                let mut options = DiffStatOptions::default();
+                options.merge_args(args);
+                Ok(DiffFormat::NumStat(
+                    Box::new(options),
+                    NameListOptions::from_args(args),
+                ))
+            }
             Self::Types => Ok(DiffFormat::Types),
-            Self::NameOnly => Ok(DiffFormat::NameOnly),
+            Self::NameOnly => Ok(DiffFormat::NameOnly(NameListOptions::from_args(args))),
+            Self::NameStatus => Ok(DiffFormat::NameStatus(NameListOptions::from_args(args))),
             Self::Git => {
                 let mut options = UnifiedDiffOptions::from_settings(settings)?;
                 options.merge_args(args);
@@ -291,11 +387,13 @@ pub fn diff_formats_for(
     args: &DiffFormatArgs,
 ) -> Result<Vec<DiffFormat>, CommandError> {
     let formats = diff_formats_from_args(settings, args)?;
-    if formats.iter().all(|f| f.is_none()) {
-        Ok(vec![default_diff_format(settings, args)?])
+    let formats = if formats.iter().all(|f| f.is_none()) {
+        vec![default_diff_format(settings, args)?]
     } else {
-        Ok(formats.into_iter().flatten().collect())
-    }
+        formats.into_iter().flatten().collect()
+    };
+    ensure_null_supported(args, &formats)?;
+    Ok(formats)
 }
 
 /// Returns a list of requested diff formats for log-like commands, which may be
@@ -315,7 +413,32 @@ pub fn diff_formats_for_log(
             long_format = Some(default_format);
         }
     }
-    Ok([short_format, long_format].into_iter().flatten().collect())
+    let formats = [short_format, long_format]
+        .into_iter()
+        .flatten()
+        .collect_vec();
+    ensure_null_supported(args, &formats)?;
+    Ok(formats)
+}
+
+/// Checks that `--null` isn't used with formats which don't support it. The
+/// formats may also be specified by `--tool` or the config.
+fn ensure_null_supported(
+    args: &DiffFormatArgs,
+    formats: &[DiffFormat],
+) -> Result<(), CommandError> {
+    let supported = |format: &DiffFormat| {
+        matches!(
+            format,
+            DiffFormat::NumStat(..) | DiffFormat::NameOnly(_) | DiffFormat::NameStatus(_)
+        )
+    };
+    if args.null && (formats.is_empty() || !formats.iter().all(supported)) {
+        return Err(cli_error(
+            "--null can only be used with --numstat, --name-only, or --name-status",
+        ));
+    }
+    Ok(())
 }
 
 fn diff_formats_from_args(
@@ -363,10 +486,71 @@ fn default_diff_format(
     args: &DiffFormatArgs,
 ) -> Result<DiffFormat, CommandError> {
     let tool_args: CommandNameAndArgs = settings.get("ui.diff-formatter")?;
+    diff_format_from_config(settings, args, &tool_args, "ui.diff-formatter")
+}
+
+/// Returns diff formats to be used for specific files instead of the default
+/// format. The overrides are ignored if a "long" format is specified by
+/// command arguments.
+pub fn diff_format_overrides_for(
+    settings: &UserSettings,
+    args: &DiffFormatArgs,
+) -> Result<Vec<DiffFormatOverride>, CommandError> {
+    #[derive(serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct RawDiffFormatOverride {
+        patterns: Vec<String>,
+        formatter: CommandNameAndArgs,
+    }
+
+    if args.git || args.color_words || args.tool.is_some() {
+        return Ok(vec![]);
+    }
+    settings
+        .table_keys("ui.diff-formatter-overrides")
+        // Sort keys so the formats are applied in deterministic order.
+        .sorted()
+        .map(|name| -> Result<_, CommandError> {
+            let config: RawDiffFormatOverride =
+                settings.get(["ui", "diff-formatter-overrides", name])?;
+            let expressions: Vec<_> = config
+                .patterns
+                .iter()
+                .map(|text| {
+                    fileset::parse(
+                        &mut FilesetDiagnostics::new(),
+                        text,
+                        &RepoPathUiConverter::Fs {
+                            cwd: "".into(),
+                            base: "".into(),
+                        },
+                    )
+                })
+                .try_collect()?;
+            let format = diff_format_from_config(
+                settings,
+                args,
+                &config.formatter,
+                &format!("ui.diff-formatter-overrides.{name}.formatter"),
+            )?;
+            Ok(DiffFormatOverride {
+                matcher: FilesetExpression::union_all(expressions).to_matcher(),
+                format,
+            })
+        })
+        .try_collect()
+}
+
+fn diff_format_from_config(
+    settings: &UserSettings,
+    args: &DiffFormatArgs,
+    tool_args: &CommandNameAndArgs,
+    config_name: &str,
+) -> Result<DiffFormat, CommandError> {
     if let Some(name) = tool_args.as_str().and_then(|s| s.strip_prefix(':')) {
         Ok(BuiltinFormatKind::from_name(name)
             .map_err(|err| ConfigGetError::Type {
-                name: "ui.diff-formatter".to_owned(),
+                name: config_name.to_owned(),
                 error: err.into(),
                 source_path: None,
             })?
@@ -377,7 +561,7 @@ fn default_diff_format(
         } else {
             None
         }
-        .unwrap_or_else(|| ExternalMergeTool::with_diff_args(&tool_args));
+        .unwrap_or_else(|| ExternalMergeTool::with_diff_args(tool_args));
         Ok(DiffFormat::Tool(Box::new(tool)))
     }
 }
@@ -399,12 +583,21 @@ pub enum DiffRenderError {
     Io(#[from] io::Error),
 }
 
+fn union_matchers<'m>(matchers: &[&'m dyn Matcher]) -> Box<dyn Matcher + 'm> {
+    let init: Box<dyn Matcher + 'm> = Box::new(NothingMatcher);
+    matchers.iter().fold(init, |acc, matcher| {
+        Box::new(UnionMatcher::new(acc, *matcher))
+    })
+}
+
 /// Configuration and environment to render textual diff.
 pub struct DiffRenderer<'a> {
     repo: &'a dyn Repo,
     path_converter: &'a RepoPathUiConverter,
     conflict_marker_style: ConflictMarkerStyle,
+    attribute_overrides: &'a GitAttributes,
     formats: Vec<DiffFormat>,
+    format_overrides: Vec<DiffFormatOverride>,
 }
 
 impl<'a> DiffRenderer<'a> {
@@ -414,14 +607,35 @@ impl<'a> DiffRenderer<'a> {
         conflict_marker_style: ConflictMarkerStyle,
         formats: Vec<DiffFormat>,
     ) -> Self {
+        static EMPTY_ATTRIBUTES: GitAttributes = GitAttributes::empty();
         DiffRenderer {
             repo,
             path_converter,
             conflict_marker_style,
+            attribute_overrides: &EMPTY_ATTRIBUTES,
             formats,
+            format_overrides: vec![],
         }
     }
 
+    /// Sets path attributes which take precedence over the `.gitattributes`
+    /// files in the diffed trees.
+    pub fn with_attribute_overrides(mut self, attribute_overrides: &'a GitAttributes) -> Self {
+        self.attribute_overrides = attribute_overrides;
+        self
+    }
+
+    fn tree_attributes(&self, tree: &MergedTree) -> TreeAttributes {
+        TreeAttributes::new(tree.clone(), self.attribute_overrides.clone())
+    }
+
+    /// Sets diff formats to be used for specific files instead of the "long"
+    /// format. The first matching override wins.
+    pub fn with_format_overrides(mut self, format_overrides: Vec<DiffFormatOverride>) -> Self {
+        self.format_overrides = format_overrides;
+        self
+    }
+
     /// Generates diff between `from_tree` and `to_tree`.
     #[expect(clippy::too_many_arguments)]
     pub fn show_diff(
@@ -459,89 +673,166 @@ impl<'a> DiffRenderer<'a> {
         copy_records: &CopyRecords,
         width: usize,
     ) -> Result<(), DiffRenderError> {
-        let store = self.repo.store();
-        let path_converter = self.path_converter;
         for format in &self.formats {
-            match format {
-                DiffFormat::Summary => {
-                    let tree_diff =
-                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    show_diff_summary(formatter, tree_diff, path_converter).await?;
-                }
-                DiffFormat::Stat(options) => {
-                    let tree_diff =
-                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    let stats =
-                        DiffStats::calculate(store, tree_diff, options, self.conflict_marker_style)
-                            .block_on()?;
-                    show_diff_stats(formatter, &stats, path_converter, width)?;
-                }
-                DiffFormat::Types => {
-                    let tree_diff =
-                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    show_types(formatter, tree_diff, path_converter).await?;
-                }
-                DiffFormat::NameOnly => {
-                    let tree_diff =
-                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    show_names(formatter, tree_diff, path_converter).await?;
-                }
-                DiffFormat::Git(options) => {
-                    let tree_diff =
-                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    show_git_diff(
-                        formatter,
-                        store,
-                        tree_diff,
-                        options,
-                        self.conflict_marker_style,
-                    )
-                    .await?;
-                }
-                DiffFormat::ColorWords(options) => {
-                    let tree_diff =
-                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                    show_color_words_diff(
-                        formatter,
-                        store,
-                        tree_diff,
-                        path_converter,
-                        options,
-                        self.conflict_marker_style,
-                    )
-                    .await?;
-                }
-                DiffFormat::Tool(tool) => {
-                    match tool.diff_invocation_mode {
-                        DiffToolMode::FileByFile => {
-                            let tree_diff =
-                                from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
-                            show_file_by_file_diff(
-                                ui,
-                                formatter,
-                                store,
-                                tree_diff,
-                                path_converter,
-                                tool,
-                                self.conflict_marker_style,
-                            )
-                            .await
-                        }
-                        DiffToolMode::Dir => {
-                            let mut writer = formatter.raw()?;
-                            generate_diff(
-                                ui,
-                                writer.as_mut(),
-                                from_tree,
-                                to_tree,
-                                matcher,
-                                tool,
-                                self.conflict_marker_style,
-                            )
-                            .map_err(DiffRenderError::DiffGenerate)
-                        }
-                    }?;
+            if self.format_overrides.is_empty() || !format.is_long() {
+                self.show_diff_in_format(
+                    ui,
+                    formatter,
+                    format,
+                    from_tree,
+                    to_tree,
+                    matcher,
+                    copy_records,
+                    width,
+                )
+                .await?;
+                continue;
+            }
+            // Files matched by an override are excluded from the default
+            // format and the following overrides.
+            let override_matchers = self
+                .format_overrides
+                .iter()
+                .map(|format_override| format_override.matcher.as_ref())
+                .collect_vec();
+            let default_matcher =
+                DifferenceMatcher::new(matcher, union_matchers(&override_matchers));
+            let mut formats_and_matchers: Vec<(&DiffFormat, Box<dyn Matcher + '_>)> = Vec::new();
+            formats_and_matchers.push((format, Box::new(default_matcher)));
+            for (i, format_override) in self.format_overrides.iter().enumerate() {
+                let file_matcher = DifferenceMatcher::new(
+                    override_matchers[i],
+                    union_matchers(&override_matchers[..i]),
+                );
+                formats_and_matchers.push((
+                    &format_override.format,
+                    Box::new(IntersectionMatcher::new(matcher, file_matcher)),
+                ));
+            }
+            for (format, matcher) in formats_and_matchers {
+                // Don't invoke external tools if there are no files to show.
+                let mut tree_diff = from_tree.diff_stream(to_tree, matcher.as_ref());
+                if tree_diff.next().await.is_none() {
+                    continue;
                 }
+                self.show_diff_in_format(
+                    ui,
+                    formatter,
+                    format,
+                    from_tree,
+                    to_tree,
+                    matcher.as_ref(),
+                    copy_records,
+                    width,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    async fn show_diff_in_format(
+        &self,
+        ui: &Ui,
+        formatter: &mut dyn Formatter,
+        format: &DiffFormat,
+        from_tree: &MergedTree,
+        to_tree: &MergedTree,
+        matcher: &dyn Matcher,
+        copy_records: &CopyRecords,
+        width: usize,
+    ) -> Result<(), DiffRenderError> {
+        let store = self.repo.store();
+        let path_converter = self.path_converter;
+        match format {
+            DiffFormat::Summary => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                show_diff_summary(formatter, tree_diff, path_converter).await?;
+            }
+            DiffFormat::Stat(options) => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                let stats =
+                    DiffStats::calculate(store, tree_diff, options, self.conflict_marker_style)
+                        .block_on()?;
+                show_diff_stats(formatter, &stats, path_converter, width)?;
+            }
+            DiffFormat::NumStat(options, name_options) => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                let stats =
+                    DiffStats::calculate(store, tree_diff, options, self.conflict_marker_style)
+                        .block_on()?;
+                show_diff_numstat(formatter, &stats, path_converter, name_options)?;
+            }
+            DiffFormat::Types => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                show_types(formatter, tree_diff, path_converter).await?;
+            }
+            DiffFormat::NameOnly(options) => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                show_names(formatter, tree_diff, path_converter, options).await?;
+            }
+            DiffFormat::NameStatus(options) => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                show_name_status(formatter, tree_diff, path_converter, options).await?;
+            }
+            DiffFormat::Git(options) => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                let attributes = [from_tree, to_tree].map(|tree| self.tree_attributes(tree));
+                show_git_diff(
+                    formatter,
+                    store,
+                    tree_diff,
+                    options,
+                    self.conflict_marker_style,
+                    attributes.each_ref(),
+                )
+                .await?;
+            }
+            DiffFormat::ColorWords(options) => {
+                let tree_diff = from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                let attributes = [from_tree, to_tree].map(|tree| self.tree_attributes(tree));
+                show_color_words_diff(
+                    formatter,
+                    store,
+                    tree_diff,
+                    path_converter,
+                    options,
+                    self.conflict_marker_style,
+                    attributes.each_ref(),
+                )
+                .await?;
+            }
+            DiffFormat::Tool(tool) => {
+                match tool.diff_invocation_mode {
+                    DiffToolMode::FileByFile => {
+                        let tree_diff =
+                            from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                        show_file_by_file_diff(
+                            ui,
+                            formatter,
+                            store,
+                            tree_diff,
+                            path_converter,
+                            tool,
+                            self.conflict_marker_style,
+                        )
+                        .await
+                    }
+                    DiffToolMode::Dir => {
+                        let mut writer = formatter.raw()?;
+                        generate_diff(
+                            ui,
+                            writer.as_mut(),
+                            from_tree,
+                            to_tree,
+                            matcher,
+                            tool,
+                            self.conflict_marker_style,
+                        )
+                        .map_err(DiffRenderError::DiffGenerate)
+                    }
+                }?;
             }
         }
         Ok(())
@@ -599,6 +890,102 @@ impl<'a> DiffRenderer<'a> {
             width,
         )
     }
+
+    /// Generates combined diff of the given `commit` compared to all of its
+    /// parents at once.
+    pub fn show_combined_diff(
+        &self,
+        formatter: &mut dyn Formatter,
+        commit: &Commit,
+        matcher: &dyn Matcher,
+        options: &UnifiedDiffOptions,
+    ) -> Result<(), DiffRenderError> {
+        formatter.with_label("diff", |formatter| {
+            show_combined_diff(
+                formatter,
+                self.repo.store(),
+                commit,
+                matcher,
+                options,
+                self.conflict_marker_style,
+                self.attribute_overrides,
+            )
+            .block_on()
+        })
+    }
+
+    /// Generates diff of the given `commit` compared to its parents. Patches
+    /// of merge commits are rendered as specified by the `merge_mode`.
+    pub fn show_patch_with_merge_mode(
+        &self,
+        ui: &Ui,
+        formatter: &mut dyn Formatter,
+        commit: &Commit,
+        matcher: &dyn Matcher,
+        width: usize,
+        merge_mode: MergeDiffMode,
+    ) -> Result<(), DiffRenderError> {
+        if commit.parent_ids().len() < 2 {
+            return self.show_patch(ui, formatter, commit, matcher, width);
+        }
+        match merge_mode {
+            MergeDiffMode::Merged => self.show_patch(ui, formatter, commit, matcher, width),
+            MergeDiffMode::FirstParent => {
+                let parent_id = &commit.parent_ids()[0];
+                let from_tree = self.repo.store().get_commit(parent_id)?.tree()?;
+                let to_tree = commit.tree()?;
+                let mut copy_records = CopyRecords::default();
+                let records = get_copy_records(self.repo.store(), parent_id, commit.id(), matcher)?;
+                copy_records.add_records(records)?;
+                self.show_diff(
+                    ui,
+                    formatter,
+                    &from_tree,
+                    &to_tree,
+                    matcher,
+                    &copy_records,
+                    width,
+                )
+            }
+            MergeDiffMode::Combined => {
+                // Like `git diff -c`, omit files taken verbatim from one of the
+                // parents.
+                let to_tree = commit.tree()?;
+                let mut changed_paths: Option<HashSet<RepoPathBuf>> = None;
+                for parent in commit.parents() {
+                    let parent_tree = parent?.tree()?;
+                    let paths: HashSet<_> = parent_tree
+                        .diff_stream(&to_tree, matcher)
+                        .map(|entry| entry.path)
+                        .collect()
+                        .block_on();
+                    changed_paths = Some(match changed_paths {
+                        Some(changed_paths) => &changed_paths & &paths,
+                        None => paths,
+                    });
+                }
+                let files_matcher = FilesMatcher::new(changed_paths.unwrap_or_default());
+                let matcher = IntersectionMatcher::new(matcher, &files_matcher);
+                self.show_patch(ui, formatter, commit, &matcher, width)
+            }
+            MergeDiffMode::Off => Ok(()),
+        }
+    }
+}
+
+/// How to show the patch of merge commits.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MergeDiffMode {
+    /// Compare with the auto-merged parents
+    #[default]
+    Merged,
+    /// Compare with the first parent only
+    FirstParent,
+    /// Compare with the auto-merged parents, but only show files which differ
+    /// from every parent
+    Combined,
+    /// Don't show the patch of merge commits
+    Off,
 }
 
 pub fn get_copy_records<'a>(
@@ -685,6 +1072,8 @@ pub struct ColorWordsDiffOptions {
     pub line_diff: LineDiffOptions,
     /// Maximum number of removed/added word alternation to inline.
     pub max_inline_alternation: Option<usize>,
+    /// Whether to highlight syntax of file contents.
+    pub syntax_highlight: bool,
 }
 
 impl ColorWordsDiffOptions {
@@ -705,6 +1094,7 @@ impl ColorWordsDiffOptions {
             context: settings.get("diff.color-words.context")?,
             line_diff: LineDiffOptions::default(),
             max_inline_alternation,
+            syntax_highlight: settings.get_bool("ui.syntax-highlight")?,
         })
     }
 
@@ -718,15 +1108,29 @@ impl ColorWordsDiffOptions {
 
 fn show_color_words_diff_hunks(
     formatter: &mut dyn Formatter,
+    paths: [&RepoPath; 2],
     [lefts, rights]: [&Merge<BString>; 2],
     options: &ColorWordsDiffOptions,
     conflict_marker_style: ConflictMarkerStyle,
 ) -> io::Result<()> {
     let line_number = DiffLineNumber { left: 1, right: 1 };
     let labels = ["removed", "added"];
+    let mut highlights = SyntaxHighlights::new();
     if let (Some(left), Some(right)) = (lefts.as_resolved(), rights.as_resolved()) {
         let contents = [left, right].map(BStr::new);
-        show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        if options.syntax_highlight {
+            for (path, content) in iter::zip(paths, contents) {
+                highlights.add_source(path, content);
+            }
+        }
+        show_color_words_resolved_hunks(
+            formatter,
+            contents,
+            line_number,
+            labels,
+            options,
+            &highlights,
+        )?;
         return Ok(());
     }
     match options.conflict {
@@ -734,13 +1138,27 @@ fn show_color_words_diff_hunks(
             let left = materialize_merge_result_to_bytes(lefts, conflict_marker_style);
             let right = materialize_merge_result_to_bytes(rights, conflict_marker_style);
             let contents = [&left, &right].map(BStr::new);
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+            show_color_words_resolved_hunks(
+                formatter,
+                contents,
+                line_number,
+                labels,
+                options,
+                &highlights,
+            )?;
         }
         ConflictDiffMethod::Pair => {
             let lefts = files::merge(lefts);
             let rights = files::merge(rights);
             let contents = [&lefts, &rights];
-            show_color_words_conflict_hunks(formatter, contents, line_number, labels, options)?;
+            show_color_words_conflict_hunks(
+                formatter,
+                contents,
+                line_number,
+                labels,
+                options,
+                &highlights,
+            )?;
         }
     }
     Ok(())
@@ -752,6 +1170,7 @@ fn show_color_words_conflict_hunks(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlights: &SyntaxHighlights,
 ) -> io::Result<DiffLineNumber> {
     let num_lefts = lefts.as_slice().len();
     let line_diff = diff_by_line(lefts.iter().chain(rights.iter()), &options.line_diff);
@@ -777,6 +1196,7 @@ fn show_color_words_conflict_hunks(
                     line_number,
                     labels,
                     options,
+                    highlights,
                     num_after,
                     num_before,
                 )?;
@@ -786,7 +1206,14 @@ fn show_color_words_conflict_hunks(
                     (hunk.lefts.as_resolved(), hunk.rights.as_resolved())
                 {
                     let contents = [left, right];
-                    show_color_words_diff_lines(formatter, contents, line_number, labels, options)?
+                    show_color_words_diff_lines(
+                        formatter,
+                        contents,
+                        line_number,
+                        labels,
+                        options,
+                        highlights,
+                    )?
                 } else {
                     show_color_words_unresolved_hunk(
                         formatter,
@@ -794,6 +1221,7 @@ fn show_color_words_conflict_hunks(
                         line_number,
                         labels,
                         options,
+                        highlights,
                     )?
                 }
             }
@@ -808,6 +1236,7 @@ fn show_color_words_conflict_hunks(
         line_number,
         labels,
         options,
+        highlights,
         num_after,
         num_before,
     )
@@ -819,6 +1248,7 @@ fn show_color_words_unresolved_hunk(
     line_number: DiffLineNumber,
     [label1, label2]: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlights: &SyntaxHighlights,
 ) -> io::Result<DiffLineNumber> {
     let hunk_desc = if hunk.lefts.is_resolved() {
         "Created conflict"
@@ -860,8 +1290,14 @@ fn show_color_words_unresolved_hunk(
             false => [label2, label1],
         };
         // Individual hunk pair may be largely the same, so diff it again.
-        let new_line_number =
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        let new_line_number = show_color_words_resolved_hunks(
+            formatter,
+            contents,
+            line_number,
+            labels,
+            options,
+            highlights,
+        )?;
         // Take max to assign unique line numbers to trailing hunks. The line
         // numbers can't be real anyway because preceding conflict hunks might
         // have been resolved.
@@ -879,6 +1315,7 @@ fn show_color_words_resolved_hunks(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlights: &SyntaxHighlights,
 ) -> io::Result<DiffLineNumber> {
     let line_diff = diff_by_line(contents, &options.line_diff);
     // Matching entries shouldn't appear consecutively in diff of two inputs.
@@ -900,6 +1337,7 @@ fn show_color_words_resolved_hunks(
                     line_number,
                     labels,
                     options,
+                    highlights,
                     num_after,
                     num_before,
                 )?;
@@ -911,6 +1349,7 @@ fn show_color_words_resolved_hunks(
                     line_number,
                     labels,
                     options,
+                    highlights,
                 )?;
             }
         }
@@ -924,6 +1363,7 @@ fn show_color_words_resolved_hunks(
         line_number,
         labels,
         options,
+        highlights,
         num_after,
         num_before,
     )
@@ -936,6 +1376,7 @@ fn show_color_words_context_lines(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlights: &SyntaxHighlights,
     num_after: usize,
     num_before: usize,
 ) -> io::Result<DiffLineNumber> {
@@ -964,6 +1405,7 @@ fn show_color_words_context_lines(
                     formatter,
                     &[(DiffLineHunkSide::Both, line.as_ref())],
                     labels,
+                    highlights,
                 )?;
                 line_number.left += 1;
                 line_number.right += 1;
@@ -978,6 +1420,7 @@ fn show_color_words_context_lines(
                 line_number,
                 labels,
                 options,
+                highlights,
             )
         }
     };
@@ -1010,6 +1453,7 @@ fn show_color_words_diff_lines(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlights: &SyntaxHighlights,
 ) -> io::Result<DiffLineNumber> {
     let word_diff_hunks = Diff::by_word(contents).hunks().collect_vec();
     let can_inline = match options.max_inline_alternation {
@@ -1036,7 +1480,7 @@ fn show_color_words_diff_lines(
                 ],
                 labels,
             )?;
-            show_color_words_inline_hunks(formatter, &diff_line.hunks, labels)?;
+            show_color_words_inline_hunks(formatter, &diff_line.hunks, labels, highlights)?;
         }
         line_number = diff_line_iter.next_line_number();
     } else {
@@ -1044,12 +1488,12 @@ fn show_color_words_diff_lines(
         let [left_label, right_label] = labels;
         for tokens in &left_lines {
             show_color_words_line_number(formatter, [Some(line_number.left), None], labels)?;
-            show_color_words_single_sided_line(formatter, tokens, left_label)?;
+            show_color_words_single_sided_line(formatter, tokens, left_label, highlights)?;
             line_number.left += 1;
         }
         for tokens in &right_lines {
             show_color_words_line_number(formatter, [None, Some(line_number.right)], labels)?;
-            show_color_words_single_sided_line(formatter, tokens, right_label)?;
+            show_color_words_single_sided_line(formatter, tokens, right_label, highlights)?;
             line_number.right += 1;
         }
     }
@@ -1085,6 +1529,7 @@ fn show_color_words_inline_hunks(
     formatter: &mut dyn Formatter,
     line_hunks: &[(DiffLineHunkSide, &BStr)],
     [left_label, right_label]: [&str; 2],
+    highlights: &SyntaxHighlights,
 ) -> io::Result<()> {
     for (side, data) in line_hunks {
         let label = match side {
@@ -1094,10 +1539,10 @@ fn show_color_words_inline_hunks(
         };
         if let Some(label) = label {
             formatter.with_label(label, |formatter| {
-                formatter.with_label("token", |formatter| formatter.write_all(data))
+                formatter.with_label("token", |formatter| highlights.write_data(formatter, data))
             })?;
         } else {
-            formatter.write_all(data)?;
+            highlights.write_data(formatter, data)?;
         }
     }
     let (_, data) = line_hunks.last().expect("diff line must not be empty");
@@ -1112,8 +1557,11 @@ fn show_color_words_single_sided_line(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
     label: &str,
+    highlights: &SyntaxHighlights,
 ) -> io::Result<()> {
-    formatter.with_label(label, |formatter| show_diff_line_tokens(formatter, tokens))?;
+    formatter.with_label(label, |formatter| {
+        show_diff_line_tokens(formatter, tokens, highlights)
+    })?;
     let (_, data) = tokens.last().expect("diff line must not be empty");
     if !data.ends_with(b"\n") {
         writeln!(formatter)?;
@@ -1163,6 +1611,16 @@ struct FileContent<T> {
     contents: T,
 }
 
+impl<T> FileContent<T> {
+    /// Overrides the binary file detection by the `diff` attribute of `path`.
+    fn with_attributes(self, path: &RepoPath, attributes: &TreeAttributes) -> BackendResult<Self> {
+        Ok(FileContent {
+            is_binary: attributes.is_binary(path)?.unwrap_or(self.is_binary),
+            contents: self.contents,
+        })
+    }
+}
+
 impl FileContent<Merge<BString>> {
     fn is_empty(&self) -> bool {
         self.contents.as_resolved().is_some_and(|c| c.is_empty())
@@ -1280,6 +1738,7 @@ pub async fn show_color_words_diff(
     path_converter: &RepoPathUiConverter,
     options: &ColorWordsDiffOptions,
     conflict_marker_style: ConflictMarkerStyle,
+    [left_attributes, right_attributes]: [&TreeAttributes; 2],
 ) -> Result<(), DiffRenderError> {
     let empty_content = || Merge::resolved(BString::default());
     let mut diff_stream = materialized_diff_stream(store, tree_diff);
@@ -1315,7 +1774,8 @@ pub async fn show_color_words_diff(
                 formatter.labeled("header"),
                 "Added {description} {right_ui_path}:"
             )?;
-            let right_content = diff_content_as_merge(right_path, right_value)?;
+            let right_content = diff_content_as_merge(right_path, right_value)?
+                .with_attributes(right_path, right_attributes)?;
             if right_content.is_empty() {
                 writeln!(formatter.labeled("empty"), "    (empty)")?;
             } else if right_content.is_binary {
@@ -1323,6 +1783,7 @@ pub async fn show_color_words_diff(
             } else {
                 show_color_words_diff_hunks(
                     formatter,
+                    [left_path, right_path],
                     [&empty_content(), &right_content.contents],
                     options,
                     conflict_marker_style,
@@ -1372,8 +1833,10 @@ pub async fn show_color_words_diff(
                     )
                 }
             };
-            let left_content = diff_content_as_merge(left_path, left_value)?;
-            let right_content = diff_content_as_merge(right_path, right_value)?;
+            let left_content = diff_content_as_merge(left_path, left_value)?
+                .with_attributes(left_path, left_attributes)?;
+            let right_content = diff_content_as_merge(right_path, right_value)?
+                .with_attributes(right_path, right_attributes)?;
             if left_path == right_path {
                 writeln!(
                     formatter.labeled("header"),
@@ -1390,6 +1853,7 @@ pub async fn show_color_words_diff(
             } else if left_content.contents != right_content.contents {
                 show_color_words_diff_hunks(
                     formatter,
+                    [left_path, right_path],
                     [&left_content.contents, &right_content.contents],
                     options,
                     conflict_marker_style,
@@ -1401,7 +1865,8 @@ pub async fn show_color_words_diff(
                 formatter.labeled("header"),
                 "Removed {description} {right_ui_path}:"
             )?;
-            let left_content = diff_content_as_merge(left_path, left_value)?;
+            let left_content = diff_content_as_merge(left_path, left_value)?
+                .with_attributes(left_path, left_attributes)?;
             if left_content.is_empty() {
                 writeln!(formatter.labeled("empty"), "    (empty)")?;
             } else if left_content.is_binary {
@@ -1409,6 +1874,7 @@ pub async fn show_color_words_diff(
             } else {
                 show_color_words_diff_hunks(
                     formatter,
+                    [left_path, right_path],
                     [&left_content.contents, &empty_content()],
                     options,
                     conflict_marker_style,
@@ -1578,26 +2044,96 @@ fn git_diff_part(
 pub struct UnifiedDiffOptions {
     /// Number of context lines to show.
     pub context: usize,
+    /// Whether to extend context to the enclosing function.
+    pub function_context: bool,
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to highlight syntax of file contents.
+    pub syntax_highlight: bool,
 }
 
 impl UnifiedDiffOptions {
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         Ok(UnifiedDiffOptions {
             context: settings.get("diff.git.context")?,
+            function_context: false,
             line_diff: LineDiffOptions::default(),
+            syntax_highlight: settings.get_bool("ui.syntax-highlight")?,
         })
     }
 
+    /// Creates options from the settings, overridden by the command
+    /// arguments.
+    pub fn from_settings_and_args(
+        settings: &UserSettings,
+        args: &DiffFormatArgs,
+    ) -> Result<Self, ConfigGetError> {
+        let mut options = Self::from_settings(settings)?;
+        options.merge_args(args);
+        Ok(options)
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         if let Some(context) = args.context {
             self.context = context;
         }
+        if args.function_context {
+            self.function_context = true;
+        }
         self.line_diff.merge_args(args);
     }
 }
 
+/// Heuristic to detect the first line of a function (or other top-level
+/// definition) for `--function-context`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum FunctionHeaderPattern {
+    /// Line starting with an identifier character, like Git's default.
+    Generic,
+    /// Line having an identifier followed by `(` at the first column.
+    CFamily,
+    Python,
+    Rust,
+}
+
+impl FunctionHeaderPattern {
+    fn from_path(path: &RepoPath) -> Self {
+        let file_name = path.split().map_or("", |(_, name)| name.as_internal_str());
+        let extension = file_name.rsplit_once('.').map_or("", |(_, ext)| ext);
+        match extension {
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "java" | "js" | "ts" | "go"
+            | "cs" => Self::CFamily,
+            _ => Self::Generic,
+        }
+    }
+
+    fn is_match(self, line: &[u8]) -> bool {
+        static GENERIC: LazyLock<regex::bytes::Regex> =
+            LazyLock::new(|| regex::bytes::Regex::new(r"^[[:alpha:]_$]").unwrap());
+        static C_FAMILY: LazyLock<regex::bytes::Regex> =
+            LazyLock::new(|| regex::bytes::Regex::new(r"^[[:alpha:]_$][^;]*\(").unwrap());
+        static PYTHON: LazyLock<regex::bytes::Regex> =
+            LazyLock::new(|| regex::bytes::Regex::new(r"^\s*(async\s+)?(def|class)\s").unwrap());
+        static RUST: LazyLock<regex::bytes::Regex> = LazyLock::new(|| {
+            regex::bytes::Regex::new(concat!(
+                r"^\s*(pub(\([^)]*\))?\s+)?",
+                r#"((async|const|default|unsafe|extern\s+"[^"]*")\s+)*"#,
+                r"(fn|struct|enum|union|impl|trait|mod|macro_rules!)[\s<]",
+            ))
+            .unwrap()
+        });
+        let regex = match self {
+            Self::Generic => &GENERIC,
+            Self::CFamily => &C_FAMILY,
+            Self::Python => &PYTHON,
+            Self::Rust => &RUST,
+        };
+        regex.is_match(line)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum DiffLineType {
     Context,
@@ -1648,6 +2184,7 @@ impl<'content> UnifiedDiffHunk<'content> {
 fn unified_diff_hunks<'content>(
     contents: [&'content BStr; 2],
     options: &UnifiedDiffOptions,
+    function_header: Option<FunctionHeaderPattern>,
 ) -> Vec<UnifiedDiffHunk<'content>> {
     let mut hunks = vec![];
     let mut current_hunk = UnifiedDiffHunk {
@@ -1664,17 +2201,34 @@ fn unified_diff_hunks<'content>(
                 // number of skipped lines separately, but the number of the
                 // context lines should match the displayed content.
                 let [_, right] = hunk.contents[..].try_into().unwrap();
-                let mut lines = right.split_inclusive(|b| *b == b'\n').fuse();
-                if !current_hunk.lines.is_empty() {
+                let lines = right.split_inclusive(|b| *b == b'\n').collect_vec();
+                let num_after_lines = if current_hunk.lines.is_empty() {
+                    0
+                } else {
                     // The previous hunk line should be either removed/added.
-                    current_hunk.extend_context_lines(lines.by_ref().take(options.context));
-                }
-                let before_lines = if diff_hunks.peek().is_some() {
-                    lines.by_ref().rev().take(options.context).collect()
+                    let num_to_function_end = function_header.map_or(0, |pattern| {
+                        lines
+                            .iter()
+                            .position(|line| pattern.is_match(line))
+                            .unwrap_or(lines.len())
+                    });
+                    max(options.context, num_to_function_end).min(lines.len())
+                };
+                let (after_lines, lines) = lines.split_at(num_after_lines);
+                current_hunk.extend_context_lines(after_lines.iter().copied());
+                let num_before_lines = if diff_hunks.peek().is_some() {
+                    let num_from_function_start = function_header.map_or(0, |pattern| {
+                        lines
+                            .iter()
+                            .rposition(|line| pattern.is_match(line))
+                            .map_or(lines.len(), |pos| lines.len() - pos)
+                    });
+                    max(options.context, num_from_function_start).min(lines.len())
                 } else {
-                    vec![] // No more hunks
+                    0 // No more hunks
                 };
-                let num_skip_lines = lines.count();
+                let (skipped_lines, before_lines) = lines.split_at(lines.len() - num_before_lines);
+                let num_skip_lines = skipped_lines.len();
                 if num_skip_lines > 0 {
                     let left_start = current_hunk.left_line_range.end + num_skip_lines;
                     let right_start = current_hunk.right_line_range.end + num_skip_lines;
@@ -1688,7 +2242,7 @@ fn unified_diff_hunks<'content>(
                     };
                 }
                 // The next hunk should be of DiffHunk::Different type if any.
-                current_hunk.extend_context_lines(before_lines.into_iter().rev());
+                current_hunk.extend_context_lines(before_lines.iter().copied());
             }
             DiffHunkKind::Different => {
                 let [left_lines, right_lines] =
@@ -1763,6 +2317,8 @@ fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     contents: [&BStr; 2],
     options: &UnifiedDiffOptions,
+    function_header: Option<FunctionHeaderPattern>,
+    highlights: &SyntaxHighlights,
 ) -> io::Result<()> {
     // "If the chunk size is 0, the first number is one lower than one would
     // expect." - https://www.artima.com/weblogs/viewpost.jsp?thread=164293
@@ -1779,7 +2335,7 @@ fn show_unified_diff_hunks(
         }
     }
 
-    for hunk in unified_diff_hunks(contents, options) {
+    for hunk in unified_diff_hunks(contents, options, function_header) {
         writeln!(
             formatter.labeled("hunk_header"),
             "@@ -{},{} +{},{} @@",
@@ -1796,7 +2352,7 @@ fn show_unified_diff_hunks(
             };
             formatter.with_label(label, |formatter| {
                 write!(formatter, "{sigil}")?;
-                show_diff_line_tokens(formatter, tokens)
+                show_diff_line_tokens(formatter, tokens, highlights)
             })?;
             let (_, content) = tokens.last().expect("hunk line must not be empty");
             if !content.ends_with(b"\n") {
@@ -1810,12 +2366,15 @@ fn show_unified_diff_hunks(
 fn show_diff_line_tokens(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
+    highlights: &SyntaxHighlights,
 ) -> io::Result<()> {
     for (token_type, content) in tokens {
         match token_type {
-            DiffTokenType::Matching => formatter.write_all(content)?,
+            DiffTokenType::Matching => highlights.write_data(formatter, content)?,
             DiffTokenType::Different => {
-                formatter.with_label("token", |formatter| formatter.write_all(content))?;
+                formatter.with_label("token", |formatter| {
+                    highlights.write_data(formatter, content)
+                })?;
             }
         }
     }
@@ -1828,6 +2387,7 @@ pub async fn show_git_diff(
     tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
     options: &UnifiedDiffOptions,
     conflict_marker_style: ConflictMarkerStyle,
+    [left_attributes, right_attributes]: [&TreeAttributes; 2],
 ) -> Result<(), DiffRenderError> {
     let mut diff_stream = materialized_diff_stream(store, tree_diff);
     while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
@@ -1837,8 +2397,14 @@ pub async fn show_git_diff(
         let right_path_string = right_path.as_internal_file_string();
         let (left_value, right_value) = values?;
 
-        let left_part = git_diff_part(left_path, left_value, conflict_marker_style)?;
-        let right_part = git_diff_part(right_path, right_value, conflict_marker_style)?;
+        let mut left_part = git_diff_part(left_path, left_value, conflict_marker_style)?;
+        let mut right_part = git_diff_part(right_path, right_value, conflict_marker_style)?;
+        left_part.content = left_part
+            .content
+            .with_attributes(left_path, left_attributes)?;
+        right_part.content = right_part
+            .content
+            .with_attributes(right_path, right_attributes)?;
 
         formatter.with_label("file_header", |formatter| {
             writeln!(
@@ -1905,12 +2471,262 @@ pub async fn show_git_diff(
                 writeln!(formatter, "+++ {right_path}")?;
                 io::Result::Ok(())
             })?;
-            show_unified_diff_hunks(
-                formatter,
-                [&left_part.content.contents, &right_part.content.contents].map(BStr::new),
-                options,
+            let contents =
+                [&left_part.content.contents, &right_part.content.contents].map(BStr::new);
+            let mut highlights = SyntaxHighlights::new();
+            if options.syntax_highlight {
+                for (file_path, content) in iter::zip([path.source(), path.target()], contents) {
+                    highlights.add_source(file_path, content);
+                }
+            }
+            let function_header = options
+                .function_context
+                .then(|| FunctionHeaderPattern::from_path(path.target()));
+            show_unified_diff_hunks(formatter, contents, options, function_header, &highlights)?;
+        }
+    }
+    Ok(())
+}
+
+/// Shows the diff of `commit` against all of its parents at once, like
+/// `git diff --cc`. Only files that differ from every parent are shown.
+pub async fn show_combined_diff(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+    options: &UnifiedDiffOptions,
+    conflict_marker_style: ConflictMarkerStyle,
+    attribute_overrides: &GitAttributes,
+) -> Result<(), DiffRenderError> {
+    let to_tree = commit.tree()?;
+    let parent_trees: Vec<MergedTree> = commit
+        .parents()
+        .map(|parent| parent?.tree())
+        .try_collect()?;
+    let trees = parent_trees.iter().chain([&to_tree]).collect_vec();
+    let tree_attributes = trees
+        .iter()
+        .map(|&tree| TreeAttributes::new(tree.clone(), attribute_overrides.clone()))
+        .collect_vec();
+    let mut paths: Vec<RepoPathBuf> = vec![];
+    let mut other_paths: Vec<HashSet<RepoPathBuf>> = vec![];
+    for (i, parent_tree) in parent_trees.iter().enumerate() {
+        let diff_paths: Vec<_> = parent_tree
+            .diff_stream(&to_tree, matcher)
+            .map(|entry| entry.path)
+            .collect()
+            .await;
+        if i == 0 {
+            paths = diff_paths;
+        } else {
+            other_paths.push(diff_paths.into_iter().collect());
+        }
+    }
+    paths.retain(|path| other_paths.iter().all(|other| other.contains(path)));
+
+    for path in &paths {
+        let path_string = path.as_internal_file_string();
+        let mut parts = Vec::with_capacity(parent_trees.len() + 1);
+        for (tree, attributes) in iter::zip(&trees, &tree_attributes) {
+            let value = materialize_tree_value(store, path, tree.path_value(path)?).await?;
+            let mut part = git_diff_part(path, value, conflict_marker_style)?;
+            part.content = part.content.with_attributes(path, attributes)?;
+            parts.push(part);
+        }
+        let (to_part, parent_parts) = parts.split_last().unwrap();
+
+        formatter.with_label("file_header", |formatter| {
+            writeln!(formatter, "diff --cc {path_string}")?;
+            let parent_hashes = parent_parts.iter().map(|part| &part.hash).join(",");
+            match to_part.mode {
+                Some(mode) if parent_parts.iter().all(|part| part.mode.is_none()) => {
+                    writeln!(formatter, "new file mode {mode}")?;
+                    writeln!(formatter, "index {parent_hashes}..{}", to_part.hash)?;
+                }
+                Some(mode) => {
+                    writeln!(formatter, "index {parent_hashes}..{} {mode}", to_part.hash)?;
+                }
+                None => {
+                    let parent_modes = parent_parts
+                        .iter()
+                        .map(|part| part.mode.unwrap_or("000000"))
+                        .join(",");
+                    writeln!(formatter, "deleted file mode {parent_modes}")?;
+                    writeln!(formatter, "index {parent_hashes}..{}", to_part.hash)?;
+                }
+            }
+            io::Result::Ok(())
+        })?;
+
+        if parts.iter().any(|part| part.content.is_binary) {
+            writeln!(formatter, "Binary files differ")?;
+            continue;
+        }
+        let to_path = match to_part.mode {
+            Some(_) => format!("b/{path_string}"),
+            None => "/dev/null".to_owned(),
+        };
+        formatter.with_label("file_header", |formatter| {
+            writeln!(formatter, "--- a/{path_string}")?;
+            writeln!(formatter, "+++ {to_path}")?;
+            io::Result::Ok(())
+        })?;
+        let contents = parts
+            .iter()
+            .map(|part| BStr::new(&part.content.contents))
+            .collect_vec();
+        show_combined_diff_hunks(formatter, &contents, options)?;
+    }
+    Ok(())
+}
+
+/// Line of a combined diff.
+struct CombinedDiffLine<'content> {
+    /// Sigil per parent. A removed line has `-` in the column of the parent
+    /// it was removed from. Other lines have `+` in the columns of the parents
+    /// they were added to.
+    sigils: Vec<u8>,
+    is_removed: bool,
+    content: &'content [u8],
+}
+
+impl CombinedDiffLine<'_> {
+    fn is_changed(&self) -> bool {
+        self.sigils.iter().any(|&c| c != b' ')
+    }
+
+    /// Returns true if this line exists in the input at `index`, where the
+    /// last input is the commit contents.
+    fn belongs_to(&self, index: usize) -> bool {
+        match self.sigils.get(index) {
+            Some(&c) if self.is_removed => c == b'-',
+            Some(&c) => c == b' ',
+            None => !self.is_removed,
+        }
+    }
+}
+
+/// Splits n-way diff of `contents` into lines. The last item of `contents` is
+/// the commit contents, and the others are the parent contents.
+fn combined_diff_lines<'content>(
+    contents: &[&'content BStr],
+    options: &LineDiffOptions,
+) -> Vec<CombinedDiffLine<'content>> {
+    let num_parents = contents.len() - 1;
+    let context_line = |content| CombinedDiffLine {
+        sigils: vec![b' '; num_parents],
+        is_removed: false,
+        content,
+    };
+    let mut lines = vec![];
+    let diff = diff_by_line(contents.iter().copied(), options);
+    for hunk in diff.hunks() {
+        match hunk.kind {
+            DiffHunkKind::Matching => {
+                let content = hunk.contents[num_parents];
+                lines.extend(content.split_inclusive(|b| *b == b'\n').map(context_line));
+            }
+            DiffHunkKind::Different => {
+                let to_content = hunk.contents[num_parents];
+                let mut to_lines = to_content
+                    .split_inclusive(|b| *b == b'\n')
+                    .map(context_line)
+                    .collect_vec();
+                for (i, parent_content) in hunk.contents[..num_parents].iter().enumerate() {
+                    let mut to_index = 0;
+                    let pair_diff = diff_by_line([*parent_content, to_content], options);
+                    for pair_hunk in pair_diff.hunks() {
+                        let [left, right] = pair_hunk.contents[..]
+                            .try_into()
+                            .expect("hunk should have exactly two inputs");
+                        let num_right_lines = right.split_inclusive(|b| *b == b'\n').count();
+                        if pair_hunk.kind == DiffHunkKind::Different {
+                            for content in left.split_inclusive(|b| *b == b'\n') {
+                                let mut sigils = vec![b' '; num_parents];
+                                sigils[i] = b'-';
+                                lines.push(CombinedDiffLine {
+                                    sigils,
+                                    is_removed: true,
+                                    content,
+                                });
+                            }
+                            for line in &mut to_lines[to_index..to_index + num_right_lines] {
+                                line.sigils[i] = b'+';
+                            }
+                        }
+                        to_index += num_right_lines;
+                    }
+                }
+                lines.extend(to_lines);
+            }
+        }
+    }
+    lines
+}
+
+fn show_combined_diff_hunks(
+    formatter: &mut dyn Formatter,
+    contents: &[&BStr],
+    options: &UnifiedDiffOptions,
+) -> io::Result<()> {
+    let lines = combined_diff_lines(contents, &options.line_diff);
+    let changed_indices = lines
+        .iter()
+        .positions(|line| line.is_changed())
+        .collect_vec();
+    // Group changes whose context lines overlap or are adjacent
+    let mut hunk_ranges: Vec<Range<usize>> = vec![];
+    for index in changed_indices {
+        let start = index.saturating_sub(options.context);
+        let end = (index + options.context + 1).min(lines.len());
+        match hunk_ranges.last_mut() {
+            Some(range) if start <= range.end => range.end = end,
+            _ => hunk_ranges.push(start..end),
+        }
+    }
+
+    let sigil = "@".repeat(contents.len());
+    for range in hunk_ranges {
+        write!(formatter.labeled("hunk_header"), "{sigil}")?;
+        for index in 0..contents.len() {
+            let start = lines[..range.start]
+                .iter()
+                .filter(|line| line.belongs_to(index))
+                .count();
+            let len = lines[range.clone()]
+                .iter()
+                .filter(|line| line.belongs_to(index))
+                .count();
+            // Empty range starts at the preceding line, as in unified diff.
+            let line_number = if len == 0 { start } else { start + 1 };
+            let prefix = if index + 1 == contents.len() {
+                '+'
+            } else {
+                '-'
+            };
+            write!(
+                formatter.labeled("hunk_header"),
+                " {prefix}{line_number},{len}"
             )?;
         }
+        writeln!(formatter.labeled("hunk_header"), " {sigil}")?;
+        for line in &lines[range] {
+            let label = if line.is_removed {
+                "removed"
+            } else if line.is_changed() {
+                "added"
+            } else {
+                "context"
+            };
+            formatter.with_label(label, |formatter| {
+                formatter.write_all(&line.sigils)?;
+                formatter.write_all(line.content)
+            })?;
+            if !line.content.ends_with(b"\n") {
+                write!(formatter, "\n\\ No newline at end of file\n")?;
+            }
+        }
     }
     Ok(())
 }
@@ -2139,6 +2955,33 @@ pub fn show_diff_stats(
     Ok(())
 }
 
+pub fn show_diff_numstat(
+    formatter: &mut dyn Formatter,
+    stats: &DiffStats,
+    path_converter: &RepoPathUiConverter,
+    options: &NameListOptions,
+) -> io::Result<()> {
+    let terminator = options.terminator();
+    for stat in stats.entries() {
+        let added = stat.added;
+        let removed = stat.removed;
+        let path = &stat.path;
+        if path.copy_operation().is_none() {
+            let target = path_converter.format_file_path(path.target());
+            write!(formatter, "{added}\t{removed}\t{target}{terminator}")?;
+        } else if options.null_terminated {
+            // Like Git, the source and target paths are NUL-separated fields
+            let source = path_converter.format_file_path(path.source());
+            let target = path_converter.format_file_path(path.target());
+            write!(formatter, "{added}\t{removed}\t\0{source}\0{target}\0")?;
+        } else {
+            let paths = path_converter.format_copied_path(path.source(), path.target());
+            write!(formatter, "{added}\t{removed}\t{paths}{terminator}")?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn show_types(
     formatter: &mut dyn Formatter,
     mut tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
@@ -2170,21 +3013,82 @@ fn diff_summary_char(value: &MergedTreeValue) -> char {
     }
 }
 
+/// Options for the `--numstat`, `--name-only`, and `--name-status` formats.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NameListOptions {
+    /// Whether entries are terminated (and fields separated) by NUL.
+    pub null_terminated: bool,
+}
+
+impl NameListOptions {
+    fn from_args(args: &DiffFormatArgs) -> Self {
+        NameListOptions {
+            null_terminated: args.null,
+        }
+    }
+
+    fn terminator(&self) -> char {
+        if self.null_terminated {
+            '\0'
+        } else {
+            '\n'
+        }
+    }
+
+    fn separator(&self) -> char {
+        if self.null_terminated {
+            '\0'
+        } else {
+            '\t'
+        }
+    }
+}
+
 pub async fn show_names(
     formatter: &mut dyn Formatter,
     mut tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
     path_converter: &RepoPathUiConverter,
+    options: &NameListOptions,
 ) -> io::Result<()> {
+    let terminator = options.terminator();
     while let Some(CopiesTreeDiffEntry { path, .. }) = tree_diff.next().await {
-        writeln!(
+        write!(
             formatter,
-            "{}",
+            "{}{terminator}",
             path_converter.format_file_path(path.target())
         )?;
     }
     Ok(())
 }
 
+pub async fn show_name_status(
+    formatter: &mut dyn Formatter,
+    mut tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+    options: &NameListOptions,
+) -> Result<(), DiffRenderError> {
+    let terminator = options.terminator();
+    let separator = options.separator();
+    while let Some(CopiesTreeDiffEntry { path, values }) = tree_diff.next().await {
+        let (before, after) = values?;
+        let (label, sigil) = diff_status_label_and_char(&path, &before, &after);
+        let target = path_converter.format_file_path(path.target());
+        if path.copy_operation().is_some() {
+            let source = path_converter.format_file_path(path.source());
+            write!(
+                formatter.labeled(label),
+                "{sigil}{separator}{source}{separator}{target}{terminator}"
+            )?;
+        } else {
+            write!(
+                formatter.labeled(label),
+                "{sigil}{separator}{target}{terminator}"
+            )?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn show_templated(
     formatter: &mut dyn Formatter,
     mut tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
@@ -16,12 +16,14 @@ use std::hash::Hash;
 use std::io;
 use std::io::Write;
 
+use itertools::Itertools as _;
 use jj_lib::config::ConfigGetError;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
 use jj_lib::settings::UserSettings;
 use renderdag::Ancestor;
 use renderdag::GraphRowRenderer;
+use renderdag::NodeLine;
 use renderdag::Renderer;
 
 pub trait GraphLog<K: Clone + Eq + Hash> {
@@ -94,6 +96,52 @@ impl<'writer, R> SaplingGraphLog<'writer, R> {
     }
 }
 
+/// Computes the placement of graph nodes without rendering them.
+pub struct GraphLayout<K> {
+    renderer: GraphRowRenderer<K>,
+}
+
+/// Placement of a node in the graph.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GraphRowLayout {
+    /// Column of the node, counted from the left.
+    pub column: usize,
+    /// Number of columns in the row.
+    pub width: usize,
+}
+
+impl<K: Clone + Eq + Hash> GraphLayout<K> {
+    pub fn new() -> Self {
+        GraphLayout {
+            renderer: GraphRowRenderer::new(),
+        }
+    }
+
+    pub fn next_row(&mut self, id: &K, edges: &[GraphEdge<K>]) -> GraphRowLayout {
+        let row = self.renderer.next_row(
+            id.clone(),
+            edges.iter().map(convert_graph_edge_into_ancestor).collect(),
+            String::new(),
+            String::new(),
+        );
+        let column = row
+            .node_line
+            .iter()
+            .position(|line| matches!(line, NodeLine::Node))
+            .expect("node should be placed in the row");
+        GraphRowLayout {
+            column,
+            width: row.node_line.len(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> Default for GraphLayout<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
 #[serde(rename_all(deserialize = "kebab-case"))]
 pub enum GraphStyle {
@@ -111,8 +159,12 @@ impl GraphStyle {
 
 pub fn get_graphlog<'a, K: Clone + Eq + Hash + 'a>(
     style: GraphStyle,
+    compact: bool,
     formatter: &'a mut dyn Write,
 ) -> Box<dyn GraphLog<K> + 'a> {
+    if compact {
+        return CompactGraphLog::create(style, formatter);
+    }
     let builder = GraphRowRenderer::new().output().with_min_row_height(0);
     match style {
         GraphStyle::Ascii => SaplingGraphLog::create(builder.build_ascii(), formatter),
@@ -123,3 +175,462 @@ pub fn get_graphlog<'a, K: Clone + Eq + Hash + 'a>(
         }
     }
 }
+
+/// Lane of the compact graph, leading down to a node that hasn't been rendered
+/// yet.
+#[derive(Clone, Debug)]
+struct Lane<K> {
+    target: K,
+    edge_type: GraphEdgeType,
+}
+
+/// Placement of a node and its edges in the compact graph.
+struct CompactRow<K> {
+    node_column: usize,
+    /// Other columns whose lanes end at the node. They are merged into the
+    /// node column before the node is drawn.
+    joined_columns: Vec<usize>,
+    /// Columns of the lanes to the node's parents, and whether each lane
+    /// already existed above the node.
+    parent_columns: Vec<(usize, bool)>,
+    /// Columns of existing lanes to parents that are moved into the node
+    /// column below the node.
+    moved_columns: Vec<usize>,
+    /// Column where the missing edge of the node, if any, is terminated.
+    missing_column: Option<usize>,
+    lanes_after: Vec<Option<Lane<K>>>,
+    width: usize,
+}
+
+/// Characters used to draw the compact graph.
+struct CompactGlyphs {
+    vertical: char,
+    vertical_indirect: char,
+    horizontal: char,
+    /// Edges turning from up to right (`╰`), up to left (`╯`), down to right
+    /// (`╭`), and down to left (`╮`).
+    corners: [char; 4],
+    /// Vertical line with an edge to the right (`├`) and to the left (`┤`),
+    /// and horizontal line with an edge up (`┴`) and down (`┬`).
+    tees: [char; 4],
+    cross: char,
+    terminal: char,
+}
+
+const ASCII_GLYPHS: CompactGlyphs = CompactGlyphs {
+    vertical: '|',
+    vertical_indirect: ':',
+    horizontal: '-',
+    corners: ['\'', '\'', '.', '.'],
+    tees: ['+', '+', '+', '+'],
+    cross: '+',
+    terminal: '~',
+};
+
+const CURVED_GLYPHS: CompactGlyphs = CompactGlyphs {
+    vertical: '│',
+    vertical_indirect: '╷',
+    horizontal: '─',
+    corners: ['╰', '╯', '╭', '╮'],
+    tees: ['├', '┤', '┴', '┬'],
+    cross: '┼',
+    terminal: '~',
+};
+
+const SQUARE_GLYPHS: CompactGlyphs = CompactGlyphs {
+    corners: ['└', '┘', '┌', '┐'],
+    ..CURVED_GLYPHS
+};
+
+/// Connections of a single character cell of the compact graph.
+#[derive(Clone, Copy, Debug, Default)]
+struct Cell {
+    up: bool,
+    down: bool,
+    left: bool,
+    right: bool,
+    indirect: bool,
+    /// An edge passes horizontally behind the vertical lane of this cell.
+    crossing: bool,
+    /// A missing edge ends in this cell.
+    terminal: bool,
+}
+
+impl Cell {
+    fn glyph(&self, glyphs: &CompactGlyphs) -> char {
+        let vertical = if self.indirect {
+            glyphs.vertical_indirect
+        } else {
+            glyphs.vertical
+        };
+        if self.terminal {
+            return glyphs.terminal;
+        }
+        if self.crossing {
+            return vertical;
+        }
+        match (self.up, self.down, self.left, self.right) {
+            (false, false, false, false) => ' ',
+            (_, _, false, false) => vertical,
+            (false, false, _, _) => glyphs.horizontal,
+            (true, false, false, true) => glyphs.corners[0],
+            (true, false, true, false) => glyphs.corners[1],
+            (false, true, false, true) => glyphs.corners[2],
+            (false, true, true, false) => glyphs.corners[3],
+            (true, true, false, true) => glyphs.tees[0],
+            (true, true, true, false) => glyphs.tees[1],
+            (true, false, true, true) => glyphs.tees[2],
+            (false, true, true, true) => glyphs.tees[3],
+            (true, true, true, true) => glyphs.cross,
+        }
+    }
+}
+
+/// Graph renderer that keeps the graph narrow by placing new lanes in the
+/// leftmost free column, even if edges have to cross other lanes to get
+/// there. Lanes leading to the same node are merged as soon as possible.
+///
+/// Crossed lanes are drawn on top of the crossing edge, so crossings can be
+/// told apart from edges joining a lane.
+pub struct CompactGraphLog<'writer, K> {
+    lanes: Vec<Option<Lane<K>>>,
+    glyphs: &'static CompactGlyphs,
+    writer: &'writer mut dyn Write,
+}
+
+impl<'writer, K> CompactGraphLog<'writer, K> {
+    pub fn create(
+        style: GraphStyle,
+        writer: &'writer mut dyn Write,
+    ) -> Box<dyn GraphLog<K> + 'writer>
+    where
+        K: Clone + Eq + Hash + 'writer,
+    {
+        let glyphs = match style {
+            GraphStyle::Ascii | GraphStyle::AsciiLarge => &ASCII_GLYPHS,
+            GraphStyle::Curved => &CURVED_GLYPHS,
+            GraphStyle::Square => &SQUARE_GLYPHS,
+        };
+        Box::new(CompactGraphLog {
+            lanes: vec![],
+            glyphs,
+            writer,
+        })
+    }
+}
+
+impl<K: Clone + Eq + Hash> CompactGraphLog<'_, K> {
+    fn plan_row(&self, id: &K, edges: &[GraphEdge<K>]) -> CompactRow<K> {
+        let incoming = self
+            .lanes
+            .iter()
+            .positions(|lane| lane.as_ref().is_some_and(|lane| lane.target == *id))
+            .collect_vec();
+        let mut lanes = self.lanes.clone();
+        for &column in &incoming {
+            lanes[column] = None;
+        }
+        let node_column = incoming
+            .first()
+            .copied()
+            .unwrap_or_else(|| first_free_column(&lanes));
+        let mut parent_columns: Vec<(usize, bool)> = vec![];
+        let mut moved_columns = vec![];
+        let mut has_missing_edge = false;
+        for edge in edges {
+            if edge.edge_type == GraphEdgeType::Missing {
+                has_missing_edge = true;
+                continue;
+            }
+            let existing_column = lanes
+                .iter()
+                .position(|lane| lane.as_ref().is_some_and(|lane| lane.target == edge.target));
+            if let Some(column) = existing_column {
+                if column > node_column && is_free_column(&lanes, node_column) {
+                    lanes[node_column] = lanes[column].take();
+                    moved_columns.push(column);
+                    parent_columns.push((node_column, false));
+                } else if !parent_columns.iter().any(|&(c, _)| c == column) {
+                    let existed_above = self.lanes.get(column).is_some_and(Option::is_some)
+                        && !incoming.contains(&column);
+                    parent_columns.push((column, existed_above));
+                }
+                continue;
+            }
+            let column = if is_free_column(&lanes, node_column) {
+                node_column
+            } else {
+                first_free_column(&lanes)
+            };
+            let lane = Lane {
+                target: edge.target.clone(),
+                edge_type: edge.edge_type,
+            };
+            if column == lanes.len() {
+                lanes.push(Some(lane));
+            } else {
+                lanes[column] = Some(lane);
+            }
+            parent_columns.push((column, false));
+        }
+        let missing_column = has_missing_edge.then(|| {
+            if is_free_column(&lanes, node_column) {
+                node_column
+            } else {
+                first_free_column(&lanes)
+            }
+        });
+        let width = [
+            self.lanes.len(),
+            lanes.len(),
+            node_column + 1,
+            missing_column.map_or(0, |column| column + 1),
+        ]
+        .into_iter()
+        .max()
+        .unwrap();
+        CompactRow {
+            node_column,
+            joined_columns: incoming.into_iter().skip(1).collect(),
+            parent_columns,
+            moved_columns,
+            missing_column,
+            lanes_after: lanes,
+            width,
+        }
+    }
+
+    fn render_line(&self, cells: &[Cell], node: Option<(usize, &str)>, text: &str) -> String {
+        let mut line = String::new();
+        for (column, cell) in cells.iter().enumerate() {
+            match node {
+                Some((node_column, symbol)) if node_column == column => line.push_str(symbol),
+                _ => line.push(cell.glyph(self.glyphs)),
+            }
+            line.push(if cell.right {
+                self.glyphs.horizontal
+            } else {
+                ' '
+            });
+        }
+        if text.is_empty() {
+            line.truncate(line.trim_end().len());
+        } else {
+            line.push(' ');
+            line.push_str(text);
+        }
+        line
+    }
+}
+
+impl<K: Clone + Eq + Hash> GraphLog<K> for CompactGraphLog<'_, K> {
+    fn add_node(
+        &mut self,
+        id: &K,
+        edges: &[GraphEdge<K>],
+        node_symbol: &str,
+        text: &str,
+    ) -> io::Result<()> {
+        let row = self.plan_row(id, edges);
+        let mut lines = vec![];
+
+        // Merge the other lanes ending at the node into the node column.
+        if !row.joined_columns.is_empty() {
+            let mut cells = lane_cells(&self.lanes, row.width);
+            for &column in &row.joined_columns {
+                cells[column].down = false;
+            }
+            for &column in &row.joined_columns {
+                connect_cells(&mut cells, row.node_column, column);
+            }
+            lines.push(self.render_line(&cells, None, ""));
+        }
+
+        let mut lanes_through = self.lanes.clone();
+        for column in itertools::chain([row.node_column], row.joined_columns.iter().copied()) {
+            if let Some(lane) = lanes_through.get_mut(column) {
+                *lane = None;
+            }
+        }
+        let mut text_lines = text.lines();
+        let node_cells = lane_cells(&lanes_through, row.width);
+        lines.push(self.render_line(
+            &node_cells,
+            Some((row.node_column, node_symbol)),
+            text_lines.next().unwrap_or(""),
+        ));
+
+        // Connect the node to the lanes of its parents.
+        let new_columns = row
+            .parent_columns
+            .iter()
+            .copied()
+            .chain(row.missing_column.map(|column| (column, false)));
+        if !row.moved_columns.is_empty()
+            || new_columns
+                .clone()
+                .any(|(column, _)| column != row.node_column)
+        {
+            let mut cells = lane_cells(&lanes_through, row.width);
+            cells[row.node_column].up = true;
+            for (column, existed_above) in new_columns.clone() {
+                if column == row.node_column {
+                    cells[column].down = true;
+                    continue;
+                }
+                connect_cells(&mut cells, row.node_column, column);
+                if !existed_above {
+                    cells[column].down = true;
+                    cells[column].indirect = row
+                        .lanes_after
+                        .get(column)
+                        .and_then(Option::as_ref)
+                        .is_some_and(|lane| lane.edge_type == GraphEdgeType::Indirect);
+                }
+            }
+            for &column in &row.moved_columns {
+                cells[column].down = false;
+                connect_cells(&mut cells, row.node_column, column);
+            }
+            // Edges ending in a lane join it rather than crossing it.
+            for (column, _) in new_columns {
+                cells[column].crossing = false;
+            }
+            lines.push(self.render_line(&cells, None, text_lines.next().unwrap_or("")));
+        }
+
+        let cells_after = lane_cells(&row.lanes_after, row.width);
+        if let Some(column) = row.missing_column {
+            let mut cells = cells_after.clone();
+            cells[column].terminal = true;
+            lines.push(self.render_line(&cells, None, text_lines.next().unwrap_or("")));
+        }
+        for text_line in text_lines {
+            lines.push(self.render_line(&cells_after, None, text_line));
+        }
+
+        let mut lanes = row.lanes_after;
+        while lanes.last().is_some_and(Option::is_none) {
+            lanes.pop();
+        }
+        self.lanes = lanes;
+        for line in lines {
+            writeln!(self.writer, "{line}")?;
+        }
+        Ok(())
+    }
+
+    fn width(&self, id: &K, edges: &[GraphEdge<K>]) -> usize {
+        let row = self.plan_row(id, edges);
+        // Each column is followed by a space, and the text by another space.
+        row.width * 2 + 1
+    }
+}
+
+fn first_free_column<K>(lanes: &[Option<Lane<K>>]) -> usize {
+    lanes
+        .iter()
+        .position(Option::is_none)
+        .unwrap_or(lanes.len())
+}
+
+fn is_free_column<K>(lanes: &[Option<Lane<K>>], column: usize) -> bool {
+    lanes.get(column).is_none_or(Option::is_none)
+}
+
+/// Returns cells with a vertical line for each lane.
+fn lane_cells<K>(lanes: &[Option<Lane<K>>], width: usize) -> Vec<Cell> {
+    let mut cells = vec![Cell::default(); width];
+    for (cell, lane) in cells.iter_mut().zip(lanes) {
+        if let Some(lane) = lane {
+            *cell = Cell {
+                up: true,
+                down: true,
+                indirect: lane.edge_type == GraphEdgeType::Indirect,
+                ..Cell::default()
+            };
+        }
+    }
+    cells
+}
+
+/// Draws a horizontal edge between two columns. Vertical lanes in between are
+/// drawn on top of the edge.
+fn connect_cells(cells: &mut [Cell], from: usize, to: usize) {
+    let (left, right) = if from < to { (from, to) } else { (to, from) };
+    cells[left].right = true;
+    cells[right].left = true;
+    for cell in &mut cells[left + 1..right] {
+        if cell.up && cell.down && !cell.left && !cell.right {
+            cell.crossing = true;
+        }
+        cell.left = true;
+        cell.right = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_compact(
+        style: GraphStyle,
+        nodes: &[(&'static str, Vec<GraphEdge<&'static str>>)],
+    ) -> String {
+        let mut output = vec![];
+        let mut graph = CompactGraphLog::create(style, &mut output);
+        for (id, edges) in nodes {
+            graph.add_node(id, edges, "○", id).unwrap();
+        }
+        drop(graph);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_compact_graph_crossing_edge() {
+        // The edge from "c" to "a" crosses the lane of "d" to "b", which is
+        // drawn on top of the edge.
+        let nodes = [
+            ("e", vec![GraphEdge::direct("a")]),
+            ("d", vec![GraphEdge::direct("b")]),
+            ("c", vec![GraphEdge::direct("a")]),
+            ("b", vec![GraphEdge::direct("a")]),
+            ("a", vec![]),
+        ];
+        insta::assert_snapshot!(render_compact(GraphStyle::Curved, &nodes), @r"
+        ○  e
+        │ ○  d
+        │ │ ○  c
+        ├─│─╯
+        │ ○  b
+        ├─╯
+        ○  a
+        ");
+        insta::assert_snapshot!(render_compact(GraphStyle::Ascii, &nodes), @r"
+        ○  e
+        | ○  d
+        | | ○  c
+        +-|-'
+        | ○  b
+        +-'
+        ○  a
+        ");
+    }
+
+    #[test]
+    fn test_compact_graph_merge_edges() {
+        // The lane to "b" is moved into the column of "a" once "a" is rendered.
+        let nodes = [
+            ("p", vec![GraphEdge::direct("a"), GraphEdge::indirect("b")]),
+            ("a", vec![GraphEdge::direct("b")]),
+            ("b", vec![]),
+        ];
+        insta::assert_snapshot!(render_compact(GraphStyle::Curved, &nodes), @r"
+        ○    p
+        ├─╮
+        ○ ╷  a
+        ├─╯
+        ○  b
+        ");
+    }
+}
@@ -22,6 +22,7 @@ pub mod commit_templater;
 pub mod complete;
 pub mod config;
 pub mod description_util;
+mod diagnostics;
 pub mod diff_util;
 pub mod formatter;
 pub mod generic_templater;
@@ -39,14 +40,18 @@ pub mod git_util {
     }
 }
 pub mod graphlog;
+mod maintenance;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
 mod progress;
 pub mod revset_util;
+mod snapshot_daemon;
+mod syntax_highlight;
 pub mod template_builder;
 pub mod template_parser;
 pub mod templater;
+pub mod test_results;
 pub mod text_util;
 pub mod time_util;
 pub mod ui;
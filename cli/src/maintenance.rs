@@ -0,0 +1,223 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Automatic repository maintenance.
+//!
+//! When `gc.auto` is enabled, commands that write a new operation spawn a
+//! detached `jj util gc --auto` process. The background process takes the
+//! maintenance lock, re-checks whether garbage collection is due, and records
+//! the time of the run so that subsequent commands don't trigger it again
+//! until `gc.auto-interval-hours` has elapsed. Loading the repository in the
+//! background process also warms up the operation index.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use jj_lib::config::ConfigGetError;
+use jj_lib::lock::FileLock;
+use jj_lib::lock::FileLockError;
+use jj_lib::settings::UserSettings;
+
+use crate::config::ConfigArgKind;
+
+const STATE_FILE_NAME: &str = "gc_state";
+const LOCK_FILE_NAME: &str = "gc.lock";
+
+/// Returns true if automatic maintenance is enabled.
+pub fn is_auto_gc_enabled(settings: &UserSettings) -> Result<bool, ConfigGetError> {
+    settings.get_bool("gc.auto")
+}
+
+/// Returns true if the last recorded garbage collection is older than the
+/// configured interval.
+pub fn is_auto_gc_due(settings: &UserSettings, repo_path: &Path) -> Result<bool, ConfigGetError> {
+    let interval = settings.get_value_with("gc.auto-interval-hours", |value| {
+        let hours = value
+            .as_integer()
+            .and_then(|n| u64::try_from(n).ok())
+            .ok_or("Expected a non-negative integer")?;
+        hours
+            .checked_mul(3600)
+            .map(Duration::from_secs)
+            .ok_or("Interval is too large")
+    })?;
+    let Some(last_run) = read_last_gc_time(repo_path) else {
+        return Ok(true);
+    };
+    // If the clock went backwards, assume that the last run is recent.
+    let elapsed = SystemTime::now()
+        .duration_since(last_run)
+        .unwrap_or(Duration::ZERO);
+    Ok(elapsed >= interval)
+}
+
+/// Acquires the lock that serializes maintenance runs.
+pub fn lock_maintenance(repo_path: &Path) -> Result<FileLock, FileLockError> {
+    FileLock::lock(repo_path.join(LOCK_FILE_NAME))
+}
+
+/// Records the current time as the time of the last garbage collection.
+pub fn record_gc_run(repo_path: &Path) -> io::Result<()> {
+    let secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let path = state_file_path(repo_path);
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, format!("{secs}\n"))?;
+    fs::rename(&temp_path, &path)
+}
+
+/// Spawns a detached `jj util gc --auto` process if automatic maintenance is
+/// enabled and due.
+///
+/// The `--config*` arguments of the current command are passed down to the
+/// background process so that it sees the same settings. Relative
+/// `--config-file` paths are resolved against `cwd`.
+///
+/// Failures are logged and otherwise ignored, since maintenance is an
+/// optimization that shouldn't make the current command fail.
+pub fn maybe_spawn_auto_gc(
+    settings: &UserSettings,
+    workspace_root: &Path,
+    repo_path: &Path,
+    config_args: &[(ConfigArgKind, &str)],
+    cwd: &Path,
+) {
+    let should_run = is_auto_gc_enabled(settings)
+        .and_then(|enabled| Ok(enabled && is_auto_gc_due(settings, repo_path)?));
+    match should_run {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(err) => {
+            tracing::warn!(?err, "failed to read automatic maintenance config");
+            return;
+        }
+    }
+    let result = env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(["util", "gc", "--auto", "--ignore-working-copy", "--quiet"])
+            .arg("--color=never")
+            .args(config_args_to_cli(config_args, cwd))
+            .current_dir(workspace_root)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    });
+    match result {
+        // The child process is intentionally not waited for.
+        Ok(child) => tracing::info!(pid = child.id(), "spawned background gc"),
+        Err(err) => tracing::warn!(?err, "failed to spawn background gc"),
+    }
+}
+
+/// Converts `--config*` arguments back to command-line arguments.
+fn config_args_to_cli(config_args: &[(ConfigArgKind, &str)], cwd: &Path) -> Vec<OsString> {
+    config_args
+        .iter()
+        .map(|&(kind, value)| match kind {
+            ConfigArgKind::Item => format!("--config={value}").into(),
+            ConfigArgKind::Toml => format!("--config-toml={value}").into(),
+            ConfigArgKind::File => {
+                let mut arg = OsString::from("--config-file=");
+                arg.push(cwd.join(value));
+                arg
+            }
+        })
+        .collect()
+}
+
+fn state_file_path(repo_path: &Path) -> PathBuf {
+    repo_path.join(STATE_FILE_NAME)
+}
+
+fn read_last_gc_time(repo_path: &Path) -> Option<SystemTime> {
+    let content = fs::read_to_string(state_file_path(repo_path)).ok()?;
+    let secs: u64 = content.trim().parse().ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use jj_lib::config::ConfigLayer;
+    use jj_lib::config::ConfigSource;
+    use jj_lib::config::StackedConfig;
+
+    use super::*;
+
+    fn settings_with(text: &str) -> UserSettings {
+        let mut config = StackedConfig::with_defaults();
+        config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+        UserSettings::from_config(config).unwrap()
+    }
+
+    #[test]
+    fn test_is_auto_gc_due() {
+        let temp_dir = testutils::new_temp_dir();
+        let repo_path = temp_dir.path();
+        let settings = settings_with("gc.auto-interval-hours = 1");
+        assert!(is_auto_gc_due(&settings, repo_path).unwrap());
+        record_gc_run(repo_path).unwrap();
+        assert!(!is_auto_gc_due(&settings, repo_path).unwrap());
+
+        let settings = settings_with("gc.auto-interval-hours = 0");
+        assert!(is_auto_gc_due(&settings, repo_path).unwrap());
+
+        // Unparsable state is treated as if gc has never run.
+        fs::write(state_file_path(repo_path), "garbage").unwrap();
+        let settings = settings_with("gc.auto-interval-hours = 1");
+        assert!(is_auto_gc_due(&settings, repo_path).unwrap());
+    }
+
+    #[test]
+    fn test_is_auto_gc_due_invalid_interval() {
+        let temp_dir = testutils::new_temp_dir();
+        let repo_path = temp_dir.path();
+        let settings = settings_with("gc.auto-interval-hours = 9223372036854775807");
+        assert!(is_auto_gc_due(&settings, repo_path).is_err());
+        let settings = settings_with("gc.auto-interval-hours = -1");
+        assert!(is_auto_gc_due(&settings, repo_path).is_err());
+    }
+
+    #[test]
+    fn test_config_args_to_cli() {
+        let cwd = Path::new("/work");
+        let mut config_file_arg = OsString::from("--config-file=");
+        config_file_arg.push(cwd.join("extra.toml"));
+        let config_args = [
+            (ConfigArgKind::Item, "gc.auto=true"),
+            (ConfigArgKind::Toml, "gc.auto-interval-hours = 0"),
+            (ConfigArgKind::File, "extra.toml"),
+            (ConfigArgKind::Item, "user.name='Test User'"),
+        ];
+        assert_eq!(
+            config_args_to_cli(&config_args, cwd),
+            [
+                OsString::from("--config=gc.auto=true"),
+                OsString::from("--config-toml=gc.auto-interval-hours = 0"),
+                config_file_arg,
+                OsString::from("--config=user.name='Test User'"),
+            ]
+        );
+    }
+}
@@ -50,6 +50,18 @@ pub enum BuiltinToolError {
     BackendError(#[from] jj_lib::backend::BackendError),
 }
 
+/// How finely changes can be selected in the builtin diff editor.
+#[derive(serde::Deserialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffEditGranularity {
+    /// Changed lines are selected as a whole.
+    #[default]
+    Line,
+    /// Changed lines are further split into words, so that parts of a line can
+    /// be selected.
+    Word,
+}
+
 #[derive(Clone, Debug)]
 enum FileContents {
     Absent,
@@ -214,6 +226,7 @@ fn make_section_changed_lines(
 fn make_diff_sections(
     left_contents: &str,
     right_contents: &str,
+    granularity: DiffEditGranularity,
 ) -> Result<Vec<scm_record::Section<'static>>, BuiltinToolError> {
     let diff = Diff::by_line([left_contents.as_bytes(), right_contents.as_bytes()]);
     let mut sections = Vec::new();
@@ -247,14 +260,63 @@ fn make_diff_sections(
                         source: err,
                         item: "right side of diff hunk",
                     })?;
-                sections.push(scm_record::Section::Changed {
-                    lines: [
-                        make_section_changed_lines(left_side, scm_record::ChangeType::Removed),
-                        make_section_changed_lines(right_side, scm_record::ChangeType::Added),
-                    ]
-                    .concat(),
+                match granularity {
+                    DiffEditGranularity::Line => {
+                        sections.push(make_changed_section(left_side, right_side));
+                    }
+                    DiffEditGranularity::Word => {
+                        sections.extend(make_word_diff_sections(left_side, right_side)?);
+                    }
+                }
+            }
+        }
+    }
+    Ok(sections)
+}
+
+fn make_changed_section(left_side: &str, right_side: &str) -> scm_record::Section<'static> {
+    scm_record::Section::Changed {
+        lines: [
+            make_section_changed_lines(left_side, scm_record::ChangeType::Removed),
+            make_section_changed_lines(right_side, scm_record::ChangeType::Added),
+        ]
+        .concat(),
+    }
+}
+
+/// Splits a changed hunk into word-level sections. The "lines" of the
+/// resulting sections may be fragments of lines, which are concatenated back
+/// when the selected contents are computed.
+fn make_word_diff_sections(
+    left_side: &str,
+    right_side: &str,
+) -> Result<Vec<scm_record::Section<'static>>, BuiltinToolError> {
+    let diff = Diff::by_word([left_side.as_bytes(), right_side.as_bytes()]);
+    let mut sections = Vec::new();
+    for hunk in diff.hunks() {
+        // Word boundaries never split multi-byte UTF-8 sequences, so decoding
+        // shouldn't fail.
+        let [left_words, right_words] = [
+            (hunk.contents[0], "left words of diff hunk"),
+            (hunk.contents[1], "right words of diff hunk"),
+        ]
+        .map(|(text, item)| {
+            std::str::from_utf8(text)
+                .map_err(|err| BuiltinToolError::DecodeUtf8 { source: err, item })
+        });
+        match hunk.kind {
+            DiffHunkKind::Matching => {
+                let text = left_words?;
+                sections.push(scm_record::Section::Unchanged {
+                    lines: text
+                        .split_inclusive('\n')
+                        .map(|line| Cow::Owned(line.to_owned()))
+                        .collect(),
                 });
             }
+            DiffHunkKind::Different => {
+                sections.push(make_changed_section(left_words?, right_words?));
+            }
         }
     }
     Ok(sections)
@@ -264,6 +326,7 @@ async fn make_diff_files(
     store: &Arc<Store>,
     tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
     conflict_marker_style: ConflictMarkerStyle,
+    granularity: DiffEditGranularity,
 ) -> Result<(Vec<RepoPathBuf>, Vec<scm_record::File<'static>>), BuiltinToolError> {
     let mut diff_stream = materialized_diff_stream(store, tree_diff);
     let mut changed_files = Vec::new();
@@ -339,7 +402,11 @@ async fn make_diff_files(
                     num_bytes: _,
                 },
             ) => {
-                sections.extend(make_diff_sections(&old_contents, &new_contents)?);
+                sections.extend(make_diff_sections(
+                    &old_contents,
+                    &new_contents,
+                    granularity,
+                )?);
             }
 
             (
@@ -538,13 +605,14 @@ pub fn edit_diff_builtin(
     right_tree: &MergedTree,
     matcher: &dyn Matcher,
     conflict_marker_style: ConflictMarkerStyle,
+    granularity: DiffEditGranularity,
 ) -> Result<MergedTreeId, BuiltinToolError> {
     let store = left_tree.store().clone();
     // TODO: handle copy tracking
     let copy_records = CopyRecords::default();
     let tree_diff = left_tree.diff_stream_with_copies(right_tree, matcher, &copy_records);
     let (changed_files, files) =
-        make_diff_files(&store, tree_diff, conflict_marker_style).block_on()?;
+        make_diff_files(&store, tree_diff, conflict_marker_style, granularity).block_on()?;
     let mut input = scm_record::helpers::CrosstermInput;
     let recorder = scm_record::Recorder::new(
         scm_record::RecordState {
@@ -755,9 +823,14 @@ mod tests {
     ) -> (Vec<RepoPathBuf>, Vec<scm_record::File<'static>>) {
         let copy_records = CopyRecords::default();
         let tree_diff = left_tree.diff_stream_with_copies(right_tree, matcher, &copy_records);
-        make_diff_files(store, tree_diff, ConflictMarkerStyle::Diff)
-            .block_on()
-            .unwrap()
+        make_diff_files(
+            store,
+            tree_diff,
+            ConflictMarkerStyle::Diff,
+            DiffEditGranularity::Line,
+        )
+        .block_on()
+        .unwrap()
     }
 
     fn apply_diff(
@@ -907,6 +980,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_edit_diff_builtin_word_granularity() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let path = repo_path("file");
+        let left_tree =
+            testutils::create_tree(&test_repo.repo, &[(path, "let x = foo(a);\nend\n")]);
+        let right_tree =
+            testutils::create_tree(&test_repo.repo, &[(path, "let y = foo(b);\nend\n")]);
+
+        let copy_records = CopyRecords::default();
+        let tree_diff =
+            left_tree.diff_stream_with_copies(&right_tree, &EverythingMatcher, &copy_records);
+        let (changed_files, files) = make_diff_files(
+            store,
+            tree_diff,
+            ConflictMarkerStyle::Diff,
+            DiffEditGranularity::Word,
+        )
+        .block_on()
+        .unwrap();
+        insta::assert_debug_snapshot!(files[0].sections, @r#"
+        [
+            Unchanged {
+                lines: [
+                    "let ",
+                ],
+            },
+            Changed {
+                lines: [
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Removed,
+                        line: "x",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "y",
+                    },
+                ],
+            },
+            Unchanged {
+                lines: [
+                    " = foo(",
+                ],
+            },
+            Changed {
+                lines: [
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Removed,
+                        line: "a",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "b",
+                    },
+                ],
+            },
+            Unchanged {
+                lines: [
+                    ");\n",
+                ],
+            },
+            Unchanged {
+                lines: [
+                    "end\n",
+                ],
+            },
+        ]
+        "#);
+
+        // Select only the first word change
+        let mut files = files;
+        if let scm_record::Section::Changed { lines } = &mut files[0].sections[1] {
+            for line in lines {
+                line.is_checked = true;
+            }
+        }
+        let expected_tree =
+            testutils::create_tree(&test_repo.repo, &[(path, "let y = foo(a);\nend\n")]);
+        let actual_tree_id = apply_diff(store, &left_tree, &right_tree, &changed_files, &files);
+        let actual_tree = store.get_root_tree(&actual_tree_id).unwrap();
+        assert_tree_eq!(&expected_tree.id(), &actual_tree.id(), store);
+    }
+
     #[test]
     fn test_edit_diff_builtin_add_empty_file() {
         let test_repo = TestRepo::init();
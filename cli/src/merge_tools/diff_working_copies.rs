@@ -22,6 +22,7 @@ use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::store::Store;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::NewDirectoryPolicy;
 use jj_lib::working_copy::SnapshotOptions;
 use pollster::FutureExt as _;
 use tempfile::TempDir;
@@ -312,6 +313,8 @@ diff editing in mind and be a little inaccurate.
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
             conflict_marker_style,
+            new_directory_policy: NewDirectoryPolicy::Allow,
+            report_ignored_paths: false,
         })?;
         Ok(output_tree_state.current_tree_id().clone())
     }
@@ -15,6 +15,7 @@ use jj_lib::backend::TreeValue;
 use jj_lib::conflicts;
 use jj_lib::conflicts::choose_materialized_conflict_marker_len;
 use jj_lib::conflicts::materialize_merge_result_to_bytes_with_marker_len;
+use jj_lib::conflicts::ConflictLabels;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
 use jj_lib::gitignore::GitIgnoreFile;
@@ -185,7 +186,7 @@ fn run_mergetool_external_single_file(
     merge_tool_file: &MergeToolFile,
     default_conflict_marker_style: ConflictMarkerStyle,
     tree_builder: &mut MergedTreeBuilder,
-) -> Result<(), ConflictResolveError> {
+) -> Result<bool, ConflictResolveError> {
     let MergeToolFile {
         repo_path,
         conflict,
@@ -315,6 +316,7 @@ fn run_mergetool_external_single_file(
         ));
     }
 
+    let is_resolved = new_file_ids.is_resolved();
     let new_tree_value = match new_file_ids.into_resolved() {
         Ok(file_id) => {
             let executable = file.executable.expect("should have been resolved");
@@ -328,7 +330,63 @@ fn run_mergetool_external_single_file(
         Err(file_ids) => conflict.with_new_file_ids(&file_ids),
     };
     tree_builder.set_or_remove(repo_path.to_owned(), new_tree_value);
-    Ok(())
+    Ok(is_resolved)
+}
+
+/// Progress of resolving a list of conflicted files one by one.
+struct ResolutionSession<'a> {
+    merge_tool_files: &'a [MergeToolFile],
+    /// Number of files the merge tool has been run for.
+    processed_count: usize,
+    /// Number of processed files which still have conflicts.
+    unresolved_count: usize,
+}
+
+impl<'a> ResolutionSession<'a> {
+    fn new(merge_tool_files: &'a [MergeToolFile]) -> Self {
+        ResolutionSession {
+            merge_tool_files,
+            processed_count: 0,
+            unresolved_count: 0,
+        }
+    }
+
+    fn record(&mut self, is_resolved: bool) {
+        self.processed_count += 1;
+        if !is_resolved {
+            self.unresolved_count += 1;
+        }
+    }
+
+    fn total_count(&self) -> usize {
+        self.merge_tool_files.len()
+    }
+
+    /// Number of files that still have conflicts, including the ones not
+    /// processed yet.
+    fn remaining_count(&self) -> usize {
+        self.unresolved_count + self.total_count() - self.processed_count
+    }
+
+    fn next_file(&self) -> Option<&'a MergeToolFile> {
+        self.merge_tool_files.get(self.processed_count)
+    }
+
+    fn write_progress(&self, ui: &Ui, path_converter: &RepoPathUiConverter) -> io::Result<()> {
+        let Some(mut formatter) = ui.status_formatter() else {
+            return Ok(());
+        };
+        let Some(next_file) = self.next_file() else {
+            return Ok(());
+        };
+        writeln!(
+            formatter.labeled("resolve_progress"),
+            "Remaining conflicts: {} of {} files; next: {}",
+            self.remaining_count(),
+            self.total_count(),
+            path_converter.format_file_path(&next_file.repo_path)
+        )
+    }
 }
 
 pub fn run_mergetool_external(
@@ -343,6 +401,7 @@ pub fn run_mergetool_external(
     // "diff-invocation-mode" config option for diffs
     let mut tree_builder = MergedTreeBuilder::new(tree.id());
     let mut partial_resolution_error = None;
+    let mut session = ResolutionSession::new(merge_tool_files);
     for (i, merge_tool_file) in merge_tool_files.iter().enumerate() {
         writeln!(
             ui.status(),
@@ -356,7 +415,10 @@ pub fn run_mergetool_external(
             default_conflict_marker_style,
             &mut tree_builder,
         ) {
-            Ok(()) => {}
+            Ok(is_resolved) => {
+                session.record(is_resolved);
+                session.write_progress(ui, path_converter)?;
+            }
             Err(err) if i == 0 => {
                 // If the first resolution fails, just return the error normally
                 return Err(err);
@@ -390,6 +452,8 @@ pub fn edit_diff_external(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_labels: ConflictLabels::unlabeled(),
+        progress: None,
     };
 
     let got_output_field = find_all_variables(&editor.edit_args).contains(&"output");
@@ -438,6 +502,8 @@ pub fn generate_diff(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_labels: ConflictLabels::unlabeled(),
+        progress: None,
     };
     let store = left_tree.store();
     let diff_wc = check_out_trees(store, left_tree, right_tree, matcher, None, &options)?;
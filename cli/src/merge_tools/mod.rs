@@ -47,7 +47,9 @@ use thiserror::Error;
 use self::builtin::edit_diff_builtin;
 use self::builtin::edit_merge_builtin;
 use self::builtin::BuiltinToolError;
+pub use self::builtin::DiffEditGranularity;
 pub(crate) use self::diff_working_copies::new_utf8_temp_dir;
+pub(crate) use self::diff_working_copies::set_readonly_recursively;
 use self::diff_working_copies::DiffCheckoutError;
 use self::external::edit_diff_external;
 pub use self::external::generate_diff;
@@ -237,6 +239,7 @@ pub struct DiffEditor {
     base_ignores: Arc<GitIgnoreFile>,
     use_instructions: bool,
     conflict_marker_style: ConflictMarkerStyle,
+    builtin_granularity: DiffEditGranularity,
 }
 
 impl DiffEditor {
@@ -287,6 +290,7 @@ impl DiffEditor {
             base_ignores,
             use_instructions: settings.get_bool("ui.diff-instructions")?,
             conflict_marker_style,
+            builtin_granularity: settings.get("ui.diff-editor-granularity")?,
         })
     }
 
@@ -299,12 +303,14 @@ impl DiffEditor {
         format_instructions: impl FnOnce() -> String,
     ) -> Result<MergedTreeId, DiffEditError> {
         match &self.tool {
-            DiffEditTool::Builtin => {
-                Ok(
-                    edit_diff_builtin(left_tree, right_tree, matcher, self.conflict_marker_style)
-                        .map_err(Box::new)?,
-                )
-            }
+            DiffEditTool::Builtin => Ok(edit_diff_builtin(
+                left_tree,
+                right_tree,
+                matcher,
+                self.conflict_marker_style,
+                self.builtin_granularity,
+            )
+            .map_err(Box::new)?),
             DiffEditTool::External(editor) => {
                 let instructions = self.use_instructions.then(format_instructions);
                 edit_diff_external(
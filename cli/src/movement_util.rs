@@ -37,6 +37,7 @@ pub(crate) struct MovementArgs {
     pub edit: bool,
     pub no_edit: bool,
     pub conflict: bool,
+    pub skip_empty: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -44,6 +45,7 @@ struct MovementArgsInternal {
     offset: u64,
     should_edit: bool,
     conflict: bool,
+    skip_empty: bool,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -145,6 +147,20 @@ impl Direction {
                 .heads(),
         };
 
+        // Keep moving in the same direction past empty commits until the first
+        // non-empty one.
+        let target_revset = match (self, args.skip_empty) {
+            (_, false) => target_revset,
+            (Direction::Next, true) => target_revset
+                .descendants()
+                .minus(&RevsetExpression::is_empty())
+                .roots(),
+            (Direction::Prev, true) => target_revset
+                .ancestors()
+                .minus(&RevsetExpression::is_empty())
+                .heads(),
+        };
+
         Ok(target_revset)
     }
 }
@@ -241,6 +257,7 @@ pub(crate) fn move_to_commit(
         should_edit: args.edit || (!args.no_edit && config_edit_flag),
         offset: args.offset,
         conflict: args.conflict,
+        skip_empty: args.skip_empty,
     };
 
     let target = get_target_commit(ui, &workspace_command, direction, current_wc_id, &args)?;
@@ -250,7 +267,13 @@ pub(crate) fn move_to_commit(
     // We're editing, just move to the target commit.
     if args.should_edit {
         // We're editing, the target must be rewritable.
-        workspace_command.check_rewritable([target.id()])?;
+        workspace_command
+            .check_rewritable([target.id()])
+            .map_err(|err| {
+                err.hinted(format!(
+                    "Use `jj {cmd} --no-edit` to create a new commit on top of it instead."
+                ))
+            })?;
         let mut tx = workspace_command.start_transaction();
         tx.edit(&target)?;
         tx.finish(
@@ -15,15 +15,24 @@
 use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io;
+use std::sync::Arc;
 
+use futures::StreamExt as _;
 use itertools::Itertools as _;
 use jj_lib::extensions_map::ExtensionsMap;
+use jj_lib::matchers::EverythingMatcher;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::OpStoreResult;
 use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
+use jj_lib::refs;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::UserSettings;
+use jj_lib::store::Store;
+use jj_lib::view::View;
+use pollster::FutureExt as _;
 
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
@@ -33,6 +42,7 @@ use crate::template_builder::CoreTemplatePropertyKind;
 use crate::template_builder::CoreTemplatePropertyVar;
 use crate::template_builder::TemplateBuildMethodFnMap;
 use crate::template_builder::TemplateLanguage;
+use crate::template_builder::TemplateSymbolNames;
 use crate::template_parser;
 use crate::template_parser::FunctionCallNode;
 use crate::template_parser::TemplateDiagnostics;
@@ -43,6 +53,7 @@ use crate::templater::ListTemplate;
 use crate::templater::PlainTextFormattedProperty;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
+use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
 
 pub trait OperationTemplateLanguageExtension {
@@ -252,7 +263,7 @@ pub struct OperationTemplateBuildFnTable {
 
 impl OperationTemplateBuildFnTable {
     /// Creates new symbol table containing the builtin methods.
-    fn builtin() -> Self {
+    pub fn builtin() -> Self {
         OperationTemplateBuildFnTable {
             core: CoreTemplateBuildFnTable::builtin(),
             operation_methods: builtin_operation_methods(),
@@ -283,6 +294,15 @@ impl OperationTemplateBuildFnTable {
         merge_fn_map(&mut self.operation_list_methods, operation_list_methods);
         merge_fn_map(&mut self.operation_id_methods, operation_id_methods);
     }
+
+    /// Returns the names of the functions and methods in this table.
+    pub fn symbol_names(&self) -> TemplateSymbolNames {
+        let mut names = self.core.symbol_names();
+        names.add_methods("Operation", &self.operation_methods);
+        names.add_methods("List<Operation>", &self.operation_list_methods);
+        names.add_methods("OperationId", &self.operation_id_methods);
+        names
+    }
 }
 
 fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
@@ -376,9 +396,121 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "changed_bookmarks_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| {
+                let Some((view, parent_view)) = load_op_and_parent_views(&op)? else {
+                    return Ok(0);
+                };
+                let count = refs::diff_named_ref_targets(
+                    view.local_bookmarks(),
+                    parent_view.local_bookmarks(),
+                )
+                .count();
+                Ok(i64::try_from(count)?)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "added_heads_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| {
+                let Some((view, parent_view)) = load_op_and_parent_views(&op)? else {
+                    return Ok(0);
+                };
+                let count = view.heads().difference(parent_view.heads()).count();
+                Ok(i64::try_from(count)?)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "removed_heads_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| {
+                let Some((view, parent_view)) = load_op_and_parent_views(&op)? else {
+                    return Ok(0);
+                };
+                let count = parent_view.heads().difference(view.heads()).count();
+                Ok(i64::try_from(count)?)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "changed_paths_count",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let ([], [limit_node]) = function.expect_arguments()?;
+            let limit_property = limit_node
+                .map(|node| {
+                    template_builder::expect_usize_expression(
+                        language,
+                        diagnostics,
+                        build_ctx,
+                        node,
+                    )
+                })
+                .transpose()?;
+            let store = language.repo_loader.store().clone();
+            let out_property = (self_property, limit_property).and_then(move |(op, limit)| {
+                let limit = limit.unwrap_or(DEFAULT_CHANGED_PATHS_LIMIT);
+                let count = count_changed_wc_paths(&store, &op, limit)?;
+                Ok(i64::try_from(count)?)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
+/// Default maximum number of paths to be counted by `changed_paths_count()`.
+const DEFAULT_CHANGED_PATHS_LIMIT: usize = 1000;
+
+/// Loads views of the operation and its first parent. Returns `None` for the
+/// root operation.
+fn load_op_and_parent_views(op: &Operation) -> OpStoreResult<Option<(View, View)>> {
+    let Some(parent_op) = op.parents().next() else {
+        return Ok(None);
+    };
+    Ok(Some((op.view()?, parent_op?.view()?)))
+}
+
+/// Counts paths changed in working-copy commits between the operation and its
+/// first parent, up to `limit`.
+fn count_changed_wc_paths(
+    store: &Arc<Store>,
+    op: &Operation,
+    limit: usize,
+) -> Result<usize, TemplatePropertyError> {
+    let Some((view, parent_view)) = load_op_and_parent_views(op)? else {
+        return Ok(0);
+    };
+    let mut changed_paths = HashSet::new();
+    for (name, commit_id) in view.wc_commit_ids() {
+        let Some(parent_commit_id) = parent_view.get_wc_commit_id(name) else {
+            continue;
+        };
+        if commit_id == parent_commit_id {
+            continue;
+        }
+        let tree = store.get_commit(commit_id)?.tree()?;
+        let parent_tree = store.get_commit(parent_commit_id)?.tree()?;
+        let mut diff_stream = parent_tree.diff_stream(&tree, &EverythingMatcher);
+        while let Some(entry) = diff_stream.next().block_on() {
+            if changed_paths.len() >= limit {
+                return Ok(limit);
+            }
+            changed_paths.insert(entry.path);
+        }
+    }
+    Ok(changed_paths.len())
+}
+
 impl Template for OperationId {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{}", self.hex())
@@ -14,6 +14,7 @@
 
 //! Utility for parsing and evaluating user-provided revset expressions.
 
+use std::any::Any;
 use std::io;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -29,6 +30,8 @@ use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::ref_name::RefNameBuf;
 use jj_lib::repo::Repo;
 use jj_lib::revset;
+use jj_lib::revset::FunctionCallNode;
+use jj_lib::revset::LoweringContext;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
@@ -36,6 +39,8 @@ use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetExtensions;
+use jj_lib::revset::RevsetFilterExtension;
+use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::RevsetParseError;
@@ -43,10 +48,12 @@ use jj_lib::revset::RevsetResolutionError;
 use jj_lib::revset::SymbolResolver;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::revset::UserRevsetExpression;
+use jj_lib::str_util::StringPattern;
 use thiserror::Error;
 
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::description_util::topic_from_description;
 use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
@@ -227,6 +234,43 @@ pub fn parse_immutable_heads_expression(
     Ok(heads.union(&RevsetExpression::root()))
 }
 
+#[derive(Debug)]
+struct TopicFilter {
+    pattern: StringPattern,
+}
+
+impl RevsetFilterExtension for TopicFilter {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn matches_commit(&self, commit: &Commit) -> bool {
+        // Match the topic shown by the template, which is the last one if
+        // there are multiple topic trailers.
+        topic_from_description(commit.description())
+            .is_some_and(|topic| self.pattern.is_match(&topic))
+    }
+}
+
+/// Implements the `topic(name)` revset function.
+///
+/// Unlike most string arguments, a plain topic name is matched exactly. Other
+/// kinds of matching can be requested by an explicit string pattern.
+pub fn topic_revset_function(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    _context: &LoweringContext,
+) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
+    let [arg] = function.expect_exact_arguments()?;
+    let pattern = match revset::expect_literal::<String>("string", arg) {
+        Ok(name) => StringPattern::exact(name),
+        Err(_) => revset::expect_string_pattern(diagnostics, arg)?,
+    };
+    Ok(RevsetExpression::filter(RevsetFilterPredicate::Extension(
+        Rc::new(TopicFilter { pattern }),
+    )))
+}
+
 /// Prints warning if `trunk()` alias cannot be resolved. This alias could be
 /// generated by `jj git init`/`clone`.
 pub(super) fn warn_unresolvable_trunk(
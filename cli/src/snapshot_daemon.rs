@@ -0,0 +1,322 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handshake with a `jj debug snapshot --watch` process.
+//!
+//! The watching process listens on a Unix socket in the working-copy state
+//! directory. Before loading the repo, regular commands connect to it and send
+//! the fingerprint of their config. If the config matches, the watching
+//! process snapshots the working copy on their behalf, and the command skips
+//! its own snapshot. Otherwise, the command snapshots by itself.
+//!
+//! Either way, the command keeps the connection open until it exits, and the
+//! watching process doesn't touch the working copy while any connection is
+//! open. This prevents it from racing with the command's own updates of the
+//! working copy.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use jj_lib::config::StackedConfig;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::hex_util;
+
+const SOCKET_FILE_NAME: &str = "snapshot-watch.sock";
+const REQUEST_PREFIX: &str = "snapshot ";
+const SNAPSHOTTED_RESPONSE: &str = "snapshotted";
+const SKIPPED_RESPONSE: &str = "skipped";
+/// How long a command waits for the watching process to snapshot.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the path to the socket of the workspace at `workspace_root`.
+pub(crate) fn socket_path(workspace_root: &Path) -> PathBuf {
+    workspace_root
+        .join(".jj")
+        .join("working_copy")
+        .join(SOCKET_FILE_NAME)
+}
+
+/// Returns a hash of all config layers.
+///
+/// The watching process only snapshots on behalf of commands with the same
+/// fingerprint, since the config affects which files are tracked, how they
+/// are hashed, and the metadata of the snapshot commit and operation.
+pub(crate) fn config_fingerprint(config: &StackedConfig) -> String {
+    let mut layers = vec![env!("CARGO_PKG_VERSION").to_owned()];
+    layers.extend(
+        config
+            .layers()
+            .iter()
+            .map(|layer| format!("{}\n{}", layer.source, layer.data)),
+    );
+    hex_util::encode_hex(&blake2b_hash(&layers))
+}
+
+/// Connection to the watching process, which is paused while this is alive.
+#[cfg_attr(not(unix), expect(dead_code))]
+pub(crate) struct SnapshotLease {
+    #[cfg(unix)]
+    _stream: std::os::unix::net::UnixStream,
+    snapshotted: bool,
+}
+
+impl SnapshotLease {
+    /// Whether the watching process snapshotted the working copy on behalf of
+    /// this command.
+    pub fn snapshotted(&self) -> bool {
+        self.snapshotted
+    }
+}
+
+/// Asks the watching process, if any, to snapshot the working copy.
+///
+/// Returns `None` if no process is watching the working copy, or if it failed
+/// to respond. The caller should snapshot by itself unless the returned lease
+/// says otherwise.
+#[cfg(unix)]
+pub(crate) fn request_snapshot(socket_path: &Path, fingerprint: &str) -> Option<SnapshotLease> {
+    use std::io::BufRead as _;
+    use std::io::BufReader;
+    use std::io::Write as _;
+    use std::os::unix::net::UnixStream;
+
+    if !socket_path.exists() {
+        return None;
+    }
+    let mut stream = UnixStream::connect(socket_path).ok()?;
+    let mut handshake = || -> std::io::Result<bool> {
+        stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+        stream.set_write_timeout(Some(RESPONSE_TIMEOUT))?;
+        writeln!(stream, "{REQUEST_PREFIX}{fingerprint}")?;
+        let mut response = String::new();
+        BufReader::new(&stream).read_line(&mut response)?;
+        Ok(response.trim_end() == SNAPSHOTTED_RESPONSE)
+    };
+    match handshake() {
+        Ok(snapshotted) => Some(SnapshotLease {
+            _stream: stream,
+            snapshotted,
+        }),
+        Err(err) => {
+            tracing::debug!(?err, "failed to communicate with snapshot watcher");
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn request_snapshot(_socket_path: &Path, _fingerprint: &str) -> Option<SnapshotLease> {
+    None
+}
+
+#[cfg(unix)]
+pub(crate) use self::platform::ClientConnection;
+#[cfg(unix)]
+pub(crate) use self::platform::SnapshotListener;
+
+#[cfg(unix)]
+mod platform {
+    use std::io;
+    use std::io::BufRead as _;
+    use std::io::BufReader;
+    use std::io::Read as _;
+    use std::io::Write as _;
+    use std::os::unix::net::UnixListener;
+    use std::os::unix::net::UnixStream;
+    use std::path::Path;
+
+    use super::REQUEST_PREFIX;
+    use super::RESPONSE_TIMEOUT;
+    use super::SKIPPED_RESPONSE;
+    use super::SNAPSHOTTED_RESPONSE;
+    use crate::cleanup_guard::CleanupGuard;
+
+    /// Listens for snapshot requests from other commands.
+    pub(crate) struct SnapshotListener {
+        listener: UnixListener,
+        // Removes the socket file on exit, including on SIGINT/SIGTERM.
+        _guard: CleanupGuard,
+    }
+
+    impl SnapshotListener {
+        /// Creates the socket at `socket_path`. Fails if another process is
+        /// already listening on it.
+        pub fn bind(socket_path: &Path) -> io::Result<Self> {
+            if socket_path.exists() {
+                if UnixStream::connect(socket_path).is_ok() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        "Another process is already watching this working copy",
+                    ));
+                }
+                // Left behind by a process that was killed
+                std::fs::remove_file(socket_path)?;
+            }
+            let listener = UnixListener::bind(socket_path)?;
+            listener.set_nonblocking(true)?;
+            let path = socket_path.to_owned();
+            let guard = CleanupGuard::new(move || {
+                std::fs::remove_file(&path).ok();
+            });
+            Ok(SnapshotListener {
+                listener,
+                _guard: guard,
+            })
+        }
+
+        /// Returns the pending snapshot requests without blocking.
+        pub fn pending_requests(&self) -> io::Result<Vec<SnapshotRequest>> {
+            let mut requests = vec![];
+            loop {
+                match self.listener.accept() {
+                    Ok((stream, _)) => {
+                        if let Some(request) = SnapshotRequest::read(stream) {
+                            requests.push(request);
+                        }
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(requests),
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+    }
+
+    /// Connection from a command waiting for the snapshot.
+    pub(crate) struct SnapshotRequest {
+        stream: UnixStream,
+        fingerprint: String,
+    }
+
+    impl SnapshotRequest {
+        fn read(stream: UnixStream) -> Option<Self> {
+            let read_request = || -> io::Result<Option<String>> {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+                stream.set_write_timeout(Some(RESPONSE_TIMEOUT))?;
+                let mut request = String::new();
+                BufReader::new(&stream).read_line(&mut request)?;
+                let fingerprint = request
+                    .strip_suffix('\n')
+                    .and_then(|line| line.strip_prefix(REQUEST_PREFIX));
+                Ok(fingerprint.map(ToOwned::to_owned))
+            };
+            match read_request() {
+                Ok(Some(fingerprint)) => Some(SnapshotRequest {
+                    stream,
+                    fingerprint,
+                }),
+                Ok(None) => None,
+                Err(err) => {
+                    tracing::debug!(?err, "failed to read snapshot request");
+                    None
+                }
+            }
+        }
+
+        /// Config fingerprint of the requesting command.
+        pub fn fingerprint(&self) -> &str {
+            &self.fingerprint
+        }
+
+        /// Tells the command whether the working copy was snapshotted on its
+        /// behalf. Returns the connection which stays open until the command
+        /// exits.
+        pub fn reply(mut self, snapshotted: bool) -> ClientConnection {
+            let response = if snapshotted {
+                SNAPSHOTTED_RESPONSE
+            } else {
+                SKIPPED_RESPONSE
+            };
+            // The command may have given up waiting
+            writeln!(self.stream, "{response}").ok();
+            ClientConnection {
+                stream: self.stream,
+            }
+        }
+    }
+
+    /// Connection from a running command.
+    pub(crate) struct ClientConnection {
+        stream: UnixStream,
+    }
+
+    impl ClientConnection {
+        /// Returns true if the command exited.
+        pub fn is_closed(&self) -> bool {
+            if let Err(err) = self.stream.set_nonblocking(true) {
+                tracing::debug!(?err, "failed to poll snapshot client");
+                return true;
+            }
+            let mut buf = [0; 1];
+            match (&self.stream).read(&mut buf) {
+                Ok(0) => true,
+                Ok(_) => false,
+                Err(err) => err.kind() != io::ErrorKind::WouldBlock,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_request_snapshot() {
+        let temp_dir = testutils::new_temp_dir();
+        let socket_path = temp_dir.path().join(SOCKET_FILE_NAME);
+
+        // No process is watching
+        assert!(request_snapshot(&socket_path, "fingerprint").is_none());
+
+        let listener = SnapshotListener::bind(&socket_path).unwrap();
+        assert!(listener.pending_requests().unwrap().is_empty());
+        // Only one process can watch the working copy
+        assert!(SnapshotListener::bind(&socket_path).is_err());
+
+        let serve = |snapshotted: bool| {
+            thread::scope(|s| {
+                let client = s.spawn(|| request_snapshot(&socket_path, "fingerprint").unwrap());
+                let requests = loop {
+                    let requests = listener.pending_requests().unwrap();
+                    if !requests.is_empty() {
+                        break requests;
+                    }
+                    thread::yield_now();
+                };
+                assert_eq!(requests.len(), 1);
+                let request = requests.into_iter().next().unwrap();
+                assert_eq!(request.fingerprint(), "fingerprint");
+                let connection = request.reply(snapshotted);
+                let lease = client.join().unwrap();
+                (lease, connection)
+            })
+        };
+        let (lease, connection) = serve(true);
+        assert!(lease.snapshotted());
+        // The connection stays open until the lease is dropped
+        assert!(!connection.is_closed());
+        drop(lease);
+        assert!(connection.is_closed());
+        let (lease, _connection) = serve(false);
+        assert!(!lease.snapshotted());
+
+        // The socket is removed when the process exits
+        drop(listener);
+        assert!(!socket_path.exists());
+    }
+}
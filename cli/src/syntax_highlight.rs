@@ -0,0 +1,280 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Syntax highlighting of file contents in diffs.
+//!
+//! The highlighter is backed by tree-sitter grammars, which are only compiled
+//! in if the `syntax-highlight` feature is enabled. Without the feature, no
+//! highlights are produced and diffs are rendered as before.
+
+use std::io;
+use std::ops::Range;
+
+use jj_lib::repo_path::RepoPath;
+
+use crate::formatter::Formatter;
+
+/// Names of the highlight captures that are emitted as formatter labels.
+///
+/// Capture names reported by the grammars (e.g. `function.method`) are mapped
+/// to the longest matching entry of this list.
+#[cfg_attr(not(feature = "syntax-highlight"), expect(dead_code))]
+const HIGHLIGHT_NAMES: &[&str] = &[
+    "attribute",
+    "comment",
+    "constant",
+    "constructor",
+    "function",
+    "keyword",
+    "label",
+    "number",
+    "operator",
+    "property",
+    "punctuation",
+    "string",
+    "type",
+    "variable",
+];
+
+/// Highlighted byte ranges of file contents to be rendered.
+///
+/// The highlighted contents are borrowed so that the diff renderer can look up
+/// highlights of any token sliced from them. Data that isn't a subslice of
+/// highlighted contents (e.g. materialized conflicts) is written without
+/// syntax labels.
+#[derive(Debug, Default)]
+pub struct SyntaxHighlights<'a> {
+    sources: Vec<SourceHighlights<'a>>,
+}
+
+#[derive(Debug)]
+struct SourceHighlights<'a> {
+    /// The highlighted contents.
+    content: &'a [u8],
+    /// Sorted and non-overlapping ranges of highlighted bytes.
+    spans: Vec<(Range<usize>, &'static str)>,
+}
+
+impl<'a> SyntaxHighlights<'a> {
+    /// Creates an empty set of highlights.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Highlights `source` if the language can be detected from the `path`
+    /// extension.
+    pub fn add_source(&mut self, path: &RepoPath, source: &'a [u8]) {
+        if source.is_empty() {
+            return;
+        }
+        let Some(spans) = highlight_spans(path, source) else {
+            return;
+        };
+        self.sources.push(SourceHighlights {
+            content: source,
+            spans,
+        });
+    }
+
+    /// Writes `data` with `syntax <name>` labels applied to the highlighted
+    /// ranges.
+    pub fn write_data(&self, formatter: &mut dyn Formatter, data: &[u8]) -> io::Result<()> {
+        let Some((offset, spans)) = self.locate(data) else {
+            return formatter.write_all(data);
+        };
+        let end = offset + data.len();
+        let mut pos = offset;
+        let first = spans.partition_point(|(range, _)| range.end <= offset);
+        for (range, name) in &spans[first..] {
+            if range.start >= end {
+                break;
+            }
+            let start = range.start.max(pos);
+            let stop = range.end.min(end);
+            formatter.write_all(&data[pos - offset..start - offset])?;
+            formatter.with_label("syntax", |formatter| {
+                formatter.with_label(name, |formatter| {
+                    formatter.write_all(&data[start - offset..stop - offset])
+                })
+            })?;
+            pos = stop;
+        }
+        formatter.write_all(&data[pos - offset..])
+    }
+
+    /// Returns the offset of `data` within the highlighted contents and the
+    /// spans of the contents.
+    fn locate(&self, data: &[u8]) -> Option<(usize, &[(Range<usize>, &'static str)])> {
+        self.sources.iter().find_map(|source| {
+            let offset = subslice_offset(source.content, data)?;
+            Some((offset, source.spans.as_slice()))
+        })
+    }
+}
+
+/// Returns the offset of `data` if it is a subslice of `content`.
+fn subslice_offset(content: &[u8], data: &[u8]) -> Option<usize> {
+    let content_range = content.as_ptr_range();
+    let data_range = data.as_ptr_range();
+    if content_range.start <= data_range.start && data_range.end <= content_range.end {
+        Some(data_range.start as usize - content_range.start as usize)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "syntax-highlight")]
+fn highlight_spans(path: &RepoPath, source: &[u8]) -> Option<Vec<(Range<usize>, &'static str)>> {
+    use tree_sitter_highlight::HighlightEvent;
+    use tree_sitter_highlight::Highlighter;
+
+    let config = languages::config_for_path(path)?;
+    let mut highlighter = Highlighter::new();
+    let events = highlighter
+        .highlight(config, source, None, |_| None)
+        .inspect_err(|err| tracing::debug!(?err, ?path, "failed to highlight"))
+        .ok()?;
+    let mut spans: Vec<(Range<usize>, &'static str)> = Vec::new();
+    let mut stack = Vec::new();
+    for event in events {
+        match event.ok()? {
+            HighlightEvent::HighlightStart(highlight) => stack.push(HIGHLIGHT_NAMES[highlight.0]),
+            HighlightEvent::HighlightEnd => {
+                stack.pop();
+            }
+            HighlightEvent::Source { start, end } => {
+                // Nested captures are flattened to the innermost one.
+                let Some(&name) = stack.last() else {
+                    continue;
+                };
+                match spans.last_mut() {
+                    Some((range, last_name)) if range.end == start && *last_name == name => {
+                        range.end = end;
+                    }
+                    _ => spans.push((start..end, name)),
+                }
+            }
+        }
+    }
+    Some(spans)
+}
+
+#[cfg(not(feature = "syntax-highlight"))]
+fn highlight_spans(_path: &RepoPath, _source: &[u8]) -> Option<Vec<(Range<usize>, &'static str)>> {
+    None
+}
+
+#[cfg(feature = "syntax-highlight")]
+mod languages {
+    use std::sync::LazyLock;
+
+    use jj_lib::repo_path::RepoPath;
+    use tree_sitter_highlight::HighlightConfiguration;
+
+    use super::HIGHLIGHT_NAMES;
+
+    fn new_config(
+        language: tree_sitter_language::LanguageFn,
+        name: &str,
+        highlights_query: &str,
+    ) -> HighlightConfiguration {
+        let mut config =
+            HighlightConfiguration::new(language.into(), name, highlights_query, "", "")
+                .expect("bundled highlights query should be valid");
+        config.configure(HIGHLIGHT_NAMES);
+        config
+    }
+
+    static GO: LazyLock<HighlightConfiguration> = LazyLock::new(|| {
+        new_config(
+            tree_sitter_go::LANGUAGE,
+            "go",
+            tree_sitter_go::HIGHLIGHTS_QUERY,
+        )
+    });
+    static PYTHON: LazyLock<HighlightConfiguration> = LazyLock::new(|| {
+        new_config(
+            tree_sitter_python::LANGUAGE,
+            "python",
+            tree_sitter_python::HIGHLIGHTS_QUERY,
+        )
+    });
+    static RUST: LazyLock<HighlightConfiguration> = LazyLock::new(|| {
+        new_config(
+            tree_sitter_rust::LANGUAGE,
+            "rust",
+            tree_sitter_rust::HIGHLIGHTS_QUERY,
+        )
+    });
+
+    /// Looks up the highlighter configuration by file extension.
+    pub fn config_for_path(path: &RepoPath) -> Option<&'static HighlightConfiguration> {
+        let (_, file_name) = path.split()?;
+        let (_, extension) = file_name.as_internal_str().rsplit_once('.')?;
+        match extension {
+            "go" => Some(&*GO),
+            "py" | "pyi" => Some(&*PYTHON),
+            "rs" => Some(&*RUST),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jj_lib::config::StackedConfig;
+
+    use super::*;
+    use crate::formatter::ColorFormatter;
+
+    fn format_labeled(write: impl FnOnce(&mut dyn Formatter) -> io::Result<()>) -> String {
+        let mut output = Vec::new();
+        let mut formatter =
+            ColorFormatter::for_config(&mut output, &StackedConfig::empty(), true).unwrap();
+        write(&mut formatter).unwrap();
+        drop(formatter);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_data_outside_sources() {
+        let highlights = SyntaxHighlights::new();
+        let output =
+            format_labeled(|formatter| highlights.write_data(formatter, b"fn main() {}\n"));
+        insta::assert_snapshot!(output, @"fn main() {}");
+    }
+
+    #[test]
+    fn test_write_data_spans() {
+        let source = b"let x = 1;\n";
+        let highlights = SyntaxHighlights {
+            sources: vec![SourceHighlights {
+                content: source,
+                spans: vec![(0..3, "keyword"), (8..9, "number")],
+            }],
+        };
+        // Tokens sliced in the middle of spans are labeled as a whole.
+        let output = format_labeled(|formatter| {
+            highlights.write_data(formatter, &source[1..9])?;
+            highlights.write_data(formatter, &source[9..])
+        });
+        insta::assert_snapshot!(output, @"<<syntax keyword::et>> x = <<syntax number::1>>;");
+
+        // Equal data that isn't sliced from the highlighted contents isn't
+        // labeled.
+        let other = source.to_vec();
+        let output = format_labeled(|formatter| highlights.write_data(formatter, &other));
+        insta::assert_snapshot!(output, @"let x = 1;");
+    }
+}
@@ -20,6 +20,7 @@ use std::iter;
 use itertools::Itertools as _;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigValue;
 use jj_lib::op_store::TimestampRange;
@@ -55,6 +56,7 @@ use crate::templater::Literal;
 use crate::templater::PlainTextFormattedProperty;
 use crate::templater::PropertyPlaceholder;
 use crate::templater::RawEscapeSequenceTemplate;
+use crate::templater::RecordProperty;
 use crate::templater::ReformatTemplate;
 use crate::templater::SeparateTemplate;
 use crate::templater::SizeHint;
@@ -62,6 +64,7 @@ use crate::templater::Template;
 use crate::templater::TemplateProperty;
 use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
+use crate::templater::TemplateRecord;
 use crate::templater::TemplateRenderer;
 use crate::templater::WrapTemplateProperty;
 use crate::text_util;
@@ -160,6 +163,7 @@ where
     Self: WrapTemplateProperty<'a, SizeHint>,
     Self: WrapTemplateProperty<'a, Timestamp>,
     Self: WrapTemplateProperty<'a, TimestampRange>,
+    Self: WrapTemplateProperty<'a, TemplateRecord>,
 {
     fn wrap_template(template: Box<dyn Template + 'a>) -> Self;
     fn wrap_list_template(template: Box<dyn ListTemplate + 'a>) -> Self;
@@ -194,6 +198,7 @@ pub enum CoreTemplatePropertyKind<'a> {
     SizeHint(BoxedTemplateProperty<'a, SizeHint>),
     Timestamp(BoxedTemplateProperty<'a, Timestamp>),
     TimestampRange(BoxedTemplateProperty<'a, TimestampRange>),
+    Record(BoxedTemplateProperty<'a, TemplateRecord>),
 
     // Both TemplateProperty and Template can represent a value to be evaluated
     // dynamically, which suggests that `Box<dyn Template + 'a>` could be
@@ -227,6 +232,7 @@ macro_rules! impl_core_property_wrappers {
             SizeHint($crate::templater::SizeHint),
             Timestamp(jj_lib::backend::Timestamp),
             TimestampRange(jj_lib::op_store::TimestampRange),
+            Record($crate::templater::TemplateRecord),
         });
     };
 }
@@ -257,6 +263,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::SizeHint(_) => "SizeHint",
             Self::Timestamp(_) => "Timestamp",
             Self::TimestampRange(_) => "TimestampRange",
+            Self::Record(_) => "Record",
             Self::Template(_) => "Template",
             Self::ListTemplate(_) => "ListTemplate",
         }
@@ -275,6 +282,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::SizeHint(_) => None,
             Self::Timestamp(_) => None,
             Self::TimestampRange(_) => None,
+            Self::Record(_) => None,
             // Template types could also be evaluated to boolean, but it's less likely
             // to apply label() or .map() and use the result as conditional. It's also
             // unclear whether ListTemplate should behave as a "list" or a "template".
@@ -314,6 +322,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::SizeHint(property) => Some(property.into_serialize()),
             Self::Timestamp(property) => Some(property.into_serialize()),
             Self::TimestampRange(property) => Some(property.into_serialize()),
+            Self::Record(property) => Some(property.into_serialize()),
             Self::Template(_) => None,
             Self::ListTemplate(_) => None,
         }
@@ -332,6 +341,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::SizeHint(_) => None,
             Self::Timestamp(property) => Some(property.into_template()),
             Self::TimestampRange(property) => Some(property.into_template()),
+            Self::Record(property) => Some(property.into_template()),
             Self::Template(template) => Some(template),
             Self::ListTemplate(template) => Some(template.into_template()),
         }
@@ -377,6 +387,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::SizeHint(_), _) => None,
             (Self::Timestamp(_), _) => None,
             (Self::TimestampRange(_), _) => None,
+            (Self::Record(_), _) => None,
             (Self::Template(_), _) => None,
             (Self::ListTemplate(_), _) => None,
         }
@@ -407,6 +418,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::SizeHint(_), _) => None,
             (Self::Timestamp(_), _) => None,
             (Self::TimestampRange(_), _) => None,
+            (Self::Record(_), _) => None,
             (Self::Template(_), _) => None,
             (Self::ListTemplate(_), _) => None,
         }
@@ -471,10 +483,30 @@ pub struct CoreTemplateBuildFnTable<'a, L: TemplateLanguage<'a> + ?Sized> {
     pub size_hint_methods: TemplateBuildMethodFnMap<'a, L, SizeHint>,
     pub timestamp_methods: TemplateBuildMethodFnMap<'a, L, Timestamp>,
     pub timestamp_range_methods: TemplateBuildMethodFnMap<'a, L, TimestampRange>,
+    pub record_methods: TemplateBuildMethodFnMap<'a, L, TemplateRecord>,
     pub template_methods: BuildTemplateMethodFnMap<'a, L>,
     pub list_template_methods: BuildListTemplateMethodFnMap<'a, L>,
 }
 
+/// Names of the functions and methods registered in a symbol table.
+#[derive(Clone, Debug, Default)]
+pub struct TemplateSymbolNames {
+    /// Global function names in sorted order.
+    pub functions: Vec<&'static str>,
+    /// Pairs of type name and sorted method names.
+    pub methods: Vec<(&'static str, Vec<&'static str>)>,
+}
+
+impl TemplateSymbolNames {
+    /// Adds methods of the type `type_name` if any.
+    pub fn add_methods<F>(&mut self, type_name: &'static str, map: &HashMap<&'static str, F>) {
+        if !map.is_empty() {
+            let names = map.keys().copied().sorted().collect();
+            self.methods.push((type_name, names));
+        }
+    }
+}
+
 pub fn merge_fn_map<'s, F>(base: &mut HashMap<&'s str, F>, extension: HashMap<&'s str, F>) {
     for (name, function) in extension {
         if base.insert(name, function).is_some() {
@@ -498,6 +530,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods: builtin_size_hint_methods(),
             timestamp_methods: builtin_timestamp_methods(),
             timestamp_range_methods: builtin_timestamp_range_methods(),
+            record_methods: builtin_record_methods(),
             template_methods: HashMap::new(),
             list_template_methods: builtin_list_template_methods(),
         }
@@ -516,6 +549,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods: HashMap::new(),
             timestamp_methods: HashMap::new(),
             timestamp_range_methods: HashMap::new(),
+            record_methods: HashMap::new(),
             template_methods: HashMap::new(),
             list_template_methods: HashMap::new(),
         }
@@ -534,6 +568,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods,
             timestamp_methods,
             timestamp_range_methods,
+            record_methods,
             template_methods,
             list_template_methods,
         } = extension;
@@ -549,10 +584,33 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
         merge_fn_map(&mut self.size_hint_methods, size_hint_methods);
         merge_fn_map(&mut self.timestamp_methods, timestamp_methods);
         merge_fn_map(&mut self.timestamp_range_methods, timestamp_range_methods);
+        merge_fn_map(&mut self.record_methods, record_methods);
         merge_fn_map(&mut self.template_methods, template_methods);
         merge_fn_map(&mut self.list_template_methods, list_template_methods);
     }
 
+    /// Returns the names of the functions and methods in this table.
+    pub fn symbol_names(&self) -> TemplateSymbolNames {
+        let mut names = TemplateSymbolNames {
+            functions: self.functions.keys().copied().sorted().collect(),
+            methods: vec![],
+        };
+        names.add_methods("String", &self.string_methods);
+        names.add_methods("List<String>", &self.string_list_methods);
+        names.add_methods("Boolean", &self.boolean_methods);
+        names.add_methods("Integer", &self.integer_methods);
+        names.add_methods("ConfigValue", &self.config_value_methods);
+        names.add_methods("Email", &self.email_methods);
+        names.add_methods("Signature", &self.signature_methods);
+        names.add_methods("SizeHint", &self.size_hint_methods);
+        names.add_methods("Timestamp", &self.timestamp_methods);
+        names.add_methods("TimestampRange", &self.timestamp_range_methods);
+        names.add_methods("Record", &self.record_methods);
+        names.add_methods("Template", &self.template_methods);
+        names.add_methods("ListTemplate", &self.list_template_methods);
+        names
+    }
+
     /// Translates the function call node `function` by using this symbol table.
     pub fn build_function(
         &self,
@@ -635,6 +693,11 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::Record(property) => {
+                let table = &self.record_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
             CoreTemplatePropertyKind::Template(template) => {
                 let table = &self.template_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -1006,6 +1069,47 @@ fn builtin_string_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "subject",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|s| text_util::split_subject_body(&s).0);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "body",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|s| text_util::split_subject_body(&s).1.to_owned());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "paragraphs",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|s| text_util::split_paragraphs(&s));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "wrap",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [width_node] = function.expect_exact_arguments()?;
+            let width_property =
+                expect_usize_expression(language, diagnostics, build_ctx, width_node)?;
+            let out_property = (self_property, width_property).map(|(s, width)| {
+                text_util::wrap_bytes(s.trim_end_matches('\n').as_bytes(), width)
+                    .into_iter()
+                    // Lines are split at ASCII spaces and newlines.
+                    .map(|line| std::str::from_utf8(line).unwrap().to_owned())
+                    .collect_vec()
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "upper",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -1353,6 +1457,71 @@ fn builtin_timestamp_range_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
     map
 }
 
+fn builtin_record_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, TemplateRecord> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, TemplateRecord>::new();
+    map.insert(
+        "get",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [name_node] = function.expect_exact_arguments()?;
+            let name_property =
+                expect_stringify_expression(language, diagnostics, build_ctx, name_node)?;
+            let out_property = (self_property, name_property).and_then(|(record, name)| {
+                let value = record.get(&name).ok_or_else(|| {
+                    TemplatePropertyError(format!("Record has no field {name:?}").into())
+                })?;
+                json_to_config_value(value.clone())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "keys",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property
+                .map(|record| record.0.into_iter().map(|(name, _)| name).collect_vec());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
+/// Converts JSON value to the TOML representation, which can be inspected by
+/// `ConfigValue` methods.
+fn json_to_config_value(value: serde_json::Value) -> Result<ConfigValue, TemplatePropertyError> {
+    match value {
+        serde_json::Value::Null => Err(TemplatePropertyError("Null value cannot be read".into())),
+        serde_json::Value::Bool(b) => Ok(b.into()),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(i.into())
+            } else if let Some(f) = n.as_f64() {
+                Ok(f.into())
+            } else {
+                Err(TemplatePropertyError(
+                    format!("Unsupported number: {n}").into(),
+                ))
+            }
+        }
+        serde_json::Value::String(s) => Ok(s.into()),
+        serde_json::Value::Array(items) => {
+            let array: toml_edit::Array =
+                items.into_iter().map(json_to_config_value).try_collect()?;
+            Ok(array.into())
+        }
+        serde_json::Value::Object(entries) => {
+            let table: toml_edit::InlineTable = entries
+                .into_iter()
+                .map(|(key, value)| Ok((key, json_to_config_value(value)?)))
+                .try_collect::<_, _, TemplatePropertyError>()?;
+            Ok(table.into())
+        }
+    }
+}
+
 fn builtin_list_template_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
 ) -> BuildListTemplateMethodFnMap<'a, L> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1670,6 +1839,18 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
         let out_property = value.and_then(|v| Ok(serde_json::to_string(&v)?));
         Ok(out_property.into_dyn_wrapped())
     });
+    map.insert("record", |language, diagnostics, build_ctx, function| {
+        let fields = function
+            .expect_keyword_arguments()?
+            .iter()
+            .map(|arg| {
+                expect_serialize_expression(language, diagnostics, build_ctx, &arg.value)
+                    .map(|value| (arg.name.to_owned(), value))
+            })
+            .try_collect()?;
+        let out_property = RecordProperty::new(fields);
+        Ok(out_property.into_dyn_wrapped())
+    });
     map.insert("if", |language, diagnostics, build_ctx, function| {
         let ([condition_node, true_node], [false_node]) = function.expect_arguments()?;
         let condition =
@@ -1749,6 +1930,40 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
         // .decorated("", "") to trim leading/trailing whitespace
         Ok(Literal(value.decorated("", "")).into_dyn_wrapped())
     });
+    map.insert(
+        "read_config",
+        |language, diagnostics, _build_ctx, function| {
+            let [name_node] = function.expect_exact_arguments()?;
+            let name: ConfigNamePathBuf =
+                template_parser::catch_aliases(diagnostics, name_node, |_diagnostics, node| {
+                    let name = template_parser::expect_string_literal(node)?;
+                    let relative_name: ConfigNamePathBuf = name.parse().map_err(|err| {
+                        TemplateParseError::expression("Failed to parse config name", node.span)
+                            .with_source(err)
+                    })?;
+                    Ok(iter::once("template-vars".into())
+                        .chain(relative_name.components().cloned())
+                        .collect())
+                })?;
+            let value = language
+                .settings()
+                .get_value(&name)
+                .optional()
+                .map_err(|err| {
+                    TemplateParseError::expression("Failed to get config value", function.name_span)
+                        .with_source(err)
+                })?;
+            match value {
+                ConfigValue::String(value) => Ok(Literal(value.into_value()).into_dyn_wrapped()),
+                ConfigValue::Integer(value) => Ok(Literal(value.into_value()).into_dyn_wrapped()),
+                ConfigValue::Boolean(value) => Ok(Literal(value.into_value()).into_dyn_wrapped()),
+                _ => Err(TemplateParseError::expression(
+                    format!("Config value `{name}` must be a string, integer, or boolean"),
+                    name_node.span,
+                )),
+            }
+        },
+    );
     map
 }
 
@@ -1918,6 +2133,29 @@ where
     Ok(TemplateRenderer::new(template, self_placeholder))
 }
 
+/// Builds the given expression `node` with fresh build context, and returns the
+/// type name of the resulting property.
+///
+/// Nodes referring to lambda parameters can't be built out of context.
+pub fn infer_type_name<'a, C, L>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    node: &ExpressionNode,
+) -> TemplateParseResult<&'static str>
+where
+    C: Clone + 'a,
+    L: TemplateLanguage<'a> + ?Sized,
+    L::Property: WrapTemplateProperty<'a, C>,
+{
+    let self_placeholder = PropertyPlaceholder::<C>::new();
+    let build_ctx = BuildContext {
+        local_variables: HashMap::new(),
+        self_variable: &|| self_placeholder.clone().into_dyn_wrapped(),
+    };
+    let expression = build_expression(language, diagnostics, &build_ctx, node)?;
+    Ok(expression.type_name())
+}
+
 /// Parses text, expands aliases, then builds template evaluation tree.
 pub fn parse<'a, C, L>(
     language: &L,
@@ -2792,6 +3030,29 @@ mod tests {
         insta::assert_snapshot!(env.render_ok(r#""".lines()"#), @"");
         insta::assert_snapshot!(env.render_ok(r#""a\nb\nc\n".lines()"#), @"a b c");
 
+        insta::assert_snapshot!(env.render_ok(r#""".subject()"#), @"");
+        insta::assert_snapshot!(env.render_ok(r#""\nfoo\nbar\n\nbaz\n".subject()"#), @"foo bar");
+        insta::assert_snapshot!(env.render_ok(r##""# foo\n\nbar\n".subject()"##), @"foo");
+        insta::assert_snapshot!(env.render_ok(r#""".body()"#), @"");
+        insta::assert_snapshot!(env.render_ok(r#""foo\n".body()"#), @"");
+        insta::assert_snapshot!(
+            env.render_ok(r#""foo\nbar\n\n\nbaz\n\nqux\n".body()"#),
+            @r"
+        baz
+
+        qux
+        ");
+
+        insta::assert_snapshot!(env.render_ok(r#""".paragraphs()"#), @"");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\n\n\nc\n".paragraphs().map(|p| p.lines().join(",")).join("|")"#),
+            @"a,b|c");
+
+        insta::assert_snapshot!(
+            env.render_ok(r#""foo bar baz\nqux\n".wrap(7).join("|")"#),
+            @"foo bar|baz|qux");
+        insta::assert_snapshot!(env.render_ok(r#""".wrap(10).join("|")"#), @"");
+
         insta::assert_snapshot!(env.render_ok(r#""".starts_with("")"#), @"true");
         insta::assert_snapshot!(env.render_ok(r#""everything".starts_with("")"#), @"true");
         insta::assert_snapshot!(env.render_ok(r#""".starts_with("foo")"#), @"false");
@@ -3231,6 +3492,15 @@ mod tests {
         insta::assert_snapshot!(
             env.render_ok(r"truncate_end(2, label('red', 'foobar')) ++ 'baz'"),
             @"[38;5;9mfo[39mbaz");
+        insta::assert_snapshot!(
+            env.render_ok(r"truncate_middle(3, label('red', 'foobar')) ++ 'baz'"),
+            @"[38;5;9mfar[39mbaz");
+        insta::assert_snapshot!(
+            env.render_ok(r"truncate_middle(5, 'foobar', ellipsis='..') ++ 'baz'"),
+            @"f..arbaz");
+        insta::assert_snapshot!(
+            env.render_ok(r"'{' ++ pad_centered(8, truncate_middle(5, 'foobar', '~')) ++ '}'"),
+            @"{ fo~ar  }");
     }
 
     #[test]
@@ -3370,6 +3640,48 @@ mod tests {
         ");
     }
 
+    #[test]
+    fn test_record_function() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("string_list", || {
+            literal(vec!["foo".to_owned(), "bar".to_owned()])
+        });
+
+        insta::assert_snapshot!(env.render_ok("record()"), @"{}");
+        insta::assert_snapshot!(
+            env.render_ok(r#"json(record(b="x", a=1, c=false))"#),
+            @r#"{"b":"x","a":1,"c":false}"#);
+        insta::assert_snapshot!(
+            env.render_ok("record(list=string_list, inner=record(x=1))"),
+            @r#"{"list":["foo","bar"],"inner":{"x":1}}"#);
+
+        insta::assert_snapshot!(env.render_ok("record(b=1, a=2).keys()"), @"b a");
+        insta::assert_snapshot!(env.render_ok(r#"record(a=1).get("a").as_integer() + 1"#), @"2");
+        insta::assert_snapshot!(
+            env.render_ok(r#"record(a=string_list).get("a").as_string_list()"#),
+            @"foo bar");
+        insta::assert_snapshot!(
+            env.render_ok(r#"record(a=1).get("b")"#),
+            @r#"<Error: Record has no field "b">"#);
+
+        insta::assert_snapshot!(env.parse_err("record(1)"), @r"
+         --> 1:8
+          |
+        1 | record(1)
+          |        ^
+          |
+          = Function `record`: Unexpected positional arguments
+        ");
+        insta::assert_snapshot!(env.parse_err("record(a=1, a=2)"), @r#"
+         --> 1:13
+          |
+        1 | record(a=1, a=2)
+          |             ^-^
+          |
+          = Function `record`: Got multiple values for keyword "a"
+        "#);
+    }
+
     #[test]
     fn test_coalesce_function() {
         let mut env = TestTemplateEnv::new();
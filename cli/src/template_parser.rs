@@ -704,6 +704,89 @@ fn catch_aliases_no_diagnostics<'a, 'i, T>(
     f(node).map_err(|err| attach_aliases_err(err, &stack))
 }
 
+/// Reports branches that are never evaluated because their condition is a
+/// literal, such as the else branch of `if(true, ..)`.
+pub fn check_unreachable_branches(diagnostics: &mut TemplateDiagnostics, node: &ExpressionNode) {
+    let (node, stack) = skip_aliases(node);
+    if !stack.is_empty() {
+        let mut inner_diagnostics = TemplateDiagnostics::new();
+        check_unreachable_branches(&mut inner_diagnostics, node);
+        diagnostics.extend_with(inner_diagnostics, |diag| attach_aliases_err(diag, &stack));
+        return;
+    }
+    match &node.kind {
+        ExpressionKind::Identifier(_)
+        | ExpressionKind::Boolean(_)
+        | ExpressionKind::Integer(_)
+        | ExpressionKind::String(_) => {}
+        ExpressionKind::Unary(_, arg) => check_unreachable_branches(diagnostics, arg),
+        ExpressionKind::Binary(op, lhs, rhs) => {
+            match (op, boolean_literal(lhs)) {
+                (BinaryOp::LogicalOr, Some(true)) => {
+                    diagnostics.add_warning(TemplateParseError::expression(
+                        "This operand is never evaluated because the left operand is always true",
+                        rhs.span,
+                    ))
+                }
+                (BinaryOp::LogicalAnd, Some(false)) => {
+                    diagnostics.add_warning(TemplateParseError::expression(
+                        "This operand is never evaluated because the left operand is always false",
+                        rhs.span,
+                    ))
+                }
+                _ => {}
+            }
+            check_unreachable_branches(diagnostics, lhs);
+            check_unreachable_branches(diagnostics, rhs);
+        }
+        ExpressionKind::Concat(nodes) => {
+            for node in nodes {
+                check_unreachable_branches(diagnostics, node);
+            }
+        }
+        ExpressionKind::FunctionCall(function) => {
+            if function.name == "if" {
+                let unreachable = match function.args.first().and_then(boolean_literal) {
+                    Some(true) => function.args.get(2).map(|node| (node, "true")),
+                    Some(false) => function.args.get(1).map(|node| (node, "false")),
+                    None => None,
+                };
+                if let Some((branch, condition)) = unreachable {
+                    let message = format!(
+                        "This branch is never evaluated because the condition is always \
+                         {condition}"
+                    );
+                    diagnostics.add_warning(TemplateParseError::expression(message, branch.span));
+                }
+            }
+            check_function_call_branches(diagnostics, function);
+        }
+        ExpressionKind::MethodCall(method) => {
+            check_unreachable_branches(diagnostics, &method.object);
+            check_function_call_branches(diagnostics, &method.function);
+        }
+        ExpressionKind::Lambda(lambda) => check_unreachable_branches(diagnostics, &lambda.body),
+        ExpressionKind::AliasExpanded(..) => unreachable!(),
+    }
+}
+
+fn check_function_call_branches(
+    diagnostics: &mut TemplateDiagnostics,
+    function: &FunctionCallNode,
+) {
+    let keyword_values = function.keyword_args.iter().map(|arg| &arg.value);
+    for node in function.args.iter().chain(keyword_values) {
+        check_unreachable_branches(diagnostics, node);
+    }
+}
+
+fn boolean_literal(node: &ExpressionNode) -> Option<bool> {
+    match skip_aliases(node).0.kind {
+        ExpressionKind::Boolean(value) => Some(value),
+        _ => None,
+    }
+}
+
 fn skip_aliases<'a, 'i>(
     mut node: &'a ExpressionNode<'i>,
 ) -> (&'a ExpressionNode<'i>, Vec<(AliasId<'i>, pest::Span<'i>)>) {
@@ -26,6 +26,7 @@ use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigValue;
 use jj_lib::op_store::TimestampRange;
+use serde::ser::SerializeMap as _;
 
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
@@ -122,6 +123,37 @@ impl Template for Email {
     }
 }
 
+/// Ordered list of named values, which is serialized as a JSON object.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TemplateRecord(pub Vec<(String, serde_json::Value)>);
+
+impl TemplateRecord {
+    /// Looks up the value of the field `name`.
+    pub fn get(&self, name: &str) -> Option<&serde_json::Value> {
+        self.0
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl serde::Serialize for TemplateRecord {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl Template for TemplateRecord {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        let text = serde_json::to_string(self).map_err(io::Error::other)?;
+        write!(formatter, "{text}")
+    }
+}
+
 // In template language, an integer value is represented as i64. However, we use
 // usize here because it's more convenient to guarantee that the lower value is
 // bounded to 0.
@@ -525,6 +557,33 @@ impl<T: Template> TemplateProperty for PlainTextFormattedProperty<T> {
     }
 }
 
+/// Adapter that evaluates named properties into a [`TemplateRecord`].
+pub struct RecordProperty<'a> {
+    fields: Vec<(String, BoxedSerializeProperty<'a>)>,
+}
+
+impl<'a> RecordProperty<'a> {
+    pub fn new(fields: Vec<(String, BoxedSerializeProperty<'a>)>) -> Self {
+        RecordProperty { fields }
+    }
+}
+
+impl TemplateProperty for RecordProperty<'_> {
+    type Output = TemplateRecord;
+
+    fn extract(&self) -> Result<Self::Output, TemplatePropertyError> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, property)| {
+                let value = serde_json::to_value(property.extract()?)?;
+                Ok((name.clone(), value))
+            })
+            .collect::<Result<_, TemplatePropertyError>>()?;
+        Ok(TemplateRecord(fields))
+    }
+}
+
 /// Renders template property of list type with the given separator.
 ///
 /// Each list item will be formatted by the given `format_item()` function.
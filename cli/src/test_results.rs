@@ -0,0 +1,269 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of `jj test run` results keyed by test command and tree id.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use itertools::Itertools as _;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::commit::Commit;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::file_util::PathError;
+use jj_lib::hex_util;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::revset;
+use jj_lib::revset::FunctionCallNode;
+use jj_lib::revset::LoweringContext;
+use jj_lib::revset::RevsetDiagnostics;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetFilterExtension;
+use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::revset::RevsetParseError;
+use jj_lib::revset::UserRevsetExpression;
+
+use crate::config::CommandNameAndArgs;
+
+/// File which records the key of the test command last run.
+const LAST_COMMAND_FILE_NAME: &str = "last_command";
+
+/// Outcome of a test run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+}
+
+impl TestStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "passed" => Some(Self::Passed),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Test results stored in the `test_results` directory of the repo.
+///
+/// Results are keyed by tree id, so rewritten commits with unchanged contents
+/// don't have to be tested again. Results of each test command are stored in
+/// separate directories, so changing the command invalidates the results.
+#[derive(Clone, Debug)]
+pub struct TestResultStore {
+    dir: PathBuf,
+    command_key: Option<String>,
+}
+
+impl TestResultStore {
+    /// Opens the results of the test command last run by `jj test run`.
+    pub fn new(repo_path: &Path) -> Self {
+        let dir = repo_path.join("test_results");
+        let command_key = fs::read_to_string(dir.join(LAST_COMMAND_FILE_NAME))
+            .ok()
+            .map(|content| content.trim().to_owned())
+            .filter(|key| !key.is_empty());
+        Self { dir, command_key }
+    }
+
+    /// Opens the results of the given test command, and records it as the
+    /// last command run.
+    pub fn for_command(repo_path: &Path, command: &CommandNameAndArgs) -> Result<Self, PathError> {
+        let dir = repo_path.join("test_results");
+        let key = command_key(command);
+        fs::create_dir_all(dir.join(&key)).context(&dir)?;
+        file_util::write_file_atomically(&dir.join(LAST_COMMAND_FILE_NAME), |file| {
+            file.write_all(key.as_bytes())
+        })?;
+        Ok(Self {
+            dir,
+            command_key: Some(key),
+        })
+    }
+
+    fn command_dir(&self) -> Option<PathBuf> {
+        self.command_key.as_ref().map(|key| self.dir.join(key))
+    }
+
+    /// Looks up the cached result. Unreadable entries are treated as missing.
+    pub fn get(&self, tree_id: &MergedTreeId) -> Option<TestStatus> {
+        let command_dir = self.command_dir()?;
+        let content = fs::read_to_string(command_dir.join(tree_key(tree_id))).ok()?;
+        TestStatus::parse(content.trim())
+    }
+
+    /// Stores the result. The store must be opened by
+    /// [`TestResultStore::for_command()`].
+    pub fn insert(&self, tree_id: &MergedTreeId, status: TestStatus) -> Result<(), PathError> {
+        let command_dir = self
+            .command_dir()
+            .expect("test results should be opened for a command");
+        fs::create_dir_all(&command_dir).context(&command_dir)?;
+        let path = command_dir.join(tree_key(tree_id));
+        file_util::write_file_atomically(&path, |file| file.write_all(status.as_str().as_bytes()))?;
+        Ok(())
+    }
+
+    /// Removes results last written before `keep_newer`, except for the
+    /// `tree_ids` that are still in use. Leftover temporary files are removed
+    /// the same way.
+    pub fn gc<'a>(
+        &self,
+        tree_ids: impl IntoIterator<Item = &'a MergedTreeId>,
+        keep_newer: SystemTime,
+    ) -> Result<(), PathError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err).context(&self.dir),
+        };
+        let keep_keys: HashSet<String> = tree_ids.into_iter().map(tree_key).collect();
+        for entry in entries {
+            let entry = entry.context(&self.dir)?;
+            let path = entry.path();
+            if entry.file_name() == LAST_COMMAND_FILE_NAME {
+                continue;
+            }
+            if entry.file_type().context(&path)?.is_dir() {
+                gc_dir(&path, &keep_keys, keep_newer)?;
+                // Remove the directory of a command if no results are left.
+                fs::remove_dir(&path).ok();
+            } else {
+                gc_file(&path, keep_newer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if there are no stored results.
+    pub fn is_empty(&self) -> bool {
+        fs::read_dir(&self.dir).map_or(true, |mut entries| entries.next().is_none())
+    }
+}
+
+fn gc_dir(
+    dir: &Path,
+    keep_keys: &HashSet<String>,
+    keep_newer: SystemTime,
+) -> Result<(), PathError> {
+    for entry in fs::read_dir(dir).context(dir)? {
+        let entry = entry.context(dir)?;
+        if entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| keep_keys.contains(name))
+        {
+            continue;
+        }
+        gc_file(&entry.path(), keep_newer)?;
+    }
+    Ok(())
+}
+
+fn gc_file(path: &Path, keep_newer: SystemTime) -> Result<(), PathError> {
+    let modified = path
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .context(path)?;
+    if modified > keep_newer {
+        return Ok(());
+    }
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).context(path),
+    }
+}
+
+fn tree_key(tree_id: &MergedTreeId) -> String {
+    tree_id.to_merge().iter().map(|id| id.hex()).join("-")
+}
+
+/// Returns a key identifying the test command and its environment.
+fn command_key(command: &CommandNameAndArgs) -> String {
+    let (name, args) = command.split_name_and_args();
+    let argv = itertools::chain([name.into_owned()], args.iter().cloned()).collect_vec();
+    let env = match command {
+        CommandNameAndArgs::Structured { env, .. } => env
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .sorted()
+            .collect_vec(),
+        CommandNameAndArgs::String(_) | CommandNameAndArgs::Vec(_) => vec![],
+    };
+    let hash = blake2b_hash(&vec![argv, env]);
+    hex_util::encode_hex(&hash[..16])
+}
+
+#[derive(Debug)]
+struct TestStatusFilter {
+    store: Option<TestResultStore>,
+    status: Option<TestStatus>,
+}
+
+impl RevsetFilterExtension for TestStatusFilter {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn matches_commit(&self, commit: &Commit) -> bool {
+        let status = self
+            .store
+            .as_ref()
+            .and_then(|store| store.get(commit.tree_id()));
+        status == self.status
+    }
+}
+
+/// Implements the `tests(passed|failed|unknown)` revset function.
+pub fn tests_revset_function(
+    _diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    context: &LoweringContext,
+) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
+    let [arg] = function.expect_exact_arguments()?;
+    let value: String = revset::expect_literal("string", arg)?;
+    let status = match value.as_str() {
+        "unknown" => None,
+        _ => Some(TestStatus::parse(&value).ok_or_else(|| {
+            RevsetParseError::expression(
+                "Expected one of `passed`, `failed`, or `unknown`",
+                arg.span,
+            )
+        })?),
+    };
+    let store = context
+        .workspace()
+        .map(|workspace| TestResultStore::new(workspace.repo_path));
+    Ok(RevsetExpression::filter(RevsetFilterPredicate::Extension(
+        Rc::new(TestStatusFilter { store, status }),
+    )))
+}
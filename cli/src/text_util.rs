@@ -15,6 +15,7 @@
 use std::borrow::Cow;
 use std::cmp;
 use std::io;
+use std::ops::Range;
 
 use bstr::ByteSlice as _;
 use unicode_width::UnicodeWidthChar as _;
@@ -39,6 +40,60 @@ pub fn split_email(email: &str) -> (&str, Option<&str>) {
     }
 }
 
+/// Splits `text` into paragraphs separated by blank lines. Lines of each
+/// paragraph are joined by "\n", without trailing newline.
+pub fn split_paragraphs(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .split(|line| line.is_empty())
+        .filter(|lines| !lines.is_empty())
+        .map(|lines| lines.join("\n"))
+        .collect()
+}
+
+/// Splits a commit description into the subject and the body.
+///
+/// The subject is the first paragraph joined into a single line. A leading
+/// Markdown heading marker (e.g. `# `) is removed from it. The body is the
+/// rest of the description after the blank lines following the subject.
+pub fn split_subject_body(text: &str) -> (String, &str) {
+    let mut subject_lines = vec![];
+    let mut rest = skip_blank_lines(text);
+    while !rest.is_empty() {
+        let (line, tail) = rest.split_once('\n').unwrap_or((rest, ""));
+        if line.trim().is_empty() {
+            break;
+        }
+        subject_lines.push(line.trim());
+        rest = tail;
+    }
+    let body = skip_blank_lines(rest);
+    let subject = subject_lines.join(" ");
+    let subject = match subject.trim_start_matches('#') {
+        stripped if stripped.len() < subject.len() && stripped.starts_with(' ') => {
+            stripped.trim_start().to_owned()
+        }
+        _ => subject,
+    };
+    (subject, body)
+}
+
+/// Removes leading blank lines. Returns an empty string if all lines are blank.
+fn skip_blank_lines(mut text: &str) -> &str {
+    while let Some((line, tail)) = text.split_once('\n') {
+        if !line.trim().is_empty() {
+            break;
+        }
+        text = tail;
+    }
+    if text.trim().is_empty() {
+        ""
+    } else {
+        text
+    }
+}
+
 /// Shortens `text` to `max_width` by removing leading characters. `ellipsis` is
 /// added if the `text` gets truncated.
 ///
@@ -307,6 +362,53 @@ pub fn write_truncated_end(
     Ok(truncated_width)
 }
 
+/// Writes text truncated to `max_width` by removing characters in the middle.
+/// Returns width of the truncated text, which may be shorter than `max_width`.
+///
+/// If an odd width is left for the content, the trailing part will be one
+/// longer than the leading part. The input `recorded_content` should be a
+/// single-line text.
+pub fn write_truncated_middle(
+    formatter: &mut dyn Formatter,
+    recorded_content: &FormatRecorder,
+    recorded_ellipsis: &FormatRecorder,
+    max_width: usize,
+) -> io::Result<usize> {
+    let data = recorded_content.data();
+    let data_width = String::from_utf8_lossy(data).width();
+    if data_width <= max_width {
+        recorded_content.replay(formatter)?;
+        return Ok(data_width);
+    }
+    let ellipsis_data = recorded_ellipsis.data();
+    let ellipsis_width = String::from_utf8_lossy(ellipsis_data).width();
+
+    let available_width = max_width.saturating_sub(ellipsis_width);
+    let (head_end, head_width) = truncate_end_pos_bytes(data, available_width / 2);
+    let (tail_start, tail_width) =
+        truncate_start_pos_bytes(&data[head_end..], available_width - head_width);
+    let tail_start = head_end + tail_start;
+    let tail_start = tail_start + count_start_zero_width_chars_bytes(&data[tail_start..]);
+
+    let mut replay_truncated = |recorded: &FormatRecorder, range_to_keep: Range<usize>| {
+        recorded.replay_with(formatter, |formatter, range| {
+            let start = cmp::max(range.start, range_to_keep.start);
+            let end = cmp::min(range.end, range_to_keep.end);
+            if start < end {
+                formatter.write_all(&recorded.data()[start..end])?;
+            }
+            Ok(())
+        })
+    };
+
+    replay_truncated(recorded_content, 0..head_end)?;
+    // The ellipsis itself may be larger than max_width, so maybe truncate it too.
+    let (ellipsis_end, ellipsis_width) = truncate_end_pos_bytes(ellipsis_data, max_width);
+    replay_truncated(recorded_ellipsis, 0..ellipsis_end)?;
+    replay_truncated(recorded_content, tail_start..data.len())?;
+    Ok(head_width + ellipsis_width + tail_width)
+}
+
 /// Writes text padded to `min_width` by adding leading fill characters.
 ///
 /// The input `recorded_content` should be a single-line text. The
@@ -586,6 +688,39 @@ mod tests {
         String::from_utf8(output).unwrap()
     }
 
+    #[test]
+    fn test_split_paragraphs() {
+        assert!(split_paragraphs("").is_empty());
+        assert!(split_paragraphs("\n \n").is_empty());
+        assert_eq!(split_paragraphs("foo\n"), ["foo"]);
+        assert_eq!(
+            split_paragraphs("\nfoo\nbar  \n \n\nbaz\n"),
+            ["foo\nbar", "baz"]
+        );
+    }
+
+    #[test]
+    fn test_split_subject_body() {
+        assert_eq!(split_subject_body(""), ("".to_owned(), ""));
+        assert_eq!(split_subject_body("foo\n"), ("foo".to_owned(), ""));
+        assert_eq!(
+            split_subject_body("\n\nfoo\nbar\n\n \nbaz\n\nqux\n"),
+            ("foo bar".to_owned(), "baz\n\nqux\n")
+        );
+        assert_eq!(split_subject_body("foo\n\n\n"), ("foo".to_owned(), ""));
+
+        // Markdown heading markers are removed from the subject
+        assert_eq!(
+            split_subject_body("## Title\n\nbody\n"),
+            ("Title".to_owned(), "body\n")
+        );
+        assert_eq!(
+            split_subject_body("#123 fix\n"),
+            ("#123 fix".to_owned(), "")
+        );
+        assert_eq!(split_subject_body("#\n"), ("#".to_owned(), ""));
+    }
+
     #[test]
     fn test_elide_start() {
         // Empty string
@@ -807,6 +942,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_write_truncated_middle_labeled() {
+        let mut recorder = FormatRecorder::new();
+        for (label, word) in [("red", "foo"), ("cyan", "bar")] {
+            recorder.push_label(label).unwrap();
+            write!(recorder, "{word}").unwrap();
+            recorder.pop_label().unwrap();
+        }
+
+        // Without ellipsis
+        let ellipsis_recorder = FormatRecorder::new();
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 6).map(|_| ())
+            }),
+            @"[38;5;1mfoo[39m[38;5;6mbar[39m"
+        );
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 5).map(|_| ())
+            }),
+            @"[38;5;1mfo[39m[38;5;6mbar[39m"
+        );
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 3).map(|_| ())
+            }),
+            @"[38;5;1mf[39m[38;5;6mar[39m"
+        );
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 0).map(|_| ())
+            }),
+            @""
+        );
+
+        // With ellipsis
+        let ellipsis_recorder = FormatRecorder::with_data("..");
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 6).map(|_| ())
+            }),
+            @"[38;5;1mfoo[39m[38;5;6mbar[39m"
+        );
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 5).map(|_| ())
+            }),
+            @"[38;5;1mf[39m..[38;5;6mar[39m"
+        );
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 2).map(|_| ())
+            }),
+            @".."
+        );
+        insta::assert_snapshot!(
+            format_colored(|formatter| {
+                write_truncated_middle(formatter, &recorder, &ellipsis_recorder, 1).map(|_| ())
+            }),
+            @"."
+        );
+    }
+
     #[test]
     fn test_write_truncated_non_ascii_chars() {
         let ellipsis_recorder = FormatRecorder::new();
@@ -210,6 +210,7 @@ impl Write for UiStderr<'_> {
 
 pub struct Ui {
     quiet: bool,
+    error_format: ErrorFormat,
     pager: PagerConfig,
     progress_indicator: bool,
     formatter_factory: FormatterFactory,
@@ -237,6 +238,26 @@ impl fmt::Display for ColorChoice {
     }
 }
 
+/// How command errors are reported.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorFormat {
+    /// Human-readable messages.
+    Text,
+    /// JSON object per error.
+    Json,
+}
+
+impl fmt::Display for ErrorFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ErrorFormat::Text => "text",
+            ErrorFormat::Json => "json",
+        };
+        write!(f, "{s}")
+    }
+}
+
 fn prepare_formatter_factory(
     config: &StackedConfig,
     stdout: &Stdout,
@@ -341,6 +362,7 @@ impl Ui {
     pub fn null() -> Ui {
         Ui {
             quiet: true,
+            error_format: ErrorFormat::Text,
             pager: PagerConfig::Disabled,
             progress_indicator: false,
             formatter_factory: FormatterFactory::plain_text(),
@@ -352,6 +374,7 @@ impl Ui {
         let formatter_factory = prepare_formatter_factory(config, &io::stdout())?;
         Ok(Ui {
             quiet: config.get("ui.quiet")?,
+            error_format: config.get("ui.error-format")?,
             formatter_factory,
             pager: PagerConfig::from_config(config)?,
             progress_indicator: config.get("ui.progress-indicator")?,
@@ -361,6 +384,7 @@ impl Ui {
 
     pub fn reset(&mut self, config: &StackedConfig) -> Result<(), CommandError> {
         self.quiet = config.get("ui.quiet")?;
+        self.error_format = config.get("ui.error-format")?;
         self.pager = PagerConfig::from_config(config)?;
         self.progress_indicator = config.get("ui.progress-indicator")?;
         self.formatter_factory = prepare_formatter_factory(config, &io::stdout())?;
@@ -415,6 +439,11 @@ impl Ui {
         self.formatter_factory.is_color()
     }
 
+    /// How command errors should be reported.
+    pub fn error_format(&self) -> ErrorFormat {
+        self.error_format
+    }
+
     pub fn new_formatter<'output, W: Write + 'output>(
         &self,
         output: W,
@@ -29,6 +29,7 @@ use itertools::Itertools as _;
 /// `fake-formatter --stdout foo --stderr bar --fail` is similar to
 ///   `echo foo; echo bar >&2; false`.
 /// `fake-formatter --tee foo` is similar to `tee foo`).
+/// `fake-formatter --stdout-env FOO` is similar to `echo $FOO`.
 ///
 /// This program acts as a portable alternative to that class of shell commands.
 #[derive(Parser, Debug)]
@@ -57,6 +58,11 @@ struct Args {
     #[arg(long)]
     stdout: Option<String>,
 
+    /// Write the values of these environment variables to stdout, one per
+    /// line, and ignore stdin.
+    #[arg(long)]
+    stdout_env: Vec<String>,
+
     /// Write this string to stderr.
     #[arg(long)]
     stderr: Option<String>,
@@ -79,6 +85,11 @@ fn main() -> ExitCode {
         assert!(!args.lowercase);
         assert!(args.append.is_none());
         data
+    } else if !args.stdout_env.is_empty() {
+        args.stdout_env
+            .iter()
+            .map(|name| format!("{}\n", std::env::var(name).unwrap()))
+            .join("")
     } else {
         let mut stdout = std::io::stdin()
             .lines()
@@ -33,6 +33,7 @@ mod test_edit_command;
 mod test_evolog_command;
 mod test_file_annotate_command;
 mod test_file_chmod_command;
+mod test_file_search_command;
 mod test_file_show_command;
 mod test_file_track_untrack_commands;
 mod test_fix_command;
@@ -48,6 +49,7 @@ mod test_git_remotes;
 mod test_git_root;
 mod test_gitignores;
 mod test_global_opts;
+mod test_grep_command;
 mod test_help_command;
 mod test_immutable_commits;
 mod test_interdiff_command;
@@ -72,6 +74,8 @@ mod test_squash_command;
 mod test_status_command;
 mod test_tag_command;
 mod test_templater;
+mod test_test_command;
+mod test_topic_command;
 mod test_undo;
 mod test_util_command;
 mod test_working_copy;
@@ -808,6 +808,67 @@ fn test_absorb_from_into() {
     ");
 }
 
+#[test]
+fn test_absorb_into_non_contiguous() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["new", "-m1"]).success();
+    work_dir.write_file("file1", "1a\n1b\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b1"])
+        .success();
+
+    work_dir.run_jj(["new", "-m2"]).success();
+    work_dir.write_file("file1", "1a\n2a\n1b\n2b\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b2"])
+        .success();
+
+    work_dir.run_jj(["new", "-m3"]).success();
+    work_dir.write_file("file1", "1a\n2a\n3a\n1b\n2b\n3b\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b3"])
+        .success();
+
+    // Line "2b" was last modified by the revision 2, which isn't a
+    // destination. It shouldn't be absorbed into the revision 3.
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "1A\n2a\n3A\n1b\n2B\n3b\n");
+    work_dir.run_jj(["absorb", "--into=b1 | b3"]).success();
+
+    let output = work_dir.run_jj(["file", "show", "-rb1", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    1A
+    1b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-rb2", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    1A
+    2a
+    1b
+    2b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-rb3", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    1A
+    2a
+    3A
+    1b
+    2b
+    3b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    M file1
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_absorb_paths() {
     let test_env = TestEnvironment::default();
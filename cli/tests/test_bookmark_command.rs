@@ -541,6 +541,7 @@ fn test_bookmark_move_conflicting() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Bookmarks with conflicting changes from concurrent operations: foo
     [EOF]
     ");
 
@@ -856,6 +857,77 @@ fn test_bookmark_delete_glob() {
     ");
 }
 
+#[test]
+fn test_bookmark_delete_glob_unmerged() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    git::init_bare(git_repo_path);
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../git-repo"])
+        .success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+
+    work_dir.run_jj(["describe", "-m=pushed"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo-1"])
+        .success();
+    work_dir
+        .run_jj(["git", "push", "--allow-new", "-b", "foo-1"])
+        .success();
+    work_dir.run_jj(["new", "-m=local"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo-2"])
+        .success();
+
+    // Dry-run lists the matched bookmarks and the remotes referencing them
+    let output = work_dir.run_jj(["bookmark", "delete", "--dry-run", "glob:foo-*"]);
+    insta::assert_snapshot!(output, @r"
+    foo-1: pushed
+      Still present on remotes: origin
+    foo-2: local
+    ------- stderr -------
+    Warning: Deleting unmerged bookmarks requires --force: foo-2
+    Dry-run requested, not deleting bookmarks.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bookmark", "forget", "--dry-run", "glob:foo-*"]);
+    insta::assert_snapshot!(output, @r"
+    foo-1: pushed
+      Still present on remotes: origin
+    foo-2: local
+    ------- stderr -------
+    Dry-run requested, not forgetting bookmarks.
+    [EOF]
+    ");
+
+    // Bookmarks which aren't merged anywhere can't be deleted by pattern
+    let output = work_dir.run_jj(["bookmark", "delete", "glob:foo-*"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Refusing to delete bookmarks not merged into trunk() or any remote bookmark: foo-2
+    Hint: Use --force to delete them anyway, or --dry-run to review the matched bookmarks.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Exact names and --force bypass the check
+    let output = work_dir.run_jj(["bookmark", "delete", "--force", "glob:foo-*"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Deleted 2 bookmarks.
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["bookmark", "delete", "foo-2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Deleted 1 bookmarks.
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_bookmark_delete_export() {
     let test_env = TestEnvironment::default();
@@ -1353,6 +1425,66 @@ fn test_bookmark_track_conflict() {
     ");
 }
 
+#[test]
+fn test_bookmark_create_set_at_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let git_repo_path = test_env.env_root().join("git-repo");
+    git::init_bare(git_repo_path);
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../git-repo"])
+        .success();
+    let list_tracking = || {
+        let template = r#"name ++ if(remote, "@" ++ remote) ++ if(tracked, " (tracked)") ++ "\n""#;
+        work_dir.run_jj(["bookmark", "list", "--all-remotes", "-T", template])
+    };
+
+    // New remote bookmark can't be tracked until it gets pushed
+    let output = work_dir.run_jj(["bookmark", "create", "-r@", "foo", "--at-remote", "origin"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 1 bookmarks pointing to qpvuntsm e8849ae1 foo | (empty) (no description set)
+    Hint: Remote bookmarks will be tracked once pushed. Use `--push` or run `jj git push --remote origin --allow-new --bookmark foo` to push them.
+    [EOF]
+    ");
+
+    // --push requires --at-remote
+    let output = work_dir.run_jj(["bookmark", "set", "foo", "--push"]);
+    assert!(output.stderr.raw().contains("--at-remote <REMOTE>"));
+
+    // Create and push in one go
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "foo", "--at-remote", "origin", "--push"])
+        .success();
+    insta::assert_snapshot!(list_tracking(), @r"
+    foo
+    foo@origin (tracked)
+    [EOF]
+    ");
+
+    // Existing remote bookmark gets tracked, and the local bookmark is moved
+    // to the target revision
+    work_dir
+        .run_jj(["bookmark", "untrack", "foo@origin"])
+        .success();
+    work_dir.run_jj(["new", "-m", "b"]).success();
+    let output = work_dir.run_jj(["bookmark", "set", "foo", "--at-remote", "origin"]);
+    assert!(!output.stderr.raw().contains("Hint:"));
+    insta::assert_snapshot!(list_tracking(), @r"
+    foo
+    foo@origin (tracked)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "foo", "--no-graph", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    b
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_bookmark_track_untrack_patterns() {
     let test_env = TestEnvironment::default();
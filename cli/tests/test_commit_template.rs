@@ -605,6 +605,24 @@ fn test_log_evolog_divergence() {
        [38;5;8m--[39m operation [38;5;4m8f47435a3990[39m ([38;5;6m2001-02-03 08:05:07[39m) add workspace 'default'
     [EOF]
     ");
+
+    // Divergence-aware template methods
+    let template = r#"
+    separate(" ",
+      change_id.short(8),
+      change_id.prefix_len(),
+      commit_id.short(8),
+      commit_id.prefix_len(),
+      divergent_commits().len(),
+    ) ++ "\n"
+    "#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm 1 556daeb7 2 2
+    qpvuntsm 1 5cea51a1 2 2
+    zzzzzzzz 1 00000000 1 1
+    [EOF]
+    ");
 }
 
 #[test]
@@ -995,6 +1013,47 @@ fn test_log_contained_in() {
     "#);
 }
 
+#[test]
+fn test_log_closest_bookmark() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-mA", "root()"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "base"])
+        .success();
+    work_dir.run_jj(["new", "-mB"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["new", "-mC"]).success();
+    work_dir.run_jj(["new", "-mD"]).success();
+    work_dir.run_jj(["new", "-mE", "root()"]).success();
+
+    let template = r#"separate(" ", description.first_line(), closest_bookmark) ++ "\n""#;
+    let output = work_dir.run_jj(["log", "-r::", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  E
+    │ ○  D main~2
+    │ ○  C main~1
+    │ ○  B main
+    │ ○  A base
+    ├─╯
+    ◆
+    [EOF]
+    ");
+
+    // The closest bookmark is found through the merge parents
+    work_dir
+        .run_jj(["new", "-mF", "description(A)", "description(C)"])
+        .success();
+    let output = work_dir.run_jj(["log", "-r@", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    F base~1
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_short_prefix_in_transaction() {
     let test_env = TestEnvironment::default();
@@ -212,6 +212,7 @@ fn test_bookmark_names() {
     --color	When to colorize output
     --quiet	Silence non-primary command output
     --no-pager	Disable the pager
+    --error-format	Format of error messages
     --config	Additional configuration options (can be repeated)
     --config-file	Additional configuration files (can be repeated)
     --help	Print help (see more with '--help')
@@ -808,6 +809,14 @@ fn test_revisions() {
     [EOF]
     ");
 
+    // complete revset functions
+    let output = work_dir.complete_fish(["log", "-r", "::desc"]);
+    insta::assert_snapshot!(output, @r"
+    ::descendants(	Revset function
+    ::description(	Revset function
+    [EOF]
+    ");
+
     // complete only mutable revisions
     let output = work_dir.complete_fish(["squash", "--into", ""]);
     insta::assert_snapshot!(output, @r"
@@ -839,6 +848,33 @@ fn test_revisions() {
     [EOF]
     ");
 
+    // complete revisions inside a function call
+    let output = work_dir.complete_fish(["log", "-r", "::ancestors(x, mu"]);
+    insta::assert_snapshot!(output, @r"
+    ::ancestors(x, mutable_bookmark	mutable
+    [EOF]
+    ");
+
+    // complete bookmark names inside bookmarks()
+    let output = work_dir.complete_fish(["log", "-r", "bookmarks("]);
+    insta::assert_snapshot!(output, @r"
+    bookmarks(immutable_bookmark
+    bookmarks(mutable_bookmark
+    bookmarks(remote_bookmark
+    [EOF]
+    ");
+    let output = work_dir.complete_fish(["log", "-r", "@ | remote_bookmarks(rem"]);
+    insta::assert_snapshot!(output, @r"
+    @ | remote_bookmarks(remote_bookmark
+    [EOF]
+    ");
+
+    // nothing to complete inside string literals or non-revset arguments
+    let output = work_dir.complete_fish(["log", "-r", r#"description("mu"#]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.complete_fish(["log", "-r", "author(mu"]);
+    insta::assert_snapshot!(output, @"");
+
     // complete args of the default command
     test_env.add_config("ui.default-command = 'log'");
     let output = work_dir.complete_fish(["-r", ""]);
@@ -883,6 +919,13 @@ fn test_revisions() {
     let output = work_dir.complete_fish(["git", "push", "--named", "a=a"]);
     insta::assert_snapshot!(output, @r"
     a=alias_with_newline	    roots(
+    a=all(	Revset function
+    a=ancestors(	Revset function
+    a=at_operation(	Revset function
+    a=author(	Revset function
+    a=author_date(	Revset function
+    a=author_email(	Revset function
+    a=author_name(	Revset function
     [EOF]
     ");
 }
@@ -1086,6 +1129,32 @@ fn test_template_alias() {
     name_placeholder
     [EOF]
     ");
+
+    let output = test_env.complete_fish(["log", "-T", "desc"]);
+    insta::assert_snapshot!(output, @r"
+    description_placeholder
+    description	Keyword
+    [EOF]
+    ");
+
+    let output = test_env.complete_fish(["log", "-T", "commit_id ++ sep"]);
+    insta::assert_snapshot!(output, @r"
+    commit_id ++ separate(	Template function
+    [EOF]
+    ");
+
+    // Keywords depend on the template type
+    let output = test_env.complete_fish(["op", "log", "-T", "snap"]);
+    insta::assert_snapshot!(output, @r"
+    snapshot	Keyword
+    [EOF]
+    ");
+
+    // Methods and string literals aren't completed
+    let output = test_env.complete_fish(["log", "-T", "author.na"]);
+    insta::assert_snapshot!(output, @"");
+    let output = test_env.complete_fish(["log", "-T", r#""desc"#]);
+    insta::assert_snapshot!(output, @"");
 }
 
 #[test]
@@ -1102,8 +1171,10 @@ fn test_merge_tools() {
     insta::assert_snapshot!(output, @r"
     :summary
     :stat
+    :numstat
     :types
     :name-only
+    :name-status
     :git
     :color-words
     diffedit3
@@ -1329,6 +1400,12 @@ fn test_files() {
     [EOF]
     ");
 
+    let output = work_dir.complete_fish(["file", "show", "a"]);
+    insta::assert_snapshot!(output, @r"
+    all()	Fileset function
+    [EOF]
+    ");
+
     let output = work_dir.complete_fish(["file", "annotate", "-r@-", "f_"]);
     insta::assert_snapshot!(output.normalize_backslash(), @r"
     f_added
@@ -65,6 +65,72 @@ fn test_concurrent_operation_divergence() {
     ");
 }
 
+#[test]
+fn test_concurrent_operations_merge_heads_policy() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "message 1"]).success();
+    work_dir
+        .run_jj(["describe", "-m", "message 2", "--at-op", "@-"])
+        .success();
+
+    // "op heads" lists the heads without merging them
+    let template = r#"id.short() ++ " " ++ description ++ "\n""#;
+    let output = work_dir.run_jj(["op", "heads", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    d8ced2ea64a8 describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    b2cffe4f3026 describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--config=operation.merge-heads=fail"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Concurrent modification detected: found 2 operation heads
+    Hint: Run `jj op heads` to inspect them, or run a command with `--config operation.merge-heads=auto` to merge them.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The prompt defaults to not merging in non-interactive sessions
+    let output = work_dir.run_jj(["log", "--config=operation.merge-heads=prompt"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Concurrent modification detected. Operation heads:
+      b2cffe4f3026 describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+      d8ced2ea64a8 describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Merge the operation heads? (yN): n
+    Error: Operation heads were not merged
+    Hint: Run `jj op heads` to inspect them.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The heads are still there
+    let output = work_dir.run_jj(["op", "heads", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    d8ced2ea64a8 describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    b2cffe4f3026 describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    [EOF]
+    ");
+
+    let output = get_log_output(&work_dir);
+    insta::assert_snapshot!(output, @r"
+    @  message 1
+    │ ○  message 2
+    ├─╯
+    ◆
+    [EOF]
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "heads", "-T", "description"]);
+    insta::assert_snapshot!(output, @"reconcile divergent operations[EOF]");
+}
+
 #[test]
 fn test_concurrent_operations_auto_rebase() {
     let test_env = TestEnvironment::default();
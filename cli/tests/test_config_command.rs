@@ -1258,6 +1258,70 @@ fn test_config_get() {
     ");
 }
 
+#[test]
+fn test_config_env_var_interpolation() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("TEST_SERVER", "example.com");
+    test_env.add_config(
+        r#"
+    [test-table]
+    url = "https://${env:TEST_SERVER}/path"
+    literal = "$${env:TEST_SERVER}"
+    missing = "${env:TEST_MISSING}"
+    shell = ["sh", "-c", "echo ${1} ${TEST_SERVER}"]
+    "#,
+    );
+
+    let output = test_env.run_jj_in(".", ["config", "get", "test-table.url"]);
+    insta::assert_snapshot!(output, @r"
+    https://example.com/path
+    [EOF]
+    ");
+    // Unset variables and non-`env:` references are left as is
+    let output = test_env.run_jj_in(".", ["config", "list", "test-table"]);
+    insta::assert_snapshot!(output, @r#"
+    test-table.url = "https://example.com/path"
+    test-table.literal = "${env:TEST_SERVER}"
+    test-table.missing = "${env:TEST_MISSING}"
+    test-table.shell = ["sh", "-c", "echo ${1} ${TEST_SERVER}"]
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_config_list_redacts_secrets() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    [test-table]
+    token = "abc"
+    nested.password = "def"
+    api-token = "jkl"
+    smtp_password = "mno"
+    user = "ghi"
+    tokens = "pqr"
+    "#,
+    );
+
+    let output = test_env.run_jj_in(".", ["config", "list", "test-table"]);
+    insta::assert_snapshot!(output, @r#"
+    test-table.token = "<redacted>"
+    test-table.nested.password = "<redacted>"
+    test-table.api-token = "<redacted>"
+    test-table.smtp_password = "<redacted>"
+    test-table.user = "ghi"
+    test-table.tokens = "pqr"
+    [EOF]
+    "#);
+
+    // The value can still be looked up explicitly
+    let output = test_env.run_jj_in(".", ["config", "get", "test-table.token"]);
+    insta::assert_snapshot!(output, @r"
+    abc
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_config_get_yields_values_consistent_with_schema_defaults() {
     let test_env = TestEnvironment::default();
@@ -147,6 +147,82 @@ fn test_debug_revset() {
     ");
 }
 
+#[test]
+fn test_debug_template_json_ast() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config(
+        r#"
+        [template-aliases]
+        'greeting(x)' = '"hello " ++ x'
+        "#,
+    );
+
+    let template = r#"if(false, author.username(), greeting("x"))"#;
+    let output = work_dir
+        .run_jj(["debug", "template", "--json-ast", template])
+        .success();
+    let json: serde_json::Value = serde_json::from_str(output.stdout.raw()).unwrap();
+    let ast = &json["ast"];
+    assert_eq!(ast["kind"], "FunctionCall");
+    assert_eq!(ast["name"], "if");
+    assert_eq!(ast["span"], serde_json::json!([0, 43]));
+    let args = &ast["args"];
+    assert_eq!(args[0]["kind"], "Boolean");
+    assert_eq!(args[0]["type"], "Boolean");
+    assert_eq!(args[1]["kind"], "MethodCall");
+    assert_eq!(args[1]["name"], "username");
+    assert_eq!(args[1]["object"]["type"], "Signature");
+    assert_eq!(args[1]["type"], "String");
+    assert_eq!(args[1]["span"], serde_json::json!([10, 27]));
+    assert_eq!(args[2]["kind"], "AliasExpanded");
+    assert_eq!(args[2]["alias"], "greeting(x)");
+    assert_eq!(args[2]["expansion"]["kind"], "Concat");
+    assert_eq!(args[2]["type"], "Template");
+    assert_eq!(
+        json["warnings"],
+        serde_json::json!([
+            "username() is deprecated; use email().local() instead",
+            "This branch is never evaluated because the condition is always false",
+        ])
+    );
+    assert_snapshot!(output.stderr, @r#"
+    Warning: In template expression
+     --> 1:18
+      |
+    1 | if(false, author.username(), greeting("x"))
+      |                  ^------^
+      |
+      = username() is deprecated; use email().local() instead
+    Warning: In template expression
+     --> 1:11
+      |
+    1 | if(false, author.username(), greeting("x"))
+      |           ^---------------^
+      |
+      = This branch is never evaluated because the condition is always false
+    [EOF]
+    "#);
+
+    // Lambda parameters can't be typed out of context
+    let output = work_dir
+        .run_jj([
+            "debug",
+            "template",
+            "--json-ast",
+            "parents.map(|c| c.author())",
+        ])
+        .success();
+    let json: serde_json::Value = serde_json::from_str(output.stdout.raw()).unwrap();
+    let lambda = &json["ast"]["args"][0];
+    assert_eq!(lambda["kind"], "Lambda");
+    assert_eq!(lambda["params"], serde_json::json!(["c"]));
+    assert_eq!(lambda["body"]["type"], serde_json::Value::Null);
+    assert_eq!(json["ast"]["object"]["type"], "List<Commit>");
+    assert_eq!(json["warnings"], serde_json::json!([]));
+}
+
 #[test]
 fn test_debug_index() {
     let test_env = TestEnvironment::default();
@@ -211,6 +287,92 @@ fn test_debug_reindex() {
     ");
 }
 
+#[test]
+fn test_debug_upgrade_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let version_path = work_dir
+        .root()
+        .join(".jj")
+        .join("repo")
+        .join("format_version");
+
+    let output = work_dir.run_jj(["debug", "upgrade-repo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Repository format is up to date (version 1).
+    [EOF]
+    ");
+
+    // Repos created before the format version was recorded can still be
+    // loaded, and can be upgraded.
+    std::fs::remove_file(&version_path).unwrap();
+    work_dir.run_jj(["log"]).success();
+    let output = work_dir.run_jj(["debug", "upgrade-repo", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Would upgrade repository format from version 0 to 1:
+      Record repository format version
+    [EOF]
+    ");
+    assert!(!version_path.exists());
+    let output = work_dir.run_jj(["debug", "upgrade-repo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Upgraded repository format from version 0 to 1:
+      Record repository format version
+    [EOF]
+    ");
+    assert_eq!(std::fs::read_to_string(&version_path).unwrap(), "1\n");
+
+    // Repos written by newer versions can't be loaded
+    std::fs::write(&version_path, "2\n").unwrap();
+    let output = work_dir.run_jj(["log"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Repository format version 2 is newer than the supported version 1; please upgrade jj
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_debug_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["new", "-mleft"]).success();
+    work_dir.write_file("file", "b\n");
+    work_dir.run_jj(["new", "-mright", "@-"]).success();
+    work_dir.write_file("file", "c\n");
+    work_dir
+        .run_jj(["new", "description(left)", "description(right)"])
+        .success();
+
+    let output = work_dir.run_jj(["debug", "conflict", "file"]);
+    assert_snapshot!(output, @r#"
+    Terms:
+      +0: Some(File { id: FileId("61780798228d17af2d34fce4cfbdf35556832472"), executable: false, copy_id: CopyId("") })
+      -0: Some(File { id: FileId("78981922613b2afb6025042ff6bd878ac1994e85"), executable: false, copy_id: CopyId("") })
+      +1: Some(File { id: FileId("f2ad6c76f0115a6ba5b00456a849810e7ec0af20"), executable: false, copy_id: CopyId("") })
+    Simplification:
+      (none)
+    Resolution: unresolved (the file contents have conflicting hunks)
+    [EOF]
+    "#);
+
+    // Resolved paths have a single term
+    let output = work_dir.run_jj(["debug", "conflict", "-rdescription(left)", "file"]);
+    assert_snapshot!(output, @r#"
+    Terms:
+      +0: Some(File { id: FileId("61780798228d17af2d34fce4cfbdf35556832472"), executable: false, copy_id: CopyId("") })
+    Resolution: not conflicted
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_debug_tree() {
     let test_env = TestEnvironment::default();
@@ -288,6 +450,115 @@ fn test_debug_tree() {
     );
 }
 
+#[test]
+fn test_debug_object() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "foo\n");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+
+    // Objects can be looked up with or without type
+    let stdin = [
+        "257cc5642cb1a054f08cc83f2d943e56fd3ebe99",
+        "tree 4a1c03029e7407c0afe9fc0320b3258e188b115e",
+        "0000000000000000000000000000000000000000",
+    ]
+    .join("\n");
+    let output = work_dir
+        .run_jj_with(|cmd| cmd.args(["debug", "object"]).write_stdin(stdin))
+        .normalize_stdout_with(|s| s.replace('\t', "<TAB>"));
+    assert_snapshot!(output, @r"
+    257cc5642cb1a054f08cc83f2d943e56fd3ebe99 file 4
+    foo
+
+    4a1c03029e7407c0afe9fc0320b3258e188b115e tree 51
+    file 257cc5642cb1a054f08cc83f2d943e56fd3ebe99<TAB>file
+
+    0000000000000000000000000000000000000000 commit 125
+    tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+    change-id zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz
+    author  <> 0 +0
+    committer  <> 0 +0
+
+
+    [EOF]
+    ");
+
+    // Unknown types, invalid ids, and nonexistent objects are missing
+    let stdin = [
+        "blob 257cc5642cb1a054f08cc83f2d943e56fd3ebe99",
+        "zz",
+        "file 1111111111111111111111111111111111111111",
+    ]
+    .join("\n");
+    let output = work_dir.run_jj_with(|cmd| cmd.args(["debug", "object"]).write_stdin(stdin));
+    assert_snapshot!(output, @r"
+    257cc5642cb1a054f08cc83f2d943e56fd3ebe99 missing
+    zz missing
+    1111111111111111111111111111111111111111 missing
+    [EOF]
+    ");
+
+    // Other backend errors, such as reading an object as a wrong type, are
+    // reported
+    let stdin = "file 4a1c03029e7407c0afe9fc0320b3258e188b115e";
+    let output = work_dir.run_jj_with(|cmd| cmd.args(["debug", "object"]).write_stdin(stdin));
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Internal error: Unexpected error from backend
+    Caused by:
+    1: Error when reading object 4a1c03029e7407c0afe9fc0320b3258e188b115e of type file
+    2: Object named 4a1c03029e7407c0afe9fc0320b3258e188b115e was supposed to be of kind blob, but was kind tree.
+    [EOF]
+    [exit status: 255]
+    ");
+}
+
+#[test]
+fn test_debug_env() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // The features and backends depend on how jj is built
+    let output = work_dir
+        .run_jj(["debug", "env"])
+        .normalize_stdout_with(|s| {
+            let regex = Regex::new(r"(?m)^(jj|Features:|Backends:|Fsmonitors:) .*$").unwrap();
+            regex.replace_all(&s, "$1 <redacted>").into_owned()
+        });
+    assert_snapshot!(output, @r"
+    jj <redacted>
+    Features: <redacted>
+    Backends: <redacted>
+    Working copies: local
+    Fsmonitors: <redacted>
+    Configured fsmonitor: none
+    Workspace: $TEST_ENV/repo
+    Backend: git
+    Working copy: local
+    Git interop: internal
+    Environment variables: JJ_CONFIG, JJ_EMAIL, JJ_OP_HOSTNAME, JJ_OP_TIMESTAMP, JJ_OP_USERNAME, JJ_RANDOMNESS_SEED, JJ_TIMESTAMP, JJ_TZ_OFFSET_MINS, JJ_USER
+    [EOF]
+    ------- stderr -------
+    Warning: Environment variables meant for testing are set: JJ_OP_TIMESTAMP, JJ_RANDOMNESS_SEED, JJ_TIMESTAMP, JJ_TZ_OFFSET_MINS
+    Hint: These make timestamps and ids deterministic. Unset them unless you're running tests.
+    [EOF]
+    ");
+
+    // Problems in the config are reported
+    let output = test_env.run_jj_in(".", ["debug", "env", "--config=user.email="]);
+    assert_snapshot!(output.stderr, @r#"
+    Warning: Email not configured.
+    Hint: Run `jj config set --user user.email "someone@example.com"`.
+    Warning: Environment variables meant for testing are set: JJ_OP_TIMESTAMP, JJ_RANDOMNESS_SEED, JJ_TIMESTAMP, JJ_TZ_OFFSET_MINS
+    Hint: These make timestamps and ids deterministic. Unset them unless you're running tests.
+    [EOF]
+    "#);
+    assert!(output.stdout.raw().contains("Workspace: none\n"));
+}
+
 #[test]
 fn test_debug_operation_id() {
     let test_env = TestEnvironment::default();
@@ -300,6 +571,261 @@ fn test_debug_operation_id() {
     ");
 }
 
+#[test]
+fn test_debug_op_store_fsck() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let op_heads_dir = work_dir.root().join(".jj/repo/op_heads/heads");
+
+    let output = work_dir.run_jj(["debug", "op-store", "fsck"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Checked 2 operations, found 0 problems.
+    [EOF]
+    ");
+
+    // Add a head pointing to a missing operation, and a file left behind by a
+    // sync tool
+    let missing_op_id = "ab".repeat(64);
+    std::fs::write(op_heads_dir.join(&missing_op_id), "").unwrap();
+    std::fs::write(op_heads_dir.join("conflicted copy"), "").unwrap();
+    let read_error_regex = Regex::new(r"Failed to read operation: .*").unwrap();
+    let normalize = |text: String| {
+        read_error_regex
+            .replace_all(&text, "Failed to read operation: [error]")
+            .into_owned()
+    };
+    let output = work_dir.run_jj(["debug", "op-store", "fsck"]);
+    assert_snapshot!(output.normalize_stdout_with(normalize), @r"
+    Unrecognized file in op heads directory: conflicted copy
+    Operation abababababab: Failed to read operation: [error]
+    Op head abababababab has broken history
+    ------- stderr -------
+    Checked 3 operations, found 2 problems.
+    Error: The operation store is corrupted
+    Hint: Run `jj debug op-store fsck --quarantine` to move the broken op heads aside.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The broken head can be moved aside
+    std::fs::remove_file(op_heads_dir.join("conflicted copy")).unwrap();
+    let output = work_dir.run_jj(["debug", "op-store", "fsck", "--quarantine"]);
+    assert_snapshot!(output.normalize_stdout_with(normalize).normalize_backslash(), @r"
+    Operation abababababab: Failed to read operation: [error]
+    Op head abababababab has broken history
+    ------- stderr -------
+    Checked 3 operations, found 1 problems.
+    Quarantined op head abababababab to $TEST_ENV/repo/.jj/repo/op_heads/quarantine/abababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababab
+    Hint: Run `jj op log` to check the remaining operation history.
+    [EOF]
+    ");
+    assert!(!op_heads_dir.join(&missing_op_id).exists());
+
+    let output = work_dir.run_jj(["debug", "op-store", "fsck"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Checked 2 operations, found 0 problems.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_debug_op_store_export_import() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.run_jj_in(".", ["git", "init", "other"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let other_dir = test_env.work_dir("other");
+    work_dir
+        .run_jj(["describe", "-m", "secret message"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "secret-bookmark"])
+        .success();
+    work_dir.run_jj(["new"]).success();
+
+    let output = work_dir.run_jj([
+        "debug",
+        "op",
+        "export",
+        "-o",
+        "../bundle",
+        "--with-commits",
+        "--redact",
+    ]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Exported 4 operations and 3 commits.
+    [EOF]
+    ");
+
+    let output = other_dir.run_jj(["debug", "op", "import", "../bundle"]);
+    let op_id = output.stdout.raw().trim().to_owned();
+    assert_snapshot!(output.normalize_stdout_with(|_| "[op id]\n".to_owned()), @r"
+    [op id]
+    ------- stderr -------
+    Imported 4 operations and 3 commits.
+    [EOF]
+    ");
+
+    // The imported operations aren't added to the op heads
+    let output = other_dir.run_jj(["op", "log", "--no-graph", "-T", r#"description ++ "\n""#]);
+    assert_snapshot!(output, @r"
+    add workspace 'default'
+
+    [EOF]
+    ");
+    let output = other_dir.run_jj([
+        "op",
+        "log",
+        "--at-op",
+        &op_id,
+        "--no-graph",
+        "-T",
+        r#"separate(" ", description.first_line(), user) ++ "\n""#,
+    ]);
+    assert_snapshot!(output, @r"
+    <redacted> user-2@host-1
+    <redacted> user-2@host-1
+    <redacted> user-2@host-1
+    <redacted> user-2@host-1
+    @
+    [EOF]
+    ");
+    // The workspace name is redacted, so the working-copy commit can't be
+    // looked up as `@`
+    let output = other_dir.run_jj([
+        "log",
+        "--at-op",
+        &op_id,
+        "--ignore-working-copy",
+        "--no-graph",
+        "-r",
+        "working_copies()-",
+        "-T",
+        r#"separate(" ", description, author, bookmarks) ++ "\n""#,
+    ]);
+    assert_snapshot!(output, @r"
+    <redacted> user-1 <email-1@example.com> bookmark-1
+    [EOF]
+    ");
+
+    std::fs::write(test_env.env_root().join("garbage"), "foo").unwrap();
+    let output = other_dir.run_jj(["debug", "op", "import", "../garbage"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Invalid operation bundle: unrecognized header
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_debug_op_store_fsck_hash_mismatch() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let op_heads_dir = work_dir.root().join(".jj/repo/op_heads/heads");
+    let operations_dir = work_dir.root().join(".jj/repo/op_store/operations");
+
+    // Store a copy of the head operation under a different id
+    let head_op_path = std::fs::read_dir(&op_heads_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .find(|name| name != "lock")
+        .map(|name| operations_dir.join(name))
+        .unwrap();
+    let bad_op_id = "cd".repeat(64);
+    std::fs::copy(head_op_path, operations_dir.join(&bad_op_id)).unwrap();
+    std::fs::write(op_heads_dir.join(&bad_op_id), "").unwrap();
+    let output = work_dir.run_jj(["debug", "op-store", "fsck"]);
+    assert_snapshot!(output, @r"
+    Operation cdcdcdcdcdcd: Operation content doesn't match its id
+    Op head cdcdcdcdcdcd has broken history
+    ------- stderr -------
+    Checked 3 operations, found 1 problems.
+    Error: The operation store is corrupted
+    Hint: Run `jj debug op-store fsck --quarantine` to move the broken op heads aside.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_debug_snapshot_watch() {
+    use std::process::Child;
+    use std::process::Command;
+    use std::process::Stdio;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    struct KillOnDrop(Child);
+
+    impl Drop for KillOnDrop {
+        fn drop(&mut self) {
+            self.0.kill().ok();
+            self.0.wait().ok();
+        }
+    }
+
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let socket_path = work_dir.root().join(".jj/working_copy/snapshot-watch.sock");
+
+    // The watching process only snapshots on behalf of commands with the same
+    // config, so run all processes with the same environment.
+    let jj_cmd = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("jj"));
+        cmd.current_dir(work_dir.root())
+            .env_clear()
+            .env("HOME", test_env.home_dir())
+            .env("JJ_CONFIG", test_env.config_path())
+            .env("JJ_USER", "Test User")
+            .env("JJ_EMAIL", "test.user@example.com")
+            .env("JJ_OP_HOSTNAME", "host.example.com")
+            .env("JJ_OP_USERNAME", "test-username")
+            .env("JJ_TIMESTAMP", "2001-02-03T04:05:06+07:00")
+            .env("JJ_OP_TIMESTAMP", "2001-02-03T04:05:06+07:00")
+            .env("JJ_RANDOMNESS_SEED", "1")
+            .args(args);
+        cmd
+    };
+    let last_op = |args: &[&str]| {
+        let mut args = args.to_vec();
+        args.extend(["op", "log", "--no-graph", "-n1", "-T", r#"tags ++ "\n""#]);
+        let output = jj_cmd(&args).output().unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let _watcher = KillOnDrop(
+        jj_cmd(&["debug", "snapshot", "--watch", "--interval=1000"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap(),
+    );
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !socket_path.exists() {
+        assert!(Instant::now() < deadline, "watching process didn't start");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // The watching process snapshots on behalf of the command
+    work_dir.write_file("file1", "");
+    assert_snapshot!(last_op(&[]), @"args: jj debug snapshot --watch --interval=1000");
+
+    // Commands with different config snapshot by themselves
+    work_dir.write_file("file2", "");
+    assert_snapshot!(last_op(&["--config=ui.color=never"]), @r#"
+    args: jj --config=ui.color=never op log --no-graph -n1 -T 'tags ++ "\n"'
+    "#);
+}
+
 fn filter_index_stats(output: CommandOutput) -> CommandOutput {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     output.normalize_stdout_with(|text| regex.replace_all(&text, "    Name: [hash]").into_owned())
@@ -534,6 +534,56 @@ fn test_describe_multiple_commits() {
     ");
 }
 
+#[test]
+fn test_describe_multiple_commits_from_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"separate(" ", change_id.shortest(8), description.first_line())"#;
+
+    work_dir.run_jj(["new"]).success();
+    work_dir.run_jj(["new"]).success();
+
+    // Descriptions are looked up by change ID prefix
+    work_dir
+        .run_jj_with(|cmd| {
+            cmd.args(["describe", "-r@", "-r@--", "--stdin", "--json"])
+                .write_stdin(r#"{"kkmpptxz": "first\n", "qpv": "third\n"}"#)
+        })
+        .success();
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  kkmpptxz first
+    ○  rlvkpnrz
+    ○  qpvuntsm third
+    ◆  zzzzzzzz
+    [EOF]
+    ");
+
+    // Prefixes must match one of the revisions being described
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["describe", "-r@", "--stdin", "--json"])
+            .write_stdin(r#"{"qpvuntsm": "foo\n"}"#)
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Change ID prefix `qpvuntsm` doesn't match any of the revisions being described
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["describe", "-r@", "-r@-", "--stdin", "--json"])
+            .write_stdin(r#"{"": "foo\n"}"#)
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Change ID prefix `` is ambiguous among the revisions being described
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_multiple_message_args() {
     let test_env = TestEnvironment::default();
@@ -646,6 +696,57 @@ fn test_describe_default_description() {
     "#);
 }
 
+#[test]
+fn test_describe_template() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "bar\n");
+    std::fs::write(edit_script, ["dump editor"].join("\0")).unwrap();
+    let template = r#"concat(description, "JJ: ", diff.files().len(), " files changed\n")"#;
+    work_dir
+        .run_jj(["describe", "--template", template])
+        .success();
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    JJ: 2 files changed
+    JJ:
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+
+    // The builtin template is used for commits with too many changed files
+    test_env.add_config("describe.draft-max-files = 1");
+    work_dir
+        .run_jj(["describe", "--template", template])
+        .success();
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    JJ: This commit changes more than 1 files; using the builtin draft template.
+
+    JJ: This commit contains the following changes:
+    JJ:     A file1
+    JJ:     A file2
+    JJ:
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+
+    // The template is only used when an editor is opened
+    let output = work_dir.run_jj(["describe", "--no-edit", "--template", template]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--no-edit' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj describe --no-edit [REVSETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_describe_author() {
     let mut test_env = TestEnvironment::default();
@@ -322,8 +322,10 @@ fn test_diff_basic() {
     error: the argument '--template <TEMPLATE>' cannot be used with:
       --summary
       --stat
+      --numstat
       --types
       --name-only
+      --name-status
 
     Usage: jj diff --template <TEMPLATE> --summary [FILESETS]...
 
@@ -399,6 +401,60 @@ fn test_diff_empty() {
     ");
 }
 
+#[test]
+fn test_diff_pathspec_from_file() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "");
+    work_dir.write_file("file2", "");
+    work_dir.write_file("file3", "");
+    let pathspec_path = test_env.env_root().join("paths.txt");
+    let pathspec_arg = pathspec_path.to_str().unwrap();
+
+    // Filesets from the file are added to the ones from arguments
+    std::fs::write(&pathspec_path, "file1\n\nglob:file3\n").unwrap();
+    let output = work_dir.run_jj(["diff", "--summary", "--pathspec-from-file", pathspec_arg]);
+    insta::assert_snapshot!(output, @r"
+    A file1
+    A file3
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "diff",
+        "--summary",
+        "--pathspec-from-file",
+        pathspec_arg,
+        "file2",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    A file1
+    A file2
+    A file3
+    [EOF]
+    ");
+    let output = work_dir
+        .run_jj(["status", "--pathspec-from-file", pathspec_arg])
+        .success();
+    assert!(output.stdout.raw().contains("A file1\nA file3\n"));
+
+    // An empty file matches nothing
+    std::fs::write(&pathspec_path, "").unwrap();
+    let output = work_dir.run_jj(["diff", "--summary", "--pathspec-from-file", pathspec_arg]);
+    insta::assert_snapshot!(output, @"");
+
+    // Filesets can be read from stdin
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["diff", "--summary", "--pathspec-from-file", "-"])
+            .write_stdin("file2\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    A file2
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_file_mode() {
     let test_env = TestEnvironment::default();
@@ -659,6 +715,159 @@ fn test_diff_name_only() {
     ");
 }
 
+#[cfg(feature = "syntax-highlight")]
+#[test]
+fn test_diff_syntax_highlight() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("main.rs", "fn main() {}\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("main.rs", "// greeting\nfn main() {}\n");
+    work_dir.write_file("notes.txt", "fn main() {}\n");
+
+    // Disabled by default
+    let output = work_dir.run_jj(["diff", "--git", "--color=debug"]);
+    assert!(!output.stdout.raw().contains("syntax"));
+
+    // Tokens of files in known languages are labeled by the grammar, for both
+    // the added and context lines. The same line in notes.txt isn't labeled.
+    for format in ["--git", "--color-words"] {
+        let output = work_dir.run_jj([
+            "diff",
+            format,
+            "--color=debug",
+            "--config=ui.syntax-highlight=true",
+        ]);
+        let stdout = output.stdout.raw();
+        assert!(stdout.contains("syntax comment::// greeting>>"), "{stdout}");
+        assert_eq!(
+            stdout.matches("syntax keyword::fn>>").count(),
+            1,
+            "{stdout}"
+        );
+    }
+}
+
+#[test]
+fn test_diff_name_status() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "1\n2\n3\n4\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "1\n5\n3\n");
+    work_dir.write_file("file3", "foo\n");
+    work_dir.write_file("file4", "1\n2\n3\n4\n");
+    work_dir.write_file("file5", "new\n");
+
+    let output = work_dir
+        .run_jj(["diff", "--name-status"])
+        .normalize_stdout_with(|s| s.replace('\t', "<TAB>"));
+    insta::assert_snapshot!(output, @r"
+    M<TAB>file2
+    R<TAB>file1<TAB>file3
+    C<TAB>file2<TAB>file4
+    A<TAB>file5
+    [EOF]
+    ");
+
+    let output = work_dir
+        .run_jj(["diff", "--name-status", "--null"])
+        .normalize_stdout_with(|s| s.replace('\0', "<NUL>"));
+    insta::assert_snapshot!(output, @"M<NUL>file2<NUL>R<NUL>file1<NUL>file3<NUL>C<NUL>file2<NUL>file4<NUL>A<NUL>file5<NUL>[EOF]");
+
+    let output = work_dir
+        .run_jj(["diff", "--name-only", "--null"])
+        .normalize_stdout_with(|s| s.replace('\0', "<NUL>"));
+    insta::assert_snapshot!(output, @"file2<NUL>file3<NUL>file4<NUL>file5<NUL>[EOF]");
+    let output = work_dir
+        .run_jj(["diff", "--tool=:name-only", "--null"])
+        .normalize_stdout_with(|s| s.replace('\0', "<NUL>"));
+    insta::assert_snapshot!(output, @"file2<NUL>file3<NUL>file4<NUL>file5<NUL>[EOF]");
+
+    // --null is rejected for formats which don't support it
+    let output = work_dir.run_jj(["diff", "--null", "--git"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--null' cannot be used with '--git'
+
+    Usage: jj diff --null --git [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    let output = work_dir.run_jj(["diff", "--null", "--tool=:summary"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --null can only be used with --numstat, --name-only, or --name-status
+    [EOF]
+    [exit status: 2]
+    ");
+    // The default format doesn't support it either
+    let output = work_dir.run_jj(["diff", "--null"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --null can only be used with --numstat, --name-only, or --name-status
+    [EOF]
+    [exit status: 2]
+    ");
+
+    let output = work_dir
+        .run_jj(["diff", "--tool=:name-status", "glob:file[12]"])
+        .normalize_stdout_with(|s| s.replace('\t', "<TAB>"));
+    insta::assert_snapshot!(output, @r"
+    D<TAB>file1
+    M<TAB>file2
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_numstat() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "1\n2\n3\n4\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "1\n5\n3\n");
+    work_dir.write_file("file3", "foo\n");
+    work_dir.write_file("file4", "1\n2\n3\n4\n");
+    work_dir.write_file("file5", "new\n");
+
+    let output = work_dir
+        .run_jj(["diff", "--numstat"])
+        .normalize_stdout_with(|s| s.replace('\t', "<TAB>"));
+    insta::assert_snapshot!(output, @r"
+    1<TAB>2<TAB>file2
+    0<TAB>0<TAB>{file1 => file3}
+    0<TAB>0<TAB>{file2 => file4}
+    1<TAB>0<TAB>file5
+    [EOF]
+    ");
+
+    let output = work_dir
+        .run_jj(["diff", "--numstat", "--null"])
+        .normalize_stdout_with(|s| s.replace('\t', "<TAB>").replace('\0', "<NUL>"));
+    insta::assert_snapshot!(output, @"1<TAB>2<TAB>file2<NUL>0<TAB>0<TAB><NUL>file1<NUL>file3<NUL>0<TAB>0<TAB><NUL>file2<NUL>file4<NUL>1<TAB>0<TAB>file5<NUL>[EOF]");
+
+    let output = work_dir
+        .run_jj(["diff", "--tool=:numstat", "glob:file[12]"])
+        .normalize_stdout_with(|s| s.replace('\t', "<TAB>"));
+    insta::assert_snapshot!(output, @r"
+    0<TAB>1<TAB>file1
+    1<TAB>2<TAB>file2
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_renamed_file_and_dir() {
     let test_env = TestEnvironment::default();
@@ -2400,6 +2609,63 @@ fn test_diff_leading_trailing_context() {
     ");
 }
 
+#[test]
+fn test_diff_function_context() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(
+        "lib.rs",
+        indoc! {"
+            use std::fmt;
+            fn foo() {
+                let a = 1;
+                let b = 2;
+                let c = 3;
+            }
+            fn bar() {
+                let x = 1;
+            }
+        "},
+    );
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file(
+        "lib.rs",
+        indoc! {"
+            use std::fmt;
+            fn foo() {
+                let a = 1;
+                let b = 20;
+                let c = 3;
+            }
+            fn bar() {
+                let x = 1;
+            }
+        "},
+    );
+
+    let render_hunks = |args: &[&str]| {
+        let output = work_dir.run_jj([["diff", "--git", "--context=0"].as_slice(), args].concat());
+        let stdout = output.stdout.normalized();
+        stdout[stdout.find("@@").unwrap()..].to_owned()
+    };
+    insta::assert_snapshot!(render_hunks(&[]), @r"
+    @@ -4,1 +4,1 @@
+    -    let b = 2;
+    +    let b = 20;
+    ");
+    insta::assert_snapshot!(render_hunks(&["--function-context"]), @r"
+    @@ -2,5 +2,5 @@
+     fn foo() {
+         let a = 1;
+    -    let b = 2;
+    +    let b = 20;
+         let c = 3;
+     }
+    ");
+}
+
 #[test]
 fn test_diff_conflict_sides_differ() {
     let test_env = TestEnvironment::default();
@@ -3024,6 +3290,97 @@ fn test_diff_conflict_three_sides() {
     ");
 }
 
+#[test]
+fn test_diff_against_all_parents() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("file", "a\nb\nc\n"), ("file2", "x\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "left",
+        &["base"],
+        &[("file", "a\nB\nc\n"), ("file2", "y\n")],
+    );
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "a\nb\nC\n")]);
+    create_commit_with_files(
+        &work_dir,
+        "merge",
+        &["left", "right"],
+        &[("file", "a\nB\nC\nd\n")],
+    );
+
+    // file2 is taken from the left parent, so it isn't shown
+    let output = work_dir.run_jj(["diff", "--against-all-parents"]);
+    insta::assert_snapshot!(output, @r"
+    diff --cc file
+    index 7be73ce3c1,6dcce7d0cf..5d9294bd8d 100644
+    --- a/file
+    +++ b/file
+    @@@ -1,3 -1,3 +1,4 @@@
+      a
+    - c
+     -b
+     +B
+    + C
+    ++d
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--against-all-parents", "-rleft", "-rright"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --against-all-parents requires exactly one revision
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_diff_formatter_overrides() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config(
+        r#"
+        [ui.diff-formatter-overrides.rust]
+        patterns = ['glob:"**/*.rs"']
+        formatter = ":summary"
+        "#,
+    );
+
+    work_dir.write_file("file.txt", "1\n");
+    work_dir.write_file("file.rs", "1\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file.txt", "2\n");
+    work_dir.write_file("file.rs", "2\n");
+
+    // Files matching the override are shown by the override formatter
+    let output = work_dir.run_jj(["diff"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file.txt:
+       1    1: 12
+    M file.rs
+    [EOF]
+    ");
+
+    // The overrides are ignored if the format is specified by arguments
+    let output = work_dir.run_jj(["diff", "--tool=:color-words"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file.rs:
+       1    1: 12
+    Modified regular file file.txt:
+       1    1: 12
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_external_tool() {
     let mut test_env = TestEnvironment::default();
@@ -3802,6 +4159,68 @@ fn test_diff_binary() {
     ");
 }
 
+#[test]
+fn test_diff_binary_by_attributes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(".gitattributes", "*.dat binary\n");
+    work_dir.write_file("file.dat", "foo\n");
+    work_dir.write_file("file.txt", "foo\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file.dat", "bar\n");
+    work_dir.write_file("file.txt", "bar\n");
+
+    // Text files are diffed as binary if the "diff" attribute is unset
+    let output = work_dir.run_jj(["diff"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file.dat:
+        (binary)
+    Modified regular file file.txt:
+       1    1: foobar
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git", "file.dat"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/file.dat b/file.dat
+    index 257cc5642c..5716ca5987 100644
+    Binary files a/file.dat and b/file.dat differ
+    [EOF]
+    ");
+
+    // Rules in .jj/attributes take precedence
+    work_dir.write_file(".jj/attributes", "file.dat diff\n");
+    let output = work_dir.run_jj(["diff", "file.dat"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file.dat:
+       1    1: foobar
+    [EOF]
+    ");
+
+    work_dir.remove_file(".jj/attributes");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file(".gitattributes", "");
+    work_dir.write_file("sub/.gitattributes", "*.txt -diff\n");
+    work_dir.write_file("sub/file.txt", "foo\n");
+
+    // Attributes are read from the diffed trees, not from the working copy
+    let output = work_dir.run_jj(["diff", "-r@-", "file.dat"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file.dat:
+        (binary)
+    [EOF]
+    ");
+
+    // Rules in nested .gitattributes file apply to the paths in its directory
+    let output = work_dir.run_jj(["diff", "sub/file.txt"]);
+    insta::assert_snapshot!(output, @r"
+    Added regular file sub/file.txt:
+        (binary)
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_revisions() {
     let test_env = TestEnvironment::default();
@@ -2520,6 +2520,73 @@ fn test_duplicate_description_template() {
     ");
 }
 
+#[test]
+fn test_duplicate_onto_each() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "r1", &[]);
+    create_commit(&work_dir, "r2", &[]);
+    let normalize_ids = |text: String| {
+        let regex = regex::Regex::new(r"\b[0-9a-f]{40}\b").unwrap();
+        regex.replace_all(&text, "<id>").into_owned()
+    };
+
+    let template = r#"
+    description.first_line() ++ " onto " ++ parents.map(|p| p.description().first_line())
+    "#;
+    let output = work_dir
+        .run_jj([
+            "duplicate",
+            "a::b",
+            "--onto-each=r1",
+            "--onto-each=r2",
+            "--mapping-template",
+            template,
+        ])
+        .success();
+    insta::assert_snapshot!(output.normalize_stdout_with(normalize_ids).stdout, @r"
+    <id> a onto r1
+    <id> b onto a
+    <id> a onto r2
+    <id> b onto a
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r=(r1 | r2):: ~ (r1 | r2)",
+        "-T",
+        &format!(r#"{template} ++ "\n""#),
+    ]);
+    insta::assert_snapshot!(output, @r"
+    b onto a
+    a onto r2
+    b onto a
+    a onto r1
+    [EOF]
+    ");
+
+    let output = work_dir
+        .run_jj(["duplicate", "a", "--onto-each=r1", "--mapping-json"])
+        .success();
+    insta::assert_snapshot!(output.normalize_stdout_with(normalize_ids).stdout, @r#"
+    [{"old":"<id>","new":"<id>","parents":["<id>"]}]
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["duplicate", "a", "--onto-each=none()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No revisions to duplicate onto
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"commit_id.short() ++ "   " ++ description.first_line()"#;
@@ -202,6 +202,10 @@ fn test_chmod_file_dir_deletion_conflicts() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion and an executable
+    Hint: file was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours file
+    To delete the file, run:
+      jj resolve --tool :theirs file
     New conflicts appeared in 1 commits:
       kmkuslsw dc89f9e7 file_deletion | (conflict) file_deletion
     Hint: To resolve the conflicts, start by creating a commit on top of
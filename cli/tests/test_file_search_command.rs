@@ -0,0 +1,114 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_file_search() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("README.md", "");
+    work_dir.write_file("cli/src/merge_tools/mod.rs", "");
+    work_dir.write_file("docs/merging.md", "");
+    work_dir.write_file("lib/src/merge.rs", "");
+    work_dir.write_file("lib/src/merged_tree.rs", "");
+
+    // Substring matches in the file name come first
+    let output = work_dir.run_jj(["file", "search", "--name", "MERGE"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    lib/src/merge.rs
+    lib/src/merged_tree.rs
+    cli/src/merge_tools/mod.rs
+    [EOF]
+    ");
+
+    // Fuzzy matches with equal scores are ordered by length
+    let output = work_dir.run_jj(["file", "search", "--name", "mrg"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    docs/merging.md
+    lib/src/merge.rs
+    lib/src/merged_tree.rs
+    cli/src/merge_tools/mod.rs
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "search", "--name", "mrg", "--limit=2"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    docs/merging.md
+    lib/src/merge.rs
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "search", "--name", "xyz"]);
+    insta::assert_snapshot!(output, @"");
+
+    // Paths are relative to the current directory
+    let output = work_dir
+        .dir("lib")
+        .run_jj(["file", "search", "--name", "tree"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    src/merged_tree.rs
+    [EOF]
+    ");
+
+    // The path list of the tree is cached
+    let cache_dir = work_dir.root().join(".jj/repo/file_search_cache");
+    assert_eq!(std::fs::read_dir(cache_dir).unwrap().count(), 1);
+}
+
+#[test]
+fn test_file_search_interactive() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("lib/src/merge.rs", "");
+    work_dir.write_file("lib/src/merged_tree.rs", "");
+
+    // The first path is picked in non-interactive sessions
+    let output = work_dir.run_jj(["file", "search", "--name", "merge", "-i"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    lib/src/merge.rs
+    [EOF]
+    ------- stderr -------
+      1: lib/src/merge.rs
+      2: lib/src/merged_tree.rs
+    Enter the number of the path: 1
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["file", "search", "--name", "merge", "-i"])
+            .env("JJ_INTERACTIVE", "1")
+            .write_stdin("2\n")
+    });
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    lib/src/merged_tree.rs
+    [EOF]
+    ------- stderr -------
+      1: lib/src/merge.rs
+      2: lib/src/merged_tree.rs
+    Enter the number of the path: [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "search", "--name", "xyz", "-i"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No paths matching `xyz` in the revision
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -128,3 +128,50 @@ fn test_show_symlink() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_show_archive() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\n");
+    work_dir.create_dir("dir");
+    work_dir.write_file("dir/file2", "c\n");
+
+    // Each file is preceded by its path and size
+    let output = work_dir.run_jj(["file", "show", "--null", "."]).success();
+    assert_eq!(
+        output.stdout.raw(),
+        "dir/file2\x002\x00c\nfile1\x002\x00a\n"
+    );
+
+    // Each file is stored as a header block and a data block, followed by two
+    // empty blocks
+    let output = work_dir.run_jj(["file", "show", "--tar", "."]).success();
+    let tar = output.stdout.raw().as_bytes();
+    assert_eq!(tar.len(), 512 * 6);
+    assert!(tar.starts_with(b"dir/file2\0"));
+    assert_eq!(&tar[257..263], b"ustar\0");
+    assert_eq!(&tar[512..515], b"c\n\0");
+    assert!(tar[1024..].starts_with(b"file1\0"));
+    assert_eq!(&tar[1536..1539], b"a\n\0");
+    assert!(tar[2048..].iter().all(|&b| b == 0));
+
+    let output = test_env
+        .new_jj_cmd()
+        .current_dir(work_dir.root())
+        .args(["file", "show", "--zip", "."])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let zip = output.stdout;
+    assert!(zip.starts_with(b"PK\x03\x04"));
+    let end_record = &zip[zip.len() - 22..];
+    assert!(end_record.starts_with(b"PK\x05\x06"));
+    assert_eq!(&end_record[8..10], &2u16.to_le_bytes());
+
+    // Archive modes are exclusive
+    let output = work_dir.run_jj(["file", "show", "--tar", "--zip", "."]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -72,7 +72,11 @@ fn test_track_untrack() {
     // Can untrack a single file
     assert!(files_before.stdout.raw().contains("file1.bak\n"));
     let output = work_dir.run_jj(["file", "untrack", "file1.bak"]);
-    insta::assert_snapshot!(output, @r"");
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Untracked 1 files
+    [EOF]
+    ");
     let files_after = work_dir.run_jj(["file", "list"]).success();
     // The file is no longer tracked
     assert!(!files_after.stdout.raw().contains("file1.bak"));
@@ -96,11 +100,78 @@ fn test_track_untrack() {
     // Can untrack after adding to ignore patterns
     work_dir.write_file(".gitignore", ".bak\ntarget/\n");
     let output = work_dir.run_jj(["file", "untrack", "target"]);
-    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Untracked 2 files
+    [EOF]
+    ");
     let files_after = work_dir.run_jj(["file", "list"]).success();
     assert!(!files_after.stdout.raw().contains("target"));
 }
 
+#[test]
+fn test_untrack_conflicted() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "base"]).success();
+    work_dir.write_file("file.bak", "base\n");
+    work_dir.write_file("dir/file.bak", "base\n");
+    work_dir.write_file("dir/file", "base\n");
+    work_dir.run_jj(["new", "-m", "left"]).success();
+    work_dir.write_file("file.bak", "left\n");
+    work_dir.write_file("dir/file.bak", "left\n");
+    work_dir
+        .run_jj(["new", "description(base)", "-m", "right"])
+        .success();
+    work_dir.write_file("file.bak", "right\n");
+    work_dir.write_file("dir/file.bak", "right\n");
+    work_dir
+        .run_jj([
+            "new",
+            "description(left)",
+            "description(right)",
+            "-m",
+            "merge",
+        ])
+        .success();
+    work_dir.write_file(".gitignore", "*.bak\n");
+
+    // All sides of the conflicted file are dropped
+    let output = work_dir.run_jj(["file", "untrack", "file.bak"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Untracked 1 files, including 1 conflicted files
+    [EOF]
+    ");
+    assert!(work_dir.root().join("file.bak").exists());
+
+    // Directories with both conflicted and resolved files can't be untracked
+    // partially
+    let output = work_dir.run_jj(["file", "untrack", "dir"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    ------- stderr -------
+    Error: 'dir/file' is not ignored.
+    Hint: Files that are not ignored will be added back by the next command.
+    Make sure they're ignored, then try again.
+    [EOF]
+    [exit status: 1]
+    ");
+    work_dir.write_file(".gitignore", "*.bak\ndir/\n");
+    let output = work_dir.run_jj(["file", "untrack", "dir"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Untracked 2 files, including 1 conflicted files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    .gitignore
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_track_untrack_sparse() {
     let test_env = TestEnvironment::default();
@@ -123,7 +194,11 @@ fn test_track_untrack_sparse() {
         .run_jj(["sparse", "set", "--clear", "--add", "file1"])
         .success();
     let output = work_dir.run_jj(["file", "untrack", "file2"]);
-    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Untracked 1 files
+    [EOF]
+    ");
     let output = work_dir.run_jj(["file", "list"]);
     insta::assert_snapshot!(output, @r"
     file1
@@ -170,7 +245,11 @@ fn test_auto_track() {
 
     // Can manually untrack paths
     let output = work_dir.run_jj(["file", "untrack", "file3.md"]);
-    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Untracked 1 files
+    [EOF]
+    ");
     let output = work_dir.run_jj(["file", "list"]);
     insta::assert_snapshot!(output, @r"
     file1.rs
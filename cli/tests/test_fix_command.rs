@@ -943,6 +943,49 @@ fn test_deduplication() {
     ");
 }
 
+#[test]
+fn test_fix_per_commit() {
+    // Tools with `per-commit = true` are run for each commit containing the
+    // file, including descendants in which the file is unchanged.
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    set_up_fake_formatter(
+        &test_env,
+        &["--stdout-env=JJ_CHANGE_ID", "--tee", "$path-fixlog"],
+    );
+    test_env.add_config("fix.tools.fake-formatter.per-commit = true");
+
+    work_dir.write_file("file", "foo\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a"])
+        .success();
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("other", "bar\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b"])
+        .success();
+    let change_id = |rev: &str| {
+        work_dir
+            .run_jj(["log", "--no-graph", "-r", rev, "-T", "change_id"])
+            .success()
+            .stdout
+            .into_raw()
+    };
+    let change_id_a = change_id("a");
+    let change_id_b = change_id("b");
+
+    work_dir.run_jj(["fix", "-s", "a"]).success();
+    let output = work_dir.run_jj(["file", "show", "file", "-r", "a"]);
+    assert_eq!(output.stdout.raw(), format!("{change_id_a}\n"));
+    let output = work_dir.run_jj(["file", "show", "file", "-r", "b"]);
+    assert_eq!(output.stdout.raw(), format!("{change_id_b}\n"));
+
+    // The unchanged file was fixed again in the child commit
+    let fixlog = std::fs::read_to_string(work_dir.root().join("file-fixlog")).unwrap();
+    assert_eq!(fixlog.lines().count(), 2);
+}
+
 fn sorted_lines(path: PathBuf) -> String {
     let mut log: Vec<_> = std::fs::read_to_string(path.as_os_str())
         .unwrap()
@@ -1482,3 +1525,118 @@ fn test_all_files() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_fix_tool_env_vars() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    set_up_fake_formatter(
+        &test_env,
+        &[
+            "--stdout-env=JJ_FILE_PATH",
+            "--stdout-env=JJ_COMMIT_ID",
+            "--stdout-env=JJ_CHANGE_ID",
+        ],
+    );
+    work_dir.write_file("dir/file", "content\n");
+    let ids = work_dir
+        .run_jj([
+            "log",
+            "--no-graph",
+            "-r@",
+            "-T",
+            r#"commit_id ++ "\n" ++ change_id ++ "\n""#,
+        ])
+        .success()
+        .stdout
+        .into_raw();
+
+    work_dir.run_jj(["fix", "-s", "@"]).success();
+    let output = work_dir
+        .run_jj(["file", "show", "dir/file", "-r", "@"])
+        .success()
+        .stdout
+        .into_raw();
+    assert_eq!(output, format!("dir/file\n{ids}"));
+}
+
+#[test]
+fn test_fix_json_output() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config("fix.tools.fake-formatter.output-format = 'json'");
+    let file_list_template = r#"separate(" ", path, "executable=" ++ executable) ++ "\n""#;
+
+    // Change the content and the executable bit
+    set_up_fake_formatter(
+        &test_env,
+        &[
+            "--stdout",
+            r#"{"content": "CONTENT\n", "executable": true}"#,
+        ],
+    );
+    work_dir.write_file("file", "content\n");
+    work_dir.run_jj(["fix", "-s", "@"]).success();
+    let output = work_dir.run_jj(["file", "show", "file", "-r", "@"]);
+    insta::assert_snapshot!(output, @r"
+    CONTENT
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "file",
+        "list",
+        "--ignore-working-copy",
+        "-T",
+        file_list_template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    file executable=true
+    [EOF]
+    ");
+
+    // Rename the file, keeping its content
+    work_dir.run_jj(["new"]).success();
+    set_up_fake_formatter(&test_env, &["--stdout", r#"{"path": "dir/renamed"}"#]);
+    work_dir.write_file("file", "new content\n");
+    work_dir.run_jj(["fix", "-s", "@"]).success();
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    dir/renamed
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "dir/renamed", "-r", "@"]);
+    insta::assert_snapshot!(output, @r"
+    new content
+    [EOF]
+    ");
+
+    // Delete the file
+    work_dir.run_jj(["new"]).success();
+    set_up_fake_formatter(&test_env, &["--stdout", r#"{"delete": true}"#]);
+    work_dir.write_file("dir/renamed", "newer content\n");
+    work_dir.run_jj(["fix", "-s", "@"]).success();
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @"");
+
+    // Binary content
+    work_dir.run_jj(["new"]).success();
+    set_up_fake_formatter(&test_env, &["--stdout", r#"{"content": [0, 255, 10]}"#]);
+    work_dir.write_file("file", "content\n");
+    work_dir.run_jj(["fix", "-s", "@"]).success();
+    assert_eq!(work_dir.read_file("file"), b"\0\xff\n".as_slice());
+
+    // Invalid output is ignored
+    work_dir.run_jj(["new"]).success();
+    set_up_fake_formatter(&test_env, &["--stdout", "content"]);
+    work_dir.write_file("file", "content\n");
+    let output = work_dir.run_jj(["fix", "-s", "@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Ignoring invalid output of fix tool for file: expected value at line 1 column 1
+    Fixed 0 commits of 1 checked.
+    Nothing changed.
+    [EOF]
+    ");
+}
@@ -1105,6 +1105,41 @@ fn test_git_fetch_bookmarks_some_missing() {
     ");
 }
 
+#[test]
+fn test_git_fetch_bookmarks_from_config() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let rem1_repo = add_git_remote(&test_env, &work_dir, "rem1");
+    add_commit_to_branch(&rem1_repo, "extra");
+    add_git_remote(&test_env, &work_dir, "rem2");
+    test_env.add_config(
+        r#"
+        [remotes.rem1]
+        fetch-bookmarks = ["extra", "missing"]
+        "#,
+    );
+
+    // Only the configured bookmarks are fetched from rem1
+    let output = work_dir.run_jj(["git", "fetch", "--all-remotes"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: extra@rem1 [new] tracked
+    bookmark: rem2@rem2  [new] tracked
+    Warning: No branch matching `missing` found on any specified/configured remote
+    [EOF]
+    ");
+
+    // Command arguments take precedence over the config
+    let output = work_dir.run_jj(["git", "fetch", "--remote=rem1", "--branch=rem1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: rem1@rem1 [new] tracked
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_fetch_bookmarks_missing_with_subprocess_localized_message() {
     let test_env = TestEnvironment::default();
@@ -1755,3 +1790,77 @@ fn test_git_fetch_preserve_commits_across_repos() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_git_fetch_auto_track_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = add_git_remote(&test_env, &work_dir, "origin");
+    add_commit_to_branch(&git_repo, "feature1");
+    add_commit_to_branch(&git_repo, "feature2");
+    let template = r#"name ++ if(remote, "@" ++ remote) ++ if(tracked, " (tracked)") ++ "\n""#;
+
+    // Only bookmarks matching the pattern of the remote are tracked
+    test_env.add_config(r#"remotes.origin.auto-track-bookmarks = "glob:feature1""#);
+    test_env.add_config(r#"remotes.upstream.auto-track-bookmarks = "glob:*""#);
+    work_dir.run_jj(["git", "fetch"]).success();
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    feature1
+    feature1@origin (tracked)
+    feature2@origin
+    origin@origin
+    [EOF]
+    ");
+
+    // Existing remote bookmarks can be tracked by updated patterns
+    let output = work_dir.run_jj([
+        "bookmark",
+        "track",
+        "--auto-from-config",
+        "--config=remotes.origin.auto-track-bookmarks='glob:feature*'",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Started tracking 1 remote bookmarks.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    feature1
+    feature1@origin (tracked)
+    feature2
+    feature2@origin (tracked)
+    origin@origin
+    [EOF]
+    ");
+
+    // git.auto-local-bookmark only applies to remotes without a pattern
+    add_git_remote(&test_env, &work_dir, "other");
+    work_dir
+        .run_jj(["git", "fetch", "--remote=other"])
+        .success();
+    let output = work_dir.run_jj([
+        "bookmark",
+        "track",
+        "--auto-from-config",
+        "--config=git.auto-local-bookmark=true",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Started tracking 1 remote bookmarks.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    feature1
+    feature1@origin (tracked)
+    feature2
+    feature2@origin (tracked)
+    origin@origin
+    other
+    other@other (tracked)
+    [EOF]
+    ");
+}
@@ -116,6 +116,120 @@ fn test_git_init_internal_at_operation() {
     ");
 }
 
+#[test]
+fn test_git_init_template() {
+    let test_env = TestEnvironment::default();
+    let template_dir = test_env.work_dir("").create_dir("template");
+    template_dir.write_file(
+        "config.toml",
+        r#"revset-aliases."mine()" = "author('someone')""#,
+    );
+    template_dir.write_file("sparse-patterns", "# only sources\nsrc\n");
+    template_dir.write_file("files/src/lib.rs", "// lib\n");
+
+    let output = test_env.run_jj_in(".", ["git", "init", "repo", "--template=template"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Initialized repo in "repo"
+    [EOF]
+    "#);
+
+    let work_dir = test_env.work_dir("repo");
+    let output = work_dir.run_jj(["config", "get", r#"revset-aliases."mine()""#]);
+    insta::assert_snapshot!(output, @r"
+    author('someone')
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["config", "get", "repo-template.source"]);
+    insta::assert_snapshot!(output, @r"
+    $TEST_ENV/template
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    src
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    src/lib.rs
+    [EOF]
+    ");
+
+    // Only local templates are supported
+    let output = test_env.run_jj_in(
+        ".",
+        [
+            "git",
+            "init",
+            "repo2",
+            "--template=https://example.com/template",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Template `https://example.com/template` isn't a local path; only local templates are supported
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_colocate_internal() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "contents");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+
+    let output = work_dir.run_jj(["git", "colocate"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved the Git repo to ".git"
+    [EOF]
+    "#);
+    assert!(work_dir.root().join(".git").is_dir());
+    assert!(!work_dir.root().join(".jj/repo/store/git").exists());
+    assert_eq!(read_git_target(&work_dir), "../../../.git");
+
+    // The bookmark is exported, and Git HEAD points to the working-copy parent
+    let template = r#"separate(" ", bookmarks, if(git_head, "git_head()"), description)"#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@-", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    main git_head() first
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "colocate"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The repo is already colocated with Git
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_colocate_existing_dot_git() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.create_dir(".git");
+
+    let output = work_dir.run_jj(["git", "colocate"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot colocate the repo because `.git` already exists in the workspace root
+    [EOF]
+    [exit status: 1]
+    ");
+    assert!(work_dir.root().join(".jj/repo/store/git").is_dir());
+    assert_eq!(read_git_target(&work_dir), "git");
+}
+
 #[test_case(false; "full")]
 #[test_case(true; "bare")]
 fn test_git_init_external(bare: bool) {
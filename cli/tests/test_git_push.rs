@@ -83,6 +83,42 @@ fn test_git_push_nothing() {
     ");
 }
 
+#[test]
+fn test_git_push_verbose() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    work_dir.run_jj(["new", "bookmark2"]).success();
+    work_dir.write_file("file", "contents");
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--verbose"]);
+    let stderr = output.stderr.normalized();
+    assert!(stderr.contains("Push negotiation:"), "{stderr}");
+    assert!(stderr.contains("  Objects: "), "{stderr}");
+    assert!(
+        stderr.contains("  refs/heads/bookmark2: 38a2047.."),
+        "{stderr}"
+    );
+
+    // Negotiation details aren't shown by default
+    work_dir.write_file("file", "new contents");
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    let output = work_dir.run_jj(["git", "push"]);
+    assert!(!output.stderr.normalized().contains("Push negotiation:"));
+
+    // Nor with --quiet
+    work_dir.write_file("file", "newer contents");
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--verbose", "--quiet"]);
+    assert!(!output.stderr.normalized().contains("Push negotiation:"));
+}
+
 #[test]
 fn test_git_push_current_bookmark() {
     let test_env = TestEnvironment::default();
@@ -1761,7 +1797,12 @@ fn test_git_push_conflicting_bookmarks() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Warning: Bookmark bookmark2 is conflicted
-    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    Hint: Bookmark bookmark2 points to:
+      yostqsxw ebedbe63 bookmark2?? | (empty) description 3
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    and bookmark2@origin points to:
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up. Use --resolve-with=local or --resolve-with=remote to resolve it while pushing.
     Nothing changed.
     [EOF]
     ");
@@ -1771,7 +1812,12 @@ fn test_git_push_conflicting_bookmarks() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Error: Bookmark bookmark2 is conflicted
-    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    Hint: Bookmark bookmark2 points to:
+      yostqsxw ebedbe63 bookmark2?? | (empty) description 3
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    and bookmark2@origin points to:
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up. Use --resolve-with=local or --resolve-with=remote to resolve it while pushing.
     [EOF]
     [exit status: 1]
     ");
@@ -1782,7 +1828,12 @@ fn test_git_push_conflicting_bookmarks() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Warning: Bookmark bookmark2 is conflicted
-    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    Hint: Bookmark bookmark2 points to:
+      yostqsxw ebedbe63 bookmark2?? | (empty) description 3
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    and bookmark2@origin points to:
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up. Use --resolve-with=local or --resolve-with=remote to resolve it while pushing.
     Changes to push to origin:
       Move forward bookmark bookmark1 from 9b2e76de3920 to 749c2e6d999f
     [EOF]
@@ -1794,11 +1845,48 @@ fn test_git_push_conflicting_bookmarks() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Warning: Bookmark bookmark2 is conflicted
-    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    Hint: Bookmark bookmark2 points to:
+      yostqsxw ebedbe63 bookmark2?? | (empty) description 3
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    and bookmark2@origin points to:
+      zsuskuln 38a20473 bookmark2?? bookmark2@origin | (empty) description 2
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up. Use --resolve-with=local or --resolve-with=remote to resolve it while pushing.
     Changes to push to origin:
       Move forward bookmark bookmark1 from 749c2e6d999f to 9bb0f427b517
     [EOF]
     ");
+
+    // --resolve-with=remote resets the bookmark to the remote target
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--bookmark=bookmark2",
+        "--resolve-with=remote",
+        "--dry-run",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Resolved conflicted bookmark bookmark2 to zsuskuln 38a20473 bookmark2 | (empty) description 2
+    Bookmark bookmark2@origin already matches bookmark2
+    Nothing changed.
+    [EOF]
+    ");
+
+    // --resolve-with=local takes the target which isn't on the remote
+    work_dir
+        .run_jj([
+            "git",
+            "push",
+            "--bookmark=bookmark2",
+            "--resolve-with=local",
+        ])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes", "bookmark2"]);
+    insta::assert_snapshot!(output, @r"
+    bookmark2: yostqsxw ebedbe63 (empty) description 3
+      @origin: yostqsxw ebedbe63 (empty) description 3
+    [EOF]
+    ");
 }
 
 #[test]
@@ -2249,3 +2337,54 @@ fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
     work_dir.run_jj(["bookmark", "list", "--all-remotes", "--quiet"])
 }
+
+#[test]
+fn test_git_outgoing() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+
+    let output = work_dir.run_jj(["git", "outgoing"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No outgoing changes to origin.
+    [EOF]
+    ");
+
+    work_dir.run_jj(["new", "bookmark1", "-m=foo"]).success();
+    work_dir.run_jj(["new", "-m=bar"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark1", "-r@"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "delete", "bookmark2"])
+        .success();
+    let template = "description.first_line()";
+    let output = work_dir.run_jj(["git", "outgoing", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    bookmark1@origin
+      bar
+      foo
+    bookmark2@origin
+      Delete bookmark from 38a20473
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "outgoing", "-T", template, "-b=bookmark1", "--stat"]);
+    insta::assert_snapshot!(output, @r"
+    bookmark1@origin
+      bar
+    0 files changed, 0 insertions(+), 0 deletions(-)
+      foo
+    0 files changed, 0 insertions(+), 0 deletions(-)
+    [EOF]
+    ");
+
+    // Nothing is pushed to other remotes
+    let output = work_dir.run_jj(["git", "outgoing", "--remote=other"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No outgoing changes to other.
+    [EOF]
+    ");
+}
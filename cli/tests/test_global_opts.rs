@@ -64,6 +64,28 @@ fn test_version() {
     );
 }
 
+#[test]
+fn test_version_verbose() {
+    let test_env = TestEnvironment::default();
+
+    // The features and backends depend on how jj is built
+    let output = test_env
+        .run_jj_in(".", ["version", "--verbose"])
+        .success()
+        .normalize_stdout_with(|s| {
+            let regex = Regex::new(r"(?m)^(jj|Features:|Backends:|Fsmonitors:) .*$").unwrap();
+            regex.replace_all(&s, "$1 <redacted>").into_owned()
+        });
+    insta::assert_snapshot!(output, @r"
+    jj <redacted>
+    Features: <redacted>
+    Backends: <redacted>
+    Working copies: local
+    Fsmonitors: <redacted>
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_no_subcommand() {
     let test_env = TestEnvironment::default();
@@ -626,6 +648,30 @@ fn test_quiet() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_error_format_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["--error-format=json", "log", "-r=foo"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    {"kind":"user","message":"Revision `foo` doesn't exist","sources":[],"hints":[]}
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // The format can also be set by config
+    let output = work_dir.run_jj(["--config=ui.error-format=json", "log", "-r=foo"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    {"kind":"user","message":"Revision `foo` doesn't exist","sources":[],"hints":[]}
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
 #[test]
 fn test_early_args() {
     // Test that help output parses early args
@@ -1084,6 +1130,7 @@ fn test_help() {
                                          auto]
           --quiet                        Silence non-primary command output
           --no-pager                     Disable the pager
+          --error-format <FORMAT>        Format of error messages [possible values: text, json]
           --config <NAME=VALUE>          Additional configuration options (can be repeated)
           --config-file <PATH>           Additional configuration files (can be repeated)
     [EOF]
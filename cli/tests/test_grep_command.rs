@@ -0,0 +1,93 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_grep() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\nbar\nFoo baz\n");
+    work_dir.write_file("file2", "qux foo\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.write_file("file1", "bar\n");
+    work_dir.run_jj(["describe", "-m", "second"]).success();
+
+    // Searches the working copy by default
+    let output = work_dir.run_jj(["grep", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    file2:1:qux foo
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["grep", "foo", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    file1:1:foo
+    file2:1:qux foo
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["grep", "-i", "foo", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    file1:1:foo
+    file1:3:Foo baz
+    file2:1:qux foo
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["grep", "-l", "foo", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    file1
+    file2
+    [EOF]
+    ");
+
+    // Restricted to a fileset
+    let output = work_dir.run_jj(["grep", "foo", "-r@-", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    file2:1:qux foo
+    [EOF]
+    ");
+
+    // Matches are grouped by revision
+    let output = work_dir.run_jj(["grep", "bar", "-r@", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    second
+    file1:1:bar
+
+    first
+    file1:2:bar
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["grep", "nonexistent", "-r@", "-r@-"]);
+    insta::assert_snapshot!(output, @"");
+}
+
+#[test]
+fn test_grep_binary() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", b"foo\0bar\n");
+    let output = work_dir.run_jj(["grep", "bar"]);
+    insta::assert_snapshot!(output, @r"
+    Binary file file matches
+    [EOF]
+    ");
+}
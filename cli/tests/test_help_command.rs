@@ -170,3 +170,57 @@ fn test_help_keyword() {
     [exit status: 2]
     ");
 }
+
+#[test]
+fn test_help_reference() {
+    let test_env = TestEnvironment::default();
+
+    let output = test_env.run_jj_in(".", ["help", "--reference", "filesets"]);
+    insta::assert_snapshot!(output, @r"
+    Functions:
+      all()
+      none()
+    [EOF]
+    ------- stderr -------
+    Hint: Use `jj help -k filesets` for the full documentation.
+    [EOF]
+    ");
+
+    // Method names are grouped by type
+    let output = test_env
+        .run_jj_in(".", ["help", "--reference", "templates"])
+        .success();
+    assert!(output
+        .stdout
+        .raw()
+        .contains("\nCommit methods:\n  .author()\n"));
+    assert!(output.stdout.raw().contains("\nOperation methods:\n"));
+
+    let output = test_env.run_jj_in(".", ["help", "--web", "--reference", "filesets"]);
+    insta::assert_snapshot!(output, @r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+    <meta charset="utf-8">
+    <title>jj language reference</title>
+    </head>
+    <body>
+    <h1>jj language reference</h1>
+    <h2>Filesets</h2>
+    <h3>Functions</h3>
+    <ul>
+    <li><code>all()</code></li>
+    <li><code>none()</code></li>
+    </ul>
+    </body>
+    </html>
+    [EOF]
+    "#);
+
+    // Type names are escaped
+    let output = test_env.run_jj_in(".", ["help", "--web"]).success();
+    assert!(output
+        .stdout
+        .raw()
+        .contains("<h3>List&lt;Commit&gt; methods</h3>"));
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools as _;
+
 use crate::common::to_toml_value;
 use crate::common::TestEnvironment;
 
@@ -345,6 +347,62 @@ fn test_log_with_or_without_diff() {
     ");
 }
 
+#[test]
+fn test_log_patch_merge_modes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "left"]).success();
+    work_dir.write_file("left", "left\n");
+    work_dir.write_file("conflict", "l\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "left"])
+        .success();
+    work_dir.run_jj(["new", "root()", "-m", "right"]).success();
+    work_dir.write_file("right", "right\n");
+    work_dir.write_file("conflict", "r\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "right"])
+        .success();
+    work_dir
+        .run_jj(["new", "left", "right", "-m", "merge"])
+        .success();
+    work_dir.write_file("merged", "merged\n");
+    work_dir.write_file("conflict", "l\n");
+
+    let template = "description";
+    let log_merge = |args: &[&str]| {
+        let mut full_args = vec!["log", "-r@", "--no-graph", "--summary", "-T", template];
+        full_args.extend_from_slice(args);
+        work_dir.run_jj(full_args)
+    };
+
+    // The conflict resolution is shown against the auto-merged parents
+    insta::assert_snapshot!(log_merge(&[]), @r"
+    merge
+    M conflict
+    A merged
+    [EOF]
+    ");
+    insta::assert_snapshot!(log_merge(&["--first-parent"]), @r"
+    merge
+    A merged
+    A right
+    [EOF]
+    ");
+    // Files taken verbatim from one of the parents are omitted
+    insta::assert_snapshot!(log_merge(&["--merges=combined"]), @r"
+    merge
+    A merged
+    [EOF]
+    ");
+    insta::assert_snapshot!(log_merge(&["--merges=off"]), @r"
+    merge
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_null_terminate_multiline_descriptions() {
     let test_env = TestEnvironment::default();
@@ -743,6 +801,64 @@ fn test_log_prefix_highlight_counts_hidden_commits() {
     ");
 }
 
+#[test]
+fn test_log_shortest_within() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config(
+        r#"
+        [revsets]
+        short-prefixes = "" # Disable short prefixes
+        [template-aliases]
+        'format_id(id)' = 'id.shortest(12).prefix() ++ "[" ++ id.shortest(12).rest() ++ "]"'
+        'format_id_within(id)' = """
+          id.shortest_within("all()", 12).prefix()
+          ++ "[" ++ id.shortest_within("all()", 12).rest() ++ "]"
+        """
+        "#,
+    );
+
+    let prefix_format = r#"
+    separate(" ",
+      "Change",
+      format_id(change_id),
+      format_id_within(change_id),
+      format_id(commit_id),
+      format_id_within(commit_id),
+    )
+    "#;
+
+    work_dir.write_file("file", "original file\n");
+    work_dir.run_jj(["describe", "-m", "initial"]).success();
+    work_dir
+        .run_jj(["bookmark", "c", "-r@", "original"])
+        .success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-r", "all()", "-T", prefix_format]), @r"
+    @  Change q[pvuntsmwlqt] q[pvuntsmwlqt] 8[216f646c36d] 8[216f646c36d]
+    ◆  Change z[zzzzzzzzzzz] z[zzzzzzzzzzz] 00[0000000000] 0[00000000000]
+    [EOF]
+    ");
+
+    // Create 2^7 hidden commits
+    work_dir.run_jj(["new", "root()", "-m", "extra"]).success();
+    for _ in 0..7 {
+        work_dir
+            .run_jj(["duplicate", "description(extra)"])
+            .success();
+    }
+    work_dir.run_jj(["abandon", "description(extra)"]).success();
+
+    // Hidden commits don't make the prefixes within the visible set longer.
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", prefix_format]), @r"
+    @  Change wq[nwkozpkust] w[qnwkozpkust] 88[e8407a4f0a] 88[e8407a4f0a]
+    │ ○  Change qpv[untsmwlqt] q[pvuntsmwlqt] 82[16f646c36d] 82[16f646c36d]
+    ├─╯
+    ◆  Change zzz[zzzzzzzzz] z[zzzzzzzzzzz] 00[0000000000] 0[00000000000]
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_author_format() {
     let test_env = TestEnvironment::default();
@@ -770,6 +886,47 @@ fn test_log_author_format() {
     ");
 }
 
+#[test]
+fn test_log_mailmap() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file(
+        ".mailmap",
+        "Proper Name <proper@example.com> <Test.User@example.com>\n",
+    );
+
+    let template = r#"author ++ " / " ++ committer ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=@", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    Proper Name <proper@example.com> / Proper Name <proper@example.com>
+    [EOF]
+    ");
+
+    // Revsets match the canonical identity
+    let revset = "author(exact:'Proper Name')";
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", revset, "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    Proper Name <proper@example.com> / Proper Name <proper@example.com>
+    [EOF]
+    ");
+    let revset = "author_email(exact:'test.user@example.com')";
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", revset]);
+    insta::assert_snapshot!(output, @"");
+
+    let output = work_dir.run_jj([
+        "debug",
+        "mailmap",
+        "Anyone <TEST.user@example.com>",
+        "Other <other@example.com>",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    Proper Name <proper@example.com>
+    Other <other@example.com>
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_divergence() {
     let test_env = TestEnvironment::default();
@@ -831,6 +988,158 @@ fn test_log_reversed() {
     ");
 }
 
+#[test]
+fn test_log_hide_empty() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    work_dir.write_file("file2", "bar\n");
+    work_dir.run_jj(["commit", "-m", "third"]).success();
+    work_dir.run_jj(["describe", "-m", "fourth"]).success();
+
+    // The working-copy commit is kept even if it's empty
+    let output = work_dir.run_jj(["log", "-T", "description", "--hide-empty"]);
+    insta::assert_snapshot!(output, @r"
+    @  fourth
+    ○  third
+    │
+    ~  (elided revisions)
+    ○  first
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "-T", "description", "--hide-empty", "--no-graph"]);
+    insta::assert_snapshot!(output, @r"
+    fourth
+    third
+    first
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a"])
+        .success();
+    work_dir.run_jj(["new", "root()", "-m", "b"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b"])
+        .success();
+    work_dir.run_jj(["new", "a", "b", "-m", "merge"]).success();
+
+    let render = |output: &str| {
+        output
+            .lines()
+            .map(|line| {
+                let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+                assert!(entry["column"].as_u64().unwrap() < entry["width"].as_u64().unwrap());
+                let edges = entry["edges"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|edge| edge["type"].as_str().unwrap())
+                    .join(", ");
+                format!(
+                    "{:?} column={} edges=[{edges}]\n",
+                    entry["commit"]["description"].as_str().unwrap(),
+                    entry["column"],
+                )
+            })
+            .collect::<String>()
+    };
+
+    let output = work_dir.run_jj(["log", "--json", "-r", "::@"]).success();
+    insta::assert_snapshot!(render(output.stdout.raw()), @r#"
+    "merge\n" column=0 edges=[direct, direct]
+    "b\n" column=1 edges=[direct]
+    "a\n" column=0 edges=[direct]
+    "" column=0 edges=[]
+    "#);
+
+    // Edges to parents outside the set are missing
+    let output = work_dir.run_jj(["log", "--json", "-r", "@ | a"]).success();
+    insta::assert_snapshot!(render(output.stdout.raw()), @r#"
+    "merge\n" column=0 edges=[direct, missing]
+    "a\n" column=0 edges=[missing]
+    "#);
+
+    let output = work_dir.run_jj(["log", "--json", "--no-graph"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--json' cannot be used with '--no-graph'
+
+    Usage: jj log --json [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_log_compact_graph() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a"])
+        .success();
+    work_dir.run_jj(["new", "root()", "-m", "b"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b"])
+        .success();
+    work_dir.run_jj(["new", "root()", "-m", "c"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "c"])
+        .success();
+    work_dir
+        .run_jj(["new", "a", "b", "c", "-m", "merge"])
+        .success();
+
+    let template = r#"if(root, "root", description)"#;
+    let output = work_dir
+        .run_jj(["log", "--compact-graph", "-r", "::@", "-T", template])
+        .success();
+    insta::assert_snapshot!(output, @r"
+    @      merge
+    ├─┬─╮
+    │ │ ○  c
+    │ ○ │  b
+    │ ├─╯
+    ○ │  a
+    ├─╯
+    ◆  root
+    [EOF]
+    ");
+
+    // Missing edges are terminated in the leftmost free column
+    let output = work_dir
+        .run_jj(["log", "--compact-graph", "-r", "@ | a", "-T", template])
+        .success();
+    insta::assert_snapshot!(output, @r"
+    @    merge
+    ├─╮
+    │ ~
+    ○  a
+    ~
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_filtered_by_path() {
     let test_env = TestEnvironment::default();
@@ -927,6 +1236,101 @@ fn test_log_filtered_by_path() {
     ");
 }
 
+#[test]
+fn test_log_follow() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1\n2\n3\n");
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.run_jj(["new", "-m", "unrelated"]).success();
+    work_dir.write_file("other", "foo\n");
+    work_dir.run_jj(["new", "-m", "rename"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "1\n2\n3\n");
+    work_dir.run_jj(["new", "-m", "modify"]).success();
+    work_dir.write_file("file2", "1\n2\n3\n4\n");
+
+    // Without --follow, the history stops at the rename
+    let output = work_dir.run_jj(["log", "-T", "description", "-s", "--no-graph", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    modify
+    M file2
+    rename
+    R {file1 => file2}
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        "description",
+        "-s",
+        "--no-graph",
+        "--follow",
+        "file2",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    modify
+    M file2
+    rename
+    R {file1 => file2}
+    first
+    A file1
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--follow", "file1", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --follow requires exactly one path
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_log_follow_branches() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // A file at the new path on an unrelated branch is still part of the history
+    work_dir
+        .run_jj(["new", "root()", "-m", "unrelated"])
+        .success();
+    work_dir.write_file("file2", "foo\n");
+    work_dir.run_jj(["new", "root()", "-m", "first"]).success();
+    work_dir.write_file("file1", "1\n2\n3\n");
+    work_dir.run_jj(["new", "-m", "rename"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "1\n2\n3\n");
+    work_dir.run_jj(["new", "-m", "modify"]).success();
+    work_dir.write_file("file2", "1\n2\n3\n4\n");
+
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        "description",
+        "-s",
+        "--no-graph",
+        "--follow",
+        "file2",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    modify
+    M file2
+    rename
+    R {file1 => file2}
+    first
+    A file1
+    unrelated
+    A file2
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_limit() {
     let test_env = TestEnvironment::default();
@@ -732,6 +732,47 @@ fn test_new_with_trailers() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_new_detached() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "A"]).success();
+    work_dir.write_file("file", "modified\n");
+
+    // The new commit is created on top of the last snapshot of @, and the
+    // working copy isn't snapshotted
+    work_dir.run_jj(["new", "--detached", "-m", "B"]).success();
+    let template = r#"if(description, description, "root")"#;
+    let output = work_dir.run_jj(["log", "--ignore-working-copy", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    ○  B
+    @  A
+    ◆  root
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "--ignore-working-copy", "-r", "@+"]);
+    insta::assert_snapshot!(output, @"");
+
+    // The working-copy commit can't be rewritten
+    let output = work_dir.run_jj(["new", "--detached", "--insert-before", "@", "-m", "C"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The working-copy commit would be rewritten, but the working copy is detached.
+    Hint: Run the command without --detached to update the working copy.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The modified file is still picked up by the next snapshot
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    file
+    [EOF]
+    ");
+}
+
 fn setup_before_insertion(work_dir: &TestWorkDir) {
     work_dir
         .run_jj(["bookmark", "create", "-r@", "A"])
@@ -1008,6 +1008,7 @@ fn test_movement_edit_mode_true() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Error: The root commit 000000000000 is immutable
+    Hint: Use `jj prev --no-edit` to create a new commit on top of it instead.
     [EOF]
     [exit status: 1]
     ");
@@ -1287,6 +1288,53 @@ fn test_next_offset_when_wc_has_descendants() {
     ");
 }
 
+#[test]
+fn test_prev_next_skip_empty() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    work_dir.run_jj(["commit", "-m", "third"]).success();
+    work_dir.write_file("file2", "2");
+    work_dir.run_jj(["commit", "-m", "fourth"]).success();
+    let get_description = |revision: &str| {
+        work_dir.run_jj(["log", "--no-graph", "-r", revision, "-T", "description"])
+    };
+
+    // The empty "third" and "second" are skipped.
+    work_dir.run_jj(["prev", "--skip-empty"]).success();
+    insta::assert_snapshot!(get_description("@-"), @r"
+    first
+    [EOF]
+    ");
+
+    work_dir.run_jj(["next", "--skip-empty"]).success();
+    insta::assert_snapshot!(get_description("@-"), @r"
+    fourth
+    [EOF]
+    ");
+
+    // In edit mode, the non-empty parent is the target.
+    work_dir
+        .run_jj(["prev", "--edit", "--skip-empty"])
+        .success();
+    insta::assert_snapshot!(get_description("@"), @r"
+    fourth
+    [EOF]
+    ");
+
+    work_dir
+        .run_jj(["prev", "--edit", "--skip-empty"])
+        .success();
+    insta::assert_snapshot!(get_description("@"), @r"
+    first
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"separate(" ", change_id.short(), local_bookmarks, if(conflict, "conflict"), description)"#;
@@ -370,6 +370,122 @@ fn test_op_log_no_graph_null_terminated() {
     insta::assert_debug_snapshot!(output.stdout.normalized(), @r#""a9e5\00265\08f47\00000\0""#);
 }
 
+#[test]
+fn test_op_log_ops_affecting() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "a");
+    work_dir.run_jj(["status"]).success();
+    work_dir.write_file("file2", "b");
+    work_dir.run_jj(["commit", "-m", "c"]).success();
+    work_dir.run_jj(["new", "zzzzzzzzzzzz"]).success();
+
+    let template = r#"description.first_line() ++ ": " ++ tags ++ "\n""#;
+    let run_op_log = |args: &[&str]| {
+        work_dir.run_jj([["op", "log", "--no-graph", "-T", template].as_slice(), args].concat())
+    };
+    let output = run_op_log(&["--ops-affecting=file1"]);
+    insta::assert_snapshot!(output, @r"
+    new empty commit: args: jj new zzzzzzzzzzzz
+    snapshot working copy: args: jj status
+    [EOF]
+    ");
+    let output = run_op_log(&["--ops-affecting=file2"]);
+    insta::assert_snapshot!(output, @r"
+    new empty commit: args: jj new zzzzzzzzzzzz
+    snapshot working copy: args: jj commit -m c
+    [EOF]
+    ");
+
+    // --limit applies to the filtered operations
+    let output = run_op_log(&["--ops-affecting=file2", "--limit=1"]);
+    insta::assert_snapshot!(output, @r"
+    new empty commit: args: jj new zzzzzzzzzzzz
+    [EOF]
+    ");
+
+    // No operation touched this path
+    let output = run_op_log(&["--ops-affecting=file3"]);
+    insta::assert_snapshot!(output, @"");
+}
+
+#[test]
+fn test_op_log_snapshot_ops() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "a");
+    work_dir.run_jj(["status"]).success();
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir.write_file("file2", "b");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b"])
+        .success();
+
+    let template = r#"separate(" ", if(snapshot, "[snapshot]"), if(tags, tags, description.first_line())) ++ "\n""#;
+    let run_op_log = |args: &[&str]| {
+        work_dir.run_jj([["op", "log", "--no-graph", "-T", template].as_slice(), args].concat())
+    };
+    let output = run_op_log(&["--no-snapshot-ops", "--limit=3"]);
+    insta::assert_snapshot!(output, @r"
+    args: jj bookmark create -r@ b
+    args: jj describe -m a
+    add workspace 'default'
+    [EOF]
+    ");
+    let output = run_op_log(&["--only-snapshots"]);
+    insta::assert_snapshot!(output, @r"
+    [snapshot] args: jj bookmark create -r@ b
+    [snapshot] args: jj status
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_op_log_between() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir.run_jj(["describe", "-m", "b"]).success();
+    work_dir.run_jj(["describe", "-m", "c"]).success();
+
+    let template = r#"if(tags, tags, description.first_line()) ++ "\n""#;
+    let run_op_log = |args: &[&str]| {
+        work_dir.run_jj([["op", "log", "--no-graph", "-T", template].as_slice(), args].concat())
+    };
+    let output = run_op_log(&["--between=@---..@-"]);
+    insta::assert_snapshot!(output, @r"
+    args: jj describe -m b
+    args: jj describe -m a
+    [EOF]
+    ");
+
+    // Omitted root defaults to the root operation
+    let output = run_op_log(&["--between=..@---", "--limit=1"]);
+    insta::assert_snapshot!(output, @r"
+    add workspace 'default'
+    [EOF]
+    ");
+
+    // Omitted head defaults to the current operation
+    let output = run_op_log(&["--between=@-.."]);
+    insta::assert_snapshot!(output, @r"
+    args: jj describe -m c
+    [EOF]
+    ");
+
+    // Not a range
+    let output = run_op_log(&["--between=@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Invalid operation range `@-`; expected `ROOT..HEAD`
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_log_template() {
     let test_env = TestEnvironment::default();
@@ -423,6 +539,35 @@ fn test_op_log_template() {
     ");
 }
 
+#[test]
+fn test_op_log_change_counts() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "");
+    work_dir.write_file("file2", "");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+
+    let template = r#"
+        separate(" ",
+          "bookmarks:" ++ self.changed_bookmarks_count(),
+          "heads:+" ++ self.added_heads_count() ++ "-" ++ self.removed_heads_count(),
+          "paths:" ++ self.changed_paths_count(),
+          "capped:" ++ self.changed_paths_count(1),
+        ) ++ "\n"
+    "#;
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    bookmarks:1 heads:+0-0 paths:0 capped:0
+    bookmarks:0 heads:+1-1 paths:2 capped:1
+    bookmarks:0 heads:+1-1 paths:0 capped:0
+    bookmarks:0 heads:+0-0 paths:0 capped:0
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_op_log_builtin_templates() {
     let test_env = TestEnvironment::default();
@@ -764,6 +909,67 @@ fn test_op_abandon_ancestors() {
     ");
 }
 
+#[test]
+fn test_op_squash() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.run_jj(["commit", "-m", "commit 2"]).success();
+    work_dir.run_jj(["commit", "-m", "commit 3"]).success();
+
+    let output = work_dir.run_jj(["op", "squash", "@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Expected an operation range like `X..Y`, got `@-`
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Squash operations in the middle. The descendant should be reparented.
+    let output = work_dir.run_jj(["op", "squash", "@---..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Squashed 2 operations and reparented 1 descendant operations.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "--no-graph", r#"-Tdescription ++ "\n""#]);
+    insta::assert_snapshot!(output, @r"
+    commit 4b087e94a5d14530c3953d617623d075a13294c8
+    commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    commit 4e0592f3dd52e7a4998a97d9a1f354e2727a856b
+    add workspace 'default'
+
+    [EOF]
+    ");
+
+    // Squash operations including the current operation. The working-copy
+    // operation id should be updated.
+    let output = work_dir.run_jj(["op", "squash", "@--..@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Squashed 2 operations and reparented 0 descendant operations.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "--no-graph", r#"-Tdescription ++ "\n""#]);
+    insta::assert_snapshot!(output, @r"
+    commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    commit 4e0592f3dd52e7a4998a97d9a1f354e2727a856b
+    commit 4b087e94a5d14530c3953d617623d075a13294c8
+    add workspace 'default'
+
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    commit 3
+    commit 2
+    commit 1
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_op_abandon_without_updating_working_copy() {
     let test_env = TestEnvironment::default();
@@ -1279,6 +1485,7 @@ fn test_op_diff() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Bookmarks with conflicting changes from concurrent operations: bookmark-1
     [EOF]
     ");
     let output = work_dir.run_jj(["op", "log"]);
@@ -1371,9 +1578,11 @@ fn test_op_diff() {
     bookmark-1@origin:
     + tracked zkmtkqvo 0dee6313 bookmark-1?? bookmark-1@origin | Commit 4
     - tracked pukowqtp 0cb7e07e Commit 1
+    (moved forward by 1 commits)
     bookmark-2@origin:
     + untracked kulxwnxm e1a239a5 bookmark-2@origin | Commit 5
     - untracked rnnslrkn 4ff62539 bookmark-1?? | Commit 2
+    (moved forward by 1 commits)
     bookmark-3@origin:
     + untracked (absent)
     - untracked rnnkyono hidden 11671e4c Commit 3
@@ -1526,6 +1735,7 @@ fn test_op_diff() {
     bookmark-1@origin:
     + tracked xlzxqlsl 731ab199 bookmark-1 | (empty) new commit
     - tracked zkmtkqvo 0dee6313 Commit 4
+    (moved forward by 1 commits)
     bookmark-2@origin:
     + untracked (absent)
     - tracked kulxwnxm e1a239a5 Commit 5
@@ -2050,6 +2260,83 @@ fn test_op_diff_at_merge_op_with_rebased_commits() {
     ");
 }
 
+#[test]
+fn test_op_diff_bookmark_move() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+    // Strip the operation header, which contains operation ids.
+    let op_diff = || {
+        work_dir
+            .run_jj(["op", "diff"])
+            .normalize_stdout_with(|s| s.split_once("\n\n").unwrap().1.to_owned())
+    };
+
+    work_dir.run_jj(["describe", "-m", "alpha"]).success();
+    work_dir.run_jj(["new", "-m", "bravo"]).success();
+    work_dir.run_jj(["new", "-m", "charlie"]).success();
+    work_dir
+        .run_jj(["new", "description(alpha)", "-m", "delta"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "foo", "-r", "description(alpha)"])
+        .success();
+
+    // Forward move
+    work_dir
+        .run_jj(["bookmark", "set", "foo", "-r", "description(charlie)"])
+        .success();
+    insta::assert_snapshot!(op_diff(), @r"
+    Changed local bookmarks:
+    foo:
+    + charlie
+    - alpha
+    (moved forward by 2 commits)
+    [EOF]
+    ");
+
+    // Sideways move
+    work_dir
+        .run_jj([
+            "bookmark",
+            "set",
+            "foo",
+            "--allow-backwards",
+            "-r",
+            "description(delta)",
+        ])
+        .success();
+    insta::assert_snapshot!(op_diff(), @r"
+    Changed local bookmarks:
+    foo:
+    + delta
+    - charlie
+    (moved sideways: 1 commits ahead, 2 commits behind)
+    [EOF]
+    ");
+
+    // Backward move
+    work_dir
+        .run_jj([
+            "bookmark",
+            "set",
+            "foo",
+            "--allow-backwards",
+            "-r",
+            "description(alpha)",
+        ])
+        .success();
+    insta::assert_snapshot!(op_diff(), @r"
+    Changed local bookmarks:
+    foo:
+    + alpha
+    - delta
+    (moved backward by 1 commits)
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_op_diff_word_wrap() {
     let test_env = TestEnvironment::default();
@@ -2306,6 +2593,7 @@ fn test_op_show() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Bookmarks with conflicting changes from concurrent operations: bookmark-1
     [EOF]
     ");
     // Showing a merge operation is empty.
@@ -2350,9 +2638,11 @@ fn test_op_show() {
     bookmark-1@origin:
     + tracked zkmtkqvo 0dee6313 bookmark-1?? bookmark-1@origin | Commit 4
     - tracked pukowqtp 0cb7e07e Commit 1
+    (moved forward by 1 commits)
     bookmark-2@origin:
     + untracked kulxwnxm e1a239a5 bookmark-2@origin | Commit 5
     - untracked rnnslrkn 4ff62539 bookmark-1?? | Commit 2
+    (moved forward by 1 commits)
     bookmark-3@origin:
     + untracked (absent)
     - untracked rnnkyono hidden 11671e4c Commit 3
@@ -2511,6 +2801,7 @@ fn test_op_show() {
     bookmark-1@origin:
     + tracked tlkvzzqu 8f340dd7 bookmark-1 | (empty) new commit
     - tracked zkmtkqvo 0dee6313 Commit 4
+    (moved forward by 1 commits)
     bookmark-2@origin:
     + untracked (absent)
     - tracked kulxwnxm e1a239a5 Commit 5
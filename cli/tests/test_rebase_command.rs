@@ -14,6 +14,7 @@
 
 use crate::common::create_commit;
 use crate::common::create_commit_with_files;
+use crate::common::force_interactive;
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
@@ -250,6 +251,58 @@ fn test_rebase_bookmark() {
     ");
 }
 
+#[test]
+fn test_rebase_move_bookmarks_policy() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+    create_commit(&work_dir, "e", &["a"]);
+
+    // "b" stays at the original commit, which stays visible
+    test_env.add_config("rebase.move-bookmarks-overrides.c = 'follow'");
+    let output = work_dir.run_jj(["rebase", "-s=b", "-d=e", "--move-bookmarks-policy=leave"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 2 commits to destination
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    ○  c
+    ○  : e
+    @  e: a
+    │ ○  b: a
+    ├─╯
+    ○  a
+    ◆
+    [EOF]
+    ");
+
+    // Bookmarks are moved if answered "y"
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .args(["rebase", "-s=b", "-d=e", "--move-bookmarks-policy=ask"])
+            .write_stdin("y\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Move bookmark b to the rebased revision? (Yn): Rebased 2 commits to destination
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    ○  c: b
+    ○  b: e
+    @  e: a
+    ○  a
+    ◆
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_rebase_bookmark_with_merge() {
     let test_env = TestEnvironment::default();
@@ -3042,6 +3095,68 @@ fn test_rebase_skip_duplicate_divergent() {
     ");
 }
 
+#[test]
+fn test_rebase_committer_timestamp_policy() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    let template = r#"description ++ committer.timestamp().format("%Y") ++ "\n""#;
+    let get_timestamps = || work_dir.run_jj(["log", "--no-graph", "-r", "a | b", "-T", template]);
+
+    // Use a fixed timestamp
+    work_dir
+        .run_jj([
+            "rebase",
+            "-r",
+            "b",
+            "-d",
+            "root()",
+            "--config=rewrite.committer-timestamp=2020-01-02T03:04:05+07:00",
+        ])
+        .success();
+    insta::assert_snapshot!(get_timestamps(), @r"
+    b
+    2020
+    a
+    2001
+    [EOF]
+    ");
+
+    // Preserve the timestamp of the original commit
+    work_dir
+        .run_jj([
+            "rebase",
+            "-r",
+            "b",
+            "-d",
+            "a",
+            "--config=rewrite.committer-timestamp=preserve",
+        ])
+        .success();
+    insta::assert_snapshot!(get_timestamps(), @r"
+    b
+    2020
+    a
+    2001
+    [EOF]
+    ");
+
+    // The current time is used by default
+    work_dir
+        .run_jj(["rebase", "-r", "b", "-d", "root()"])
+        .success();
+    insta::assert_snapshot!(get_timestamps(), @r"
+    b
+    2001
+    a
+    2001
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = "bookmarks ++ surround(': ', '', parents.map(|c| c.bookmarks()))";
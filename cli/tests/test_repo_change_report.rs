@@ -36,6 +36,10 @@ fn test_report_conflicts() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+    Hint: file was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file
+    To delete the file, run:
+      jj resolve --tool :ours file
     New conflicts appeared in 2 commits:
       kkmpptxz c7f5d6e5 (conflict) C
       rlvkpnrz 032a8668 (conflict) B
@@ -92,6 +96,10 @@ fn test_report_conflicts() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+    Hint: file was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file
+    To delete the file, run:
+      jj resolve --tool :ours file
     [EOF]
     ");
     work_dir.write_file("file", "resolved\n");
@@ -132,6 +140,10 @@ fn test_report_conflicts_with_divergent_commits() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+    Hint: file was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file
+    To delete the file, run:
+      jj resolve --tool :ours file
     New conflicts appeared in 3 commits:
       zsuskuln?? df34134a (conflict) C3
       zsuskuln?? 08a31f4f (conflict) C2
@@ -166,6 +178,10 @@ fn test_report_conflicts_with_divergent_commits() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+    Hint: file was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file
+    To delete the file, run:
+      jj resolve --tool :ours file
     New conflicts appeared in 1 commits:
       zsuskuln?? dfe73891 (conflict) C2
     Hint: To resolve the conflicts, start by creating a commit on top of
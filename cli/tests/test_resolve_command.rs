@@ -1193,6 +1193,17 @@ fn test_resolve_change_delete_executable() {
     file5    2-sided conflict including 1 deletion and an executable
     [EOF]
     ");
+    let conflict_template = r#"separate(' ', path, conflict_side_count,
+        if(conflict_has_deletion, "d", "-"), if(executable_conflict, "x", "-")) ++ "\n""#;
+    let output = work_dir.run_jj(["resolve", "--list", "-T", conflict_template]);
+    insta::assert_snapshot!(output, @r"
+    file1 2 d x
+    file2 2 d x
+    file3 2 - x
+    file4 2 d -
+    file5 2 d -
+    [EOF]
+    ");
     insta::assert_snapshot!(file_list("all()"), @r"
     file1 c -
     file2 c -
@@ -1311,6 +1322,14 @@ fn test_resolve_change_delete_executable() {
     file3    2-sided conflict including an executable
     file4    2-sided conflict including 1 deletion
     file5    2-sided conflict including 1 deletion and an executable
+    Hint: file4 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours file4
+    To delete the file, run:
+      jj resolve --tool :theirs file4
+    Hint: file5 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file5
+    To delete the file, run:
+      jj resolve --tool :ours file5
     [EOF]
     ");
 
@@ -1340,6 +1359,10 @@ fn test_resolve_change_delete_executable() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file5    2-sided conflict including 1 deletion and an executable
+    Hint: file5 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file5
+    To delete the file, run:
+      jj resolve --tool :ours file5
     [EOF]
     ");
 
@@ -1800,6 +1823,62 @@ fn test_multiple_conflicts() {
     ");
 }
 
+#[test]
+fn test_list_conflicts_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("file", "base\n"), ("binary", "\0base\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "a",
+        &["base"],
+        &[("file", "a\n"), ("binary", "\0a\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "b",
+        &["base"],
+        &[("file", "b\n"), ("binary", "\0b\n")],
+    );
+    create_commit_with_files(&work_dir, "conflict", &["a", "b"], &[]);
+    create_commit_with_files(&work_dir, "child", &["conflict"], &[("file", "c\n")]);
+
+    let get_commit_id = |name: &str| {
+        work_dir
+            .run_jj(["log", "--no-graph", "-T=commit_id", "-r", name])
+            .success()
+            .stdout
+            .into_raw()
+    };
+    let commit_id_a = get_commit_id("a");
+    let commit_id_b = get_commit_id("b");
+    let normalize = |output: String| {
+        output
+            .replace(&commit_id_a, "<a>")
+            .replace(&commit_id_b, "<b>")
+    };
+
+    let output = work_dir.run_jj(["resolve", "--list", "--json", "-r", "conflict"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(normalize), @r#"
+    [{"path":"binary","sides":2,"binary":true,"side_commits":["<a>","<b>"]},{"path":"file","sides":2,"binary":false,"side_commits":["<a>","<b>"]}]
+    [EOF]
+    "#);
+
+    // The sides can't be attributed to the parent of the child commit
+    let output = work_dir.run_jj(["resolve", "--list", "--json"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(normalize), @r#"
+    [{"path":"binary","sides":2,"binary":true,"side_commits":[null,null]}]
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_multiple_conflicts_with_error() {
     let mut test_env = TestEnvironment::default();
@@ -1867,6 +1946,7 @@ fn test_multiple_conflicts_with_error() {
     insta::assert_snapshot!(output.normalize_stderr_exit_status(), @r###"
     ------- stderr -------
     Resolving conflicts in: file1
+    Remaining conflicts: 1 of 2 files; next: file2
     Resolving conflicts in: file2
     Working copy  (@) now at: vruxwmqv 98296abe conflict | (conflict) conflict
     Parent commit (@-)      : zsuskuln 6c31698c a | a
@@ -1919,6 +1999,7 @@ fn test_multiple_conflicts_with_error() {
     insta::assert_snapshot!(output.normalize_stderr_exit_status(), @r###"
     ------- stderr -------
     Resolving conflicts in: file1
+    Remaining conflicts: 1 of 2 files; next: file2
     Resolving conflicts in: file2
     Working copy  (@) now at: vruxwmqv 7daa6406 conflict | (conflict) conflict
     Parent commit (@-)      : zsuskuln 6c31698c a | a
@@ -70,6 +70,10 @@ fn test_restore() {
     Added 0 files, modified 1 files, removed 0 files
     Warning: There are unresolved conflicts at these paths:
     file2    2-sided conflict including 1 deletion
+    Hint: file2 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :theirs file2
+    To delete the file, run:
+      jj resolve --tool :ours file2
     New conflicts appeared in 1 commits:
       kkmpptxz c1d65a0f (conflict) (no description set)
     Hint: To resolve the conflicts, start by creating a commit on top of
@@ -354,6 +354,54 @@ fn test_revert_description_template() {
     "#);
 }
 
+#[test]
+fn test_revert_conflict_labels() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[("file", "c\n")]);
+    work_dir.run_jj(["new"]).success();
+
+    // The conflict markers written to the working copy describe which commit
+    // the sides came from
+    work_dir.run_jj(["revert", "-rb", "-B@"]).success();
+    let hash_regex = regex::Regex::new(r"\b[0-9a-f]{12}\b").unwrap();
+    let content = work_dir.read_file("file").to_string();
+    insta::assert_snapshot!(hash_regex.replace_all(&content, "<hash>"), @r#"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base (reverted <hash> "b") to side #1 (destination)
+    -b
+    +c
+    +++++++ Contents of side #2 (parent of <hash> "b")
+    a
+    >>>>>>> Conflict 1 of 1 ends
+    "#);
+}
+
+#[test]
+fn test_revert_conflict_labels_existing_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\n"), ("other", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[("file", "c\n")]);
+    create_commit_with_files(&work_dir, "d", &["a"], &[("other", "d\n")]);
+    create_commit_with_files(&work_dir, "e", &["a"], &[("other", "e\n")]);
+    work_dir.run_jj(["new", "c", "d", "e"]).success();
+
+    // Only the conflict produced by the revert is labeled, not the conflict
+    // which already existed in the destination
+    work_dir.run_jj(["revert", "-rb", "-B@"]).success();
+    let content = work_dir.read_file("file").to_string();
+    assert!(content.contains("(destination)"), "{content}");
+    let content = work_dir.read_file("other").to_string();
+    assert!(content.starts_with("<<<<<<<"), "{content}");
+    assert!(!content.contains("(destination)"), "{content}");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"commit_id.short() ++ " " ++ description"#;
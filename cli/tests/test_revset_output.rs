@@ -161,13 +161,13 @@ fn test_bad_function_call() {
     let output = work_dir.run_jj(["log", "-r", "ancestors()"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Error: Failed to parse revset: Function `ancestors`: Expected 1 to 2 arguments
+    Error: Failed to parse revset: Function `ancestors`: Expected 1 to 3 arguments
     Caused by:  --> 1:11
       |
     1 | ancestors()
       |           ^
       |
-      = Function `ancestors`: Expected 1 to 2 arguments
+      = Function `ancestors`: Expected 1 to 3 arguments
     [EOF]
     [exit status: 1]
     ");
@@ -894,6 +894,51 @@ fn test_all_modifier() {
     ");
 }
 
+#[test]
+fn test_mine_email_aliases() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-mdefault"]).success();
+    work_dir
+        .run_jj(["new", "-mold", "--config=user.email=OLD@example.org"])
+        .success();
+    work_dir
+        .run_jj(["new", "-mcorp", "--config=user.email=me@corp.example.com"])
+        .success();
+    work_dir
+        .run_jj(["new", "-mother", "--config=user.email=other@example.org"])
+        .success();
+    test_env.add_config(r#"user.email-aliases = ["old@example.org", "glob:*@corp.example.com"]"#);
+
+    let template = r#"description ++ " " ++ mine() ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r", "mine()"]);
+    insta::assert_snapshot!(output, @r"
+    corp true
+    old true
+    default true
+    [EOF]
+    ");
+
+    // author() and committer() without arguments match the user's emails
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r", "author()"]);
+    insta::assert_snapshot!(output, @r"
+    corp true
+    old true
+    default true
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        "committer() ~ mine()",
+    ]);
+    insta::assert_snapshot!(output, @"");
+}
+
 /// Verifies that the committer_date revset honors the local time zone.
 /// This test cannot run on Windows because The TZ env var does not control
 /// chrono::Local on that platform.
@@ -182,6 +182,83 @@ fn test_sparse_manage_patterns() {
     ");
 }
 
+#[test]
+fn test_sparse_set_from_file() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "contents");
+    work_dir.write_file("file2", "contents");
+    work_dir.write_file("dir/a", "contents");
+    work_dir.write_file("dir/b", "contents");
+    let sub_dir = work_dir.dir("dir");
+
+    // Patterns are workspace-relative fileset expressions
+    let patterns_path = test_env.env_root().join("patterns");
+    std::fs::write(&patterns_path, "# comment\n\nfile1 | dir\n").unwrap();
+    let output = sub_dir.run_jj([
+        "sparse",
+        "set",
+        "--clear",
+        "--from-file",
+        patterns_path.to_str().unwrap(),
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added 0 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    dir
+    file1
+    [EOF]
+    ");
+
+    // Patterns that can't be represented as path prefixes are rejected
+    std::fs::write(&patterns_path, "glob:*.txt\n").unwrap();
+    let output = work_dir.run_jj([
+        "sparse",
+        "set",
+        "--from-file",
+        patterns_path.to_str().unwrap(),
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Sparse pattern `glob:*.txt` is not a union of path prefixes
+    Hint: Sparse patterns can only include path prefixes such as `dir` or `root:dir`. File and glob patterns are not supported.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Exact file paths aren't widened to prefixes
+    std::fs::write(&patterns_path, "file1 | file:dir/a\n").unwrap();
+    let output = work_dir.run_jj([
+        "sparse",
+        "set",
+        "--from-file",
+        patterns_path.to_str().unwrap(),
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Sparse pattern `file1 | file:dir/a` is not a union of path prefixes
+    Hint: Sparse patterns can only include path prefixes such as `dir` or `root:dir`. File and glob patterns are not supported.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Can read patterns from stdin
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["sparse", "set", "--from-file", "-"])
+            .write_stdin("file2\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_sparse_editor_avoids_unc() {
     use std::path::PathBuf;
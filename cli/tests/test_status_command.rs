@@ -88,6 +88,18 @@ fn test_status_ignored_gitignore() {
     Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
     [EOF]
     ");
+
+    // Ignored directory is reported as a whole
+    let output = work_dir.run_jj(["status", "--untracked=all"]);
+    insta::assert_snapshot!(output, @r"
+    Working copy changes:
+    A .gitignore
+    Untracked paths:
+    ? untracked (ignored)
+    Working copy  (@) : qpvuntsm 32bad97e (no description set)
+    Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
 }
 
 #[test]
@@ -330,6 +342,118 @@ fn test_status_display_relevant_working_commit_conflict_hints() {
     ");
 }
 
+#[test]
+fn test_status_display_conflict_resolution_hints() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("deleted.txt", "base\n"), ("binary file.bin", "base\0")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "a",
+        &["base"],
+        &[("deleted.txt", "a\n"), ("binary file.bin", "a\0")],
+    );
+    create_commit_with_files(&work_dir, "b", &["base"], &[("binary file.bin", "b\0")]);
+    work_dir.remove_file("deleted.txt");
+    create_commit_with_files(&work_dir, "conflict", &["a", "b"], &[]);
+
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : conflict
+    Parent commit (@-): a
+    Parent commit (@-): b
+    Warning: There are unresolved conflicts at these paths:
+    binary file.bin    2-sided conflict
+    deleted.txt        2-sided conflict including 1 deletion
+    Hint: binary file.bin is a binary file. To take one side of the conflict, run one of:
+      jj resolve --tool :ours 'binary file.bin'
+      jj resolve --tool :theirs 'binary file.bin'
+    Hint: deleted.txt was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours deleted.txt
+    To delete the file, run:
+      jj resolve --tool :theirs deleted.txt
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["status", "--config=hints.resolving-conflicts=false"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : conflict
+    Parent commit (@-): a
+    Parent commit (@-): b
+    Warning: There are unresolved conflicts at these paths:
+    binary file.bin    2-sided conflict
+    deleted.txt        2-sided conflict including 1 deletion
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_status_display_conflict_resolution_hints_limit() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    let paths = ["f1", "f2", "f3", "f4", "f5", "f6"];
+    let base_files = paths.map(|path| (path, "base\n"));
+    let a_files = paths.map(|path| (path, "a\n"));
+    create_commit_with_files(&work_dir, "base", &[], &base_files);
+    create_commit_with_files(&work_dir, "a", &["base"], &a_files);
+    create_commit_with_files(&work_dir, "b", &["base"], &[]);
+    for path in paths {
+        work_dir.remove_file(path);
+    }
+    create_commit_with_files(&work_dir, "conflict", &["a", "b"], &[]);
+
+    // Only the first few conflicts get a hint
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : conflict
+    Parent commit (@-): a
+    Parent commit (@-): b
+    Warning: There are unresolved conflicts at these paths:
+    f1    2-sided conflict including 1 deletion
+    f2    2-sided conflict including 1 deletion
+    f3    2-sided conflict including 1 deletion
+    f4    2-sided conflict including 1 deletion
+    f5    2-sided conflict including 1 deletion
+    f6    2-sided conflict including 1 deletion
+    Hint: f1 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours f1
+    To delete the file, run:
+      jj resolve --tool :theirs f1
+    Hint: f2 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours f2
+    To delete the file, run:
+      jj resolve --tool :theirs f2
+    Hint: f3 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours f3
+    To delete the file, run:
+      jj resolve --tool :theirs f3
+    Hint: f4 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours f4
+    To delete the file, run:
+      jj resolve --tool :theirs f4
+    Hint: f5 was deleted on one side of the conflict. To keep the modified file, run:
+      jj resolve --tool :ours f5
+    To delete the file, run:
+      jj resolve --tool :theirs f5
+    Hint: More conflicts may need to be resolved this way. Run `jj resolve --list` to see all of them.
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_status_simplify_conflict_sides() {
     let test_env = TestEnvironment::default();
@@ -398,6 +522,26 @@ fn test_status_untracked_files() {
     [EOF]
     ");
 
+    let output = work_dir.run_jj(["status", "--untracked=all"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    Untracked paths:
+    ? always-untracked-file (not auto-tracked)
+    ? initially-untracked-file (not auto-tracked)
+    ? sub/always-untracked (not auto-tracked)
+    ? sub/initially-untracked (not auto-tracked)
+    Working copy  (@) : qpvuntsm e8849ae1 (empty) (no description set)
+    Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["status", "--untracked=no"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : qpvuntsm e8849ae1 (empty) (no description set)
+    Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+
     work_dir
         .run_jj([
             "file",
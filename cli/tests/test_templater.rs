@@ -532,6 +532,53 @@ fn test_templater_config_function() {
     ");
 }
 
+#[test]
+fn test_templater_read_config_function() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(indoc! {"
+        [template-vars]
+        greeting = 'Hello'
+        count = 42
+        enabled = true
+        list = ['a', 'b']
+        nested.name = 'inner'
+    "});
+    let work_dir = test_env.work_dir("repo");
+    let render = |template| get_template_output(&work_dir, "@-", template);
+
+    insta::assert_snapshot!(
+        render("read_config('greeting') ++ ' ' ++ read_config('greeting').upper()"),
+        @"Hello HELLO[EOF]");
+    insta::assert_snapshot!(render("read_config('count') + 1"), @"43[EOF]");
+    insta::assert_snapshot!(render("if(read_config('enabled'), 'on', 'off')"), @"on[EOF]");
+    insta::assert_snapshot!(render("read_config('nested.name')"), @"inner[EOF]");
+    insta::assert_snapshot!(render("read_config('list')"), @r"
+    ------- stderr -------
+    Error: Failed to parse template: Config value `template-vars.list` must be a string, integer, or boolean
+    Caused by:  --> 1:13
+      |
+    1 | read_config('list')
+      |             ^----^
+      |
+      = Config value `template-vars.list` must be a string, integer, or boolean
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(render("read_config('unknown')"), @r"
+    ------- stderr -------
+    Error: Failed to parse template: Config value `template-vars.unknown` is not set
+    Caused by:  --> 1:13
+      |
+    1 | read_config('unknown')
+      |             ^-------^
+      |
+      = Config value `template-vars.unknown` is not set
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[must_use]
 fn get_template_output(work_dir: &TestWorkDir, rev: &str, template: &str) -> CommandOutput {
     work_dir.run_jj(["log", "--no-graph", "-r", rev, "-T", template])
@@ -0,0 +1,216 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_test_run_no_command() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["test", "run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: No test command is configured
+    Hint: Set `test.command` to the command to run, e.g. `jj config set --repo test.command 'cargo test'`
+    For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_test_run() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(r#"test.command = ["sh", "-c", "grep -q good file"]"#);
+    test_env.add_config(r#"templates.commit_summary = "description.first_line()""#);
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "good\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.write_file("file", "bad\n");
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    // Same tree as "second"
+    work_dir.run_jj(["describe", "-m", "third"]).success();
+
+    let output = work_dir.run_jj(["test", "run", "-r", "root()..@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    passed: first
+    failed: second
+    failed (cached): third
+    Error: 2 of 3 revisions failed
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let template = r#"description.first_line() ++ " " ++ tests_status() ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    third failed
+    second failed
+    first passed
+     unknown
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "tests(failed)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    third failed
+    second failed
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "tests(unknown)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+     unknown
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "tests(foo)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse revset: Expected one of `passed`, `failed`, or `unknown`
+    Caused by:  --> 1:7
+      |
+    1 | tests(foo)
+      |       ^-^
+      |
+      = Expected one of `passed`, `failed`, or `unknown`
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Cached results are reused unless --rerun is specified
+    let output = work_dir.run_jj(["test", "run", "-r", "description(first)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    passed (cached): first
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["test", "run", "-r", "description(first)", "--rerun"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    passed: first
+    [EOF]
+    ");
+
+    // Results of trees that are no longer visible are garbage-collected
+    let results_dir = work_dir
+        .root()
+        .join(".jj")
+        .join("repo")
+        .join("test_results");
+    let count_results = || {
+        std::fs::read_dir(&results_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .map(|path| std::fs::read_dir(path).unwrap().count())
+            .sum::<usize>()
+    };
+    assert_eq!(count_results(), 2);
+    work_dir.run_jj(["util", "gc", "--expire=now"]).success();
+    assert_eq!(count_results(), 2);
+    work_dir
+        .run_jj(["abandon", "--restore-descendants", "description(first)"])
+        .success();
+    work_dir.run_jj(["util", "gc", "--expire=now"]).success();
+    assert_eq!(count_results(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_test_run_command_changed() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(r#"templates.commit_summary = "description.first_line()""#);
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "good\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+
+    let output = work_dir.run_jj([
+        "test",
+        "run",
+        "-r",
+        "description(first)",
+        "--config",
+        r#"test.command=["sh", "-c", "grep -q good file"]"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    passed: first
+    [EOF]
+    ");
+
+    // The result of another command isn't reused
+    let output = work_dir.run_jj([
+        "test",
+        "run",
+        "-r",
+        "description(first)",
+        "--config",
+        r#"test.command=["sh", "-c", "grep -q bad file"]"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    failed: first
+    Error: 1 of 1 revisions failed
+    [EOF]
+    [exit status: 1]
+    ");
+    let template = r#"description.first_line() ++ " " ++ tests_status() ++ "\n""#;
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "description(first)",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    first failed
+    [EOF]
+    ");
+
+    // Nor is the result of the same command with another environment
+    let output = work_dir.run_jj([
+        "test",
+        "run",
+        "-r",
+        "description(first)",
+        "--config",
+        r#"test.command={ env = { FOO = "1" }, command = ["sh", "-c", "grep -q good file"] }"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    passed: first
+    [EOF]
+    ");
+
+    // The results of the original command are still cached
+    let output = work_dir.run_jj([
+        "test",
+        "run",
+        "-r",
+        "description(first)",
+        "--config",
+        r#"test.command=["sh", "-c", "grep -q good file"]"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    passed (cached): first
+    [EOF]
+    ");
+}
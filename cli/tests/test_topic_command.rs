@@ -0,0 +1,122 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_topic_set_list_clear() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m=first"]).success();
+    work_dir.run_jj(["new", "-m=second"]).success();
+    work_dir.run_jj(["new", "-m=third"]).success();
+
+    work_dir
+        .run_jj(["topic", "set", "feature", "-r=@--|@-"])
+        .success();
+    work_dir.run_jj(["topic", "set", "other"]).success();
+
+    let output = work_dir.run_jj(["topic", "list"]);
+    insta::assert_snapshot!(output, @r"
+    feature: 2 revisions
+    other: 1 revisions
+    [EOF]
+    ");
+
+    let template = r#"separate(" ", description.first_line(), topic) ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=topic(feature)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    second feature
+    first feature
+    [EOF]
+    ");
+    // Plain names are matched exactly
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=topic(feat)", "-T", template]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r=topic(glob:'feat*')",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    second feature
+    first feature
+    [EOF]
+    ");
+
+    // Setting the same topic again is a no-op
+    let output = work_dir.run_jj(["topic", "set", "other"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    work_dir.run_jj(["topic", "clear", "-r=@-"]).success();
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=mutable()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    third other
+    second
+    first feature
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=@-", "-T=description"]);
+    insta::assert_snapshot!(output, @r"
+    second
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_topic_set_invalid_name() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["topic", "set", " "]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Topic name must be a non-empty single line
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_topic_multiple_trailers() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["describe", "-m=first\n\nTopic: old\nTopic: new"])
+        .success();
+
+    // The last topic trailer wins in both the template and the revset
+    let template = r#"separate(" ", description.first_line(), topic) ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=topic(new)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    first new
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=topic(old)", "-T", template]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=topic(glob:'*')", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    first new
+    [EOF]
+    ");
+}
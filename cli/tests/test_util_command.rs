@@ -100,6 +100,60 @@ fn test_gc_operation_log() {
     "#);
 }
 
+#[test]
+fn test_gc_auto() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let state_path = work_dir.root().join(".jj").join("repo").join("gc_state");
+
+    // The first run collects garbage and records the time.
+    let output = work_dir.run_jj(["util", "gc", "--auto"]);
+    insta::assert_snapshot!(output, @"");
+    assert!(state_path.exists());
+
+    // Subsequent runs within the interval are no-ops.
+    std::fs::write(&state_path, "0\n").unwrap();
+    work_dir
+        .run_jj(["util", "gc", "--auto", "--config=gc.auto-interval-hours=1"])
+        .success();
+    assert_ne!(std::fs::read_to_string(&state_path).unwrap(), "0\n");
+    let recorded = std::fs::read_to_string(&state_path).unwrap();
+    work_dir
+        .run_jj(["util", "gc", "--auto", "--config=gc.auto-interval-hours=1"])
+        .success();
+    assert_eq!(std::fs::read_to_string(&state_path).unwrap(), recorded);
+}
+
+#[test]
+fn test_gc_auto_background_config_args() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let state_path = work_dir.root().join(".jj").join("repo").join("gc_state");
+    // A time in the future, which the default interval considers recent.
+    let future_state = "99999999999\n";
+    std::fs::write(&state_path, future_state).unwrap();
+
+    // Automatic gc is only enabled by command-line arguments, which have to be
+    // passed down to the background process for it to run gc.
+    work_dir
+        .run_jj([
+            "new",
+            "--config=gc.auto=true",
+            "--config=gc.auto-interval-hours=0",
+        ])
+        .success();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+    while std::fs::read_to_string(&state_path).unwrap() == future_state {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "background gc didn't run"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
 #[test]
 fn test_shell_completions() {
     #[track_caller]
@@ -141,6 +195,86 @@ fn test_util_exec() {
     insta::assert_snapshot!(output, @"hello[EOF]");
 }
 
+#[cfg(unix)]
+#[test]
+fn test_util_exec_on_rev() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "old\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.write_file("file", "new\n");
+
+    let script = r#"
+        test "$JJ_ROOT" = "$PWD" || exit 1
+        test -w file && exit 1
+        echo "$JJ_CHANGE_ID $JJ_COMMIT_ID"
+        cat file
+    "#;
+    let output = work_dir.run_jj(["util", "exec", "--on-rev", "@-", "--", "sh", "-c", script]);
+    let ids = work_dir
+        .run_jj([
+            "log",
+            "--no-graph",
+            "-r@-",
+            "-T",
+            r#"change_id ++ " " ++ commit_id"#,
+        ])
+        .success();
+    assert_eq!(
+        output.success().stdout.raw(),
+        format!("{}\nold\n", ids.stdout.raw())
+    );
+
+    // The temporary directory is removed
+    let output = work_dir.run_jj(["util", "exec", "--on-rev", "@", "--", "sh", "-c", "pwd"]);
+    let root = output.stdout.raw().trim_end();
+    assert!(!std::path::Path::new(root).exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_util_exec_workspace_env() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "a\n");
+
+    let script = r#"
+        test -d "$JJ_WORKSPACE_ROOT/.jj" || exit 1
+        test -d "$JJ_REPO_PATH/store" || exit 1
+        echo "$JJ_WORKSPACE_NAME"
+        echo "$JJ_WC_CHANGE_ID $JJ_WC_COMMIT_ID"
+        echo "$JJ_OP_ID"
+    "#;
+    let output = work_dir.run_jj(["util", "exec", "--", "sh", "-c", script]);
+    // The working copy is snapshotted before running the command
+    let ids = work_dir
+        .run_jj([
+            "log",
+            "--no-graph",
+            "-r@",
+            "-T",
+            r#"change_id ++ " " ++ commit_id"#,
+        ])
+        .success();
+    let op_id = work_dir
+        .run_jj(["op", "log", "--no-graph", "-n1", "-T", "id"])
+        .success();
+    assert_eq!(
+        output.success().stdout.raw(),
+        format!("default\n{}\n{}\n", ids.stdout.raw(), op_id.stdout.raw())
+    );
+
+    // Outside of a repo, the variables aren't set
+    let script = r#"echo "[$JJ_OP_ID]""#;
+    let output = test_env.run_jj_in(".", ["util", "exec", "--", "sh", "-c", script]);
+    insta::assert_snapshot!(output, @r"
+    []
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_util_exec_fail() {
     let test_env = TestEnvironment::default();
@@ -152,3 +286,46 @@ fn test_util_exec_fail() {
     [exit status: 1]
     ");
 }
+
+#[test]
+fn test_util_quote() {
+    let test_env = TestEnvironment::default();
+    let output = test_env.run_jj_in(".", ["util", "quote", "foo", "a b", r#"x"y\"#, "\n"]);
+    insta::assert_snapshot!(output, @r#"
+    "foo"
+    "a b"
+    "x\"y\\"
+    "\n"
+    [EOF]
+    "#);
+
+    let output = test_env.run_jj_in(".", ["util", "quote", "--kind=symbol", "foo", "@", "a-b"]);
+    insta::assert_snapshot!(output, @r#"
+    foo
+    "@"
+    a-b
+    [EOF]
+    "#);
+
+    let output = test_env.run_jj_in(".", ["util", "quote", "--kind=file", "--union", "a", "b c"]);
+    insta::assert_snapshot!(output, @r#"
+    file:"a" | file:"b c"
+    [EOF]
+    "#);
+
+    // The quoted value round-trips through the fileset parser
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("a (b)", "");
+    work_dir.write_file("c", "");
+    let quoted = test_env
+        .run_jj_in(".", ["util", "quote", "--kind=root-file", "a (b)"])
+        .success()
+        .stdout
+        .into_raw();
+    let output = work_dir.run_jj(["file", "list", quoted.trim_end()]);
+    insta::assert_snapshot!(output, @r"
+    a (b)
+    [EOF]
+    ");
+}
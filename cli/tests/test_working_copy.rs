@@ -115,6 +115,96 @@ fn test_snapshot_large_file() {
     ");
 }
 
+#[test]
+fn test_snapshot_large_new_directory() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    test_env.add_config(
+        r#"
+        snapshot.new-directory-policy = "exclude"
+        snapshot.new-directory-max-files = 2
+        "#,
+    );
+    work_dir.write_file("file", "");
+    work_dir.write_file("target/a", "");
+    work_dir.write_file("target/b", "");
+    work_dir.write_file("target/debug/c", "");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    file
+    [EOF]
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      target: new directory with more than 2 files; it's likely a build output
+    Hint: This is to prevent build outputs from being added by accident. You can fix this by:
+      - Adding the directory to `.gitignore`
+      - Run `jj --config snapshot.new-directory-policy=allow st`
+        This will snapshot the directory, for this command only.
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "file",
+        "list",
+        "--config=snapshot.new-directory-policy=warn",
+    ]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    file
+    target/a
+    target/b
+    target/debug/c
+    [EOF]
+    ------- stderr -------
+    Warning: Snapshotted new directories with many files:
+      target
+    Hint: If these are build outputs, ignore and untrack them with:
+      echo /target/ >> .gitignore && jj file untrack target
+    [EOF]
+    ");
+
+    // The directory is no longer new
+    let output = work_dir.run_jj([
+        "file",
+        "list",
+        "--config=snapshot.new-directory-policy=warn",
+    ]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    file
+    target/a
+    target/b
+    target/debug/c
+    [EOF]
+    ");
+
+    // Paths in the hint are quoted
+    work_dir.write_file("build out/a", "");
+    work_dir.write_file("build out/b", "");
+    work_dir.write_file("build out/c", "");
+    let output = work_dir.run_jj([
+        "file",
+        "list",
+        "--config=snapshot.new-directory-policy=warn",
+    ]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    build out/a
+    build out/b
+    build out/c
+    file
+    target/a
+    target/b
+    target/debug/c
+    [EOF]
+    ------- stderr -------
+    Warning: Snapshotted new directories with many files:
+      build out
+    Hint: If these are build outputs, ignore and untrack them with:
+      echo '/build out/' >> .gitignore && jj file untrack 'root:"build out"'
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_snapshot_large_file_restore() {
     let test_env = TestEnvironment::default();
@@ -978,6 +978,55 @@ fn test_workspaces_update_stale_snapshot() {
     ");
 }
 
+/// Test that local changes in a stale working copy can be rebased onto the
+/// updated working-copy commit
+#[test]
+fn test_workspaces_update_stale_rebase_local_changes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    let secondary_dir = test_env.work_dir("secondary");
+
+    main_dir.write_file("file", "contents\n");
+    main_dir.run_jj(["new"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    secondary_dir
+        .run_jj(["describe", "-m", "secondary"])
+        .success();
+
+    // Make changes in both working copies, and rewrite the secondary
+    // working-copy commit from the main workspace
+    main_dir.write_file("file", "changed in main\n");
+    secondary_dir.write_file("file2", "added in second\n");
+    main_dir.run_jj(["squash"]).success();
+
+    secondary_dir
+        .run_jj(["workspace", "update-stale", "--rebase-local-changes"])
+        .success();
+
+    // The local changes are committed on top of the rewritten working-copy
+    // commit, and the stale snapshot isn't left behind as a divergent commit
+    let template = r#"separate(" ", description.first_line(), working_copies) ++ "\n""#;
+    let output = secondary_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  secondary@
+    ○  secondary
+    │ ○  default@
+    ├─╯
+    ○
+    ◆
+    [EOF]
+    ");
+    let output = secondary_dir.run_jj(["file", "show", "file", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    changed in main
+    added in second
+    [EOF]
+    ");
+}
+
 /// Test forgetting workspaces
 #[test]
 fn test_workspaces_forget() {
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use jj_lib::absorb::split_hunks_to_trees;
+use jj_lib::absorb::AbsorbSource;
+use jj_lib::absorb::UnambiguousAbsorbPolicy;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::ResolvedRevsetExpression;
+use pollster::FutureExt as _;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepo;
+
+const LINES_PER_COMMIT: usize = 100;
+
+/// Generates file content where the lines owned by the first `num_commits`
+/// ancestors are rewritten.
+fn file_content(num_lines: usize, num_commits: usize) -> String {
+    (0..num_lines)
+        .map(|i| {
+            let owner = i / LINES_PER_COMMIT;
+            if owner < num_commits {
+                format!("commit {owner} line {i}\n")
+            } else {
+                format!("base line {i}\n")
+            }
+        })
+        .collect()
+}
+
+/// Builds a linear history of `num_commits` commits, each of which modifies
+/// its own chunk of a single file, and a source commit on top of it which
+/// modifies every other line.
+fn generate_history(test_repo: &TestRepo, num_commits: usize) -> (Arc<ReadonlyRepo>, Commit) {
+    let path = repo_path("file");
+    let num_lines = num_commits * LINES_PER_COMMIT;
+    let mut tx = test_repo.repo.start_transaction();
+    let mut parent_id = test_repo.repo.store().root_commit_id().clone();
+    for k in 0..=num_commits {
+        let content = file_content(num_lines, k);
+        let tree = create_tree(&test_repo.repo, &[(path, &content)]);
+        let commit = tx
+            .repo_mut()
+            .new_commit(vec![parent_id], tree.id())
+            .set_description(format!("commit {k}"))
+            .write()
+            .unwrap();
+        parent_id = commit.id().clone();
+    }
+    let source_content: String = file_content(num_lines, num_commits)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i % 2 == 0 {
+                format!("{line} modified\n")
+            } else {
+                format!("{line}\n")
+            }
+        })
+        .collect();
+    let tree = create_tree(&test_repo.repo, &[(path, &source_content)]);
+    let source = tx
+        .repo_mut()
+        .new_commit(vec![parent_id], tree.id())
+        .set_description("source")
+        .write()
+        .unwrap();
+    let repo = tx.commit("generate history").unwrap();
+    (repo, source)
+}
+
+fn bench_absorb_split_hunks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench_absorb_split_hunks");
+    for num_commits in [10, 100] {
+        let test_repo = TestRepo::init();
+        let (repo, source_commit) = generate_history(&test_repo, num_commits);
+        let source = AbsorbSource::from_commit(repo.as_ref(), source_commit.clone()).unwrap();
+        let destinations =
+            ResolvedRevsetExpression::commits(source_commit.parent_ids().to_vec()).ancestors();
+        group.bench_with_input(
+            BenchmarkId::new("commits", num_commits),
+            &(source, destinations),
+            |b, (source, destinations)| {
+                b.iter(|| {
+                    let selected_trees = split_hunks_to_trees(
+                        repo.as_ref(),
+                        source,
+                        destinations,
+                        &EverythingMatcher,
+                        &UnambiguousAbsorbPolicy,
+                    )
+                    .block_on()
+                    .unwrap();
+                    assert_eq!(selected_trees.target_commits.len(), num_commits);
+                });
+            },
+        );
+    }
+}
+
+criterion_group!(benches, bench_absorb_split_hunks,);
+criterion_main!(benches);
@@ -54,6 +54,38 @@ fn bench_diff_lines(c: &mut Criterion) {
     }
 }
 
+/// Generates `num_inputs` versions of `count` lines, each of which modifies
+/// a different subset of the lines.
+fn multi_input_lines(count: usize, num_inputs: usize) -> Vec<String> {
+    (0..num_inputs)
+        .map(|input| {
+            let mut lines = vec![];
+            for i in 0..count {
+                if i % num_inputs == input {
+                    lines.push(format!("input {input} line {i}\n"));
+                } else {
+                    lines.push(format!("left line {i}\n"));
+                }
+            }
+            lines.join("")
+        })
+        .collect()
+}
+
+fn bench_diff_multi_inputs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench_diff_multi_inputs");
+    for count in [1000, 10000] {
+        for num_inputs in [3, 5] {
+            let label = format!("{}k", count / 1000);
+            group.bench_with_input(
+                BenchmarkId::new(format!("inputs_{num_inputs}"), &label),
+                &multi_input_lines(count, num_inputs),
+                |b, inputs| b.iter(|| diff::diff(inputs)),
+            );
+        }
+    }
+}
+
 fn bench_diff_git_git_read_tree_c(c: &mut Criterion) {
     c.bench_function("bench_diff_git_git_read_tree_c", |b| {
         b.iter(|| {
@@ -201,5 +233,10 @@ int main(int argc, char **argv)
     });
 }
 
-criterion_group!(benches, bench_diff_lines, bench_diff_git_git_read_tree_c,);
+criterion_group!(
+    benches,
+    bench_diff_lines,
+    bench_diff_multi_inputs,
+    bench_diff_git_git_read_tree_c,
+);
 criterion_main!(benches);
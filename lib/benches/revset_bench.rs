@@ -0,0 +1,72 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use jj_lib::commit::Commit;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::ResolvedRevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
+use testutils::CommitGraphBuilder;
+use testutils::TestRepo;
+
+/// Builds a history of `count` commits on the main line. Every 8th commit
+/// forks a side branch of 3 commits, which is merged back into the main line.
+fn generate_history(test_repo: &TestRepo, count: usize) -> (Arc<ReadonlyRepo>, Commit, Commit) {
+    let mut tx = test_repo.repo.start_transaction();
+    let mut graph = CommitGraphBuilder::new(tx.repo_mut());
+    let first = graph.initial_commit();
+    let mut head = first.clone();
+    for i in 1..count {
+        if i % 8 == 0 {
+            let mut side = head.clone();
+            for _ in 0..3 {
+                side = graph.commit_with_parents(&[&side]);
+            }
+            head = graph.commit_with_parents(&[&head, &side]);
+        } else {
+            head = graph.commit_with_parents(&[&head]);
+        }
+    }
+    let repo = tx.commit("generate history").unwrap();
+    (repo, first, head)
+}
+
+fn evaluate_count(repo: &ReadonlyRepo, expression: &Rc<ResolvedRevsetExpression>) -> usize {
+    expression.clone().evaluate(repo).unwrap().iter().count()
+}
+
+fn bench_revset_evaluation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench_revset_evaluation");
+    for count in [1000, 10000] {
+        let label = format!("{}k", count / 1000);
+        let test_repo = TestRepo::init();
+        let (repo, first, head) = generate_history(&test_repo, count);
+        let first = ResolvedRevsetExpression::commit(first.id().clone());
+        let head = ResolvedRevsetExpression::commit(head.id().clone());
+        let merges =
+            ResolvedRevsetExpression::filter(RevsetFilterPredicate::ParentCount(2..u32::MAX));
+        let expressions = [
+            ("ancestors", head.ancestors()),
+            ("descendants", first.descendants()),
+            ("range", first.range(&head)),
+            ("heads_all", ResolvedRevsetExpression::all().heads()),
+            ("roots_merges", merges.roots()),
+            ("ancestors_minus_merges", head.ancestors().minus(&merges)),
+        ];
+        for (name, expression) in &expressions {
+            group.bench_with_input(BenchmarkId::new(*name, &label), expression, |b, expr| {
+                b.iter(|| evaluate_count(&repo, expr));
+            });
+        }
+        // Sanity check that the generated graph has the expected size
+        assert!(evaluate_count(&repo, &head.ancestors()) > count);
+        assert_eq!(repo.view().heads().len(), 1);
+    }
+}
+
+criterion_group!(benches, bench_revset_evaluation,);
+criterion_main!(benches);
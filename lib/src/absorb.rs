@@ -26,6 +26,8 @@ use itertools::Itertools as _;
 use pollster::FutureExt as _;
 use thiserror::Error;
 
+use crate::annotate::get_file_contents;
+use crate::annotate::FileAnnotation;
 use crate::annotate::FileAnnotator;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
@@ -44,8 +46,10 @@ use crate::merged_tree::MergedTree;
 use crate::merged_tree::MergedTreeBuilder;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
+use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 
 /// The source commit to absorb into its ancestry.
@@ -87,15 +91,128 @@ pub struct SelectedTrees {
     pub skipped_paths: Vec<(RepoPathBuf, String)>,
 }
 
+/// Hunk of the source commit, and the destination commits it may be absorbed
+/// into.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbsorbHunk<'a> {
+    /// Byte range in the source parent (= left) content replaced by the hunk.
+    pub left_range: Range<usize>,
+    /// Byte range in the source (= right) content.
+    pub right_range: Range<usize>,
+    /// Destination commits which last modified the left lines overlapping with
+    /// the hunk, in file order. If the hunk is pure insertion, the lines
+    /// adjacent to the insertion point are considered.
+    pub candidates: Vec<&'a CommitId>,
+    /// Whether the left range is fully covered by the lines of the candidates.
+    pub covered: bool,
+}
+
+/// Policy to select the destination commit of each source hunk.
+///
+/// Pure deletions are split across the overlapping destinations without
+/// consulting the policy since they can be mapped unambiguously.
+pub trait AbsorbPolicy {
+    /// Selects the destination of the `hunk` in the file at `path`, or returns
+    /// `None` to leave the hunk in the source commit. The returned commit
+    /// should be one of the `hunk.candidates`; other commits are ignored.
+    fn select_destination<'a>(
+        &self,
+        path: &RepoPath,
+        left_text: &[u8],
+        right_text: &[u8],
+        hunk: &AbsorbHunk<'a>,
+    ) -> Option<&'a CommitId>;
+}
+
+/// Default policy which absorbs a hunk only if it is fully contained in the
+/// lines last modified by a single destination commit.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnambiguousAbsorbPolicy;
+
+impl AbsorbPolicy for UnambiguousAbsorbPolicy {
+    fn select_destination<'a>(
+        &self,
+        _path: &RepoPath,
+        _left_text: &[u8],
+        _right_text: &[u8],
+        hunk: &AbsorbHunk<'a>,
+    ) -> Option<&'a CommitId> {
+        select_unambiguous_destination(hunk)
+    }
+}
+
+/// Policy which falls back to ranking the candidates by a user-provided
+/// function if a hunk can't be mapped unambiguously.
+///
+/// The ranker returns a score of the candidate commit, or `None` if the commit
+/// shouldn't be selected. The candidate with the highest score is selected
+/// unless there's a tie.
+pub struct RankedAbsorbPolicy<F> {
+    ranker: F,
+}
+
+impl<F> RankedAbsorbPolicy<F>
+where
+    F: Fn(&RepoPath, &[u8], &[u8], &AbsorbHunk<'_>, &CommitId) -> Option<u64>,
+{
+    /// Creates policy that ranks ambiguous candidates by `ranker`.
+    pub fn new(ranker: F) -> Self {
+        RankedAbsorbPolicy { ranker }
+    }
+}
+
+impl<F> AbsorbPolicy for RankedAbsorbPolicy<F>
+where
+    F: Fn(&RepoPath, &[u8], &[u8], &AbsorbHunk<'_>, &CommitId) -> Option<u64>,
+{
+    fn select_destination<'a>(
+        &self,
+        path: &RepoPath,
+        left_text: &[u8],
+        right_text: &[u8],
+        hunk: &AbsorbHunk<'a>,
+    ) -> Option<&'a CommitId> {
+        if let Some(commit_id) = select_unambiguous_destination(hunk) {
+            return Some(commit_id);
+        }
+        if !hunk.covered {
+            return None;
+        }
+        let ranked = hunk
+            .candidates
+            .iter()
+            .unique()
+            .filter_map(|&commit_id| {
+                let score = (self.ranker)(path, left_text, right_text, hunk, commit_id)?;
+                Some((score, commit_id))
+            })
+            .max_set_by_key(|&(score, _)| score);
+        match ranked[..] {
+            [(_, commit_id)] => Some(commit_id),
+            _ => None,
+        }
+    }
+}
+
+fn select_unambiguous_destination<'a>(hunk: &AbsorbHunk<'a>) -> Option<&'a CommitId> {
+    match hunk.candidates[..] {
+        [commit_id] if hunk.covered => Some(commit_id),
+        _ => None,
+    }
+}
+
 /// Builds trees to be merged into destination commits by splitting source
-/// changes based on file annotation.
+/// changes based on file annotation. The destination of each hunk is selected
+/// by the `policy`.
 pub async fn split_hunks_to_trees(
     repo: &dyn Repo,
     source: &AbsorbSource,
     destinations: &Rc<ResolvedRevsetExpression>,
     matcher: &dyn Matcher,
+    policy: &dyn AbsorbPolicy,
 ) -> Result<SelectedTrees, AbsorbError> {
     let mut selected_trees = SelectedTrees::default();
+    let is_destination = destinations.evaluate(repo)?.containing_fn();
 
     let left_tree = &source.parent_tree;
     let right_tree = source.commit.tree_async().await?;
@@ -138,12 +255,14 @@ pub async fn split_hunks_to_trees(
             FileAnnotator::with_file_content(source.commit.id(), left_path, left_text.clone());
         annotator.compute(repo, destinations)?;
         let annotation = annotator.to_annotation();
-        let annotation_ranges = annotation
-            .compact_line_ranges()
-            .filter_map(|(commit_id, range)| Some((commit_id.ok()?, range)))
-            .collect_vec();
+        let annotation_ranges =
+            destination_line_ranges(repo, left_path, &annotation, &*is_destination).await?;
         let diff = Diff::by_line([&left_text, &right_text]);
-        let selected_ranges = split_file_hunks(&annotation_ranges, &diff);
+        let selected_ranges = map_file_hunks(&annotation_ranges, &diff, |hunk| {
+            policy
+                .select_destination(left_path, &left_text, &right_text, hunk)
+                .filter(|commit_id| hunk.candidates.contains(commit_id))
+        });
         // Build trees containing parent (= left) contents + selected hunks
         for (&commit_id, ranges) in &selected_ranges {
             let tree_builder = selected_trees
@@ -174,13 +293,105 @@ pub async fn split_hunks_to_trees(
     Ok(selected_trees)
 }
 
+/// Returns compacted line ranges of the `annotation` mapped to the
+/// destination commits.
+///
+/// If the destination set isn't a contiguous range, lines modified by
+/// non-destination revisions may be attributed to the closest destination
+/// ancestor. Such lines are excluded unless the destination commit actually
+/// modified them relative to its parents.
+async fn destination_line_ranges<'a>(
+    repo: &dyn Repo,
+    path: &RepoPath,
+    annotation: &'a FileAnnotation,
+    is_destination: &RevsetContainingFn<'_>,
+) -> Result<Vec<(&'a CommitId, Range<usize>)>, AbsorbError> {
+    // None if all lines attributed to the commit can be trusted
+    let mut modified_lines_map: HashMap<&CommitId, Option<Vec<bool>>> = HashMap::new();
+    let mut ranges: Vec<(&CommitId, Range<usize>)> = Vec::new();
+    let mut line_start = 0;
+    for (origin, line) in annotation.line_origins() {
+        let range = line_start..line_start + line.len();
+        line_start = range.end;
+        let Ok(origin) = origin else {
+            continue;
+        };
+        let commit_id = &origin.commit_id;
+        if !modified_lines_map.contains_key(commit_id) {
+            let modified_lines = modified_lines_at(repo, path, commit_id, is_destination).await?;
+            modified_lines_map.insert(commit_id, modified_lines);
+        }
+        if let Some(modified_lines) = &modified_lines_map[commit_id] {
+            if !modified_lines
+                .get(origin.line_number)
+                .copied()
+                .unwrap_or(false)
+            {
+                continue;
+            }
+        }
+        match ranges.last_mut() {
+            Some((last_id, last_range))
+                if *last_id == commit_id && last_range.end == range.start =>
+            {
+                last_range.end = range.end;
+            }
+            _ => ranges.push((commit_id, range)),
+        }
+    }
+    Ok(ranges)
+}
+
+/// Returns flags indicating which lines of the file were added or modified by
+/// the commit, or `None` if all parents of the commit are destinations.
+async fn modified_lines_at(
+    repo: &dyn Repo,
+    path: &RepoPath,
+    commit_id: &CommitId,
+    is_destination: &RevsetContainingFn<'_>,
+) -> Result<Option<Vec<bool>>, AbsorbError> {
+    if !is_destination(commit_id)? {
+        return Ok(Some(vec![]));
+    }
+    let commit = repo.store().get_commit_async(commit_id).await?;
+    let mut all_parents_are_destinations = true;
+    for parent_id in commit.parent_ids() {
+        if !is_destination(parent_id)? {
+            all_parents_are_destinations = false;
+            break;
+        }
+    }
+    if all_parents_are_destinations {
+        return Ok(None);
+    }
+
+    let text = get_file_contents(repo.store(), path, &commit.tree_async().await?).await?;
+    let mut modified_lines = vec![true; text.split_inclusive(|b| *b == b'\n').count()];
+    for parent in commit.parents_async().await? {
+        let parent_text =
+            get_file_contents(repo.store(), path, &parent.tree_async().await?).await?;
+        let diff = Diff::by_line([&text, &parent_text]);
+        let mut line_number = 0;
+        for hunk in diff.hunks() {
+            let count = hunk.contents[0].split_inclusive(|b| *b == b'\n').count();
+            if hunk.kind == DiffHunkKind::Matching {
+                modified_lines[line_number..line_number + count].fill(false);
+            }
+            line_number += count;
+        }
+    }
+    Ok(Some(modified_lines))
+}
+
 type SelectedRange = (Range<usize>, Range<usize>);
 
 /// Maps `diff` hunks to commits based on the left `annotation_ranges`. The
-/// `annotation_ranges` should be compacted.
-fn split_file_hunks<'a>(
+/// `annotation_ranges` should be compacted. Destinations of hunks other than
+/// pure deletions are chosen by `select_destination`.
+fn map_file_hunks<'a>(
     mut annotation_ranges: &[(&'a CommitId, Range<usize>)],
     diff: &Diff,
+    mut select_destination: impl FnMut(&AbsorbHunk<'a>) -> Option<&'a CommitId>,
 ) -> HashMap<&'a CommitId, Vec<SelectedRange>> {
     debug_assert!(annotation_ranges.iter().all(|(_, range)| !range.is_empty()));
     let mut selected_ranges: HashMap<&CommitId, Vec<_>> = HashMap::new();
@@ -228,24 +439,48 @@ fn split_file_hunks<'a>(
                 }
             }
         } else {
-            // In other cases, the hunk should be included in an annotation
-            // range to map it unambiguously. Skip any pre-overlapped ranges.
+            // In other cases, collect the annotation ranges overlapping with
+            // the hunk. If the hunk is pure insertion, it can be mapped to two
+            // adjacent annotation ranges.
+            let skip = annotation_ranges
+                .iter()
+                .take_while(|(_, range)| range.end < left_range.start)
+                .count();
+            annotation_ranges = &annotation_ranges[skip..];
+            let overlapped_ranges = annotation_ranges
+                .iter()
+                .take_while(|(_, range)| range.start <= left_range.end)
+                .filter(|(_, range)| {
+                    left_range.is_empty()
+                        || (range.start < left_range.end && left_range.start < range.end)
+                })
+                .collect_vec();
+            let covered = !overlapped_ranges.is_empty()
+                && overlapped_ranges
+                    .iter()
+                    .try_fold(left_range.start, |prev_end, (_, cur)| {
+                        (cur.start <= prev_end).then_some(cur.end)
+                    })
+                    .is_some_and(|last_end| left_range.end <= last_end);
+            let hunk = AbsorbHunk {
+                left_range: left_range.clone(),
+                right_range: right_range.clone(),
+                candidates: overlapped_ranges
+                    .iter()
+                    .map(|&&(commit_id, _)| commit_id)
+                    .collect(),
+                covered,
+            };
+            // Skip any pre-overlapped ranges.
             let skip = annotation_ranges
                 .iter()
                 .take_while(|(_, range)| range.end < left_range.end)
                 .count();
             annotation_ranges = &annotation_ranges[skip..];
-            let Some((commit_id, cur_range)) = annotation_ranges.first() else {
+            if hunk.candidates.is_empty() {
                 continue;
-            };
-            let contained = cur_range.start <= left_range.start && left_range.end <= cur_range.end;
-            // If the hunk is pure insertion, it can be mapped to two distinct
-            // annotation ranges, which is ambiguous.
-            let ambiguous = cur_range.end == left_range.start
-                && annotation_ranges
-                    .get(1)
-                    .is_some_and(|(_, next_range)| next_range.start == left_range.end);
-            if contained && !ambiguous {
+            }
+            if let Some(commit_id) = select_destination(&hunk) {
                 let selected = selected_ranges.entry(commit_id).or_default();
                 selected.push((left_range.clone(), right_range.clone()));
             }
@@ -357,6 +592,13 @@ mod tests {
 
     use super::*;
 
+    fn split_file_hunks<'a>(
+        annotation_ranges: &[(&'a CommitId, Range<usize>)],
+        diff: &Diff,
+    ) -> HashMap<&'a CommitId, Vec<SelectedRange>> {
+        map_file_hunks(annotation_ranges, diff, select_unambiguous_destination)
+    }
+
     #[test]
     fn test_split_file_hunks_empty_or_single_line() {
         let commit_id1 = &CommitId::from_hex("111111");
@@ -1093,6 +1335,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_map_file_hunks_ranked_policy() {
+        let commit_id1 = &CommitId::from_hex("111111");
+        let commit_id2 = &CommitId::from_hex("222222");
+        let path = RepoPath::from_internal_string("file").unwrap();
+        // Prefer the second commit if ambiguous
+        let policy = RankedAbsorbPolicy::new(
+            |_: &RepoPath, _: &[u8], _: &[u8], _: &AbsorbHunk<'_>, commit_id: &CommitId| {
+                Some(u64::from(commit_id.hex().starts_with('2')))
+            },
+        );
+        let map_hunks = |left: &str, right: &str| {
+            map_file_hunks(
+                &[(commit_id1, 0..6), (commit_id2, 6..12)],
+                &Diff::by_line([left, right]),
+                |hunk| policy.select_destination(path, left.as_bytes(), right.as_bytes(), hunk),
+            )
+        };
+
+        // insert middle line to first range (unambiguous)
+        assert_eq!(
+            map_hunks("1a\n1b\n2a\n2b\n", "1a\n1X\n1b\n2a\n2b\n"),
+            hashmap! { commit_id1 => vec![(3..3, 3..6)] }
+        );
+        // insert middle line between ranges
+        assert_eq!(
+            map_hunks("1a\n1b\n2a\n2b\n", "1a\n1b\n3X\n2a\n2b\n"),
+            hashmap! { commit_id2 => vec![(6..6, 6..9)] }
+        );
+        // modify lines across ranges
+        assert_eq!(
+            map_hunks("1a\n1b\n2a\n2b\n", "1a\n1X\n2X\n2b\n"),
+            hashmap! { commit_id2 => vec![(3..9, 3..9)] }
+        );
+    }
+
     #[test]
     fn test_combine_texts() {
         assert_eq!(combine_texts(b"", b"", &[]), "");
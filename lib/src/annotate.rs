@@ -432,7 +432,7 @@ fn copy_same_lines_with(
     }
 }
 
-async fn get_file_contents(
+pub(crate) async fn get_file_contents(
     store: &Store,
     path: &RepoPath,
     tree: &MergedTree,
@@ -324,6 +324,16 @@ pub enum BackendError {
         id: FileId,
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    #[error(
+        "Object {hash} of type {object_type} is corrupt (its content hashes to {actual_hash})"
+    )]
+    CorruptObject {
+        object_type: String,
+        hash: String,
+        actual_hash: String,
+        /// Backend-specific guidance on how to recover the object.
+        hint: Option<String>,
+    },
     #[error("Could not write object of type {object_type}")]
     WriteObject {
         object_type: &'static str,
@@ -439,6 +449,13 @@ impl Tree {
     }
 }
 
+/// Content of an object read from the backend, to be checked against its id.
+#[derive(Clone, Copy, Debug)]
+pub enum ObjectContent<'a> {
+    File(&'a [u8]),
+    Tree(&'a Tree),
+}
+
 pub fn make_root_commit(root_change_id: ChangeId, empty_tree_id: TreeId) -> Commit {
     let timestamp = Timestamp {
         timestamp: MillisSinceEpoch(0),
@@ -532,6 +549,24 @@ pub trait Backend: Send + Sync + Debug {
 
     async fn read_tree(&self, path: &RepoPath, id: &TreeId) -> BackendResult<Tree>;
 
+    /// Computes the id of an object with the given content, as it would be
+    /// written by this backend.
+    ///
+    /// If object verification is enabled, the `Store` checks file and tree
+    /// objects read from the backend against the ids computed by this
+    /// function. Returns `None` if the id can't be computed from the content,
+    /// in which case the object isn't checked.
+    fn hash_object(&self, _content: ObjectContent<'_>) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Called by the `Store` if the content of the object doesn't match its
+    /// `id`. The backend may move the object out of the way so that it can be
+    /// restored. Returns guidance on how to recover the object.
+    fn quarantine_object(&self, _id: &dyn ObjectId) -> Option<String> {
+        None
+    }
+
     async fn write_tree(&self, path: &RepoPath, contents: &Tree) -> BackendResult<TreeId>;
 
     // Not async because it would force `MergedTree::value()` to be async. We don't
@@ -580,6 +615,21 @@ pub trait Backend: Send + Sync + Debug {
         head: &CommitId,
     ) -> BackendResult<BoxStream<'_, BackendResult<CopyRecord>>>;
 
+    /// Starts buffering objects written by the current thread until
+    /// [`Backend::flush_write_batch()`] is called on the same thread. Objects
+    /// written in the meantime must still be readable by that thread. Writes
+    /// from other threads aren't affected.
+    ///
+    /// This is a hint for backends which can persist many objects more
+    /// efficiently at once. The default implementation does nothing.
+    fn begin_write_batch(&self) {}
+
+    /// Persists the objects buffered since [`Backend::begin_write_batch()`]
+    /// was called on the current thread, and stops buffering.
+    fn flush_write_batch(&self) -> BackendResult<()> {
+        Ok(())
+    }
+
     /// Perform garbage collection.
     ///
     /// All commits found in the `index` won't be removed. In addition to that,
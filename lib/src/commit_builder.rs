@@ -29,6 +29,7 @@ use crate::commit::is_backend_commit_empty;
 use crate::commit::Commit;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
+use crate::settings::CommitterTimestampPolicy;
 use crate::settings::JJRng;
 use crate::settings::SignSettings;
 use crate::settings::UserSettings;
@@ -209,6 +210,15 @@ impl DetachedCommitBuilder {
         let mut commit = backend::Commit::clone(predecessor.store_commit());
         commit.predecessors = vec![predecessor.id().clone()];
         commit.committer = settings.signature();
+        match settings.rewrite_committer_timestamp() {
+            CommitterTimestampPolicy::Now => {}
+            CommitterTimestampPolicy::Preserve => {
+                commit.committer.timestamp = predecessor.committer().timestamp;
+            }
+            CommitterTimestampPolicy::Fixed(timestamp) => {
+                commit.committer.timestamp = timestamp;
+            }
+        }
         // If the user had not configured a name and email before but now they have,
         // update the author fields with the new information.
         if commit.author.name.is_empty()
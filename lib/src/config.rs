@@ -35,6 +35,7 @@ use thiserror::Error;
 use toml_edit::DocumentMut;
 use toml_edit::ImDocument;
 
+pub use crate::config_resolver::interpolate_env_vars;
 pub use crate::config_resolver::migrate;
 pub use crate::config_resolver::resolve;
 pub use crate::config_resolver::ConfigMigrateError;
@@ -200,6 +200,137 @@ fn pop_scope_tables(layer: &mut ConfigLayer) -> Result<toml_edit::ArrayOfTables,
         })
 }
 
+/// Substitutes `${env:NAME}` in string values with the value of the
+/// environment variable `NAME` looked up by `get_env`. References to unset
+/// variables are left as is. `$${env:` can be used to write a literal
+/// `${env:`. Returns new config.
+///
+/// Other `${...}` expressions aren't touched, so values like
+/// `["util", "exec", "--", "sh", "-c", "echo ${1}"]` are kept intact.
+/// Substituted values are kept in their original layers, so the source of
+/// each value is preserved.
+pub fn interpolate_env_vars(
+    source_config: &StackedConfig,
+    get_env: impl Fn(&str) -> Option<String>,
+) -> StackedConfig {
+    let mut layers = source_config.layers().to_vec();
+    for layer in &mut layers {
+        if !item_contains_interpolation(layer.data.as_item()) {
+            continue; // reuse original table
+        }
+        let layer_mut = Arc::make_mut(layer);
+        interpolate_item(layer_mut.data.as_item_mut(), &get_env);
+    }
+    let mut interpolated_config = StackedConfig::empty();
+    interpolated_config.extend_layers(layers);
+    interpolated_config
+}
+
+const ENV_VAR_PREFIX: &str = "${env:";
+
+fn item_contains_interpolation(item: &toml_edit::Item) -> bool {
+    match item {
+        toml_edit::Item::None => false,
+        toml_edit::Item::Value(value) => value_contains_interpolation(value),
+        toml_edit::Item::Table(table) => table
+            .iter()
+            .any(|(_, item)| item_contains_interpolation(item)),
+        toml_edit::Item::ArrayOfTables(tables) => tables
+            .iter()
+            .flat_map(|table| table.iter())
+            .any(|(_, item)| item_contains_interpolation(item)),
+    }
+}
+
+fn value_contains_interpolation(value: &ConfigValue) -> bool {
+    match value {
+        ConfigValue::String(v) => v.value().contains(ENV_VAR_PREFIX),
+        ConfigValue::Array(array) => array.iter().any(value_contains_interpolation),
+        ConfigValue::InlineTable(table) => table
+            .iter()
+            .any(|(_, value)| value_contains_interpolation(value)),
+        _ => false,
+    }
+}
+
+fn interpolate_item(item: &mut toml_edit::Item, get_env: &impl Fn(&str) -> Option<String>) {
+    match item {
+        toml_edit::Item::None => {}
+        toml_edit::Item::Value(value) => interpolate_value(value, get_env),
+        toml_edit::Item::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                interpolate_item(item, get_env);
+            }
+        }
+        toml_edit::Item::ArrayOfTables(tables) => {
+            for table in tables.iter_mut() {
+                for (_, item) in table.iter_mut() {
+                    interpolate_item(item, get_env);
+                }
+            }
+        }
+    }
+}
+
+fn interpolate_value(value: &mut ConfigValue, get_env: &impl Fn(&str) -> Option<String>) {
+    match value {
+        ConfigValue::String(v) => {
+            if let Some(text) = expand_env_vars(v.value(), get_env) {
+                let decor = v.decor().clone();
+                *v = toml_edit::Formatted::new(text);
+                *v.decor_mut() = decor;
+            }
+        }
+        ConfigValue::Array(array) => {
+            for value in array.iter_mut() {
+                interpolate_value(value, get_env);
+            }
+        }
+        ConfigValue::InlineTable(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate_value(value, get_env);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Expands `${env:NAME}` in `text`. Returns `None` if there's nothing to
+/// expand.
+fn expand_env_vars(text: &str, get_env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if !text.contains(ENV_VAR_PREFIX) {
+        return None;
+    }
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(tail) = rest.strip_prefix("$${env:") {
+            expanded.push_str(ENV_VAR_PREFIX);
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix(ENV_VAR_PREFIX) {
+            let var_value = tail
+                .split_once('}')
+                .filter(|(var_name, _)| !var_name.is_empty())
+                .and_then(|(var_name, tail)| Some((get_env(var_name)?, tail)));
+            if let Some((var_value, tail)) = var_value {
+                expanded.push_str(&var_value);
+                rest = tail;
+            } else {
+                // Leave unclosed or unresolved reference as is
+                expanded.push_str(ENV_VAR_PREFIX);
+                rest = tail;
+            }
+        } else {
+            expanded.push('$');
+            rest = &rest[1..];
+        }
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
+
 /// Error that can occur when migrating config variables.
 #[derive(Debug, Error)]
 #[error("Migration failed")]
@@ -798,6 +929,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_env_vars() {
+        let get_env = |name: &str| (name == "FOO").then(|| "foo".to_owned());
+        assert_eq!(expand_env_vars("a $b ${FOO}", get_env), None);
+        assert_eq!(
+            expand_env_vars("${env:FOO}/${env:FOO}", get_env),
+            Some("foo/foo".to_owned())
+        );
+        assert_eq!(
+            expand_env_vars("$${env:FOO} ${env:FOO}$ ${1}", get_env),
+            Some("${env:FOO} foo$ ${1}".to_owned())
+        );
+        // Unresolved references are left as is
+        assert_eq!(
+            expand_env_vars("${env:BAR} ${env:FOO}", get_env),
+            Some("${env:BAR} foo".to_owned())
+        );
+        assert_eq!(
+            expand_env_vars("${env:}", get_env),
+            Some("${env:}".to_owned())
+        );
+        assert_eq!(
+            expand_env_vars("${env:FOO", get_env),
+            Some("${env:FOO".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_interpolate_env_vars() {
+        let mut source_config = StackedConfig::empty();
+        source_config.add_layer(new_user_layer(indoc! {"
+            a = 'plain ${FOO}'
+        "}));
+        let mut layer = new_user_layer(indoc! {"
+            b = '${env:FOO}' # comment
+            c = ['${env:FOO}', 1, { d = 'x${env:FOO}x' }]
+            [[e]]
+            f = '${env:FOO} ${env:BAR}'
+        "});
+        layer.path = Some("source.toml".into());
+        source_config.add_layer(layer);
+
+        let get_env = |name: &str| (name == "FOO").then(|| "foo".to_owned());
+        let config = interpolate_env_vars(&source_config, get_env);
+        assert!(Arc::ptr_eq(&source_config.layers()[0], &config.layers()[0]));
+        insta::assert_snapshot!(config.layers()[1].data, @r#"
+        b = "foo" # comment
+        c = ["foo", 1, { d = "xfoox" }]
+        [[e]]
+        f = "foo ${env:BAR}"
+        "#);
+        assert_eq!(config.layers()[1].path, Some("source.toml".into()));
+    }
+
     #[test]
     fn test_migrate_noop() {
         let mut config = StackedConfig::empty();
@@ -14,6 +14,7 @@
 
 #![allow(missing_docs)]
 
+use std::collections::HashSet;
 use std::io;
 use std::io::Write;
 use std::iter::zip;
@@ -27,6 +28,7 @@ use futures::Stream;
 use futures::StreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
+use thiserror::Error;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt as _;
 
@@ -48,6 +50,7 @@ use crate::files::MergeResult;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::store::Store;
 
 /// Minimum length of conflict markers.
@@ -395,6 +398,62 @@ pub fn choose_materialized_conflict_marker_len<T: AsRef<[u8]>>(single_hunk: &Mer
         .max(MIN_CONFLICT_MARKER_LEN)
 }
 
+/// Labels describing where the terms of a conflict came from.
+///
+/// If set, the labels are appended to the headers of the conflict markers,
+/// e.g. `+++++++ Contents of side #2 (parent of abc123)`. Labels are only used
+/// if the number of terms matches the conflict to be materialized, and if the
+/// labels [apply](Self::applies_to) to the path of the conflict.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConflictLabels {
+    labels: Option<Merge<String>>,
+    /// Paths of the conflicts to be labeled, or `None` for any path.
+    paths: Option<HashSet<RepoPathBuf>>,
+}
+
+impl ConflictLabels {
+    /// Creates labels which add nothing to the conflict markers.
+    pub fn unlabeled() -> Self {
+        Self::default()
+    }
+
+    /// Creates labels for the corresponding terms of conflicts.
+    pub fn from_merge(labels: Merge<String>) -> Self {
+        ConflictLabels {
+            labels: Some(labels),
+            paths: None,
+        }
+    }
+
+    /// Restricts the labels to the conflicts at the given `paths`.
+    pub fn with_paths(self, paths: impl IntoIterator<Item = RepoPathBuf>) -> Self {
+        ConflictLabels {
+            paths: Some(paths.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Returns true if there are no labels.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_none()
+    }
+
+    /// Returns true if the conflict at the `path` should be labeled.
+    pub fn applies_to(&self, path: &RepoPath) -> bool {
+        self.labels.is_some() && self.paths.as_ref().is_none_or(|paths| paths.contains(path))
+    }
+
+    fn for_hunk<T>(&self, hunk: &Merge<T>) -> Option<&Merge<String>> {
+        self.labels
+            .as_ref()
+            .filter(|labels| labels.num_sides() == hunk.num_sides())
+    }
+}
+
+fn label_suffix(label: Option<&String>) -> String {
+    label.map(|label| format!(" ({label})")).unwrap_or_default()
+}
+
 pub fn materialize_merge_result<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
@@ -405,7 +464,13 @@ pub fn materialize_merge_result<T: AsRef<[u8]>>(
         MergeResult::Resolved(content) => output.write_all(content),
         MergeResult::Conflict(hunks) => {
             let conflict_marker_len = choose_materialized_conflict_marker_len(single_hunk);
-            materialize_conflict_hunks(hunks, conflict_marker_style, conflict_marker_len, output)
+            materialize_conflict_hunks(
+                hunks,
+                conflict_marker_style,
+                conflict_marker_len,
+                &ConflictLabels::unlabeled(),
+                output,
+            )
         }
     }
 }
@@ -419,9 +484,13 @@ pub fn materialize_merge_result_with_marker_len<T: AsRef<[u8]>>(
     let merge_result = files::merge_hunks(single_hunk);
     match &merge_result {
         MergeResult::Resolved(content) => output.write_all(content),
-        MergeResult::Conflict(hunks) => {
-            materialize_conflict_hunks(hunks, conflict_marker_style, conflict_marker_len, output)
-        }
+        MergeResult::Conflict(hunks) => materialize_conflict_hunks(
+            hunks,
+            conflict_marker_style,
+            conflict_marker_len,
+            &ConflictLabels::unlabeled(),
+            output,
+        ),
     }
 }
 
@@ -439,6 +508,7 @@ pub fn materialize_merge_result_to_bytes<T: AsRef<[u8]>>(
                 &hunks,
                 conflict_marker_style,
                 conflict_marker_len,
+                &ConflictLabels::unlabeled(),
                 &mut output,
             )
             .expect("writing to an in-memory buffer should never fail");
@@ -451,6 +521,22 @@ pub fn materialize_merge_result_to_bytes_with_marker_len<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
+) -> BString {
+    materialize_merge_result_to_bytes_with_labels(
+        single_hunk,
+        conflict_marker_style,
+        conflict_marker_len,
+        &ConflictLabels::unlabeled(),
+    )
+}
+
+/// Like [`materialize_merge_result_to_bytes_with_marker_len()`], but annotates
+/// the conflict markers with the given `labels`.
+pub fn materialize_merge_result_to_bytes_with_labels<T: AsRef<[u8]>>(
+    single_hunk: &Merge<T>,
+    conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_len: usize,
+    labels: &ConflictLabels,
 ) -> BString {
     let merge_result = files::merge_hunks(single_hunk);
     match merge_result {
@@ -461,6 +547,7 @@ pub fn materialize_merge_result_to_bytes_with_marker_len<T: AsRef<[u8]>>(
                 &hunks,
                 conflict_marker_style,
                 conflict_marker_len,
+                labels,
                 &mut output,
             )
             .expect("writing to an in-memory buffer should never fail");
@@ -473,6 +560,7 @@ fn materialize_conflict_hunks(
     hunks: &[Merge<BString>],
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
+    labels: &ConflictLabels,
     output: &mut dyn Write,
 ) -> io::Result<()> {
     let num_conflicts = hunks
@@ -486,6 +574,7 @@ fn materialize_conflict_hunks(
         } else {
             conflict_index += 1;
             let conflict_info = format!("Conflict {conflict_index} of {num_conflicts}");
+            let hunk_labels = labels.for_hunk(hunk);
 
             match (conflict_marker_style, hunk.as_slice()) {
                 // 2-sided conflicts can use Git-style conflict markers
@@ -496,6 +585,7 @@ fn materialize_conflict_hunks(
                         right,
                         &conflict_info,
                         conflict_marker_len,
+                        hunk_labels,
                         output,
                     )?;
                 }
@@ -505,6 +595,7 @@ fn materialize_conflict_hunks(
                         &conflict_info,
                         conflict_marker_style,
                         conflict_marker_len,
+                        hunk_labels,
                         output,
                     )?;
                 }
@@ -520,13 +611,20 @@ fn materialize_git_style_conflict(
     right: &[u8],
     conflict_info: &str,
     conflict_marker_len: usize,
+    labels: Option<&Merge<String>>,
     output: &mut dyn Write,
 ) -> io::Result<()> {
+    let add_label = |index| label_suffix(labels.and_then(|labels| labels.get_add(index)));
+    let base_label = label_suffix(labels.and_then(|labels| labels.get_remove(0)));
     write_conflict_marker(
         output,
         ConflictMarkerLineChar::ConflictStart,
         conflict_marker_len,
-        &format!("Side #1 ({conflict_info})"),
+        &format!(
+            "Side #1 ({conflict_info}){}{}",
+            add_label(0),
+            maybe_no_eol_comment(left)
+        ),
     )?;
     write_and_ensure_newline(output, left)?;
 
@@ -534,11 +632,12 @@ fn materialize_git_style_conflict(
         output,
         ConflictMarkerLineChar::GitAncestor,
         conflict_marker_len,
-        "Base",
+        &format!("Base{base_label}{}", maybe_no_eol_comment(base)),
     )?;
     write_and_ensure_newline(output, base)?;
 
-    // VS Code doesn't seem to support any trailing text on the separator line
+    // VS Code doesn't seem to support any trailing text on the separator line,
+    // so the missing newline of side #2 is noted on the end marker instead
     write_conflict_marker(
         output,
         ConflictMarkerLineChar::GitSeparator,
@@ -551,7 +650,11 @@ fn materialize_git_style_conflict(
         output,
         ConflictMarkerLineChar::ConflictEnd,
         conflict_marker_len,
-        &format!("Side #2 ({conflict_info} ends)"),
+        &format!(
+            "Side #2 ({conflict_info} ends){}{}",
+            add_label(1),
+            maybe_no_eol_comment(right)
+        ),
     )?;
 
     Ok(())
@@ -562,8 +665,12 @@ fn materialize_jj_style_conflict(
     conflict_info: &str,
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
+    labels: Option<&Merge<String>>,
     output: &mut dyn Write,
 ) -> io::Result<()> {
+    let add_label = |index| label_suffix(labels.and_then(|labels| labels.get_add(index)));
+    let remove_label = |index| label_suffix(labels.and_then(|labels| labels.get_remove(index)));
+
     // Write a positive snapshot (side) of a conflict
     let write_side = |add_index: usize, data: &[u8], output: &mut dyn Write| {
         write_conflict_marker(
@@ -571,8 +678,9 @@ fn materialize_jj_style_conflict(
             ConflictMarkerLineChar::Add,
             conflict_marker_len,
             &format!(
-                "Contents of side #{}{}",
+                "Contents of side #{}{}{}",
                 add_index + 1,
+                add_label(add_index),
                 maybe_no_eol_comment(data)
             ),
         )?;
@@ -580,42 +688,51 @@ fn materialize_jj_style_conflict(
     };
 
     // Write a negative snapshot (base) of a conflict
-    let write_base = |base_str: &str, data: &[u8], output: &mut dyn Write| {
+    let write_base = |base_index: usize, base_str: &str, data: &[u8], output: &mut dyn Write| {
         write_conflict_marker(
             output,
             ConflictMarkerLineChar::Remove,
             conflict_marker_len,
-            &format!("Contents of {base_str}{}", maybe_no_eol_comment(data)),
+            &format!(
+                "Contents of {base_str}{}{}",
+                remove_label(base_index),
+                maybe_no_eol_comment(data)
+            ),
         )?;
         write_and_ensure_newline(output, data)
     };
 
     // Write a diff from a negative term to a positive term
-    let write_diff =
-        |base_str: &str, add_index: usize, diff: &[DiffHunk], output: &mut dyn Write| {
-            let no_eol_remove = diff
-                .last()
-                .is_some_and(|diff_hunk| has_no_eol(diff_hunk.contents[0]));
-            let no_eol_add = diff
-                .last()
-                .is_some_and(|diff_hunk| has_no_eol(diff_hunk.contents[1]));
-            let no_eol_comment = match (no_eol_remove, no_eol_add) {
-                (true, true) => NO_EOL_COMMENT,
-                (true, _) => REMOVE_NO_EOL_COMMENT,
-                (_, true) => ADD_NO_EOL_COMMENT,
-                _ => "",
-            };
-            write_conflict_marker(
-                output,
-                ConflictMarkerLineChar::Diff,
-                conflict_marker_len,
-                &format!(
-                    "Changes from {base_str} to side #{}{no_eol_comment}",
-                    add_index + 1
-                ),
-            )?;
-            write_diff_hunks(diff, output)
+    let write_diff = |base_index: usize,
+                      base_str: &str,
+                      add_index: usize,
+                      diff: &[DiffHunk],
+                      output: &mut dyn Write| {
+        let no_eol_remove = diff
+            .last()
+            .is_some_and(|diff_hunk| has_no_eol(diff_hunk.contents[0]));
+        let no_eol_add = diff
+            .last()
+            .is_some_and(|diff_hunk| has_no_eol(diff_hunk.contents[1]));
+        let no_eol_comment = match (no_eol_remove, no_eol_add) {
+            (true, true) => NO_EOL_COMMENT,
+            (true, _) => REMOVE_NO_EOL_COMMENT,
+            (_, true) => ADD_NO_EOL_COMMENT,
+            _ => "",
         };
+        write_conflict_marker(
+            output,
+            ConflictMarkerLineChar::Diff,
+            conflict_marker_len,
+            &format!(
+                "Changes from {base_str}{} to side #{}{}{no_eol_comment}",
+                remove_label(base_index),
+                add_index + 1,
+                add_label(add_index)
+            ),
+        )?;
+        write_diff_hunks(diff, output)
+    };
 
     write_conflict_marker(
         output,
@@ -636,14 +753,14 @@ fn materialize_jj_style_conflict(
         let Some(right1) = hunk.get_add(add_index) else {
             // If we have no more positive terms, emit the remaining negative terms as
             // snapshots.
-            write_base(&base_str, left, output)?;
+            write_base(base_index, &base_str, left, output)?;
             continue;
         };
 
         // For any style other than "diff", always emit sides and bases separately
         if conflict_marker_style != ConflictMarkerStyle::Diff {
             write_side(add_index, right1, output)?;
-            write_base(&base_str, left, output)?;
+            write_base(base_index, &base_str, left, output)?;
             add_index += 1;
             continue;
         }
@@ -658,13 +775,13 @@ fn materialize_jj_style_conflict(
                 // If the next positive term is a better match, emit the current positive term
                 // as a snapshot and the next positive term as a diff.
                 write_side(add_index, right1, output)?;
-                write_diff(&base_str, add_index + 1, &diff2, output)?;
+                write_diff(base_index, &base_str, add_index + 1, &diff2, output)?;
                 add_index += 2;
                 continue;
             }
         }
 
-        write_diff(&base_str, add_index, &diff1, output)?;
+        write_diff(base_index, &base_str, add_index, &diff1, output)?;
         add_index += 1;
     }
 
@@ -798,6 +915,153 @@ pub fn parse_conflict(
     }
 }
 
+/// A hunk which looks like a conflict but couldn't be parsed as one. Its
+/// contents are kept as regular text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MalformedConflictHunk {
+    /// Line number (1-based) of the conflict start marker.
+    pub line: usize,
+    /// Why the hunk couldn't be parsed.
+    pub reason: MalformedConflictReason,
+}
+
+/// Reason why a conflict hunk couldn't be parsed.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum MalformedConflictReason {
+    /// The conflict start marker has no matching end marker.
+    #[error("Conflict has no end marker")]
+    Unterminated,
+    /// The conflict markers between the start and end markers are invalid.
+    #[error("Conflict markers are missing, out of order, or malformed")]
+    InvalidMarkers,
+    /// The conflict doesn't have the same number of sides as the original.
+    #[error("Conflict has {actual} sides, but {expected} sides were expected")]
+    WrongNumberOfSides {
+        /// Number of sides of the original conflict.
+        expected: usize,
+        /// Number of sides parsed from the conflict markers.
+        actual: usize,
+    },
+}
+
+/// Parses conflict markers from a slice, detecting the marker length and style
+/// of each hunk separately.
+///
+/// Unlike [`parse_conflict()`], conflict markers shorter than the expected
+/// length are accepted, so conflicts are still parsed if the user edited the
+/// marker lengths. To avoid misinterpreting file contents as conflicts, a
+/// shorter start marker is only accepted if it has a label like "Conflict 1 of
+/// 2" written by jj, and if the line doesn't appear in any term of the
+/// `original` conflict. Hunks which look like conflicts but can't be parsed are
+/// reported as [`MalformedConflictHunk`]s instead of being silently treated as
+/// resolved text.
+pub fn parse_conflict_leniently<T: AsRef<[u8]>>(
+    input: &[u8],
+    original: &Merge<T>,
+    expected_marker_len: usize,
+) -> (Option<Vec<Merge<BString>>>, Vec<MalformedConflictHunk>) {
+    struct OpenHunk {
+        body_start: usize,
+        line: usize,
+        marker_len: usize,
+    }
+    let num_sides = original.num_sides();
+    let is_original_line = |line: &[u8]| {
+        original
+            .iter()
+            .any(|term| term.as_ref().lines_with_terminator().contains(&line))
+    };
+    let mut hunks = vec![];
+    let mut malformed_hunks = vec![];
+    let mut pos = 0;
+    let mut resolved_start = 0;
+    let mut conflict_start: Option<(usize, OpenHunk)> = None;
+    for (index, line) in input.lines_with_terminator().enumerate() {
+        let marker = parse_conflict_marker_any_len(line)
+            .filter(|marker| marker.len >= MIN_CONFLICT_MARKER_LEN);
+        // Markers shorter than the start marker are part of the hunk contents.
+        let open_marker_len = conflict_start.as_ref().map(|(_, hunk)| hunk.marker_len);
+        match marker {
+            Some(ConflictMarkerLine {
+                kind: ConflictMarkerLineChar::ConflictStart,
+                len,
+            }) if open_marker_len.is_none_or(|marker_len| len >= marker_len) => {
+                if let Some((_, hunk)) = conflict_start.take() {
+                    malformed_hunks.push(MalformedConflictHunk {
+                        line: hunk.line,
+                        reason: MalformedConflictReason::Unterminated,
+                    });
+                }
+                if len >= expected_marker_len
+                    || (has_conflict_label(line) && !is_original_line(line))
+                {
+                    let hunk = OpenHunk {
+                        body_start: pos + line.len(),
+                        line: index + 1,
+                        marker_len: len.min(expected_marker_len),
+                    };
+                    conflict_start = Some((pos, hunk));
+                }
+            }
+            Some(ConflictMarkerLine {
+                kind: ConflictMarkerLineChar::ConflictEnd,
+                len,
+            }) if open_marker_len.is_some_and(|marker_len| len >= marker_len) => {
+                let (start, hunk) = conflict_start.take().unwrap();
+                let body = &input[hunk.body_start..pos];
+                let parsed = parse_conflict_hunk(body, hunk.marker_len);
+                if !parsed.is_resolved() && parsed.num_sides() == num_sides {
+                    let resolved_slice = &input[resolved_start..start];
+                    if !resolved_slice.is_empty() {
+                        hunks.push(Merge::resolved(BString::from(resolved_slice)));
+                    }
+                    hunks.push(parsed);
+                    resolved_start = pos + line.len();
+                } else {
+                    let reason = if parsed.is_resolved() {
+                        MalformedConflictReason::InvalidMarkers
+                    } else {
+                        MalformedConflictReason::WrongNumberOfSides {
+                            expected: num_sides,
+                            actual: parsed.num_sides(),
+                        }
+                    };
+                    malformed_hunks.push(MalformedConflictHunk {
+                        line: hunk.line,
+                        reason,
+                    });
+                }
+            }
+            _ => {}
+        }
+        pos += line.len();
+    }
+    if let Some((_, hunk)) = conflict_start {
+        malformed_hunks.push(MalformedConflictHunk {
+            line: hunk.line,
+            reason: MalformedConflictReason::Unterminated,
+        });
+    }
+
+    if hunks.is_empty() {
+        (None, malformed_hunks)
+    } else {
+        if resolved_start < input.len() {
+            hunks.push(Merge::resolved(BString::from(&input[resolved_start..])));
+        }
+        (Some(hunks), malformed_hunks)
+    }
+}
+
+/// Returns true if the conflict start marker `line` has a label like
+/// "Conflict 1 of 2".
+fn has_conflict_label(line: &[u8]) -> bool {
+    line.find(b"Conflict ").is_some_and(|index| {
+        line.get(index + "Conflict ".len())
+            .is_some_and(u8::is_ascii_digit)
+    })
+}
+
 /// This method handles parsing both JJ-style and Git-style conflict markers,
 /// meaning that switching conflict marker styles won't prevent existing files
 /// with other conflict marker styles from being parsed successfully. The
@@ -957,6 +1221,29 @@ pub async fn update_from_content(
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
 ) -> BackendResult<Merge<Option<FileId>>> {
+    let (new_file_ids, _malformed_hunks) = update_from_content_with_diagnostics(
+        file_ids,
+        store,
+        path,
+        content,
+        conflict_marker_style,
+        conflict_marker_len,
+    )
+    .await?;
+    Ok(new_file_ids)
+}
+
+/// Like [`update_from_content()`], but also returns the hunks which looked
+/// like conflicts but couldn't be parsed. Conflict markers are parsed
+/// leniently by [`parse_conflict_leniently()`].
+pub async fn update_from_content_with_diagnostics(
+    file_ids: &Merge<Option<FileId>>,
+    store: &Store,
+    path: &RepoPath,
+    content: &[u8],
+    conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_len: usize,
+) -> BackendResult<(Merge<Option<FileId>>, Vec<MalformedConflictHunk>)> {
     let simplified_file_ids = file_ids.simplify();
 
     // First check if the new content is unchanged compared to the old content. If
@@ -974,19 +1261,17 @@ pub async fn update_from_content(
     )
     .unwrap();
     if content == old_content {
-        return Ok(file_ids.clone());
+        return Ok((file_ids.clone(), vec![]));
     }
 
     // Parse conflicts from the new content using the arity of the simplified
     // conflicts.
-    let Some(mut hunks) = parse_conflict(
-        content,
-        simplified_file_ids.num_sides(),
-        conflict_marker_len,
-    ) else {
+    let (hunks, malformed_hunks) =
+        parse_conflict_leniently(content, &merge_hunk, conflict_marker_len);
+    let Some(mut hunks) = hunks else {
         // Either there are no markers or they don't have the expected arity
         let file_id = store.write_file(path, &mut &content[..]).await?;
-        return Ok(Merge::normal(file_id));
+        return Ok((Merge::normal(file_id), malformed_hunks));
     };
 
     // If there is a conflict at the end of the file and a term ends with a newline,
@@ -1019,7 +1304,7 @@ pub async fn update_from_content(
         .any(|(content, file_id)| file_id.is_none() && !content.is_empty())
     {
         let file_id = store.write_file(path, &mut &content[..]).await?;
-        return Ok(Merge::normal(file_id));
+        return Ok((Merge::normal(file_id), malformed_hunks));
     }
 
     // Now write the new files contents we found by parsing the file with conflict
@@ -1050,7 +1335,7 @@ pub async fn update_from_content(
     } else {
         Merge::from_vec(new_file_ids)
     };
-    Ok(new_file_ids)
+    Ok((new_file_ids, malformed_hunks))
 }
 
 #[cfg(test)]
@@ -57,6 +57,29 @@ use crate::store::Store;
 
 id_type!(pub(super) CommitIndexSegmentId { hex() });
 
+/// Minimum number of candidates to compute heads or roots by using bit set.
+/// For small candidate sets, walking ancestors through a priority queue is
+/// cheaper.
+const BIT_SET_MIN_CANDIDATES: usize = 64;
+
+/// Maximum average distance between the candidates to compute heads or roots
+/// by using bit set. The bit set walk visits every position in the range, which
+/// is wasteful if a few candidates are spread over a long history.
+const BIT_SET_MAX_CANDIDATE_SPACING: u64 = 16;
+
+/// Returns true if heads or roots of the `candidate_positions`, sorted in
+/// descending order, should be computed by scanning the range with bit set.
+pub(super) fn should_use_bit_set(candidate_positions: &[GlobalCommitPosition]) -> bool {
+    let (Some(max_pos), Some(min_pos)) = (candidate_positions.first(), candidate_positions.last())
+    else {
+        return false;
+    };
+    let count = candidate_positions.len();
+    count >= BIT_SET_MIN_CANDIDATES
+        && u64::try_from(count).unwrap() * BIT_SET_MAX_CANDIDATE_SPACING
+            >= u64::from(max_pos.0 - min_pos.0)
+}
+
 pub(super) trait CommitIndexSegment: Send + Sync {
     fn num_parent_commits(&self) -> u32;
 
@@ -443,6 +466,9 @@ impl CompositeCommitIndex {
         candidate_positions: Vec<GlobalCommitPosition>,
     ) -> Vec<GlobalCommitPosition> {
         debug_assert!(candidate_positions.is_sorted_by(|a, b| a > b));
+        if should_use_bit_set(&candidate_positions) {
+            return self.heads_pos_by_bit_set(candidate_positions);
+        }
         let Some(min_generation) = candidate_positions
             .iter()
             .map(|&pos| self.entry_by_pos(pos).generation_number())
@@ -477,6 +503,85 @@ impl CompositeCommitIndex {
         heads
     }
 
+    /// Computes heads of the `candidate_positions` by propagating reachability
+    /// bits from descendants to parents.
+    ///
+    /// Only the positions between the first and the last candidates are
+    /// visited, and each entry in that range is loaded at most once. This is
+    /// faster than [`Self::heads_pos()`] if the candidates are dense.
+    pub(super) fn heads_pos_by_bit_set(
+        &self,
+        candidate_positions: Vec<GlobalCommitPosition>,
+    ) -> Vec<GlobalCommitPosition> {
+        let (Some(&max_pos), Some(&min_pos)) =
+            (candidate_positions.first(), candidate_positions.last())
+        else {
+            return candidate_positions;
+        };
+        // Ancestors of the candidates visited so far
+        let mut reachable = PositionsBitSet::with_max_pos(max_pos);
+        let mut candidates_iter = candidate_positions.iter().copied().peekable();
+        let mut heads = Vec::new();
+        for pos in (min_pos.0..=max_pos.0).rev().map(GlobalCommitPosition) {
+            let is_candidate = candidates_iter.next_if_eq(&pos).is_some();
+            let is_reachable = reachable.get(pos);
+            if is_candidate && !is_reachable {
+                heads.push(pos);
+            } else if !is_reachable {
+                continue;
+            }
+            for parent_pos in self.entry_by_pos(pos).parent_positions() {
+                if parent_pos >= min_pos {
+                    reachable.set(parent_pos);
+                }
+            }
+        }
+        heads
+    }
+
+    /// Returns the subset of positions in `candidate_positions` which aren't
+    /// descendants of the other candidates.
+    ///
+    /// The `candidate_positions` must be sorted in descending order, and have
+    /// no duplicates. The returned root positions are also sorted in
+    /// descending order.
+    ///
+    /// Like [`Self::heads_pos_by_bit_set()`], every position between the first
+    /// and the last candidates is visited.
+    pub(super) fn roots_pos_by_bit_set(
+        &self,
+        candidate_positions: Vec<GlobalCommitPosition>,
+    ) -> Vec<GlobalCommitPosition> {
+        debug_assert!(candidate_positions.is_sorted_by(|a, b| a > b));
+        let (Some(&max_pos), Some(&min_pos)) =
+            (candidate_positions.first(), candidate_positions.last())
+        else {
+            return candidate_positions;
+        };
+        // Candidates and their descendants visited so far. Since parents have
+        // smaller positions than children, the bits can be propagated by
+        // visiting the range in ascending order.
+        let mut descendants = PositionsBitSet::with_max_pos(max_pos);
+        let mut candidates_iter = candidate_positions.iter().rev().copied().peekable();
+        let mut roots = Vec::new();
+        for pos in (min_pos.0..=max_pos.0).map(GlobalCommitPosition) {
+            let is_candidate = candidates_iter.next_if_eq(&pos).is_some();
+            let has_marked_parent = self
+                .entry_by_pos(pos)
+                .parent_positions()
+                .iter()
+                .any(|&parent_pos| parent_pos >= min_pos && descendants.get(parent_pos));
+            if is_candidate && !has_marked_parent {
+                roots.push(pos);
+            }
+            if is_candidate || has_marked_parent {
+                descendants.set(pos);
+            }
+        }
+        roots.reverse();
+        roots
+    }
+
     /// Find the heads of a range of positions `roots..heads`, applying a filter
     /// to the commits in the range. The heads are sorted in descending order.
     /// The filter will also be called in descending index position order.
@@ -56,6 +56,7 @@ mod tests {
     use smallvec::smallvec_inline;
     use test_case::test_case;
 
+    use super::composite::should_use_bit_set;
     use super::composite::AsCompositeIndex as _;
     use super::composite::CommitIndexSegment as _;
     use super::composite::CompositeCommitIndex;
@@ -1257,6 +1258,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_use_bit_set() {
+        let positions = |range: std::ops::Range<u32>, step: usize| {
+            range
+                .rev()
+                .step_by(step)
+                .map(GlobalCommitPosition)
+                .collect_vec()
+        };
+        assert!(!should_use_bit_set(&[]));
+        assert!(!should_use_bit_set(&positions(0..63, 1)));
+        assert!(should_use_bit_set(&positions(0..64, 1)));
+        assert!(should_use_bit_set(&positions(0..64 * 16, 16)));
+        // Sparse candidates spread over a long history
+        assert!(!should_use_bit_set(&positions(0..64 * 100, 100)));
+    }
+
+    #[test]
+    fn test_heads_and_roots_by_bit_set() {
+        let mut new_change_id = change_id_generator();
+        let mut index = DefaultMutableIndex::full(TEST_FIELD_LENGTHS);
+        // 5
+        // |\
+        // 4 | 3
+        // | |/
+        // 1 2
+        // |/
+        // 0
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_5 = CommitId::from_hex("555555");
+        index.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        index.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_2.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_3.clone(), new_change_id(), &[id_2.clone()]);
+        index.add_commit_data(id_4.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_5.clone(), new_change_id(), &[id_4.clone(), id_2.clone()]);
+
+        let commits = index.as_composite().commits();
+        let to_positions = |ids: &[&CommitId]| {
+            ids.iter()
+                .map(|id| commits.commit_id_to_pos(id).unwrap())
+                .sorted_by_key(|&pos| Reverse(pos))
+                .collect_vec()
+        };
+        let to_ids = |positions: Vec<GlobalCommitPosition>| {
+            positions
+                .into_iter()
+                .map(|pos| commits.entry_by_pos(pos).commit_id())
+                .collect_vec()
+        };
+        let heads = |ids: &[&CommitId]| {
+            let positions = to_positions(ids);
+            let heads = commits.heads_pos_by_bit_set(positions.clone());
+            // Should be consistent with the queue-based implementation
+            assert_eq!(heads, commits.heads_pos(positions));
+            to_ids(heads)
+        };
+        let roots = |ids: &[&CommitId]| to_ids(commits.roots_pos_by_bit_set(to_positions(ids)));
+
+        assert!(heads(&[]).is_empty());
+        assert_eq!(heads(&[&id_4, &id_1]), vec![id_4.clone()]);
+        assert_eq!(heads(&[&id_4, &id_0]), vec![id_4.clone()]);
+        assert_eq!(heads(&[&id_3, &id_4]), vec![id_4.clone(), id_3.clone()]);
+        assert_eq!(heads(&[&id_5, &id_2]), vec![id_5.clone()]);
+        assert_eq!(
+            heads(&[&id_5, &id_3, &id_1, &id_0]),
+            vec![id_5.clone(), id_3.clone()]
+        );
+
+        assert!(roots(&[]).is_empty());
+        assert_eq!(roots(&[&id_4, &id_1]), vec![id_1.clone()]);
+        assert_eq!(roots(&[&id_3, &id_4]), vec![id_4.clone(), id_3.clone()]);
+        assert_eq!(
+            roots(&[&id_5, &id_3, &id_1]),
+            vec![id_3.clone(), id_1.clone()]
+        );
+        assert_eq!(roots(&[&id_5, &id_3]), vec![id_5.clone(), id_3.clone()]);
+        assert_eq!(roots(&[&id_5, &id_2, &id_0]), vec![id_0.clone()]);
+    }
+
     #[test]
     fn test_heads_range_with_filter() {
         let mut new_change_id = change_id_generator();
@@ -31,6 +31,7 @@ use futures::StreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
 
+use super::composite::should_use_bit_set;
 use super::composite::AsCompositeIndex;
 use super::composite::CompositeIndex;
 use super::entry::GlobalCommitPosition;
@@ -62,10 +63,12 @@ use crate::revset::Revset;
 use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetFilterPredicate;
+use crate::revset::SignatureField;
 use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
 use crate::store::Store;
 use crate::str_util::StringPattern;
+use crate::trailer::parse_description_trailers;
 use crate::union_find;
 
 type BoxedPredicateFn<'a> = Box<
@@ -983,18 +986,22 @@ impl EvaluationContext<'_> {
                     .positions()
                     .attach(index)
                     .try_collect()?;
-                let filled = RevWalkBuilder::new(index)
-                    .wanted_heads(positions.clone())
-                    .descendants(positions.iter().copied().collect())
-                    .collect_positions_set();
-                positions.retain(|&pos| {
-                    !index
-                        .commits()
-                        .entry_by_pos(pos)
-                        .parent_positions()
-                        .iter()
-                        .any(|parent| filled.contains(parent))
-                });
+                if should_use_bit_set(&positions) {
+                    positions = index.commits().roots_pos_by_bit_set(positions);
+                } else {
+                    let filled = RevWalkBuilder::new(index)
+                        .wanted_heads(positions.clone())
+                        .descendants(positions.iter().copied().collect())
+                        .collect_positions_set();
+                    positions.retain(|&pos| {
+                        !index
+                            .commits()
+                            .entry_by_pos(pos)
+                            .parent_positions()
+                            .iter()
+                            .any(|parent| filled.contains(parent))
+                    });
+                }
                 Ok(Box::new(EagerRevset { positions }))
             }
             ResolvedExpression::ForkPoint(expression) => {
@@ -1218,6 +1225,18 @@ fn build_predicate_fn(
                 Ok(pattern.is_match(commit.description().lines().next().unwrap_or_default()))
             })
         }
+        RevsetFilterPredicate::Trailer { key, value } => {
+            let key = key.clone();
+            let value = value.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.commits().entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let trailers = parse_description_trailers(commit.description());
+                Ok(trailers
+                    .iter()
+                    .any(|trailer| trailer.key == key && value.is_match(&trailer.value)))
+            })
+        }
         RevsetFilterPredicate::AuthorName(pattern) => {
             let pattern = pattern.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1268,6 +1287,31 @@ fn build_predicate_fn(
                 Ok(expression.matches(committer_date))
             })
         }
+        RevsetFilterPredicate::MailmapSignature {
+            mailmap,
+            field,
+            pattern,
+        } => {
+            let mailmap = mailmap.clone();
+            let field = *field;
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.commits().entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let signature = match field {
+                    SignatureField::AuthorName | SignatureField::AuthorEmail => commit.author(),
+                    SignatureField::CommitterName | SignatureField::CommitterEmail => {
+                        commit.committer()
+                    }
+                };
+                let (name, email) = mailmap.resolve(&signature.name, &signature.email);
+                let text = match field {
+                    SignatureField::AuthorName | SignatureField::CommitterName => name,
+                    SignatureField::AuthorEmail | SignatureField::CommitterEmail => email,
+                };
+                Ok(pattern.is_match(text))
+            })
+        }
         RevsetFilterPredicate::File(expr) => {
             let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
             box_pure_predicate_fn(move |index, pos| {
@@ -1299,6 +1343,22 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.has_conflict()?)
         }),
+        RevsetFilterPredicate::HasConflictIn(expr) => {
+            let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.commits().entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                // Fast path: no need to load the tree if the commit has no conflicts
+                if !commit.has_conflict()? {
+                    return Ok(false);
+                }
+                let tree = commit.tree()?;
+                match tree.conflicts_matching(&*matcher).next() {
+                    Some((_path, values)) => Ok(values.map(|_| true)?),
+                    None => Ok(false),
+                }
+            })
+        }
         RevsetFilterPredicate::Signed => box_pure_predicate_fn(move |index, pos| {
             let entry = index.commits().entry_by_pos(pos);
             let commit = store.get_commit(&entry.commit_id())?;
@@ -1335,6 +1395,14 @@ async fn has_diff_from_parent(
     // Conflict resolution is expensive, try that only for matched files.
     let from_tree =
         rewrite::merge_commit_trees_no_resolve_without_repo(store, index, &parents).await?;
+    if matcher.visit(RepoPath::root()) == Visit::AllRecursively {
+        // Fast path for empty(): if the parents can be merged at tree level
+        // to the commit's tree, there's no need to diff file by file.
+        let merged_tree = from_tree.clone().resolve().await?;
+        if merged_tree.id() == *commit.tree_id() {
+            return Ok(false);
+        }
+    }
     let to_tree = commit.tree_async().await?;
     // TODO: handle copy tracking
     let mut tree_diff = from_tree.diff_stream(&to_tree, matcher);
@@ -216,6 +216,31 @@ impl<'i, T> FunctionCallNode<'i, T> {
         }
     }
 
+    /// Extracts keyword arguments of arbitrary names. Positional arguments
+    /// are rejected, and each name must be unique.
+    pub fn expect_keyword_arguments(
+        &self,
+    ) -> Result<&[KeywordArgument<'i, T>], InvalidArguments<'i>> {
+        if let (Some(first), Some(last)) = (self.args.first(), self.args.last()) {
+            let span = first.span.start_pos().span(&last.span.end_pos());
+            return Err(self.invalid_arguments("Unexpected positional arguments".to_owned(), span));
+        }
+        for (i, arg) in self.keyword_args.iter().enumerate() {
+            if self.keyword_args[..i]
+                .iter()
+                .any(|prev| prev.name == arg.name)
+            {
+                let name = arg.name;
+                let span = arg.name_span.start_pos().span(&arg.value.span.end_pos());
+                return Err(self.invalid_arguments(
+                    format!(r#"Got multiple values for keyword "{name}""#),
+                    span,
+                ));
+            }
+        }
+        Ok(&self.keyword_args)
+    }
+
     #[expect(clippy::type_complexity)]
     fn expect_named_arguments_vec(
         &self,
@@ -18,8 +18,12 @@ use bstr::ByteSlice as _;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt as _;
 
+use crate::backend::BackendResult;
 use crate::config::ConfigGetError;
+use crate::gitattributes::AttributeState;
+use crate::gitattributes::TreeAttributes;
 use crate::local_working_copy::TreeStateSettings;
+use crate::repo_path::RepoPath;
 use crate::settings::UserSettings;
 
 pub(crate) fn create_target_eol_strategy(
@@ -48,49 +52,110 @@ impl TargetEolStrategy {
 
     pub(crate) async fn convert_eol_for_snapshot<'a>(
         &self,
-        mut contents: impl AsyncRead + Send + Unpin + 'a,
+        attribute: EolAttribute,
+        contents: impl AsyncRead + Send + Unpin + 'a,
     ) -> Result<Box<dyn AsyncRead + Send + Unpin + 'a>, std::io::Error> {
-        match self.eol_conversion_mode {
-            EolConversionMode::None => Ok(Box::new(contents)),
-            EolConversionMode::Input | EolConversionMode::InputOutput => {
-                let mut peek = vec![];
-                (&mut contents)
-                    .take(Self::PROBE_LIMIT)
-                    .read_to_end(&mut peek)
-                    .await?;
-                let target_eol = if is_binary(&peek) {
-                    TargetEol::PassThrough
-                } else {
-                    TargetEol::Lf
-                };
-                let peek = Cursor::new(peek);
-                let contents = peek.chain(contents);
-                convert_eol(contents, target_eol).await
+        match (attribute, self.eol_conversion_mode) {
+            (EolAttribute::Binary, _) | (EolAttribute::Unspecified, EolConversionMode::None) => {
+                Ok(Box::new(contents))
             }
+            (EolAttribute::Text(_), _) => convert_eol(contents, TargetEol::Lf).await,
+            (EolAttribute::Auto(_), _)
+            | (
+                EolAttribute::Unspecified,
+                EolConversionMode::Input | EolConversionMode::InputOutput,
+            ) => Self::convert_eol_unless_binary(contents, TargetEol::Lf).await,
         }
     }
 
     pub(crate) async fn convert_eol_for_update<'a>(
         &self,
-        mut contents: impl AsyncRead + Send + Unpin + 'a,
+        attribute: EolAttribute,
+        contents: impl AsyncRead + Send + Unpin + 'a,
     ) -> Result<Box<dyn AsyncRead + Send + Unpin + 'a>, std::io::Error> {
-        match self.eol_conversion_mode {
-            EolConversionMode::None | EolConversionMode::Input => Ok(Box::new(contents)),
-            EolConversionMode::InputOutput => {
-                let mut peek = vec![];
-                (&mut contents)
-                    .take(Self::PROBE_LIMIT)
-                    .read_to_end(&mut peek)
-                    .await?;
-                let target_eol = if is_binary(&peek) {
-                    TargetEol::PassThrough
-                } else {
-                    TargetEol::Crlf
-                };
-                let peek = Cursor::new(peek);
-                let contents = peek.chain(contents);
-                convert_eol(contents, target_eol).await
+        let default_eol = match self.eol_conversion_mode {
+            EolConversionMode::None | EolConversionMode::Input => TargetEol::PassThrough,
+            EolConversionMode::InputOutput => TargetEol::Crlf,
+        };
+        // The contents are stored with LF line endings, so `eol=lf` doesn't
+        // need conversion.
+        let checkout_eol = |eol: Option<TargetEol>| match eol.unwrap_or(default_eol) {
+            TargetEol::Lf | TargetEol::PassThrough => TargetEol::PassThrough,
+            TargetEol::Crlf => TargetEol::Crlf,
+        };
+        match attribute {
+            EolAttribute::Binary => Ok(Box::new(contents)),
+            EolAttribute::Text(eol) => convert_eol(contents, checkout_eol(eol)).await,
+            EolAttribute::Auto(eol) => {
+                Self::convert_eol_unless_binary(contents, checkout_eol(eol)).await
             }
+            EolAttribute::Unspecified => {
+                Self::convert_eol_unless_binary(contents, default_eol).await
+            }
+        }
+    }
+
+    /// Converts the EOL of the `contents` to `target_eol` unless the contents
+    /// look binary.
+    async fn convert_eol_unless_binary<'a>(
+        mut contents: impl AsyncRead + Send + Unpin + 'a,
+        target_eol: TargetEol,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin + 'a>, std::io::Error> {
+        if target_eol == TargetEol::PassThrough {
+            return Ok(Box::new(contents));
+        }
+        let mut peek = vec![];
+        (&mut contents)
+            .take(Self::PROBE_LIMIT)
+            .read_to_end(&mut peek)
+            .await?;
+        let target_eol = if is_binary(&peek) {
+            TargetEol::PassThrough
+        } else {
+            target_eol
+        };
+        let peek = Cursor::new(peek);
+        let contents = peek.chain(contents);
+        convert_eol(contents, target_eol).await
+    }
+}
+
+/// EOL conversion selected by the `text` and `eol` attributes of a path.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum EolAttribute {
+    /// Neither attribute is specified. The `working-copy.eol-conversion`
+    /// setting applies.
+    Unspecified,
+    /// `-text`: The file is never converted.
+    Binary,
+    /// `text=auto`: The file is converted unless it looks binary. The EOL on
+    /// checkout is specified by the `eol` attribute if any.
+    Auto(Option<TargetEol>),
+    /// `text` or `eol`: The file is always converted. The EOL on checkout is
+    /// specified by the `eol` attribute if any.
+    Text(Option<TargetEol>),
+}
+
+impl EolAttribute {
+    /// Looks up the attributes of `path`.
+    pub(crate) fn for_path(attributes: &TreeAttributes, path: &RepoPath) -> BackendResult<Self> {
+        let text = attributes.get(path, "text")?;
+        let eol = attributes.get(path, "eol")?;
+        Ok(Self::from_states(text.as_ref(), eol.as_ref()))
+    }
+
+    fn from_states(text: Option<&AttributeState>, eol: Option<&AttributeState>) -> Self {
+        let eol = match eol {
+            Some(AttributeState::Value(value)) if value == "lf" => Some(TargetEol::Lf),
+            Some(AttributeState::Value(value)) if value == "crlf" => Some(TargetEol::Crlf),
+            _ => None,
+        };
+        match text {
+            Some(AttributeState::Unset) => Self::Binary,
+            Some(AttributeState::Value(value)) if value == "auto" => Self::Auto(eol),
+            Some(AttributeState::Set) => Self::Text(eol),
+            _ if eol.is_some() => Self::Text(eol),
+            _ => Self::Unspecified,
         }
     }
 }
@@ -121,7 +186,7 @@ impl EolConversionMode {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum TargetEol {
+pub(crate) enum TargetEol {
     Lf,
     Crlf,
     PassThrough,
@@ -273,7 +338,7 @@ mod tests {
     ) {
         let mut actual_output = vec![];
         strategy
-            .convert_eol_for_snapshot(contents)
+            .convert_eol_for_snapshot(EolAttribute::Unspecified, contents)
             .await
             .unwrap()
             .read_to_end(&mut actual_output)
@@ -305,7 +370,7 @@ mod tests {
     ) {
         let mut actual_output = vec![];
         strategy
-            .convert_eol_for_update(contents)
+            .convert_eol_for_update(EolAttribute::Unspecified, contents)
             .await
             .unwrap()
             .read_to_end(&mut actual_output)
@@ -313,4 +378,98 @@ mod tests {
             .unwrap();
         assert_eq!(actual_output, expected_output);
     }
+
+    #[tokio::main(flavor = "current_thread")]
+    #[test_case(EolAttribute::Binary, b"a\r\n", b"a\r\n"; "binary attribute")]
+    #[test_case(EolAttribute::Text(None), b"a\0\r\n", b"a\0\n"; "text attribute binary input")]
+    #[test_case(EolAttribute::Auto(None), b"a\r\n", b"a\n"; "auto attribute text input")]
+    #[test_case(EolAttribute::Auto(None), b"a\0\r\n", b"a\0\r\n"; "auto attribute binary input")]
+    async fn test_eol_attribute_convert_eol_for_snapshot(
+        attribute: EolAttribute,
+        contents: &[u8],
+        expected_output: &[u8],
+    ) {
+        let strategy = TargetEolStrategy {
+            eol_conversion_mode: EolConversionMode::None,
+        };
+        let mut actual_output = vec![];
+        strategy
+            .convert_eol_for_snapshot(attribute, contents)
+            .await
+            .unwrap()
+            .read_to_end(&mut actual_output)
+            .await
+            .unwrap();
+        assert_eq!(actual_output, expected_output);
+    }
+
+    #[tokio::main(flavor = "current_thread")]
+    #[test_case(
+        EolAttribute::Binary, EolConversionMode::InputOutput, b"a\n", b"a\n";
+        "binary attribute"
+    )]
+    #[test_case(
+        EolAttribute::Text(Some(TargetEol::Crlf)), EolConversionMode::None, b"a\n", b"a\r\n";
+        "crlf attribute"
+    )]
+    #[test_case(
+        EolAttribute::Text(Some(TargetEol::Lf)), EolConversionMode::InputOutput, b"a\n", b"a\n";
+        "lf attribute"
+    )]
+    #[test_case(
+        EolAttribute::Text(None), EolConversionMode::InputOutput, b"a\n", b"a\r\n";
+        "text attribute"
+    )]
+    #[test_case(
+        EolAttribute::Auto(Some(TargetEol::Crlf)), EolConversionMode::None, b"\0\n", b"\0\n";
+        "auto attribute binary input"
+    )]
+    async fn test_eol_attribute_convert_eol_for_update(
+        attribute: EolAttribute,
+        eol_conversion_mode: EolConversionMode,
+        contents: &[u8],
+        expected_output: &[u8],
+    ) {
+        let strategy = TargetEolStrategy {
+            eol_conversion_mode,
+        };
+        let mut actual_output = vec![];
+        strategy
+            .convert_eol_for_update(attribute, contents)
+            .await
+            .unwrap()
+            .read_to_end(&mut actual_output)
+            .await
+            .unwrap();
+        assert_eq!(actual_output, expected_output);
+    }
+
+    #[test]
+    fn test_eol_attribute_from_states() {
+        let value = |value: &str| AttributeState::Value(value.to_owned());
+        assert_eq!(
+            EolAttribute::from_states(None, None),
+            EolAttribute::Unspecified
+        );
+        assert_eq!(
+            EolAttribute::from_states(Some(&AttributeState::Unset), Some(&value("crlf"))),
+            EolAttribute::Binary
+        );
+        assert_eq!(
+            EolAttribute::from_states(Some(&AttributeState::Set), None),
+            EolAttribute::Text(None)
+        );
+        assert_eq!(
+            EolAttribute::from_states(None, Some(&value("crlf"))),
+            EolAttribute::Text(Some(TargetEol::Crlf))
+        );
+        assert_eq!(
+            EolAttribute::from_states(Some(&value("auto")), Some(&value("lf"))),
+            EolAttribute::Auto(Some(TargetEol::Lf))
+        );
+        assert_eq!(
+            EolAttribute::from_states(Some(&value("other")), None),
+            EolAttribute::Unspecified
+        );
+    }
 }
@@ -222,6 +222,24 @@ pub fn persist_content_addressed_temp_file<P: AsRef<Path>>(
     }
 }
 
+/// Replaces the file at `path` with the content written by `write`.
+///
+/// The content is written to a temporary file in the same directory, which is
+/// then renamed to `path`, so readers never see a partially-written file.
+/// Returns the persisted file.
+pub fn write_file_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> Result<File, PathError> {
+    let dir = path.parent().expect("file path should have a parent");
+    let mut temp_file = NamedTempFile::new_in(dir).context(dir)?;
+    write(temp_file.as_file_mut()).context(temp_file.path())?;
+    temp_file
+        .persist(path)
+        .map_err(|PersistError { error, file: _ }| error)
+        .context(path)
+}
+
 /// Reads from an async source and writes to a sync destination. Does not spawn
 /// a task, so writes will block.
 pub async fn copy_async_to_sync<R: AsyncRead, W: Write + ?Sized>(
@@ -383,6 +401,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_write_file_atomically() {
+        let temp_dir = new_temp_dir();
+        let path = temp_dir.path().join("file");
+        write_file_atomically(&path, |file| file.write_all(b"old")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+        write_file_atomically(&path, |file| file.write_all(b"new")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+
+        // The file is left intact if writing fails
+        let err = write_file_atomically(&path, |file| {
+            file.write_all(b"partial")?;
+            Err(io::Error::other("failed"))
+        })
+        .unwrap_err();
+        assert_ne!(err.path, path);
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn normalize_too_many_dot_dot() {
         assert_eq!(normalize_path(Path::new("foo/..")), Path::new("."));
@@ -293,6 +293,27 @@ pub fn try_merge<T: AsRef<[u8]>>(inputs: &Merge<T>) -> Option<BString> {
     merge_inner(inputs)
 }
 
+/// Splits `inputs` into hunks, and resolves conflicting hunks by concatenating
+/// the contents of all sides, like Git's `union` merge driver.
+pub fn merge_union<T: AsRef<[u8]>>(inputs: &Merge<T>) -> BString {
+    match merge_hunks(inputs) {
+        MergeResult::Resolved(content) => content,
+        MergeResult::Conflict(hunks) => {
+            let mut content = BString::default();
+            for hunk in &hunks {
+                if let Some(resolved) = hunk.as_resolved() {
+                    content.extend_from_slice(resolved);
+                } else {
+                    for side in hunk.adds() {
+                        content.extend_from_slice(side);
+                    }
+                }
+            }
+            content
+        }
+    }
+}
+
 fn merge_inner<'input, T: AsRef<[u8]>, B: FromMergeHunks<'input>>(inputs: &'input Merge<T>) -> B {
     // TODO: Using the first remove as base (first in the inputs) is how it's
     // usually done for 3-way conflicts. Are there better heuristics when there are
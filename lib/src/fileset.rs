@@ -21,7 +21,6 @@ use std::slice;
 use std::sync::LazyLock;
 
 use globset::Glob;
-use globset::GlobBuilder;
 use itertools::Itertools as _;
 use thiserror::Error;
 
@@ -50,6 +49,7 @@ use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathUiConverter;
 use crate::repo_path::UiPathParseError;
+use crate::str_util;
 
 /// Error occurred during file pattern parsing.
 #[derive(Debug, Error)]
@@ -104,17 +104,21 @@ impl FilePattern {
         //   * files-in: files in directory non-recursively
         //   * name: file name component (or suffix match?)
         //   * substring: substring match?
-        // * string pattern syntax (+ case sensitivity?)
+        // * string pattern syntax
         //   * path: literal path (default) (default anchor: prefix)
         //   * glob: glob pattern (default anchor: file)
         //   * regex?
-        match kind {
-            "cwd" => Self::cwd_prefix_path(path_converter, input),
-            "cwd-file" | "file" => Self::cwd_file_path(path_converter, input),
-            "cwd-glob" | "glob" => Self::cwd_file_glob(path_converter, input),
-            "root" => Self::root_prefix_path(input),
-            "root-file" => Self::root_file_path(input),
-            "root-glob" => Self::root_file_glob(input),
+        // * case sensitivity
+        //   * -i: case-insensitive glob pattern
+        match str_util::split_case_insensitive_kind(kind) {
+            ("cwd", false) => Self::cwd_prefix_path(path_converter, input),
+            ("cwd-file" | "file", false) => Self::cwd_file_path(path_converter, input),
+            ("cwd-glob" | "glob", false) => Self::cwd_file_glob(path_converter, input),
+            ("cwd-glob" | "glob", true) => Self::cwd_file_glob_i(path_converter, input),
+            ("root", false) => Self::root_prefix_path(input),
+            ("root-file", false) => Self::root_file_path(input),
+            ("root-glob", false) => Self::root_file_glob(input),
+            ("root-glob", true) => Self::root_file_glob_i(input),
             _ => Err(FilePatternParseError::InvalidKind(kind.to_owned())),
         }
     }
@@ -142,9 +146,19 @@ impl FilePattern {
         path_converter: &RepoPathUiConverter,
         input: impl AsRef<str>,
     ) -> Result<Self, FilePatternParseError> {
-        let (dir, pattern) = split_glob_path(input.as_ref());
+        let (dir, pattern) = split_glob_path(input.as_ref(), false);
         let dir = path_converter.parse_file_path(dir)?;
-        Self::file_glob_at(dir, pattern)
+        Self::file_glob_at(dir, pattern, false)
+    }
+
+    /// Pattern that case-insensitively matches cwd-relative file path glob.
+    pub fn cwd_file_glob_i(
+        path_converter: &RepoPathUiConverter,
+        input: impl AsRef<str>,
+    ) -> Result<Self, FilePatternParseError> {
+        let (dir, pattern) = split_glob_path(input.as_ref(), true);
+        let dir = path_converter.parse_file_path(dir)?;
+        Self::file_glob_at(dir, pattern, true)
     }
 
     /// Pattern that matches workspace-relative file (or exact) path.
@@ -162,19 +176,34 @@ impl FilePattern {
 
     /// Pattern that matches workspace-relative file path glob.
     pub fn root_file_glob(input: impl AsRef<str>) -> Result<Self, FilePatternParseError> {
-        let (dir, pattern) = split_glob_path(input.as_ref());
+        let (dir, pattern) = split_glob_path(input.as_ref(), false);
         let dir = RepoPathBuf::from_relative_path(dir)?;
-        Self::file_glob_at(dir, pattern)
+        Self::file_glob_at(dir, pattern, false)
     }
 
-    fn file_glob_at(dir: RepoPathBuf, input: &str) -> Result<Self, FilePatternParseError> {
+    /// Pattern that case-insensitively matches workspace-relative file path
+    /// glob.
+    pub fn root_file_glob_i(input: impl AsRef<str>) -> Result<Self, FilePatternParseError> {
+        let (dir, pattern) = split_glob_path(input.as_ref(), true);
+        let dir = RepoPathBuf::from_relative_path(dir)?;
+        Self::file_glob_at(dir, pattern, true)
+    }
+
+    fn file_glob_at(
+        dir: RepoPathBuf,
+        input: &str,
+        icase: bool,
+    ) -> Result<Self, FilePatternParseError> {
         if input.is_empty() {
             return Ok(FilePattern::FilePath(dir));
         }
         // Normalize separator to '/', reject ".." which will never match
         let normalized = RepoPathBuf::from_relative_path(input)?;
-        let pattern = Box::new(parse_file_glob(normalized.as_internal_file_string())?);
-        Ok(FilePattern::FileGlob { dir, pattern })
+        let pattern = str_util::parse_file_glob(normalized.as_internal_file_string(), icase)?;
+        Ok(FilePattern::FileGlob {
+            dir,
+            pattern: Box::new(pattern),
+        })
     }
 
     /// Returns path if this pattern represents a literal path in a workspace.
@@ -188,12 +217,11 @@ impl FilePattern {
     }
 }
 
-pub(super) fn parse_file_glob(input: &str) -> Result<Glob, globset::Error> {
-    GlobBuilder::new(input).literal_separator(true).build()
-}
-
 /// Splits `input` path into literal directory path and glob pattern.
-fn split_glob_path(input: &str) -> (&str, &str) {
+///
+/// If `icase` is true, the directory path is limited to leading components
+/// which have no case distinction, such as "..".
+fn split_glob_path(input: &str, icase: bool) -> (&str, &str) {
     // See globset::escape(). In addition to that, backslash is parsed as an
     // escape sequence on Unix.
     const GLOB_CHARS: &[char] = if cfg!(windows) {
@@ -204,6 +232,7 @@ fn split_glob_path(input: &str) -> (&str, &str) {
     let prefix_len = input
         .split_inclusive(path::is_separator)
         .take_while(|component| !component.contains(GLOB_CHARS))
+        .take_while(|component| !icase || !component.chars().any(char::is_alphabetic))
         .map(|component| component.len())
         .sum();
     input.split_at(prefix_len)
@@ -412,6 +441,11 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, FilesetFunction>> = LazyLock
     map
 });
 
+/// Returns the names of the builtin fileset functions in sorted order.
+pub fn builtin_function_names() -> Vec<&'static str> {
+    BUILTIN_FUNCTION_MAP.keys().copied().sorted().collect()
+}
+
 fn resolve_function(
     diagnostics: &mut FilesetDiagnostics,
     path_converter: &RepoPathUiConverter,
@@ -722,6 +756,37 @@ mod tests {
             "#);
         }
 
+        // case-insensitive, only ".." components are parsed as literal path
+        insta::assert_debug_snapshot!(
+            parse(r#"glob-i:"../Foo/*""#).unwrap(), @r#"
+        Pattern(
+            FileGlob {
+                dir: "",
+                pattern: Glob {
+                    glob: "Foo/*",
+                    re: "(?-u)(?i)^Foo/[^/]*$",
+                    opts: _,
+                    tokens: _,
+                },
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse(r#"root-glob-i:"Foo""#).unwrap(), @r#"
+        Pattern(
+            FileGlob {
+                dir: "",
+                pattern: Glob {
+                    glob: "Foo",
+                    re: "(?-u)(?i)^Foo$",
+                    opts: _,
+                    tokens: _,
+                },
+            },
+        )
+        "#);
+        assert!(parse(r#"root-i:"foo""#).is_err());
+
         // workspace-relative, without meta characters
         insta::assert_debug_snapshot!(
             parse(r#"root-glob:"foo""#).unwrap(),
@@ -931,7 +996,7 @@ mod tests {
         let glob_expr = |dir: &str, pattern: &str| {
             FilesetExpression::pattern(FilePattern::FileGlob {
                 dir: repo_path_buf(dir),
-                pattern: Box::new(parse_file_glob(pattern).unwrap()),
+                pattern: Box::new(str_util::parse_file_glob(pattern, false).unwrap()),
             })
         };
 
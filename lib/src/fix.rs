@@ -26,11 +26,15 @@ use jj_lib::backend::CommitId;
 use jj_lib::backend::FileId;
 use jj_lib::backend::TreeValue;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetIteratorExt as _;
@@ -55,6 +59,36 @@ pub struct FileToFix {
     ///  - Update parts of the file's content that should be derived from the
     ///    file's path.
     pub repo_path: RepoPathBuf,
+
+    /// Whether the file is executable.
+    pub executable: bool,
+
+    /// The first commit (in topological order) that contains the file. Since
+    /// identical files are only fixed once, the same fix is also applied to
+    /// the descendants containing the file, unless
+    /// [`FileFixer::depends_on_commit()`] is true for the path.
+    pub commit_id: CommitId,
+}
+
+/// Path, content, and executable bit of a file to fix, and the commit it was
+/// found in if the fix may depend on the commit.
+type FileKey = (RepoPathBuf, FileId, bool, Option<CommitId>);
+
+/// New state of a file changed by a [FileFixer].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FixedFile {
+    /// The file content or executable bit was changed, and the file may have
+    /// been moved to a new path.
+    Modified {
+        /// Identifier for the new file content.
+        file_id: FileId,
+        /// Whether the new file is executable.
+        executable: bool,
+        /// The path to move the file to, if it was renamed.
+        new_path: Option<RepoPathBuf>,
+    },
+    /// The file was deleted.
+    Deleted,
 }
 
 /// Error fixing files.
@@ -72,6 +106,18 @@ pub enum FixError {
     /// Error occurred while processing the file content.
     #[error(transparent)]
     FixContent(Box<dyn std::error::Error + Send + Sync>),
+    /// A file was renamed to a path that already exists.
+    #[error(
+        "Cannot rename {} to {} because the path already exists",
+        .path.as_internal_file_string(),
+        .new_path.as_internal_file_string()
+    )]
+    RenameTargetExists {
+        /// The path of the renamed file.
+        path: RepoPathBuf,
+        /// The path the file was renamed to.
+        new_path: RepoPathBuf,
+    },
 }
 
 /// Fixes a set of files.
@@ -83,8 +129,8 @@ pub trait FileFixer {
     /// files).
     ///
     /// Returns a map describing the subset of `files_to_fix` that resulted in
-    /// changed file content (unchanged files should not be present in the map),
-    /// pointing to the new FileId for the file.
+    /// changed files (unchanged files should not be present in the map),
+    /// pointing to the new state of the file.
     ///
     /// TODO: Better error handling so we can tell the user what went wrong with
     /// each failed input.
@@ -92,7 +138,15 @@ pub trait FileFixer {
         &mut self,
         store: &Store,
         files_to_fix: &'a HashSet<FileToFix>,
-    ) -> Result<HashMap<&'a FileToFix, FileId>, FixError>;
+    ) -> Result<HashMap<&'a FileToFix, FixedFile>, FixError>;
+
+    /// Returns true if the fix of the file at `repo_path` may depend on the
+    /// commit it's in. Such files are fixed separately in each commit
+    /// containing them, even if they are unchanged from the parent. Other
+    /// files are only fixed once per unique content.
+    fn depends_on_commit(&self, _repo_path: &RepoPath) -> bool {
+        false
+    }
 }
 
 /// Aggregate information about the outcome of the file fixer.
@@ -116,36 +170,47 @@ pub struct FixSummary {
 // pipes.
 pub struct ParallelFileFixer<T> {
     fix_fn: T,
+    commit_dependent_paths: Box<dyn Matcher>,
 }
 
 impl<T> ParallelFileFixer<T>
 where
-    T: Fn(&Store, &FileToFix) -> Result<Option<FileId>, FixError> + Sync + Send,
+    T: Fn(&Store, &FileToFix) -> Result<Option<FixedFile>, FixError> + Sync + Send,
 {
     /// Creates a ParallelFileFixer.
     pub fn new(fix_fn: T) -> Self {
-        Self { fix_fn }
+        Self {
+            fix_fn,
+            commit_dependent_paths: Box::new(NothingMatcher),
+        }
+    }
+
+    /// Sets the paths of which fixes may depend on the commit. See
+    /// [`FileFixer::depends_on_commit()`].
+    pub fn with_commit_dependent_paths(mut self, matcher: Box<dyn Matcher>) -> Self {
+        self.commit_dependent_paths = matcher;
+        self
     }
 }
 
 impl<T> FileFixer for ParallelFileFixer<T>
 where
-    T: Fn(&Store, &FileToFix) -> Result<Option<FileId>, FixError> + Sync + Send,
+    T: Fn(&Store, &FileToFix) -> Result<Option<FixedFile>, FixError> + Sync + Send,
 {
     /// Applies `fix_fn()` to the inputs and stores the resulting file content.
     fn fix_files<'a>(
         &mut self,
         store: &Store,
         files_to_fix: &'a HashSet<FileToFix>,
-    ) -> Result<HashMap<&'a FileToFix, FileId>, FixError> {
+    ) -> Result<HashMap<&'a FileToFix, FixedFile>, FixError> {
         let (updates_tx, updates_rx) = channel();
         files_to_fix.into_par_iter().try_for_each_init(
             || updates_tx.clone(),
             |updates_tx, file_to_fix| -> Result<(), FixError> {
                 let result = (self.fix_fn)(store, file_to_fix)?;
                 match result {
-                    Some(new_file_id) => {
-                        updates_tx.send((file_to_fix, new_file_id)).unwrap();
+                    Some(fixed_file) => {
+                        updates_tx.send((file_to_fix, fixed_file)).unwrap();
                         Ok(())
                     }
                     None => Ok(()),
@@ -154,11 +219,15 @@ where
         )?;
         drop(updates_tx);
         let mut result = HashMap::new();
-        while let Ok((file_to_fix, new_file_id)) = updates_rx.recv() {
-            result.insert(file_to_fix, new_file_id);
+        while let Ok((file_to_fix, fixed_file)) = updates_rx.recv() {
+            result.insert(file_to_fix, fixed_file);
         }
         Ok(result)
     }
+
+    fn depends_on_commit(&self, repo_path: &RepoPath) -> bool {
+        self.commit_dependent_paths.matches(repo_path)
+    }
 }
 
 /// Updates files with formatting fixes or other changes, using the given
@@ -185,9 +254,11 @@ pub async fn fix_files(
     // be deterministic, and should not consider outside information, so it is
     // safe to deduplicate inputs that correspond to multiple files or commits.
     // This is typically more efficient, but it does prevent certain use cases
-    // like providing commit IDs as inputs to be inserted into files. We also
-    // need to record the mapping between files-to-fix and paths/commits, to
-    // efficiently rewrite the commits later.
+    // like inserting commit IDs into files, since only the first commit
+    // containing each file is passed to the file fixer. Paths for which
+    // file_fixer.depends_on_commit() is true are instead fixed in every commit
+    // containing them. We also need to record the mapping between files-to-fix
+    // and paths/commits, to efficiently rewrite the commits later.
     //
     // If a path is being fixed in a particular commit, it must also be fixed in all
     // that commit's descendants. We do this as a way of propagating changes,
@@ -208,7 +279,7 @@ pub async fn fix_files(
         "looking for files to fix in commits:"
     );
 
-    let mut unique_files_to_fix: HashSet<FileToFix> = HashSet::new();
+    let mut unique_files_to_fix: HashMap<FileKey, FileToFix> = HashMap::new();
     let mut commit_paths: HashMap<CommitId, HashSet<RepoPathBuf>> = HashMap::new();
     for commit in commits.iter().rev() {
         let mut paths: HashSet<RepoPathBuf> = HashSet::new();
@@ -227,36 +298,53 @@ pub async fn fix_files(
             }
             commit.parent_tree(repo_mut)?
         };
-        // TODO: handle copy tracking
-        let mut diff_stream = parent_tree.diff_stream(&commit.tree_async().await?, &matcher);
-        while let Some(TreeDiffEntry {
-            path: repo_path,
-            values,
-        }) = diff_stream.next().await
-        {
-            let (_before, after) = values?;
-            // Deleted files have no file content to fix, and they have no terms in `after`,
+        let inherited_paths = paths.clone();
+        let tree = commit.tree_async().await?;
+        let mut add_files_to_fix = |repo_path: &RepoPath, value: MergedTreeValue| {
+            // Deleted files have no file content to fix, and they have no terms in `value`,
             // so we don't add any files-to-fix for them. Conflicted files produce one
             // file-to-fix for each side of the conflict.
-            for term in after.into_iter().flatten() {
-                // We currently only support fixing the content of normal files, so we skip
-                // directories and symlinks, and we ignore the executable bit.
+            for term in value.into_iter().flatten() {
+                // We currently only support fixing normal files, so we skip directories and
+                // symlinks.
                 if let TreeValue::File {
                     id,
-                    executable: _,
+                    executable,
                     copy_id: _,
                 } = term
                 {
                     // TODO: Skip the file if its content is larger than some configured size,
                     // preferably without actually reading it yet.
-                    let file_to_fix = FileToFix {
-                        file_id: id.clone(),
-                        repo_path: repo_path.clone(),
-                    };
-                    unique_files_to_fix.insert(file_to_fix.clone());
-                    paths.insert(repo_path.clone());
+                    let key = file_key(file_fixer, repo_path, &id, executable, commit.id());
+                    unique_files_to_fix.entry(key).or_insert_with(|| FileToFix {
+                        file_id: id,
+                        repo_path: repo_path.to_owned(),
+                        executable,
+                        commit_id: commit.id().clone(),
+                    });
+                    paths.insert(repo_path.to_owned());
                 }
             }
+        };
+        // TODO: handle copy tracking
+        let mut changed_paths: HashSet<RepoPathBuf> = HashSet::new();
+        let mut diff_stream = parent_tree.diff_stream(&tree, &matcher);
+        while let Some(TreeDiffEntry {
+            path: repo_path,
+            values,
+        }) = diff_stream.next().await
+        {
+            let (_before, after) = values?;
+            add_files_to_fix(&repo_path, after);
+            changed_paths.insert(repo_path);
+        }
+        // Unchanged paths fixed in ancestors are fixed again in this commit if
+        // the fix may depend on the commit. Otherwise the ancestor's fix is
+        // reused.
+        for repo_path in inherited_paths.difference(&changed_paths) {
+            if file_fixer.depends_on_commit(repo_path) {
+                add_files_to_fix(repo_path, tree.path_value_async(repo_path).await?);
+            }
         }
 
         commit_paths.insert(commit.id().clone(), paths);
@@ -269,12 +357,16 @@ pub async fn fix_files(
     );
 
     // Fix all of the chosen inputs.
-    let fixed_file_ids = file_fixer.fix_files(repo_mut.store().as_ref(), &unique_files_to_fix)?;
-    tracing::debug!(?fixed_file_ids, "file fixer fixed these files:");
+    let files_to_fix: HashSet<FileToFix> = unique_files_to_fix.values().cloned().collect();
+    let fixed_files = file_fixer.fix_files(repo_mut.store().as_ref(), &files_to_fix)?;
+    tracing::debug!(?fixed_files, "file fixer fixed these files:");
+    let fixed_files: HashMap<FileKey, FixedFile> = unique_files_to_fix
+        .into_iter()
+        .filter_map(|(key, file_to_fix)| Some((key, fixed_files.get(&file_to_fix)?.clone())))
+        .collect();
 
-    // Substitute the fixed file IDs into all of the affected commits. Currently,
-    // fixes cannot delete or rename files, change the executable bit, or modify
-    // other parts of the commit like the description.
+    // Substitute the fixed files into all of the affected commits. Currently,
+    // fixes cannot modify other parts of the commit like the description.
     repo_mut.transform_descendants(root_commits, |mut rewriter| {
         // TODO: Build the trees in parallel before `transform_descendants()` and only
         // keep the tree IDs in memory, so we can pass them to the rewriter.
@@ -283,8 +375,56 @@ pub async fn fix_files(
         let old_tree = rewriter.old_commit().tree()?;
         let mut tree_builder = MergedTreeBuilder::new(old_tree.id().clone());
         let mut has_changes = false;
+        let mut renamed_paths: HashSet<&RepoPathBuf> = HashSet::new();
         for repo_path in repo_paths {
             let old_value = old_tree.path_value(repo_path)?;
+            if let Some(Some(TreeValue::File {
+                id,
+                executable,
+                copy_id,
+            })) = old_value.as_resolved()
+            {
+                let key = file_key(file_fixer, repo_path, id, *executable, &old_commit_id);
+                match fixed_files.get(&key) {
+                    Some(FixedFile::Modified {
+                        file_id,
+                        executable,
+                        new_path,
+                    }) => {
+                        let new_value = Merge::normal(TreeValue::File {
+                            id: file_id.clone(),
+                            executable: *executable,
+                            copy_id: copy_id.clone(),
+                        });
+                        match new_path {
+                            Some(new_path) if new_path != repo_path => {
+                                if old_tree.path_value(new_path)?.is_present()
+                                    || !renamed_paths.insert(new_path)
+                                {
+                                    let err = FixError::RenameTargetExists {
+                                        path: repo_path.clone(),
+                                        new_path: new_path.clone(),
+                                    };
+                                    return Err(BackendError::Other(err.into()));
+                                }
+                                tree_builder.set_or_remove(repo_path.clone(), Merge::absent());
+                                tree_builder.set_or_remove(new_path.clone(), new_value);
+                            }
+                            _ if new_value == old_value => continue,
+                            _ => tree_builder.set_or_remove(repo_path.clone(), new_value),
+                        }
+                        has_changes = true;
+                    }
+                    Some(FixedFile::Deleted) => {
+                        tree_builder.set_or_remove(repo_path.clone(), Merge::absent());
+                        has_changes = true;
+                    }
+                    None => {}
+                }
+                continue;
+            }
+            // Conflicted files are fixed on all sides of the conflict. Renaming or
+            // deleting individual sides isn't supported, so those fixes are ignored.
             let new_value = old_value.map(|old_term| {
                 if let Some(TreeValue::File {
                     id,
@@ -292,13 +432,15 @@ pub async fn fix_files(
                     copy_id,
                 }) = old_term
                 {
-                    let file_to_fix = FileToFix {
-                        file_id: id.clone(),
-                        repo_path: repo_path.clone(),
-                    };
-                    if let Some(new_id) = fixed_file_ids.get(&file_to_fix) {
+                    let key = file_key(file_fixer, repo_path, id, *executable, &old_commit_id);
+                    if let Some(FixedFile::Modified {
+                        file_id,
+                        executable,
+                        new_path: _,
+                    }) = fixed_files.get(&key)
+                    {
                         return Some(TreeValue::File {
-                            id: new_id.clone(),
+                            id: file_id.clone(),
                             executable: *executable,
                             copy_id: copy_id.clone(),
                         });
@@ -332,3 +474,16 @@ pub async fn fix_files(
     tracing::debug!(?summary);
     Ok(summary)
 }
+
+fn file_key(
+    file_fixer: &impl FileFixer,
+    repo_path: &RepoPath,
+    id: &FileId,
+    executable: bool,
+    commit_id: &CommitId,
+) -> FileKey {
+    let commit_id = file_fixer
+        .depends_on_commit(repo_path)
+        .then(|| commit_id.clone());
+    (repo_path.to_owned(), id.clone(), executable, commit_id)
+}
@@ -123,6 +123,34 @@ impl GitPushStats {
     }
 }
 
+/// Details of a git push negotiation, reported through
+/// [`RemoteCallbacks::push_negotiation`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GitPushNegotiation {
+    /// summary reported for each reference, e.g. `abc1234..def5678` or
+    /// `[new branch]`
+    pub summaries: Vec<(GitRefNameBuf, String)>,
+    /// objects sent to the remote during pack negotiation
+    pub transfer: GitPushTransferStats,
+    /// messages sent by the remote through the sideband channel
+    pub remote_messages: Vec<String>,
+}
+
+/// Object counts reported by git while negotiating a push
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GitPushTransferStats {
+    /// number of objects counted for the pack
+    pub counted_objects: u64,
+    /// number of objects compressed as deltas
+    pub compressed_objects: u64,
+    /// number of objects written to the pack
+    pub written_objects: u64,
+    /// number of deltas included in the pack
+    pub deltas: u64,
+    /// number of deltas resolved by the remote
+    pub resolved_deltas: u64,
+}
+
 /// Newtype to look up `HashMap` entry by key of shorter lifetime.
 ///
 /// https://users.rust-lang.org/t/unexpected-lifetime-issue-with-hashmap-remove/113961/6
@@ -732,7 +760,9 @@ fn default_remote_ref_state_for(
 ) -> RemoteRefState {
     match kind {
         GitRefKind::Bookmark => {
-            if symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO || git_settings.auto_local_bookmark {
+            if symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                || git_settings.should_auto_track_bookmark(symbol)
+            {
                 RemoteRefState::Tracked
             } else {
                 RemoteRefState::New
@@ -2182,6 +2212,9 @@ impl<'a> GitFetch<'a> {
         }
 
         let mut branches_to_prune = Vec::new();
+        // If all branches are specified by exact names, the missing refs can be
+        // looked up at once without enumerating all remote refs.
+        let mut can_query_missing_refs = branch_names.iter().all(StringPattern::is_exact);
         // git unfortunately errors out if one of the many refspecs is not found
         //
         // our approach is to filter out failures and retry,
@@ -2194,13 +2227,36 @@ impl<'a> GitFetch<'a> {
                 .spawn_fetch(remote_name, &remaining_refspecs, &mut callbacks, depth)?
         {
             tracing::debug!(failing_refspec, "failed to fetch ref");
-            remaining_refspecs.retain(|r| r.source.as_ref() != Some(&failing_refspec));
-
-            if let Some(branch_name) = failing_refspec.strip_prefix("refs/heads/") {
-                branches_to_prune.push(format!(
-                    "{remote_name}/{branch_name}",
-                    remote_name = remote_name.as_str()
-                ));
+            let mut failing_refspecs = vec![failing_refspec];
+            if can_query_missing_refs {
+                can_query_missing_refs = false;
+                let sources = remaining_refspecs
+                    .iter()
+                    .filter_map(|r| r.source.as_deref())
+                    .filter(|&source| source != failing_refspecs[0])
+                    .collect_vec();
+                let existing_refs = self.git_ctx.spawn_ls_remote(remote_name, &sources)?;
+                tracing::debug!(?existing_refs, "queried remote refs");
+                failing_refspecs.extend(
+                    sources
+                        .into_iter()
+                        .filter(|source| !existing_refs.iter().any(|name| name == source))
+                        .map(str::to_owned),
+                );
+            }
+            remaining_refspecs.retain(|r| {
+                r.source
+                    .as_ref()
+                    .is_none_or(|source| !failing_refspecs.contains(source))
+            });
+
+            for failing_refspec in &failing_refspecs {
+                if let Some(branch_name) = failing_refspec.strip_prefix("refs/heads/") {
+                    branches_to_prune.push(format!(
+                        "{remote_name}/{branch_name}",
+                        remote_name = remote_name.as_str()
+                    ));
+                }
             }
         }
 
@@ -2400,6 +2456,7 @@ pub struct RemoteCallbacks<'a> {
     pub get_ssh_keys: Option<&'a mut dyn FnMut(&str) -> Vec<PathBuf>>,
     pub get_password: Option<&'a mut dyn FnMut(&str, &str) -> Option<String>>,
     pub get_username_password: Option<&'a mut dyn FnMut(&str) -> Option<(String, String)>>,
+    pub push_negotiation: Option<&'a mut dyn FnMut(&GitPushNegotiation)>,
 }
 
 #[derive(Clone, Debug)]
@@ -15,6 +15,7 @@
 #![allow(missing_docs)]
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::fmt::Debug;
@@ -23,22 +24,30 @@ use std::fmt::Formatter;
 use std::fs;
 use std::io;
 use std::io::Cursor;
+use std::io::Write as _;
+use std::ops::Deref;
 use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::process::Command;
 use std::process::ExitStatus;
+use std::process::Stdio;
 use std::str;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
+use std::thread;
+use std::thread::ThreadId;
 use std::time::SystemTime;
 
 use async_trait::async_trait;
 use bstr::BStr;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 use futures::stream::BoxStream;
 use gix::bstr::BString;
 use gix::objs::CommitRefIter;
+use gix::objs::Write as _;
 use gix::objs::WriteTo as _;
 use itertools::Itertools as _;
 use once_cell::sync::OnceCell as OnceLock;
@@ -67,6 +76,7 @@ use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::ObjectContent;
 use crate::backend::SecureSig;
 use crate::backend::Signature;
 use crate::backend::SigningFn;
@@ -167,13 +177,72 @@ pub enum GitGcError {
     GcCommandErrorStatus(ExitStatus),
 }
 
+#[derive(Debug, Error)]
+enum GitPackError {
+    #[error("Failed to run git index-pack command")]
+    IndexPackCommand(#[source] std::io::Error),
+    #[error("git index-pack command exited with an error: {0}")]
+    IndexPackCommandErrorStatus(ExitStatus),
+    #[error("Failed to write pack data")]
+    WritePack(#[from] std::io::Error),
+    #[error("Failed to hash pack data")]
+    HashPack(#[from] gix::hash::hasher::Error),
+}
+
+/// Batches with fewer objects are written as loose objects, like Git's
+/// `transfer.unpackLimit`.
+const MIN_OBJECTS_TO_PACK: usize = 100;
+
+/// Repositories the backend methods operate on.
+struct GitRepos {
+    shared: gix::Repository,
+    /// Write batches started by threads. Objects written by these threads are
+    /// kept in memory until the batch is flushed.
+    batches: HashMap<ThreadId, WriteBatch>,
+}
+
+/// Pending writes of a thread which started a write batch.
+struct WriteBatch {
+    repo: gix::Repository,
+    /// Extra metadata of the commits written to the batch. No-gc refs and
+    /// metadata entries are saved once the objects are flushed to disk.
+    pending_extras: HashMap<CommitId, Vec<u8>>,
+}
+
+/// Repository of the current thread, locked for the duration of a backend
+/// method call.
+struct LockedGitRepo<'a> {
+    repos: MutexGuard<'a, GitRepos>,
+    thread_id: ThreadId,
+}
+
+impl LockedGitRepo<'_> {
+    fn batch(&self) -> Option<&WriteBatch> {
+        self.repos.batches.get(&self.thread_id)
+    }
+
+    fn batch_mut(&mut self) -> Option<&mut WriteBatch> {
+        self.repos.batches.get_mut(&self.thread_id)
+    }
+}
+
+impl Deref for LockedGitRepo<'_> {
+    type Target = gix::Repository;
+
+    fn deref(&self) -> &Self::Target {
+        self.batch()
+            .map(|batch| &batch.repo)
+            .unwrap_or(&self.repos.shared)
+    }
+}
+
 pub struct GitBackend {
     // While gix::Repository can be created from gix::ThreadSafeRepository, it's
     // cheaper to cache the thread-local instance behind a mutex than creating
     // one for each backend method call. Our GitBackend is most likely to be
     // used in a single-threaded context.
     base_repo: gix::ThreadSafeRepository,
-    repo: Mutex<gix::Repository>,
+    repos: Mutex<GitRepos>,
     root_commit_id: CommitId,
     root_change_id: ChangeId,
     empty_tree_id: TreeId,
@@ -194,13 +263,16 @@ impl GitBackend {
         extra_metadata_store: TableStore,
         git_settings: GitSettings,
     ) -> Self {
-        let repo = Mutex::new(base_repo.to_thread_local());
+        let repos = Mutex::new(GitRepos {
+            shared: base_repo.to_thread_local(),
+            batches: HashMap::new(),
+        });
         let root_commit_id = CommitId::from_bytes(&[0; HASH_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
         let empty_tree_id = TreeId::from_hex("4b825dc642cb6eb9a060e54bf8d69288fbee4904");
         GitBackend {
             base_repo,
-            repo,
+            repos,
             root_commit_id,
             root_change_id,
             empty_tree_id,
@@ -339,8 +411,11 @@ impl GitBackend {
         Ok(GitBackend::new(repo, extra_metadata_store, git_settings))
     }
 
-    fn lock_git_repo(&self) -> MutexGuard<'_, gix::Repository> {
-        self.repo.lock().unwrap()
+    fn lock_git_repo(&self) -> LockedGitRepo<'_> {
+        LockedGitRepo {
+            repos: self.repos.lock().unwrap(),
+            thread_id: thread::current().id(),
+        }
     }
 
     /// Returns new thread-local instance to access to the underlying Git repo.
@@ -864,6 +939,93 @@ fn recreate_no_gc_refs(
     Ok(())
 }
 
+/// Writes `objects` to a single pack by piping them to `git index-pack`.
+fn write_pack(
+    program: &OsStr,
+    git_dir: &Path,
+    objects: &gix::odb::memory::Storage,
+) -> Result<(), GitPackError> {
+    let mut git = Command::new(program);
+    git.arg("--git-dir=.") // turn off discovery
+        .arg("index-pack")
+        .arg("--stdin")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null());
+    // Don't specify it by GIT_DIR/--git-dir. See run_git_gc().
+    git.current_dir(git_dir);
+    tracing::info!(?git, num_objects = objects.len(), "running git index-pack");
+    let mut child = git.spawn().map_err(GitPackError::IndexPackCommand)?;
+    let stdin = child.stdin.take().unwrap();
+    // Closes stdin before waiting for the command to exit
+    let written = write_pack_data(io::BufWriter::new(stdin), objects);
+    let status = child.wait().map_err(GitPackError::IndexPackCommand)?;
+    tracing::info!(?status, "git index-pack exited");
+    written?;
+    if !status.success() {
+        return Err(GitPackError::IndexPackCommandErrorStatus(status));
+    }
+    Ok(())
+}
+
+/// Writes `objects` in the pack format, without deltas.
+fn write_pack_data(
+    out: impl io::Write,
+    objects: &gix::odb::memory::Storage,
+) -> Result<(), GitPackError> {
+    let mut out = HashingWriter {
+        inner: out,
+        hasher: gix::hash::hasher(gix::hash::Kind::Sha1),
+    };
+    out.write_all(b"PACK")?;
+    out.write_all(&2_u32.to_be_bytes())?;
+    let num_objects = u32::try_from(objects.len()).expect("too many objects to pack");
+    out.write_all(&num_objects.to_be_bytes())?;
+    for (kind, data) in objects.values() {
+        // Type and size, followed by the size continued in 7-bit groups
+        let type_id: u8 = match kind {
+            gix::objs::Kind::Commit => 1,
+            gix::objs::Kind::Tree => 2,
+            gix::objs::Kind::Blob => 3,
+            gix::objs::Kind::Tag => 4,
+        };
+        let mut size = data.len();
+        let mut byte = (type_id << 4) | (size & 0x0f) as u8;
+        size >>= 4;
+        while size > 0 {
+            out.write_all(&[byte | 0x80])?;
+            byte = (size & 0x7f) as u8;
+            size >>= 7;
+        }
+        out.write_all(&[byte])?;
+        let mut encoder = ZlibEncoder::new(&mut out, Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()?;
+    }
+    let HashingWriter { mut inner, hasher } = out;
+    let checksum = hasher.try_finalize()?;
+    inner.write_all(checksum.as_bytes())?;
+    inner.flush()?;
+    Ok(())
+}
+
+/// Passes written data through to `inner` while hashing it.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: gix::hash::Hasher,
+}
+
+impl<W: io::Write> io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.update(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 fn run_git_gc(program: &OsStr, git_dir: &Path, keep_newer: SystemTime) -> Result<(), GitGcError> {
     let keep_newer = keep_newer
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -1143,6 +1305,49 @@ impl Backend for GitBackend {
         Ok(Tree::from_sorted_entries(entries))
     }
 
+    fn hash_object(&self, content: ObjectContent<'_>) -> Option<Vec<u8>> {
+        match content {
+            ObjectContent::File(data) => {
+                let id =
+                    gix::objs::compute_hash(gix::hash::Kind::Sha1, gix::object::Kind::Blob, data)
+                        .ok()?;
+                Some(id.as_bytes().to_vec())
+            }
+            // Tree entry modes are normalized when read, so the tree written
+            // from the entries may differ from the stored one.
+            ObjectContent::Tree(_) => None,
+        }
+    }
+
+    fn quarantine_object(&self, id: &dyn ObjectId) -> Option<String> {
+        // Only loose objects can be moved out of the object database so that
+        // they can be fetched again.
+        let hex = id.hex();
+        let object_path = self
+            .git_repo_path()
+            .join("objects")
+            .join(&hex[..2])
+            .join(&hex[2..]);
+        let quarantine_dir = self.git_repo_path().join("jj-quarantine");
+        let quarantine_path = quarantine_dir.join(&hex);
+        let moved = fs::create_dir_all(&quarantine_dir)
+            .and_then(|()| fs::rename(&object_path, &quarantine_path))
+            .inspect_err(|err| tracing::debug!(?err, ?object_path, "failed to quarantine object"))
+            .is_ok();
+        let hint = if moved {
+            format!(
+                "The corrupt object was moved to {}. Re-fetch it from a remote with `jj git \
+                 fetch`, or restore it from another clone of the repository.",
+                quarantine_path.display()
+            )
+        } else {
+            "The object is stored in a pack file. Restore the pack from another clone of the \
+             repository."
+                .to_owned()
+        };
+        Some(hint)
+    }
+
     async fn write_tree(&self, _path: &RepoPath, contents: &Tree) -> BackendResult<TreeId> {
         // Tree entries to be written must be sorted by Entry::filename(), which
         // is slightly different from the order of our backend::Tree.
@@ -1239,20 +1444,26 @@ impl Backend for GitBackend {
         }
         let git_commit_id = validate_git_object_id(id)?;
 
-        let mut commit = {
+        let (mut commit, pending_extras) = {
             let locked_repo = self.lock_git_repo();
             let git_object = locked_repo
                 .find_object(git_commit_id)
                 .map_err(|err| map_not_found_err(err, id))?;
             let is_shallow = self.shallow_root_ids(&locked_repo)?.contains(id);
-            commit_from_git_without_root_parent(id, &git_object, false, is_shallow)?
+            let commit = commit_from_git_without_root_parent(id, &git_object, false, is_shallow)?;
+            let pending_extras = locked_repo
+                .batch()
+                .and_then(|batch| batch.pending_extras.get(id).cloned());
+            (commit, pending_extras)
         };
         if commit.parents.is_empty() {
             commit.parents.push(self.root_commit_id.clone());
         };
 
         let table = self.cached_extra_metadata_table()?;
-        if let Some(extras) = table.get_value(id.as_bytes()) {
+        if let Some(extras) = &pending_extras {
+            deserialize_extras(&mut commit, extras);
+        } else if let Some(extras) = table.get_value(id.as_bytes()) {
             deserialize_extras(&mut commit, extras);
         } else {
             // TODO: Remove this hack and map to ObjectNotFound error if we're sure that
@@ -1275,7 +1486,7 @@ impl Backend for GitBackend {
     ) -> BackendResult<(CommitId, Commit)> {
         assert!(contents.secure_sig.is_none(), "commit.secure_sig was set");
 
-        let locked_repo = self.lock_git_repo();
+        let mut locked_repo = self.lock_git_repo();
         let git_tree_id = match &contents.root_tree {
             MergedTreeId::Legacy(tree_id) => validate_git_object_id(tree_id)?,
             MergedTreeId::Merge(tree_ids) => match tree_ids.as_resolved() {
@@ -1366,7 +1577,14 @@ impl Backend for GitBackend {
                         source: Box::new(err),
                     })?;
 
-            match table.get_value(git_id.as_bytes()) {
+            let id = CommitId::from_bytes(git_id.as_bytes());
+            let pending_extras = locked_repo
+                .batch()
+                .and_then(|batch| batch.pending_extras.get(&id));
+            match pending_extras
+                .map(Vec::as_slice)
+                .or_else(|| table.get_value(id.as_bytes()))
+            {
                 Some(existing_extras) if existing_extras != extras => {
                     // It's possible a commit already exists with the same
                     // commit id but different change id. Adjust the timestamp
@@ -1383,19 +1601,28 @@ impl Backend for GitBackend {
                     // and read back by `jj`.
                     committer.time.seconds -= 1;
                 }
-                _ => break CommitId::from_bytes(git_id.as_bytes()),
+                _ => break id,
             }
         };
 
+        // Update the signature to match the one that was actually written to the object
+        // store
+        contents.committer.timestamp.timestamp = MillisSinceEpoch(committer.time.seconds * 1000);
+
+        // The commit object only exists in memory while a write batch is open.
+        // Defer the no-gc ref and metadata entry until the batch is flushed so
+        // a failed flush wouldn't leave them pointing to a missing object.
+        if let Some(batch) = locked_repo.batch_mut() {
+            batch.pending_extras.insert(id.clone(), extras);
+            return Ok((id, contents));
+        }
+
         // Everything up to this point had no permanent effect on the repo except
         // GC-able objects
         locked_repo
             .edit_reference(to_no_gc_ref_update(&id))
             .map_err(|err| BackendError::Other(Box::new(err)))?;
 
-        // Update the signature to match the one that was actually written to the object
-        // store
-        contents.committer.timestamp.timestamp = MillisSinceEpoch(committer.time.seconds * 1000);
         let mut mut_table = table.start_mutation();
         mut_table.add_entry(id.to_bytes(), extras);
         self.save_extra_metadata_table(mut_table, &table_lock)?;
@@ -1481,6 +1708,59 @@ impl Backend for GitBackend {
         Ok(Box::pin(futures::stream::iter(records)))
     }
 
+    fn begin_write_batch(&self) {
+        let mut repos = self.repos.lock().unwrap();
+        let batch = WriteBatch {
+            repo: repos.shared.clone().with_object_memory(),
+            pending_extras: HashMap::new(),
+        };
+        repos.batches.insert(thread::current().id(), batch);
+    }
+
+    fn flush_write_batch(&self) -> BackendResult<()> {
+        let mut repos = self.repos.lock().unwrap();
+        let Some(mut batch) = repos.batches.remove(&thread::current().id()) else {
+            return Err(BackendError::Other(
+                "No write batch was started by this thread".into(),
+            ));
+        };
+        let objects = batch.repo.objects.take_object_memory().unwrap_or_default();
+        let packed = objects.len() >= MIN_OBJECTS_TO_PACK
+            && match write_pack(self.git_executable.as_ref(), self.git_repo_path(), &objects) {
+                Ok(()) => true,
+                Err(err) => {
+                    tracing::warn!(?err, "failed to write pack, writing loose objects instead");
+                    false
+                }
+            };
+        if !packed {
+            for (kind, data) in objects.values() {
+                repos.shared.objects.write_buf(*kind, data).map_err(|err| {
+                    BackendError::WriteObject {
+                        object_type: "object",
+                        source: err,
+                    }
+                })?;
+            }
+        }
+        if batch.pending_extras.is_empty() {
+            return Ok(());
+        }
+
+        // Now that the commit objects are on disk, make them reachable.
+        repos
+            .shared
+            .edit_references(batch.pending_extras.keys().map(to_no_gc_ref_update))
+            .map_err(|err| BackendError::Other(Box::new(err)))?;
+        let (table, table_lock) = self.read_extra_metadata_table_locked()?;
+        let mut mut_table = table.start_mutation();
+        for (id, extras) in batch.pending_extras {
+            mut_table.add_entry(id.to_bytes(), extras);
+        }
+        self.save_extra_metadata_table(mut_table, &table_lock)?;
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self, index))]
     fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
         let git_repo = self.lock_git_repo();
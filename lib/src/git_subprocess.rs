@@ -28,7 +28,9 @@ use bstr::ByteSlice as _;
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::git::GitPushNegotiation;
 use crate::git::GitPushStats;
+use crate::git::GitPushTransferStats;
 use crate::git::Progress;
 use crate::git::RefSpec;
 use crate::git::RefToPush;
@@ -179,11 +181,33 @@ impl<'a> GitSubprocessContext<'a> {
         command.arg("--").arg(remote_name.as_str());
         command.args(refspecs.iter().map(|x| x.to_git_format()));
 
-        let output = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
+        let (output, _log) = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
 
         parse_git_fetch_output(output)
     }
 
+    /// List which of the fully qualified `ref_names` exist on the remote
+    ///
+    /// `git ls-remote --refs -- <remote_name> <ref_names>...`
+    ///
+    /// Only the requested refs are listed, so this is cheap even if the
+    /// remote has a huge number of refs.
+    pub(crate) fn spawn_ls_remote(
+        &self,
+        remote_name: &RemoteName,
+        ref_names: &[&str],
+    ) -> Result<Vec<String>, GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::piped());
+        command.args(["ls-remote", "--refs", "--", remote_name.as_str()]);
+        command.args(ref_names);
+        let output = wait_with_output(self.spawn_cmd(command)?)?;
+
+        // ls-remote fails in the same way as remote show
+        let output = parse_git_remote_show_output(output)?;
+        Ok(parse_git_ls_remote_refs(&output.stdout))
+    }
+
     /// Prune particular branches
     pub(crate) fn spawn_branch_prune(
         &self,
@@ -249,10 +273,10 @@ impl<'a> GitSubprocessContext<'a> {
         //
         // https://github.com/jj-vcs/jj/issues/3577 and https://github.com/jj-vcs/jj/issues/405
         // offer more context
-        command.args(["push", "--porcelain", "--no-verify"]);
-        if callbacks.progress.is_some() {
-            command.arg("--progress");
-        }
+        //
+        // --progress is always passed so that the pack negotiation stats are
+        // reported even if stderr isn't a terminal
+        command.args(["push", "--porcelain", "--no-verify", "--progress"]);
         command.args(
             references
                 .iter()
@@ -267,9 +291,17 @@ impl<'a> GitSubprocessContext<'a> {
                 .map(|r| r.refspec.to_git_format_not_forced()),
         );
 
-        let output = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
+        let (output, log) = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
 
-        parse_git_push_output(output)
+        let (push_stats, summaries) = parse_git_push_output(output)?;
+        if let Some(cb) = callbacks.push_negotiation.as_mut() {
+            cb(&GitPushNegotiation {
+                summaries,
+                transfer: log.progress.to_push_transfer_stats(),
+                remote_messages: log.remote_messages,
+            });
+        }
+        Ok(push_stats)
     }
 }
 
@@ -422,6 +454,15 @@ fn parse_git_remote_show_output(output: Output) -> Result<Output, GitSubprocessE
     Err(external_git_error(&output.stderr))
 }
 
+fn parse_git_ls_remote_refs(stdout: &[u8]) -> Vec<String> {
+    // each line is formatted as "<oid>\t<ref name>"
+    stdout
+        .lines()
+        .filter_map(|line| line.split_once_str("\t"))
+        .map(|(_, name)| name.trim().to_str_lossy().into_owned())
+        .collect()
+}
+
 fn parse_git_remote_show_default_branch(
     stdout: &[u8],
 ) -> Result<Option<String>, GitSubprocessError> {
@@ -454,7 +495,12 @@ fn parse_git_remote_show_default_branch(
 // <summary> is extra info (commit ranges or reason for rejected)
 //
 // <reason> is a human-readable explanation
-fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushStats, GitSubprocessError> {
+/// Push stats and the summary reported for each reference.
+type RefPushes = (GitPushStats, Vec<(GitRefNameBuf, String)>);
+
+/// Parses the reference updates reported by `git push --porcelain`, along with
+/// the summary of each update.
+fn parse_ref_pushes(stdout: &[u8]) -> Result<RefPushes, GitSubprocessError> {
     if !stdout.starts_with(b"To ") {
         return Err(GitSubprocessError::External(format!(
             "Git push output unfamiliar:\n{}",
@@ -463,6 +509,8 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushStats, GitSubprocessError> {
     }
 
     let mut push_stats = GitPushStats::default();
+    let mut summaries = vec![];
+    let summary_string = |summary: &[u8]| summary.to_str_lossy().into_owned();
     for (idx, line) in stdout
         .lines()
         .skip(1)
@@ -504,10 +552,12 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushStats, GitSubprocessError> {
             //  * for a successfully pushed new ref
             //  =  for a ref that was up to date and did not need pushing.
             b"+" | b"-" | b"*" | b"=" | b" " => {
+                summaries.push((reference.clone(), summary_string(summary)));
                 push_stats.pushed.push(reference);
             }
             // ! for a ref that was rejected or failed to push; and
             b"!" => {
+                summaries.push((reference.clone(), summary_string(summary)));
                 if let Some(reason) = summary.strip_prefix(b"[remote rejected]") {
                     let reason = reason
                         .strip_prefix(b" (")
@@ -534,13 +584,13 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushStats, GitSubprocessError> {
         }
     }
 
-    Ok(push_stats)
+    Ok((push_stats, summaries))
 }
 
 // on Ok, return a tuple with
 //  1. list of failed references from test and set
 //  2. list of successful references pushed
-fn parse_git_push_output(output: Output) -> Result<GitPushStats, GitSubprocessError> {
+fn parse_git_push_output(output: Output) -> Result<RefPushes, GitSubprocessError> {
     if output.status.success() {
         let ref_pushes = parse_ref_pushes(&output.stdout)?;
         return Ok(ref_pushes);
@@ -585,12 +635,13 @@ fn wait_with_output(child: Child) -> Result<Output, GitSubprocessError> {
 /// remote:
 /// ```
 ///
-/// The returned `stderr` content does not include sideband messages.
+/// The returned `stderr` content does not include sideband messages nor
+/// progress lines. These are collected in the returned [`ProgressLog`].
 fn wait_with_progress(
     mut child: Child,
     callbacks: &mut RemoteCallbacks<'_>,
-) -> Result<Output, GitSubprocessError> {
-    let (stdout, stderr) = thread::scope(|s| -> io::Result<_> {
+) -> Result<(Output, ProgressLog), GitSubprocessError> {
+    let (stdout, (stderr, log)) = thread::scope(|s| -> io::Result<_> {
         drop(child.stdin.take());
         let mut child_stdout = child.stdout.take().expect("stdout should be piped");
         let mut child_stderr = child.stderr.take().expect("stderr should be piped");
@@ -605,11 +656,20 @@ fn wait_with_progress(
     })
     .map_err(GitSubprocessError::Wait)?;
     let status = child.wait().map_err(GitSubprocessError::Wait)?;
-    Ok(Output {
+    let output = Output {
         status,
         stdout,
         stderr,
-    })
+    };
+    Ok((output, log))
+}
+
+/// Progress and sideband messages reported by the `git` command.
+#[derive(Default)]
+struct ProgressLog {
+    progress: GitProgress,
+    /// Complete lines sent by the remote, without the `remote: ` prefix.
+    remote_messages: Vec<String>,
 }
 
 #[derive(Default)]
@@ -619,6 +679,10 @@ struct GitProgress {
     objects: (u64, u64),
     counted_objects: (u64, u64),
     compressed_objects: (u64, u64),
+    written_objects: (u64, u64),
+    remote_deltas: (u64, u64),
+    // number of deltas in the pack sent by push
+    pack_deltas: u64,
 }
 
 impl GitProgress {
@@ -629,22 +693,44 @@ impl GitProgress {
         }
     }
 
+    fn to_push_transfer_stats(&self) -> GitPushTransferStats {
+        GitPushTransferStats {
+            counted_objects: self.counted_objects.1,
+            compressed_objects: self.compressed_objects.1,
+            written_objects: self.written_objects.1,
+            deltas: self.pack_deltas,
+            resolved_deltas: self.remote_deltas.1,
+        }
+    }
+
     fn fraction(&self) -> u64 {
-        self.objects.0 + self.deltas.0 + self.counted_objects.0 + self.compressed_objects.0
+        self.objects.0
+            + self.deltas.0
+            + self.counted_objects.0
+            + self.compressed_objects.0
+            + self.written_objects.0
+            + self.remote_deltas.0
     }
 
     fn total(&self) -> u64 {
-        self.objects.1 + self.deltas.1 + self.counted_objects.1 + self.compressed_objects.1
+        self.objects.1
+            + self.deltas.1
+            + self.counted_objects.1
+            + self.compressed_objects.1
+            + self.written_objects.1
+            + self.remote_deltas.1
     }
 }
 
 fn read_to_end_with_progress<R: Read>(
     src: R,
     callbacks: &mut RemoteCallbacks<'_>,
-) -> io::Result<Vec<u8>> {
+) -> io::Result<(Vec<u8>, ProgressLog)> {
     let mut reader = BufReader::new(src);
     let mut data = Vec::new();
-    let mut git_progress = GitProgress::default();
+    let mut log = ProgressLog::default();
+    let git_progress = &mut log.progress;
+    let mut remote_line = Vec::new();
 
     loop {
         // progress sent through sideband channel may be terminated by \r
@@ -667,23 +753,63 @@ fn read_to_end_with_progress<R: Read>(
                 &mut git_progress.compressed_objects,
                 b"remote: Compressing objects:",
             )
+            || update_progress(
+                line,
+                &mut git_progress.remote_deltas,
+                b"remote: Resolving deltas:",
+            )
+            // pack sent by push
+            || update_progress(line, &mut git_progress.counted_objects, b"Counting objects:")
+            || update_progress(
+                line,
+                &mut git_progress.compressed_objects,
+                b"Compressing objects:",
+            )
+            || update_progress(line, &mut git_progress.written_objects, b"Writing objects:")
         {
             if let Some(cb) = callbacks.progress.as_mut() {
                 cb(&git_progress.to_progress());
             }
             data.truncate(start);
+        } else if let Some(deltas) = parse_pack_total_deltas(line) {
+            git_progress.pack_deltas = deltas;
+            data.truncate(start);
+        } else if line.starts_with(b"Enumerating objects:")
+            || line.starts_with(b"Delta compression using")
+        {
+            data.truncate(start);
         } else if let Some(message) = line.strip_prefix(b"remote: ") {
+            let (body, term) = trim_sideband_line(message);
             if let Some(cb) = callbacks.sideband_progress.as_mut() {
-                let (body, term) = trim_sideband_line(message);
                 cb(body);
                 if let Some(term) = term {
                     cb(&[term]);
                 }
             }
+            // Lines terminated by \r will be overwritten by the next line.
+            remote_line.clear();
+            remote_line.extend_from_slice(body);
+            if term == Some(b'\n') {
+                log.remote_messages
+                    .push(remote_line.to_str_lossy().into_owned());
+                remote_line.clear();
+            }
             data.truncate(start);
         }
     }
-    Ok(data)
+    if !remote_line.is_empty() {
+        log.remote_messages
+            .push(remote_line.to_str_lossy().into_owned());
+    }
+    Ok((data, log))
+}
+
+/// Parses the number of deltas from the summary line of the pack sent by push,
+/// e.g. `Total 3 (delta 1), reused 0 (delta 0), pack-reused 0`.
+fn parse_pack_total_deltas(line: &[u8]) -> Option<u64> {
+    let (_objects, rest) = line.strip_prefix(b"Total ")?.split_once_str(" (delta ")?;
+    let (deltas, _rest) = rest.split_once_str(")")?;
+    deltas.to_str().ok()?.parse().ok()
 }
 
 fn update_progress(line: &[u8], progress: &mut (u64, u64), prefix: &[u8]) -> bool {
@@ -752,6 +878,7 @@ fn trim_sideband_line(line: &[u8]) -> (&[u8], Option<u8>) {
 #[cfg(test)]
 mod test {
     use indoc::formatdoc;
+    use indoc::indoc;
 
     use super::*;
 
@@ -852,11 +979,14 @@ Done";
         assert!(parse_ref_pushes(SAMPLE_NO_SUCH_REMOTE_ERROR).is_err());
         assert!(parse_ref_pushes(SAMPLE_NO_REMOTE_REF_ERROR).is_err());
         assert!(parse_ref_pushes(SAMPLE_NO_REMOTE_TRACKING_BRANCH_ERROR).is_err());
-        let GitPushStats {
-            pushed,
-            rejected,
-            remote_rejected,
-        } = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
+        let (
+            GitPushStats {
+                pushed,
+                rejected,
+                remote_rejected,
+            },
+            summaries,
+        ) = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
         assert_eq!(
             pushed,
             [
@@ -888,6 +1018,15 @@ Done";
                 ("refs/heads/bookmark9".into(), None)
             ]
         );
+        assert_eq!(summaries.len(), 9);
+        assert_eq!(
+            summaries[0],
+            ("refs/heads/bookmark1".into(), "[new branch]".to_string())
+        );
+        assert_eq!(
+            summaries[4],
+            ("refs/heads/bookmark5".into(), "abcd..abcd".to_string())
+        );
         assert!(parse_ref_pushes(SAMPLE_OK_STDERR).is_err());
     }
 
@@ -901,7 +1040,8 @@ Done";
             callbacks.progress = Some(&mut progress_cb);
             let mut sideband_cb = |s: &[u8]| sideband.push(s.to_owned());
             callbacks.sideband_progress = Some(&mut sideband_cb);
-            let output = read_to_end_with_progress(&mut &sample[..], &mut callbacks).unwrap();
+            let (output, _log) =
+                read_to_end_with_progress(&mut &sample[..], &mut callbacks).unwrap();
             (output, sideband, progress)
         };
         const DUMB_SUFFIX: &str = "        ";
@@ -940,6 +1080,56 @@ Done";
         assert_eq!(output, b"blah blah\nsome error message");
     }
 
+    #[test]
+    fn test_read_to_end_with_push_progress() {
+        let sample = indoc! {"
+            Enumerating objects: 5, done.
+            Counting objects: 100% (5/5), done.
+            Delta compression using up to 8 threads
+            Compressing objects:  50% (1/2)\rCompressing objects: 100% (2/2), done.
+            Writing objects: 100% (3/3), 280 bytes | 280.00 KiB/s, done.
+            Total 3 (delta 1), reused 0 (delta 0), pack-reused 0
+            remote: Resolving deltas: 100% (1/1), completed with 1 local object.
+            remote: Create a pull request by visiting:
+            remote:   https://example.com/pull/new  \rremote:   https://example.com/pull/new
+            some error message
+        "};
+        let mut callbacks = RemoteCallbacks::default();
+        let (output, log) =
+            read_to_end_with_progress(&mut sample.as_bytes(), &mut callbacks).unwrap();
+        assert_eq!(output, b"some error message\n");
+        assert_eq!(
+            log.progress.to_push_transfer_stats(),
+            GitPushTransferStats {
+                counted_objects: 5,
+                compressed_objects: 2,
+                written_objects: 3,
+                deltas: 1,
+                resolved_deltas: 1,
+            }
+        );
+        assert_eq!(
+            log.remote_messages,
+            [
+                "Create a pull request by visiting:",
+                "  https://example.com/pull/new"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pack_total_deltas() {
+        assert_eq!(
+            parse_pack_total_deltas(b"Total 3 (delta 1), reused 0 (delta 0), pack-reused 0\n"),
+            Some(1)
+        );
+        assert_eq!(
+            parse_pack_total_deltas(b"Total 0 (delta 0), reused 0"),
+            Some(0)
+        );
+        assert_eq!(parse_pack_total_deltas(b"remote: Total 3 (delta 1)"), None);
+    }
+
     #[test]
     fn test_read_progress_line() {
         assert_eq!(
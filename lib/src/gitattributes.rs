@@ -0,0 +1,438 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-path attributes declared in `.gitattributes` files.
+//!
+//! The file format is the same as Git's. See
+//! <https://git-scm.com/docs/gitattributes> for details. Only the subset of
+//! the format that can be evaluated without Git's configuration is supported:
+//! quoted patterns and macro definitions other than the builtin `binary`
+//! macro are ignored, and merge drivers other than the builtin ones fall back
+//! to the `text` driver.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use globset::GlobBuilder;
+use globset::GlobMatcher;
+use pollster::FutureExt as _;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::TreeValue;
+use crate::merged_tree::MergedTree;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponent;
+
+/// Name of the file declaring the attributes of the paths in its directory.
+pub const GITATTRIBUTES_FILE_NAME: &str = ".gitattributes";
+
+/// State of an attribute assigned to a path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeState {
+    /// The attribute is set, e.g. `text`.
+    Set,
+    /// The attribute is unset, e.g. `-text`.
+    Unset,
+    /// The attribute is set to a value, e.g. `eol=lf`.
+    Value(String),
+    /// The attribute is reverted to the unspecified state, e.g. `!text`.
+    Unspecified,
+}
+
+/// Content merge strategy selected by the `merge` attribute.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MergeDriver {
+    /// Merges the contents line by line. This is the default.
+    Text,
+    /// Doesn't merge the contents. Files changed on both sides are left
+    /// conflicted.
+    Binary,
+    /// Resolves conflicting hunks by taking the lines added by all sides.
+    Union,
+}
+
+/// Parsed attribute rules.
+#[derive(Clone, Debug, Default)]
+pub struct GitAttributes {
+    /// Rules in precedence order. Later rules override earlier ones.
+    rules: Vec<AttributeRule>,
+}
+
+#[derive(Clone, Debug)]
+struct AttributeRule {
+    matcher: GlobMatcher,
+    attributes: Vec<(String, AttributeState)>,
+}
+
+impl GitAttributes {
+    /// Creates an empty rule set which assigns no attributes.
+    pub const fn empty() -> Self {
+        GitAttributes { rules: Vec::new() }
+    }
+
+    /// Parses the content of `.gitattributes` file placed at the workspace
+    /// root.
+    ///
+    /// Malformed lines are ignored as Git does.
+    pub fn parse(text: &str) -> Self {
+        Self::parse_in_dir(RepoPath::root(), text)
+    }
+
+    /// Parses the content of `.gitattributes` file placed in `dir`. The rules
+    /// only apply to the paths under `dir`.
+    pub fn parse_in_dir(dir: &RepoPath, text: &str) -> Self {
+        let prefix = if dir.is_root() {
+            String::new()
+        } else {
+            format!("{}/", globset::escape(dir.as_internal_file_string()))
+        };
+        let rules = text
+            .lines()
+            .filter_map(|line| parse_line(&prefix, line))
+            .collect();
+        GitAttributes { rules }
+    }
+
+    /// Returns new rule set in which the rules of `other` take precedence over
+    /// the rules of `self`.
+    pub fn chain(mut self, other: Self) -> Self {
+        self.rules.extend(other.rules);
+        self
+    }
+
+    /// Returns true if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Looks up the state of the attribute `name` assigned to `path`.
+    ///
+    /// Returns `None` if the attribute is unspecified.
+    pub fn get(&self, path: &RepoPath, name: &str) -> Option<&AttributeState> {
+        match self.lookup(path, name)? {
+            AttributeState::Unspecified => None,
+            state => Some(state),
+        }
+    }
+
+    /// Looks up the last state of the attribute `name` assigned to `path`,
+    /// including explicit [`AttributeState::Unspecified`].
+    fn lookup(&self, path: &RepoPath, name: &str) -> Option<&AttributeState> {
+        let path = path.as_internal_file_string();
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.matcher.is_match(path))
+            .find_map(|rule| {
+                rule.attributes
+                    .iter()
+                    .rev()
+                    .find(|(attr_name, _)| attr_name == name)
+                    .map(|(_, state)| state)
+            })
+    }
+
+    /// Returns whether the content of `path` should be diffed as binary.
+    ///
+    /// This is determined by the `diff` attribute. Returns `None` if the
+    /// attribute is unspecified, in which case the content should be
+    /// inspected.
+    pub fn is_binary(&self, path: &RepoPath) -> Option<bool> {
+        diff_state_to_binary(self.get(path, "diff"))
+    }
+
+    /// Returns the merge driver selected by the `merge` attribute of `path`.
+    pub fn merge_driver(&self, path: &RepoPath) -> MergeDriver {
+        merge_state_to_driver(self.get(path, "merge"))
+    }
+}
+
+fn diff_state_to_binary(state: Option<&AttributeState>) -> Option<bool> {
+    match state? {
+        AttributeState::Unset => Some(true),
+        AttributeState::Set | AttributeState::Value(_) => Some(false),
+        AttributeState::Unspecified => None,
+    }
+}
+
+fn merge_state_to_driver(state: Option<&AttributeState>) -> MergeDriver {
+    match state {
+        Some(AttributeState::Unset) => MergeDriver::Binary,
+        Some(AttributeState::Value(driver)) if driver == "binary" => MergeDriver::Binary,
+        Some(AttributeState::Value(driver)) if driver == "union" => MergeDriver::Union,
+        // Custom drivers are defined in Git's configuration, which isn't
+        // available here.
+        Some(AttributeState::Set | AttributeState::Value(_) | AttributeState::Unspecified)
+        | None => MergeDriver::Text,
+    }
+}
+
+/// Attributes declared by the `.gitattributes` files in a tree.
+///
+/// The files are read lazily as paths are looked up. Rules in subdirectories
+/// take precedence over the rules in their parent directories, and the
+/// `overrides` (e.g. `.jj/attributes`) take precedence over all of them.
+#[derive(Debug)]
+pub struct TreeAttributes {
+    tree: Option<MergedTree>,
+    overrides: GitAttributes,
+    /// Rules applicable to the paths in each directory looked up so far.
+    dir_rules: Mutex<HashMap<RepoPathBuf, Arc<GitAttributes>>>,
+}
+
+impl TreeAttributes {
+    /// Creates attributes declared in `tree` and `overrides`.
+    pub fn new(tree: MergedTree, overrides: GitAttributes) -> Self {
+        TreeAttributes {
+            tree: Some(tree),
+            overrides,
+            dir_rules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates attributes declared only in `overrides`.
+    pub fn from_overrides(overrides: GitAttributes) -> Self {
+        TreeAttributes {
+            tree: None,
+            overrides,
+            dir_rules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Looks up the state of the attribute `name` assigned to `path`.
+    ///
+    /// Returns `None` if the attribute is unspecified.
+    pub fn get(&self, path: &RepoPath, name: &str) -> BackendResult<Option<AttributeState>> {
+        let state = match self.overrides.lookup(path, name) {
+            Some(state) => Some(state.clone()),
+            None => {
+                let dir = path.parent().unwrap_or(RepoPath::root());
+                self.rules_for_dir(dir)?.lookup(path, name).cloned()
+            }
+        };
+        match state {
+            Some(AttributeState::Unspecified) | None => Ok(None),
+            state => Ok(state),
+        }
+    }
+
+    /// Returns whether the content of `path` should be diffed as binary. See
+    /// [`GitAttributes::is_binary()`].
+    pub fn is_binary(&self, path: &RepoPath) -> BackendResult<Option<bool>> {
+        Ok(diff_state_to_binary(self.get(path, "diff")?.as_ref()))
+    }
+
+    /// Returns the merge driver selected by the `merge` attribute of `path`.
+    pub fn merge_driver(&self, path: &RepoPath) -> BackendResult<MergeDriver> {
+        Ok(merge_state_to_driver(self.get(path, "merge")?.as_ref()))
+    }
+
+    fn rules_for_dir(&self, dir: &RepoPath) -> BackendResult<Arc<GitAttributes>> {
+        if let Some(rules) = self.dir_rules.lock().unwrap().get(dir) {
+            return Ok(rules.clone());
+        }
+        let parent_rules = match dir.parent() {
+            Some(parent) => self.rules_for_dir(parent)?,
+            None => Arc::new(GitAttributes::empty()),
+        };
+        let rules = match self.read_attributes_file(dir)? {
+            Some(text) => Arc::new(
+                GitAttributes::clone(&parent_rules).chain(GitAttributes::parse_in_dir(dir, &text)),
+            ),
+            None => parent_rules,
+        };
+        self.dir_rules
+            .lock()
+            .unwrap()
+            .insert(dir.to_owned(), rules.clone());
+        Ok(rules)
+    }
+
+    fn read_attributes_file(&self, dir: &RepoPath) -> BackendResult<Option<String>> {
+        let Some(tree) = &self.tree else {
+            return Ok(None);
+        };
+        let path = dir.join(RepoPathComponent::new(GITATTRIBUTES_FILE_NAME).unwrap());
+        // Conflicted files are ignored.
+        let Ok(Some(TreeValue::File { id, .. })) = tree.path_value(&path)?.into_resolved() else {
+            return Ok(None);
+        };
+        let mut content = vec![];
+        tree.store()
+            .read_file(&path, &id)
+            .block_on()?
+            .read_to_end(&mut content)
+            .block_on()
+            .map_err(|err| BackendError::ReadFile {
+                path: path.clone(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        Ok(Some(String::from_utf8_lossy(&content).into_owned()))
+    }
+}
+
+fn parse_line(prefix: &str, line: &str) -> Option<AttributeRule> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let mut tokens = line.split_ascii_whitespace();
+    let pattern = tokens.next()?;
+    // Quoted patterns, macro definitions, and negative patterns aren't
+    // supported. Patterns ending with "/" never match files.
+    let is_macro = pattern.starts_with("[attr]");
+    if pattern.starts_with(['"', '!']) || is_macro || pattern.ends_with('/') {
+        return None;
+    }
+    let glob = if let Some(anchored) = pattern.strip_prefix('/') {
+        format!("{prefix}{anchored}")
+    } else if pattern.contains('/') {
+        format!("{prefix}{pattern}")
+    } else {
+        format!("{prefix}**/{pattern}")
+    };
+    let matcher = GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .ok()?
+        .compile_matcher();
+    let mut attributes = vec![];
+    for token in tokens {
+        if token == "binary" {
+            // Builtin macro: "[attr]binary -diff -merge -text"
+            attributes.push(("binary".to_owned(), AttributeState::Set));
+            for name in ["diff", "merge", "text"] {
+                attributes.push((name.to_owned(), AttributeState::Unset));
+            }
+        } else if let Some(name) = token.strip_prefix('-') {
+            attributes.push((name.to_owned(), AttributeState::Unset));
+        } else if let Some(name) = token.strip_prefix('!') {
+            attributes.push((name.to_owned(), AttributeState::Unspecified));
+        } else if let Some((name, value)) = token.split_once('=') {
+            attributes.push((name.to_owned(), AttributeState::Value(value.to_owned())));
+        } else {
+            attributes.push((token.to_owned(), AttributeState::Set));
+        }
+    }
+    Some(AttributeRule {
+        matcher,
+        attributes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_path(value: &str) -> &RepoPath {
+        RepoPath::from_internal_string(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_get() {
+        let attributes = GitAttributes::parse(
+            "\
+# comment
+*.png binary
+/top.txt text eol=lf
+docs/*.md -text
+\"quoted.txt\" text
+[attr]custom text
+",
+        );
+        assert_eq!(attributes.rules.len(), 3);
+        assert_eq!(
+            attributes.get(repo_path("a/b.png"), "diff"),
+            Some(&AttributeState::Unset)
+        );
+        assert_eq!(
+            attributes.get(repo_path("a/b.png"), "binary"),
+            Some(&AttributeState::Set)
+        );
+        assert_eq!(
+            attributes.get(repo_path("top.txt"), "eol"),
+            Some(&AttributeState::Value("lf".to_owned()))
+        );
+        assert_eq!(attributes.get(repo_path("dir/top.txt"), "eol"), None);
+        assert_eq!(
+            attributes.get(repo_path("docs/a.md"), "text"),
+            Some(&AttributeState::Unset)
+        );
+        assert_eq!(attributes.get(repo_path("docs/sub/a.md"), "text"), None);
+        assert_eq!(attributes.get(repo_path("quoted.txt"), "text"), None);
+    }
+
+    #[test]
+    fn test_later_rule_wins() {
+        let attributes = GitAttributes::parse(
+            "\
+*.dat binary
+special.dat diff
+*.bin -diff
+*.bin !diff
+",
+        );
+        assert_eq!(attributes.is_binary(repo_path("a.dat")), Some(true));
+        assert_eq!(attributes.is_binary(repo_path("special.dat")), Some(false));
+        assert_eq!(attributes.is_binary(repo_path("a.bin")), None);
+        assert_eq!(attributes.is_binary(repo_path("a.txt")), None);
+
+        let overrides = GitAttributes::parse("a.dat diff\n");
+        let attributes = attributes.chain(overrides);
+        assert_eq!(attributes.is_binary(repo_path("a.dat")), Some(false));
+        assert_eq!(attributes.is_binary(repo_path("b.dat")), Some(true));
+        assert!(GitAttributes::empty().is_empty());
+    }
+
+    #[test]
+    fn test_parse_in_dir() {
+        let attributes = GitAttributes::parse_in_dir(
+            repo_path("sub[1]"),
+            "\
+*.dat binary
+/top.txt eol=crlf
+docs/*.md merge=union
+",
+        );
+        assert_eq!(attributes.is_binary(repo_path("sub[1]/a.dat")), Some(true));
+        assert_eq!(
+            attributes.is_binary(repo_path("sub[1]/x/a.dat")),
+            Some(true)
+        );
+        assert_eq!(attributes.is_binary(repo_path("a.dat")), None);
+        assert_eq!(attributes.is_binary(repo_path("sub1/a.dat")), None);
+        assert_eq!(
+            attributes.get(repo_path("sub[1]/top.txt"), "eol"),
+            Some(&AttributeState::Value("crlf".to_owned()))
+        );
+        assert_eq!(attributes.get(repo_path("sub[1]/x/top.txt"), "eol"), None);
+        assert_eq!(
+            attributes.merge_driver(repo_path("sub[1]/docs/a.md")),
+            MergeDriver::Union
+        );
+        assert_eq!(
+            attributes.merge_driver(repo_path("docs/a.md")),
+            MergeDriver::Text
+        );
+        assert_eq!(
+            attributes.merge_driver(repo_path("sub[1]/a.dat")),
+            MergeDriver::Binary
+        );
+    }
+}
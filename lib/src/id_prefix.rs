@@ -132,6 +132,12 @@ impl IdPrefixContext {
         self
     }
 
+    /// Creates a new context which disambiguates IDs within the given
+    /// `expression` instead, sharing the revset extensions with this context.
+    pub fn scoped_to(&self, expression: Rc<UserRevsetExpression>) -> Self {
+        Self::new(self.extensions.clone()).disambiguate_within(expression)
+    }
+
     /// Loads disambiguation index once, returns a borrowed index to
     /// disambiguate commit/change IDs.
     pub fn populate(&self, repo: &dyn Repo) -> Result<IdPrefixIndex<'_>, IdPrefixIndexLoadError> {
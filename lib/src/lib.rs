@@ -71,6 +71,7 @@ pub mod git {
 pub mod git_backend;
 #[cfg(feature = "git")]
 mod git_subprocess;
+pub mod gitattributes;
 pub mod gitignore;
 pub mod gpg_signing;
 pub mod graph;
@@ -79,10 +80,12 @@ pub mod id_prefix;
 pub mod index;
 pub mod local_working_copy;
 pub mod lock;
+pub mod mailmap;
 pub mod matchers;
 pub mod merge;
 pub mod merged_tree;
 pub mod object_id;
+pub mod op_bundle;
 pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
@@ -92,7 +95,9 @@ pub mod protos;
 pub mod ref_name;
 pub mod refs;
 pub mod repo;
+pub mod repo_format;
 pub mod repo_path;
+pub mod repo_snapshot;
 pub mod revset;
 mod revset_parser;
 pub mod rewrite;
@@ -17,6 +17,7 @@
 
 use std::any::Any;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
@@ -71,23 +72,29 @@ use crate::commit::Commit;
 use crate::config::ConfigGetError;
 use crate::conflicts;
 use crate::conflicts::choose_materialized_conflict_marker_len;
-use crate::conflicts::materialize_merge_result_to_bytes_with_marker_len;
+use crate::conflicts::materialize_merge_result_to_bytes_with_labels;
 use crate::conflicts::materialize_tree_value;
+use crate::conflicts::ConflictLabels;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::MalformedConflictHunk;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::MIN_CONFLICT_MARKER_LEN;
 use crate::eol::create_target_eol_strategy;
+use crate::eol::EolAttribute;
 pub use crate::eol::EolConversionMode;
 use crate::eol::TargetEolStrategy;
 use crate::file_util::check_symlink_support;
 use crate::file_util::copy_async_to_sync;
 use crate::file_util::try_symlink;
+use crate::file_util::write_file_atomically;
 use crate::file_util::BlockingAsyncReader;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::WatchmanConfig;
+use crate::gitattributes::GitAttributes;
+use crate::gitattributes::TreeAttributes;
 use crate::gitignore::GitIgnoreFile;
 use crate::lock::FileLock;
 use crate::matchers::DifferenceMatcher;
@@ -114,8 +121,10 @@ use crate::store::Store;
 use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
 use crate::working_copy::CheckoutOptions;
+use crate::working_copy::CheckoutProgress;
 use crate::working_copy::CheckoutStats;
 use crate::working_copy::LockedWorkingCopy;
+use crate::working_copy::NewDirectoryPolicy;
 use crate::working_copy::ResetError;
 use crate::working_copy::SnapshotError;
 use crate::working_copy::SnapshotOptions;
@@ -155,6 +164,50 @@ impl FileExecutableFlag {
     }
 }
 
+/// Heuristics to determine the executable bit of new files on platforms which
+/// don't support the executable bit.
+///
+/// They are only applied when a new file is snapshotted. Existing files
+/// preserve the executable bit recorded in the tree, and checkout doesn't
+/// depend on them.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExecBitHeuristics {
+    /// Files starting with `#!` are executable.
+    pub shebang: bool,
+    /// Files with one of these extensions (without the leading `.`) are
+    /// executable.
+    pub extensions: Vec<String>,
+}
+
+impl ExecBitHeuristics {
+    /// Loads the `working-copy.exec-bit-heuristics` setting.
+    pub fn try_from_settings(user_settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        user_settings.get("working-copy.exec-bit-heuristics")
+    }
+
+    /// Returns true if the file at `disk_path` should be considered
+    /// executable.
+    pub fn is_executable(&self, disk_path: &Path) -> bool {
+        let has_listed_extension = disk_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|listed| listed.eq_ignore_ascii_case(ext))
+            });
+        has_listed_extension || (self.shebang && has_shebang(disk_path))
+    }
+}
+
+fn has_shebang(disk_path: &Path) -> bool {
+    let mut prefix = [0; 2];
+    File::open(disk_path)
+        .and_then(|mut file| file.read_exact(&mut prefix))
+        .is_ok_and(|()| &prefix == b"#!")
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum FileType {
     Normal { executable: FileExecutableFlag },
@@ -167,11 +220,63 @@ pub struct MaterializedConflictData {
     pub conflict_marker_len: u32,
 }
 
+/// Additional file metadata to detect changes which can't be detected by the
+/// mtime in milliseconds and the size.
+///
+/// Fields which are unknown (e.g. recorded by older versions, or unsupported
+/// on the platform) are zero, and they aren't compared.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct FileFingerprint {
+    /// Sub-millisecond part of the mtime in nanoseconds.
+    pub mtime_nanos: u32,
+    /// Inode change time in nanoseconds since epoch.
+    pub ctime_nanos: i64,
+    /// Inode number.
+    pub inode: u64,
+}
+
+impl FileFingerprint {
+    fn from_metadata(metadata: &Metadata) -> Self {
+        let mtime_nanos = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since_epoch| since_epoch.subsec_nanos() % 1_000_000);
+        #[cfg(unix)]
+        let (ctime_nanos, inode) = {
+            use std::os::unix::fs::MetadataExt as _;
+            let ctime_nanos = metadata
+                .ctime()
+                .saturating_mul(1_000_000_000)
+                .saturating_add(metadata.ctime_nsec());
+            (ctime_nanos, metadata.ino())
+        };
+        #[cfg(not(unix))]
+        let (ctime_nanos, inode) = (0, 0);
+        FileFingerprint {
+            mtime_nanos,
+            ctime_nanos,
+            inode,
+        }
+    }
+
+    /// Returns false if any of the known fields differs.
+    fn matches(&self, other: &Self) -> bool {
+        fn field_matches<T: Default + Eq>(a: T, b: T) -> bool {
+            a == T::default() || b == T::default() || a == b
+        }
+        field_matches(self.mtime_nanos, other.mtime_nanos)
+            && field_matches(self.ctime_nanos, other.ctime_nanos)
+            && field_matches(self.inode, other.inode)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct FileState {
     pub file_type: FileType,
     pub mtime: MillisSinceEpoch,
     pub size: u64,
+    pub fingerprint: FileFingerprint,
     pub materialized_conflict_data: Option<MaterializedConflictData>,
     /* TODO: What else do we need here? Git stores a lot of fields.
      * TODO: Could possibly handle case-insensitive file systems keeping an
@@ -185,6 +290,7 @@ impl FileState {
         self.file_type == old_file_state.file_type
             && self.mtime == old_file_state.mtime
             && self.size == old_file_state.size
+            && self.fingerprint.matches(&old_file_state.fingerprint)
     }
 
     /// Indicates that a file exists in the tree but that it needs to be
@@ -195,6 +301,7 @@ impl FileState {
             file_type: FileType::Normal { executable },
             mtime: MillisSinceEpoch(0),
             size: 0,
+            fingerprint: FileFingerprint::default(),
             materialized_conflict_data: None,
         }
     }
@@ -210,6 +317,7 @@ impl FileState {
             file_type: FileType::Normal { executable },
             mtime: mtime_from_metadata(metadata),
             size,
+            fingerprint: FileFingerprint::from_metadata(metadata),
             materialized_conflict_data,
         }
     }
@@ -222,6 +330,7 @@ impl FileState {
             file_type: FileType::Symlink,
             mtime: mtime_from_metadata(metadata),
             size: metadata.len(),
+            fingerprint: FileFingerprint::from_metadata(metadata),
             materialized_conflict_data: None,
         }
     }
@@ -231,6 +340,7 @@ impl FileState {
             file_type: FileType::GitSubmodule,
             mtime: MillisSinceEpoch(0),
             size: 0,
+            fingerprint: FileFingerprint::default(),
             materialized_conflict_data: None,
         }
     }
@@ -448,6 +558,9 @@ pub struct TreeStateSettings {
     /// file to the backend, and vice versa when it checks out code onto your
     /// filesystem.
     pub eol_conversion_mode: EolConversionMode,
+    /// How to determine the executable bit of new files on platforms without
+    /// executable bit support.
+    pub exec_bit_heuristics: ExecBitHeuristics,
 }
 
 impl TreeStateSettings {
@@ -455,6 +568,7 @@ impl TreeStateSettings {
     pub fn try_from_user_settings(user_settings: &UserSettings) -> Result<Self, ConfigGetError> {
         Ok(Self {
             eol_conversion_mode: EolConversionMode::try_from_settings(user_settings)?,
+            exec_bit_heuristics: ExecBitHeuristics::try_from_settings(user_settings)?,
         })
     }
 }
@@ -475,7 +589,23 @@ pub struct TreeState {
     /// Watchman has been queried at least once.
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
 
+    /// New directories left untracked by the last snapshot because they had
+    /// too many files.
+    large_new_directories: HashMap<RepoPathBuf, LargeNewDirectory>,
+
     target_eol_strategy: TargetEolStrategy,
+    exec_bit_heuristics: ExecBitHeuristics,
+}
+
+/// New directory that had too many files to be snapshotted. See
+/// [`NewDirectoryPolicy::Exclude`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct LargeNewDirectory {
+    /// The mtime of the directory when its files were counted. The files are
+    /// counted again if the mtime changes.
+    mtime: MillisSinceEpoch,
+    /// The number of files the directory was found to have more than.
+    max_files: usize,
 }
 
 fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> FileState {
@@ -498,6 +628,11 @@ fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> File
         file_type,
         mtime: MillisSinceEpoch(proto.mtime_millis_since_epoch),
         size: proto.size,
+        fingerprint: FileFingerprint {
+            mtime_nanos: proto.mtime_nanos,
+            ctime_nanos: proto.ctime_nanos_since_epoch,
+            inode: proto.inode,
+        },
         materialized_conflict_data: proto.materialized_conflict_data.as_ref().map(|data| {
             MaterializedConflictData {
                 conflict_marker_len: data.conflict_marker_len,
@@ -522,6 +657,9 @@ fn file_state_to_proto(file_state: &FileState) -> crate::protos::working_copy::F
     proto.file_type = file_type as i32;
     proto.mtime_millis_since_epoch = file_state.mtime.0;
     proto.size = file_state.size;
+    proto.mtime_nanos = file_state.fingerprint.mtime_nanos;
+    proto.ctime_nanos_since_epoch = file_state.fingerprint.ctime_nanos;
+    proto.inode = file_state.fingerprint.inode;
     proto.materialized_conflict_data = file_state.materialized_conflict_data.map(|data| {
         crate::protos::working_copy::MaterializedConflictData {
             conflict_marker_len: data.conflict_marker_len,
@@ -764,6 +902,7 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
             file_type,
             mtime,
             size,
+            fingerprint: FileFingerprint::from_metadata(metadata),
             materialized_conflict_data: None,
         }
     })
@@ -774,6 +913,50 @@ struct FsmonitorMatcher {
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
 }
 
+/// Journal of a checkout in progress, stored in the `pending_checkout` file.
+///
+/// The file starts with the old and new tree ids on separate lines, followed
+/// by NUL-terminated paths. Each path is appended before its file on disk is
+/// touched, so the journal covers every file the checkout may have written.
+struct PendingCheckout {
+    old_tree_id: MergedTreeId,
+    new_tree_id: MergedTreeId,
+    written_paths: HashSet<RepoPathBuf>,
+}
+
+/// Journal to record the paths modified by [`TreeState::update()`] to.
+#[derive(Default)]
+struct CheckoutJournal {
+    /// Journal file to append paths to, if the update can be recovered.
+    file: Option<File>,
+    /// Paths written by an interrupted checkout. These files are replaced even
+    /// if they aren't tracked in the old tree.
+    written_paths: HashSet<RepoPathBuf>,
+}
+
+impl CheckoutJournal {
+    fn record(&mut self, path: &RepoPath) -> Result<(), CheckoutError> {
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+        let entry = format!("{}\0", path.as_internal_file_string());
+        file.write_all(entry.as_bytes())
+            .map_err(|err| CheckoutError::Other {
+                message: "Failed to write pending checkout".to_string(),
+                err: err.into(),
+            })
+    }
+}
+
+/// How to recover from an interrupted checkout.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CheckoutRecovery {
+    /// Finish updating the working copy to the new tree.
+    Resume,
+    /// Restore the files written by the interrupted checkout to the old tree.
+    Rollback,
+}
+
 #[derive(Debug, Error)]
 pub enum TreeStateError {
     #[error("Reading tree state from {path}")]
@@ -787,6 +970,8 @@ pub enum TreeStateError {
     WriteTreeState { path: PathBuf, source: io::Error },
     #[error("Persisting tree state to file {path}")]
     PersistTreeState { path: PathBuf, source: io::Error },
+    #[error("Removing pending checkout {path}")]
+    RemovePendingCheckout { path: PathBuf, source: io::Error },
     #[error("Filesystem monitor error")]
     Fsmonitor(#[source] Box<dyn Error + Send + Sync>),
 }
@@ -818,8 +1003,7 @@ impl TreeState {
         state_path: PathBuf,
         tree_state_settings: &TreeStateSettings,
     ) -> Result<TreeState, TreeStateError> {
-        let target_eol_strategy = create_target_eol_strategy(tree_state_settings);
-        let mut wc = TreeState::empty(store, working_copy_path, state_path, target_eol_strategy);
+        let mut wc = TreeState::empty(store, working_copy_path, state_path, tree_state_settings);
         wc.save()?;
         Ok(wc)
     }
@@ -828,7 +1012,7 @@ impl TreeState {
         store: Arc<Store>,
         working_copy_path: PathBuf,
         state_path: PathBuf,
-        target_eol_strategy: TargetEolStrategy,
+        tree_state_settings: &TreeStateSettings,
     ) -> TreeState {
         let tree_id = store.empty_merged_tree_id();
         TreeState {
@@ -841,7 +1025,9 @@ impl TreeState {
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
-            target_eol_strategy,
+            large_new_directories: HashMap::new(),
+            target_eol_strategy: create_target_eol_strategy(tree_state_settings),
+            exec_bit_heuristics: tree_state_settings.exec_bit_heuristics.clone(),
         }
     }
 
@@ -851,7 +1037,6 @@ impl TreeState {
         state_path: PathBuf,
         tree_state_settings: &TreeStateSettings,
     ) -> Result<TreeState, TreeStateError> {
-        let target_eol_strategy = create_target_eol_strategy(tree_state_settings);
         let tree_state_path = state_path.join("tree_state");
         let file = match File::open(&tree_state_path) {
             Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
@@ -866,7 +1051,7 @@ impl TreeState {
             Ok(file) => file,
         };
 
-        let mut wc = TreeState::empty(store, working_copy_path, state_path, target_eol_strategy);
+        let mut wc = TreeState::empty(store, working_copy_path, state_path, tree_state_settings);
         wc.read(&tree_state_path, file)?;
         Ok(wc)
     }
@@ -907,6 +1092,18 @@ impl TreeState {
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
         self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
         self.watchman_clock = proto.watchman_clock;
+        self.large_new_directories = proto
+            .large_new_directories
+            .iter()
+            .filter_map(|entry| {
+                let path = RepoPathBuf::from_internal_string(&entry.path).ok()?;
+                let large_new_directory = LargeNewDirectory {
+                    mtime: MillisSinceEpoch(entry.mtime_millis_since_epoch),
+                    max_files: entry.max_files.try_into().unwrap_or(usize::MAX),
+                };
+                Some((path, large_new_directory))
+            })
+            .collect();
         Ok(())
     }
 
@@ -933,6 +1130,18 @@ impl TreeState {
         }
         proto.sparse_patterns = Some(sparse_patterns);
         proto.watchman_clock = self.watchman_clock.clone();
+        proto.large_new_directories = self
+            .large_new_directories
+            .iter()
+            .map(
+                |(path, large_new_directory)| crate::protos::working_copy::LargeNewDirectory {
+                    path: path.as_internal_file_string().to_owned(),
+                    mtime_millis_since_epoch: large_new_directory.mtime.0,
+                    max_files: large_new_directory.max_files.try_into().unwrap_or(u64::MAX),
+                },
+            )
+            .sorted_by(|a, b| a.path.cmp(&b.path))
+            .collect();
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -956,9 +1165,99 @@ impl TreeState {
                     source: error,
                 }
             })?;
+        // The saved state now reflects any checkout recorded in the journal.
+        let journal_path = self.pending_checkout_path();
+        match fs::remove_file(&journal_path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(TreeStateError::RemovePendingCheckout {
+                    path: journal_path,
+                    source: err,
+                });
+            }
+        }
         Ok(())
     }
 
+    fn pending_checkout_path(&self) -> PathBuf {
+        self.state_path.join("pending_checkout")
+    }
+
+    /// Starts the journal of the checkout from `old_tree_id` to `new_tree_id`
+    /// before the working copy is modified, so it can be recovered if
+    /// interrupted. `written_paths` are carried over from a previous journal.
+    fn start_pending_checkout(
+        &self,
+        old_tree_id: &MergedTreeId,
+        new_tree_id: &MergedTreeId,
+        written_paths: &HashSet<RepoPathBuf>,
+    ) -> Result<File, CheckoutError> {
+        let format_id = |id: &MergedTreeId| id.to_merge().iter().map(|id| id.hex()).join(",");
+        let mut content = format!("{}\n{}\n", format_id(old_tree_id), format_id(new_tree_id));
+        for path in written_paths {
+            content.push_str(path.as_internal_file_string());
+            content.push('\0');
+        }
+        write_file_atomically(&self.pending_checkout_path(), |file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_data()
+        })
+        .map_err(|err| CheckoutError::Other {
+            message: "Failed to write pending checkout".to_string(),
+            err: err.into(),
+        })
+    }
+
+    /// Reads the journal of the interrupted checkout, if any.
+    fn read_pending_checkout(&self) -> Result<Option<PendingCheckout>, CheckoutError> {
+        let path = self.pending_checkout_path();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(CheckoutError::Other {
+                    message: format!("Failed to read pending checkout {}", path.display()),
+                    err: err.into(),
+                });
+            }
+        };
+        let malformed = || CheckoutError::Other {
+            message: format!("Malformed pending checkout {}", path.display()),
+            err: "unexpected content".into(),
+        };
+        let parse_id = |line: &str| {
+            let ids: Vec<TreeId> = line
+                .split(',')
+                .map(TreeId::try_from_hex)
+                .collect::<Option<_>>()?;
+            (ids.len() % 2 == 1).then(|| MergedTreeId::Merge(Merge::from_vec(ids)))
+        };
+        let mut parts = content.splitn(3, '\n');
+        let (Some(old_tree_id), Some(new_tree_id), Some(paths)) = (
+            parts.next().and_then(parse_id),
+            parts.next().and_then(parse_id),
+            parts.next(),
+        ) else {
+            return Err(malformed());
+        };
+        // Each path is terminated by NUL. An unterminated path at the end was
+        // being recorded when the checkout was interrupted, so its file hasn't
+        // been touched yet.
+        let mut entries = paths.split('\0').collect_vec();
+        entries.pop();
+        let written_paths = entries
+            .into_iter()
+            .map(RepoPathBuf::from_internal_string)
+            .try_collect()
+            .map_err(|_| malformed())?;
+        Ok(Some(PendingCheckout {
+            old_tree_id,
+            new_tree_id,
+            written_paths,
+        }))
+    }
+
     fn current_tree(&self) -> BackendResult<MergedTree> {
         self.store.get_root_tree(&self.tree_id)
     }
@@ -1002,14 +1301,78 @@ impl TreeState {
     }
 }
 
+/// Returns true if the directory tree at `disk_dir` contains more than
+/// `max_files` files not ignored by `.gitignore`. `git_ignore` is the ignore
+/// file of the parent directory. Scanning stops as soon as the limit is
+/// exceeded.
+fn has_more_files_than(
+    dir: &RepoPath,
+    disk_dir: &Path,
+    git_ignore: &Arc<GitIgnoreFile>,
+    max_files: usize,
+) -> Result<bool, SnapshotError> {
+    let mut file_count = 0;
+    let mut dirs_to_visit = vec![(dir.to_owned(), disk_dir.to_owned(), git_ignore.clone())];
+    while let Some((dir, disk_dir, git_ignore)) = dirs_to_visit.pop() {
+        let git_ignore = git_ignore
+            .chain_with_file(&dir.to_internal_dir_string(), disk_dir.join(".gitignore"))?;
+        let entries: Vec<_> = disk_dir
+            .read_dir()
+            .and_then(|entries| entries.try_collect())
+            .map_err(|err| SnapshotError::Other {
+                message: format!("Failed to read directory {}", disk_dir.display()),
+                err: err.into(),
+            })?;
+        for entry in entries {
+            let file_name = entry.file_name();
+            // Paths that can't be snapshotted are counted anyway.
+            let path = file_name
+                .to_str()
+                .and_then(|name| RepoPathComponent::new(name).ok())
+                .map(|name| dir.join(name));
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                let is_reserved = file_name
+                    .to_str()
+                    .is_some_and(|name| RESERVED_DIR_NAMES.contains(&name));
+                let path = path.filter(|path| {
+                    !is_reserved && !git_ignore.matches(&path.to_internal_dir_string())
+                });
+                if let Some(path) = path {
+                    dirs_to_visit.push((path, entry.path(), git_ignore.clone()));
+                }
+            } else {
+                if path.is_some_and(|path| git_ignore.matches(path.as_internal_file_string())) {
+                    continue;
+                }
+                file_count += 1;
+                if file_count > max_files {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
 /// Functions to snapshot local-disk files to the store.
 impl TreeState {
     /// Look for changes to the working copy. If there are any changes, create
     /// a new tree from it.
-    #[instrument(skip_all)]
     pub fn snapshot(
         &mut self,
         options: &SnapshotOptions,
+    ) -> Result<(bool, SnapshotStats), SnapshotError> {
+        self.snapshot_matching(options, &EverythingMatcher)
+    }
+
+    /// Like [`Self::snapshot()`], but only looks at the paths matching
+    /// `path_matcher`. The other paths are left as they are in the current
+    /// tree.
+    #[instrument(skip_all)]
+    fn snapshot_matching(
+        &mut self,
+        options: &SnapshotOptions,
+        path_matcher: &dyn Matcher,
     ) -> Result<(bool, SnapshotStats), SnapshotError> {
         let &SnapshotOptions {
             ref base_ignores,
@@ -1018,6 +1381,8 @@ impl TreeState {
             start_tracking_matcher,
             max_new_file_size,
             conflict_marker_style,
+            new_directory_policy,
+            report_ignored_paths,
         } = options;
 
         let sparse_matcher = self.sparse_matcher();
@@ -1033,7 +1398,10 @@ impl TreeState {
             Some(fsmonitor_matcher) => fsmonitor_matcher.as_ref(),
         };
 
-        let matcher = IntersectionMatcher::new(sparse_matcher.as_ref(), fsmonitor_matcher);
+        let matcher = IntersectionMatcher::new(
+            IntersectionMatcher::new(sparse_matcher.as_ref(), fsmonitor_matcher),
+            path_matcher,
+        );
         if matcher.visit(RepoPath::root()).is_nothing() {
             // No need to load the current tree, set up channels, etc.
             self.watchman_clock = watchman_clock;
@@ -1043,30 +1411,41 @@ impl TreeState {
         let (tree_entries_tx, tree_entries_rx) = channel();
         let (file_states_tx, file_states_rx) = channel();
         let (untracked_paths_tx, untracked_paths_rx) = channel();
+        let (large_new_directories_tx, large_new_directories_rx) = channel();
+        let (excluded_directories_tx, excluded_directories_rx) = channel();
+        let (malformed_conflicts_tx, malformed_conflicts_rx) = channel();
         let (deleted_files_tx, deleted_files_rx) = channel();
 
         trace_span!("traverse filesystem").in_scope(|| -> Result<(), SnapshotError> {
+            let current_tree = self.current_tree()?;
             let snapshotter = FileSnapshotter {
                 tree_state: self,
-                current_tree: &self.current_tree()?,
+                current_tree: &current_tree,
                 matcher: &matcher,
                 start_tracking_matcher,
                 // Move tx sides so they'll be dropped at the end of the scope.
                 tree_entries_tx,
                 file_states_tx,
                 untracked_paths_tx,
+                large_new_directories_tx,
+                excluded_directories_tx,
+                malformed_conflicts_tx,
                 deleted_files_tx,
                 error: OnceLock::new(),
                 progress,
                 max_new_file_size,
                 conflict_marker_style,
+                new_directory_policy,
+                report_ignored_paths,
                 target_eol_strategy: self.target_eol_strategy.clone(),
+                attributes: TreeAttributes::new(current_tree.clone(), GitAttributes::empty()),
             };
             let directory_to_visit = DirectoryToVisit {
                 dir: RepoPathBuf::root(),
                 disk_dir: self.working_copy_path.clone(),
                 git_ignore: base_ignores.clone(),
                 file_states: self.file_states.all(),
+                is_new: false,
             };
             // Here we use scope as a queue of per-directory jobs.
             rayon::scope(|scope| {
@@ -1079,7 +1458,14 @@ impl TreeState {
 
         let stats = SnapshotStats {
             untracked_paths: untracked_paths_rx.into_iter().collect(),
+            large_new_directories: large_new_directories_rx.into_iter().collect(),
+            malformed_conflicts: malformed_conflicts_rx.into_iter().collect(),
         };
+        let excluded_directories: HashMap<_, _> = excluded_directories_rx.into_iter().collect();
+        if excluded_directories != self.large_new_directories {
+            is_dirty = true;
+            self.large_new_directories = excluded_directories;
+        }
         let mut tree_builder = MergedTreeBuilder::new(self.tree_id.clone());
         trace_span!("process tree entries").in_scope(|| {
             for (path, tree_values) in &tree_entries_rx {
@@ -1180,6 +1566,8 @@ struct DirectoryToVisit<'a> {
     disk_dir: PathBuf,
     git_ignore: Arc<GitIgnoreFile>,
     file_states: FileStates<'a>,
+    /// Whether this directory or one of its ancestors had no tracked files.
+    is_new: bool,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -1203,12 +1591,20 @@ struct FileSnapshotter<'a> {
     tree_entries_tx: Sender<(RepoPathBuf, MergedTreeValue)>,
     file_states_tx: Sender<(RepoPathBuf, FileState)>,
     untracked_paths_tx: Sender<(RepoPathBuf, UntrackedReason)>,
+    large_new_directories_tx: Sender<RepoPathBuf>,
+    /// New directories left untracked because they have too many files.
+    excluded_directories_tx: Sender<(RepoPathBuf, LargeNewDirectory)>,
+    malformed_conflicts_tx: Sender<(RepoPathBuf, Vec<MalformedConflictHunk>)>,
     deleted_files_tx: Sender<RepoPathBuf>,
     error: OnceLock<SnapshotError>,
     progress: Option<&'a SnapshotProgress<'a>>,
     max_new_file_size: u64,
     conflict_marker_style: ConflictMarkerStyle,
+    new_directory_policy: NewDirectoryPolicy,
+    report_ignored_paths: bool,
     target_eol_strategy: TargetEolStrategy,
+    /// Attributes of the current tree, which select the EOL conversion.
+    attributes: TreeAttributes,
 }
 
 impl FileSnapshotter<'_> {
@@ -1247,6 +1643,7 @@ impl FileSnapshotter<'_> {
             disk_dir,
             git_ignore,
             file_states,
+            is_new,
         } = directory_to_visit;
 
         let git_ignore = git_ignore
@@ -1264,7 +1661,7 @@ impl FileSnapshotter<'_> {
             // sequential scan should be fast enough.
             .with_min_len(100)
             .filter_map(|entry| {
-                self.process_dir_entry(&dir, &git_ignore, file_states, &entry, scope)
+                self.process_dir_entry(&dir, is_new, &git_ignore, file_states, &entry, scope)
                     .transpose()
             })
             .map(|item| match item {
@@ -1278,9 +1675,48 @@ impl FileSnapshotter<'_> {
         Ok(())
     }
 
+    /// Returns true if the new directory at `path` has more than `max_files`
+    /// files. With [`NewDirectoryPolicy::Exclude`], the directory stays
+    /// untracked, so the result is cached in the tree state until the
+    /// directory is modified.
+    fn is_large_new_directory(
+        &self,
+        path: &RepoPath,
+        entry: &DirEntry,
+        git_ignore: &Arc<GitIgnoreFile>,
+        max_files: usize,
+    ) -> Result<bool, SnapshotError> {
+        let disk_dir = entry.path();
+        let NewDirectoryPolicy::Exclude { .. } = self.new_directory_policy else {
+            return has_more_files_than(path, &disk_dir, git_ignore, max_files);
+        };
+        let metadata = entry.metadata().map_err(|err| SnapshotError::Other {
+            message: format!("Failed to stat directory {}", disk_dir.display()),
+            err: err.into(),
+        })?;
+        let mtime = mtime_from_metadata(&metadata);
+        let cached = self
+            .tree_state
+            .large_new_directories
+            .get(path)
+            .filter(|cached| cached.mtime == mtime && cached.max_files >= max_files);
+        let large_new_directory = if let Some(cached) = cached {
+            *cached
+        } else if has_more_files_than(path, &disk_dir, git_ignore, max_files)? {
+            LargeNewDirectory { mtime, max_files }
+        } else {
+            return Ok(false);
+        };
+        self.excluded_directories_tx
+            .send((path.to_owned(), large_new_directory))
+            .ok();
+        Ok(true)
+    }
+
     fn process_dir_entry<'scope>(
         &'scope self,
         dir: &RepoPath,
+        dir_is_new: bool,
         git_ignore: &Arc<GitIgnoreFile>,
         file_states: FileStates<'scope>,
         entry: &DirEntry,
@@ -1312,13 +1748,39 @@ impl FileSnapshotter<'_> {
                 // ignored directory must be ignored. It's also more efficient.
                 // start_tracking_matcher is NOT tested here because we need to
                 // scan directory entries to report untracked paths.
+                if self.report_ignored_paths
+                    && file_states.is_empty()
+                    && !self.matcher.visit(&path).is_nothing()
+                {
+                    self.untracked_paths_tx
+                        .send((path, UntrackedReason::Ignored))
+                        .ok();
+                    return Ok(Some((PresentDirEntryKind::Dir, name_string)));
+                }
                 self.spawn_ok(scope, move |_| self.visit_tracked_files(file_states));
             } else if !self.matcher.visit(&path).is_nothing() {
+                let is_new = dir_is_new || file_states.is_empty();
+                if let Some(max_files) = self.new_directory_policy.max_files() {
+                    // Only the topmost new directory is checked. Its
+                    // subdirectories can't have more files than it.
+                    if is_new
+                        && !dir_is_new
+                        && self.is_large_new_directory(&path, entry, git_ignore, max_files)?
+                    {
+                        if let NewDirectoryPolicy::Exclude { .. } = self.new_directory_policy {
+                            let reason = UntrackedReason::NewDirectoryTooLarge { max_files };
+                            self.untracked_paths_tx.send((path, reason)).ok();
+                            return Ok(Some((PresentDirEntryKind::Dir, name_string)));
+                        }
+                        self.large_new_directories_tx.send(path.clone()).ok();
+                    }
+                }
                 let directory_to_visit = DirectoryToVisit {
                     dir: path,
                     disk_dir: entry.path(),
                     git_ignore: git_ignore.clone(),
                     file_states,
+                    is_new,
                 };
                 self.spawn_ok(scope, |scope| {
                     self.visit_directory(directory_to_visit, scope)
@@ -1336,6 +1798,11 @@ impl FileSnapshotter<'_> {
             {
                 // If it wasn't already tracked and it matches
                 // the ignored paths, then ignore it.
+                if self.report_ignored_paths {
+                    self.untracked_paths_tx
+                        .send((path, UntrackedReason::Ignored))
+                        .ok();
+                }
                 Ok(None)
             } else if maybe_current_file_state.is_none()
                 && !self.start_tracking_matcher.matches(&path)
@@ -1546,7 +2013,8 @@ impl FileSnapshotter<'_> {
     ) -> Result<MergedTreeValue, SnapshotError> {
         if let Some(current_tree_value) = current_tree_values.as_resolved() {
             let id = self.write_file_to_store(repo_path, disk_path).await?;
-            // On Windows, we preserve the executable bit from the current tree.
+            // On Windows, we preserve the executable bit from the current tree,
+            // or guess it for new files.
             let executable = executable.unwrap_or_else(|| {
                 if let Some(TreeValue::File {
                     id: _,
@@ -1556,7 +2024,7 @@ impl FileSnapshotter<'_> {
                 {
                     *executable
                 } else {
-                    false
+                    self.tree_state.exec_bit_heuristics.is_executable(disk_path)
                 }
             });
             // Preserve the copy id from the current tree
@@ -1590,8 +2058,9 @@ impl FileSnapshotter<'_> {
                 message: format!("Failed to open file {}", disk_path.display()),
                 err: err.into(),
             })?;
+            let eol_attribute = EolAttribute::for_path(&self.attributes, repo_path)?;
             self.target_eol_strategy
-                .convert_eol_for_snapshot(BlockingAsyncReader::new(file))
+                .convert_eol_for_snapshot(eol_attribute, BlockingAsyncReader::new(file))
                 .await
                 .map_err(|err| SnapshotError::Other {
                     message: "Failed to convert the EOL".to_string(),
@@ -1606,7 +2075,7 @@ impl FileSnapshotter<'_> {
             // If the file contained a conflict before and is a normal file on
             // disk, we try to parse any conflict markers in the file into a
             // conflict.
-            let new_file_ids = conflicts::update_from_content(
+            let (new_file_ids, malformed_hunks) = conflicts::update_from_content_with_diagnostics(
                 &old_file_ids,
                 self.store(),
                 repo_path,
@@ -1617,6 +2086,11 @@ impl FileSnapshotter<'_> {
                 }),
             )
             .await?;
+            if !malformed_hunks.is_empty() {
+                self.malformed_conflicts_tx
+                    .send((repo_path.to_owned(), malformed_hunks))
+                    .ok();
+            }
             match new_file_ids.into_resolved() {
                 Ok(file_id) => {
                     // On Windows, we preserve the executable bit from the merged trees.
@@ -1655,9 +2129,10 @@ impl FileSnapshotter<'_> {
             message: format!("Failed to open file {}", disk_path.display()),
             err: err.into(),
         })?;
+        let eol_attribute = EolAttribute::for_path(&self.attributes, path)?;
         let mut contents = self
             .target_eol_strategy
-            .convert_eol_for_snapshot(BlockingAsyncReader::new(file))
+            .convert_eol_for_snapshot(eol_attribute, BlockingAsyncReader::new(file))
             .await
             .map_err(|err| SnapshotError::Other {
                 message: "Failed to convert the EOL".to_string(),
@@ -1704,7 +2179,7 @@ impl TreeState {
         disk_path: &Path,
         contents: impl AsyncRead + Send + Unpin,
         executable: bool,
-        apply_eol_conversion: bool,
+        eol_attribute: EolAttribute,
     ) -> Result<FileState, CheckoutError> {
         let mut file = File::options()
             .write(true)
@@ -1714,17 +2189,14 @@ impl TreeState {
                 message: format!("Failed to open file {} for writing", disk_path.display()),
                 err: err.into(),
             })?;
-        let contents = if apply_eol_conversion {
-            self.target_eol_strategy
-                .convert_eol_for_update(contents)
-                .await
-                .map_err(|err| CheckoutError::Other {
-                    message: "Failed to convert the EOL for the content".to_string(),
-                    err: err.into(),
-                })?
-        } else {
-            Box::new(contents)
-        };
+        let contents = self
+            .target_eol_strategy
+            .convert_eol_for_update(eol_attribute, contents)
+            .await
+            .map_err(|err| CheckoutError::Other {
+                message: "Failed to convert the EOL for the content".to_string(),
+                err: err.into(),
+            })?;
         let size = copy_async_to_sync(contents, &mut file)
             .await
             .map_err(|err| CheckoutError::Other {
@@ -1821,18 +2293,106 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
+        let mut journal = CheckoutJournal {
+            file: Some(self.start_pending_checkout(
+                &self.tree_id,
+                &new_tree.id(),
+                &HashSet::new(),
+            )?),
+            written_paths: HashSet::new(),
+        };
         let stats = self
             .update(
                 &old_tree,
                 new_tree,
                 self.sparse_matcher().as_ref(),
-                options.conflict_marker_style,
+                options,
+                &mut journal,
             )
             .block_on()?;
         self.tree_id = new_tree.id();
         Ok(stats)
     }
 
+    /// Recovers from a [`Self::check_out()`] that was interrupted.
+    ///
+    /// The working copy is snapshotted first so changes made since the
+    /// interruption are preserved. Files recorded in the journal are excluded
+    /// from the snapshot since they can't be told apart from the files written
+    /// by the checkout. Returns the tree the working copy is now checked out
+    /// to, not including the preserved changes, or `None` if there was no
+    /// checkout to recover.
+    pub fn recover_checkout(
+        &mut self,
+        recovery: CheckoutRecovery,
+        snapshot_options: &SnapshotOptions,
+        options: &CheckoutOptions,
+    ) -> Result<Option<(MergedTreeId, CheckoutStats)>, CheckoutError> {
+        let Some(pending) = self.read_pending_checkout()? else {
+            return Ok(None);
+        };
+        if self.tree_id != pending.old_tree_id {
+            // Left over from a checkout whose state was saved by other means.
+            // It will be cleared when the state is saved next time.
+            return Ok(None);
+        }
+        let written_matcher = FilesMatcher::new(&pending.written_paths);
+        self.snapshot_matching(
+            snapshot_options,
+            &DifferenceMatcher::new(&EverythingMatcher, &written_matcher),
+        )
+        .map_err(|err| CheckoutError::Other {
+            message: "Failed to snapshot the working copy before recovering".to_string(),
+            err: err.into(),
+        })?;
+        let old_tree = self.store.get_root_tree(&pending.old_tree_id)?;
+        let new_tree = self.store.get_root_tree(&pending.new_tree_id)?;
+        let current_tree = self.current_tree()?;
+        match recovery {
+            CheckoutRecovery::Resume => {
+                let target_tree = if current_tree.id() == pending.old_tree_id {
+                    new_tree
+                } else {
+                    new_tree.merge(old_tree, current_tree.clone()).block_on()?
+                };
+                let mut journal = CheckoutJournal {
+                    file: Some(self.start_pending_checkout(
+                        &current_tree.id(),
+                        &target_tree.id(),
+                        &pending.written_paths,
+                    )?),
+                    written_paths: pending.written_paths,
+                };
+                let stats = self
+                    .update(
+                        &current_tree,
+                        &target_tree,
+                        self.sparse_matcher().as_ref(),
+                        options,
+                        &mut journal,
+                    )
+                    .block_on()?;
+                self.tree_id = target_tree.id();
+                Ok(Some((pending.new_tree_id, stats)))
+            }
+            CheckoutRecovery::Rollback => {
+                // Only the recorded paths may differ from the old tree. If the
+                // rollback is interrupted, the journal is left as is, so the
+                // checkout will be resumed instead.
+                let sparse_matcher = self.sparse_matcher();
+                let matcher = IntersectionMatcher::new(sparse_matcher.as_ref(), &written_matcher);
+                let mut journal = CheckoutJournal {
+                    file: None,
+                    written_paths: pending.written_paths.clone(),
+                };
+                let stats = self
+                    .update(&new_tree, &current_tree, &matcher, options, &mut journal)
+                    .block_on()?;
+                Ok(Some((pending.old_tree_id, stats)))
+            }
+        }
+    }
+
     pub fn set_sparse_patterns(
         &mut self,
         sparse_patterns: Vec<RepoPathBuf>,
@@ -1844,6 +2404,7 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
+        // Only the paths whose inclusion changed need to be updated.
         let old_matcher = PrefixMatcher::new(&self.sparse_patterns);
         let new_matcher = PrefixMatcher::new(&sparse_patterns);
         let added_matcher = DifferenceMatcher::new(&new_matcher, &old_matcher);
@@ -1854,38 +2415,74 @@ impl TreeState {
                 &empty_tree,
                 &tree,
                 &added_matcher,
-                options.conflict_marker_style,
-            )
-            .block_on()?;
-        let removed_stats = self
-            .update(
-                &tree,
-                &empty_tree,
-                &removed_matcher,
-                options.conflict_marker_style,
+                options,
+                &mut CheckoutJournal::default(),
             )
             .block_on()?;
+        let removed_files = self.remove_files(&removed_matcher, options.progress.as_deref())?;
         self.sparse_patterns = sparse_patterns;
         assert_eq!(added_stats.updated_files, 0);
         assert_eq!(added_stats.removed_files, 0);
-        assert_eq!(removed_stats.updated_files, 0);
-        assert_eq!(removed_stats.added_files, 0);
-        assert_eq!(removed_stats.skipped_files, 0);
         Ok(CheckoutStats {
             updated_files: 0,
             added_files: added_stats.added_files,
-            removed_files: removed_stats.removed_files,
+            removed_files,
             skipped_files: added_stats.skipped_files,
         })
     }
 
+    /// Removes the files matching `matcher` from the working copy, and returns
+    /// the number of removed files.
+    ///
+    /// Unlike `update()`, this only looks at the file states, so no trees need
+    /// to be read from the backend.
+    fn remove_files(
+        &mut self,
+        matcher: &dyn Matcher,
+        progress: Option<&CheckoutProgress>,
+    ) -> Result<usize, CheckoutError> {
+        let mut deleted_files = HashSet::new();
+        for path in self.file_states.all().paths() {
+            if !matcher.matches(path) {
+                continue;
+            }
+            if let Some(progress) = progress {
+                progress(path);
+            }
+            // Parent directories are created to ensure that the path never
+            // traverses symlinks. If a parent is a file, there's nothing to
+            // remove.
+            if let Some(disk_path) = create_parent_dirs(&self.working_copy_path, path)? {
+                remove_old_file(&disk_path)?;
+                let mut parent_dir = disk_path.parent().unwrap();
+                loop {
+                    if fs::remove_dir(parent_dir).is_err() {
+                        break;
+                    }
+                    parent_dir = parent_dir.parent().unwrap();
+                }
+            }
+            deleted_files.insert(path.to_owned());
+        }
+        let removed_files = deleted_files.len();
+        self.file_states.merge_in(vec![], &deleted_files);
+        Ok(removed_files)
+    }
+
     async fn update(
         &mut self,
         old_tree: &MergedTree,
         new_tree: &MergedTree,
         matcher: &dyn Matcher,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: &CheckoutOptions,
+        journal: &mut CheckoutJournal,
     ) -> Result<CheckoutStats, CheckoutError> {
+        let &CheckoutOptions {
+            conflict_marker_style,
+            ref conflict_labels,
+            ref progress,
+        } = options;
+        let progress = progress.as_deref();
         // TODO: maybe it's better not include the skipped counts in the "intended"
         // counts
         let mut stats = CheckoutStats {
@@ -1896,6 +2493,8 @@ impl TreeState {
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
+        let unlabeled = ConflictLabels::unlabeled();
+        let attributes = TreeAttributes::new(new_tree.clone(), GitAttributes::empty());
         let mut diff_stream = old_tree
             .diff_stream_for_file_system(new_tree, matcher)
             .map(async |TreeDiffEntry { path, values }| match values {
@@ -1908,6 +2507,9 @@ impl TreeState {
             .buffered(self.store.concurrency().max(1));
         while let Some((path, data)) = diff_stream.next().await {
             let (before, after) = data?;
+            if let Some(progress) = progress {
+                progress(&path);
+            }
             if after.is_absent() {
                 stats.removed_files += 1;
             } else if before.is_absent() {
@@ -1939,8 +2541,12 @@ impl TreeState {
                 stats.skipped_files += 1;
                 continue;
             };
+            journal.record(&path)?;
             // If the path was present, check reserved path first and delete it.
-            let present_file_deleted = before.is_present() && remove_old_file(&disk_path)?;
+            // The file may also have been written by an interrupted checkout.
+            let present_file_deleted = (before.is_present()
+                || journal.written_paths.contains(&path))
+                && remove_old_file(&disk_path)?;
             // If not, create temporary file to test the path validity.
             if !present_file_deleted && !can_create_new_file(&disk_path)? {
                 changed_file_states.push((path, FileState::placeholder()));
@@ -1962,14 +2568,15 @@ impl TreeState {
                     continue;
                 }
                 MaterializedTreeValue::File(file) => {
-                    self.write_file(&disk_path, file.reader, file.executable, true)
+                    let eol_attribute = EolAttribute::for_path(&attributes, &path)?;
+                    self.write_file(&disk_path, file.reader, file.executable, eol_attribute)
                         .await?
                 }
                 MaterializedTreeValue::Symlink { id: _, target } => {
                     if self.symlink_support {
                         self.write_symlink(&disk_path, target)?
                     } else {
-                        self.write_file(&disk_path, target.as_bytes(), false, false)
+                        self.write_file(&disk_path, target.as_bytes(), false, EolAttribute::Binary)
                             .await?
                     }
                 }
@@ -1983,10 +2590,15 @@ impl TreeState {
                 MaterializedTreeValue::FileConflict(file) => {
                     let conflict_marker_len =
                         choose_materialized_conflict_marker_len(&file.contents);
-                    let data = materialize_merge_result_to_bytes_with_marker_len(
+                    let data = materialize_merge_result_to_bytes_with_labels(
                         &file.contents,
                         conflict_marker_style,
                         conflict_marker_len,
+                        if conflict_labels.applies_to(&path) {
+                            conflict_labels
+                        } else {
+                            &unlabeled
+                        },
                     )
                     .into();
                     let materialized_conflict_data = MaterializedConflictData {
@@ -2063,6 +2675,7 @@ impl TreeState {
                     file_type,
                     mtime: MillisSinceEpoch(0),
                     size: 0,
+                    fingerprint: FileFingerprint::default(),
                     materialized_conflict_data: None,
                 };
                 changed_file_states.push((path, file_state));
@@ -2236,6 +2849,12 @@ impl LocalWorkingCopy {
         &self.state_path
     }
 
+    /// Returns true if a checkout was interrupted and should be resumed by
+    /// [`LockedLocalWorkingCopy::recover_checkout()`].
+    pub fn has_pending_checkout(&self) -> bool {
+        self.state_path.join("pending_checkout").exists()
+    }
+
     fn write_proto(&self, proto: crate::protos::working_copy::Checkout) {
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -2417,8 +3036,6 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         commit: &Commit,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
-        // TODO: Write a "pending_checkout" file with the new TreeId so we can
-        // continue an interrupted update if we find such a file.
         let new_tree = commit.tree()?;
         let tree_state = self
             .wc
@@ -2513,12 +3130,39 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
             self.wc.checkout_state_mut().operation_id = operation_id;
             self.wc.save();
         }
-        // TODO: Clear the "pending_checkout" file here.
         Ok(Box::new(self.wc))
     }
 }
 
 impl LockedLocalWorkingCopy {
+    /// Recovers from a checkout that was interrupted before the working-copy
+    /// state could be saved. The tree the working copy is checked out to
+    /// becomes the old tree of this mutation, so changes made since the
+    /// interruption will be picked up by the next snapshot. Returns `None` if
+    /// there was no checkout to recover.
+    pub fn recover_checkout(
+        &mut self,
+        recovery: CheckoutRecovery,
+        snapshot_options: &SnapshotOptions,
+        options: &CheckoutOptions,
+    ) -> Result<Option<CheckoutStats>, CheckoutError> {
+        let tree_state = self
+            .wc
+            .tree_state_mut()
+            .map_err(|err| CheckoutError::Other {
+                message: "Failed to load the working copy state".to_string(),
+                err: err.into(),
+            })?;
+        let Some((tree_id, stats)) =
+            tree_state.recover_checkout(recovery, snapshot_options, options)?
+        else {
+            return Ok(None);
+        };
+        self.old_tree_id = tree_id;
+        self.tree_state_dirty = true;
+        Ok(Some(stats))
+    }
+
     pub fn reset_watchman(&mut self) -> Result<(), SnapshotError> {
         self.wc
             .tree_state_mut()
@@ -2537,6 +3181,7 @@ mod tests {
     use maplit::hashset;
 
     use super::*;
+    use crate::tests::new_temp_dir;
 
     fn repo_path(value: &str) -> &RepoPath {
         RepoPath::from_internal_string(value).unwrap()
@@ -2546,6 +3191,34 @@ mod tests {
         RepoPathComponent::new(value).unwrap()
     }
 
+    #[test]
+    fn test_exec_bit_heuristics() {
+        let temp_dir = new_temp_dir();
+        let write_file = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let script = write_file("script", "#!/bin/sh\n");
+        let text = write_file("text", "# not a script\n");
+        let batch = write_file("run.BAT", "echo\n");
+        let empty = write_file("empty", "");
+
+        let heuristics = ExecBitHeuristics::default();
+        assert!(!heuristics.is_executable(&script));
+        assert!(!heuristics.is_executable(&batch));
+
+        let heuristics = ExecBitHeuristics {
+            shebang: true,
+            extensions: vec!["bat".to_owned()],
+        };
+        assert!(heuristics.is_executable(&script));
+        assert!(!heuristics.is_executable(&text));
+        assert!(heuristics.is_executable(&batch));
+        assert!(!heuristics.is_executable(&empty));
+        assert!(!heuristics.is_executable(&temp_dir.path().join("missing")));
+    }
+
     #[test]
     fn test_file_states_merge() {
         let new_state = |size| FileState {
@@ -2554,6 +3227,7 @@ mod tests {
             },
             mtime: MillisSinceEpoch(0),
             size,
+            fingerprint: FileFingerprint::default(),
             materialized_conflict_data: None,
         };
         let new_static_entry = |path: &'static str, size| (repo_path(path), new_state(size));
@@ -2603,6 +3277,7 @@ mod tests {
             },
             mtime: MillisSinceEpoch(0),
             size,
+            fingerprint: FileFingerprint::default(),
             materialized_conflict_data: None,
         };
         let new_proto_entry = |path: &str, size| {
@@ -2668,6 +3343,7 @@ mod tests {
             },
             mtime: MillisSinceEpoch(0),
             size,
+            fingerprint: FileFingerprint::default(),
             materialized_conflict_data: None,
         };
         let new_proto_entry = |path: &str, size| {
@@ -2734,4 +3410,32 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_file_fingerprint_matches() {
+        let fingerprint = FileFingerprint {
+            mtime_nanos: 1,
+            ctime_nanos: 2,
+            inode: 3,
+        };
+        assert!(fingerprint.matches(&fingerprint));
+        // Unknown fields are ignored
+        assert!(fingerprint.matches(&FileFingerprint::default()));
+        assert!(FileFingerprint::default().matches(&fingerprint));
+        let other = FileFingerprint {
+            inode: 0,
+            ..fingerprint
+        };
+        assert!(fingerprint.matches(&other));
+        let other = FileFingerprint {
+            ctime_nanos: 4,
+            ..fingerprint
+        };
+        assert!(!fingerprint.matches(&other));
+        let other = FileFingerprint {
+            mtime_nanos: 4,
+            ..fingerprint
+        };
+        assert!(!fingerprint.matches(&other));
+    }
 }
@@ -0,0 +1,183 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Canonicalization of author and committer identities by `.mailmap` file.
+//!
+//! The file format is the same as Git's. See
+//! <https://git-scm.com/docs/gitmailmap> for details.
+
+use crate::backend::Signature;
+
+/// Parsed mailmap that maps identities recorded in commits to canonical ones.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Mailmap {
+    entries: Vec<MailmapEntry>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct MailmapEntry {
+    proper_name: Option<String>,
+    proper_email: Option<String>,
+    /// Lowercased name to match, or `None` to match any name.
+    commit_name: Option<String>,
+    /// Lowercased email to match.
+    commit_email: String,
+}
+
+impl Mailmap {
+    /// Creates an empty mailmap which maps identities to themselves.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Parses the content of `.mailmap` file.
+    ///
+    /// Malformed lines are ignored as Git does.
+    pub fn parse(text: &str) -> Self {
+        let entries = text.lines().filter_map(parse_line).collect();
+        Mailmap { entries }
+    }
+
+    /// Returns true if there are no mapping entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the canonical name and email of the given identity.
+    pub fn resolve<'a>(&'a self, name: &'a str, email: &'a str) -> (&'a str, &'a str) {
+        if self.entries.is_empty() {
+            return (name, email);
+        }
+        let name_lower = name.to_lowercase();
+        let email_lower = email.to_lowercase();
+        let matches_email = |entry: &&MailmapEntry| entry.commit_email == email_lower;
+        // Entries qualified by the commit name take precedence over the
+        // email-only ones. Fields of the same kind of entries are merged, and
+        // later entries override earlier ones.
+        let mut candidates = self
+            .entries
+            .iter()
+            .filter(matches_email)
+            .filter(|entry| entry.commit_name.as_ref() == Some(&name_lower))
+            .peekable();
+        let candidates: Vec<&MailmapEntry> = if candidates.peek().is_some() {
+            candidates.collect()
+        } else {
+            self.entries
+                .iter()
+                .filter(matches_email)
+                .filter(|entry| entry.commit_name.is_none())
+                .collect()
+        };
+        let proper_name = candidates
+            .iter()
+            .rev()
+            .find_map(|entry| entry.proper_name.as_deref());
+        let proper_email = candidates
+            .iter()
+            .rev()
+            .find_map(|entry| entry.proper_email.as_deref());
+        (proper_name.unwrap_or(name), proper_email.unwrap_or(email))
+    }
+
+    /// Returns a copy of the signature with the canonical name and email.
+    pub fn resolve_signature(&self, signature: &Signature) -> Signature {
+        let (name, email) = self.resolve(&signature.name, &signature.email);
+        Signature {
+            name: name.to_owned(),
+            email: email.to_owned(),
+            timestamp: signature.timestamp,
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<MailmapEntry> {
+    let line = line.trim_start();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (name1, email1, rest) = parse_name_and_email(line)?;
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_owned());
+    let entry = if let Some((name2, email2, _)) = parse_name_and_email(rest) {
+        // Proper Name <proper@email> Commit Name <commit@email>
+        MailmapEntry {
+            proper_name: non_empty(name1),
+            proper_email: non_empty(email1),
+            commit_name: non_empty(name2).map(|name| name.to_lowercase()),
+            commit_email: email2.to_lowercase(),
+        }
+    } else {
+        // Proper Name <commit@email>
+        MailmapEntry {
+            proper_name: non_empty(name1),
+            proper_email: None,
+            commit_name: None,
+            commit_email: email1.to_lowercase(),
+        }
+    };
+    Some(entry)
+}
+
+/// Splits `Name <email> rest` into its parts.
+fn parse_name_and_email(text: &str) -> Option<(&str, &str, &str)> {
+    let (name, rest) = text.split_once('<')?;
+    let (email, rest) = rest.split_once('>')?;
+    Some((name.trim(), email.trim(), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let mailmap = Mailmap::parse(
+            "\
+# comment
+Proper Name <old@example.com>
+<proper@example.com> <Old@Example.com>
+Other <other@example.com> Typo <old@example.com>
+<no-closing-bracket
+",
+        );
+        assert_eq!(mailmap.entries.len(), 3);
+        assert_eq!(
+            mailmap.resolve("Whoever", "OLD@example.com"),
+            ("Proper Name", "proper@example.com")
+        );
+        assert_eq!(
+            mailmap.resolve("typo", "old@example.com"),
+            ("Other", "other@example.com")
+        );
+        assert_eq!(
+            mailmap.resolve("Someone", "someone@example.com"),
+            ("Someone", "someone@example.com")
+        );
+    }
+
+    #[test]
+    fn test_resolve_later_entry_wins() {
+        let mailmap = Mailmap::parse(
+            "\
+First <a@example.com>
+Second <a@example.com>
+",
+        );
+        assert_eq!(
+            mailmap.resolve("a", "a@example.com"),
+            ("Second", "a@example.com")
+        );
+        assert!(Mailmap::empty().is_empty());
+    }
+}
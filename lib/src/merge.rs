@@ -103,6 +103,16 @@ where
     }
 }
 
+/// Pair of add and remove terms that cancelled each other out while
+/// simplifying a merge.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SimplificationStep {
+    /// Index of the add (positive) term in the unsimplified merge.
+    pub add_index: usize,
+    /// Index of the remove (negative) term in the unsimplified merge.
+    pub remove_index: usize,
+}
+
 /// A generic representation of merged values.
 ///
 /// There is exactly one more `adds()` than `removes()`. When interpreted as a
@@ -248,6 +258,18 @@ impl<T> Merge<T> {
     /// The merge is simplified by removing identical values in add and remove
     /// values.
     fn get_simplified_mapping(&self) -> Vec<usize>
+    where
+        T: PartialEq,
+    {
+        self.get_simplified_mapping_with(|_| {})
+    }
+
+    /// Like `get_simplified_mapping()`, but calls `on_cancel` for each pair of
+    /// add and remove values that cancelled each other out.
+    fn get_simplified_mapping_with(
+        &self,
+        mut on_cancel: impl FnMut(SimplificationStep),
+    ) -> Vec<usize>
     where
         T: PartialEq,
     {
@@ -265,6 +287,10 @@ impl<T> Merge<T> {
             if let Some((remove_index, _)) = remove_indices
                 .find(|&(_, original_remove_index)| &self.values[*original_remove_index] == add)
             {
+                on_cancel(SimplificationStep {
+                    add_index: simplified_to_original_indices[add_index],
+                    remove_index: simplified_to_original_indices[remove_index],
+                });
                 // Align the current "add" value to the `remove_index/2`-th diff, then
                 // delete the diff pair.
                 simplified_to_original_indices.swap(remove_index + 1, add_index);
@@ -293,6 +319,17 @@ impl<T> Merge<T> {
         Merge { values }
     }
 
+    /// Returns the pairs of terms which would be removed by `simplify()`, in
+    /// the order they are cancelled out.
+    pub fn simplification_steps(&self) -> Vec<SimplificationStep>
+    where
+        T: PartialEq,
+    {
+        let mut steps = vec![];
+        self.get_simplified_mapping_with(|step| steps.push(step));
+        steps
+    }
+
     /// Updates the merge based on the given simplified merge.
     pub fn update_from_simplified(mut self, simplified: Merge<T>) -> Self
     where
@@ -1027,6 +1064,23 @@ mod tests {
         assert_eq!(c(&[0, 1, 2, 3, 4, 5, 1]).simplify(), c(&[0, 3, 4, 5, 2]));
     }
 
+    #[test]
+    fn test_simplification_steps() {
+        let step = |add_index, remove_index| SimplificationStep {
+            add_index,
+            remove_index,
+        };
+        assert!(c(&[0]).simplification_steps().is_empty());
+        assert_eq!(c(&[0, 0, 0]).simplification_steps(), vec![step(0, 1)]);
+        assert_eq!(c(&[0, 1, 1]).simplification_steps(), vec![step(2, 1)]);
+        assert!(c(&[0, 1, 2]).simplification_steps().is_empty());
+        assert_eq!(c(&[0, 1, 0, 0, 0]).simplification_steps(), vec![step(0, 3)]);
+        assert_eq!(
+            c(&[0, 0, 1, 1, 2]).simplification_steps(),
+            vec![step(0, 1), step(2, 3)]
+        );
+    }
+
     #[test]
     fn test_update_from_simplified() {
         // 1-way merge
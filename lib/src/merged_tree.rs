@@ -35,15 +35,21 @@ use futures::StreamExt as _;
 use itertools::EitherOrBoth;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
+use tokio::io::AsyncReadExt as _;
 
 use crate::backend;
+use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::copies::CopiesTreeDiffEntry;
 use crate::copies::CopiesTreeDiffStream;
 use crate::copies::CopyRecords;
+use crate::gitattributes::GitAttributes;
+use crate::gitattributes::MergeDriver;
+use crate::gitattributes::GITATTRIBUTES_FILE_NAME;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
 use crate::merge::Merge;
@@ -162,7 +168,7 @@ impl MergedTree {
     /// Tries to resolve any conflicts, resolving any conflicts that can be
     /// automatically resolved and leaving the rest unresolved.
     pub async fn resolve(self) -> BackendResult<MergedTree> {
-        let merged = merge_trees(self.trees).await?;
+        let merged = merge_trees(self.trees, &GitAttributes::empty()).await?;
         // If the result can be resolved, then `merge_trees()` above would have returned
         // a resolved merge. However, that function will always preserve the arity of
         // conflicts it cannot resolve. So we simplify the conflict again
@@ -172,7 +178,9 @@ impl MergedTree {
         // particular,  that this last simplification doesn't enable further automatic
         // resolutions
         if cfg!(debug_assertions) {
-            let re_merged = merge_trees(simplified.clone()).await.unwrap();
+            let re_merged = merge_trees(simplified.clone(), &GitAttributes::empty())
+                .await
+                .unwrap();
             debug_assert_eq!(re_merged, simplified);
         }
         Ok(MergedTree { trees: simplified })
@@ -182,11 +190,33 @@ impl MergedTree {
     /// Recurses into subtrees and yields conflicts in those, but only if
     /// all sides are trees, so tree/file conflicts will be reported as a single
     /// conflict, not one for each path in the tree.
-    // TODO: Restrict this by a matcher (or add a separate method for that).
     pub fn conflicts(
         &self,
     ) -> impl Iterator<Item = (RepoPathBuf, BackendResult<MergedTreeValue>)> + use<> {
-        ConflictIterator::new(self)
+        ConflictIterator::new(self, &EverythingMatcher)
+    }
+
+    /// Like `conflicts()`, but only yields conflicts at paths matching the
+    /// `matcher`. Subtrees the `matcher` doesn't visit aren't read.
+    pub fn conflicts_matching<'matcher>(
+        &self,
+        matcher: &'matcher dyn Matcher,
+    ) -> impl Iterator<Item = (RepoPathBuf, BackendResult<MergedTreeValue>)> + use<'matcher> {
+        ConflictIterator::new(self, matcher)
+    }
+
+    /// Summarizes the conflicts in this tree at paths matching the `matcher`.
+    ///
+    /// This reads the conflicting files to tell whether they are binary.
+    pub async fn conflict_summaries(
+        &self,
+        matcher: &dyn Matcher,
+    ) -> BackendResult<Vec<ConflictSummary>> {
+        let mut summaries = vec![];
+        for (path, values) in self.conflicts_matching(matcher) {
+            summaries.push(ConflictSummary::new(self.store(), path, values?).await?);
+        }
+        Ok(summaries)
     }
 
     /// Whether this tree has conflicts.
@@ -370,6 +400,66 @@ impl MergedTree {
     }
 }
 
+/// Summary of a conflicted path in a tree.
+#[derive(Clone, Debug)]
+pub struct ConflictSummary {
+    /// The conflicted path.
+    pub path: RepoPathBuf,
+    /// The simplified conflicting values.
+    pub values: MergedTreeValue,
+    /// Whether any of the conflicting files looks binary.
+    pub is_binary: bool,
+}
+
+impl ConflictSummary {
+    /// Summarizes the conflict at `path`, reading the first few kilobytes of
+    /// the conflicting files to tell whether they are binary.
+    pub async fn new(
+        store: &Store,
+        path: RepoPathBuf,
+        values: MergedTreeValue,
+    ) -> BackendResult<Self> {
+        let values = values.simplify();
+        let mut is_binary = false;
+        for value in values.iter().flatten() {
+            if let TreeValue::File { id, .. } = value {
+                if is_binary_file(store, &path, id).await? {
+                    is_binary = true;
+                    break;
+                }
+            }
+        }
+        Ok(Self {
+            path,
+            values,
+            is_binary,
+        })
+    }
+
+    /// Returns the number of sides of the conflict.
+    pub fn num_sides(&self) -> usize {
+        self.values.num_sides()
+    }
+}
+
+/// Returns true if the first few kilobytes of the file contain a NUL byte.
+async fn is_binary_file(store: &Store, path: &RepoPath, id: &FileId) -> BackendResult<bool> {
+    const PROBE_LIMIT: u64 = 8 << 10;
+    let mut content = vec![];
+    store
+        .read_file(path, id)
+        .await?
+        .take(PROBE_LIMIT)
+        .read_to_end(&mut content)
+        .await
+        .map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    Ok(content.contains(&b'\0'))
+}
+
 /// A single entry in a tree diff.
 pub struct TreeDiffEntry {
     /// The path.
@@ -469,7 +559,13 @@ fn trees_value<'a>(trees: &'a Merge<Tree>, basename: &RepoPathComponent) -> Merg
 
 /// The returned conflict will either be resolved or have the same number of
 /// sides as the input.
-async fn merge_trees(merge: Merge<Tree>) -> BackendResult<Merge<Tree>> {
+///
+/// The `parent_attributes` are the rules declared in the `.gitattributes`
+/// files of the parent directories, which select the merge driver of files.
+async fn merge_trees(
+    merge: Merge<Tree>,
+    parent_attributes: &GitAttributes,
+) -> BackendResult<Merge<Tree>> {
     let merge = match merge.into_resolved() {
         Ok(tree) => return Ok(Merge::resolved(tree)),
         Err(merge) => merge,
@@ -478,6 +574,7 @@ async fn merge_trees(merge: Merge<Tree>) -> BackendResult<Merge<Tree>> {
     let base_tree = merge.first();
     let store = base_tree.store();
     let dir = base_tree.dir();
+    let attributes = dir_attributes(base_tree, parent_attributes).await?;
     // Keep resolved entries in `new_tree` and conflicted entries in `conflicts` to
     // start with. Then we'll create the full trees later, and only if there are
     // any conflicts.
@@ -486,7 +583,7 @@ async fn merge_trees(merge: Merge<Tree>) -> BackendResult<Merge<Tree>> {
     // TODO: Merge values concurrently
     for (basename, path_merge) in all_merged_tree_entries(&merge) {
         let path = dir.join(basename);
-        let path_merge = merge_tree_values(store, &path, &path_merge).await?;
+        let path_merge = merge_tree_values(store, &path, &path_merge, &attributes).await?;
         match path_merge.into_resolved() {
             Ok(Some(value)) => {
                 new_tree_entries.push((basename.to_owned(), value));
@@ -532,6 +629,7 @@ async fn merge_tree_values(
     store: &Arc<Store>,
     path: &RepoPath,
     values: &MergedTreeVal<'_>,
+    attributes: &GitAttributes,
 ) -> BackendResult<MergedTreeValue> {
     if let Some(resolved) = values.resolve_trivial() {
         return Ok(Merge::resolved(resolved.cloned()));
@@ -541,15 +639,43 @@ async fn merge_tree_values(
         // If all sides are trees or missing, merge the trees recursively, treating
         // missing trees as empty.
         let empty_tree_id = store.empty_tree_id();
-        let merged_tree = Box::pin(merge_trees(trees)).await?;
+        let merged_tree = Box::pin(merge_trees(trees, attributes)).await?;
         Ok(merged_tree
             .map(|tree| (tree.id() != empty_tree_id).then(|| TreeValue::Tree(tree.id().clone()))))
     } else {
-        let maybe_resolved = try_resolve_file_values(store, path, values).await?;
+        let driver = attributes.merge_driver(path);
+        let maybe_resolved = try_resolve_file_values(store, path, values, driver).await?;
         Ok(maybe_resolved.unwrap_or_else(|| values.cloned()))
     }
 }
 
+/// Returns `parent_attributes` extended with the rules declared in the
+/// `.gitattributes` file of `tree`. The file of the first side is used if the
+/// directory is conflicted.
+async fn dir_attributes(
+    tree: &Tree,
+    parent_attributes: &GitAttributes,
+) -> BackendResult<GitAttributes> {
+    let name = RepoPathComponent::new(GITATTRIBUTES_FILE_NAME).unwrap();
+    let Some(TreeValue::File { id, .. }) = tree.value(name) else {
+        return Ok(parent_attributes.clone());
+    };
+    let path = tree.dir().join(name);
+    let mut content = vec![];
+    tree.store()
+        .read_file(&path, id)
+        .await?
+        .read_to_end(&mut content)
+        .await
+        .map_err(|err| BackendError::ReadFile {
+            path: path.clone(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    let rules = GitAttributes::parse_in_dir(tree.dir(), &String::from_utf8_lossy(&content));
+    Ok(parent_attributes.clone().chain(rules))
+}
+
 /// Tries to resolve file conflicts by merging the file contents. Treats missing
 /// files as empty. If the file conflict cannot be resolved, returns the passed
 /// `values` unmodified.
@@ -562,7 +688,7 @@ pub async fn resolve_file_values(
         return Ok(Merge::resolved(resolved.clone()));
     }
 
-    let maybe_resolved = try_resolve_file_values(store, path, &values).await?;
+    let maybe_resolved = try_resolve_file_values(store, path, &values, MergeDriver::Text).await?;
     Ok(maybe_resolved.unwrap_or(values))
 }
 
@@ -570,6 +696,7 @@ async fn try_resolve_file_values<T: Borrow<TreeValue>>(
     store: &Arc<Store>,
     path: &RepoPath,
     values: &Merge<Option<T>>,
+    driver: MergeDriver,
 ) -> BackendResult<Option<MergedTreeValue>> {
     // The values may contain trees canceling each other (notably padded absent
     // trees), so we need to simplify them first.
@@ -578,7 +705,7 @@ async fn try_resolve_file_values<T: Borrow<TreeValue>>(
         .simplify();
     // No fast path for simplified.is_resolved(). If it could be resolved, it would
     // have been caught by values.resolve_trivial() above.
-    if let Some(resolved) = try_resolve_file_conflict(store, path, &simplified).await? {
+    if let Some(resolved) = try_resolve_file_conflict(store, path, &simplified, driver).await? {
         Ok(Some(Merge::normal(resolved)))
     } else {
         // Failed to merge the files, or the paths are not files
@@ -676,36 +803,50 @@ impl From<&Merge<Tree>> for ConflictsDirItem {
     }
 }
 
-struct ConflictIterator {
+struct ConflictIterator<'matcher> {
     store: Arc<Store>,
     stack: Vec<ConflictsDirItem>,
+    matcher: &'matcher dyn Matcher,
 }
 
-impl ConflictIterator {
-    fn new(tree: &MergedTree) -> Self {
+impl<'matcher> ConflictIterator<'matcher> {
+    fn new(tree: &MergedTree, matcher: &'matcher dyn Matcher) -> Self {
+        let mut stack = Vec::new();
+        if !matcher.visit(RepoPath::root()).is_nothing() {
+            stack.push(ConflictsDirItem::from(&tree.trees));
+        }
         ConflictIterator {
             store: tree.store().clone(),
-            stack: vec![ConflictsDirItem::from(&tree.trees)],
+            stack,
+            matcher,
         }
     }
 }
 
-impl Iterator for ConflictIterator {
+impl Iterator for ConflictIterator<'_> {
     type Item = (RepoPathBuf, BackendResult<MergedTreeValue>);
 
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(top) = self.stack.last_mut() {
             if let Some((path, tree_values)) = top.entries.pop() {
+                let visit_dir = !self.matcher.visit(&path).is_nothing();
+                if !visit_dir && !self.matcher.matches(&path) {
+                    continue;
+                }
                 match tree_values.to_tree_merge(&self.store, &path).block_on() {
                     Ok(Some(trees)) => {
                         // If all sides are trees or missing, descend into the merged tree
-                        self.stack.push(ConflictsDirItem::from(&trees));
+                        if visit_dir {
+                            self.stack.push(ConflictsDirItem::from(&trees));
+                        }
                     }
                     Ok(None) => {
                         // Otherwise this is a conflict between files, trees, etc. If they could
                         // be automatically resolved, they should have been when the top-level
                         // tree conflict was written, so we assume that they can't be.
-                        return Some((path, Ok(tree_values)));
+                        if self.matcher.matches(&path) {
+                            return Some((path, Ok(tree_values)));
+                        }
                     }
                     Err(err) => {
                         return Some((path, Err(err)));
@@ -0,0 +1,602 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bundles of operations, which can be exported from a repo and imported into
+//! another repo to reproduce problems with the operation log.
+//!
+//! A bundle contains a range of operations and their views, and optionally the
+//! commits reachable from the views. File contents are never included, so
+//! imported commits have empty trees. Since the ids of the imported objects
+//! may differ from the original ids, all references between the objects are
+//! rewritten on import.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::slice;
+
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use prost::Message as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::Commit;
+use crate::backend::CommitId;
+use crate::backend::Signature;
+use crate::dag_walk;
+use crate::object_id::ObjectId as _;
+use crate::op_store;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::op_store::RefTarget;
+use crate::op_store::View;
+use crate::op_store::ViewId;
+use crate::op_walk;
+use crate::operation::Operation;
+use crate::ref_name::GitRefNameBuf;
+use crate::ref_name::RefNameBuf;
+use crate::ref_name::WorkspaceNameBuf;
+use crate::repo::RepoLoader;
+use crate::simple_backend;
+use crate::simple_op_store;
+
+const MAGIC: &[u8] = b"jj-op-bundle-v1\n";
+const REDACTED: &str = "<redacted>";
+
+const RECORD_ROOT: u8 = b'r';
+const RECORD_COMMIT: u8 = b'c';
+const RECORD_VIEW: u8 = b'v';
+const RECORD_OPERATION: u8 = b'o';
+
+/// Error that may occur while exporting or importing a bundle.
+#[derive(Debug, Error)]
+pub enum OpBundleError {
+    /// The bundle data is malformed.
+    #[error("Invalid operation bundle: {0}")]
+    InvalidFormat(String),
+    /// The bundle refers to an object which isn't included.
+    #[error("Operation bundle doesn't contain {object_type} {hash}")]
+    MissingObject {
+        /// Type of the missing object.
+        object_type: String,
+        /// Hex id of the missing object.
+        hash: String,
+    },
+    /// The exported commits have a cycle.
+    #[error("Commit graph has a cycle at {hash}")]
+    CommitCycle {
+        /// Hex id of a commit in the cycle.
+        hash: String,
+    },
+    /// Failed to read or write the bundle data.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Failed to access the operation store.
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    /// Failed to access the commit backend.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// Options for [`OpBundle::export()`].
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+    /// Include the commits reachable from the exported views.
+    pub with_commits: bool,
+    /// Replace commit and operation descriptions, operation tags, user names,
+    /// emails, hostnames, and bookmark, tag, and workspace names with
+    /// placeholders. Empty values are kept empty. The same name is replaced
+    /// with the same placeholder, so the structure of the views is preserved.
+    pub redact: bool,
+}
+
+/// Stats about [`OpBundle::import()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportStats {
+    /// New ids of the operations which aren't parents of other operations in
+    /// the bundle.
+    pub new_head_ids: Vec<OperationId>,
+    /// The number of imported operations.
+    pub operation_count: usize,
+    /// The number of imported commits.
+    pub commit_count: usize,
+}
+
+/// Range of the operation log with the referenced objects.
+///
+/// Objects are stored in topological order, parents first.
+#[derive(Clone, Debug)]
+pub struct OpBundle {
+    /// Id of the root operation in the exporting repo.
+    pub root_operation_id: OperationId,
+    /// Id of the root commit in the exporting repo.
+    pub root_commit_id: CommitId,
+    /// Commits reachable from the views, excluding the root commit.
+    pub commits: Vec<(CommitId, Commit)>,
+    /// Views of the operations.
+    pub views: Vec<(ViewId, View)>,
+    /// Operations excluding the root operation.
+    pub operations: Vec<(OperationId, op_store::Operation)>,
+}
+
+impl OpBundle {
+    /// Collects the operations reachable from `head_ops` excluding the
+    /// ancestors of `since_ops`. If `since_ops` is empty, all operations but
+    /// the root operation are collected.
+    pub fn export(
+        repo_loader: &RepoLoader,
+        head_ops: &[Operation],
+        since_ops: &[Operation],
+        options: &ExportOptions,
+    ) -> Result<Self, OpBundleError> {
+        let root_op = repo_loader.root_operation();
+        let since_ops = if since_ops.is_empty() {
+            slice::from_ref(&root_op)
+        } else {
+            since_ops
+        };
+        let mut ops: Vec<Operation> =
+            op_walk::walk_ancestors_range(head_ops, since_ops).try_collect()?;
+        ops.reverse();
+
+        let mut views = vec![];
+        let mut seen_view_ids = HashSet::new();
+        for op in &ops {
+            if seen_view_ids.insert(op.view_id().clone()) {
+                views.push((op.view_id().clone(), op.view()?));
+            }
+        }
+
+        let store = repo_loader.store();
+        let mut commits = if options.with_commits {
+            let start_ids = views
+                .iter()
+                .flat_map(|(_, view)| view_commit_ids(view))
+                .filter(|id| *id != store.root_commit_id())
+                .unique()
+                .cloned()
+                .collect_vec();
+            let read_commit = |id: CommitId| -> Result<(CommitId, Commit), OpBundleError> {
+                let commit = store.backend().read_commit(&id).block_on()?;
+                Ok((id, commit))
+            };
+            dag_walk::topo_order_forward_ok(
+                start_ids.into_iter().map(read_commit),
+                |(id, _)| id.clone(),
+                |(_, commit)| {
+                    commit
+                        .parents
+                        .iter()
+                        .filter(|id| *id != store.root_commit_id())
+                        .map(|id| read_commit(id.clone()))
+                        .collect_vec()
+                },
+                |(id, _)| OpBundleError::CommitCycle { hash: id.hex() },
+            )?
+        } else {
+            vec![]
+        };
+
+        let mut operations = ops
+            .iter()
+            .map(|op| (op.id().clone(), op.store_operation().clone()))
+            .collect_vec();
+        if options.redact {
+            let mut redactor = Redactor::default();
+            for (_, commit) in &mut commits {
+                redact(&mut commit.description);
+                redactor.redact_signature(&mut commit.author);
+                redactor.redact_signature(&mut commit.committer);
+            }
+            for (_, view) in &mut views {
+                *view = redactor.redact_view(view);
+            }
+            for (_, operation) in &mut operations {
+                let metadata = &mut operation.metadata;
+                redact(&mut metadata.description);
+                metadata.tags.values_mut().for_each(redact);
+                redactor.redact_name("host", &mut metadata.hostname);
+                redactor.redact_name("user", &mut metadata.username);
+            }
+        }
+
+        Ok(OpBundle {
+            root_operation_id: root_op.id().clone(),
+            root_commit_id: store.root_commit_id().clone(),
+            commits,
+            views,
+            operations,
+        })
+    }
+
+    /// Writes the objects into the stores of the repo, rewriting the
+    /// references between them.
+    ///
+    /// Imported commits have empty trees. Parents of the operations which are
+    /// neither included in the bundle nor present in the repo are replaced
+    /// with the root operation.
+    pub fn import(&self, repo_loader: &RepoLoader) -> Result<ImportStats, OpBundleError> {
+        let store = repo_loader.store();
+        let op_store = repo_loader.op_store();
+
+        let mut commit_id_map =
+            HashMap::from([(self.root_commit_id.clone(), store.root_commit_id().clone())]);
+        for (old_id, commit) in &self.commits {
+            let parents = commit
+                .parents
+                .iter()
+                .map(|id| {
+                    commit_id_map
+                        .get(id)
+                        .cloned()
+                        .ok_or_else(|| missing_object("commit", id.hex()))
+                })
+                .try_collect()?;
+            let predecessors = commit
+                .predecessors
+                .iter()
+                .filter_map(|id| commit_id_map.get(id).cloned())
+                .collect();
+            let new_commit = Commit {
+                parents,
+                predecessors,
+                root_tree: store.empty_merged_tree_id(),
+                secure_sig: None,
+                ..commit.clone()
+            };
+            let (new_id, _) = store.backend().write_commit(new_commit, None).block_on()?;
+            commit_id_map.insert(old_id.clone(), new_id);
+        }
+        let map_commit_id = |id: &CommitId| commit_id_map.get(id).unwrap_or(id).clone();
+
+        let mut view_id_map = HashMap::new();
+        for (old_id, view) in &self.views {
+            let new_id = op_store.write_view(&map_view_commit_ids(view, map_commit_id))?;
+            view_id_map.insert(old_id.clone(), new_id);
+        }
+
+        let mut op_id_map = HashMap::from([(
+            self.root_operation_id.clone(),
+            op_store.root_operation_id().clone(),
+        )]);
+        let mut parent_ids = HashSet::new();
+        for (old_id, operation) in &self.operations {
+            let view_id = view_id_map
+                .get(&operation.view_id)
+                .ok_or_else(|| missing_object("view", operation.view_id.hex()))?;
+            let mut parents = vec![];
+            for id in &operation.parents {
+                let new_id = if let Some(new_id) = op_id_map.get(id) {
+                    new_id.clone()
+                } else if op_store.read_operation(id).is_ok() {
+                    id.clone()
+                } else {
+                    op_store.root_operation_id().clone()
+                };
+                parents.push(new_id);
+            }
+            parent_ids.extend(operation.parents.iter().cloned());
+            let commit_predecessors = operation.commit_predecessors.as_ref().map(|map| {
+                map.iter()
+                    .map(|(new_id, old_ids)| {
+                        let old_ids = old_ids.iter().map(map_commit_id).collect();
+                        (map_commit_id(new_id), old_ids)
+                    })
+                    .collect()
+            });
+            let new_operation = op_store::Operation {
+                view_id: view_id.clone(),
+                parents: parents.into_iter().unique().collect(),
+                metadata: operation.metadata.clone(),
+                commit_predecessors,
+            };
+            let new_id = op_store.write_operation(&new_operation)?;
+            op_id_map.insert(old_id.clone(), new_id);
+        }
+
+        let new_head_ids = self
+            .operations
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| !parent_ids.contains(*id))
+            .map(|id| op_id_map[id].clone())
+            .collect();
+        Ok(ImportStats {
+            new_head_ids,
+            operation_count: self.operations.len(),
+            commit_count: self.commits.len(),
+        })
+    }
+
+    /// Serializes the bundle.
+    pub fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        write_record(
+            out,
+            RECORD_ROOT,
+            self.root_operation_id.as_bytes(),
+            self.root_commit_id.as_bytes(),
+        )?;
+        for (id, commit) in &self.commits {
+            let data = simple_backend::commit_to_proto(commit).encode_to_vec();
+            write_record(out, RECORD_COMMIT, id.as_bytes(), &data)?;
+        }
+        for (id, view) in &self.views {
+            let data = simple_op_store::view_to_proto(view).encode_to_vec();
+            write_record(out, RECORD_VIEW, id.as_bytes(), &data)?;
+        }
+        for (id, operation) in &self.operations {
+            let data = simple_op_store::operation_to_proto(operation).encode_to_vec();
+            write_record(out, RECORD_OPERATION, id.as_bytes(), &data)?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a bundle written by [`OpBundle::write_to()`].
+    pub fn read_from(input: &mut dyn Read) -> Result<Self, OpBundleError> {
+        let mut magic = [0; MAGIC.len()];
+        read_exact(input, &mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_format("unrecognized header"));
+        }
+        let Some((RECORD_ROOT, root_operation_id, root_commit_id)) = read_record(input)? else {
+            return Err(invalid_format("missing root record"));
+        };
+        let mut bundle = OpBundle {
+            root_operation_id: OperationId::new(root_operation_id),
+            root_commit_id: CommitId::new(root_commit_id),
+            commits: vec![],
+            views: vec![],
+            operations: vec![],
+        };
+        while let Some((kind, id, data)) = read_record(input)? {
+            match kind {
+                RECORD_COMMIT => {
+                    let proto = crate::protos::simple_store::Commit::decode(&*data)
+                        .map_err(invalid_format)?;
+                    let commit = simple_backend::commit_from_proto(proto);
+                    bundle.commits.push((CommitId::new(id), commit));
+                }
+                RECORD_VIEW => {
+                    let proto =
+                        crate::protos::op_store::View::decode(&*data).map_err(invalid_format)?;
+                    let view = simple_op_store::view_from_proto(proto);
+                    bundle.views.push((ViewId::new(id), view));
+                }
+                RECORD_OPERATION => {
+                    let proto = crate::protos::op_store::Operation::decode(&*data)
+                        .map_err(invalid_format)?;
+                    let operation =
+                        simple_op_store::operation_from_proto(proto).map_err(invalid_format)?;
+                    bundle.operations.push((OperationId::new(id), operation));
+                }
+                _ => return Err(invalid_format(format!("unknown record type {kind}"))),
+            }
+        }
+        Ok(bundle)
+    }
+}
+
+fn redact(text: &mut String) {
+    if !text.is_empty() {
+        *text = REDACTED.to_owned();
+    }
+}
+
+/// Replaces names with numbered placeholders like `bookmark-1`, consistently
+/// across the bundle.
+#[derive(Debug, Default)]
+struct Redactor {
+    names: HashMap<(&'static str, String), String>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl Redactor {
+    fn placeholder(&mut self, kind: &'static str, name: &str) -> String {
+        let key = (kind, name.to_owned());
+        if let Some(placeholder) = self.names.get(&key) {
+            return placeholder.clone();
+        }
+        let count = self.counts.entry(kind).or_default();
+        *count += 1;
+        let placeholder = format!("{kind}-{count}");
+        self.names.insert(key, placeholder.clone());
+        placeholder
+    }
+
+    fn redact_name(&mut self, kind: &'static str, name: &mut String) {
+        if !name.is_empty() {
+            *name = self.placeholder(kind, name);
+        }
+    }
+
+    fn redact_signature(&mut self, signature: &mut Signature) {
+        self.redact_name("user", &mut signature.name);
+        if !signature.email.is_empty() {
+            let user = self.placeholder("email", &signature.email);
+            signature.email = format!("{user}@example.com");
+        }
+    }
+
+    fn redact_view(&mut self, view: &View) -> View {
+        let mut bookmark_name = |name: &str| RefNameBuf::from(self.placeholder("bookmark", name));
+        let local_bookmarks = view
+            .local_bookmarks
+            .iter()
+            .map(|(name, target)| (bookmark_name(name.as_str()), target.clone()))
+            .collect();
+        let mut remote_views = view.remote_views.clone();
+        for remote_view in remote_views.values_mut() {
+            remote_view.bookmarks = remote_view
+                .bookmarks
+                .iter()
+                .map(|(name, remote_ref)| (bookmark_name(name.as_str()), remote_ref.clone()))
+                .collect();
+        }
+        let tags = view
+            .tags
+            .iter()
+            .map(|(name, target)| {
+                let name = RefNameBuf::from(self.placeholder("tag", name.as_str()));
+                (name, target.clone())
+            })
+            .collect();
+        let git_refs = view
+            .git_refs
+            .iter()
+            .map(|(name, target)| (self.redact_git_ref_name(name.as_str()), target.clone()))
+            .collect();
+        let wc_commit_ids = view
+            .wc_commit_ids
+            .iter()
+            .map(|(name, id)| {
+                let name = WorkspaceNameBuf::from(self.placeholder("workspace", name.as_str()));
+                (name, id.clone())
+            })
+            .collect();
+        View {
+            local_bookmarks,
+            tags,
+            remote_views,
+            git_refs,
+            wc_commit_ids,
+            ..view.clone()
+        }
+    }
+
+    /// Redacts the bookmark or tag name in the Git ref name, keeping the
+    /// `refs/heads/` prefix and the like.
+    fn redact_git_ref_name(&mut self, name: &str) -> GitRefNameBuf {
+        let redacted = if let Some(name) = name.strip_prefix("refs/heads/") {
+            format!("refs/heads/{}", self.placeholder("bookmark", name))
+        } else if let Some(name) = name.strip_prefix("refs/tags/") {
+            format!("refs/tags/{}", self.placeholder("tag", name))
+        } else if let Some((remote, name)) = name
+            .strip_prefix("refs/remotes/")
+            .and_then(|rest| rest.split_once('/'))
+        {
+            let name = self.placeholder("bookmark", name);
+            format!("refs/remotes/{remote}/{name}")
+        } else {
+            format!("refs/{}", self.placeholder("git-ref", name))
+        };
+        GitRefNameBuf::from(redacted)
+    }
+}
+
+/// Returns ids of all commits referenced by the view.
+fn view_commit_ids(view: &View) -> impl Iterator<Item = &CommitId> {
+    let ref_targets = itertools::chain!(
+        view.local_bookmarks.values(),
+        view.tags.values(),
+        view.remote_views
+            .values()
+            .flat_map(|remote_view| remote_view.bookmarks.values())
+            .map(|remote_ref| &remote_ref.target),
+        view.git_refs.values(),
+        [&view.git_head],
+    );
+    itertools::chain!(
+        &view.head_ids,
+        view.wc_commit_ids.values(),
+        ref_targets.flat_map(|target| target.as_merge().iter().flatten()),
+    )
+}
+
+fn map_view_commit_ids(view: &View, map_id: impl Fn(&CommitId) -> CommitId) -> View {
+    let map_target = |target: &RefTarget| {
+        RefTarget::from_merge(target.as_merge().map(|id| id.as_ref().map(&map_id)))
+    };
+    let mut view = view.clone();
+    view.head_ids = view.head_ids.iter().map(&map_id).collect();
+    for id in view.wc_commit_ids.values_mut() {
+        *id = map_id(id);
+    }
+    for target in itertools::chain!(
+        view.local_bookmarks.values_mut(),
+        view.tags.values_mut(),
+        view.git_refs.values_mut(),
+        [&mut view.git_head],
+    ) {
+        *target = map_target(target);
+    }
+    for remote_ref in view
+        .remote_views
+        .values_mut()
+        .flat_map(|remote_view| remote_view.bookmarks.values_mut())
+    {
+        remote_ref.target = map_target(&remote_ref.target);
+    }
+    view
+}
+
+fn write_record(out: &mut dyn Write, kind: u8, id: &[u8], data: &[u8]) -> io::Result<()> {
+    out.write_all(&[kind])?;
+    for bytes in [id, data] {
+        let len = u32::try_from(bytes.len()).map_err(io::Error::other)?;
+        out.write_all(&len.to_le_bytes())?;
+        out.write_all(bytes)?;
+    }
+    Ok(())
+}
+
+/// Reads the next `(kind, id, data)` record, or returns `None` at the end of
+/// the input.
+fn read_record(input: &mut dyn Read) -> Result<Option<(u8, Vec<u8>, Vec<u8>)>, OpBundleError> {
+    let mut kind = [0];
+    if input.read(&mut kind)? == 0 {
+        return Ok(None);
+    }
+    let id = read_bytes(input)?;
+    let data = read_bytes(input)?;
+    Ok(Some((kind[0], id, data)))
+}
+
+fn read_bytes(input: &mut dyn Read) -> Result<Vec<u8>, OpBundleError> {
+    let mut len = [0; 4];
+    read_exact(input, &mut len)?;
+    let len = u32::from_le_bytes(len);
+    // Don't preallocate the buffer since the length may be corrupted.
+    let mut bytes = vec![];
+    (&mut *input).take(len.into()).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(invalid_format("unexpected end of data"));
+    }
+    Ok(bytes)
+}
+
+fn read_exact(input: &mut dyn Read, buf: &mut [u8]) -> Result<(), OpBundleError> {
+    input.read_exact(buf).map_err(|err| {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            invalid_format("unexpected end of data")
+        } else {
+            err.into()
+        }
+    })
+}
+
+fn invalid_format(message: impl ToString) -> OpBundleError {
+    OpBundleError::InvalidFormat(message.to_string())
+}
+
+fn missing_object(object_type: &str, hash: String) -> OpBundleError {
+    OpBundleError::MissingObject {
+        object_type: object_type.to_owned(),
+        hash,
+    }
+}
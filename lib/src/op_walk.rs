@@ -133,6 +133,51 @@ pub fn resolve_op_at(
     resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
 }
 
+/// Range of operations which are ancestors of the `head_ops`, but not
+/// ancestors of the `root_op`.
+#[derive(Clone, Debug)]
+pub struct OperationRange {
+    /// Operation whose ancestors (including itself) are excluded.
+    pub root_op: Operation,
+    /// Operations whose ancestors (including themselves) are included.
+    pub head_ops: Vec<Operation>,
+}
+
+impl OperationRange {
+    /// Walks operations in the range in reverse topological order.
+    pub fn walk(&self) -> impl Iterator<Item = OpStoreResult<Operation>> + use<> {
+        walk_ancestors_range(&self.head_ops, slice::from_ref(&self.root_op))
+    }
+}
+
+/// Resolves operation range expression `root..head` at the given head
+/// operations.
+///
+/// If `root` is omitted, it defaults to the root operation. If `head` is
+/// omitted, it defaults to the given `head_ops`. Returns `None` if `op_str`
+/// isn't a range expression.
+pub fn resolve_op_range_at(
+    repo_loader: &RepoLoader,
+    head_ops: &[Operation],
+    op_str: &str,
+) -> Result<Option<OperationRange>, OpsetEvaluationError> {
+    let Some((root_op_str, head_op_str)) = op_str.split_once("..") else {
+        return Ok(None);
+    };
+    let op_store = repo_loader.op_store();
+    let root_op = if root_op_str.is_empty() {
+        repo_loader.root_operation()
+    } else {
+        resolve_op_at(op_store, head_ops, root_op_str)?
+    };
+    let head_ops = if head_op_str.is_empty() {
+        head_ops.to_vec()
+    } else {
+        vec![resolve_op_at(op_store, head_ops, head_op_str)?]
+    };
+    Ok(Some(OperationRange { root_op, head_ops }))
+}
+
 /// Resolves operation set expression with the given "@" symbol resolution
 /// callbacks.
 fn resolve_single_op(
@@ -19,6 +19,13 @@ pub struct FileState {
     pub conflict_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag = "5")]
     pub materialized_conflict_data: ::core::option::Option<MaterializedConflictData>,
+    /// Sub-millisecond part of the mtime
+    #[prost(uint32, tag = "6")]
+    pub mtime_nanos: u32,
+    #[prost(int64, tag = "7")]
+    pub ctime_nanos_since_epoch: i64,
+    #[prost(uint64, tag = "8")]
+    pub inode: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FileStateEntry {
@@ -32,6 +39,18 @@ pub struct SparsePatterns {
     #[prost(string, repeated, tag = "1")]
     pub prefixes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
+/// New directory that had too many files to be snapshotted.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LargeNewDirectory {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    /// The mtime of the directory when its files were counted
+    #[prost(int64, tag = "2")]
+    pub mtime_millis_since_epoch: i64,
+    /// The number of files the directory was found to have more than
+    #[prost(uint64, tag = "3")]
+    pub max_files: u64,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TreeState {
     #[prost(bytes = "vec", tag = "1")]
@@ -48,6 +67,8 @@ pub struct TreeState {
     pub sparse_patterns: ::core::option::Option<SparsePatterns>,
     #[prost(message, optional, tag = "4")]
     pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    #[prost(message, repeated, tag = "7")]
+    pub large_new_directories: ::prost::alloc::vec::Vec<LargeNewDirectory>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WatchmanClock {
@@ -85,6 +85,9 @@ use crate::refs::diff_named_ref_targets;
 use crate::refs::diff_named_remote_refs;
 use crate::refs::merge_ref_targets;
 use crate::refs::merge_remote_refs;
+use crate::repo_format;
+use crate::repo_format::RepoFormatError;
+use crate::repo_format::REPO_FORMAT_VERSION;
 use crate::revset;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
@@ -196,13 +199,14 @@ impl ReadonlyRepo {
         submodule_store_initializer: &SubmoduleStoreInitializer,
     ) -> Result<Arc<ReadonlyRepo>, RepoInitError> {
         let repo_path = dunce::canonicalize(repo_path).context(repo_path)?;
+        repo_format::write_format_version(&repo_path, REPO_FORMAT_VERSION)?;
 
         let store_path = repo_path.join("store");
         fs::create_dir(&store_path).context(&store_path)?;
         let backend = backend_initializer(settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let store = Store::new(backend, signer);
+        let store = Store::new(backend, signer, settings.store_verify_objects());
 
         let op_store_path = repo_path.join("op_store");
         fs::create_dir(&op_store_path).context(&op_store_path)?;
@@ -474,6 +478,8 @@ pub enum StoreLoadError {
     Backend(#[from] BackendLoadError),
     #[error(transparent)]
     Signing(#[from] SignInitError),
+    #[error(transparent)]
+    Format(#[from] RepoFormatError),
 }
 
 impl StoreFactories {
@@ -510,6 +516,11 @@ impl StoreFactories {
         self.backend_factories.insert(name.to_string(), factory);
     }
 
+    /// Returns the names of the registered commit backends.
+    pub fn backend_names(&self) -> impl Iterator<Item = &str> {
+        self.backend_factories.keys().map(String::as_str)
+    }
+
     pub fn load_backend(
         &self,
         settings: &UserSettings,
@@ -674,9 +685,11 @@ impl RepoLoader {
         repo_path: &Path,
         store_factories: &StoreFactories,
     ) -> Result<Self, StoreLoadError> {
+        repo_format::check_format_version(repo_path)?;
         let store = Store::new(
             store_factories.load_backend(settings, &repo_path.join("store"))?,
             Signer::from_settings(settings)?,
+            settings.store_verify_objects(),
         );
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
@@ -1103,24 +1116,37 @@ impl MutableRepo {
         &mut self,
         options: &RewriteRefsOptions,
     ) -> BackendResult<()> {
-        self.update_all_references(options)?;
+        let pinned_commit_ids = self.update_all_references(options)?;
         self.update_heads()
             .map_err(|err| err.into_backend_error())?;
+        if !pinned_commit_ids.is_empty() {
+            let pinned_commits: Vec<_> = pinned_commit_ids
+                .iter()
+                .map(|id| self.store().get_commit(id))
+                .try_collect()?;
+            self.add_heads(&pinned_commits)?;
+        }
         Ok(())
     }
 
-    fn update_all_references(&mut self, options: &RewriteRefsOptions) -> BackendResult<()> {
+    /// Returns the rewritten commits which are still pointed to by pinned
+    /// bookmarks.
+    fn update_all_references(
+        &mut self,
+        options: &RewriteRefsOptions,
+    ) -> BackendResult<Vec<CommitId>> {
         let rewrite_mapping = self.resolve_rewrite_mapping_with(|_| true);
-        self.update_local_bookmarks(&rewrite_mapping, options);
+        let pinned_commit_ids = self.update_local_bookmarks(&rewrite_mapping, options);
         self.update_wc_commits(&rewrite_mapping)?;
-        Ok(())
+        Ok(pinned_commit_ids)
     }
 
     fn update_local_bookmarks(
         &mut self,
         rewrite_mapping: &HashMap<CommitId, Vec<CommitId>>,
         options: &RewriteRefsOptions,
-    ) {
+    ) -> Vec<CommitId> {
+        let mut pinned_commit_ids = vec![];
         let changed_branches = self
             .view()
             .local_bookmarks()
@@ -1132,6 +1158,10 @@ impl MutableRepo {
             })
             .collect_vec();
         for (bookmark_name, (old_commit_id, new_commit_ids)) in changed_branches {
+            if options.pinned_bookmarks.contains(&bookmark_name) {
+                pinned_commit_ids.push(old_commit_id.clone());
+                continue;
+            }
             let should_delete = options.delete_abandoned_bookmarks
                 && matches!(
                     self.parent_mapping.get(old_commit_id),
@@ -1148,6 +1178,7 @@ impl MutableRepo {
 
             self.merge_local_bookmark(&bookmark_name, &old_target, &new_target);
         }
+        pinned_commit_ids
     }
 
     fn update_wc_commits(
@@ -1331,14 +1362,25 @@ impl MutableRepo {
         mut callback: impl FnMut(CommitRewriter) -> BackendResult<()>,
     ) -> BackendResult<()> {
         let mut to_visit = self.order_commits_for_rebase(commits, new_parents_map)?;
+        // Let the backend persist the objects of the rewritten commits at once.
+        let store = self.store().clone();
+        store.begin_write_batch();
+        let mut result = Ok(());
         while let Some(old_commit) = to_visit.pop() {
             let parent_ids = new_parents_map
                 .get(old_commit.id())
                 .map_or(old_commit.parent_ids(), |parent_ids| parent_ids);
             let new_parent_ids = self.new_parents(parent_ids);
             let rewriter = CommitRewriter::new(self, old_commit, new_parent_ids);
-            callback(rewriter)?;
+            result = callback(rewriter);
+            if result.is_err() {
+                break;
+            }
         }
+        // The batch has to be finished even if the callback failed.
+        let flushed = store.flush_write_batch();
+        result?;
+        flushed?;
         self.update_rewritten_references(options)?;
         // Since we didn't necessarily visit all descendants of rewritten commits (e.g.
         // if they were rewritten in the callback), there can still be commits left to
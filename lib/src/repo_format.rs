@@ -0,0 +1,199 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Versioning of the on-disk repository format.
+//!
+//! The format version is stored in `.jj/repo/format_version`. Repositories
+//! created before the version file was introduced are treated as version 0.
+//! Repositories with a newer version than [`REPO_FORMAT_VERSION`] can't be
+//! loaded. Older repositories can still be loaded, and can be upgraded by
+//! running the pending [`RepoMigration`]s.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+
+/// Repository format version written by this version of the library.
+pub const REPO_FORMAT_VERSION: u32 = 1;
+
+const FORMAT_VERSION_FILE: &str = "format_version";
+
+/// Error while reading, checking, or upgrading the repository format.
+#[derive(Debug, Error)]
+pub enum RepoFormatError {
+    /// The repository was written by a newer version.
+    #[error(
+        "Repository format version {found} is newer than the supported version {supported}; \
+         please upgrade jj"
+    )]
+    TooNew {
+        /// Version found in the repository.
+        found: u32,
+        /// Latest version supported by this library.
+        supported: u32,
+    },
+    /// The version file couldn't be parsed.
+    #[error("Invalid repository format version {0:?}")]
+    Invalid(String),
+    /// A migration step failed.
+    #[error("Failed to upgrade repository format from version {from_version}")]
+    Migration {
+        /// Version the failed migration was upgrading from.
+        from_version: u32,
+        /// Underlying error.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// I/O error.
+    #[error(transparent)]
+    Path(#[from] PathError),
+}
+
+/// Step that upgrades the repository format by one version.
+#[derive(Clone, Copy, Debug)]
+pub struct RepoMigration {
+    /// Version the repository should be at before running this step. The
+    /// repository will be at `from_version + 1` after.
+    pub from_version: u32,
+    /// Human-readable summary of the step.
+    pub description: &'static str,
+    /// Function that migrates the repository at the given `.jj/repo` path.
+    pub run: fn(&Path) -> Result<(), RepoFormatError>,
+}
+
+/// Registered migrations in ascending order of `from_version`.
+///
+/// When the on-disk format changes (e.g. the index format, op-store layout, or
+/// tree-state format), bump [`REPO_FORMAT_VERSION`] and add a step here.
+static MIGRATIONS: &[RepoMigration] = &[RepoMigration {
+    from_version: 0,
+    description: "Record repository format version",
+    // The version file is written after each step, so nothing else to do.
+    run: |_repo_path| Ok(()),
+}];
+
+/// Returns all registered migrations.
+pub fn migrations() -> &'static [RepoMigration] {
+    MIGRATIONS
+}
+
+/// Returns migrations to be run to upgrade a repository at `version` to the
+/// latest version.
+pub fn pending_migrations(version: u32) -> &'static [RepoMigration] {
+    let start = MIGRATIONS.partition_point(|migration| migration.from_version < version);
+    &MIGRATIONS[start..]
+}
+
+/// Reads the format version of the repository at `repo_path`.
+///
+/// Returns 0 if the repository doesn't have a version file.
+pub fn read_format_version(repo_path: &Path) -> Result<u32, RepoFormatError> {
+    let path = repo_path.join(FORMAT_VERSION_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => content
+            .trim()
+            .parse()
+            .map_err(|_| RepoFormatError::Invalid(content.trim().to_owned())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err).context(&path).map_err(Into::into),
+    }
+}
+
+/// Writes the format version of the repository at `repo_path`.
+pub fn write_format_version(repo_path: &Path, version: u32) -> Result<(), PathError> {
+    let path = repo_path.join(FORMAT_VERSION_FILE);
+    fs::write(&path, format!("{version}\n")).context(&path)
+}
+
+/// Reads the format version of the repository at `repo_path`, and checks if
+/// the repository can be loaded by this version of the library.
+pub fn check_format_version(repo_path: &Path) -> Result<u32, RepoFormatError> {
+    let version = read_format_version(repo_path)?;
+    if version > REPO_FORMAT_VERSION {
+        return Err(RepoFormatError::TooNew {
+            found: version,
+            supported: REPO_FORMAT_VERSION,
+        });
+    }
+    Ok(version)
+}
+
+/// Runs pending migrations of the repository at `repo_path`, returning the
+/// migrations that were run.
+///
+/// The version file is updated after each step, so an interrupted upgrade can
+/// be resumed.
+pub fn upgrade_repo(repo_path: &Path) -> Result<Vec<RepoMigration>, RepoFormatError> {
+    let version = check_format_version(repo_path)?;
+    let migrations = pending_migrations(version);
+    for migration in migrations {
+        (migration.run)(repo_path)?;
+        write_format_version(repo_path, migration.from_version + 1)?;
+    }
+    Ok(migrations.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::tests::new_temp_dir;
+
+    #[test]
+    fn test_migrations_are_contiguous() {
+        for (i, migration) in migrations().iter().enumerate() {
+            assert_eq!(migration.from_version, u32::try_from(i).unwrap());
+        }
+        assert_eq!(
+            migrations().len(),
+            usize::try_from(REPO_FORMAT_VERSION).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_upgrade_repo() {
+        let temp_dir = new_temp_dir();
+        let repo_path = temp_dir.path();
+
+        // Missing version file
+        assert_eq!(read_format_version(repo_path).unwrap(), 0);
+        assert_eq!(pending_migrations(0).len(), migrations().len());
+        let run = upgrade_repo(repo_path).unwrap();
+        assert_eq!(run.len(), migrations().len());
+        assert_eq!(read_format_version(repo_path).unwrap(), REPO_FORMAT_VERSION);
+
+        // Already up to date
+        assert!(pending_migrations(REPO_FORMAT_VERSION).is_empty());
+        assert!(upgrade_repo(repo_path).unwrap().is_empty());
+
+        // Newer version
+        write_format_version(repo_path, REPO_FORMAT_VERSION + 1).unwrap();
+        assert_matches!(
+            check_format_version(repo_path),
+            Err(RepoFormatError::TooNew { .. })
+        );
+
+        // Garbage
+        fs::write(repo_path.join(FORMAT_VERSION_FILE), "foo").unwrap();
+        assert_matches!(
+            check_format_version(repo_path),
+            Err(RepoFormatError::Invalid(_))
+        );
+    }
+}
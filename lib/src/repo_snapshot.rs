@@ -0,0 +1,258 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only access to a repo for tools embedding `jj-lib`.
+//!
+//! [`RepoSnapshot`] loads the repo of a workspace at a single operation and
+//! answers the common queries (revset evaluation, file contents, and tree
+//! diffs) without the caller having to assemble loaders, backend factories,
+//! and revset parsing contexts. Tools that don't need the Git backend can
+//! depend on `jj-lib` with `default-features = false`.
+
+#![allow(missing_docs)]
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::commit::Commit;
+use crate::conflicts::materialize_merge_result_to_bytes;
+use crate::conflicts::materialize_tree_value;
+use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::MaterializedTreeValue;
+use crate::mailmap::Mailmap;
+use crate::matchers::Matcher;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::TreeDiffEntry;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::operation::Operation;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo as _;
+use crate::repo::RepoLoaderError;
+use crate::repo::StoreFactories;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathUiConverter;
+use crate::revset;
+use crate::revset::RevsetAliasesMap;
+use crate::revset::RevsetDiagnostics;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExtensions;
+use crate::revset::RevsetIteratorExt as _;
+use crate::revset::RevsetParseContext;
+use crate::revset::RevsetParseError;
+use crate::revset::RevsetResolutionError;
+use crate::revset::RevsetWorkspaceContext;
+use crate::revset::SymbolResolver;
+use crate::settings::UserSettings;
+use crate::workspace::default_working_copy_factories;
+use crate::workspace::Workspace;
+use crate::workspace::WorkspaceLoadError;
+
+/// Error that may occur while loading or querying a [`RepoSnapshot`].
+#[derive(Debug, Error)]
+pub enum RepoSnapshotError {
+    #[error(transparent)]
+    WorkspaceLoad(#[from] WorkspaceLoadError),
+    #[error(transparent)]
+    RepoLoad(#[from] RepoLoaderError),
+    #[error(transparent)]
+    Operation(#[from] OpsetEvaluationError),
+    #[error(transparent)]
+    RevsetParse(#[from] RevsetParseError),
+    #[error(transparent)]
+    RevsetResolution(#[from] RevsetResolutionError),
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// A file that differs between two trees.
+#[derive(Clone, Debug)]
+pub struct FileDiff {
+    pub path: RepoPathBuf,
+    pub before: MergedTreeValue,
+    pub after: MergedTreeValue,
+}
+
+/// Read-only view of the repo of a workspace at a single operation.
+///
+/// The snapshot never changes after loading, even if other processes modify
+/// the repo. Load a new snapshot to observe newer operations.
+pub struct RepoSnapshot {
+    workspace: Workspace,
+    repo: Arc<ReadonlyRepo>,
+    path_converter: RepoPathUiConverter,
+    revset_extensions: RevsetExtensions,
+}
+
+impl RepoSnapshot {
+    /// Loads the workspace at `workspace_root` and its repo at the current
+    /// operation.
+    pub fn load(settings: &UserSettings, workspace_root: &Path) -> Result<Self, RepoSnapshotError> {
+        let workspace = load_workspace(settings, workspace_root)?;
+        let repo = workspace.repo_loader().load_at_head()?;
+        Ok(Self::from_workspace(workspace, repo))
+    }
+
+    /// Loads the workspace at `workspace_root` and its repo at the operation
+    /// specified by `op_str`, e.g. an operation id prefix or `@-`.
+    pub fn load_at_operation(
+        settings: &UserSettings,
+        workspace_root: &Path,
+        op_str: &str,
+    ) -> Result<Self, RepoSnapshotError> {
+        let workspace = load_workspace(settings, workspace_root)?;
+        let op = op_walk::resolve_op_for_load(workspace.repo_loader(), op_str)?;
+        let repo = workspace.repo_loader().load_at(&op)?;
+        Ok(Self::from_workspace(workspace, repo))
+    }
+
+    /// Creates a snapshot from an already loaded workspace and repo. Use this
+    /// if the repo needs custom backend or working-copy factories.
+    pub fn from_workspace(workspace: Workspace, repo: Arc<ReadonlyRepo>) -> Self {
+        let workspace_root = workspace.workspace_root().to_owned();
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: workspace_root.clone(),
+            base: workspace_root,
+        };
+        Self {
+            workspace,
+            repo,
+            path_converter,
+            revset_extensions: RevsetExtensions::default(),
+        }
+    }
+
+    pub fn workspace(&self) -> &Workspace {
+        &self.workspace
+    }
+
+    pub fn repo(&self) -> &Arc<ReadonlyRepo> {
+        &self.repo
+    }
+
+    pub fn operation(&self) -> &Operation {
+        self.repo.operation()
+    }
+
+    /// Evaluates `revset_str` and returns the matching commits in reverse
+    /// topological order.
+    ///
+    /// `@` refers to the working-copy commit of the workspace, and file paths
+    /// are relative to the workspace root. Revset aliases aren't expanded.
+    pub fn evaluate_revset(&self, revset_str: &str) -> Result<Vec<Commit>, RepoSnapshotError> {
+        let context = RevsetParseContext {
+            aliases_map: &RevsetAliasesMap::default(),
+            local_variables: HashMap::new(),
+            user_email: self.repo.settings().user_email(),
+            user_email_aliases: self.repo.settings().user_email_aliases(),
+            date_pattern_context: chrono::Local::now().into(),
+            extensions: &self.revset_extensions,
+            workspace: Some(RevsetWorkspaceContext {
+                path_converter: &self.path_converter,
+                workspace_name: self.workspace.workspace_name(),
+                repo_path: self.workspace.repo_path(),
+            }),
+            mailmap: &Mailmap::empty(),
+        };
+        let expression = revset::parse(&mut RevsetDiagnostics::new(), revset_str, &context)?;
+        let repo = self.repo.as_ref();
+        let symbol_resolver = SymbolResolver::new(repo, self.revset_extensions.symbol_resolvers());
+        let commits = expression
+            .resolve_user_expression(repo, &symbol_resolver)?
+            .evaluate(repo)?
+            .iter()
+            .commits(repo.store())
+            .try_collect()?;
+        Ok(commits)
+    }
+
+    /// Reads the file at `path` in `commit`.
+    ///
+    /// Conflicted files are materialized with conflict markers. Returns `None`
+    /// if the path doesn't exist or isn't a file.
+    pub fn read_file(
+        &self,
+        commit: &Commit,
+        path: &RepoPath,
+    ) -> Result<Option<Vec<u8>>, RepoSnapshotError> {
+        let value = commit.tree()?.path_value(path)?;
+        let contents = read_file_value(&self.repo, path, value).block_on()?;
+        Ok(contents)
+    }
+
+    /// Returns the files matching `matcher` that differ between the trees of
+    /// `from` and `to`.
+    pub fn diff(
+        &self,
+        from: &Commit,
+        to: &Commit,
+        matcher: &dyn Matcher,
+    ) -> Result<Vec<FileDiff>, RepoSnapshotError> {
+        let from_tree = from.tree()?;
+        let to_tree = to.tree()?;
+        let diffs = from_tree
+            .diff_stream(&to_tree, matcher)
+            .map(|TreeDiffEntry { path, values }| {
+                let (before, after) = values?;
+                Ok(FileDiff {
+                    path,
+                    before,
+                    after,
+                })
+            })
+            .try_collect::<Vec<_>>()
+            .block_on()
+            .map_err(RepoSnapshotError::Backend)?;
+        Ok(diffs)
+    }
+}
+
+fn load_workspace(
+    settings: &UserSettings,
+    workspace_root: &Path,
+) -> Result<Workspace, WorkspaceLoadError> {
+    Workspace::load(
+        settings,
+        workspace_root,
+        &StoreFactories::default(),
+        &default_working_copy_factories(),
+    )
+}
+
+async fn read_file_value(
+    repo: &ReadonlyRepo,
+    path: &RepoPath,
+    value: MergedTreeValue,
+) -> BackendResult<Option<Vec<u8>>> {
+    match materialize_tree_value(repo.store(), path, value).await? {
+        MaterializedTreeValue::File(mut file) => Ok(Some(file.read_all(path).await?)),
+        MaterializedTreeValue::FileConflict(file) => Ok(Some(
+            materialize_merge_result_to_bytes(&file.contents, ConflictMarkerStyle::default())
+                .into(),
+        )),
+        _ => Ok(None),
+    }
+}
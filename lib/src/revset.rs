@@ -21,6 +21,7 @@ use std::convert::Infallible;
 use std::fmt;
 use std::ops::ControlFlow;
 use std::ops::Range;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::LazyLock;
@@ -40,6 +41,7 @@ use crate::fileset::FilesetExpression;
 use crate::graph::GraphNode;
 use crate::id_prefix::IdPrefixContext;
 use crate::id_prefix::IdPrefixIndex;
+use crate::mailmap::Mailmap;
 use crate::object_id::HexPrefix;
 use crate::object_id::PrefixResolution;
 use crate::op_store::RefTarget;
@@ -175,6 +177,9 @@ pub enum RevsetFilterPredicate {
     Description(StringPattern),
     /// Commits with first line of the description matching the pattern.
     Subject(StringPattern),
+    /// Commits with a description trailer of the `key` whose value matches the
+    /// pattern.
+    Trailer { key: String, value: StringPattern },
     /// Commits with author name matching the pattern.
     AuthorName(StringPattern),
     /// Commits with author email matching the pattern.
@@ -187,6 +192,13 @@ pub enum RevsetFilterPredicate {
     CommitterEmail(StringPattern),
     /// Commits with committer dates matching the given date pattern.
     CommitterDate(DatePattern),
+    /// Commits with author or committer name or email matching the pattern
+    /// after the identity is canonicalized by the mailmap.
+    MailmapSignature {
+        mailmap: Mailmap,
+        field: SignatureField,
+        pattern: StringPattern,
+    },
     /// Commits modifying the paths specified by the fileset.
     File(FilesetExpression),
     /// Commits containing diffs matching the `text` pattern within the `files`.
@@ -196,12 +208,23 @@ pub enum RevsetFilterPredicate {
     },
     /// Commits with conflicts
     HasConflict,
+    /// Commits with conflicts at the paths specified by the fileset.
+    HasConflictIn(FilesetExpression),
     /// Commits that are cryptographically signed.
     Signed,
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
 
+/// Name or email field of commit signatures.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureField {
+    AuthorName,
+    AuthorEmail,
+    CommitterName,
+    CommitterEmail,
+}
+
 mod private {
     /// Defines [`RevsetExpression`] variants depending on resolution state.
     pub trait ExpressionState {
@@ -726,25 +749,15 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
         }
     });
     map.insert("ancestors", |diagnostics, function, context| {
-        let ([heads_arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let ([heads_arg], [depth_or_from_arg, to_arg]) = function.expect_arguments()?;
         let heads = lower_expression(diagnostics, heads_arg, context)?;
-        let generation = if let Some(depth_arg) = depth_opt_arg {
-            let depth = expect_literal("integer", depth_arg)?;
-            0..depth
-        } else {
-            GENERATION_RANGE_FULL
-        };
+        let generation = expect_generation_range(depth_or_from_arg, to_arg)?;
         Ok(heads.ancestors_range(generation))
     });
     map.insert("descendants", |diagnostics, function, context| {
-        let ([roots_arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let ([roots_arg], [depth_or_from_arg, to_arg]) = function.expect_arguments()?;
         let roots = lower_expression(diagnostics, roots_arg, context)?;
-        let generation = if let Some(depth_arg) = depth_opt_arg {
-            let depth = expect_literal("integer", depth_arg)?;
-            0..depth
-        } else {
-            GENERATION_RANGE_FULL
-        };
+        let generation = expect_generation_range(depth_or_from_arg, to_arg)?;
         Ok(roots.descendants_range(generation))
     });
     map.insert("connected", |diagnostics, function, context| {
@@ -881,24 +894,41 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
         let predicate = RevsetFilterPredicate::Subject(pattern);
         Ok(RevsetExpression::filter(predicate))
     });
-    map.insert("author", |diagnostics, function, _context| {
-        let [arg] = function.expect_exact_arguments()?;
+    map.insert("trailer", |diagnostics, function, _context| {
+        let ([key_arg], [value_opt_arg]) = function.expect_arguments()?;
+        let key = revset_parser::expect_literal("string", key_arg)?;
+        let value = if let Some(value_arg) = value_opt_arg {
+            expect_string_pattern(diagnostics, value_arg)?
+        } else {
+            StringPattern::everything()
+        };
+        let predicate = RevsetFilterPredicate::Trailer { key, value };
+        Ok(RevsetExpression::filter(predicate))
+    });
+    map.insert("author", |diagnostics, function, context| {
+        let ([], [arg]) = function.expect_arguments()?;
+        let Some(arg) = arg else {
+            // author() without arguments is a shorthand for mine().
+            let expression = context.user_email_expression(RevsetFilterPredicate::AuthorEmail);
+            return Ok(expression);
+        };
         let pattern = expect_string_pattern(diagnostics, arg)?;
-        let name_predicate = RevsetFilterPredicate::AuthorName(pattern.clone());
-        let email_predicate = RevsetFilterPredicate::AuthorEmail(pattern);
+        let name_predicate =
+            context.signature_predicate(SignatureField::AuthorName, pattern.clone());
+        let email_predicate = context.signature_predicate(SignatureField::AuthorEmail, pattern);
         Ok(RevsetExpression::filter(name_predicate)
             .union(&RevsetExpression::filter(email_predicate)))
     });
-    map.insert("author_name", |diagnostics, function, _context| {
+    map.insert("author_name", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_string_pattern(diagnostics, arg)?;
-        let predicate = RevsetFilterPredicate::AuthorName(pattern);
+        let predicate = context.signature_predicate(SignatureField::AuthorName, pattern);
         Ok(RevsetExpression::filter(predicate))
     });
-    map.insert("author_email", |diagnostics, function, _context| {
+    map.insert("author_email", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_string_pattern(diagnostics, arg)?;
-        let predicate = RevsetFilterPredicate::AuthorEmail(pattern);
+        let predicate = context.signature_predicate(SignatureField::AuthorEmail, pattern);
         Ok(RevsetExpression::filter(predicate))
     });
     map.insert("author_date", |diagnostics, function, context| {
@@ -915,31 +945,32 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
     });
     map.insert("mine", |_diagnostics, function, context| {
         function.expect_no_arguments()?;
-        // Email address domains are inherently case‐insensitive, and the local‐parts
-        // are generally (although not universally) treated as case‐insensitive too, so
-        // we use a case‐insensitive match here.
-        let predicate =
-            RevsetFilterPredicate::AuthorEmail(StringPattern::exact_i(context.user_email));
-        Ok(RevsetExpression::filter(predicate))
+        let expression = context.user_email_expression(RevsetFilterPredicate::AuthorEmail);
+        Ok(expression)
     });
-    map.insert("committer", |diagnostics, function, _context| {
-        let [arg] = function.expect_exact_arguments()?;
+    map.insert("committer", |diagnostics, function, context| {
+        let ([], [arg]) = function.expect_arguments()?;
+        let Some(arg) = arg else {
+            let expression = context.user_email_expression(RevsetFilterPredicate::CommitterEmail);
+            return Ok(expression);
+        };
         let pattern = expect_string_pattern(diagnostics, arg)?;
-        let name_predicate = RevsetFilterPredicate::CommitterName(pattern.clone());
-        let email_predicate = RevsetFilterPredicate::CommitterEmail(pattern);
+        let name_predicate =
+            context.signature_predicate(SignatureField::CommitterName, pattern.clone());
+        let email_predicate = context.signature_predicate(SignatureField::CommitterEmail, pattern);
         Ok(RevsetExpression::filter(name_predicate)
             .union(&RevsetExpression::filter(email_predicate)))
     });
-    map.insert("committer_name", |diagnostics, function, _context| {
+    map.insert("committer_name", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_string_pattern(diagnostics, arg)?;
-        let predicate = RevsetFilterPredicate::CommitterName(pattern);
+        let predicate = context.signature_predicate(SignatureField::CommitterName, pattern);
         Ok(RevsetExpression::filter(predicate))
     });
-    map.insert("committer_email", |diagnostics, function, _context| {
+    map.insert("committer_email", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_string_pattern(diagnostics, arg)?;
-        let predicate = RevsetFilterPredicate::CommitterEmail(pattern);
+        let predicate = context.signature_predicate(SignatureField::CommitterEmail, pattern);
         Ok(RevsetExpression::filter(predicate))
     });
     map.insert("committer_date", |diagnostics, function, context| {
@@ -964,6 +995,21 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
         let expr = expect_fileset_expression(diagnostics, arg, ctx.path_converter)?;
         Ok(RevsetExpression::filter(RevsetFilterPredicate::File(expr)))
     });
+    map.insert("touches", |_diagnostics, function, context| {
+        let ctx = context.workspace.as_ref().ok_or_else(|| {
+            RevsetParseError::with_span(
+                RevsetParseErrorKind::FsPathWithoutWorkspace,
+                function.args_span,
+            )
+        })?;
+        let [arg] = function.expect_exact_arguments()?;
+        let input: String = expect_literal("string", arg)?;
+        let path = ctx.path_converter.parse_file_path(&input).map_err(|err| {
+            RevsetParseError::expression("Invalid file path", arg.span).with_source(err)
+        })?;
+        let expr = FilesetExpression::file_path(path);
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::File(expr)))
+    });
     map.insert("diff_contains", |diagnostics, function, context| {
         let ([text_arg], [files_opt_arg]) = function.expect_arguments()?;
         let text = expect_string_pattern(diagnostics, text_arg)?;
@@ -984,9 +1030,21 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
             RevsetFilterPredicate::DiffContains { text, files },
         ))
     });
-    map.insert("conflicts", |_diagnostics, function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::filter(RevsetFilterPredicate::HasConflict))
+    map.insert("conflicts", |diagnostics, function, context| {
+        let ([], [files_opt_arg]) = function.expect_arguments()?;
+        let Some(files_arg) = files_opt_arg else {
+            return Ok(RevsetExpression::filter(RevsetFilterPredicate::HasConflict));
+        };
+        let ctx = context.workspace.as_ref().ok_or_else(|| {
+            RevsetParseError::with_span(
+                RevsetParseErrorKind::FsPathWithoutWorkspace,
+                files_arg.span,
+            )
+        })?;
+        let files = expect_fileset_expression(diagnostics, files_arg, ctx.path_converter)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::HasConflictIn(files),
+        ))
     });
     map.insert("present", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
@@ -1016,6 +1074,31 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
     map
 });
 
+/// Returns the names of the builtin revset functions in sorted order.
+pub fn builtin_function_names() -> Vec<&'static str> {
+    BUILTIN_FUNCTION_MAP.keys().copied().sorted().collect()
+}
+
+/// Parses the optional `depth` or `from, to` arguments of `ancestors()` and
+/// `descendants()` as a generation range.
+fn expect_generation_range(
+    depth_or_from_arg: Option<&ExpressionNode>,
+    to_arg: Option<&ExpressionNode>,
+) -> Result<Range<u64>, RevsetParseError> {
+    match (depth_or_from_arg, to_arg) {
+        (Some(from_arg), Some(to_arg)) => {
+            let from = expect_literal("integer", from_arg)?;
+            let to = expect_literal("integer", to_arg)?;
+            Ok(from..to)
+        }
+        (Some(depth_arg), None) => {
+            let depth = expect_literal("integer", depth_arg)?;
+            Ok(0..depth)
+        }
+        (None, _) => Ok(GENERATION_RANGE_FULL),
+    }
+}
+
 /// Parses the given `node` as a fileset expression.
 pub fn expect_fileset_expression(
     diagnostics: &mut RevsetDiagnostics,
@@ -3119,9 +3202,13 @@ pub struct RevsetParseContext<'a> {
     pub aliases_map: &'a RevsetAliasesMap,
     pub local_variables: HashMap<&'a str, ExpressionNode<'a>>,
     pub user_email: &'a str,
+    /// Patterns of additional email addresses which identify the user.
+    pub user_email_aliases: &'a [StringPattern],
     pub date_pattern_context: DatePatternContext,
     pub extensions: &'a RevsetExtensions,
     pub workspace: Option<RevsetWorkspaceContext<'a>>,
+    /// Mailmap to canonicalize author and committer identities.
+    pub mailmap: &'a Mailmap,
 }
 
 impl<'a> RevsetParseContext<'a> {
@@ -3130,15 +3217,19 @@ impl<'a> RevsetParseContext<'a> {
             aliases_map: _,
             local_variables: _,
             user_email,
+            user_email_aliases,
             date_pattern_context,
             extensions,
             workspace,
+            mailmap,
         } = *self;
         LoweringContext {
             user_email,
+            user_email_aliases,
             date_pattern_context,
             extensions,
             workspace,
+            mailmap,
         }
     }
 }
@@ -3147,9 +3238,11 @@ impl<'a> RevsetParseContext<'a> {
 #[derive(Clone)]
 pub struct LoweringContext<'a> {
     user_email: &'a str,
+    user_email_aliases: &'a [StringPattern],
     date_pattern_context: DatePatternContext,
     extensions: &'a RevsetExtensions,
     workspace: Option<RevsetWorkspaceContext<'a>>,
+    mailmap: &'a Mailmap,
 }
 
 impl<'a> LoweringContext<'a> {
@@ -3157,6 +3250,10 @@ impl<'a> LoweringContext<'a> {
         self.user_email
     }
 
+    pub fn user_email_aliases(&self) -> &'a [StringPattern] {
+        self.user_email_aliases
+    }
+
     pub fn date_pattern_context(&self) -> &DatePatternContext {
         &self.date_pattern_context
     }
@@ -3164,6 +3261,54 @@ impl<'a> LoweringContext<'a> {
     pub fn symbol_resolvers(&self) -> &'a [impl AsRef<dyn SymbolResolverExtension> + use<>] {
         self.extensions.symbol_resolvers()
     }
+
+    pub fn mailmap(&self) -> &'a Mailmap {
+        self.mailmap
+    }
+
+    pub fn workspace(&self) -> Option<RevsetWorkspaceContext<'a>> {
+        self.workspace
+    }
+
+    /// Builds expression matching commits whose email (selected by
+    /// `to_predicate`) is the user's email or one of the email aliases.
+    fn user_email_expression(
+        &self,
+        to_predicate: fn(StringPattern) -> RevsetFilterPredicate,
+    ) -> Rc<UserRevsetExpression> {
+        // Email address domains are inherently case‐insensitive, and the local‐parts
+        // are generally (although not universally) treated as case‐insensitive too, so
+        // we use a case‐insensitive match here.
+        let user_pattern = StringPattern::exact_i(self.user_email);
+        let expressions = [user_pattern]
+            .into_iter()
+            .chain(self.user_email_aliases.iter().cloned())
+            .map(|pattern| RevsetExpression::filter(to_predicate(pattern)))
+            .collect_vec();
+        RevsetExpression::union_all(&expressions)
+    }
+
+    /// Builds predicate matching the signature field, which is canonicalized
+    /// by the mailmap if any.
+    fn signature_predicate(
+        &self,
+        field: SignatureField,
+        pattern: StringPattern,
+    ) -> RevsetFilterPredicate {
+        if !self.mailmap.is_empty() {
+            return RevsetFilterPredicate::MailmapSignature {
+                mailmap: self.mailmap.clone(),
+                field,
+                pattern,
+            };
+        }
+        match field {
+            SignatureField::AuthorName => RevsetFilterPredicate::AuthorName(pattern),
+            SignatureField::AuthorEmail => RevsetFilterPredicate::AuthorEmail(pattern),
+            SignatureField::CommitterName => RevsetFilterPredicate::CommitterName(pattern),
+            SignatureField::CommitterEmail => RevsetFilterPredicate::CommitterEmail(pattern),
+        }
+    }
 }
 
 /// Workspace information needed to parse revset expression.
@@ -3171,6 +3316,9 @@ impl<'a> LoweringContext<'a> {
 pub struct RevsetWorkspaceContext<'a> {
     pub path_converter: &'a RepoPathUiConverter,
     pub workspace_name: &'a WorkspaceName,
+    /// Path to the repo directory shared by the workspace, where extensions
+    /// may keep their own data.
+    pub repo_path: &'a Path,
 }
 
 /// Formats a string as symbol by quoting and escaping it if necessary.
@@ -3233,9 +3381,11 @@ mod tests {
             aliases_map: &aliases_map,
             local_variables: HashMap::new(),
             user_email: "test.user@example.com",
+            user_email_aliases: &[],
             date_pattern_context: chrono::Utc::now().fixed_offset().into(),
             extensions: &RevsetExtensions::default(),
             workspace: None,
+            mailmap: &Mailmap::empty(),
         };
         super::parse(&mut RevsetDiagnostics::new(), revset_str, &context)
     }
@@ -3253,6 +3403,7 @@ mod tests {
         let workspace_ctx = RevsetWorkspaceContext {
             path_converter: &path_converter,
             workspace_name,
+            repo_path: Path::new("/.jj/repo"),
         };
         let mut aliases_map = RevsetAliasesMap::new();
         for (decl, defn) in aliases {
@@ -3262,9 +3413,11 @@ mod tests {
             aliases_map: &aliases_map,
             local_variables: HashMap::new(),
             user_email: "test.user@example.com",
+            user_email_aliases: &[],
             date_pattern_context: chrono::Utc::now().fixed_offset().into(),
             extensions: &RevsetExtensions::default(),
             workspace: Some(workspace_ctx),
+            mailmap: &Mailmap::empty(),
         };
         super::parse(&mut RevsetDiagnostics::new(), revset_str, &context)
     }
@@ -3287,9 +3440,11 @@ mod tests {
             aliases_map: &aliases_map,
             local_variables: HashMap::new(),
             user_email: "test.user@example.com",
+            user_email_aliases: &[],
             date_pattern_context: chrono::Utc::now().fixed_offset().into(),
             extensions: &RevsetExtensions::default(),
             workspace: None,
+            mailmap: &Mailmap::empty(),
         };
         super::parse_with_modifier(&mut RevsetDiagnostics::new(), revset_str, &context)
     }
@@ -3753,6 +3908,27 @@ mod tests {
             generation: 2..3,
         }
         "#);
+        insta::assert_debug_snapshot!(
+            parse("ancestors(foo, 5, 11)").unwrap(), @r#"
+        Ancestors {
+            heads: CommitRef(Symbol("foo")),
+            generation: 5..11,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("descendants(foo, 2, 4)").unwrap(), @r#"
+        Descendants {
+            roots: CommitRef(Symbol("foo")),
+            generation: 2..4,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("ancestors(foo, 1, 2, 3)").unwrap_err().kind(), @r#"
+        InvalidFunctionArguments {
+            name: "ancestors",
+            message: "Expected 1 to 3 arguments",
+        }
+        "#);
         insta::assert_debug_snapshot!(
             parse("root()").unwrap(),
             @"Root");
@@ -3800,6 +3976,14 @@ mod tests {
             ),
         )
         "#);
+        insta::assert_debug_snapshot!(
+            parse_with_workspace("touches(foo)", WorkspaceName::DEFAULT).unwrap(),
+            @r#"Filter(File(Pattern(FilePath("foo"))))"#);
+        insta::assert_debug_snapshot!(
+            parse_with_workspace(r#"touches("foo/bar")"#, WorkspaceName::DEFAULT).unwrap(),
+            @r#"Filter(File(Pattern(FilePath("foo/bar"))))"#);
+        assert!(parse_with_workspace("touches(all())", WorkspaceName::DEFAULT).is_err());
+        assert!(parse("touches(foo)").is_err());
         insta::assert_debug_snapshot!(parse("signed()").unwrap(), @"Filter(Signed)");
     }
 
@@ -3865,6 +4049,12 @@ mod tests {
         insta::assert_debug_snapshot!(
             parse("mine()").unwrap(),
             @r#"Filter(AuthorEmail(ExactI("test.user@example.com")))"#);
+        insta::assert_debug_snapshot!(
+            parse("author()").unwrap(),
+            @r#"Filter(AuthorEmail(ExactI("test.user@example.com")))"#);
+        insta::assert_debug_snapshot!(
+            parse("committer()").unwrap(),
+            @r#"Filter(CommitterEmail(ExactI("test.user@example.com")))"#);
     }
 
     #[test]
@@ -42,6 +42,7 @@ use crate::matchers::Visit;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::MergedTreeBuilder;
 use crate::merged_tree::TreeDiffEntry;
+use crate::ref_name::RefNameBuf;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
@@ -255,13 +256,25 @@ impl<'repo> CommitRewriter<'repo> {
         } else {
             let old_base_tree_fut = merge_commit_trees(self.mut_repo, &old_parents);
             let new_base_tree_fut = merge_commit_trees(self.mut_repo, &new_parents);
-            let old_tree_fut = self.old_commit.tree_async();
-            let (old_base_tree, new_base_tree, old_tree) =
-                try_join!(old_base_tree_fut, new_base_tree_fut, old_tree_fut)?;
-            (
-                old_base_tree.id() == *self.old_commit.tree_id(),
-                new_base_tree.merge(old_base_tree, old_tree).await?.id(),
-            )
+            let (old_base_tree, new_base_tree) = try_join!(old_base_tree_fut, new_base_tree_fut)?;
+            let old_base_tree_id = old_base_tree.id();
+            let new_base_tree_id = new_base_tree.id();
+            let was_empty = old_base_tree_id == *self.old_commit.tree_id();
+            if new_base_tree_id == old_base_tree_id {
+                // Optimization: The parents changed, but their merged tree
+                // didn't, so the commit's tree can be reused as is.
+                (was_empty, self.old_commit.tree_id().clone())
+            } else if was_empty {
+                // Optimization: The commit doesn't change anything relative to
+                // its parents, so its new tree is the new parents' tree.
+                (was_empty, new_base_tree_id)
+            } else {
+                let old_tree = self.old_commit.tree_async().await?;
+                (
+                    was_empty,
+                    new_base_tree.merge(old_base_tree, old_tree).await?.id(),
+                )
+            }
         };
         // Ensure we don't abandon commits with multiple parents (merge commits), even
         // if they're empty.
@@ -401,6 +414,12 @@ pub struct RewriteRefsOptions {
     /// If false, bookmarks will be moved to the parents of the abandoned
     /// commit.
     pub delete_abandoned_bookmarks: bool,
+    /// Local bookmarks that should stay at the original commits instead of
+    /// following the rewritten commits.
+    ///
+    /// The original commits are kept visible, so they will usually become
+    /// divergent with the rewritten commits.
+    pub pinned_bookmarks: HashSet<RefNameBuf>,
 }
 
 pub struct MoveCommitsStats {
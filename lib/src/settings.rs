@@ -14,6 +14,7 @@
 
 #![allow(missing_docs)]
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -36,7 +37,11 @@ use crate::config::StackedConfig;
 use crate::config::ToConfigNamePath;
 use crate::fmt_util::binary_prefix;
 use crate::fsmonitor::FsmonitorSettings;
+use crate::ref_name::RemoteName;
+use crate::ref_name::RemoteNameBuf;
+use crate::ref_name::RemoteRefSymbol;
 use crate::signing::SignBehavior;
+use crate::str_util::StringPattern;
 
 #[derive(Debug, Clone)]
 pub struct UserSettings {
@@ -49,17 +54,23 @@ pub struct UserSettings {
 struct UserSettingsData {
     user_name: String,
     user_email: String,
+    user_email_aliases: Vec<StringPattern>,
     commit_timestamp: Option<Timestamp>,
     operation_timestamp: Option<Timestamp>,
+    operation_concurrency_retries: u32,
     operation_hostname: String,
     operation_username: String,
     signing_behavior: SignBehavior,
     signing_key: Option<String>,
+    store_verify_objects: bool,
+    rewrite_committer_timestamp: CommitterTimestampPolicy,
 }
 
 #[derive(Debug, Clone)]
 pub struct GitSettings {
     pub auto_local_bookmark: bool,
+    /// Patterns of bookmark names to track automatically, by remote.
+    pub auto_track_bookmarks: HashMap<RemoteNameBuf, StringPattern>,
     pub abandon_unreachable_commits: bool,
     pub executable_path: PathBuf,
     pub write_change_id_header: bool,
@@ -69,17 +80,51 @@ impl GitSettings {
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         Ok(GitSettings {
             auto_local_bookmark: settings.get_bool("git.auto-local-bookmark")?,
+            auto_track_bookmarks: get_auto_track_bookmarks(settings)?,
             abandon_unreachable_commits: settings.get_bool("git.abandon-unreachable-commits")?,
             executable_path: settings.get("git.executable-path")?,
             write_change_id_header: settings.get("git.write-change-id-header")?,
         })
     }
+
+    /// Returns true if the given remote bookmark should be tracked when it is
+    /// first imported.
+    ///
+    /// The pattern configured for the remote takes precedence over
+    /// `git.auto-local-bookmark`.
+    pub fn should_auto_track_bookmark(&self, symbol: RemoteRefSymbol<'_>) -> bool {
+        match self.auto_track_bookmarks.get(symbol.remote) {
+            Some(pattern) => pattern.is_match(symbol.name.as_str()),
+            None => self.auto_local_bookmark,
+        }
+    }
+}
+
+fn get_auto_track_bookmarks(
+    settings: &UserSettings,
+) -> Result<HashMap<RemoteNameBuf, StringPattern>, ConfigGetError> {
+    fn parse_pattern(
+        value: ConfigValue,
+    ) -> Result<StringPattern, Box<dyn std::error::Error + Send + Sync>> {
+        let text = value.as_str().ok_or("expected a string")?;
+        Ok(StringPattern::parse(text)?)
+    }
+
+    let mut patterns = HashMap::new();
+    for remote in settings.table_keys("remotes") {
+        let name = ["remotes", remote, "auto-track-bookmarks"];
+        if let Some(pattern) = settings.get_value_with(name, parse_pattern).optional()? {
+            patterns.insert(RemoteName::new(remote).to_owned(), pattern);
+        }
+    }
+    Ok(patterns)
 }
 
 impl Default for GitSettings {
     fn default() -> Self {
         GitSettings {
             auto_local_bookmark: false,
+            auto_track_bookmarks: HashMap::new(),
             abandon_unreachable_commits: true,
             executable_path: PathBuf::from("git"),
             write_change_id_header: true,
@@ -114,6 +159,48 @@ impl SignSettings {
     }
 }
 
+/// How the committer timestamp is set when a commit is rewritten.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CommitterTimestampPolicy {
+    /// Use the current time (or `debug.commit-timestamp` if set.)
+    Now,
+    /// Keep the committer timestamp of the original commit.
+    Preserve,
+    /// Use the given timestamp.
+    Fixed(Timestamp),
+}
+
+fn to_committer_timestamp_policy(
+    value: ConfigValue,
+) -> Result<CommitterTimestampPolicy, Box<dyn std::error::Error + Send + Sync>> {
+    match value.as_str() {
+        Some("now") => Ok(CommitterTimestampPolicy::Now),
+        Some("preserve") => Ok(CommitterTimestampPolicy::Preserve),
+        _ => Ok(CommitterTimestampPolicy::Fixed(to_timestamp(value)?)),
+    }
+}
+
+fn to_email_aliases(
+    value: ConfigValue,
+) -> Result<Vec<StringPattern>, Box<dyn std::error::Error + Send + Sync>> {
+    let array = value.as_array().ok_or("expected an array of strings")?;
+    array
+        .iter()
+        .map(
+            |item| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+                let text = item.as_str().ok_or("expected an array of strings")?;
+                // Plain email addresses are matched case-insensitively like the
+                // configured user.email.
+                if text.contains(':') {
+                    Ok(StringPattern::parse(text)?)
+                } else {
+                    Ok(StringPattern::exact_i(text))
+                }
+            },
+        )
+        .collect()
+}
+
 fn to_timestamp(value: ConfigValue) -> Result<Timestamp, Box<dyn std::error::Error + Send + Sync>> {
     // Since toml_edit::Datetime isn't the date-time type used across our code
     // base, we accept both string and date-time types.
@@ -138,6 +225,7 @@ impl UserSettings {
     fn from_config_and_rng(config: StackedConfig, rng: Arc<JJRng>) -> Result<Self, ConfigGetError> {
         let user_name = config.get("user.name")?;
         let user_email = config.get("user.email")?;
+        let user_email_aliases = config.get_value_with("user.email-aliases", to_email_aliases)?;
         let commit_timestamp = config
             .get_value_with("debug.commit-timestamp", to_timestamp)
             .optional()?;
@@ -146,17 +234,25 @@ impl UserSettings {
             .optional()?;
         let operation_hostname = config.get("operation.hostname")?;
         let operation_username = config.get("operation.username")?;
+        let operation_concurrency_retries = config.get("operation.concurrency-retries")?;
         let signing_behavior = config.get("signing.behavior")?;
         let signing_key = config.get("signing.key").optional()?;
+        let store_verify_objects = config.get("store.verify-objects")?;
+        let rewrite_committer_timestamp =
+            config.get_value_with("rewrite.committer-timestamp", to_committer_timestamp_policy)?;
         let data = UserSettingsData {
             user_name,
             user_email,
+            user_email_aliases,
             commit_timestamp,
             operation_timestamp,
+            operation_concurrency_retries,
             operation_hostname,
             operation_username,
             signing_behavior,
             signing_key,
+            store_verify_objects,
+            rewrite_committer_timestamp,
         };
         Ok(UserSettings {
             config: Arc::new(config),
@@ -188,6 +284,11 @@ impl UserSettings {
         &self.data.user_email
     }
 
+    /// Patterns of additional email addresses which identify the current user.
+    pub fn user_email_aliases(&self) -> &[StringPattern] {
+        &self.data.user_email_aliases
+    }
+
     pub fn fsmonitor_settings(&self) -> Result<FsmonitorSettings, ConfigGetError> {
         FsmonitorSettings::from_settings(self)
     }
@@ -204,6 +305,23 @@ impl UserSettings {
         self.data.operation_timestamp
     }
 
+    /// Number of times a transaction is rebased onto operations published
+    /// concurrently before giving up and leaving divergent operation heads.
+    pub fn operation_concurrency_retries(&self) -> u32 {
+        self.data.operation_concurrency_retries
+    }
+
+    /// Whether file and tree objects are checked against their ids when read
+    /// from the backend.
+    pub fn store_verify_objects(&self) -> bool {
+        self.data.store_verify_objects
+    }
+
+    /// How the committer timestamp is set when a commit is rewritten.
+    pub fn rewrite_committer_timestamp(&self) -> CommitterTimestampPolicy {
+        self.data.rewrite_committer_timestamp
+    }
+
     pub fn operation_hostname(&self) -> &str {
         &self.data.operation_hostname
     }
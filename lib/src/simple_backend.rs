@@ -53,6 +53,7 @@ use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::ObjectContent;
 use crate::backend::SecureSig;
 use crate::backend::Signature;
 use crate::backend::SigningFn;
@@ -281,6 +282,37 @@ impl Backend for SimpleBackend {
         Ok(tree_from_proto(proto))
     }
 
+    fn hash_object(&self, content: ObjectContent<'_>) -> Option<Vec<u8>> {
+        match content {
+            ObjectContent::File(data) => Some(Blake2b512::digest(data).to_vec()),
+            ObjectContent::Tree(tree) => Some(blake2b_hash(tree).to_vec()),
+        }
+    }
+
+    fn quarantine_object(&self, id: &dyn ObjectId) -> Option<String> {
+        let dir = match id.object_type().as_str() {
+            "file" => "files",
+            "tree" => "trees",
+            _ => return None,
+        };
+        let disk_path = self.path.join(dir).join(id.hex());
+        let quarantine_path = self.path.join("quarantine").join(dir).join(id.hex());
+        let moved = fs::create_dir_all(quarantine_path.parent().unwrap())
+            .and_then(|()| fs::rename(&disk_path, &quarantine_path))
+            .inspect_err(|err| tracing::debug!(?err, ?disk_path, "failed to quarantine object"))
+            .is_ok();
+        let hint = if moved {
+            format!(
+                "The corrupt object was moved to {}. Restore the object from a backup of the \
+                 `.jj/repo/store` directory.",
+                quarantine_path.display()
+            )
+        } else {
+            "Restore the object from a backup of the `.jj/repo/store` directory.".to_owned()
+        };
+        Some(hint)
+    }
+
     async fn write_tree(&self, _path: &RepoPath, tree: &Tree) -> BackendResult<TreeId> {
         // TODO: Write temporary file in the destination directory (#5712)
         let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
@@ -412,7 +444,7 @@ pub fn commit_to_proto(commit: &Commit) -> crate::protos::simple_store::Commit {
     proto
 }
 
-fn commit_from_proto(mut proto: crate::protos::simple_store::Commit) -> Commit {
+pub(crate) fn commit_from_proto(mut proto: crate::protos::simple_store::Commit) -> Commit {
     // Note how .take() sets the secure_sig field to None before we encode the data.
     // Needs to be done first since proto is partially moved a bunch below
     let secure_sig = proto.secure_sig.take().map(|sig| SecureSig {
@@ -74,6 +74,22 @@ impl SimpleOpHeadsStore {
         Self { dir: op_heads_dir }
     }
 
+    /// Directory containing one file per operation head.
+    pub fn heads_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Moves the file of the given operation head to a `quarantine`
+    /// directory next to the heads directory, so the operation is no longer
+    /// considered a head. Returns the new path of the file.
+    pub fn quarantine_op_head(&self, id: &OperationId) -> Result<PathBuf, PathError> {
+        let quarantine_dir = self.dir.with_file_name("quarantine");
+        fs::create_dir_all(&quarantine_dir).context(&quarantine_dir)?;
+        let new_path = quarantine_dir.join(id.hex());
+        fs::rename(self.dir.join(id.hex()), &new_path).context(&new_path)?;
+        Ok(new_path)
+    }
+
     fn add_op_head(&self, id: &OperationId) -> io::Result<()> {
         std::fs::write(self.dir.join(id.hex()), "")
     }
@@ -125,6 +125,56 @@ impl SimpleOpStore {
         Ok(())
     }
 
+    /// Checks whether the stored view hashes to its id.
+    ///
+    /// Returns `None` if the view was written in a legacy format. Legacy
+    /// records are migrated on read, so their ids can't be verified.
+    pub fn verify_view_id(&self, id: &ViewId) -> OpStoreResult<Option<bool>> {
+        if *id == self.root_view_id {
+            return Ok(Some(true));
+        }
+        let path = self.views_dir().join(id.hex());
+        let buf = fs::read(&path)
+            .context(&path)
+            .map_err(|err| io_to_read_error(err, id))?;
+        let proto = crate::protos::op_store::View::decode(&*buf)
+            .map_err(|err| to_read_error(err.into(), id))?;
+        let view = view_from_proto(proto.clone());
+        if view_to_proto(&view) != proto {
+            return Ok(None);
+        }
+        Ok(Some(ViewId::new(blake2b_hash(&view).to_vec()) == *id))
+    }
+
+    /// Checks whether the stored operation hashes to its id.
+    ///
+    /// Returns `None` if the operation was written in a legacy format. Legacy
+    /// records are migrated on read, so their ids can't be verified.
+    pub fn verify_operation_id(&self, id: &OperationId) -> OpStoreResult<Option<bool>> {
+        if *id == self.root_operation_id {
+            return Ok(Some(true));
+        }
+        let path = self.operations_dir().join(id.hex());
+        let buf = fs::read(&path)
+            .context(&path)
+            .map_err(|err| io_to_read_error(err, id))?;
+        let proto = crate::protos::op_store::Operation::decode(&*buf)
+            .map_err(|err| to_read_error(err.into(), id))?;
+        // Operations without parents predate the root operation, and the ones
+        // without commit predecessors predate that field.
+        if proto.parents.is_empty() || !proto.stores_commit_predecessors {
+            return Ok(None);
+        }
+        let operation =
+            operation_from_proto(proto.clone()).map_err(|err| to_read_error(err.into(), id))?;
+        if operation_to_proto(&operation) != proto {
+            return Ok(None);
+        }
+        Ok(Some(
+            OperationId::new(blake2b_hash(&operation).to_vec()) == *id,
+        ))
+    }
+
     fn views_dir(&self) -> PathBuf {
         self.path.join("views")
     }
@@ -389,7 +439,7 @@ fn io_to_write_error(err: PathError, object_type: &'static str) -> OpStoreError
 }
 
 #[derive(Debug, Error)]
-enum PostDecodeError {
+pub(crate) enum PostDecodeError {
     #[error("Invalid hash length (expected {expected} bytes, got {actual} bytes)")]
     InvalidHashLength { expected: usize, actual: usize },
 }
@@ -491,7 +541,7 @@ fn commit_predecessors_map_from_proto(
         .collect()
 }
 
-fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operation {
+pub(crate) fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operation {
     let (commit_predecessors, stores_commit_predecessors) = match &operation.commit_predecessors {
         Some(map) => (commit_predecessors_map_to_proto(map), true),
         None => (vec![], false),
@@ -509,7 +559,7 @@ fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operati
     proto
 }
 
-fn operation_from_proto(
+pub(crate) fn operation_from_proto(
     proto: crate::protos::op_store::Operation,
 ) -> Result<Operation, PostDecodeError> {
     let parents = proto
@@ -530,7 +580,7 @@ fn operation_from_proto(
     })
 }
 
-fn view_to_proto(view: &View) -> crate::protos::op_store::View {
+pub(crate) fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     let mut proto = crate::protos::op_store::View {
         ..Default::default()
     };
@@ -565,7 +615,7 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     proto
 }
 
-fn view_from_proto(proto: crate::protos::op_store::View) -> View {
+pub(crate) fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     // TODO: validate commit id length?
     let mut view = View::empty();
     // For compatibility with old repos before we had support for multiple working
@@ -931,6 +981,62 @@ mod tests {
         assert_eq!(read_operation, operation);
     }
 
+    #[test]
+    fn test_verify_ids() {
+        let temp_dir = new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data).unwrap();
+        let view_id = store.write_view(&create_view()).unwrap();
+        let op_id = store.write_operation(&create_operation()).unwrap();
+        assert_eq!(store.verify_view_id(&view_id).unwrap(), Some(true));
+        assert_eq!(store.verify_operation_id(&op_id).unwrap(), Some(true));
+
+        // Content stored under a different id
+        let other_view_id = ViewId::from_hex(&"ab".repeat(VIEW_ID_LENGTH));
+        fs::copy(
+            store.views_dir().join(view_id.hex()),
+            store.views_dir().join(other_view_id.hex()),
+        )
+        .unwrap();
+        assert_eq!(store.verify_view_id(&other_view_id).unwrap(), Some(false));
+        let other_op_id = OperationId::from_hex(&"ab".repeat(OPERATION_ID_LENGTH));
+        fs::copy(
+            store.operations_dir().join(op_id.hex()),
+            store.operations_dir().join(other_op_id.hex()),
+        )
+        .unwrap();
+        assert_eq!(
+            store.verify_operation_id(&other_op_id).unwrap(),
+            Some(false)
+        );
+
+        // Legacy records can't be verified
+        #[expect(deprecated)]
+        let legacy_view_proto = crate::protos::op_store::View {
+            git_head_legacy: CommitId::from_hex("fff111").to_bytes(),
+            ..view_to_proto(&create_view())
+        };
+        fs::write(
+            store.views_dir().join(other_view_id.hex()),
+            legacy_view_proto.encode_to_vec(),
+        )
+        .unwrap();
+        assert_eq!(store.verify_view_id(&other_view_id).unwrap(), None);
+        let legacy_op_proto = crate::protos::op_store::Operation {
+            stores_commit_predecessors: false,
+            commit_predecessors: vec![],
+            ..operation_to_proto(&create_operation())
+        };
+        fs::write(
+            store.operations_dir().join(other_op_id.hex()),
+            legacy_op_proto.encode_to_vec(),
+        )
+        .unwrap();
+        assert_eq!(store.verify_operation_id(&other_op_id).unwrap(), None);
+    }
+
     #[test]
     fn test_bookmark_views_legacy_roundtrip() {
         let new_remote_ref = |target: &RefTarget| RemoteRef {
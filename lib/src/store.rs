@@ -15,20 +15,25 @@
 #![allow(missing_docs)]
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::thread;
+use std::thread::ThreadId;
 use std::time::SystemTime;
 
 use clru::CLruCache;
 use futures::stream::BoxStream;
 use pollster::FutureExt as _;
 use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt as _;
 
 use crate::backend;
 use crate::backend::Backend;
+use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
@@ -36,14 +41,17 @@ use crate::backend::ConflictId;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
+use crate::backend::ObjectContent;
 use crate::backend::SigningFn;
 use crate::backend::SymlinkId;
 use crate::backend::TreeId;
 use crate::commit::Commit;
+use crate::hex_util;
 use crate::index::Index;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
 use crate::merged_tree::MergedTree;
+use crate::object_id::ObjectId;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::signing::Signer;
@@ -59,8 +67,11 @@ const TREE_CACHE_CAPACITY: usize = 1000;
 pub struct Store {
     backend: Box<dyn Backend>,
     signer: Signer,
+    verify_objects: bool,
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
+    /// Number of nested write batches per thread.
+    write_batch_depths: Mutex<HashMap<ThreadId, usize>>,
 }
 
 impl Debug for Store {
@@ -72,12 +83,16 @@ impl Debug for Store {
 }
 
 impl Store {
-    pub fn new(backend: Box<dyn Backend>, signer: Signer) -> Arc<Self> {
+    /// Creates a store. If `verify_objects` is true, file and tree objects are
+    /// checked against their ids when they are read from the backend.
+    pub fn new(backend: Box<dyn Backend>, signer: Signer, verify_objects: bool) -> Arc<Self> {
         Arc::new(Store {
             backend,
             signer,
+            verify_objects,
             commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
+            write_batch_depths: Mutex::new(HashMap::new()),
         })
     }
 
@@ -174,6 +189,36 @@ impl Store {
         Ok(Commit::new(self.clone(), commit_id, data))
     }
 
+    /// Starts buffering writes made by the current thread in the backend.
+    /// Batches can be nested. The buffered writes are persisted when the
+    /// outermost batch is flushed.
+    pub fn begin_write_batch(&self) {
+        let mut depths = self.write_batch_depths.lock().unwrap();
+        let depth = depths.entry(thread::current().id()).or_default();
+        if *depth == 0 {
+            self.backend.begin_write_batch();
+        }
+        *depth += 1;
+    }
+
+    /// Finishes the batch started by [`Store::begin_write_batch()`] on the
+    /// current thread.
+    pub fn flush_write_batch(&self) -> BackendResult<()> {
+        let mut depths = self.write_batch_depths.lock().unwrap();
+        let thread_id = thread::current().id();
+        let Some(depth) = depths.get_mut(&thread_id) else {
+            return Err(BackendError::Other(
+                "No write batch was started by this thread".into(),
+            ));
+        };
+        *depth -= 1;
+        if *depth == 0 {
+            depths.remove(&thread_id);
+            self.backend.flush_write_batch()?;
+        }
+        Ok(())
+    }
+
     pub fn get_tree(self: &Arc<Self>, dir: RepoPathBuf, id: &TreeId) -> BackendResult<Tree> {
         self.get_tree_async(dir, id).block_on()
     }
@@ -200,6 +245,9 @@ impl Store {
             }
         }
         let data = self.backend.read_tree(dir, id).await?;
+        if self.verify_objects {
+            self.verify_object(id, ObjectContent::Tree(&data))?;
+        }
         let data = Arc::new(data);
         let mut locked_cache = self.tree_cache.lock().unwrap();
         locked_cache.put(key, data.clone());
@@ -248,7 +296,39 @@ impl Store {
         path: &RepoPath,
         id: &FileId,
     ) -> BackendResult<Pin<Box<dyn AsyncRead + Send>>> {
-        self.backend.read_file(path, id).await
+        let mut reader = self.backend.read_file(path, id).await?;
+        if !self.verify_objects {
+            return Ok(reader);
+        }
+        // The content has to be read in full to be checked before it's
+        // returned to the caller.
+        let mut content = vec![];
+        reader
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        self.verify_object(id, ObjectContent::File(&content))?;
+        Ok(Box::pin(std::io::Cursor::new(content)))
+    }
+
+    /// Checks that the `content` read from the backend matches its `id`.
+    fn verify_object(&self, id: &dyn ObjectId, content: ObjectContent<'_>) -> BackendResult<()> {
+        let Some(actual_id) = self.backend.hash_object(content) else {
+            return Ok(());
+        };
+        if actual_id == id.as_bytes() {
+            return Ok(());
+        }
+        Err(BackendError::CorruptObject {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            actual_hash: hex_util::encode_hex(&actual_id),
+            hint: self.backend.quarantine_object(id),
+        })
     }
 
     pub async fn write_file(
@@ -41,6 +41,23 @@ pub enum StringPatternParseError {
     Regex(regex::Error),
 }
 
+/// Parses the given string as a glob pattern to match file paths.
+pub(crate) fn parse_file_glob(src: &str, icase: bool) -> Result<Glob, globset::Error> {
+    GlobBuilder::new(src)
+        .literal_separator(true)
+        .case_insensitive(icase)
+        .build()
+}
+
+/// Splits the `-i` suffix off the pattern `kind`. Returns the base kind and
+/// whether the pattern should match case-insensitively.
+pub fn split_case_insensitive_kind(kind: &str) -> (&str, bool) {
+    match kind.strip_suffix("-i") {
+        Some(base) => (base, true),
+        None => (kind, false),
+    }
+}
+
 /// A wrapper for [`Glob`] and its matcher with a more concise `Debug` impl.
 #[derive(Clone)]
 pub struct GlobPattern {
@@ -176,15 +193,15 @@ impl StringPattern {
 
     /// Parses the given string as a pattern of the specified `kind`.
     pub fn from_str_kind(src: &str, kind: &str) -> Result<Self, StringPatternParseError> {
-        match kind {
-            "exact" => Ok(StringPattern::exact(src)),
-            "exact-i" => Ok(StringPattern::exact_i(src)),
-            "substring" => Ok(StringPattern::substring(src)),
-            "substring-i" => Ok(StringPattern::substring_i(src)),
-            "glob" => StringPattern::glob(src),
-            "glob-i" => StringPattern::glob_i(src),
-            "regex" => StringPattern::regex(src),
-            "regex-i" => StringPattern::regex_i(src),
+        match split_case_insensitive_kind(kind) {
+            ("exact", false) => Ok(StringPattern::exact(src)),
+            ("exact", true) => Ok(StringPattern::exact_i(src)),
+            ("substring", false) => Ok(StringPattern::substring(src)),
+            ("substring", true) => Ok(StringPattern::substring_i(src)),
+            ("glob", false) => StringPattern::glob(src),
+            ("glob", true) => StringPattern::glob_i(src),
+            ("regex", false) => StringPattern::regex(src),
+            ("regex", true) => StringPattern::regex_i(src),
             _ => Err(StringPatternParseError::InvalidKind(kind.to_owned())),
         }
     }
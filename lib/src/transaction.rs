@@ -19,6 +19,7 @@ use std::sync::Arc;
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::backend::BackendError;
 use crate::backend::Timestamp;
 use crate::dag_walk;
 use crate::index::IndexWriteError;
@@ -27,6 +28,7 @@ use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
 use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
 use crate::op_store::TimestampRange;
 use crate::operation::Operation;
@@ -42,9 +44,11 @@ use crate::view::View;
 #[derive(Debug, Error)]
 #[error("Failed to commit new operation")]
 pub enum TransactionCommitError {
+    Backend(#[from] BackendError),
     IndexWrite(#[from] IndexWriteError),
     OpHeadsStore(#[from] OpHeadsStoreError),
     OpStore(#[from] OpStoreError),
+    RepoLoader(#[from] Box<RepoLoaderError>),
 }
 
 /// An in-memory representation of a repo and any changes being made to it.
@@ -63,6 +67,7 @@ pub struct Transaction {
     parent_ops: Vec<Operation>,
     op_metadata: OperationMetadata,
     end_time: Option<Timestamp>,
+    concurrency_retries: u32,
 }
 
 impl Transaction {
@@ -70,11 +75,13 @@ impl Transaction {
         let parent_ops = vec![mut_repo.base_repo().operation().clone()];
         let op_metadata = create_op_metadata(user_settings, "".to_string(), false);
         let end_time = user_settings.operation_timestamp();
+        let concurrency_retries = user_settings.operation_concurrency_retries();
         Transaction {
             mut_repo,
             parent_ops,
             op_metadata,
             end_time,
+            concurrency_retries,
         }
     }
 
@@ -116,11 +123,25 @@ impl Transaction {
     }
 
     /// Writes the transaction to the operation store and publishes it.
+    ///
+    /// If other operations have been published concurrently, the changes made
+    /// in this transaction are rebased onto the new operation heads, up to
+    /// `operation.concurrency-retries` times. Once the retries are exhausted,
+    /// the operation is published as a divergent head, which will be merged
+    /// when the repo is loaded next time.
     pub fn commit(
         self,
         description: impl Into<String>,
     ) -> Result<Arc<ReadonlyRepo>, TransactionCommitError> {
-        self.write(description)?.publish()
+        let concurrency_retries = self.concurrency_retries;
+        let mut unpublished = self.write(description)?;
+        for _ in 0..concurrency_retries {
+            match unpublished.try_publish()? {
+                None => return Ok(unpublished.repo),
+                Some(op_head_ids) => unpublished = unpublished.rebase_onto(&op_head_ids)?,
+            }
+        }
+        unpublished.publish()
     }
 
     /// Writes the transaction to the operation store, but does not publish it.
@@ -221,6 +242,63 @@ impl UnpublishedOperation {
         Ok(self.repo)
     }
 
+    /// Publishes the operation only if no other operation has been published
+    /// since its parents. Otherwise, returns the current operation heads.
+    fn try_publish(&self) -> Result<Option<Vec<OperationId>>, TransactionCommitError> {
+        let _lock = self.op_heads_store.lock()?;
+        let parent_ids = self.operation().parent_ids();
+        let op_head_ids = self.op_heads_store.get_op_heads()?;
+        if op_head_ids.iter().all(|id| parent_ids.contains(id)) {
+            self.op_heads_store
+                .update_op_heads(parent_ids, self.operation().id())?;
+            Ok(None)
+        } else {
+            Ok(Some(op_head_ids))
+        }
+    }
+
+    /// Rewrites the operation so that its changes are applied on top of the
+    /// given operation heads. The original operation is left unpublished.
+    fn rebase_onto(self, op_head_ids: &[OperationId]) -> Result<Self, TransactionCommitError> {
+        let operation = self.operation();
+        let repo_loader = self.repo.loader();
+        let op_store = repo_loader.op_store();
+        let old_parents: Vec<_> = operation.parents().try_collect()?;
+        let old_base_op = repo_loader
+            .merge_operations(old_parents, None)
+            .map_err(Box::new)?;
+        let old_base_repo = repo_loader.load_at(&old_base_op).map_err(Box::new)?;
+        let new_parents: Vec<_> = op_head_ids
+            .iter()
+            .map(|id| -> Result<_, OpStoreError> {
+                let data = op_store.read_operation(id)?;
+                Ok(Operation::new(op_store.clone(), id.clone(), data))
+            })
+            .try_collect()?;
+        let (first_parent, other_parents) = new_parents
+            .split_first()
+            .expect("op heads should not be empty");
+        let mut tx = repo_loader
+            .load_at(first_parent)
+            .map_err(Box::new)?
+            .start_transaction();
+        for other_op in other_parents {
+            tx.merge_operation(other_op.clone()).map_err(Box::new)?;
+            tx.repo_mut().rebase_descendants()?;
+        }
+        tx.repo_mut().merge(&old_base_repo, &self.repo)?;
+        tx.repo_mut().rebase_descendants()?;
+        let store_operation = operation.store_operation();
+        for (id, predecessors) in store_operation.commit_predecessors.iter().flatten() {
+            tx.repo_mut()
+                .set_predecessors(id.clone(), predecessors.clone());
+        }
+        tx.op_metadata = store_operation.metadata.clone();
+        tx.end_time = Some(store_operation.metadata.time.end);
+        let description = store_operation.metadata.description.clone();
+        tx.write(description)
+    }
+
     pub fn leave_unpublished(self) -> Arc<ReadonlyRepo> {
         self.repo
     }
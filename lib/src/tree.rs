@@ -29,10 +29,12 @@ use crate::backend;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::ConflictId;
+use crate::backend::CopyId;
 use crate::backend::TreeEntriesNonRecursiveIterator;
 use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::files;
+use crate::gitattributes::MergeDriver;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
 use crate::merge::MergedTreeVal;
@@ -254,7 +256,34 @@ impl Iterator for TreeEntriesIterator<'_> {
     }
 }
 
-/// Resolves file-level conflict by merging content hunks.
+/// Reason why a file-level conflict couldn't be resolved automatically.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileConflictFailure {
+    /// Some of the terms are absent or aren't regular files.
+    NonFileTerms,
+    /// The sides don't agree on whether the file should be executable.
+    ExecutableConflict,
+    /// The sides don't agree on the copy ID of the file.
+    CopyIdConflict,
+    /// The file contents have conflicting hunks.
+    ContentConflict,
+    /// The file contents differ, and the `merge` attribute disables merging
+    /// them.
+    MergeDisabled,
+}
+
+enum FileMergeResult {
+    Resolved(TreeValue),
+    Merged {
+        content: Vec<u8>,
+        executable: bool,
+        copy_id: CopyId,
+    },
+    Failed(FileConflictFailure),
+}
+
+/// Resolves file-level conflict by merging content hunks with the given
+/// `driver`.
 ///
 /// The input `conflict` is supposed to be simplified. It shouldn't contain
 /// non-file values that cancel each other.
@@ -262,7 +291,49 @@ pub async fn try_resolve_file_conflict(
     store: &Store,
     filename: &RepoPath,
     conflict: &MergedTreeVal<'_>,
+    driver: MergeDriver,
 ) -> BackendResult<Option<TreeValue>> {
+    match merge_file_conflict(store, filename, conflict, driver).await? {
+        FileMergeResult::Resolved(value) => Ok(Some(value)),
+        FileMergeResult::Merged {
+            content,
+            executable,
+            copy_id,
+        } => {
+            let id = store.write_file(filename, &mut content.as_slice()).await?;
+            Ok(Some(TreeValue::File {
+                id,
+                executable,
+                copy_id,
+            }))
+        }
+        FileMergeResult::Failed(_) => Ok(None),
+    }
+}
+
+/// Explains why `try_resolve_file_conflict()` can't resolve the `conflict`.
+/// Returns `None` if the conflict can be resolved.
+///
+/// Unlike `try_resolve_file_conflict()`, this doesn't write the merged file
+/// contents to the store.
+pub async fn explain_file_conflict(
+    store: &Store,
+    filename: &RepoPath,
+    conflict: &MergedTreeVal<'_>,
+    driver: MergeDriver,
+) -> BackendResult<Option<FileConflictFailure>> {
+    match merge_file_conflict(store, filename, conflict, driver).await? {
+        FileMergeResult::Resolved(_) | FileMergeResult::Merged { .. } => Ok(None),
+        FileMergeResult::Failed(failure) => Ok(Some(failure)),
+    }
+}
+
+async fn merge_file_conflict(
+    store: &Store,
+    filename: &RepoPath,
+    conflict: &MergedTreeVal<'_>,
+    driver: MergeDriver,
+) -> BackendResult<FileMergeResult> {
     // If there are any non-file or any missing parts in the conflict, we can't
     // merge it. We check early so we don't waste time reading file contents if
     // we can't merge them anyway. At the same time we determine whether the
@@ -275,7 +346,7 @@ pub async fn try_resolve_file_conflict(
         }) => Ok(id),
         _ => Err(()),
     }) else {
-        return Ok(None);
+        return Ok(FileMergeResult::Failed(FileConflictFailure::NonFileTerms));
     };
     let Ok(executable_conflict) = conflict.try_map(|term| match term {
         Some(TreeValue::File {
@@ -285,7 +356,7 @@ pub async fn try_resolve_file_conflict(
         }) => Ok(executable),
         _ => Err(()),
     }) else {
-        return Ok(None);
+        return Ok(FileMergeResult::Failed(FileConflictFailure::NonFileTerms));
     };
     let Ok(copy_id_conflict) = conflict.try_map(|term| match term {
         Some(TreeValue::File {
@@ -295,20 +366,22 @@ pub async fn try_resolve_file_conflict(
         }) => Ok(copy_id),
         _ => Err(()),
     }) else {
-        return Ok(None);
+        return Ok(FileMergeResult::Failed(FileConflictFailure::NonFileTerms));
     };
     let Some(&&executable) = executable_conflict.resolve_trivial() else {
         // We're unable to determine whether the result should be executable
-        return Ok(None);
+        return Ok(FileMergeResult::Failed(
+            FileConflictFailure::ExecutableConflict,
+        ));
     };
     let Some(&copy_id) = copy_id_conflict.resolve_trivial() else {
         // We're unable to determine the file's copy ID
-        return Ok(None);
+        return Ok(FileMergeResult::Failed(FileConflictFailure::CopyIdConflict));
     };
     if let Some(&resolved_file_id) = file_id_conflict.resolve_trivial() {
         // Don't bother reading the file contents if the conflict can be trivially
         // resolved.
-        return Ok(Some(TreeValue::File {
+        return Ok(FileMergeResult::Resolved(TreeValue::File {
             id: resolved_file_id.clone(),
             executable,
             copy_id: copy_id.clone(),
@@ -322,6 +395,9 @@ pub async fn try_resolve_file_conflict(
     // 2. The simplified conflict can sometimes be resolved when the unsimplfied one
     //    cannot
     let file_id_conflict = file_id_conflict.simplify();
+    if driver == MergeDriver::Binary && !file_id_conflict.is_resolved() {
+        return Ok(FileMergeResult::Failed(FileConflictFailure::MergeDisabled));
+    }
 
     let contents = file_id_conflict
         .try_map_async(async |file_id| {
@@ -338,16 +414,19 @@ pub async fn try_resolve_file_conflict(
             BackendResult::Ok(content)
         })
         .await?;
-    if let Some(merged_content) = files::try_merge(&contents) {
-        let id = store
-            .write_file(filename, &mut merged_content.as_slice())
-            .await?;
-        Ok(Some(TreeValue::File {
-            id,
+    let merged_content = match driver {
+        MergeDriver::Text | MergeDriver::Binary => files::try_merge(&contents),
+        MergeDriver::Union => Some(files::merge_union(&contents)),
+    };
+    if let Some(merged_content) = merged_content {
+        Ok(FileMergeResult::Merged {
+            content: merged_content.into(),
             executable,
             copy_id: copy_id.clone(),
-        }))
+        })
     } else {
-        Ok(None)
+        Ok(FileMergeResult::Failed(
+            FileConflictFailure::ContentConflict,
+        ))
     }
 }
@@ -17,6 +17,7 @@
 
 use std::any::Any;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::ffi::OsString;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -28,7 +29,9 @@ use tracing::instrument;
 use crate::backend::BackendError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
+use crate::conflicts::ConflictLabels;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::MalformedConflictHunk;
 use crate::dag_walk;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::gitignore::GitIgnoreError;
@@ -225,6 +228,14 @@ pub struct SnapshotOptions<'a> {
     pub max_new_file_size: u64,
     /// Expected conflict marker style for checking for changed files.
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// How to handle new directories (directories without tracked files) with
+    /// many files. Such directories are usually build outputs that should have
+    /// been ignored.
+    pub new_directory_policy: NewDirectoryPolicy,
+    /// Whether to report new paths matching the ignore patterns in
+    /// [`SnapshotStats::untracked_paths`]. Ignored directories without tracked
+    /// files are reported as a whole.
+    pub report_ignored_paths: bool,
 }
 
 impl SnapshotOptions<'_> {
@@ -237,6 +248,37 @@ impl SnapshotOptions<'_> {
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
             conflict_marker_style: ConflictMarkerStyle::default(),
+            new_directory_policy: NewDirectoryPolicy::Allow,
+            report_ignored_paths: false,
+        }
+    }
+}
+
+/// How to handle new directories with many files while snapshotting.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NewDirectoryPolicy {
+    /// Snapshot new directories regardless of the number of files.
+    #[default]
+    Allow,
+    /// Snapshot new directories, but report the ones with more than
+    /// `max_files` files in [`SnapshotStats::large_new_directories`].
+    Warn {
+        /// Maximum number of files in a new directory.
+        max_files: usize,
+    },
+    /// Leave new directories with more than `max_files` files untracked.
+    Exclude {
+        /// Maximum number of files in a new directory.
+        max_files: usize,
+    },
+}
+
+impl NewDirectoryPolicy {
+    /// Maximum number of files in a new directory, if limited.
+    pub fn max_files(&self) -> Option<usize> {
+        match self {
+            Self::Allow => None,
+            Self::Warn { max_files } | Self::Exclude { max_files } => Some(*max_files),
         }
     }
 }
@@ -249,6 +291,12 @@ pub type SnapshotProgress<'a> = dyn Fn(&RepoPath) + 'a + Sync;
 pub struct SnapshotStats {
     /// List of new (previously untracked) files which are still untracked.
     pub untracked_paths: BTreeMap<RepoPathBuf, UntrackedReason>,
+    /// New directories which were snapshotted even though they had more files
+    /// than allowed by [`NewDirectoryPolicy::Warn`].
+    pub large_new_directories: BTreeSet<RepoPathBuf>,
+    /// Conflicted files containing hunks which looked like conflicts but
+    /// couldn't be parsed, so they were snapshotted as regular text.
+    pub malformed_conflicts: BTreeMap<RepoPathBuf, Vec<MalformedConflictHunk>>,
 }
 
 /// Reason why the new path isn't tracked.
@@ -263,13 +311,29 @@ pub enum UntrackedReason {
     },
     /// File does not match the fileset specified in snapshot.auto-track.
     FileNotAutoTracked,
+    /// New directory had more files than allowed by
+    /// [`NewDirectoryPolicy::Exclude`]. The path is the directory.
+    NewDirectoryTooLarge {
+        /// Maximum allowed number of files.
+        max_files: usize,
+    },
+    /// Path matched the ignore patterns. Only reported if
+    /// [`SnapshotOptions::report_ignored_paths`] is set.
+    Ignored,
 }
 
+/// A callback for getting progress updates while checking out files.
+pub type CheckoutProgress = dyn Fn(&RepoPath) + Send + Sync;
+
 /// Options used when checking out a tree in the working copy.
 #[derive(Clone)]
 pub struct CheckoutOptions {
     /// Conflict marker style to use when materializing files
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Labels to annotate the conflict markers with
+    pub conflict_labels: ConflictLabels,
+    /// Called with each path which is written or removed
+    pub progress: Option<Arc<CheckoutProgress>>,
 }
 
 impl CheckoutOptions {
@@ -277,6 +341,8 @@ impl CheckoutOptions {
     pub fn empty_for_test() -> Self {
         CheckoutOptions {
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_labels: ConflictLabels::unlabeled(),
+            progress: None,
         }
     }
 }
@@ -28,8 +28,10 @@ mod test_local_working_copy_sparse;
 mod test_merge_trees;
 mod test_merged_tree;
 mod test_mut_repo;
+mod test_op_bundle;
 mod test_operations;
 mod test_refs;
+mod test_repo_snapshot;
 mod test_revset;
 mod test_revset_optimized;
 mod test_rewrite;
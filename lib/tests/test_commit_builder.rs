@@ -368,6 +368,86 @@ fn test_rewrite_resets_author_timestamp(backend: TestRepoBackend) {
     assert_eq!(rewritten_commit_2.committer().timestamp, new_timestamp_2);
 }
 
+#[test_case(TestRepoBackend::Simple ; "simple backend")]
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn test_rewrite_committer_timestamp_policy(backend: TestRepoBackend) {
+    let test_repo = TestRepo::init_with_backend(backend);
+    let test_env = &test_repo.env;
+    let settings_with_policy = |policy: &str| {
+        let mut config = config_with_commit_timestamp("2002-03-04T05:06:07+08:00");
+        let mut layer = ConfigLayer::empty(ConfigSource::User);
+        layer
+            .set_value("rewrite.committer-timestamp", policy)
+            .unwrap();
+        config.add_layer(layer);
+        UserSettings::from_config(config).unwrap()
+    };
+    let to_timestamp =
+        |text: &str| Timestamp::from_datetime(chrono::DateTime::parse_from_rfc3339(text).unwrap());
+
+    let initial_timestamp = "2001-02-03T04:05:06+07:00";
+    let settings =
+        UserSettings::from_config(config_with_commit_timestamp(initial_timestamp)).unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let mut tx = repo.start_transaction();
+    let initial_commit = tx
+        .repo_mut()
+        .new_commit(
+            vec![repo.store().root_commit_id().clone()],
+            repo.store().empty_merged_tree_id(),
+        )
+        .set_description("initial")
+        .write()
+        .unwrap();
+    tx.commit("test").unwrap();
+
+    let rewrite_with_policy = |policy: &str| {
+        let settings = settings_with_policy(policy);
+        let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+        let mut tx = repo.start_transaction();
+        let rewritten_commit = tx
+            .repo_mut()
+            .rewrite_commit(&initial_commit)
+            .set_description(format!("rewritten with {policy}"))
+            .write()
+            .unwrap();
+        tx.repo_mut().rebase_descendants().unwrap();
+        tx.commit("test").unwrap();
+        rewritten_commit
+    };
+
+    // The current time is debug.commit-timestamp
+    let commit = rewrite_with_policy("now");
+    assert_eq!(commit.author().timestamp, to_timestamp(initial_timestamp));
+    assert_eq!(
+        commit.committer().timestamp,
+        to_timestamp("2002-03-04T05:06:07+08:00")
+    );
+
+    let commit = rewrite_with_policy("preserve");
+    assert_eq!(commit.author().timestamp, to_timestamp(initial_timestamp));
+    assert_eq!(
+        commit.committer().timestamp,
+        to_timestamp(initial_timestamp)
+    );
+
+    let commit = rewrite_with_policy("2003-04-05T06:07:08+09:00");
+    assert_eq!(commit.author().timestamp, to_timestamp(initial_timestamp));
+    assert_eq!(
+        commit.committer().timestamp,
+        to_timestamp("2003-04-05T06:07:08+09:00")
+    );
+
+    // Invalid policy
+    let mut config = testutils::base_user_config();
+    let mut layer = ConfigLayer::empty(ConfigSource::User);
+    layer
+        .set_value("rewrite.committer-timestamp", "later")
+        .unwrap();
+    config.add_layer(layer);
+    assert!(UserSettings::from_config(config).is_err());
+}
+
 #[test_case(TestRepoBackend::Simple ; "simple backend")]
 #[test_case(TestRepoBackend::Git ; "git backend")]
 fn test_rewrite_to_identical_commit(backend: TestRepoBackend) {
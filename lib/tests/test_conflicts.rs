@@ -18,9 +18,14 @@ use jj_lib::backend::FileId;
 use jj_lib::conflicts::choose_materialized_conflict_marker_len;
 use jj_lib::conflicts::extract_as_single_hunk;
 use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_merge_result_to_bytes_with_labels;
 use jj_lib::conflicts::parse_conflict;
 use jj_lib::conflicts::update_from_content;
+use jj_lib::conflicts::update_from_content_with_diagnostics;
+use jj_lib::conflicts::ConflictLabels;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::MalformedConflictHunk;
+use jj_lib::conflicts::MalformedConflictReason;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
 use jj_lib::merge::Merge;
 use jj_lib::repo::Repo as _;
@@ -536,6 +541,60 @@ fn test_materialize_parse_roundtrip() {
     "#);
 }
 
+#[test]
+fn test_materialize_conflict_with_labels() {
+    let conflict = Merge::from_vec(vec!["left\n", "base\n", "right\n"]);
+    let labels = ConflictLabels::from_merge(Merge::from_vec(vec![
+        "left label".to_owned(),
+        "base label".to_owned(),
+        "right label".to_owned(),
+    ]));
+    let materialize = |style, labels: &ConflictLabels| {
+        let output = materialize_merge_result_to_bytes_with_labels(
+            &conflict,
+            style,
+            MIN_CONFLICT_MARKER_LEN,
+            labels,
+        );
+        String::from_utf8(output.into()).unwrap()
+    };
+    insta::assert_snapshot!(materialize(ConflictMarkerStyle::Diff, &labels), @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base (base label) to side #1 (left label)
+    -base
+    +left
+    +++++++ Contents of side #2 (right label)
+    right
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+    insta::assert_snapshot!(materialize(ConflictMarkerStyle::Snapshot, &labels), @r"
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1 (left label)
+    left
+    ------- Contents of base (base label)
+    base
+    +++++++ Contents of side #2 (right label)
+    right
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+    insta::assert_snapshot!(materialize(ConflictMarkerStyle::Git, &labels), @r"
+    <<<<<<< Side #1 (Conflict 1 of 1) (left label)
+    left
+    ||||||| Base (base label)
+    base
+    =======
+    right
+    >>>>>>> Side #2 (Conflict 1 of 1 ends) (right label)
+    ");
+
+    // Labels of different arity are ignored
+    let labels = ConflictLabels::from_merge(Merge::resolved("label".to_owned()));
+    assert_eq!(
+        materialize(ConflictMarkerStyle::Diff, &labels),
+        materialize(ConflictMarkerStyle::Diff, &ConflictLabels::unlabeled())
+    );
+}
+
 #[test]
 fn test_materialize_parse_roundtrip_different_markers() {
     let test_repo = TestRepo::init();
@@ -659,6 +718,32 @@ fn test_materialize_conflict_no_newlines_at_eof() {
         ],
     )
     "#);
+
+    let materialized =
+        &materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Git);
+    insta::assert_snapshot!(materialized,
+        @r"
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    ||||||| Base (no terminating newline)
+    base
+    =======
+    right
+    >>>>>>> Side #2 (Conflict 1 of 1 ends) (no terminating newline)
+    "
+    );
+    assert_eq!(
+        update_from_content(
+            &conflict,
+            store,
+            path,
+            materialized.as_bytes(),
+            ConflictMarkerStyle::Git,
+            MIN_CONFLICT_MARKER_LEN,
+        )
+        .block_on()
+        .unwrap(),
+        conflict
+    );
 }
 
 #[test]
@@ -2131,11 +2216,11 @@ fn test_update_conflict_from_content_no_eol() {
     <<<<<<< Side #1 (Conflict 2 of 2)
     base
     left
-    ||||||| Base
+    ||||||| Base (no terminating newline)
     base
     =======
     right
-    >>>>>>> Side #2 (Conflict 2 of 2 ends)
+    >>>>>>> Side #2 (Conflict 2 of 2 ends) (no terminating newline)
     "
     );
     // Parse with "diff" markers to ensure the file is actually parsed
@@ -2435,6 +2520,107 @@ fn test_update_from_content_malformed_conflict() {
     assert_eq!(second_snapshot, new_conflict);
 }
 
+#[test]
+fn test_update_from_content_lenient_markers() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = repo_path("file");
+    let base_file_id = testutils::write_file(store, path, "line 1\nline 2\nline 3\nline 4\n");
+    let left_file_id = testutils::write_file(store, path, "line 1\nleft 2\nline 3\nleft 4\n");
+    let right_file_id = testutils::write_file(store, path, "line 1\nright 2\nline 3\nright 4\n");
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_file_id.clone())],
+        vec![Some(left_file_id.clone()), Some(right_file_id.clone())],
+    );
+    let parse = |content: &str| {
+        // The conflict was materialized with longer markers than in `content`
+        update_from_content_with_diagnostics(
+            &conflict,
+            store,
+            path,
+            content.as_bytes(),
+            ConflictMarkerStyle::Diff,
+            MIN_CONFLICT_MARKER_LEN + 4,
+        )
+        .block_on()
+        .unwrap()
+    };
+
+    // Markers of each hunk may have different lengths and styles
+    let content = indoc! {"
+        line 1
+        <<<<<<< Conflict 1 of 2
+        %%%%%%% Changes from base to side #1
+        -line 2
+        +left 2
+        +++++++ Contents of side #2
+        right 2
+        >>>>>>> Conflict 1 of 2 ends
+        line 3
+        <<<<<<<<< Side #1 (Conflict 2 of 2)
+        left 4
+        ||||||||| Base
+        line 4
+        =========
+        right 4
+        >>>>>>>>> Side #2 (Conflict 2 of 2 ends)
+    "};
+    assert_eq!(parse(content), (conflict.clone(), vec![]));
+
+    // Short markers without labels aren't parsed
+    let content = indoc! {"
+        line 1
+        <<<<<<<
+        +++++++
+        left 2
+        -------
+        line 2
+        +++++++
+        right 2
+        >>>>>>>
+        line 3
+        line 4
+    "};
+    let resolved_file_id = testutils::write_file(store, path, content);
+    assert_eq!(parse(content), (Merge::normal(resolved_file_id), vec![]));
+
+    // Malformed hunks are reported
+    let content = indoc! {"
+        line 1
+        <<<<<<< Conflict 1 of 2
+        %%%%%%% Changes from base to side #1
+        -line 2
+        +left 2
+        right 2
+        >>>>>>> Conflict 1 of 2 ends
+        line 3
+        <<<<<<< Conflict 2 of 2
+        +++++++ Contents of side #1
+        left 4
+        ------- Contents of base
+        line 4
+        +++++++ Contents of side #2
+        right 4
+    "};
+    let (new_conflict, malformed_hunks) = parse(content);
+    let resolved_file_id = testutils::write_file(store, path, content);
+    assert_eq!(new_conflict, Merge::normal(resolved_file_id));
+    assert_eq!(
+        malformed_hunks,
+        vec![
+            MalformedConflictHunk {
+                line: 2,
+                reason: MalformedConflictReason::InvalidMarkers,
+            },
+            MalformedConflictHunk {
+                line: 9,
+                reason: MalformedConflictReason::Unterminated,
+            },
+        ]
+    );
+}
+
 fn materialize_conflict_string(
     store: &Store,
     path: &RepoPath,
@@ -20,6 +20,7 @@ use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo::StoreFactories;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::settings::UserSettings;
 use jj_lib::working_copy::CheckoutOptions;
@@ -505,3 +506,58 @@ fn test_eol_conversion_checkout(
     assert!(std::fs::exists(&file_disk_path).unwrap());
     std::fs::read(&file_disk_path).unwrap()
 }
+
+#[test]
+fn test_eol_attributes() {
+    let user_settings =
+        base_user_settings_with_extra_configs("working-copy.eol-conversion = \"none\"\n");
+    let mut test_workspace =
+        TestWorkspace::init_with_backend_and_settings(TestRepoBackend::Git, &user_settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let text_path = repo_path("dir/file.txt");
+    let binary_path = repo_path("dir/file.dat");
+    let attributes = [
+        (repo_path(".gitattributes"), "* eol=crlf\n"),
+        (repo_path("dir/.gitattributes"), "*.dat -text\n"),
+    ];
+    let tree = testutils::create_tree(
+        &test_workspace.repo,
+        &[
+            attributes[0],
+            attributes[1],
+            (text_path, "a\nb\n"),
+            (binary_path, "a\nb\n"),
+        ],
+    );
+    let commit = commit_with_tree(test_workspace.repo.store(), tree.id());
+    test_workspace
+        .workspace
+        .check_out(
+            test_workspace.repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+
+    // The attributes of the checked-out tree select the EOL
+    let read = |path: &RepoPath| std::fs::read(path.to_fs_path(&workspace_root).unwrap()).unwrap();
+    assert_eq!(read(text_path), b"a\r\nb\r\n");
+    assert_eq!(read(binary_path), b"a\nb\n");
+    assert_eq!(test_workspace.snapshot().unwrap().id(), tree.id());
+
+    // Text files are stored with LF, whereas files with unset "text" attribute
+    // are stored as is
+    testutils::write_working_copy_file(&workspace_root, text_path, "a\r\nc\r\n");
+    testutils::write_working_copy_file(&workspace_root, binary_path, "a\r\nc\r\n");
+    let expected_tree = testutils::create_tree(
+        &test_workspace.repo,
+        &[
+            attributes[0],
+            attributes[1],
+            (text_path, "a\nc\n"),
+            (binary_path, "a\r\nc\r\n"),
+        ],
+    );
+    assert_eq!(test_workspace.snapshot().unwrap().id(), expected_tree.id());
+}
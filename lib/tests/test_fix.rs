@@ -14,17 +14,21 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::sync::Mutex;
 
+use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
-use jj_lib::backend::FileId;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::fix::fix_files;
 use jj_lib::fix::FileFixer;
 use jj_lib::fix::FileToFix;
 use jj_lib::fix::FixError;
+use jj_lib::fix::FixedFile;
 use jj_lib::fix::ParallelFileFixer;
 use jj_lib::matchers::EverythingMatcher;
+use jj_lib::matchers::FilesMatcher;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::store::Store;
 use jj_lib::transaction::Transaction;
 use pollster::FutureExt as _;
@@ -51,11 +55,11 @@ impl FileFixer for TestFileFixer {
         &mut self,
         store: &Store,
         files_to_fix: &'a HashSet<FileToFix>,
-    ) -> Result<HashMap<&'a FileToFix, FileId>, FixError> {
+    ) -> Result<HashMap<&'a FileToFix, FixedFile>, FixError> {
         let mut changed_files = HashMap::new();
         for file_to_fix in files_to_fix {
-            if let Some(new_file_id) = fix_file(store, file_to_fix)? {
-                changed_files.insert(file_to_fix, new_file_id);
+            if let Some(fixed_file) = fix_file(store, file_to_fix)? {
+                changed_files.insert(file_to_fix, fixed_file);
             }
         }
         Ok(changed_files)
@@ -71,18 +75,43 @@ fn make_fix_content_error(message: &str) -> FixError {
 }
 
 // Reads the file from store. If the file starts with "fixme", its contents are
-// changed to uppercase and the new file id is returned. If the file starts with
-// "error", an error is raised. Otherwise returns None.
-fn fix_file(store: &Store, file_to_fix: &FileToFix) -> Result<Option<FileId>, FixError> {
+// changed to uppercase and the new file is returned. If the file starts with
+// "chmod", "rename", or "delete", the rest of the content is kept, and the file
+// is made executable, moved to "<path>.renamed", or deleted respectively. If the
+// file starts with "error", an error is raised. Otherwise returns None.
+fn fix_file(store: &Store, file_to_fix: &FileToFix) -> Result<Option<FixedFile>, FixError> {
     let old_content = read_file(store, &file_to_fix.repo_path, &file_to_fix.file_id);
+    let write_file = |content: &[u8]| {
+        store
+            .write_file(&file_to_fix.repo_path, &mut &*content)
+            .block_on()
+            .unwrap()
+    };
 
     if let Some(rest) = old_content.strip_prefix(b"fixme:") {
-        let new_content = rest.to_ascii_uppercase();
-        let new_file_id = store
-            .write_file(&file_to_fix.repo_path, &mut new_content.as_slice())
-            .block_on()
-            .unwrap();
-        Ok(Some(new_file_id))
+        Ok(Some(FixedFile::Modified {
+            file_id: write_file(&rest.to_ascii_uppercase()),
+            executable: file_to_fix.executable,
+            new_path: None,
+        }))
+    } else if let Some(rest) = old_content.strip_prefix(b"chmod:") {
+        Ok(Some(FixedFile::Modified {
+            file_id: write_file(rest),
+            executable: true,
+            new_path: None,
+        }))
+    } else if let Some(rest) = old_content.strip_prefix(b"rename:") {
+        let new_path = format!(
+            "{}.renamed",
+            file_to_fix.repo_path.as_internal_file_string()
+        );
+        Ok(Some(FixedFile::Modified {
+            file_id: write_file(rest),
+            executable: file_to_fix.executable,
+            new_path: Some(RepoPathBuf::from_internal_string(new_path).unwrap()),
+        }))
+    } else if old_content.starts_with(b"delete:") {
+        Ok(Some(FixedFile::Deleted))
     } else if let Some(rest) = old_content.strip_prefix(b"error:") {
         Err(make_fix_content_error(std::str::from_utf8(rest).unwrap()))
     } else {
@@ -585,3 +614,257 @@ fn test_fix_multiple_revisions() {
         .unwrap();
     assert_eq!(*new_commit_a.tree_id(), expected_tree_a.id());
 }
+
+#[test]
+fn test_fix_executable_bit() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let path1 = repo_path("file1");
+    let tree1 = create_tree(repo, &[(path1, "chmod:content")]);
+    let commit_a = create_commit(
+        &mut tx,
+        vec![repo.store().root_commit_id().clone()],
+        tree1.id(),
+    );
+
+    let summary = fix_files(
+        vec![commit_a.clone()],
+        &EverythingMatcher,
+        false,
+        tx.repo_mut(),
+        &mut TestFileFixer::new(),
+    )
+    .block_on()
+    .unwrap();
+
+    let expected_tree_a = create_tree_with(repo, |builder| {
+        builder.file(path1, "content").executable(true);
+    });
+    assert_eq!(summary.num_fixed_commits, 1);
+    let new_commit_a = repo
+        .store()
+        .get_commit(summary.rewrites.get(&commit_a).unwrap())
+        .unwrap();
+    assert_eq!(*new_commit_a.tree_id(), expected_tree_a.id());
+}
+
+#[test]
+fn test_fix_rename_and_delete() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let path1 = repo_path("file1");
+    let path2 = repo_path("file2");
+    let path3 = repo_path("file3");
+    let tree1 = create_tree(
+        repo,
+        &[
+            (path1, "rename:content"),
+            (path2, "delete:"),
+            (path3, "content"),
+        ],
+    );
+    let commit_a = create_commit(
+        &mut tx,
+        vec![repo.store().root_commit_id().clone()],
+        tree1.id(),
+    );
+    // The descendant doesn't modify the files, but is still fixed.
+    let path4 = repo_path("file4");
+    let tree2 = create_tree(
+        repo,
+        &[
+            (path1, "rename:content"),
+            (path2, "delete:"),
+            (path3, "content"),
+            (path4, "content"),
+        ],
+    );
+    let commit_b = create_commit(&mut tx, vec![commit_a.clone()], tree2.id());
+
+    let summary = fix_files(
+        vec![commit_a.clone()],
+        &EverythingMatcher,
+        false,
+        tx.repo_mut(),
+        &mut TestFileFixer::new(),
+    )
+    .block_on()
+    .unwrap();
+
+    let renamed_path = repo_path("file1.renamed");
+    let expected_tree_a = create_tree(repo, &[(renamed_path, "content"), (path3, "content")]);
+    let expected_tree_b = create_tree(
+        repo,
+        &[
+            (renamed_path, "content"),
+            (path3, "content"),
+            (path4, "content"),
+        ],
+    );
+    assert_eq!(summary.num_fixed_commits, 2);
+    let new_commit_a = repo
+        .store()
+        .get_commit(summary.rewrites.get(&commit_a).unwrap())
+        .unwrap();
+    assert_eq!(*new_commit_a.tree_id(), expected_tree_a.id());
+    let new_commit_b = repo
+        .store()
+        .get_commit(summary.rewrites.get(&commit_b).unwrap())
+        .unwrap();
+    assert_eq!(*new_commit_b.tree_id(), expected_tree_b.id());
+}
+
+#[test]
+fn test_fix_rename_to_existing_path() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let path1 = repo_path("file1");
+    let tree1 = create_tree(
+        repo,
+        &[
+            (path1, "rename:content"),
+            (repo_path("file1.renamed"), "content"),
+        ],
+    );
+    let commit_a = create_commit(
+        &mut tx,
+        vec![repo.store().root_commit_id().clone()],
+        tree1.id(),
+    );
+
+    let result = fix_files(
+        vec![commit_a],
+        &EverythingMatcher,
+        false,
+        tx.repo_mut(),
+        &mut TestFileFixer::new(),
+    )
+    .block_on();
+    let err = result.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Cannot rename file1 to file1.renamed because the path already exists"
+    );
+}
+
+#[test]
+fn test_fix_file_once_per_content() {
+    // Identical files in different commits are only passed to the fixer once,
+    // and the fix is reused in the descendants.
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let path1 = repo_path("file1");
+    let path2 = repo_path("file2");
+    let tree1 = create_tree(repo, &[(path1, "fixme:content")]);
+    let tree2 = create_tree(repo, &[(path1, "fixme:content"), (path2, "other")]);
+    let commit_a = create_commit(
+        &mut tx,
+        vec![repo.store().root_commit_id().clone()],
+        tree1.id(),
+    );
+    let commit_b = create_commit(&mut tx, vec![commit_a.clone()], tree2.id());
+
+    let root_commits = vec![commit_a.clone()];
+    let include_unchanged_files = false;
+    let fixed_commits = Mutex::new(vec![]);
+    let mut parallel_fixer = ParallelFileFixer::new(|store: &Store, file_to_fix: &FileToFix| {
+        fixed_commits
+            .lock()
+            .unwrap()
+            .push((file_to_fix.commit_id.clone(), file_to_fix.repo_path.clone()));
+        fix_file(store, file_to_fix)
+    });
+
+    let summary = fix_files(
+        root_commits,
+        &EverythingMatcher,
+        include_unchanged_files,
+        tx.repo_mut(),
+        &mut parallel_fixer,
+    )
+    .block_on()
+    .unwrap();
+    assert_eq!(summary.num_fixed_commits, 2);
+
+    let fixed_commits = fixed_commits.into_inner().unwrap();
+    assert_eq!(
+        fixed_commits.iter().sorted().collect_vec(),
+        [
+            (commit_a.clone(), path1.to_owned()),
+            (commit_b.clone(), path2.to_owned()),
+        ]
+        .iter()
+        .sorted()
+        .collect_vec()
+    );
+    let expected_tree_b = create_tree(repo, &[(path1, "CONTENT"), (path2, "other")]);
+    let new_commit_b = repo
+        .store()
+        .get_commit(summary.rewrites.get(&commit_b).unwrap())
+        .unwrap();
+    assert_eq!(*new_commit_b.tree_id(), expected_tree_b.id());
+}
+
+#[test]
+fn test_fix_file_in_each_commit() {
+    // Identical files in different commits are passed to the fixer separately
+    // if the fix may depend on the commit.
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let path1 = repo_path("file1");
+    let path2 = repo_path("file2");
+    let tree1 = create_tree(repo, &[(path1, "fixme:content")]);
+    let tree2 = create_tree(repo, &[(path1, "fixme:content"), (path2, "other")]);
+    let commit_a = create_commit(
+        &mut tx,
+        vec![repo.store().root_commit_id().clone()],
+        tree1.id(),
+    );
+    let commit_b = create_commit(&mut tx, vec![commit_a.clone()], tree2.id());
+
+    let root_commits = vec![commit_a.clone()];
+    let include_unchanged_files = false;
+    let fixed_commits = Mutex::new(vec![]);
+    let mut parallel_fixer = ParallelFileFixer::new(|store: &Store, file_to_fix: &FileToFix| {
+        fixed_commits
+            .lock()
+            .unwrap()
+            .push((file_to_fix.commit_id.clone(), file_to_fix.repo_path.clone()));
+        fix_file(store, file_to_fix)
+    })
+    .with_commit_dependent_paths(Box::new(FilesMatcher::new([path1])));
+
+    let summary = fix_files(
+        root_commits,
+        &EverythingMatcher,
+        include_unchanged_files,
+        tx.repo_mut(),
+        &mut parallel_fixer,
+    )
+    .block_on()
+    .unwrap();
+    assert_eq!(summary.num_fixed_commits, 2);
+
+    let fixed_commits = fixed_commits.into_inner().unwrap();
+    assert_eq!(
+        fixed_commits.iter().sorted().collect_vec(),
+        [
+            (commit_a.clone(), path1.to_owned()),
+            (commit_b.clone(), path1.to_owned()),
+            (commit_b.clone(), path2.to_owned()),
+        ]
+        .iter()
+        .sorted()
+        .collect_vec()
+    );
+}
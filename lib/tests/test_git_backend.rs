@@ -20,6 +20,8 @@ use std::time::Duration;
 use std::time::SystemTime;
 
 use futures::executor::block_on_stream;
+use itertools::Itertools as _;
+use jj_lib::backend::Backend as _;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::CopyRecord;
 use jj_lib::commit::Commit;
@@ -34,13 +36,16 @@ use jj_lib::store::Store;
 use jj_lib::transaction::Transaction;
 use maplit::hashmap;
 use maplit::hashset;
+use pollster::FutureExt as _;
 use testutils::commit_with_tree;
 use testutils::create_random_commit;
 use testutils::create_single_tree;
 use testutils::create_tree;
 use testutils::is_external_tool_installed;
+use testutils::read_file;
 use testutils::repo_path;
 use testutils::repo_path_buf;
+use testutils::write_file;
 use testutils::CommitGraphBuilder;
 use testutils::TestRepo;
 use testutils::TestRepoBackend;
@@ -241,6 +246,129 @@ fn test_gc() {
     assert_eq!(collect_no_gc_refs(git_repo_path), hashset! {});
 }
 
+fn count_packs(git_repo_path: &Path) -> usize {
+    let pack_dir = git_repo_path.join("objects").join("pack");
+    pack_dir
+        .read_dir()
+        .unwrap()
+        .filter(|entry| entry.as_ref().unwrap().path().extension() == Some("pack".as_ref()))
+        .count()
+}
+
+#[test]
+fn test_write_batch() {
+    if !is_external_tool_installed("git") {
+        eprintln!("Skipping because git command might fail to run");
+        return;
+    }
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let store = repo.store();
+    let git_repo_path = get_git_backend(repo).git_repo_path();
+    let path = repo_path("file");
+
+    // Flushing without starting a batch is an error
+    assert!(store.flush_write_batch().is_err());
+
+    // Small batches are written as loose objects
+    store.begin_write_batch();
+    let small_ids = (0..5)
+        .map(|i| write_file(store, path, &format!("small {i}")))
+        .collect_vec();
+    store.flush_write_batch().unwrap();
+    assert_eq!(count_packs(git_repo_path), 0);
+
+    // Large batches are written to a single pack. Nested batches are flushed
+    // with the outermost batch.
+    store.begin_write_batch();
+    store.begin_write_batch();
+    let large_ids = (0..200)
+        .map(|i| write_file(store, path, &format!("large {i}")))
+        .collect_vec();
+    store.flush_write_batch().unwrap();
+    assert_eq!(count_packs(git_repo_path), 0);
+    // Buffered objects can be read by the writing thread
+    assert_eq!(read_file(store, path, &large_ids[0]), b"large 0");
+    store.flush_write_batch().unwrap();
+    assert_eq!(count_packs(git_repo_path), 1);
+
+    // All objects are persisted
+    let repo = test_repo
+        .env
+        .load_repo_at_head(&testutils::user_settings(), test_repo.repo_path());
+    let store = repo.store();
+    assert_eq!(read_file(store, path, &small_ids[4]), b"small 4");
+    assert_eq!(read_file(store, path, &large_ids[199]), b"large 199");
+}
+
+#[test]
+fn test_write_batch_flush_failure() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let store = repo.store();
+    let git_repo_path = get_git_backend(repo).git_repo_path();
+
+    store.begin_write_batch();
+    let mut tx = repo.start_transaction();
+    let commit = create_random_commit(tx.repo_mut()).write().unwrap();
+    // The commit metadata can be read back by the writing thread before flushing
+    let backend = get_git_backend(repo);
+    let read_commit = backend.read_commit(commit.id()).block_on().unwrap();
+    assert_eq!(&read_commit.change_id, commit.change_id());
+    // No-gc ref isn't created until the commit object is written to disk
+    assert!(!collect_no_gc_refs(git_repo_path).contains(commit.id()));
+
+    // Make writing loose objects fail
+    let objects_dir = git_repo_path.join("objects");
+    let saved_objects_dir = git_repo_path.join("objects.saved");
+    std::fs::rename(&objects_dir, &saved_objects_dir).unwrap();
+    std::fs::write(&objects_dir, b"").unwrap();
+    assert!(store.flush_write_batch().is_err());
+    std::fs::remove_file(&objects_dir).unwrap();
+    std::fs::rename(&saved_objects_dir, &objects_dir).unwrap();
+
+    // No dangling ref should be left
+    assert!(!collect_no_gc_refs(git_repo_path).contains(commit.id()));
+}
+
+#[test]
+fn test_write_batch_on_rebase() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let git_repo_path = get_git_backend(repo).git_repo_path();
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let mut commit_top = commit_a.clone();
+    for _ in 0..5 {
+        commit_top = graph_builder.commit_with_parents(&[&commit_top]);
+    }
+    let commit_b = graph_builder.initial_commit();
+    let repo = tx.commit("test").unwrap();
+
+    let mut tx = repo.start_transaction();
+    tx.repo_mut()
+        .set_rewritten_commit(commit_a.id().clone(), commit_b.id().clone());
+    let num_rebased = tx.repo_mut().rebase_descendants().unwrap();
+    assert_eq!(num_rebased, 5);
+    let repo = tx.commit("test").unwrap();
+
+    // Rebased commits should be readable by other instances
+    let reloaded_repo = test_repo
+        .env
+        .load_repo_at_head(&testutils::user_settings(), test_repo.repo_path());
+    for head_id in repo.view().heads() {
+        reloaded_repo.store().get_commit(head_id).unwrap();
+    }
+
+    // Rebased commits should be protected from GC
+    let no_gc_refs = collect_no_gc_refs(git_repo_path);
+    for head_id in repo.view().heads() {
+        assert!(no_gc_refs.contains(head_id));
+    }
+}
+
 #[test]
 fn test_copy_detection() {
     let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
@@ -12,9 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::TreeId;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::RepoLoader;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::settings::UserSettings;
+use pollster::FutureExt as _;
+use testutils::create_single_tree;
+use testutils::repo_path;
+use testutils::write_file;
 use testutils::write_random_commit;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 
 #[test]
 fn test_load_at_operation() {
@@ -52,3 +67,58 @@ fn test_load_at_operation() {
     let old_repo = loader.load_at(repo.operation()).unwrap();
     assert!(old_repo.view().heads().contains(commit.id()));
 }
+
+#[test]
+fn test_verify_objects_on_read() {
+    let mut config = testutils::base_user_config();
+    let layer = ConfigLayer::parse(ConfigSource::User, "store.verify-objects = true").unwrap();
+    config.add_layer(layer);
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_backend_and_settings(TestRepoBackend::Simple, &settings);
+    let store = test_repo.repo.store();
+
+    let path = repo_path("file");
+    let id = write_file(store, path, "contents");
+    assert!(store.read_file(path, &id).block_on().is_ok());
+
+    // Corrupt the file in the backend
+    let object_path = test_repo.repo_path().join("store/files").join(id.hex());
+    fs::write(&object_path, "corrupted").unwrap();
+    assert_matches!(
+        store.read_file(path, &id).block_on(),
+        Err(BackendError::CorruptObject { hash, hint: Some(_), .. }) if hash == id.hex()
+    );
+
+    // The corrupt object is moved out of the way
+    let quarantine_path = test_repo
+        .repo_path()
+        .join("store/quarantine/files")
+        .join(id.hex());
+    assert!(!object_path.exists());
+    assert_eq!(fs::read(quarantine_path).unwrap(), b"corrupted");
+    assert_matches!(
+        store.read_file(path, &id).block_on(),
+        Err(BackendError::ObjectNotFound { .. })
+    );
+
+    // Trees are checked as well
+    let tree1 = create_single_tree(&test_repo.repo, &[(path, "1")]);
+    let tree2 = create_single_tree(&test_repo.repo, &[(path, "2")]);
+    let tree_path = |id: &TreeId| test_repo.repo_path().join("store/trees").join(id.hex());
+    fs::copy(tree_path(tree2.id()), tree_path(tree1.id())).unwrap();
+    // Load the store again so that the tree isn't cached
+    let loader = RepoLoader::init_from_file_system(
+        &settings,
+        test_repo.repo_path(),
+        &test_repo.env.default_store_factories(),
+    )
+    .unwrap();
+    assert_matches!(
+        loader.store().get_tree(RepoPathBuf::root(), tree1.id()),
+        Err(BackendError::CorruptObject { hash, .. }) if hash == tree1.id().hex()
+    );
+    assert!(loader
+        .store()
+        .get_tree(RepoPathBuf::root(), tree2.id())
+        .is_ok());
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs::File;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt as _;
 #[cfg(unix)]
@@ -19,6 +20,8 @@ use std::os::unix::net::UnixListener;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use assert_matches::assert_matches;
 use indoc::indoc;
@@ -31,7 +34,9 @@ use jj_lib::file_util::check_symlink_support;
 use jj_lib::file_util::try_symlink;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreFile;
+use jj_lib::local_working_copy::CheckoutRecovery;
 use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::local_working_copy::LockedLocalWorkingCopy;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
@@ -47,6 +52,7 @@ use jj_lib::tree_builder::TreeBuilder;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::NewDirectoryPolicy;
 use jj_lib::working_copy::SnapshotOptions;
 use jj_lib::working_copy::UntrackedReason;
 use jj_lib::working_copy::WorkingCopy as _;
@@ -831,6 +837,144 @@ fn test_checkout_discard() {
     assert!(!reloaded_wc.file_states().unwrap().contains_path(file2_path));
 }
 
+#[test]
+fn test_checkout_interrupted_recover() {
+    // Start a mutation, do a checkout, and then discard the mutation as if the
+    // process had been killed. The checkout should be resumed without treating
+    // the files it wrote as conflicting changes.
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let file1_path = repo_path("file1");
+    let file2_path = repo_path("file2");
+
+    let tree1 = create_tree(&repo, &[(file1_path, "contents")]);
+    let tree2 = create_tree(&repo, &[(file1_path, "changed"), (file2_path, "contents")]);
+    let commit1 = commit_with_tree(repo.store(), tree1.id());
+    let commit2 = commit_with_tree(repo.store(), tree2.id());
+
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit1,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    assert!(!wc.has_pending_checkout());
+
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    locked_ws
+        .locked_wc()
+        .check_out(&commit2, &CheckoutOptions::empty_for_test())
+        .unwrap();
+    drop(locked_ws);
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    assert!(wc.has_pending_checkout());
+    assert_eq!(*wc.tree_id().unwrap(), tree1.id());
+
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let locked_wc: &mut LockedLocalWorkingCopy =
+        locked_ws.locked_wc().as_any_mut().downcast_mut().unwrap();
+    let stats = locked_wc
+        .recover_checkout(
+            CheckoutRecovery::Resume,
+            &SnapshotOptions::empty_for_test(),
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.added_files, 1);
+    assert_eq!(stats.updated_files, 1);
+    assert_eq!(stats.skipped_files, 0);
+    assert_eq!(*locked_ws.locked_wc().old_tree_id(), tree2.id());
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    assert!(!wc.has_pending_checkout());
+    assert_eq!(*wc.tree_id().unwrap(), tree2.id());
+    assert!(wc.file_states().unwrap().contains_path(file2_path));
+    assert_eq!(
+        std::fs::read(file2_path.to_fs_path_unchecked(&workspace_root)).unwrap(),
+        b"contents"
+    );
+
+    // Nothing left to recover
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let locked_wc: &mut LockedLocalWorkingCopy =
+        locked_ws.locked_wc().as_any_mut().downcast_mut().unwrap();
+    assert!(locked_wc
+        .recover_checkout(
+            CheckoutRecovery::Resume,
+            &SnapshotOptions::empty_for_test(),
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_checkout_interrupted_rollback() {
+    // Interrupt a checkout, modify another file, and then roll back. Only the
+    // files written by the checkout should be restored.
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let file1_path = repo_path("file1");
+    let file2_path = repo_path("file2");
+    let file3_path = repo_path("file3");
+
+    let tree1 = create_tree(&repo, &[(file1_path, "contents")]);
+    let tree2 = create_tree(&repo, &[(file1_path, "changed"), (file2_path, "contents")]);
+    let commit1 = commit_with_tree(repo.store(), tree1.id());
+    let commit2 = commit_with_tree(repo.store(), tree2.id());
+
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit1,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    locked_ws
+        .locked_wc()
+        .check_out(&commit2, &CheckoutOptions::empty_for_test())
+        .unwrap();
+    drop(locked_ws);
+    std::fs::write(file3_path.to_fs_path_unchecked(&workspace_root), "user").unwrap();
+
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let locked_wc: &mut LockedLocalWorkingCopy =
+        locked_ws.locked_wc().as_any_mut().downcast_mut().unwrap();
+    let stats = locked_wc
+        .recover_checkout(
+            CheckoutRecovery::Rollback,
+            &SnapshotOptions::empty_for_test(),
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.updated_files, 1);
+    assert_eq!(stats.removed_files, 1);
+    assert_eq!(*locked_ws.locked_wc().old_tree_id(), tree1.id());
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    assert!(!wc.has_pending_checkout());
+    let expected_tree = create_tree(&repo, &[(file1_path, "contents"), (file3_path, "user")]);
+    assert_eq!(*wc.tree_id().unwrap(), expected_tree.id());
+    assert_eq!(
+        std::fs::read(file1_path.to_fs_path_unchecked(&workspace_root)).unwrap(),
+        b"contents"
+    );
+    assert!(!file2_path.to_fs_path_unchecked(&workspace_root).exists());
+}
+
 #[test]
 fn test_snapshot_file_directory_transition() {
     let mut test_workspace = TestWorkspace::init();
@@ -1028,6 +1172,71 @@ fn test_snapshot_racy_timestamps() {
     }
 }
 
+#[test]
+fn test_snapshot_racily_clean_file() {
+    // Tests that a file modified again in the same mtime tick as it was
+    // snapshotted is detected even if the size doesn't change.
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let path = repo_path("file");
+    let disk_path = path.to_fs_path_unchecked(&workspace_root);
+    // Pretend that the file is modified while snapshotting by setting the
+    // mtime in the future.
+    let mtime = SystemTime::now() + Duration::from_secs(3600);
+    let write_with_mtime = |contents: &str| {
+        std::fs::write(&disk_path, contents).unwrap();
+        let file = File::options().write(true).open(&disk_path).unwrap();
+        file.set_modified(mtime).unwrap();
+    };
+
+    write_with_mtime("contents 1\n");
+    let tree1 = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        tree1.id(),
+        create_tree(&repo, &[(path, "contents 1\n")]).id()
+    );
+    write_with_mtime("contents 2\n");
+    let tree2 = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        tree2.id(),
+        create_tree(&repo, &[(path, "contents 2\n")]).id()
+    );
+}
+
+#[test]
+fn test_snapshot_same_mtime_millis_and_size() {
+    // Tests that a file modification is detected by the sub-millisecond part
+    // of the mtime even if the size and the mtime in milliseconds don't change.
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let path = repo_path("file");
+    let disk_path = path.to_fs_path_unchecked(&workspace_root);
+    // The mtime is old enough that the file isn't racily clean.
+    let base_mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let write_with_mtime = |contents: &str, mtime: SystemTime| {
+        std::fs::write(&disk_path, contents).unwrap();
+        let file = File::options().write(true).open(&disk_path).unwrap();
+        file.set_modified(mtime).unwrap();
+    };
+
+    write_with_mtime("contents 1\n", base_mtime + Duration::from_micros(100));
+    let tree1 = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        tree1.id(),
+        create_tree(&repo, &[(path, "contents 1\n")]).id()
+    );
+    write_with_mtime("contents 2\n", base_mtime + Duration::from_micros(200));
+    let tree2 = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        tree2.id(),
+        create_tree(&repo, &[(path, "contents 2\n")]).id()
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn test_snapshot_special_file() {
@@ -2248,3 +2457,115 @@ fn test_snapshot_max_new_file_size() {
         UntrackedReason::FileTooLarge { .. }
     );
 }
+
+#[test]
+fn test_snapshot_new_directory_policy() {
+    let mut test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    testutils::write_working_copy_file(&workspace_root, repo_path("src/lib.rs"), "lib\n");
+    test_workspace.snapshot().unwrap();
+
+    // A new directory with many files, and new directories with few files
+    let build_paths = [
+        repo_path("target/a"),
+        repo_path("target/debug/b"),
+        repo_path("target/debug/c"),
+    ];
+    for path in build_paths {
+        testutils::write_working_copy_file(&workspace_root, path, "build\n");
+    }
+    testutils::write_working_copy_file(&workspace_root, repo_path("src/new/x"), "x\n");
+    testutils::write_working_copy_file(&workspace_root, repo_path("docs/y"), "y\n");
+
+    // Excluded directories stay untracked across snapshots
+    let options = SnapshotOptions {
+        new_directory_policy: NewDirectoryPolicy::Exclude { max_files: 2 },
+        ..SnapshotOptions::empty_for_test()
+    };
+    for _ in 0..2 {
+        let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+        assert_eq!(
+            stats
+                .untracked_paths
+                .keys()
+                .map(AsRef::as_ref)
+                .collect_vec(),
+            [repo_path("target")]
+        );
+        assert_matches!(
+            stats.untracked_paths.values().next().unwrap(),
+            UntrackedReason::NewDirectoryTooLarge { max_files: 2 }
+        );
+        assert!(stats.large_new_directories.is_empty());
+        assert!(tree.path_value(build_paths[0]).unwrap().is_absent());
+        assert!(tree
+            .path_value(repo_path("src/new/x"))
+            .unwrap()
+            .is_present());
+        assert!(tree.path_value(repo_path("docs/y")).unwrap().is_present());
+    }
+
+    // Directories are snapshotted with a warning, which is reported only once
+    let options = SnapshotOptions {
+        new_directory_policy: NewDirectoryPolicy::Warn { max_files: 2 },
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(stats.untracked_paths.is_empty());
+    assert_eq!(
+        stats
+            .large_new_directories
+            .iter()
+            .map(AsRef::as_ref)
+            .collect_vec(),
+        [repo_path("target")]
+    );
+    for path in build_paths {
+        assert!(tree.path_value(path).unwrap().is_present());
+    }
+    let (_tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(stats.large_new_directories.is_empty());
+}
+
+#[test]
+fn test_snapshot_report_ignored_paths() {
+    let mut test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let gitignore_path = repo_path(".gitignore");
+    testutils::write_working_copy_file(&workspace_root, gitignore_path, "*.log\ntarget/\n");
+    testutils::write_working_copy_file(&workspace_root, repo_path("a.log"), "log\n");
+    testutils::write_working_copy_file(&workspace_root, repo_path("src/b.log"), "log\n");
+    testutils::write_working_copy_file(&workspace_root, repo_path("src/c"), "c\n");
+    testutils::write_working_copy_file(&workspace_root, repo_path("target/debug/d"), "d\n");
+
+    // Ignored paths aren't reported by default
+    let (_tree, stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions::empty_for_test())
+        .unwrap();
+    assert!(stats.untracked_paths.is_empty());
+
+    // Ignored directory is reported as a whole
+    let options = SnapshotOptions {
+        report_ignored_paths: true,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(
+        stats
+            .untracked_paths
+            .keys()
+            .map(AsRef::as_ref)
+            .collect_vec(),
+        [
+            repo_path("a.log"),
+            repo_path("src/b.log"),
+            repo_path("target")
+        ]
+    );
+    assert!(stats
+        .untracked_paths
+        .values()
+        .all(|reason| matches!(reason, UntrackedReason::Ignored)));
+    assert!(tree.path_value(repo_path("a.log")).unwrap().is_absent());
+    assert!(tree.path_value(repo_path("src/c")).unwrap().is_present());
+}
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+
 use futures::StreamExt as _;
 use itertools::Itertools as _;
 use jj_lib::local_working_copy::LocalWorkingCopy;
@@ -319,3 +323,67 @@ fn test_sparse_commit_gitignore() {
     assert_eq!(entries.len(), 1);
     assert_eq!(entries[0].0.as_ref(), dir1_file2_path);
 }
+
+#[test]
+fn test_sparse_checkout_progress() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+
+    let dir1_path = repo_path("dir1");
+    let dir1_file1_path = repo_path("dir1/file1");
+    let dir2_path = repo_path("dir2");
+    let dir2_file1_path = repo_path("dir2/file1");
+    let dir2_file2_path = repo_path("dir2/file2");
+
+    let tree = create_tree(
+        repo,
+        &[
+            (dir1_file1_path, "contents"),
+            (dir2_file1_path, "contents"),
+            (dir2_file2_path, "contents"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    let ws = &mut test_workspace.workspace;
+
+    let updated_paths = Arc::new(Mutex::new(vec![]));
+    let options = CheckoutOptions {
+        progress: Some(Arc::new({
+            let updated_paths = updated_paths.clone();
+            move |path: &RepoPath| updated_paths.lock().unwrap().push(path.to_owned())
+        })),
+        ..CheckoutOptions::empty_for_test()
+    };
+
+    // Only the excluded paths are reported
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    locked_ws
+        .locked_wc()
+        .set_sparse_patterns(to_owned_path_vec(&[dir1_path]), &options)
+        .unwrap();
+    assert_eq!(
+        mem::take(&mut *updated_paths.lock().unwrap()),
+        to_owned_path_vec(&[dir2_file1_path, dir2_file2_path])
+    );
+
+    // Included paths are reported before excluded paths
+    locked_ws
+        .locked_wc()
+        .set_sparse_patterns(to_owned_path_vec(&[dir2_path]), &options)
+        .unwrap();
+    assert_eq!(
+        mem::take(&mut *updated_paths.lock().unwrap()),
+        to_owned_path_vec(&[dir2_file1_path, dir2_file2_path, dir1_file1_path])
+    );
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+}
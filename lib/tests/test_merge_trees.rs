@@ -17,7 +17,9 @@ use jj_lib::backend::TreeValue;
 use jj_lib::merge::Merge;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::rebase_commit;
+use pollster::FutureExt as _;
 use testutils::create_tree;
+use testutils::read_file;
 use testutils::repo_path;
 use testutils::TestRepo;
 
@@ -225,3 +227,43 @@ fn test_rebase_on_lossy_merge() {
     .flatten();
     assert_eq!(*commit_d2.tree_id(), MergedTreeId::Merge(expected_tree_id));
 }
+
+#[test]
+fn test_merge_with_merge_attribute() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // The nested .gitattributes file takes precedence over the root one
+    let union_path = repo_path("dir/union.txt");
+    let binary_path = repo_path("dir/binary.txt");
+    let text_path = repo_path("text.md");
+    let create_tree_with_attributes = |union: &str, binary: &str, text: &str| {
+        create_tree(
+            repo,
+            &[
+                (repo_path(".gitattributes"), "*.txt merge=union\n"),
+                (repo_path("dir/.gitattributes"), "binary.txt -merge\n"),
+                (union_path, union),
+                (binary_path, binary),
+                (text_path, text),
+            ],
+        )
+    };
+    let base = create_tree_with_attributes("a\n", "a\nb\n", "a\n");
+    let side1 = create_tree_with_attributes("a\nb\n", "a\nb\nc\n", "a\nb\n");
+    let side2 = create_tree_with_attributes("a\nc\n", "z\na\nb\n", "a\nc\n");
+    let merged = side1.merge(base, side2).block_on().unwrap();
+
+    // Conflicting hunks are resolved by taking the lines from both sides
+    let value = merged.path_value(union_path).unwrap();
+    let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+        panic!("unexpected value: {value:?}");
+    };
+    assert_eq!(read_file(repo.store(), union_path, id), b"a\nb\nc\n");
+
+    // Changes that could be merged line by line are left conflicted
+    assert!(!merged.path_value(binary_path).unwrap().is_resolved());
+
+    // Files without the attribute are merged as usual
+    assert!(!merged.path_value(text_path).unwrap().is_resolved());
+}
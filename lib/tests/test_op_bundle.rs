@@ -0,0 +1,199 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use itertools::Itertools as _;
+use jj_lib::op_bundle::ExportOptions;
+use jj_lib::op_bundle::OpBundle;
+use jj_lib::op_bundle::OpBundleError;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use testutils::create_random_commit;
+use testutils::TestRepo;
+
+fn write_and_read(bundle: &OpBundle) -> OpBundle {
+    let mut data = vec![];
+    bundle.write_to(&mut data).unwrap();
+    OpBundle::read_from(&mut data.as_slice()).unwrap()
+}
+
+#[test]
+fn test_op_bundle_roundtrip_with_commits() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit1 = create_random_commit(tx.repo_mut())
+        .set_description("first")
+        .write()
+        .unwrap();
+    let repo = tx.commit("add first").unwrap();
+    let mut tx = repo.start_transaction();
+    let commit2 = create_random_commit(tx.repo_mut())
+        .set_parents(vec![commit1.id().clone()])
+        .set_description("second")
+        .write()
+        .unwrap();
+    tx.repo_mut()
+        .set_local_bookmark_target("main".as_ref(), RefTarget::normal(commit2.id().clone()));
+    let repo = tx.commit("add second").unwrap();
+
+    let options = ExportOptions {
+        with_commits: true,
+        redact: false,
+    };
+    let bundle =
+        OpBundle::export(repo.loader(), &[repo.operation().clone()], &[], &options).unwrap();
+    assert_eq!(bundle.operations.len(), 2);
+    assert_eq!(bundle.commits.len(), 2);
+    let bundle = write_and_read(&bundle);
+
+    let other_repo = TestRepo::init();
+    let loader = other_repo.repo.loader();
+    let stats = bundle.import(loader).unwrap();
+    assert_eq!(stats.operation_count, 2);
+    assert_eq!(stats.commit_count, 2);
+    assert_eq!(stats.new_head_ids.len(), 1);
+
+    let op = loader.load_operation(&stats.new_head_ids[0]).unwrap();
+    assert_eq!(op.metadata().description, "add second");
+    let parent_op = op.parents().exactly_one().ok().unwrap().unwrap();
+    assert_eq!(parent_op.metadata().description, "add first");
+    assert_eq!(
+        parent_op.parent_ids(),
+        [loader.op_store().root_operation_id().clone()]
+    );
+
+    let imported_repo = loader.load_at(&op).unwrap();
+    let target = imported_repo.view().get_local_bookmark("main".as_ref());
+    let imported_commit2 = imported_repo
+        .store()
+        .get_commit(target.as_normal().unwrap())
+        .unwrap();
+    assert_eq!(imported_commit2.description(), "second");
+    assert_eq!(imported_commit2.change_id(), commit2.change_id());
+    assert_eq!(
+        imported_commit2.tree_id(),
+        &imported_repo.store().empty_merged_tree_id()
+    );
+    let imported_commit1 = imported_commit2
+        .parents()
+        .exactly_one()
+        .ok()
+        .unwrap()
+        .unwrap();
+    assert_eq!(imported_commit1.description(), "first");
+}
+
+#[test]
+fn test_op_bundle_since_redacted() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    create_random_commit(tx.repo_mut())
+        .set_description("secret")
+        .write()
+        .unwrap();
+    let repo = tx.commit("first").unwrap();
+    let since_op = repo.operation().clone();
+    let mut tx = repo.start_transaction();
+    tx.set_tag("args".to_owned(), "jj describe -m secret".to_owned());
+    let commit = create_random_commit(tx.repo_mut())
+        .set_description("")
+        .write()
+        .unwrap();
+    tx.repo_mut()
+        .set_local_bookmark_target("secret".as_ref(), RefTarget::normal(commit.id().clone()));
+    tx.repo_mut()
+        .set_wc_commit("secret-ws".into(), commit.id().clone())
+        .unwrap();
+    let repo = tx.commit("second").unwrap();
+
+    let options = ExportOptions {
+        with_commits: true,
+        redact: true,
+    };
+    let bundle = OpBundle::export(
+        repo.loader(),
+        &[repo.operation().clone()],
+        &[since_op],
+        &options,
+    )
+    .unwrap();
+    let bundle = write_and_read(&bundle);
+    assert_eq!(bundle.operations.len(), 1);
+    let (_, operation) = &bundle.operations[0];
+    assert_eq!(operation.metadata.description, "<redacted>");
+    assert_eq!(operation.metadata.tags["args"], "<redacted>");
+    assert_eq!(operation.metadata.username, "user-2");
+    assert_eq!(operation.metadata.hostname, "host-1");
+    let descriptions = bundle
+        .commits
+        .iter()
+        .map(|(_, commit)| commit.description.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(descriptions, ["", "<redacted>"]);
+    for (_, commit) in &bundle.commits {
+        assert_eq!(commit.author.name, "user-1");
+        assert_eq!(commit.author.email, "email-1@example.com");
+        assert_eq!(commit.committer.name, "user-1");
+        assert_eq!(commit.committer.email, "email-1@example.com");
+    }
+    let (_, view) = &bundle.views[0];
+    assert_eq!(
+        view.local_bookmarks
+            .keys()
+            .map(|name| name.as_str())
+            .collect_vec(),
+        ["bookmark-1"]
+    );
+    assert_eq!(
+        view.wc_commit_ids
+            .keys()
+            .map(|name| name.as_str())
+            .collect_vec(),
+        ["workspace-1"]
+    );
+
+    // The parent operation isn't in the bundle, so the imported operation is
+    // reparented onto the root operation.
+    let other_repo = TestRepo::init();
+    let loader = other_repo.repo.loader();
+    let stats = bundle.import(loader).unwrap();
+    let op = loader.load_operation(&stats.new_head_ids[0]).unwrap();
+    assert_eq!(
+        op.parent_ids(),
+        [loader.op_store().root_operation_id().clone()]
+    );
+    assert_eq!(op.metadata().description, "<redacted>");
+}
+
+#[test]
+fn test_op_bundle_read_invalid() {
+    assert_matches!(
+        OpBundle::read_from(&mut b"foo".as_slice()),
+        Err(OpBundleError::InvalidFormat(_))
+    );
+    assert_matches!(
+        OpBundle::read_from(&mut b"jj-op-bundle-v1\nr".as_slice()),
+        Err(OpBundleError::InvalidFormat(_))
+    );
+    // Corrupted length shouldn't allocate a huge buffer
+    assert_matches!(
+        OpBundle::read_from(&mut b"jj-op-bundle-v1\nr\xff\xff\xff\xff".as_slice()),
+        Err(OpBundleError::InvalidFormat(_))
+    );
+}
@@ -166,6 +166,40 @@ fn test_concurrent_operations() {
     assert_eq!(list_dir(&op_heads_dir), vec![merged_op_id.hex()]);
 }
 
+#[test]
+fn test_concurrent_operations_with_retries() {
+    // Test that a transaction is rebased onto a concurrent operation if
+    // retries are enabled.
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, "operation.concurrency-retries = 1").unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+    let op_heads_dir = test_repo.repo_path().join("op_heads").join("heads");
+
+    let mut tx1 = repo.start_transaction();
+    let commit1 = write_random_commit(tx1.repo_mut());
+    let op_id1 = tx1
+        .commit("transaction 1")
+        .unwrap()
+        .operation()
+        .id()
+        .clone();
+
+    let mut tx2 = repo.start_transaction();
+    let commit2 = write_random_commit(tx2.repo_mut());
+    let repo2 = tx2.commit("transaction 2").unwrap();
+
+    // The second operation is published on top of the first one instead of
+    // creating divergent op heads.
+    assert_eq!(list_dir(&op_heads_dir), vec![repo2.op_id().hex()]);
+    assert_eq!(repo2.operation().parent_ids(), slice::from_ref(&op_id1));
+    assert_eq!(repo2.operation().metadata().description, "transaction 2");
+    assert_heads(repo2.as_ref(), vec![commit1.id(), commit2.id()]);
+}
+
 fn assert_heads(repo: &dyn Repo, expected: Vec<&CommitId>) {
     let expected = expected.iter().cloned().cloned().collect();
     assert_eq!(*repo.view().heads(), expected);
@@ -846,6 +880,55 @@ fn test_resolve_op_parents_children() {
     );
 }
 
+#[test]
+fn test_resolve_op_range_at() {
+    let test_repo = TestRepo::init();
+    let repo_0 = &test_repo.repo;
+    let loader = repo_0.loader();
+
+    let repo_a = repo_0.start_transaction().commit("op A").unwrap();
+    let repo_b = repo_a.start_transaction().commit("op B").unwrap();
+    let repo_c = repo_b.start_transaction().commit("op C").unwrap();
+    let head_ops = slice::from_ref(repo_c.operation());
+    let resolve = |op_str: &str| op_walk::resolve_op_range_at(loader, head_ops, op_str);
+    let collect =
+        |range: op_walk::OperationRange| -> Vec<Operation> { range.walk().try_collect().unwrap() };
+
+    // Not a range
+    assert!(resolve("@").unwrap().is_none());
+
+    // Both ends specified
+    let op_a_id_hex = repo_a.operation().id().hex();
+    let range = resolve(&format!("{op_a_id_hex}..@-")).unwrap().unwrap();
+    assert_eq!(range.root_op, *repo_a.operation());
+    assert_eq!(collect(range), [repo_b.operation().clone()]);
+
+    // Root defaults to the root operation
+    let range = resolve("..@--").unwrap().unwrap();
+    assert_eq!(range.root_op, loader.root_operation());
+    assert_eq!(collect(range), [repo_a.operation().clone()]);
+
+    // Head defaults to the given head operations
+    let range = resolve("@--..").unwrap().unwrap();
+    assert_eq!(range.head_ops, head_ops);
+    assert_eq!(
+        collect(range),
+        [repo_c.operation().clone(), repo_b.operation().clone()]
+    );
+
+    // Empty range
+    let range = resolve("@..@-").unwrap().unwrap();
+    assert_eq!(collect(range), []);
+
+    // Unresolvable end
+    assert_matches!(
+        resolve("..@----"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::EmptyOperations(_)
+        ))
+    );
+}
+
 #[test]
 fn test_walk_ancestors() {
     let test_repo = TestRepo::init();
@@ -0,0 +1,84 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_snapshot::RepoSnapshot;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepoBackend;
+use testutils::TestWorkspace;
+
+#[test]
+fn test_repo_snapshot() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_workspace.repo;
+    let workspace_root = test_workspace.workspace.workspace_root();
+
+    let file_path = repo_path("file");
+    let added_path = repo_path("added");
+    let tree1 = create_tree(repo, &[(file_path, "a\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "b\n"), (added_path, "c\n")]);
+    let mut tx = repo.start_transaction();
+    let commit1 = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree1.id())
+        .set_description("first")
+        .write()
+        .unwrap();
+    let commit2 = tx
+        .repo_mut()
+        .new_commit(vec![commit1.id().clone()], tree2.id())
+        .set_description("second")
+        .write()
+        .unwrap();
+    tx.commit("add commits").unwrap();
+
+    let snapshot = RepoSnapshot::load(&settings, workspace_root).unwrap();
+    let commits = snapshot.evaluate_revset("description(regex:'.')").unwrap();
+    assert_eq!(commits, vec![commit2.clone(), commit1.clone()]);
+    let wc_commit_id = repo
+        .view()
+        .get_wc_commit_id(test_workspace.workspace.workspace_name())
+        .unwrap();
+    let commit_ids = snapshot
+        .evaluate_revset("@")
+        .unwrap()
+        .iter()
+        .map(|commit| commit.id().clone())
+        .collect_vec();
+    assert_eq!(commit_ids, [wc_commit_id.clone()]);
+    assert!(snapshot.evaluate_revset("(").is_err());
+
+    let contents = snapshot.read_file(&commit1, file_path).unwrap();
+    assert_eq!(contents.as_deref(), Some(b"a\n".as_slice()));
+    assert_eq!(snapshot.read_file(&commit1, added_path).unwrap(), None);
+
+    let diffs = snapshot
+        .diff(&commit1, &commit2, &EverythingMatcher)
+        .unwrap();
+    assert_eq!(
+        diffs.iter().map(|diff| diff.path.as_ref()).collect_vec(),
+        [added_path, file_path]
+    );
+    assert!(diffs[0].before.is_absent());
+    assert!(diffs[1].before.is_present());
+
+    // The previous operation doesn't contain the new commits
+    let snapshot = RepoSnapshot::load_at_operation(&settings, workspace_root, "@-").unwrap();
+    assert!(snapshot.evaluate_revset(&commit1.id().hex()).is_err());
+}
@@ -30,6 +30,7 @@ use jj_lib::git;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
 use jj_lib::id_prefix::IdPrefixContext;
+use jj_lib::mailmap::Mailmap;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
@@ -90,9 +91,11 @@ fn resolve_symbol(repo: &dyn Repo, symbol: &str) -> Result<Vec<CommitId>, Revset
         aliases_map: &RevsetAliasesMap::default(),
         local_variables: HashMap::new(),
         user_email: "",
+        user_email_aliases: &[],
         date_pattern_context: chrono::Local::now().into(),
         extensions: &RevsetExtensions::default(),
         workspace: None,
+        mailmap: &Mailmap::empty(),
     };
     let expression = parse(&mut RevsetDiagnostics::new(), symbol, &context).unwrap();
     assert_matches!(*expression, RevsetExpression::CommitRef(_));
@@ -213,9 +216,11 @@ fn test_resolve_symbol_commit_id() {
         aliases_map: &RevsetAliasesMap::default(),
         local_variables: HashMap::new(),
         user_email: settings.user_email(),
+        user_email_aliases: settings.user_email_aliases(),
         date_pattern_context: chrono::Utc::now().fixed_offset().into(),
         extensions: &RevsetExtensions::default(),
         workspace: None,
+        mailmap: &Mailmap::empty(),
     };
     assert_matches!(
         parse(&mut RevsetDiagnostics::new(), "present(01)", &context).unwrap()
@@ -996,15 +1001,25 @@ fn resolve_commit_ids(repo: &dyn Repo, revset_str: &str) -> Vec<CommitId> {
 fn try_resolve_commit_ids(
     repo: &dyn Repo,
     revset_str: &str,
+) -> Result<Vec<CommitId>, RevsetResolutionError> {
+    try_resolve_commit_ids_with_mailmap(repo, revset_str, &Mailmap::empty())
+}
+
+fn try_resolve_commit_ids_with_mailmap(
+    repo: &dyn Repo,
+    revset_str: &str,
+    mailmap: &Mailmap,
 ) -> Result<Vec<CommitId>, RevsetResolutionError> {
     let settings = testutils::user_settings();
     let context = RevsetParseContext {
         aliases_map: &RevsetAliasesMap::default(),
         local_variables: HashMap::new(),
         user_email: settings.user_email(),
+        user_email_aliases: settings.user_email_aliases(),
         date_pattern_context: chrono::Utc::now().fixed_offset().into(),
         extensions: &RevsetExtensions::default(),
         workspace: None,
+        mailmap,
     };
     let expression = parse(&mut RevsetDiagnostics::new(), revset_str, &context).unwrap();
     let symbol_resolver = default_symbol_resolver(repo);
@@ -1031,14 +1046,17 @@ fn resolve_commit_ids_in_workspace(
     let workspace_ctx = RevsetWorkspaceContext {
         path_converter: &path_converter,
         workspace_name: workspace.workspace_name(),
+        repo_path: workspace.repo_path(),
     };
     let context = RevsetParseContext {
         aliases_map: &RevsetAliasesMap::default(),
         local_variables: HashMap::new(),
         user_email: settings.user_email(),
+        user_email_aliases: settings.user_email_aliases(),
         date_pattern_context: chrono::Utc::now().fixed_offset().into(),
         extensions: &RevsetExtensions::default(),
         workspace: Some(workspace_ctx),
+        mailmap: &Mailmap::empty(),
     };
     let expression = parse(&mut RevsetDiagnostics::new(), revset_str, &context).unwrap();
     let symbol_resolver = default_symbol_resolver(repo);
@@ -1605,6 +1623,16 @@ fn test_evaluate_expression_ancestors() {
             commit1.id().clone(),
         ]
     );
+
+    // Can find ancestors within a window of generations
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("ancestors({}, 2, 3)", commit4.id())),
+        vec![commit2.id().clone(), root_commit.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("ancestors({}, 3, 3)", commit4.id())),
+        vec![]
+    );
 }
 
 #[test]
@@ -2117,6 +2145,12 @@ fn test_evaluate_expression_descendants() {
             commit3.id().clone(),
         ]
     );
+
+    // Can find descendants within a window of generations
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("descendants({}, 1, 3)", commit2.id())),
+        vec![commit5.id().clone(), commit3.id().clone()]
+    );
 }
 
 #[test]
@@ -2981,6 +3015,52 @@ fn test_evaluate_expression_description() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_trailer() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = create_random_commit(mut_repo)
+        .set_description("commit 1\n\nTopic: foo\n")
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo)
+        .set_parents(vec![commit1.id().clone()])
+        .set_description("commit 2\n\nTopic: foo-bar\nReviewed-by: Alice\n")
+        .write()
+        .unwrap();
+    // A description consisting of a single paragraph has no trailers
+    create_random_commit(mut_repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_description("Topic: foo\n")
+        .write()
+        .unwrap();
+
+    // Matches any value
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer(Topic)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer('Reviewed-by')"),
+        vec![commit2.id().clone()]
+    );
+    // The key is matched exactly
+    assert_eq!(resolve_commit_ids(mut_repo, "trailer(topic)"), vec![]);
+    // The value is matched by string pattern
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer(Topic, exact:foo)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer(Topic, glob:'foo*')"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_author() {
     let test_repo = TestRepo::init();
@@ -3081,6 +3161,56 @@ fn test_evaluate_expression_author() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_author_mailmap() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let timestamp = Timestamp {
+        timestamp: MillisSinceEpoch(0),
+        tz_offset: 0,
+    };
+    let mut create_commit = |name: &str, email: &str| {
+        create_random_commit(mut_repo)
+            .set_author(Signature {
+                name: name.to_owned(),
+                email: email.to_owned(),
+                timestamp,
+            })
+            .write()
+            .unwrap()
+    };
+    let commit1 = create_commit("name1", "email1");
+    let commit2 = create_commit("name2", "email2");
+    let commit3 = create_commit("name3", "email3");
+    let mailmap = Mailmap::parse(
+        "\
+Proper <proper@example.com> <email1>
+Proper <proper@example.com> <EMAIL2>
+",
+    );
+    let resolve = |revset_str: &str| {
+        try_resolve_commit_ids_with_mailmap(&*mut_repo, revset_str, &mailmap).unwrap()
+    };
+
+    assert_eq!(
+        resolve("author(exact:Proper)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve("author_email(exact:'proper@example.com')"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // The recorded identities no longer match
+    assert_eq!(resolve("author_name(exact:name1)"), vec![]);
+    assert_eq!(resolve("author(name)"), vec![commit3.id().clone()]);
+    // Committer isn't remapped
+    assert_eq!(resolve("committer(exact:Proper)"), vec![]);
+}
+
 fn parse_timestamp(s: &str) -> Timestamp {
     Timestamp::from_datetime(s.parse::<DateTime<chrono::FixedOffset>>().unwrap())
 }
@@ -4019,6 +4149,40 @@ fn test_evaluate_expression_file() {
         vec![commit2.id().clone()]
     );
 
+    // touches() revset:
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            r#"touches("added_modified_removed")"#,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        ),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    // The path is relative to the current directory, and isn't a prefix.
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            r#"touches("repo/added_clean_clean")"#,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root().parent().unwrap()),
+        ),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            r#"touches("added")"#,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        ),
+        vec![]
+    );
+
     // empty() revset, which is identical to ~file(".")
     assert_eq!(
         resolve_commit_ids(mut_repo, &format!("{}:: & empty()", commit1.id())),
@@ -4291,6 +4455,50 @@ fn test_evaluate_expression_file_merged_parents() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_empty_merged_parents() {
+    let test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let file_path1 = repo_path("file1");
+    let file_path2 = repo_path("file2");
+    let tree1 = create_tree(repo, &[(file_path1, "1\n"), (file_path2, "1\n")]);
+    let tree2 = create_tree(repo, &[(file_path1, "2\n"), (file_path2, "1\n")]);
+    let tree3 = create_tree(repo, &[(file_path1, "1\n"), (file_path2, "3\n")]);
+    let tree4 = create_tree(repo, &[(file_path1, "2\n"), (file_path2, "3\n")]);
+    let tree5 = create_tree(repo, &[(file_path1, "2\n"), (file_path2, "5\n")]);
+
+    let mut create_commit =
+        |parent_ids, tree_id| mut_repo.new_commit(parent_ids, tree_id).write().unwrap();
+    let commit1 = create_commit(vec![repo.store().root_commit_id().clone()], tree1.id());
+    let commit2 = create_commit(vec![commit1.id().clone()], tree2.id());
+    let commit3 = create_commit(vec![commit1.id().clone()], tree3.id());
+    // Tree is the auto-merged parents' tree
+    let commit4 = create_commit(vec![commit2.id().clone(), commit3.id().clone()], tree4.id());
+    let commit5 = create_commit(vec![commit2.id().clone(), commit3.id().clone()], tree5.id());
+    let commit6 = create_commit(vec![commit5.id().clone()], tree5.id());
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "empty()"),
+        vec![
+            commit6.id().clone(),
+            commit4.id().clone(),
+            repo.store().root_commit_id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "empty() | (empty() & merges())"),
+        vec![
+            commit6.id().clone(),
+            commit4.id().clone(),
+            repo.store().root_commit_id().clone(),
+        ]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_conflict() {
     let test_workspace = TestWorkspace::init();
@@ -4322,6 +4530,20 @@ fn test_evaluate_expression_conflict() {
         resolve_commit_ids(mut_repo, "conflicts()"),
         vec![commit4.id().clone()]
     );
+
+    // Conflicts can be restricted to paths
+    let query = |revset_str: &str| {
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            revset_str,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        )
+    };
+    assert_eq!(query("conflicts('file1')"), vec![commit4.id().clone()]);
+    assert_eq!(query("conflicts('file2')"), vec![]);
+    assert_eq!(query("conflicts(all())"), vec![commit4.id().clone()]);
+    assert_eq!(query("conflicts(~file1)"), vec![]);
 }
 
 #[test]
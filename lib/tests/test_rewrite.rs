@@ -935,6 +935,107 @@ fn test_rebase_descendants_contents() {
     );
 }
 
+#[test]
+fn test_rebase_descendants_empty_commit() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Commit B was replaced by commit D. Commit C doesn't change anything, so
+    // its new tree should be the tree of commit D.
+    //
+    // D
+    // | C
+    // | B
+    // |/
+    // A
+    let mut tx = repo.start_transaction();
+    let tree1 = create_tree(repo, &[(repo_path("file1"), "a")]);
+    let tree2 = create_tree(repo, &[(repo_path("file1"), "b")]);
+    let tree3 = create_tree(repo, &[(repo_path("file1"), "c")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree1.id())
+        .write()
+        .unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_b.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let commit_d = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree3.id())
+        .write()
+        .unwrap();
+
+    tx.repo_mut()
+        .set_rewritten_commit(commit_b.id().clone(), commit_d.id().clone());
+    let rebase_map =
+        rebase_descendants_with_options_return_map(tx.repo_mut(), &RebaseOptions::default());
+    assert_eq!(rebase_map.len(), 1);
+    let new_commit_c = assert_rebased_onto(tx.repo_mut(), &rebase_map, &commit_c, &[commit_d.id()]);
+    assert_eq!(new_commit_c.tree_id(), commit_d.tree_id());
+}
+
+#[test]
+fn test_rebase_merge_with_unchanged_base_tree() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Merge commit C is rebased onto commit B2, which has the same tree as the
+    // merge of B and A. The tree of C should be reused as is.
+    //
+    // C
+    // |\
+    // B |  B2
+    // |/  /
+    // A--'
+    let mut tx = repo.start_transaction();
+    let path1 = repo_path("file1");
+    let path2 = repo_path("file2");
+    let tree1 = create_tree(repo, &[(path1, "a")]);
+    let tree2 = create_tree(repo, &[(path1, "b")]);
+    let tree3 = create_tree(repo, &[(path1, "b"), (path2, "c")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree1.id())
+        .write()
+        .unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(
+            vec![commit_b.id().clone(), commit_a.id().clone()],
+            tree3.id(),
+        )
+        .write()
+        .unwrap();
+    let commit_b2 = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree2.id())
+        .set_description("B2")
+        .write()
+        .unwrap();
+
+    let rewriter = CommitRewriter::new(
+        tx.repo_mut(),
+        commit_c.clone(),
+        vec![commit_b2.id().clone()],
+    );
+    let new_commit_c = rewriter.rebase().unwrap().write().unwrap();
+    assert_eq!(new_commit_c.parent_ids(), [commit_b2.id().clone()]);
+    assert_eq!(new_commit_c.tree_id(), commit_c.tree_id());
+}
+
 #[test]
 fn test_rebase_descendants_basic_bookmark_update() {
     let test_repo = TestRepo::init();
@@ -1103,6 +1204,7 @@ fn test_rebase_descendants_update_bookmark_after_abandon(delete_abandoned_bookma
     let options = RebaseOptions {
         rewrite_refs: RewriteRefsOptions {
             delete_abandoned_bookmarks,
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -1382,6 +1484,7 @@ fn test_rebase_descendants_bookmark_delete_modify_abandon(delete_abandoned_bookm
     let options = RebaseOptions {
         rewrite_refs: RewriteRefsOptions {
             delete_abandoned_bookmarks,
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -1392,6 +1495,54 @@ fn test_rebase_descendants_bookmark_delete_modify_abandon(delete_abandoned_bookm
     );
 }
 
+#[test]
+fn test_rebase_descendants_pinned_bookmark() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Bookmarks "main" and "pinned" point to B. A is rewritten, which rebases
+    // B. "main" should follow B, whereas "pinned" should stay at the old B,
+    // which should remain visible.
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    for name in ["main", "pinned"] {
+        tx.repo_mut()
+            .set_local_bookmark_target(name.as_ref(), RefTarget::normal(commit_b.id().clone()));
+    }
+    let repo = tx.commit("test").unwrap();
+
+    let mut tx = repo.start_transaction();
+    let commit_a2 = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_description("different")
+        .write()
+        .unwrap();
+    let options = RebaseOptions {
+        rewrite_refs: RewriteRefsOptions {
+            pinned_bookmarks: hashset! {"pinned".into()},
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let rebase_map = rebase_descendants_with_options_return_map(tx.repo_mut(), &options);
+    let new_commit_b = assert_rebased_onto(tx.repo(), &rebase_map, &commit_b, &[commit_a2.id()]);
+    assert_eq!(
+        tx.repo().get_local_bookmark("main".as_ref()),
+        RefTarget::normal(new_commit_b.id().clone())
+    );
+    assert_eq!(
+        tx.repo().get_local_bookmark("pinned".as_ref()),
+        RefTarget::normal(commit_b.id().clone())
+    );
+    assert_eq!(
+        *tx.repo().view().heads(),
+        hashset! {commit_b.id().clone(), new_commit_b.id().clone()}
+    );
+}
+
 #[test_case(false; "slide down abandoned")]
 #[test_case(true; "delete abandoned")]
 fn test_rebase_descendants_bookmark_move_forward_abandon(delete_abandoned_bookmarks: bool) {
@@ -1426,6 +1577,7 @@ fn test_rebase_descendants_bookmark_move_forward_abandon(delete_abandoned_bookma
     let options = RebaseOptions {
         rewrite_refs: RewriteRefsOptions {
             delete_abandoned_bookmarks,
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -1478,6 +1630,7 @@ fn test_rebase_descendants_bookmark_move_sideways_abandon(delete_abandoned_bookm
     let options = RebaseOptions {
         rewrite_refs: RewriteRefsOptions {
             delete_abandoned_bookmarks,
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -1714,6 +1867,7 @@ fn test_empty_commit_option(empty_behavior: EmptyBehaviour) {
             empty: empty_behavior,
             rewrite_refs: RewriteRefsOptions {
                 delete_abandoned_bookmarks: false,
+                ..Default::default()
             },
             simplify_ancestor_merge: true,
         },
@@ -1849,6 +2003,7 @@ fn test_rebase_abandoning_empty() {
         empty: EmptyBehaviour::AbandonAllEmpty,
         rewrite_refs: RewriteRefsOptions {
             delete_abandoned_bookmarks: false,
+            ..Default::default()
         },
         simplify_ancestor_merge: true,
     };